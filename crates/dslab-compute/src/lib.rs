@@ -2,4 +2,5 @@
 #![doc = include_str!("../readme.md")]
 
 pub mod multicore;
+pub mod performance;
 pub mod singlecore;
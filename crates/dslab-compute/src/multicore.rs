@@ -10,6 +10,8 @@ use dslab_core::context::SimulationContext;
 use dslab_core::event::Event;
 use dslab_core::handler::EventHandler;
 
+use crate::performance::PerformanceModel;
+
 // STRUCTS -------------------------------------------------------------------------------------------------------------
 
 /// Resource allocation.
@@ -197,8 +199,12 @@ pub struct DeallocationFailed {
 /// Each core can only be used by one computation. The cores allocation for each computation is computed
 /// upon the request arrival and is not changed afterwards.
 /// This model also supports the manual allocation and release of cores and memory.
+///
+/// The computation time can deviate from the nominal one according to the resource [`PerformanceModel`].
 pub struct Compute {
     speed: f64,
+    performance: PerformanceModel,
+    slowed_down_count: u64,
     cores_total: u32,
     cores_available: u32,
     memory_total: u64,
@@ -213,6 +219,8 @@ impl Compute {
     pub fn new(speed: f64, cores: u32, memory: u64, ctx: SimulationContext) -> Self {
         Self {
             speed,
+            performance: PerformanceModel::default(),
+            slowed_down_count: 0,
             cores_total: cores,
             cores_available: cores,
             memory_total: memory,
@@ -228,6 +236,26 @@ impl Compute {
         self.speed
     }
 
+    /// Sets the resource performance model.
+    pub fn set_performance_model(&mut self, performance: PerformanceModel) {
+        self.performance = performance;
+    }
+
+    /// Returns the resource performance model.
+    pub fn performance_model(&self) -> &PerformanceModel {
+        &self.performance
+    }
+
+    /// Returns the core speed adjusted by the performance factor.
+    pub fn effective_speed(&self) -> f64 {
+        self.speed * self.performance.factor
+    }
+
+    /// Returns the number of computations slowed down by the performance model.
+    pub fn slowed_down_count(&self) -> u64 {
+        self.slowed_down_count
+    }
+
     /// Returns the total number of cores.
     pub fn cores_total(&self) -> u32 {
         self.cores_total
@@ -319,8 +347,12 @@ impl EventHandler for Compute {
                     self.ctx.emit_now(CompStarted { id: event.id, cores }, requester);
 
                     let speedup = cores_dependency.speedup(cores);
+                    let slowdown = self.performance.sample_slowdown(&self.ctx);
+                    if slowdown > 1. {
+                        self.slowed_down_count += 1;
+                    }
 
-                    let compute_time = flops / self.speed / speedup;
+                    let compute_time = flops / self.effective_speed() / speedup * slowdown;
                     self.ctx.emit_self(CompFinished { id: event.id }, compute_time);
                     self.computations
                         .insert(event.id, RunningComputation::new(cores, memory, requester));
@@ -0,0 +1,72 @@
+//! Model of heterogeneous and variable performance of computing resources.
+
+use dslab_core::context::SimulationContext;
+use dslab_models::queueing::TimeDistribution;
+
+/// Stochastic slowdown of individual computations (stragglers).
+///
+/// Each computation becomes a straggler with the specified probability, in which case its execution time
+/// is multiplied by the slowdown factor drawn from the specified distribution (the values below 1 are ignored).
+#[derive(Clone, Debug, PartialEq)]
+pub struct SlowdownModel {
+    /// Probability that a computation is slowed down.
+    pub probability: f64,
+    /// Distribution of slowdown factor.
+    pub factor: TimeDistribution,
+}
+
+impl SlowdownModel {
+    /// Creates a new slowdown model.
+    pub fn new(probability: f64, factor: TimeDistribution) -> Self {
+        Self { probability, factor }
+    }
+
+    /// Draws the slowdown factor of a computation using the random generator of the resource.
+    pub fn sample(&self, ctx: &SimulationContext) -> f64 {
+        if self.probability > 0. && ctx.rand() < self.probability {
+            self.factor.sample(ctx).max(1.)
+        } else {
+            1.
+        }
+    }
+}
+
+/// Describes the deviation of resource performance from its nominal speed.
+///
+/// The performance factor models the static heterogeneity of resources with the same nominal speed
+/// (e.g. different CPU models or noisy neighbors), the effective speed of resource is its nominal speed
+/// multiplied by this factor. The optional slowdown model introduces the variability of execution times
+/// of individual computations.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PerformanceModel {
+    /// Ratio of effective speed to the nominal speed.
+    pub factor: f64,
+    /// Slowdown of individual computations.
+    pub slowdown: Option<SlowdownModel>,
+}
+
+impl PerformanceModel {
+    /// Creates a model with the specified performance factor and without slowdowns.
+    pub fn new(factor: f64) -> Self {
+        assert!(factor > 0., "performance factor should be positive");
+        Self { factor, slowdown: None }
+    }
+
+    /// Sets the slowdown model.
+    pub fn with_slowdown(mut self, slowdown: SlowdownModel) -> Self {
+        self.slowdown = Some(slowdown);
+        self
+    }
+
+    /// Draws the slowdown factor of a computation, equals to 1 if the slowdown model is not set.
+    pub fn sample_slowdown(&self, ctx: &SimulationContext) -> f64 {
+        self.slowdown.as_ref().map_or(1., |s| s.sample(ctx))
+    }
+}
+
+impl Default for PerformanceModel {
+    /// Creates a model of resource running at its nominal speed.
+    fn default() -> Self {
+        Self::new(1.)
+    }
+}
@@ -10,6 +10,8 @@ use dslab_core::handler::EventHandler;
 
 use dslab_models::throughput_sharing::{FairThroughputSharingModel, ThroughputSharingModel};
 
+use crate::performance::PerformanceModel;
+
 // STRUCTS -------------------------------------------------------------------------------------------------------------
 
 /// Reason for computation failure.
@@ -85,12 +87,16 @@ pub struct CompFailed {
 /// The task completion time is determined by the amount of computations and the core share.
 /// Each time a task is completed or a new task is submitted, the core shares and completion
 /// times of all running tasks are updated accordingly.
+///
+/// The amount of computations of each task is scaled according to the resource [`PerformanceModel`].
 pub struct Compute {
     #[allow(dead_code)]
     speed: f64,
     #[allow(dead_code)]
     memory_total: u64,
     memory_available: u64,
+    performance: PerformanceModel,
+    slowed_down_count: u64,
     throughput_model: FairThroughputSharingModel<RunningComputation>,
     next_event: u64,
     ctx: SimulationContext,
@@ -103,6 +109,8 @@ impl Compute {
             speed,
             memory_total: memory,
             memory_available: memory,
+            performance: PerformanceModel::default(),
+            slowed_down_count: 0,
             throughput_model: FairThroughputSharingModel::with_fixed_throughput(speed),
             next_event: 0,
             ctx,
@@ -118,6 +126,16 @@ impl Compute {
         };
         self.ctx.emit_self_now(request)
     }
+
+    /// Sets the resource performance model.
+    pub fn set_performance_model(&mut self, performance: PerformanceModel) {
+        self.performance = performance;
+    }
+
+    /// Returns the number of computations slowed down by the performance model.
+    pub fn slowed_down_count(&self) -> u64 {
+        self.slowed_down_count
+    }
 }
 
 impl EventHandler for Compute {
@@ -140,10 +158,14 @@ impl EventHandler for Compute {
                     );
                 } else {
                     self.memory_available -= memory;
+                    let slowdown = self.performance.sample_slowdown(&self.ctx);
+                    if slowdown > 1. {
+                        self.slowed_down_count += 1;
+                    }
                     self.ctx.cancel_event(self.next_event);
                     self.throughput_model.insert(
                         RunningComputation::new(event.id, memory, requester),
-                        flops,
+                        flops * slowdown / self.performance.factor,
                         &mut self.ctx,
                     );
                     if let Some((time, computation)) = self.throughput_model.peek() {
@@ -0,0 +1,170 @@
+//! Memory allocator optimized for small simulation events.
+//!
+//! Each emitted event stores its payload in a separate heap allocation (`Box<dyn EventData>`), which is freed
+//! after the event is processed. In large simulations with millions of small events (timers, monitoring samples,
+//! network messages) this makes the general-purpose allocator a noticeable part of the running time.
+//!
+//! [`PoolAllocator`] is a global allocator which keeps the freed small blocks in thread-local free lists
+//! grouped by size class and reuses them for the next allocations, so that allocating and freeing a small event
+//! payload is a couple of pointer operations. Larger allocations are passed to the system allocator.
+//! The allocator is enabled by the application (not the library) as follows:
+//!
+//! ```rust
+//! use dslab_core::alloc::PoolAllocator;
+//!
+//! #[global_allocator]
+//! static ALLOCATOR: PoolAllocator = PoolAllocator;
+//!
+//! fn main() {
+//!     let events: Vec<Box<u64>> = (0..1000).map(Box::new).collect();
+//!     assert_eq!(*events[999], 999);
+//! }
+//! ```
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::ptr;
+
+/// Granularity of size classes in bytes, also the alignment of pooled blocks.
+const CLASS_STEP: usize = 16;
+/// Number of size classes, the largest pooled block is `CLASS_STEP * CLASS_COUNT` bytes.
+const CLASS_COUNT: usize = 8;
+/// Maximum size in bytes of free blocks kept in each thread-local free list, the rest are returned to the system.
+/// This bounds the memory kept in the pool of each thread by `MAX_FREE_BYTES * CLASS_COUNT` (2 MiB).
+const MAX_FREE_BYTES: usize = 256 << 10;
+
+struct FreeList {
+    head: Cell<*mut u8>,
+    len: Cell<usize>,
+}
+
+#[allow(clippy::declare_interior_mutable_const)]
+const EMPTY_LIST: FreeList = FreeList {
+    head: Cell::new(ptr::null_mut()),
+    len: Cell::new(0),
+};
+
+/// Free lists of the thread, which return all pooled blocks to the system allocator when the thread exits.
+struct FreeLists([FreeList; CLASS_COUNT]);
+
+impl Drop for FreeLists {
+    fn drop(&mut self) {
+        for (class, list) in self.0.iter().enumerate() {
+            let mut block = list.head.replace(ptr::null_mut());
+            while !block.is_null() {
+                unsafe {
+                    let next = *(block as *mut *mut u8);
+                    System.dealloc(block, class_layout(class));
+                    block = next;
+                }
+            }
+            list.len.set(0);
+        }
+    }
+}
+
+thread_local! {
+    static FREE_LISTS: FreeLists = const { FreeLists([EMPTY_LIST; CLASS_COUNT]) };
+}
+
+/// Global allocator which pools small blocks in thread-local free lists.
+///
+/// The blocks of up to 128 bytes with alignment up to 16 bytes are allocated from the system allocator
+/// in the size of their class and are put to the free list of the current thread when freed. The free lists are
+/// bounded, so the memory kept in the pool is limited, and are returned to the system allocator when the thread
+/// exits. A block can be freed in any thread, and all pooled blocks of a size class are allocated with the same
+/// layout, so a block can always be returned to the system allocator.
+pub struct PoolAllocator;
+
+fn size_class(layout: &Layout) -> Option<usize> {
+    if layout.size() == 0 || layout.size() > CLASS_STEP * CLASS_COUNT || layout.align() > CLASS_STEP {
+        None
+    } else {
+        Some((layout.size() - 1) / CLASS_STEP)
+    }
+}
+
+fn class_layout(class: usize) -> Layout {
+    // size is a non-zero multiple of the alignment, which is a power of two
+    unsafe { Layout::from_size_align_unchecked((class + 1) * CLASS_STEP, CLASS_STEP) }
+}
+
+/// Returns the maximum number of free blocks kept in the free list of the size class.
+fn max_free_blocks(class: usize) -> usize {
+    MAX_FREE_BYTES / ((class + 1) * CLASS_STEP)
+}
+
+unsafe impl GlobalAlloc for PoolAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let class = match size_class(&layout) {
+            Some(class) => class,
+            None => return System.alloc(layout),
+        };
+        let block = FREE_LISTS
+            .try_with(|lists| {
+                let list = &lists.0[class];
+                let head = list.head.get();
+                if !head.is_null() {
+                    // the free block stores the pointer to the next free block
+                    list.head.set(*(head as *mut *mut u8));
+                    list.len.set(list.len.get() - 1);
+                }
+                head
+            })
+            .unwrap_or(ptr::null_mut());
+        if block.is_null() {
+            System.alloc(class_layout(class))
+        } else {
+            block
+        }
+    }
+
+    unsafe fn dealloc(&self, block: *mut u8, layout: Layout) {
+        let class = match size_class(&layout) {
+            Some(class) => class,
+            None => return System.dealloc(block, layout),
+        };
+        let pooled = FREE_LISTS
+            .try_with(|lists| {
+                let list = &lists.0[class];
+                if list.len.get() >= max_free_blocks(class) {
+                    return false;
+                }
+                *(block as *mut *mut u8) = list.head.get();
+                list.head.set(block);
+                list.len.set(list.len.get() + 1);
+                true
+            })
+            .unwrap_or(false);
+        if !pooled {
+            System.dealloc(block, class_layout(class));
+        }
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        if size_class(&layout).is_none() {
+            return System.alloc_zeroed(layout);
+        }
+        let block = self.alloc(layout);
+        if !block.is_null() {
+            ptr::write_bytes(block, 0, layout.size());
+        }
+        block
+    }
+
+    unsafe fn realloc(&self, block: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_layout = Layout::from_size_align_unchecked(new_size, layout.align());
+        match (size_class(&layout), size_class(&new_layout)) {
+            (None, None) => System.realloc(block, layout, new_size),
+            (Some(old), Some(new)) if old == new => block,
+            _ => {
+                let new_block = self.alloc(new_layout);
+                if !new_block.is_null() {
+                    ptr::copy_nonoverlapping(block, new_block, layout.size().min(new_size));
+                    self.dealloc(block, layout);
+                }
+                new_block
+            }
+        }
+    }
+}
@@ -0,0 +1,330 @@
+//! Async/await programming model for simulation components.
+//!
+//! Components can spawn asynchronous tasks via [`SimulationContext::spawn()`](crate::SimulationContext::spawn())
+//! and use `await` to wait for events, timers or timeouts inside straight-line code instead of
+//! implementing multi-step protocols as state machines in [`EventHandler::on()`](crate::EventHandler::on()).
+//!
+//! The tasks are executed by the simulation itself on top of the event loop: an event awaited by some task
+//! is passed to this task instead of the component's event handler, and the task is resumed at the event time.
+//! The tasks are polled in the same thread, so they can freely use `Rc` and `RefCell` to share state with
+//! the component.
+
+use std::any::TypeId;
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Wake, Waker};
+
+use serde::Serialize;
+
+use crate::component::Id;
+use crate::event::{Event, EventData, EventId, TypedEvent};
+use crate::state::SimulationState;
+
+type TaskId = u64;
+
+type EventFilter = Box<dyn Fn(&Event) -> bool>;
+
+type Task = Pin<Box<dyn Future<Output = ()>>>;
+
+/// Internal event used to implement timers and timeouts.
+#[derive(Clone, Serialize)]
+pub(crate) struct AsyncTimer {}
+
+enum AwaitResult {
+    Event(Event),
+    Timeout,
+}
+
+/// Shared state of a pending await, which is completed either by the awaited event or by the timeout.
+#[derive(Default)]
+struct AwaitSlot {
+    result: Option<AwaitResult>,
+    waker: Option<Waker>,
+    done: bool,
+    timer: Option<EventId>,
+}
+
+struct Awaiter {
+    filter: Option<EventFilter>,
+    slot: Rc<RefCell<AwaitSlot>>,
+    timeout: bool,
+}
+
+struct TaskWaker {
+    task_id: TaskId,
+    ready: Arc<Mutex<VecDeque<TaskId>>>,
+}
+
+impl Wake for TaskWaker {
+    fn wake(self: Arc<Self>) {
+        self.ready.lock().unwrap().push_back(self.task_id);
+    }
+}
+
+/// Executor of asynchronous tasks spawned by simulation components.
+pub(crate) struct Runtime {
+    sim_state: Rc<RefCell<SimulationState>>,
+    tasks: HashMap<TaskId, Task>,
+    next_task_id: TaskId,
+    ready: Arc<Mutex<VecDeque<TaskId>>>,
+    awaiters: HashMap<(Id, TypeId), Vec<Awaiter>>,
+}
+
+impl Runtime {
+    pub fn new(sim_state: Rc<RefCell<SimulationState>>) -> Self {
+        Self {
+            sim_state,
+            tasks: HashMap::new(),
+            next_task_id: 0,
+            ready: Arc::new(Mutex::new(VecDeque::new())),
+            awaiters: HashMap::new(),
+        }
+    }
+
+    pub fn spawn(&mut self, future: impl Future<Output = ()> + 'static) {
+        let task_id = self.next_task_id;
+        self.next_task_id += 1;
+        self.tasks.insert(task_id, Box::pin(future));
+        self.ready.lock().unwrap().push_back(task_id);
+    }
+
+    fn register(&mut self, component: Id, type_id: TypeId, awaiter: Awaiter) {
+        self.awaiters.entry((component, type_id)).or_default().push(awaiter);
+    }
+
+    /// Passes the event to the task awaiting it, if any. Otherwise returns the event back.
+    pub fn try_complete(&mut self, event: Event) -> Option<Event> {
+        let key = (event.dst, (*event.data).as_any().type_id());
+        let awaiters = match self.awaiters.get_mut(&key) {
+            Some(awaiters) => awaiters,
+            None => return Some(event),
+        };
+        // awaiters dropped or completed by timeout are removed lazily
+        awaiters.retain(|awaiter| !awaiter.slot.borrow().done);
+        let position = awaiters
+            .iter()
+            .position(|awaiter| awaiter.filter.as_ref().map_or(true, |filter| filter(&event)));
+        let position = match position {
+            Some(position) => position,
+            None => {
+                if awaiters.is_empty() {
+                    self.awaiters.remove(&key);
+                }
+                return Some(event);
+            }
+        };
+        let awaiter = awaiters.remove(position);
+        if awaiters.is_empty() {
+            self.awaiters.remove(&key);
+        }
+        let mut slot = awaiter.slot.borrow_mut();
+        slot.done = true;
+        if awaiter.timeout {
+            slot.result = Some(AwaitResult::Timeout);
+        } else {
+            if let Some(timer) = slot.timer.take() {
+                self.sim_state.borrow_mut().cancel_event(timer);
+            }
+            slot.result = Some(AwaitResult::Event(event));
+        }
+        if let Some(waker) = slot.waker.take() {
+            waker.wake();
+        }
+        None
+    }
+
+    fn next_ready_task(&mut self) -> Option<(TaskId, Task)> {
+        loop {
+            let task_id = self.ready.lock().unwrap().pop_front()?;
+            // the task could be woken several times or already completed
+            if let Some(task) = self.tasks.remove(&task_id) {
+                return Some((task_id, task));
+            }
+        }
+    }
+
+    /// Polls the ready tasks until there are no more ready tasks.
+    ///
+    /// The runtime is not borrowed while polling a task, so the task can spawn new tasks and await events.
+    pub fn run_ready_tasks(runtime: &Rc<RefCell<Runtime>>) {
+        loop {
+            let next = runtime.borrow_mut().next_ready_task();
+            let (task_id, mut task) = match next {
+                Some(next) => next,
+                None => break,
+            };
+            let waker = Waker::from(Arc::new(TaskWaker {
+                task_id,
+                ready: runtime.borrow().ready.clone(),
+            }));
+            if task.as_mut().poll(&mut Context::from_waker(&waker)).is_pending() {
+                runtime.borrow_mut().tasks.insert(task_id, task);
+            }
+        }
+    }
+}
+
+/// Registers the awaits for a component, used by [`SimulationContext`](crate::SimulationContext) methods.
+pub(crate) struct AwaitRegistrar {
+    pub runtime: Rc<RefCell<Runtime>>,
+    pub sim_state: Rc<RefCell<SimulationState>>,
+    pub component: Id,
+}
+
+impl AwaitRegistrar {
+    pub fn event<T: EventData>(&self, filter: Option<EventFilter>) -> EventFuture<T> {
+        let slot = Rc::new(RefCell::new(AwaitSlot::default()));
+        self.runtime.borrow_mut().register(
+            self.component,
+            TypeId::of::<T>(),
+            Awaiter {
+                filter,
+                slot: slot.clone(),
+                timeout: false,
+            },
+        );
+        EventFuture {
+            slot,
+            runtime: self.runtime.clone(),
+            sim_state: self.sim_state.clone(),
+            component: self.component,
+            _data: PhantomData,
+        }
+    }
+
+    pub fn sleep(&self, duration: f64) -> SleepFuture {
+        let slot = Rc::new(RefCell::new(AwaitSlot::default()));
+        add_timer(&self.runtime, &self.sim_state, self.component, &slot, duration);
+        SleepFuture {
+            slot,
+            sim_state: self.sim_state.clone(),
+        }
+    }
+}
+
+fn add_timer(
+    runtime: &Rc<RefCell<Runtime>>,
+    sim_state: &Rc<RefCell<SimulationState>>,
+    component: Id,
+    slot: &Rc<RefCell<AwaitSlot>>,
+    duration: f64,
+) {
+    let timer = sim_state
+        .borrow_mut()
+        .add_event(AsyncTimer {}, component, component, duration);
+    slot.borrow_mut().timer = Some(timer);
+    runtime.borrow_mut().register(
+        component,
+        TypeId::of::<AsyncTimer>(),
+        Awaiter {
+            filter: Some(Box::new(move |event| event.id == timer)),
+            slot: slot.clone(),
+            timeout: true,
+        },
+    );
+}
+
+fn cancel_await(slot: &Rc<RefCell<AwaitSlot>>, sim_state: &Rc<RefCell<SimulationState>>) {
+    let mut slot = slot.borrow_mut();
+    if !slot.done {
+        slot.done = true;
+        if let Some(timer) = slot.timer.take() {
+            sim_state.borrow_mut().cancel_event(timer);
+        }
+    }
+}
+
+/// Future which is resolved when the awaited event is delivered to the component.
+///
+/// Created by [`SimulationContext::recv_event()`](crate::SimulationContext::recv_event()) and similar methods.
+/// The await is registered at the moment of future creation, so the event delivered after it is not missed
+/// even if the future is not polled yet. Dropping the future cancels the await.
+pub struct EventFuture<T: EventData> {
+    slot: Rc<RefCell<AwaitSlot>>,
+    runtime: Rc<RefCell<Runtime>>,
+    sim_state: Rc<RefCell<SimulationState>>,
+    component: Id,
+    _data: PhantomData<T>,
+}
+
+impl<T: EventData> EventFuture<T> {
+    /// Limits the waiting time for the event, the returned future is resolved with `None` on timeout.
+    pub fn with_timeout(self, timeout: f64) -> TimeoutFuture<T> {
+        add_timer(&self.runtime, &self.sim_state, self.component, &self.slot, timeout);
+        TimeoutFuture { inner: self }
+    }
+
+    fn poll_result(&self, cx: &mut Context<'_>) -> Poll<Option<TypedEvent<T>>> {
+        let mut slot = self.slot.borrow_mut();
+        match slot.result.take() {
+            Some(AwaitResult::Event(event)) => Poll::Ready(Some(event.downcast::<T>().ok().unwrap())),
+            Some(AwaitResult::Timeout) => Poll::Ready(None),
+            None => {
+                slot.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl<T: EventData> Future for EventFuture<T> {
+    type Output = TypedEvent<T>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.poll_result(cx).map(|event| event.unwrap())
+    }
+}
+
+impl<T: EventData> Drop for EventFuture<T> {
+    fn drop(&mut self) {
+        cancel_await(&self.slot, &self.sim_state);
+    }
+}
+
+/// Future which is resolved with the awaited event or with `None` if the event is not delivered within the timeout.
+///
+/// Created by [`EventFuture::with_timeout()`].
+pub struct TimeoutFuture<T: EventData> {
+    inner: EventFuture<T>,
+}
+
+impl<T: EventData> Future for TimeoutFuture<T> {
+    type Output = Option<TypedEvent<T>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.inner.poll_result(cx)
+    }
+}
+
+/// Future which is resolved after the specified simulation time passes.
+///
+/// Created by [`SimulationContext::sleep()`](crate::SimulationContext::sleep()).
+pub struct SleepFuture {
+    slot: Rc<RefCell<AwaitSlot>>,
+    sim_state: Rc<RefCell<SimulationState>>,
+}
+
+impl Future for SleepFuture {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut slot = self.slot.borrow_mut();
+        if slot.result.take().is_some() {
+            Poll::Ready(())
+        } else {
+            slot.waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+impl Drop for SleepFuture {
+    fn drop(&mut self) {
+        cancel_await(&self.slot, &self.sim_state);
+    }
+}
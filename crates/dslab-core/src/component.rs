@@ -1,4 +1,215 @@
 //! Simulation components.
 
+use std::any::{Any, TypeId};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use log::debug;
+use serde_json::json;
+
+use crate::handler::EventHandler;
+use crate::state::SimulationState;
+use crate::units::LogTime;
+
 /// Identifier of simulation component.
 pub type Id = u32;
+
+/// Specifies what happens with events destined for a retired component.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PendingEvents {
+    /// Events are silently dropped (logged under `DEBUG` level).
+    Drop,
+    /// Events are delivered to the specified component instead, with `event.dst` set to this component.
+    Redirect(Id),
+}
+
+/// Result of resolving the event destination.
+pub(crate) enum Route {
+    /// Event should be passed to the handler of the specified component.
+    Deliver(Id, Rc<RefCell<dyn EventHandler>>),
+    /// Event is destined for a retired component and should be dropped.
+    Drop,
+    /// There is no handler for the event destination.
+    Undelivered,
+}
+
+/// Registry of simulation components and their event handlers shared by simulation and contexts,
+/// which allows to add and remove components while the simulation is running.
+///
+/// The registry also keeps the hierarchy of composite components: child components are registered with names
+/// prefixed by the parent name (`parent/child`), and events of some type destined for a composite component
+/// can be routed to its child.
+pub(crate) struct ComponentRegistry {
+    sim_state: Rc<RefCell<SimulationState>>,
+    names: Rc<RefCell<Vec<String>>>,
+    name_to_id: HashMap<String, Id>,
+    handlers: Vec<Option<Rc<RefCell<dyn EventHandler>>>>,
+    retired: HashMap<Id, PendingEvents>,
+    parents: HashMap<Id, Id>,
+    event_routes: HashMap<(Id, TypeId), Id>,
+    instances: HashMap<Id, Rc<dyn Any>>,
+}
+
+impl ComponentRegistry {
+    pub fn new(sim_state: Rc<RefCell<SimulationState>>, names: Rc<RefCell<Vec<String>>>) -> Self {
+        Self {
+            sim_state,
+            names,
+            name_to_id: HashMap::new(),
+            handlers: Vec::new(),
+            retired: HashMap::new(),
+            parents: HashMap::new(),
+            event_routes: HashMap::new(),
+            instances: HashMap::new(),
+        }
+    }
+
+    pub fn register(&mut self, name: &str) -> Id {
+        if let Some(&id) = self.name_to_id.get(name) {
+            return id;
+        }
+        let id = self.name_to_id.len() as Id;
+        self.name_to_id.insert(name.to_owned(), id);
+        self.names.borrow_mut().push(name.to_owned());
+        self.handlers.push(None);
+        self.sim_state.borrow_mut().add_component_rng(name);
+        id
+    }
+
+    pub fn register_instance(&mut self, name: &str, instance: Rc<dyn Any>) -> Id {
+        let id = self.register(name);
+        self.instances.insert(id, instance);
+        id
+    }
+
+    /// Returns the typed handle of component instance registered with specified name.
+    pub fn instance<T: 'static>(&self, name: &str) -> Option<Rc<RefCell<T>>> {
+        let id = self.lookup_id(name)?;
+        self.instances.get(&id)?.clone().downcast::<RefCell<T>>().ok()
+    }
+
+    pub fn register_child(&mut self, parent: Id, name: &str) -> Id {
+        let full_name = format!("{}/{}", self.names.borrow()[parent as usize], name);
+        let id = self.register(&full_name);
+        self.parents.insert(id, parent);
+        id
+    }
+
+    pub fn parent(&self, id: Id) -> Option<Id> {
+        self.parents.get(&id).copied()
+    }
+
+    fn is_descendant(&self, id: Id, ancestor: Id) -> bool {
+        let mut current = id;
+        while let Some(parent) = self.parent(current) {
+            if parent == ancestor {
+                return true;
+            }
+            current = parent;
+        }
+        false
+    }
+
+    pub fn add_event_route(&mut self, composite: Id, type_id: TypeId, child: Id) {
+        assert!(
+            self.is_descendant(child, composite),
+            "Component {} is not a child of composite component {}",
+            self.names.borrow()[child as usize],
+            self.names.borrow()[composite as usize]
+        );
+        self.event_routes.insert((composite, type_id), child);
+    }
+
+    /// Resolves the child component which should receive the event of specified type destined for a composite
+    /// component, following the routes of nested composites.
+    pub fn resolve_event_route(&self, dst: Id, type_id: TypeId) -> Id {
+        let mut dst = dst;
+        if self.event_routes.is_empty() {
+            return dst;
+        }
+        // routes always point to descendants, so the loop terminates
+        while let Some(&child) = self.event_routes.get(&(dst, type_id)) {
+            dst = child;
+        }
+        dst
+    }
+
+    pub fn lookup_id(&self, name: &str) -> Option<Id> {
+        self.name_to_id.get(name).copied()
+    }
+
+    pub fn add_handler(&mut self, name: &str, handler: Rc<RefCell<dyn EventHandler>>) -> Id {
+        let id = self.register(name);
+        self.handlers[id as usize] = Some(handler);
+        self.retired.remove(&id);
+        debug!(
+            target: "simulation",
+            "[{:.3} {} simulation] Added handler: {}",
+            LogTime(self.sim_state.borrow().time()),
+            crate::log::get_colored("DEBUG", colored::Color::Blue),
+            json!({"name": name, "id": id})
+        );
+        id
+    }
+
+    pub fn remove_handler(&mut self, name: &str) -> Id {
+        let id = self.lookup_id(name).unwrap();
+        self.handlers[id as usize] = None;
+        debug!(
+            target: "simulation",
+            "[{:.3} {} simulation] Removed handler: {}",
+            LogTime(self.sim_state.borrow().time()),
+            crate::log::get_colored("DEBUG", colored::Color::Blue),
+            json!({"name": name, "id": id})
+        );
+        id
+    }
+
+    pub fn retire(&mut self, name: &str, pending: PendingEvents) {
+        let id = self.remove_handler(name);
+        if let PendingEvents::Redirect(target) = pending {
+            assert!(
+                (target as usize) < self.handlers.len() && target != id,
+                "Invalid redirect target {} for component {}",
+                target,
+                name
+            );
+        }
+        self.sim_state.borrow_mut().cancel_component_timers(id);
+        self.instances.remove(&id);
+        self.retired.insert(id, pending);
+        // the children of retired composite component are retired as well
+        let mut children: Vec<Id> = self
+            .parents
+            .iter()
+            .filter(|(_, &parent)| parent == id)
+            .map(|(&child, _)| child)
+            .collect();
+        children.sort_unstable();
+        for child in children {
+            if !self.retired.contains_key(&child) {
+                let name = self.names.borrow()[child as usize].clone();
+                self.retire(&name, PendingEvents::Drop);
+            }
+        }
+    }
+
+    /// Resolves the component which should receive the event destined for the specified component,
+    /// following the redirects of retired components.
+    pub fn route(&self, dst: Id) -> Route {
+        let mut dst = dst;
+        // the number of redirects is bounded to avoid infinite loops in cyclic redirects
+        for _ in 0..=self.retired.len() {
+            if let Some(Some(handler)) = self.handlers.get(dst as usize) {
+                return Route::Deliver(dst, handler.clone());
+            }
+            match self.retired.get(&dst) {
+                Some(PendingEvents::Drop) => return Route::Drop,
+                Some(PendingEvents::Redirect(target)) => dst = *target,
+                None => return Route::Undelivered,
+            }
+        }
+        Route::Undelivered
+    }
+}
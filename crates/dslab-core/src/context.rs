@@ -1,14 +1,19 @@
 //! Accessing simulation from components.
 
+use std::any::TypeId;
 use std::cell::RefCell;
+use std::future::Future;
 use std::rc::Rc;
 
 use rand::distributions::uniform::{SampleRange, SampleUniform};
 use rand::prelude::Distribution;
 
-use crate::component::Id;
+use crate::async_mode::{AwaitRegistrar, EventFuture, Runtime, SleepFuture};
+use crate::component::{ComponentRegistry, Id, PendingEvents};
 use crate::event::{Event, EventData, EventId};
+use crate::handler::EventHandler;
 use crate::state::SimulationState;
+use crate::timer::TimerId;
 
 /// A facade for accessing the simulation state and producing events from simulation components.
 pub struct SimulationContext {
@@ -16,6 +21,8 @@ pub struct SimulationContext {
     name: String,
     sim_state: Rc<RefCell<SimulationState>>,
     names: Rc<RefCell<Vec<String>>>,
+    registry: Rc<RefCell<ComponentRegistry>>,
+    runtime: Rc<RefCell<Runtime>>,
 }
 
 impl SimulationContext {
@@ -24,12 +31,16 @@ impl SimulationContext {
         name: &str,
         sim_state: Rc<RefCell<SimulationState>>,
         names: Rc<RefCell<Vec<String>>>,
+        registry: Rc<RefCell<ComponentRegistry>>,
+        runtime: Rc<RefCell<Runtime>>,
     ) -> Self {
         Self {
             id,
             name: name.to_owned(),
             sim_state,
             names,
+            registry,
+            runtime,
         }
     }
 
@@ -82,7 +93,12 @@ impl SimulationContext {
     }
 
     /// Returns a random float in the range _[0, 1)_
-    /// using the simulation-wide random number generator.
+    /// using the random number generator of the component.
+    ///
+    /// Each component has a separate random number generator stream, which is seeded from the simulation seed
+    /// and the component name. If the shared generator is enabled via
+    /// [`Simulation::set_shared_rng()`](crate::Simulation::set_shared_rng()),
+    /// this and other random methods of the context use the simulation-wide generator instead.
     ///
     /// # Examples
     ///
@@ -95,11 +111,11 @@ impl SimulationContext {
     /// assert!(f >= 0.0 && f < 1.0);
     /// ```
     pub fn rand(&self) -> f64 {
-        self.sim_state.borrow_mut().rand()
+        self.sim_state.borrow_mut().rand(Some(self.id))
     }
 
     /// Returns a random number in the specified range
-    /// using the simulation-wide random number generator.
+    /// using the random number generator of the component.
     ///
     /// # Examples
     ///
@@ -118,19 +134,21 @@ impl SimulationContext {
         T: SampleUniform,
         R: SampleRange<T>,
     {
-        self.sim_state.borrow_mut().gen_range(range)
+        self.sim_state.borrow_mut().gen_range(Some(self.id), range)
     }
 
     /// Returns a random value from the specified distribution
-    /// using the simulation-wide random number generator.
+    /// using the random number generator of the component.
     pub fn sample_from_distribution<T, Dist: Distribution<T>>(&self, dist: &Dist) -> T {
-        self.sim_state.borrow_mut().sample_from_distribution(dist)
+        self.sim_state
+            .borrow_mut()
+            .sample_from_distribution(Some(self.id), dist)
     }
 
     /// Returns a random alphanumeric string of specified length
-    /// using the simulation-wide random number generator.
+    /// using the random number generator of the component.
     pub fn random_string(&self, len: usize) -> String {
-        self.sim_state.borrow_mut().random_string(len)
+        self.sim_state.borrow_mut().random_string(Some(self.id), len)
     }
 
     /// Creates new event with specified payload, destination and delay, returns event id.
@@ -202,6 +220,68 @@ impl SimulationContext {
         self.sim_state.borrow_mut().add_event(data, self.id, dst, delay)
     }
 
+    /// Same as [`emit()`](Self::emit()), but the event is assigned the specified priority.
+    ///
+    /// Among the events scheduled at the same time, the events with lower priority value are processed first,
+    /// the default priority of events is 0. This allows to model, for example, processing of control messages
+    /// before data messages within a single time instant. The event priorities take precedence over the ordering
+    /// policy set via [`Simulation::set_event_ordering()`](crate::Simulation::set_event_ordering()).
+    /// The events emitted via [`emit_ordered()`](Self::emit_ordered()) have the default priority.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::cell::RefCell;
+    /// use std::rc::Rc;
+    /// use serde::Serialize;
+    /// use dslab_core::{cast, Event, EventHandler, Simulation, SimulationContext};
+    ///
+    /// #[derive(Clone, Serialize)]
+    /// pub struct Data {
+    /// }
+    ///
+    /// #[derive(Clone, Serialize)]
+    /// pub struct Control {
+    /// }
+    ///
+    /// pub struct Switch {
+    ///     log: Vec<&'static str>,
+    /// }
+    ///
+    /// impl EventHandler for Switch {
+    ///     fn on(&mut self, event: Event) {
+    ///         cast!(match event.data {
+    ///             Data {} => {
+    ///                 self.log.push("data");
+    ///             }
+    ///             Control {} => {
+    ///                 self.log.push("control");
+    ///             }
+    ///         })
+    ///     }
+    /// }
+    ///
+    /// const CONTROL_PLANE: i64 = -1;
+    ///
+    /// let mut sim = Simulation::new(123);
+    /// let switch = Rc::new(RefCell::new(Switch { log: Vec::new() }));
+    /// let switch_id = sim.add_handler("switch", switch.clone());
+    /// let ctx = sim.create_context("controller");
+    /// ctx.emit(Data {}, switch_id, 1.);
+    /// ctx.emit(Data {}, switch_id, 1.);
+    /// ctx.emit_with_priority(Control {}, switch_id, 1., CONTROL_PLANE);
+    /// sim.step_until_no_events();
+    /// assert_eq!(switch.borrow().log, vec!["control", "data", "data"]);
+    /// ```
+    pub fn emit_with_priority<T>(&self, data: T, dst: Id, delay: f64, priority: i64) -> EventId
+    where
+        T: EventData,
+    {
+        self.sim_state
+            .borrow_mut()
+            .add_event_with_priority(data, self.id, dst, delay, priority)
+    }
+
     /// This and all other `emit_ordered...` functions are special variants of normal `emit_...` functions
     /// that allow adding events to ordered event deque instead of heap, which may improve simulation performance.
     ///
@@ -542,7 +622,10 @@ impl SimulationContext {
     /// Cancels the specified event.
     ///
     /// Use [`EventId`](crate::event::EventId) obtained when creating the event to cancel it.
-    /// Note that already processed events cannot be cancelled.
+    /// Note that already processed events cannot be cancelled, cancelling such events has no effect.
+    ///
+    /// This allows to implement timers and timeouts without checking whether the timer event is still relevant:
+    /// the component stores the id of emitted timer event and cancels it when the timer is no longer needed.
     ///
     /// # Examples
     ///
@@ -607,6 +690,100 @@ impl SimulationContext {
         self.sim_state.borrow_mut().cancel_heap_events(pred);
     }
 
+    /// Sets a one-shot timer, which delivers [`Timer`](crate::timer::Timer) event to this component
+    /// after the specified delay.
+    ///
+    /// Returns the timer identifier, which is passed in the timer event and can be used to cancel the timer.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::cell::RefCell;
+    /// use std::rc::Rc;
+    /// use dslab_core::timer::Timer;
+    /// use dslab_core::{cast, Event, EventHandler, Simulation, SimulationContext};
+    ///
+    /// pub struct Component {
+    ///     fired: Vec<(u64, f64)>,
+    ///     ctx: SimulationContext,
+    /// }
+    ///
+    /// impl EventHandler for Component {
+    ///     fn on(&mut self, event: Event) {
+    ///         cast!(match event.data {
+    ///             Timer { id } => {
+    ///                 self.fired.push((id, self.ctx.time()));
+    ///             }
+    ///         })
+    ///     }
+    /// }
+    ///
+    /// let mut sim = Simulation::new(123);
+    /// let comp_ctx = sim.create_context("comp");
+    /// let timer1 = comp_ctx.set_timer(1.5);
+    /// let timer2 = comp_ctx.set_timer(2.);
+    /// let comp = Rc::new(RefCell::new(Component { fired: Vec::new(), ctx: comp_ctx }));
+    /// sim.add_handler("comp", comp.clone());
+    /// sim.step_until_no_events();
+    /// assert_eq!(comp.borrow().fired, vec![(timer1, 1.5), (timer2, 2.)]);
+    /// ```
+    pub fn set_timer(&self, delay: f64) -> TimerId {
+        self.sim_state.borrow_mut().set_timer(self.id, delay, None)
+    }
+
+    /// Sets a periodic timer, which delivers [`Timer`](crate::timer::Timer) event to this component
+    /// every `interval` time units, starting at `interval` from now, until the timer is cancelled.
+    ///
+    /// Periodic timers are convenient for monitoring and sampling of component state. Note that a component with
+    /// an active periodic timer always has a pending event, so the simulation should be run with time limit, e.g.
+    /// via [`Simulation::step_until_time()`](crate::Simulation::step_until_time()). The timers of retired component
+    /// are cancelled automatically.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::cell::RefCell;
+    /// use std::rc::Rc;
+    /// use dslab_core::timer::Timer;
+    /// use dslab_core::{cast, Event, EventHandler, Simulation, SimulationContext};
+    ///
+    /// pub struct Monitor {
+    ///     samples: Vec<f64>,
+    ///     ctx: SimulationContext,
+    /// }
+    ///
+    /// impl EventHandler for Monitor {
+    ///     fn on(&mut self, event: Event) {
+    ///         cast!(match event.data {
+    ///             Timer { id } => {
+    ///                 self.samples.push(self.ctx.time());
+    ///                 if self.samples.len() == 3 {
+    ///                     self.ctx.cancel_timer(id);
+    ///                 }
+    ///             }
+    ///         })
+    ///     }
+    /// }
+    ///
+    /// let mut sim = Simulation::new(123);
+    /// let monitor_ctx = sim.create_context("monitor");
+    /// monitor_ctx.set_periodic_timer(0.5);
+    /// let monitor = Rc::new(RefCell::new(Monitor { samples: Vec::new(), ctx: monitor_ctx }));
+    /// sim.add_handler("monitor", monitor.clone());
+    /// sim.step_until_no_events();
+    /// assert_eq!(monitor.borrow().samples, vec![0.5, 1., 1.5]);
+    /// ```
+    pub fn set_periodic_timer(&self, interval: f64) -> TimerId {
+        self.sim_state.borrow_mut().set_timer(self.id, interval, Some(interval))
+    }
+
+    /// Cancels the timer, so that it does not fire anymore.
+    ///
+    /// Cancelling the timer which has already fired (for one-shot timers) or was cancelled has no effect.
+    pub fn cancel_timer(&self, id: TimerId) {
+        self.sim_state.borrow_mut().cancel_timer(id);
+    }
+
     /// Returns component name by its identifier.
     ///
     /// # Examples
@@ -648,4 +825,370 @@ impl SimulationContext {
     pub fn lookup_name(&self, id: Id) -> String {
         self.names.borrow()[id as usize].clone()
     }
+
+    /// Creates a new simulation context with specified name.
+    ///
+    /// Same as [`Simulation::create_context()`](crate::Simulation::create_context()), but can be used by components
+    /// to create new components while the simulation is running.
+    pub fn create_context<S>(&self, name: S) -> SimulationContext
+    where
+        S: AsRef<str>,
+    {
+        let id = self.registry.borrow_mut().register(name.as_ref());
+        SimulationContext::new(
+            id,
+            name.as_ref(),
+            self.sim_state.clone(),
+            self.names.clone(),
+            self.registry.clone(),
+            self.runtime.clone(),
+        )
+    }
+
+    /// Creates a context for a child component of this component.
+    ///
+    /// Composite components allow to encapsulate several child components behind one public identifier.
+    /// The child is registered with name `<parent name>/<name>`, so that the children of different composites
+    /// do not clash. The composite component can route the incoming events of specific types to its children via
+    /// [`route_events()`](Self::route_events()), and the children can emit events on behalf of the composite via
+    /// [`emit_as_parent()`](Self::emit_as_parent()), so that the internal structure is hidden from other components.
+    /// Retiring the composite component also retires its children.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::cell::RefCell;
+    /// use std::rc::Rc;
+    /// use serde::Serialize;
+    /// use dslab_core::{cast, Event, EventHandler, Simulation, SimulationContext};
+    ///
+    /// #[derive(Clone, Serialize)]
+    /// pub struct Compute {
+    /// }
+    ///
+    /// #[derive(Clone, Serialize)]
+    /// pub struct Read {
+    /// }
+    ///
+    /// #[derive(Clone, Serialize)]
+    /// pub struct Done {
+    ///     by: String,
+    /// }
+    ///
+    /// // subsystem of the host, e.g. CPU or disk
+    /// pub struct Subsystem {
+    ///     ctx: SimulationContext,
+    ///     delay: f64,
+    /// }
+    ///
+    /// impl EventHandler for Subsystem {
+    ///     fn on(&mut self, event: Event) {
+    ///         let by = self.ctx.name().to_owned();
+    ///         self.ctx.emit_as_parent(Done { by }, event.src, self.delay);
+    ///     }
+    /// }
+    ///
+    /// pub struct Client {
+    ///     done: Vec<(f64, String, u32)>,
+    /// }
+    ///
+    /// impl EventHandler for Client {
+    ///     fn on(&mut self, event: Event) {
+    ///         cast!(match event.data {
+    ///             Done { by } => {
+    ///                 self.done.push((event.time, by, event.src));
+    ///             }
+    ///         })
+    ///     }
+    /// }
+    ///
+    /// let mut sim = Simulation::new(123);
+    /// let host_ctx = sim.create_context("host");
+    /// let cpu_ctx = host_ctx.create_child_context("cpu");
+    /// let disk_ctx = host_ctx.create_child_context("disk");
+    /// assert_eq!(cpu_ctx.name(), "host/cpu");
+    /// assert_eq!(cpu_ctx.parent_id(), Some(host_ctx.id()));
+    /// host_ctx.route_events::<Compute>(cpu_ctx.id());
+    /// host_ctx.route_events::<Read>(disk_ctx.id());
+    /// sim.add_handler("host/cpu", Rc::new(RefCell::new(Subsystem { ctx: cpu_ctx, delay: 1. })));
+    /// sim.add_handler("host/disk", Rc::new(RefCell::new(Subsystem { ctx: disk_ctx, delay: 2. })));
+    ///
+    /// let client = Rc::new(RefCell::new(Client { done: Vec::new() }));
+    /// let client_ctx = sim.create_context("client");
+    /// sim.add_handler("client", client.clone());
+    /// // the client knows only the public id of the host
+    /// client_ctx.emit(Read {}, host_ctx.id(), 0.);
+    /// client_ctx.emit(Compute {}, host_ctx.id(), 0.);
+    /// sim.step_until_no_events();
+    /// assert_eq!(
+    ///     client.borrow().done,
+    ///     vec![(1., "host/cpu".to_owned(), host_ctx.id()), (2., "host/disk".to_owned(), host_ctx.id())]
+    /// );
+    /// ```
+    pub fn create_child_context<S>(&self, name: S) -> SimulationContext
+    where
+        S: AsRef<str>,
+    {
+        let id = self.registry.borrow_mut().register_child(self.id, name.as_ref());
+        let full_name = self.lookup_name(id);
+        SimulationContext::new(
+            id,
+            &full_name,
+            self.sim_state.clone(),
+            self.names.clone(),
+            self.registry.clone(),
+            self.runtime.clone(),
+        )
+    }
+
+    /// Returns the identifier of parent composite component, if this component is a child component.
+    pub fn parent_id(&self) -> Option<Id> {
+        self.registry.borrow().parent(self.id)
+    }
+
+    /// Routes the events of type `T` destined for this component to the specified child component.
+    ///
+    /// The child can be a direct or nested child of this component. Events of other types are delivered to this
+    /// component as usual. Panics if the specified component is not a child of this component.
+    pub fn route_events<T: EventData>(&self, child: Id) {
+        self.registry
+            .borrow_mut()
+            .add_event_route(self.id, TypeId::of::<T>(), child);
+    }
+
+    /// Emits event on behalf of the parent composite component (see [`emit()`](Self::emit())).
+    ///
+    /// Panics if this component is not a child component.
+    pub fn emit_as_parent<T>(&self, data: T, dst: Id, delay: f64) -> EventId
+    where
+        T: EventData,
+    {
+        let parent = self
+            .parent_id()
+            .expect("Component is not a child of composite component");
+        self.sim_state.borrow_mut().add_event(data, parent, dst, delay)
+    }
+
+    /// Registers the event handler implementation for component with specified name, returns the component Id.
+    ///
+    /// Same as [`Simulation::add_handler()`](crate::Simulation::add_handler()), but can be used by components
+    /// to add new components while the simulation is running. The added component can receive events right away.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::cell::RefCell;
+    /// use std::rc::Rc;
+    /// use serde::Serialize;
+    /// use dslab_core::component::PendingEvents;
+    /// use dslab_core::{cast, Event, EventHandler, Simulation, SimulationContext};
+    ///
+    /// #[derive(Clone, Serialize)]
+    /// pub struct Spawn {
+    /// }
+    ///
+    /// #[derive(Clone, Serialize)]
+    /// pub struct Work {
+    /// }
+    ///
+    /// pub struct Worker {
+    ///     ctx: SimulationContext,
+    ///     done: Rc<RefCell<u32>>,
+    /// }
+    ///
+    /// impl EventHandler for Worker {
+    ///     fn on(&mut self, event: Event) {
+    ///         cast!(match event.data {
+    ///             Work { } => {
+    ///                 *self.done.borrow_mut() += 1;
+    ///                 // the worker retires itself after doing the work
+    ///                 self.ctx.retire_component(self.ctx.name(), PendingEvents::Drop);
+    ///             }
+    ///         })
+    ///     }
+    /// }
+    ///
+    /// pub struct Manager {
+    ///     ctx: SimulationContext,
+    ///     done: Rc<RefCell<u32>>,
+    /// }
+    ///
+    /// impl EventHandler for Manager {
+    ///     fn on(&mut self, event: Event) {
+    ///         cast!(match event.data {
+    ///             Spawn { } => {
+    ///                 let worker_ctx = self.ctx.create_context("worker");
+    ///                 let worker = Worker { ctx: worker_ctx, done: self.done.clone() };
+    ///                 let worker_id = self.ctx.add_handler("worker", Rc::new(RefCell::new(worker)));
+    ///                 self.ctx.emit(Work {}, worker_id, 1.0);
+    ///                 self.ctx.emit(Work {}, worker_id, 2.0);
+    ///             }
+    ///         })
+    ///     }
+    /// }
+    ///
+    /// let mut sim = Simulation::new(123);
+    /// let done = Rc::new(RefCell::new(0));
+    /// let manager = Manager { ctx: sim.create_context("manager"), done: done.clone() };
+    /// let manager_id = sim.add_handler("manager", Rc::new(RefCell::new(manager)));
+    /// sim.create_context("client").emit(Spawn {}, manager_id, 1.0);
+    /// sim.step_until_no_events();
+    /// // the second event is dropped since the worker is retired
+    /// assert_eq!(*done.borrow(), 1);
+    /// assert_eq!(sim.time(), 3.0);
+    /// ```
+    pub fn add_handler<S>(&self, name: S, handler: Rc<RefCell<dyn EventHandler>>) -> Id
+    where
+        S: AsRef<str>,
+    {
+        self.registry.borrow_mut().add_handler(name.as_ref(), handler)
+    }
+
+    /// Registers the component instance with specified name, so that it can be looked up by name.
+    ///
+    /// Same as [`Simulation::register_component()`](crate::Simulation::register_component()), but can be used
+    /// while the simulation is running.
+    pub fn register_component<S, T>(&self, name: S, component: Rc<RefCell<T>>) -> Id
+    where
+        S: AsRef<str>,
+        T: 'static,
+    {
+        self.registry.borrow_mut().register_instance(name.as_ref(), component)
+    }
+
+    /// Returns the typed handle of component instance registered with specified name.
+    ///
+    /// See [`Simulation::add_component()`](crate::Simulation::add_component()) for example.
+    /// Panics if there is no registered component with such name and type.
+    pub fn component<T: 'static>(&self, name: &str) -> Rc<RefCell<T>> {
+        self.try_component(name).unwrap_or_else(|| {
+            panic!(
+                "Component {} of type {} is not registered",
+                name,
+                std::any::type_name::<T>()
+            )
+        })
+    }
+
+    /// Returns the typed handle of component instance registered with specified name,
+    /// or `None` if there is no registered component with such name and type.
+    pub fn try_component<T: 'static>(&self, name: &str) -> Option<Rc<RefCell<T>>> {
+        self.registry.borrow().instance(name)
+    }
+
+    /// Retires the component with specified name.
+    ///
+    /// Same as [`Simulation::retire_component()`](crate::Simulation::retire_component()), but can be used by
+    /// components to retire other components or themselves while the simulation is running.
+    pub fn retire_component<S>(&self, name: S, pending: PendingEvents)
+    where
+        S: AsRef<str>,
+    {
+        self.registry.borrow_mut().retire(name.as_ref(), pending);
+    }
+
+    /// Spawns an asynchronous task, which is executed by the simulation.
+    ///
+    /// The task is started right after the current event is processed, at the current simulation time.
+    /// Inside the task, the component can wait for events with [`recv_event()`](Self::recv_event()) and
+    /// similar methods, or for the specified time with [`sleep()`](Self::sleep()).
+    /// See [`async_mode`](crate::async_mode) for details.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::cell::RefCell;
+    /// use std::rc::Rc;
+    /// use serde::Serialize;
+    /// use dslab_core::{cast, Event, EventHandler, Id, Simulation, SimulationContext};
+    ///
+    /// #[derive(Clone, Serialize)]
+    /// pub struct Request {
+    ///     value: u32,
+    /// }
+    ///
+    /// #[derive(Clone, Serialize)]
+    /// pub struct Response {
+    ///     value: u32,
+    /// }
+    ///
+    /// pub struct Server {
+    ///     ctx: SimulationContext,
+    /// }
+    ///
+    /// impl EventHandler for Server {
+    ///     fn on(&mut self, event: Event) {
+    ///         cast!(match event.data {
+    ///             Request { value } => {
+    ///                 // the server responds only to even requests
+    ///                 if value % 2 == 0 {
+    ///                     self.ctx.emit(Response { value: value * 10 }, event.src, 1.0);
+    ///                 }
+    ///             }
+    ///         })
+    ///     }
+    /// }
+    ///
+    /// let mut sim = Simulation::new(123);
+    /// let server = Server { ctx: sim.create_context("server") };
+    /// let server_id = sim.add_handler("server", Rc::new(RefCell::new(server)));
+    /// let client_ctx = Rc::new(sim.create_context("client"));
+    /// let results = Rc::new(RefCell::new(Vec::new()));
+    ///
+    /// let ctx = client_ctx.clone();
+    /// let client_results = results.clone();
+    /// client_ctx.spawn(async move {
+    ///     for value in 0..3 {
+    ///         ctx.emit(Request { value }, server_id, 0.5);
+    ///         // wait for response at most 3 time units
+    ///         let response = ctx.recv_event_from::<Response>(server_id).with_timeout(3.).await;
+    ///         client_results.borrow_mut().push((ctx.time(), response.map(|e| e.data.value)));
+    ///         ctx.sleep(1.).await;
+    ///     }
+    /// });
+    /// sim.step_until_no_events();
+    /// assert_eq!(*results.borrow(), vec![(1.5, Some(0)), (5.5, None), (8., Some(20))]);
+    /// ```
+    pub fn spawn(&self, future: impl Future<Output = ()> + 'static) {
+        self.runtime.borrow_mut().spawn(future);
+    }
+
+    fn awaits(&self) -> AwaitRegistrar {
+        AwaitRegistrar {
+            runtime: self.runtime.clone(),
+            sim_state: self.sim_state.clone(),
+            component: self.id,
+        }
+    }
+
+    /// Returns a future which is resolved with the next event of type `T` destined for this component.
+    ///
+    /// The awaited event is passed to the future instead of the component's event handler.
+    /// If several futures await the same event type, the event is passed to the earliest created one.
+    pub fn recv_event<T: EventData>(&self) -> EventFuture<T> {
+        self.awaits().event(None)
+    }
+
+    /// Returns a future which is resolved with the next event of type `T` sent by the specified component.
+    pub fn recv_event_from<T: EventData>(&self, src: Id) -> EventFuture<T> {
+        self.awaits()
+            .event(Some(Box::new(move |event: &Event| event.src == src)))
+    }
+
+    /// Returns a future which is resolved with the next event of type `T` whose payload satisfies the predicate,
+    /// e.g. the completion of a specific data transfer.
+    pub fn recv_event_by<T, F>(&self, pred: F) -> EventFuture<T>
+    where
+        T: EventData,
+        F: Fn(&T) -> bool + 'static,
+    {
+        self.awaits().event(Some(Box::new(move |event: &Event| {
+            event.data.downcast_ref::<T>().map_or(false, &pred)
+        })))
+    }
+
+    /// Returns a future which is resolved after the specified simulation time passes.
+    pub fn sleep(&self, duration: f64) -> SleepFuture {
+        self.awaits().sleep(duration)
+    }
 }
@@ -0,0 +1,227 @@
+//! Interactive debugging of simulation.
+//!
+//! The simulation can be stopped before processing events matching the breakpoints set via
+//! [`Simulation::add_breakpoint()`](crate::Simulation::add_breakpoint()) and resumed via
+//! [`Simulation::run_to_breakpoint()`](crate::Simulation::run_to_breakpoint()). The same functionality is available
+//! via text console started by [`Simulation::debug_console()`](crate::Simulation::debug_console()), which also allows
+//! to step through the simulation event by event and inspect the pending events.
+
+use std::io::{BufRead, Write};
+
+use serde_json::json;
+use serde_type_name::type_name;
+
+use crate::component::Id;
+use crate::event::{Event, EventId};
+use crate::simulation::Simulation;
+use crate::units::LogTime;
+
+/// Identifier of breakpoint.
+pub type BreakpointId = u32;
+
+/// Condition for stopping the simulation before processing an event.
+pub enum Breakpoint {
+    /// Stops before processing an event with payload of specified type (e.g. `"TaskCompleted"`).
+    EventType(String),
+    /// Stops before processing an event emitted by or destined for the specified component.
+    Component(Id),
+    /// Stops before processing the first event with time not less than the specified time.
+    ///
+    /// In contrast to other breakpoints, this breakpoint is removed after it is hit.
+    Time(f64),
+    /// Stops before processing an event satisfying the predicate.
+    Condition(Box<dyn Fn(&Event) -> bool>),
+}
+
+impl Breakpoint {
+    fn matches(&self, event: &Event) -> bool {
+        match self {
+            Breakpoint::EventType(name) => type_name(&event.data).map_or(false, |t| t == name),
+            Breakpoint::Component(id) => event.src == *id || event.dst == *id,
+            Breakpoint::Time(time) => event.time >= *time,
+            Breakpoint::Condition(pred) => pred(event),
+        }
+    }
+
+    fn describe(&self, sim: &Simulation) -> String {
+        match self {
+            Breakpoint::EventType(name) => format!("event type {}", name),
+            Breakpoint::Component(id) => format!("component {}", sim.lookup_name(*id)),
+            Breakpoint::Time(time) => format!("time {}", time),
+            Breakpoint::Condition(_) => "condition".to_string(),
+        }
+    }
+}
+
+/// Information about the hit breakpoint.
+pub struct BreakpointHit {
+    /// Identifier of the hit breakpoint.
+    pub breakpoint: BreakpointId,
+    /// The next pending event which matched the breakpoint, this event is not processed yet.
+    pub event: Event,
+}
+
+/// Set of breakpoints owned by simulation.
+#[derive(Default)]
+pub(crate) struct Breakpoints {
+    items: Vec<(BreakpointId, Breakpoint)>,
+    next_id: BreakpointId,
+    // event on which the simulation was stopped, it is not checked again when the simulation is resumed
+    stopped_at: Option<EventId>,
+}
+
+impl Breakpoints {
+    pub fn add(&mut self, breakpoint: Breakpoint) -> BreakpointId {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.items.push((id, breakpoint));
+        id
+    }
+
+    pub fn remove(&mut self, id: BreakpointId) -> bool {
+        let len = self.items.len();
+        self.items.retain(|(bp_id, _)| *bp_id != id);
+        self.items.len() < len
+    }
+
+    /// Returns the breakpoint matching the event, if any. The hit time breakpoint is removed.
+    pub fn check(&mut self, event: &Event) -> Option<BreakpointId> {
+        if self.stopped_at == Some(event.id) {
+            return None;
+        }
+        let position = self.items.iter().position(|(_, bp)| bp.matches(event))?;
+        let id = self.items[position].0;
+        if let Breakpoint::Time(_) = self.items[position].1 {
+            self.items.remove(position);
+        }
+        self.stopped_at = Some(event.id);
+        Some(id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &(BreakpointId, Breakpoint)> {
+        self.items.iter()
+    }
+}
+
+fn format_event(sim: &Simulation, event: &Event) -> String {
+    format!(
+        "#{} {:.3} {} -> {} {} {}",
+        event.id,
+        LogTime(event.time),
+        sim.lookup_name(event.src),
+        sim.lookup_name(event.dst),
+        type_name(&event.data).unwrap(),
+        json!(event.data)
+    )
+}
+
+const HELP: &str = "\
+Commands:
+  s, step [N]            process the next N events (default 1)
+  c, continue            run until the next breakpoint or the end of simulation
+  n, next                show the next pending event
+  q, queue [N]           show the first N pending events (default 10)
+  t, time                show the current simulation time
+  b type <TYPE>          break on events of specified type
+  b comp <NAME>          break on events emitted by or destined for the component
+  b time <TIME>          break on the first event at or after the specified time
+  bl                     list breakpoints
+  d <ID>                 delete breakpoint
+  h, help                show this help
+  exit                   exit the console";
+
+/// Runs the debug console reading the commands from `input` and writing the output to `output`.
+pub(crate) fn run_console<R: BufRead, W: Write>(sim: &mut Simulation, input: R, mut output: W) -> std::io::Result<()> {
+    writeln!(output, "{}", HELP)?;
+    write!(output, "({:.3}) > ", LogTime(sim.time()))?;
+    output.flush()?;
+    for line in input.lines() {
+        let line = line?;
+        let args: Vec<&str> = line.split_whitespace().collect();
+        match args.as_slice() {
+            [] => {}
+            ["s" | "step"] | ["s" | "step", _] => {
+                let count = match args.get(1).map(|n| n.parse::<u64>()) {
+                    None => 1,
+                    Some(Ok(count)) => count,
+                    Some(Err(_)) => {
+                        writeln!(output, "Invalid number of steps: {}", args[1])?;
+                        0
+                    }
+                };
+                for _ in 0..count {
+                    match sim.peek_event() {
+                        Some(event) => {
+                            writeln!(output, "{}", format_event(sim, &event))?;
+                            sim.step();
+                        }
+                        None => {
+                            writeln!(output, "No pending events")?;
+                            break;
+                        }
+                    }
+                }
+            }
+            ["c" | "continue"] => match sim.run_to_breakpoint() {
+                Some(hit) => writeln!(
+                    output,
+                    "Breakpoint {} hit before event {}",
+                    hit.breakpoint,
+                    format_event(sim, &hit.event)
+                )?,
+                None => writeln!(output, "No pending events")?,
+            },
+            ["n" | "next"] => match sim.peek_event() {
+                Some(event) => writeln!(output, "{}", format_event(sim, &event))?,
+                None => writeln!(output, "No pending events")?,
+            },
+            ["q" | "queue"] | ["q" | "queue", _] => {
+                let limit = args.get(1).and_then(|n| n.parse::<usize>().ok()).unwrap_or(10);
+                let events = sim.dump_events();
+                writeln!(output, "{} pending events", events.len())?;
+                for event in events.iter().take(limit) {
+                    writeln!(output, "  {}", format_event(sim, event))?;
+                }
+            }
+            ["t" | "time"] => writeln!(output, "{:.3}", LogTime(sim.time()))?,
+            ["b", "type", name] => {
+                let id = sim.add_breakpoint(Breakpoint::EventType(name.to_string()));
+                writeln!(output, "Breakpoint {} set", id)?;
+            }
+            ["b", "comp", name] => match sim.lookup_component(name) {
+                Some(component) => {
+                    let id = sim.add_breakpoint(Breakpoint::Component(component));
+                    writeln!(output, "Breakpoint {} set", id)?;
+                }
+                None => writeln!(output, "Unknown component: {}", name)?,
+            },
+            ["b", "time", time] => match time.parse::<f64>() {
+                Ok(time) => {
+                    let id = sim.add_breakpoint(Breakpoint::Time(time));
+                    writeln!(output, "Breakpoint {} set", id)?;
+                }
+                Err(_) => writeln!(output, "Invalid time: {}", time)?,
+            },
+            ["bl"] => {
+                for (id, breakpoint) in sim.breakpoints.iter() {
+                    writeln!(output, "  {}: {}", id, breakpoint.describe(sim))?;
+                }
+            }
+            ["d", id] => match id.parse::<BreakpointId>() {
+                Ok(id) if sim.remove_breakpoint(id) => writeln!(output, "Breakpoint {} deleted", id)?,
+                _ => writeln!(output, "Unknown breakpoint: {}", id)?,
+            },
+            ["h" | "help"] => writeln!(output, "{}", HELP)?,
+            ["exit"] => break,
+            _ => writeln!(
+                output,
+                "Unknown command: {}, type help for the list of commands",
+                line.trim()
+            )?,
+        }
+        write!(output, "({:.3}) > ", LogTime(sim.time()))?;
+        output.flush()?;
+    }
+    writeln!(output)?;
+    Ok(())
+}
@@ -39,6 +39,49 @@ pub struct Event {
     pub data: Box<dyn EventData>,
 }
 
+impl Event {
+    /// Converts event into [`TypedEvent`] with payload of type `T`.
+    ///
+    /// Returns the original event if its payload has another type.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use serde::Serialize;
+    /// use dslab_core::Simulation;
+    ///
+    /// #[derive(Clone, Serialize)]
+    /// pub struct SomeEvent {
+    ///     some_field: u32,
+    /// }
+    ///
+    /// #[derive(Clone, Serialize)]
+    /// pub struct AnotherEvent {
+    /// }
+    ///
+    /// let mut sim = Simulation::new(123);
+    /// let mut comp_ctx = sim.create_context("comp");
+    /// comp_ctx.emit_self(SomeEvent { some_field: 16 }, 1.2);
+    /// let event = sim.dump_events().pop().unwrap();
+    /// let event = event.downcast::<AnotherEvent>().err().unwrap();
+    /// let typed = event.downcast::<SomeEvent>().ok().unwrap();
+    /// assert_eq!(typed.time, 1.2);
+    /// assert_eq!(typed.data.some_field, 16);
+    /// ```
+    pub fn downcast<T: EventData>(self) -> Result<TypedEvent<T>, Event> {
+        match self.data.downcast::<T>() {
+            Ok(data) => Ok(TypedEvent {
+                id: self.id,
+                time: self.time,
+                src: self.src,
+                dst: self.dst,
+                data: *data,
+            }),
+            Err(data) => Err(Event { data, ..self }),
+        }
+    }
+}
+
 impl Eq for Event {}
 
 impl PartialEq for Event {
@@ -58,3 +101,40 @@ impl PartialOrd for Event {
         Some(self.cmp(other))
     }
 }
+
+/// Ordering of events scheduled at the same time.
+///
+/// Set via [`Simulation::set_event_ordering()`](crate::Simulation::set_event_ordering()).
+pub enum EventOrdering {
+    /// Simultaneous events are processed in the order of their creation (by event id).
+    ///
+    /// This is the default ordering. Note that an event emitted with zero delay is processed after all other
+    /// already emitted events with the same time.
+    InsertionOrder,
+    /// Simultaneous events are processed in the order of priorities of their destination components
+    /// (lower value first), and then in the order of their creation.
+    ///
+    /// The priorities are set via [`Simulation::set_component_priority()`](crate::Simulation::set_component_priority()),
+    /// the default priority is 0.
+    ComponentPriority,
+    /// Simultaneous events are processed in the order of keys returned by the user-provided function
+    /// (lower value first), and then in the order of their creation.
+    Custom(Box<dyn Fn(&Event) -> i64>),
+}
+
+/// Representation of event with payload of concrete type.
+///
+/// Passed to [`Handle`](crate::handler::Handle) implementations by typed event dispatch.
+#[derive(Clone)]
+pub struct TypedEvent<T> {
+    /// Unique event identifier.
+    pub id: EventId,
+    /// Time of event occurrence.
+    pub time: f64,
+    /// Identifier of event source.
+    pub src: Id,
+    /// Identifier of event destination.
+    pub dst: Id,
+    /// Event payload.
+    pub data: T,
+}
@@ -0,0 +1,461 @@
+//! Injection of faults into simulation components.
+//!
+//! [`FaultInjector`] is a component which injects the faults described in [`FaultScenario`] into other components:
+//! crashes of actors or hosts (crash-stop or crash-recovery), message drops and delays, disk errors, etc.
+//! Each fault is triggered either at the specified time or stochastically according to a Poisson process,
+//! and can be automatically recovered after the specified duration.
+//!
+//! The injector does not know how a particular fault affects the target component. It only delivers
+//! [`FaultInjected`] event to the target when the fault occurs and [`FaultRecovered`] event when the fault is over,
+//! so each component implements its reaction to the faults by handling these events. All injected faults are recorded
+//! and can be obtained via [`FaultInjector::records()`] or saved to a JSON file with the simulation output.
+//!
+//! The scenario can be described in a YAML file:
+//!
+//! ```yaml
+//! faults:
+//!   # crash-recovery of host1 at time 10 with recovery after 5 seconds
+//!   - target: host1
+//!     fault:
+//!       type: Crash
+//!     trigger:
+//!       type: At
+//!       time: 10
+//!     duration: 5
+//!   # message drops in the network occurring on average every 100 seconds and lasting 2 seconds
+//!   - target: network
+//!     fault:
+//!       type: MessageDrop
+//!       probability: 0.5
+//!     trigger:
+//!       type: Poisson
+//!       rate: 0.01
+//!       until: 1000
+//!     duration: 2
+//! ```
+//!
+//! # Examples
+//!
+//! ```rust
+//! use std::cell::RefCell;
+//! use std::rc::Rc;
+//! use dslab_core::faults::{FaultInjected, FaultInjector, FaultKind, FaultRecovered, FaultScenario, FaultSpec, FaultTrigger};
+//! use dslab_core::{cast, Event, EventHandler, Simulation};
+//!
+//! pub struct Host {
+//!     crashed: bool,
+//! }
+//!
+//! impl EventHandler for Host {
+//!     fn on(&mut self, event: Event) {
+//!         cast!(match event.data {
+//!             FaultInjected { kind, .. } => {
+//!                 if kind == FaultKind::Crash {
+//!                     self.crashed = true;
+//!                 }
+//!             }
+//!             FaultRecovered { kind, .. } => {
+//!                 if kind == FaultKind::Crash {
+//!                     self.crashed = false;
+//!                 }
+//!             }
+//!         })
+//!     }
+//! }
+//!
+//! let mut sim = Simulation::new(123);
+//! let host = Rc::new(RefCell::new(Host { crashed: false }));
+//! sim.add_handler("host", host.clone());
+//!
+//! let scenario = FaultScenario::default()
+//!     .with_fault(FaultSpec::new("host", FaultKind::Crash, FaultTrigger::At { time: 10. }).with_duration(5.));
+//! let injector = FaultInjector::install(&mut sim, &scenario).unwrap();
+//!
+//! sim.step_until_time(12.);
+//! assert!(host.borrow().crashed);
+//! sim.step_until_no_events();
+//! assert!(!host.borrow().crashed);
+//!
+//! let records = injector.borrow().records().to_vec();
+//! assert_eq!(records.len(), 1);
+//! assert_eq!(records[0].target, "host");
+//! assert_eq!(records[0].injected_at, 10.);
+//! assert_eq!(records[0].recovered_at, Some(15.));
+//! ```
+
+use std::cell::RefCell;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::rc::Rc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::component::Id;
+use crate::context::SimulationContext;
+use crate::event::Event;
+use crate::handler::EventHandler;
+use crate::simulation::Simulation;
+use crate::{cast, log_info};
+
+/// Kind of injected fault.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum FaultKind {
+    /// Crash of an actor or a host.
+    ///
+    /// Corresponds to crash-stop failure if the fault duration is not specified and to crash-recovery failure otherwise.
+    Crash,
+    /// Dropping of messages with the specified probability.
+    MessageDrop {
+        /// Probability of dropping a message.
+        probability: f64,
+    },
+    /// Additional delay of messages.
+    MessageDelay {
+        /// Added delay.
+        delay: f64,
+    },
+    /// Failure of disk operations with the specified probability.
+    DiskError {
+        /// Probability of failing a disk operation.
+        probability: f64,
+    },
+    /// User-defined fault interpreted by the target component.
+    Custom {
+        /// Fault name.
+        name: String,
+    },
+}
+
+/// Defines when the fault is injected.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum FaultTrigger {
+    /// Injects the fault once at the specified time.
+    At {
+        /// Injection time.
+        time: f64,
+    },
+    /// Injects the fault repeatedly according to a Poisson process with the specified rate,
+    /// i.e. the intervals between the faults are exponentially distributed.
+    Poisson {
+        /// Mean number of faults per time unit.
+        rate: f64,
+        /// Start time of the process.
+        #[serde(default)]
+        start: f64,
+        /// Time after which no faults are injected.
+        #[serde(default)]
+        until: Option<f64>,
+        /// Maximum number of injected faults.
+        #[serde(default)]
+        max_count: Option<u64>,
+    },
+}
+
+impl FaultTrigger {
+    /// Creates Poisson trigger with the specified rate starting at time zero without limits.
+    pub fn poisson(rate: f64) -> Self {
+        Self::Poisson {
+            rate,
+            start: 0.,
+            until: None,
+            max_count: None,
+        }
+    }
+}
+
+/// Description of a fault injected into a component.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FaultSpec {
+    /// Name of the target component.
+    pub target: String,
+    /// Fault kind.
+    pub fault: FaultKind,
+    /// Fault trigger.
+    pub trigger: FaultTrigger,
+    /// Fault duration, after which the target component recovers.
+    ///
+    /// The fault is permanent if the duration is not specified.
+    #[serde(default)]
+    pub duration: Option<f64>,
+}
+
+impl FaultSpec {
+    /// Creates a permanent fault.
+    pub fn new<S: Into<String>>(target: S, fault: FaultKind, trigger: FaultTrigger) -> Self {
+        Self {
+            target: target.into(),
+            fault,
+            trigger,
+            duration: None,
+        }
+    }
+
+    /// Sets the fault duration.
+    pub fn with_duration(mut self, duration: f64) -> Self {
+        self.duration = Some(duration);
+        self
+    }
+}
+
+/// Set of faults injected during the simulation.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct FaultScenario {
+    /// Injected faults.
+    #[serde(default)]
+    pub faults: Vec<FaultSpec>,
+}
+
+impl FaultScenario {
+    /// Reads the scenario from YAML file.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let path = path.as_ref();
+        let data = std::fs::read_to_string(path)
+            .map_err(|e| format!("Can't read fault scenario from {}: {}", path.display(), e))?;
+        Self::from_yaml(&data)
+    }
+
+    /// Parses the scenario from YAML string.
+    pub fn from_yaml(data: &str) -> Result<Self, String> {
+        serde_yaml::from_str(data).map_err(|e| format!("Can't parse fault scenario: {}", e))
+    }
+
+    /// Adds the fault to the scenario.
+    pub fn with_fault(mut self, fault: FaultSpec) -> Self {
+        self.faults.push(fault);
+        self
+    }
+}
+
+/// Event delivered to the target component when the fault is injected.
+#[derive(Clone, Serialize)]
+pub struct FaultInjected {
+    /// Fault identifier.
+    pub fault_id: u64,
+    /// Fault kind.
+    pub kind: FaultKind,
+    /// Fault duration, if the fault will be recovered.
+    pub duration: Option<f64>,
+}
+
+/// Event delivered to the target component when the fault is recovered.
+#[derive(Clone, Serialize)]
+pub struct FaultRecovered {
+    /// Fault identifier.
+    pub fault_id: u64,
+    /// Fault kind.
+    pub kind: FaultKind,
+}
+
+#[derive(Clone, Serialize)]
+struct TriggerFault {
+    spec_idx: usize,
+}
+
+#[derive(Clone, Serialize)]
+struct RecoverFault {
+    fault_id: u64,
+}
+
+/// Record of an injected fault.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct FaultRecord {
+    /// Fault identifier.
+    pub id: u64,
+    /// Name of the target component.
+    pub target: String,
+    /// Fault kind.
+    pub kind: FaultKind,
+    /// Time of fault injection.
+    pub injected_at: f64,
+    /// Time of fault recovery, if the fault is recovered.
+    pub recovered_at: Option<f64>,
+}
+
+struct SpecState {
+    spec: FaultSpec,
+    target: Id,
+    injected_count: u64,
+    active_fault: Option<u64>,
+}
+
+/// Component which injects the faults from [`FaultScenario`] into other components.
+///
+/// The next fault from the same specification is not injected while the previous one is active.
+pub struct FaultInjector {
+    specs: Vec<SpecState>,
+    records: Vec<FaultRecord>,
+    ctx: SimulationContext,
+}
+
+impl FaultInjector {
+    /// Name of the injector component.
+    pub const NAME: &'static str = "fault_injector";
+
+    /// Creates the injector, registers it in the simulation and schedules the faults from the scenario.
+    ///
+    /// The target components should be registered before calling this method.
+    pub fn install(sim: &mut Simulation, scenario: &FaultScenario) -> Result<Rc<RefCell<Self>>, String> {
+        let mut specs = Vec::with_capacity(scenario.faults.len());
+        for spec in scenario.faults.iter() {
+            let target = sim
+                .lookup_component(&spec.target)
+                .ok_or_else(|| format!("Unknown fault target: {}", spec.target))?;
+            Self::validate(spec)?;
+            specs.push(SpecState {
+                spec: spec.clone(),
+                target,
+                injected_count: 0,
+                active_fault: None,
+            });
+        }
+        let injector = Self {
+            specs,
+            records: Vec::new(),
+            ctx: sim.create_context(Self::NAME),
+        };
+        for spec_idx in 0..injector.specs.len() {
+            injector.schedule_next(spec_idx);
+        }
+        let injector = Rc::new(RefCell::new(injector));
+        sim.add_handler(Self::NAME, injector.clone());
+        Ok(injector)
+    }
+
+    /// Returns the records of injected faults in the order of injection.
+    pub fn records(&self) -> &[FaultRecord] {
+        &self.records
+    }
+
+    /// Returns the number of currently active faults.
+    pub fn active_fault_count(&self) -> usize {
+        self.specs.iter().filter(|s| s.active_fault.is_some()).count()
+    }
+
+    /// Saves the records of injected faults to the specified file as JSON array.
+    pub fn save_records<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer_pretty(&mut writer, &self.records)?;
+        writer.flush()
+    }
+
+    fn validate(spec: &FaultSpec) -> Result<(), String> {
+        match &spec.trigger {
+            FaultTrigger::At { time } if *time < 0. => {
+                return Err(format!("Negative fault time for target {}", spec.target));
+            }
+            FaultTrigger::Poisson { rate, .. } if *rate <= 0. => {
+                return Err(format!("Non-positive fault rate for target {}", spec.target));
+            }
+            _ => {}
+        }
+        if spec.duration.map_or(false, |duration| duration < 0.) {
+            return Err(format!("Negative fault duration for target {}", spec.target));
+        }
+        Ok(())
+    }
+
+    fn schedule_next(&self, spec_idx: usize) {
+        let state = &self.specs[spec_idx];
+        let time = match state.spec.trigger {
+            FaultTrigger::At { time } => {
+                if state.injected_count > 0 {
+                    return;
+                }
+                time
+            }
+            FaultTrigger::Poisson {
+                rate,
+                start,
+                until,
+                max_count,
+            } => {
+                if max_count.map_or(false, |max_count| state.injected_count >= max_count) {
+                    return;
+                }
+                let time = self.ctx.time().max(start) - (1. - self.ctx.rand()).ln() / rate;
+                if until.map_or(false, |until| time > until) {
+                    return;
+                }
+                time
+            }
+        };
+        self.ctx
+            .emit_self(TriggerFault { spec_idx }, (time - self.ctx.time()).max(0.));
+    }
+
+    fn trigger_fault(&mut self, spec_idx: usize) {
+        let state = &mut self.specs[spec_idx];
+        if state.active_fault.is_none() {
+            let fault_id = self.records.len() as u64;
+            state.injected_count += 1;
+            if let Some(duration) = state.spec.duration {
+                state.active_fault = Some(fault_id);
+                self.ctx.emit_self(RecoverFault { fault_id }, duration);
+            }
+            log_info!(
+                self.ctx,
+                "injected fault {} {:?} into {}",
+                fault_id,
+                state.spec.fault,
+                state.spec.target
+            );
+            self.ctx.emit_now(
+                FaultInjected {
+                    fault_id,
+                    kind: state.spec.fault.clone(),
+                    duration: state.spec.duration,
+                },
+                state.target,
+            );
+            self.records.push(FaultRecord {
+                id: fault_id,
+                target: state.spec.target.clone(),
+                kind: state.spec.fault.clone(),
+                injected_at: self.ctx.time(),
+                recovered_at: None,
+            });
+        }
+        self.schedule_next(spec_idx);
+    }
+
+    fn recover_fault(&mut self, fault_id: u64) {
+        let record = &mut self.records[fault_id as usize];
+        record.recovered_at = Some(self.ctx.time());
+        let state = self
+            .specs
+            .iter_mut()
+            .find(|s| s.active_fault == Some(fault_id))
+            .unwrap();
+        state.active_fault = None;
+        log_info!(
+            self.ctx,
+            "recovered fault {} {:?} of {}",
+            fault_id,
+            record.kind,
+            record.target
+        );
+        self.ctx.emit_now(
+            FaultRecovered {
+                fault_id,
+                kind: record.kind.clone(),
+            },
+            state.target,
+        );
+    }
+}
+
+impl EventHandler for FaultInjector {
+    fn on(&mut self, event: Event) {
+        cast!(match event.data {
+            TriggerFault { spec_idx } => {
+                self.trigger_fault(spec_idx);
+            }
+            RecoverFault { fault_id } => {
+                self.recover_fault(fault_id);
+            }
+        })
+    }
+}
@@ -1,6 +1,6 @@
 //! Event handling.
 
-use crate::event::Event;
+use crate::event::{Event, EventData, TypedEvent};
 
 /// Trait for consuming events in simulation components.
 pub trait EventHandler {
@@ -51,6 +51,111 @@ pub trait EventHandler {
     fn on(&mut self, event: Event);
 }
 
+/// Trait for consuming events with payload of type `T`.
+///
+/// This is a typed alternative to processing events in [`EventHandler::on()`] with [`cast!`](crate::cast!).
+/// A component implements this trait for each event type it consumes, and the [`EventHandler`] implementation
+/// which dispatches events to these methods is generated by [`impl_event_handler!`](crate::impl_event_handler!).
+/// Since the handlers receive concrete event types, they can also be invoked directly, e.g. in unit tests.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::cell::RefCell;
+/// use std::rc::Rc;
+/// use serde::Serialize;
+/// use dslab_core::event::TypedEvent;
+/// use dslab_core::handler::Handle;
+/// use dslab_core::{impl_event_handler, Simulation, SimulationContext};
+///
+/// #[derive(Clone, Serialize)]
+/// pub struct SomeEvent {
+///     some_field: u32,
+/// }
+///
+/// #[derive(Clone, Serialize)]
+/// pub struct AnotherEvent {
+///     another_field: f64,
+/// }
+///
+/// pub struct Component {
+///     state: u32,
+///     total: f64,
+/// }
+///
+/// impl Handle<SomeEvent> for Component {
+///     fn handle(&mut self, event: TypedEvent<SomeEvent>) {
+///         self.state = event.data.some_field;
+///     }
+/// }
+///
+/// impl Handle<AnotherEvent> for Component {
+///     fn handle(&mut self, event: TypedEvent<AnotherEvent>) {
+///         self.total += event.data.another_field;
+///     }
+/// }
+///
+/// impl_event_handler!(Component: SomeEvent, AnotherEvent);
+///
+/// let mut sim = Simulation::new(123);
+/// let comp = Rc::new(RefCell::new(Component { state: 0, total: 0. }));
+/// let comp_id = sim.add_handler("comp", comp.clone());
+/// let client_ctx = sim.create_context("client");
+/// client_ctx.emit(SomeEvent { some_field: 16 }, comp_id, 1.2);
+/// client_ctx.emit(AnotherEvent { another_field: 1.6 }, comp_id, 2.5);
+/// sim.step_until_no_events();
+/// assert_eq!(comp.borrow().state, 16);
+/// assert_eq!(comp.borrow().total, 1.6);
+///
+/// // handlers can be invoked directly without simulation
+/// let mut comp = Component { state: 0, total: 0. };
+/// comp.handle(TypedEvent { id: 0, time: 0., src: 0, dst: 1, data: SomeEvent { some_field: 8 } });
+/// assert_eq!(comp.state, 8);
+/// ```
+pub trait Handle<T: EventData> {
+    /// Processes event with payload of type `T`.
+    fn handle(&mut self, event: TypedEvent<T>);
+}
+
+/// Implements [`EventHandler`] for a component by dispatching events to its [`Handle`] implementations
+/// for the listed event types.
+///
+/// The component must implement [`Handle<T>`](Handle) for each listed type `T`, which is checked at compile time.
+/// Events with payload of other types are logged as unhandled under `ERROR` level, same as in [`cast!`](crate::cast!).
+///
+/// See [`Handle`] for usage example.
+///
+/// ```compile_fail
+/// use serde::Serialize;
+/// use dslab_core::impl_event_handler;
+///
+/// #[derive(Clone, Serialize)]
+/// pub struct SomeEvent {
+/// }
+///
+/// pub struct Component {
+/// }
+///
+/// // should not compile because Component does not implement Handle<SomeEvent>
+/// impl_event_handler!(Component: SomeEvent);
+/// ```
+#[macro_export]
+macro_rules! impl_event_handler {
+    ( $component:ty : $( $type:ty ),+ $(,)? ) => {
+        impl $crate::EventHandler for $component {
+            fn on(&mut self, event: $crate::Event) {
+                $(
+                    let event = match event.downcast::<$type>() {
+                        Ok(typed) => return $crate::handler::Handle::<$type>::handle(self, typed),
+                        Err(event) => event,
+                    };
+                )+
+                $crate::log::log_unhandled_event(event);
+            }
+        }
+    };
+}
+
 /// Enables the use of pattern matching syntax for processing different types of events
 /// by downcasting the event payload from [`EventData`](crate::event::EventData) to user-defined types.
 ///
@@ -0,0 +1,171 @@
+//! Hashing of processed events for reproducibility verification.
+//!
+//! When event hashing is enabled via [`Simulation::enable_event_hashing()`](crate::Simulation::enable_event_hashing()),
+//! the simulation computes a rolling hash of processed events, which covers the time, payload type, source and
+//! destination of each event. Two runs of a deterministic simulation with the same seed must produce the same hash.
+//! To find the first event at which the runs diverge (e.g. because of `HashMap` iteration order), the hash after
+//! each event can be recorded into [`EventHashLog`] in the first run and checked in the second run.
+
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+use log::error;
+use serde_type_name::type_name;
+
+use crate::component::Id;
+use crate::event::{Event, EventId};
+use crate::units::LogTime;
+
+const FNV_OFFSET: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+fn fnv1a(hash: u64, bytes: &[u8]) -> u64 {
+    bytes
+        .iter()
+        .fold(hash, |hash, byte| (hash ^ *byte as u64).wrapping_mul(FNV_PRIME))
+}
+
+/// Computes the hash of event, which does not depend on the process (in contrast to the default Rust hasher).
+fn event_hash(event: &Event) -> u64 {
+    let mut hash = fnv1a(FNV_OFFSET, &(event.time + 0.).to_bits().to_le_bytes());
+    hash = fnv1a(hash, type_name(&event.data).unwrap().as_bytes());
+    hash = fnv1a(hash, &event.src.to_le_bytes());
+    fnv1a(hash, &event.dst.to_le_bytes())
+}
+
+/// Sequence of rolling hashes after each processed event.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EventHashLog {
+    hashes: Vec<u64>,
+}
+
+impl EventHashLog {
+    /// Returns the rolling hashes after each processed event.
+    pub fn hashes(&self) -> &[u64] {
+        &self.hashes
+    }
+
+    /// Returns the number of recorded events.
+    pub fn len(&self) -> usize {
+        self.hashes.len()
+    }
+
+    /// Returns true if no events are recorded.
+    pub fn is_empty(&self) -> bool {
+        self.hashes.is_empty()
+    }
+
+    /// Returns the index of the first event at which the logs differ, if any.
+    ///
+    /// If one log is a prefix of the other, the index of the first event missing in the shorter log is returned.
+    pub fn first_divergence(&self, other: &EventHashLog) -> Option<usize> {
+        let common = self.hashes.len().min(other.hashes.len());
+        (0..common)
+            .find(|&i| self.hashes[i] != other.hashes[i])
+            .or(if self.hashes.len() != other.hashes.len() {
+                Some(common)
+            } else {
+                None
+            })
+    }
+
+    /// Saves the log to a text file with one hexadecimal hash per line.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        for hash in &self.hashes {
+            writeln!(writer, "{:016x}", hash)?;
+        }
+        writer.flush()
+    }
+
+    /// Loads the log saved via [`save()`](Self::save()).
+    pub fn load<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        let mut hashes = Vec::new();
+        for line in BufReader::new(File::open(path)?).lines() {
+            let line = line?;
+            let hash = u64::from_str_radix(line.trim(), 16)
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+            hashes.push(hash);
+        }
+        Ok(Self { hashes })
+    }
+}
+
+/// The first processed event at which the simulation diverged from the reference log.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Divergence {
+    /// Index of the event in the sequence of processed events.
+    pub index: u64,
+    /// Event identifier.
+    pub event_id: EventId,
+    /// Event time.
+    pub time: f64,
+    /// Name of event payload type.
+    pub event_type: String,
+    /// Event source.
+    pub src: Id,
+    /// Event destination.
+    pub dst: Id,
+}
+
+/// Computes the rolling hash of processed events, optionally recording or checking the hash log.
+pub(crate) struct EventHasher {
+    hash: u64,
+    count: u64,
+    log: Option<Vec<u64>>,
+    reference: Option<Vec<u64>>,
+    divergence: Option<Divergence>,
+}
+
+impl EventHasher {
+    pub fn new(record: bool, reference: Option<EventHashLog>) -> Self {
+        Self {
+            hash: FNV_OFFSET,
+            count: 0,
+            log: if record { Some(Vec::new()) } else { None },
+            reference: reference.map(|log| log.hashes),
+            divergence: None,
+        }
+    }
+
+    pub fn on_process(&mut self, event: &Event) {
+        self.hash = fnv1a(self.hash, &event_hash(event).to_le_bytes());
+        if let Some(log) = self.log.as_mut() {
+            log.push(self.hash);
+        }
+        if let Some(reference) = &self.reference {
+            if self.divergence.is_none() && reference.get(self.count as usize) != Some(&self.hash) {
+                let divergence = Divergence {
+                    index: self.count,
+                    event_id: event.id,
+                    time: event.time,
+                    event_type: type_name(&event.data).unwrap().to_owned(),
+                    src: event.src,
+                    dst: event.dst,
+                };
+                error!(
+                    target: "simulation",
+                    "[{:.3} {} simulation] Divergence from reference event hash log: {:?}",
+                    LogTime(event.time),
+                    crate::log::get_colored("ERROR", colored::Color::Red),
+                    divergence
+                );
+                self.divergence = Some(divergence);
+            }
+        }
+        self.count += 1;
+    }
+
+    pub fn hash(&self) -> u64 {
+        self.hash
+    }
+
+    pub fn log(&self) -> Option<EventHashLog> {
+        self.log.as_ref().map(|hashes| EventHashLog { hashes: hashes.clone() })
+    }
+
+    pub fn divergence(&self) -> Option<&Divergence> {
+        self.divergence.as_ref()
+    }
+}
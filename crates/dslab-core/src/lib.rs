@@ -1,13 +1,30 @@
 #![warn(missing_docs)]
 #![doc = include_str!("../readme.md")]
 
+pub mod alloc;
+pub mod async_mode;
 pub mod component;
 pub mod context;
+pub mod debug;
 pub mod event;
+pub mod faults;
 pub mod handler;
+pub mod hashing;
 pub mod log;
+mod pacing;
+pub mod parallel;
+pub mod profiling;
+mod queue;
+pub mod replay;
+pub mod replication;
 pub mod simulation;
 mod state;
+pub mod statistics;
+pub mod stop;
+pub mod summary;
+pub mod timer;
+pub mod trace;
+pub mod units;
 
 pub use colored;
 pub use component::Id;
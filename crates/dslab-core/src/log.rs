@@ -1,12 +1,19 @@
 //! Logging facilities.
 
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::sync::Mutex;
+
 use atty::Stream;
 use colored::{Color, ColoredString, Colorize};
-use log::error;
+use log::{debug, error, LevelFilter, Log, Metadata, Record, SetLoggerError};
 use serde_json::json;
 use serde_type_name::type_name;
 
 use crate::event::Event;
+use crate::units::LogTime;
 
 /// Applies the color to the string if stderr (log) goes to console.
 pub fn get_colored(s: &str, color: Color) -> ColoredString {
@@ -51,14 +58,14 @@ macro_rules! log_info {
         log::info!(
             target: $ctx.name(),
             "[{:.3} {}  {}] {}",
-            $ctx.time(), $crate::log::get_colored("INFO", $crate::colored::Color::Green), $ctx.name(), $msg
+            $crate::units::LogTime($ctx.time()), $crate::log::get_colored("INFO", $crate::colored::Color::Green), $ctx.name(), $msg
         )
     );
     ($ctx:expr, $format:expr, $($arg:tt)+) => (
         log::info!(
             target: $ctx.name(),
             concat!("[{:.3} {}  {}] ", $format),
-            $ctx.time(), $crate::log::get_colored("INFO", $crate::colored::Color::Green), $ctx.name(), $($arg)+
+            $crate::units::LogTime($ctx.time()), $crate::log::get_colored("INFO", $crate::colored::Color::Green), $ctx.name(), $($arg)+
         )
     );
 }
@@ -74,14 +81,14 @@ macro_rules! log_debug {
         log::debug!(
             target: $ctx.name(),
             "[{:.3} {} {}] {}",
-            $ctx.time(), $crate::log::get_colored("DEBUG", $crate::colored::Color::Blue), $ctx.name(), $msg
+            $crate::units::LogTime($ctx.time()), $crate::log::get_colored("DEBUG", $crate::colored::Color::Blue), $ctx.name(), $msg
         )
     );
     ($ctx:expr, $format:expr, $($arg:tt)+) => (
         log::debug!(
             target: $ctx.name(),
             concat!("[{:.3} {} {}] ", $format),
-            $ctx.time(), $crate::log::get_colored("DEBUG", $crate::colored::Color::Blue), $ctx.name(), $($arg)+
+            $crate::units::LogTime($ctx.time()), $crate::log::get_colored("DEBUG", $crate::colored::Color::Blue), $ctx.name(), $($arg)+
         )
     );
 }
@@ -97,14 +104,14 @@ macro_rules! log_trace {
         log::trace!(
             target: $ctx.name(),
             "[{:.3} {} {}] {}",
-            $ctx.time(), $crate::log::get_colored("TRACE", $crate::colored::Color::Cyan), $ctx.name(), $msg
+            $crate::units::LogTime($ctx.time()), $crate::log::get_colored("TRACE", $crate::colored::Color::Cyan), $ctx.name(), $msg
         )
     );
     ($ctx:expr, $format:expr, $($arg:tt)+) => (
         log::trace!(
             target: $ctx.name(),
             concat!("[{:.3} {} {}] ", $format),
-            $ctx.time(), $crate::log::get_colored("TRACE", $crate::colored::Color::Cyan), $ctx.name(), $($arg)+
+            $crate::units::LogTime($ctx.time()), $crate::log::get_colored("TRACE", $crate::colored::Color::Cyan), $ctx.name(), $($arg)+
         )
     );
 }
@@ -120,14 +127,14 @@ macro_rules! log_error {
         log::error!(
             target: $ctx.name(),
             "[{:.3} {} {}] {}",
-            $ctx.time(), $crate::log::get_colored("ERROR", $crate::colored::Color::Red), $ctx.name(), $msg
+            $crate::units::LogTime($ctx.time()), $crate::log::get_colored("ERROR", $crate::colored::Color::Red), $ctx.name(), $msg
         )
     );
     ($ctx:expr, $format:expr, $($arg:tt)+) => (
         log::error!(
             target: $ctx.name(),
             concat!("[{:.3} {} {}] ", $format),
-            $ctx.time(), $crate::log::get_colored("ERROR", $crate::colored::Color::Red), $ctx.name(), $($arg)+
+            $crate::units::LogTime($ctx.time()), $crate::log::get_colored("ERROR", $crate::colored::Color::Red), $ctx.name(), $($arg)+
         )
     );
 }
@@ -143,14 +150,14 @@ macro_rules! log_warn {
         log::warn!(
             target: $ctx.name(),
             "[{:.3} {}  {}] {}",
-            $ctx.time(), $crate::log::get_colored("WARN", $crate::colored::Color::Yellow), $ctx.name(), $msg
+            $crate::units::LogTime($ctx.time()), $crate::log::get_colored("WARN", $crate::colored::Color::Yellow), $ctx.name(), $msg
         )
     );
     ($ctx:expr, $format:expr, $($arg:tt)+) => (
         log::warn!(
             target: $ctx.name(),
             concat!("[{:.3} {}  {}] ", $format),
-            $ctx.time(), $crate::log::get_colored("WARN", $crate::colored::Color::Yellow), $ctx.name(), $($arg)+
+            $crate::units::LogTime($ctx.time()), $crate::log::get_colored("WARN", $crate::colored::Color::Yellow), $ctx.name(), $($arg)+
         )
     );
 }
@@ -162,7 +169,7 @@ pub fn log_unhandled_event(event: Event) {
     error!(
         target: "simulation",
         "[{:.3} {} simulation] Unhandled event: {}",
-        event.time,
+        LogTime(event.time),
         crate::log::get_colored("ERROR", colored::Color::Red),
         json!({"type": type_name(&event.data).unwrap(), "data": event.data, "src": event.src, "dst": event.dst})
     );
@@ -173,20 +180,218 @@ pub(crate) fn log_undelivered_event(event: Event) {
     error!(
         target: "simulation",
         "[{:.3} {} simulation] Undelivered event: {}",
-        event.time,
+        LogTime(event.time),
         crate::log::get_colored("ERROR", colored::Color::Red),
         json!({"type": type_name(&event.data).unwrap(), "data": event.data, "src": event.src, "dst": event.dst})
     );
 }
 
+/// Logs an event dropped because its destination component is retired.
+pub(crate) fn log_dropped_event(event: Event) {
+    debug!(
+        target: "simulation",
+        "[{:.3} {} simulation] Dropped event: {}",
+        LogTime(event.time),
+        crate::log::get_colored("DEBUG", colored::Color::Blue),
+        json!({"type": type_name(&event.data).unwrap(), "data": event.data, "src": event.src, "dst": event.dst})
+    );
+}
+
 /// Logs incorrect event.
 pub(crate) fn log_incorrect_event(event: Event, msg: &str) {
     error!(
         target: "simulation",
         "[{:.3} {} simulation] Incorrect event ({}): {}",
-        event.time,
+        LogTime(event.time),
         crate::log::get_colored("ERROR", colored::Color::Red),
         msg,
         json!({"type": type_name(&event.data).unwrap(), "data": event.data, "src": event.src, "dst": event.dst})
     );
 }
+
+/// Logger for simulation messages with per-component log levels.
+///
+/// The log messages produced via [`log_info!`](crate::log_info!) and other macros of this module are prefixed with
+/// the simulation time and the component name, which is also used as the log target. This logger filters
+/// the messages by the level configured for the component (the internal simulation messages use `simulation`
+/// target), and writes them to stderr or to a separate file. The level of a component also applies to its children
+/// (see [`SimulationContext::create_child_context()`](crate::SimulationContext::create_child_context())), and
+/// the component name can end with `*` to match all components with the given prefix.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::io::Write;
+/// use std::sync::{Arc, Mutex};
+/// use log::LevelFilter;
+/// use dslab_core::log::SimulationLogger;
+/// use dslab_core::{log_debug, log_info, Simulation};
+///
+/// #[derive(Clone)]
+/// struct Buffer(Arc<Mutex<Vec<u8>>>);
+///
+/// impl Write for Buffer {
+///     fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+///         self.0.lock().unwrap().write(buf)
+///     }
+///     fn flush(&mut self) -> std::io::Result<()> {
+///         Ok(())
+///     }
+/// }
+///
+/// let buffer = Buffer(Arc::new(Mutex::new(Vec::new())));
+/// SimulationLogger::new()
+///     .default_level(LevelFilter::Debug)
+///     .component_level("net", LevelFilter::Warn)
+///     .component_level("worker-*", LevelFilter::Info)
+///     .component_level("simulation", LevelFilter::Info)
+///     .writer(Box::new(buffer.clone()))
+///     .init()
+///     .unwrap();
+///
+/// let mut sim = Simulation::new(123);
+/// let net_ctx = sim.create_context("net");
+/// let worker_ctx = sim.create_context("worker-1");
+/// let client_ctx = sim.create_context("client");
+/// log_info!(net_ctx, "message sent");
+/// log_info!(worker_ctx, "task started");
+/// log_debug!(worker_ctx, "task details");
+/// log_debug!(client_ctx, "request sent");
+///
+/// let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+/// assert_eq!(output, "[0.000 INFO  worker-1] task started\n[0.000 DEBUG client] request sent\n");
+/// ```
+pub struct SimulationLogger {
+    default_level: LevelFilter,
+    component_levels: HashMap<String, LevelFilter>,
+    writer: Mutex<Box<dyn Write + Send>>,
+    colors: bool,
+}
+
+impl Default for SimulationLogger {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SimulationLogger {
+    /// Creates a logger writing messages with `INFO` and higher levels to stderr.
+    pub fn new() -> Self {
+        Self {
+            default_level: LevelFilter::Info,
+            component_levels: HashMap::new(),
+            writer: Mutex::new(Box::new(std::io::stderr())),
+            colors: true,
+        }
+    }
+
+    /// Sets the log level for components without explicitly configured level.
+    pub fn default_level(mut self, level: LevelFilter) -> Self {
+        self.default_level = level;
+        self
+    }
+
+    /// Sets the log level for the component with specified name (or names matching the prefix ending with `*`).
+    pub fn component_level(mut self, name: &str, level: LevelFilter) -> Self {
+        self.component_levels.insert(name.to_string(), level);
+        self
+    }
+
+    /// Writes the log messages to the specified file instead of stderr.
+    pub fn log_file<P: AsRef<Path>>(self, path: P) -> std::io::Result<Self> {
+        let file = File::create(path)?;
+        Ok(self.writer(Box::new(BufWriter::new(file))))
+    }
+
+    /// Writes the log messages to the specified writer instead of stderr.
+    ///
+    /// The color codes are removed from the messages written to the writer other than stderr.
+    pub fn writer(mut self, writer: Box<dyn Write + Send>) -> Self {
+        self.writer = Mutex::new(writer);
+        self.colors = false;
+        self
+    }
+
+    /// Returns the log level for the specified target (component name).
+    ///
+    /// The most specific rule is used: exact name, then the closest parent component, then the longest prefix
+    /// ending with `*`, and finally the default level.
+    pub fn level_for(&self, target: &str) -> LevelFilter {
+        if let Some(level) = self.component_levels.get(target) {
+            return *level;
+        }
+        let mut name = target;
+        while let Some(pos) = name.rfind('/') {
+            name = &name[..pos];
+            if let Some(level) = self.component_levels.get(name) {
+                return *level;
+            }
+        }
+        self.component_levels
+            .iter()
+            .filter_map(|(pattern, level)| {
+                let prefix = pattern.strip_suffix('*')?;
+                if target.starts_with(prefix) {
+                    Some((prefix.len(), *level))
+                } else {
+                    None
+                }
+            })
+            .max_by_key(|(len, _)| *len)
+            .map_or(self.default_level, |(_, level)| level)
+    }
+
+    /// Installs the logger as the global logger.
+    pub fn init(self) -> Result<(), SetLoggerError> {
+        let max_level = self
+            .component_levels
+            .values()
+            .copied()
+            .chain(std::iter::once(self.default_level))
+            .max()
+            .unwrap();
+        log::set_boxed_logger(Box::new(self))?;
+        log::set_max_level(max_level);
+        Ok(())
+    }
+}
+
+fn strip_colors(s: &str) -> String {
+    let mut result = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            // skip the escape sequence until the final letter, e.g. \x1b[32m
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+impl Log for SimulationLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level_for(metadata.target())
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let mut message = record.args().to_string();
+        if !self.colors {
+            message = strip_colors(&message);
+        }
+        let mut writer = self.writer.lock().unwrap();
+        let _ = writeln!(writer, "{}", message);
+    }
+
+    fn flush(&self) {
+        let _ = self.writer.lock().unwrap().flush();
+    }
+}
@@ -0,0 +1,43 @@
+//! Real-time pacing of simulation progress.
+
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Throttles the simulation so that the simulation time does not advance faster than the wall-clock time
+/// multiplied by the speed factor.
+///
+/// The simulation time is mapped to the wall-clock time relative to the moment when pacing was enabled.
+/// If the simulation falls behind (e.g. because of slow event handlers), the following events are processed
+/// without waiting until the simulation catches up with the wall-clock time.
+pub(crate) struct RealTimePacer {
+    speed: f64,
+    wall_start: Instant,
+    sim_start: f64,
+}
+
+impl RealTimePacer {
+    pub fn new(speed: f64, sim_time: f64) -> Self {
+        assert!(
+            speed.is_finite() && speed > 0.,
+            "Real-time pacing speed must be positive and finite, got {}",
+            speed
+        );
+        Self {
+            speed,
+            wall_start: Instant::now(),
+            sim_start: sim_time,
+        }
+    }
+
+    /// Blocks the current thread until the wall-clock time corresponding to the specified simulation time.
+    pub fn wait_until(&self, sim_time: f64) {
+        if !sim_time.is_finite() || sim_time <= self.sim_start {
+            return;
+        }
+        let target = self.wall_start + Duration::from_secs_f64((sim_time - self.sim_start) / self.speed);
+        let now = Instant::now();
+        if target > now {
+            thread::sleep(target - now);
+        }
+    }
+}
@@ -0,0 +1,430 @@
+//! Experimental parallel simulation of loosely coupled domains.
+//!
+//! Large models often consist of several parts which interact much less intensively than their internals,
+//! e.g. datacenters connected by the wide-area network. [`ParallelSimulation`] partitions the model into
+//! such *domains*, each being a separate [`Simulation`] running in its own OS thread, and synchronizes
+//! the domains conservatively, so that the results are the same as in the sequential execution.
+//!
+//! The synchronization relies on the *lookahead* — the minimum delay of events sent between the domains,
+//! such as the minimum inter-datacenter latency. The execution proceeds in time windows: if `T` is the earliest
+//! pending event time across all domains, no remote event can arrive at any domain before `T + lookahead`,
+//! so all domains process their events in `[T, T + lookahead)` independently and then exchange the remote
+//! events emitted in the window. The larger the lookahead relative to the event density, the more work is done
+//! in parallel between the synchronization points.
+//!
+//! Since the components of different domains live in different threads, they interact only through remote events
+//! sent via [`DomainLink`]. The payload of remote events must implement `Send` in addition to the usual
+//! requirements. The remote event is delivered to the destination component (addressed by name) on behalf of
+//! a proxy component named `<source domain>::<source component>`, which can be used to reply.
+//!
+//! This mode is experimental: the API may change, and the features which inspect the whole model
+//! (tracing, profiling, debugging) can be used only within individual domains.
+
+use std::any::Any;
+use std::cell::{Cell, RefCell};
+use std::panic::{catch_unwind, resume_unwind, AssertUnwindSafe};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Barrier, Mutex};
+use std::thread;
+
+use crate::context::SimulationContext;
+use crate::event::EventData;
+use crate::simulation::Simulation;
+
+/// Trait of remote event payloads which can be sent to another domain.
+///
+/// It is implemented automatically for all event payloads implementing `Send`.
+pub trait RemoteEventData: EventData + Send {
+    /// Converts the payload into a regular event payload.
+    fn into_event_data(self: Box<Self>) -> Box<dyn EventData>;
+}
+
+impl<T: EventData + Send> RemoteEventData for T {
+    fn into_event_data(self: Box<Self>) -> Box<dyn EventData> {
+        self
+    }
+}
+
+struct RemoteEvent {
+    time: f64,
+    src_domain: usize,
+    seq: u64,
+    src: String,
+    dst: String,
+    data: Box<dyn RemoteEventData>,
+}
+
+struct LinkState {
+    domain: usize,
+    domains: Arc<Vec<String>>,
+    lookahead: f64,
+    outbox: RefCell<Vec<(usize, RemoteEvent)>>,
+    sent: Cell<u64>,
+}
+
+/// Handle used by the components of a domain to send events to the components of other domains.
+#[derive(Clone)]
+pub struct DomainLink {
+    state: Rc<LinkState>,
+}
+
+impl DomainLink {
+    /// Returns the name of the domain.
+    pub fn domain(&self) -> &str {
+        &self.state.domains[self.state.domain]
+    }
+
+    /// Returns the lookahead, i.e. the minimum delay of remote events.
+    pub fn lookahead(&self) -> f64 {
+        self.state.lookahead
+    }
+
+    /// Sends the event from the component `ctx` to the component `dst` of domain `dst_domain`
+    /// with the specified delay.
+    ///
+    /// Panics if the domain does not exist or the delay is less than the lookahead. No tolerance is applied here,
+    /// since the event with a slightly smaller delay could be delivered to a window already processed by the
+    /// destination domain. The destination component is looked up by name upon delivery and must exist at that moment.
+    pub fn emit<T>(&self, ctx: &SimulationContext, data: T, dst_domain: &str, dst: &str, delay: f64)
+    where
+        T: RemoteEventData,
+    {
+        let target = self
+            .state
+            .domains
+            .iter()
+            .position(|name| name == dst_domain)
+            .unwrap_or_else(|| panic!("Domain {} does not exist", dst_domain));
+        assert!(
+            delay >= self.state.lookahead,
+            "Delay of remote event {} is less than lookahead {}",
+            delay,
+            self.state.lookahead
+        );
+        let seq = self.state.sent.get();
+        let event = RemoteEvent {
+            time: ctx.time() + delay,
+            src_domain: self.state.domain,
+            seq,
+            src: ctx.name().to_owned(),
+            dst: dst.to_owned(),
+            data: Box::new(data),
+        };
+        self.state.sent.set(seq + 1);
+        self.state.outbox.borrow_mut().push((target, event));
+    }
+}
+
+/// Results of a domain execution.
+#[derive(Clone, Debug)]
+pub struct DomainResult<R> {
+    /// Domain name.
+    pub name: String,
+    /// Simulation time of the domain at the end of execution.
+    pub time: f64,
+    /// Number of events processed in the domain.
+    pub processed_events: u64,
+    /// Number of remote events sent from the domain.
+    pub remote_events: u64,
+    /// Output returned by the domain.
+    pub output: R,
+}
+
+type DomainOutput<R> = Box<dyn FnOnce(&mut Simulation) -> R>;
+type DomainSetup<R> = Box<dyn FnOnce(&mut Simulation, DomainLink) -> DomainOutput<R> + Send>;
+
+/// Conservative parallel simulation of several domains (experimental).
+///
+/// # Examples
+///
+/// ```rust
+/// use std::cell::RefCell;
+/// use std::rc::Rc;
+/// use serde::Serialize;
+/// use dslab_core::parallel::{DomainLink, ParallelSimulation};
+/// use dslab_core::{cast, Event, EventHandler, SimulationContext};
+///
+/// #[derive(Clone, Serialize)]
+/// pub struct Request {
+///     hops: u32,
+/// }
+///
+/// pub struct Server {
+///     ctx: SimulationContext,
+///     link: DomainLink,
+///     peer_domain: String,
+///     received: u32,
+/// }
+///
+/// impl EventHandler for Server {
+///     fn on(&mut self, event: Event) {
+///         cast!(match event.data {
+///             Request { hops } => {
+///                 self.received += 1;
+///                 if hops > 0 {
+///                     let request = Request { hops: hops - 1 };
+///                     self.link.emit(&self.ctx, request, &self.peer_domain, "server", 0.1);
+///                 }
+///             }
+///         })
+///     }
+/// }
+///
+/// let mut sim = ParallelSimulation::new(123, 0.1);
+/// for (name, peer) in [("dc1", "dc2"), ("dc2", "dc1")] {
+///     sim.add_domain(name, move |sim, link| {
+///         let ctx = sim.create_context("server");
+///         if name == "dc1" {
+///             link.emit(&ctx, Request { hops: 9 }, peer, "server", 0.1);
+///         }
+///         let server = Rc::new(RefCell::new(Server {
+///             ctx,
+///             link,
+///             peer_domain: peer.to_string(),
+///             received: 0,
+///         }));
+///         sim.add_handler("server", server.clone());
+///         Box::new(move |_| server.borrow().received)
+///     });
+/// }
+/// let results = sim.run();
+/// assert_eq!(results[0].name, "dc1");
+/// assert_eq!(results[0].output, 5);
+/// assert_eq!(results[1].output, 5);
+/// assert!((results[0].time - 1.0).abs() < 1e-9);
+/// ```
+pub struct ParallelSimulation<R> {
+    seed: u64,
+    lookahead: f64,
+    domains: Vec<(String, DomainSetup<R>)>,
+}
+
+impl<R: Send + 'static> ParallelSimulation<R> {
+    /// Creates a parallel simulation with the specified seed and lookahead.
+    ///
+    /// The simulation of i-th domain is created with seed `seed + i`.
+    pub fn new(seed: u64, lookahead: f64) -> Self {
+        assert!(lookahead > 0., "Lookahead must be positive");
+        Self {
+            seed,
+            lookahead,
+            domains: Vec::new(),
+        }
+    }
+
+    /// Adds a domain with the specified name.
+    ///
+    /// The `setup` function is called in the domain thread to create the domain components in its simulation.
+    /// It receives [`DomainLink`] for sending remote events and returns the function which is called at the end
+    /// of execution to obtain the domain output.
+    pub fn add_domain<F>(&mut self, name: &str, setup: F)
+    where
+        F: FnOnce(&mut Simulation, DomainLink) -> DomainOutput<R> + Send + 'static,
+    {
+        assert!(
+            self.domains.iter().all(|(domain, _)| domain != name),
+            "Domain {} already exists",
+            name
+        );
+        self.domains.push((name.to_owned(), Box::new(setup)));
+    }
+
+    /// Runs the simulation until there are no pending events in all domains and returns the domain results
+    /// in the order of domains.
+    ///
+    /// If a domain panics, the panic is propagated after all domain threads finish.
+    pub fn run(self) -> Vec<DomainResult<R>> {
+        self.run_until_time(f64::INFINITY)
+    }
+
+    /// Runs the simulation until all events with time not exceeding the specified time are processed
+    /// and returns the domain results in the order of domains.
+    pub fn run_until_time(self, time: f64) -> Vec<DomainResult<R>> {
+        let count = self.domains.len();
+        let names = Arc::new(self.domains.iter().map(|(name, _)| name.clone()).collect::<Vec<_>>());
+        let sync = Arc::new(SyncState {
+            barrier: Barrier::new(count),
+            next_times: Mutex::new(vec![f64::INFINITY; count]),
+            inboxes: (0..count).map(|_| Mutex::new(Vec::new())).collect(),
+            aborted: AtomicBool::new(false),
+        });
+        let handles: Vec<_> = self
+            .domains
+            .into_iter()
+            .enumerate()
+            .map(|(index, (_, setup))| {
+                let seed = self.seed.wrapping_add(index as u64);
+                let link = LinkConfig {
+                    domain: index,
+                    domains: names.clone(),
+                    lookahead: self.lookahead,
+                };
+                let sync = sync.clone();
+                thread::spawn(move || run_domain(seed, link, setup, sync, time))
+            })
+            .collect();
+        let results: Vec<_> = handles.into_iter().map(|handle| handle.join().unwrap()).collect();
+        let mut outputs = Vec::with_capacity(count);
+        for result in results {
+            match result {
+                Ok(result) => outputs.push(result),
+                Err(e) => resume_unwind(e),
+            }
+        }
+        outputs
+    }
+}
+
+struct SyncState {
+    barrier: Barrier,
+    next_times: Mutex<Vec<f64>>,
+    inboxes: Vec<Mutex<Vec<RemoteEvent>>>,
+    aborted: AtomicBool,
+}
+
+struct LinkConfig {
+    domain: usize,
+    domains: Arc<Vec<String>>,
+    lookahead: f64,
+}
+
+struct Domain<R> {
+    sim: Simulation,
+    link: DomainLink,
+    output: DomainOutput<R>,
+}
+
+impl<R> Domain<R> {
+    fn deliver_remote_events(&mut self, mut events: Vec<RemoteEvent>) {
+        // the order of events does not depend on the order in which the domains have sent them
+        events.sort_by(|a, b| {
+            a.time
+                .total_cmp(&b.time)
+                .then(a.src_domain.cmp(&b.src_domain))
+                .then(a.seq.cmp(&b.seq))
+        });
+        for event in events {
+            let proxy_name = format!("{}::{}", self.link.state.domains[event.src_domain], event.src);
+            let src = self.sim.create_context(proxy_name).id();
+            let dst = self.sim.lookup_id(&event.dst);
+            self.sim
+                .add_event_at(event.data.into_event_data(), src, dst, event.time);
+        }
+    }
+
+    fn send_remote_events(&self, sync: &SyncState) {
+        for (target, event) in self.link.state.outbox.borrow_mut().drain(..) {
+            sync.inboxes[target].lock().unwrap().push(event);
+        }
+    }
+
+    fn process_window(&mut self, end: f64, until: f64) {
+        while let Some(time) = self.sim.next_event_time() {
+            if time >= end || time > until {
+                break;
+            }
+            self.sim.step();
+        }
+    }
+}
+
+fn run_domain<R>(
+    seed: u64,
+    config: LinkConfig,
+    setup: DomainSetup<R>,
+    sync: Arc<SyncState>,
+    until: f64,
+) -> Result<DomainResult<R>, Box<dyn Any + Send>> {
+    let index = config.domain;
+    let lookahead = config.lookahead;
+    let mut panic = None;
+    let mut domain = match catch_unwind(AssertUnwindSafe(|| {
+        let mut sim = Simulation::new(seed);
+        let link = DomainLink {
+            state: Rc::new(LinkState {
+                domain: config.domain,
+                domains: config.domains,
+                lookahead: config.lookahead,
+                outbox: RefCell::new(Vec::new()),
+                sent: Cell::new(0),
+            }),
+        };
+        let output = setup(&mut sim, link.clone());
+        Domain { sim, link, output }
+    })) {
+        Ok(domain) => Some(domain),
+        Err(e) => {
+            sync.aborted.store(true, Ordering::SeqCst);
+            panic = Some(e);
+            None
+        }
+    };
+
+    // send remote events emitted during the setup
+    if let Some(current) = domain.as_ref() {
+        current.send_remote_events(&sync);
+    }
+    sync.barrier.wait();
+
+    loop {
+        // deliver remote events received in the previous window and publish the next event time
+        let mut next_time = f64::INFINITY;
+        if let Some(current) = domain.as_mut() {
+            let events = std::mem::take(&mut *sync.inboxes[index].lock().unwrap());
+            match catch_unwind(AssertUnwindSafe(|| current.deliver_remote_events(events))) {
+                Ok(()) => next_time = current.sim.next_event_time().unwrap_or(f64::INFINITY),
+                Err(e) => {
+                    sync.aborted.store(true, Ordering::SeqCst);
+                    panic = Some(e);
+                    domain = None;
+                }
+            }
+        }
+        sync.next_times.lock().unwrap()[index] = next_time;
+        sync.barrier.wait();
+
+        // all domains make the same decision here, since the shared state is not modified until the next barrier
+        if sync.aborted.load(Ordering::SeqCst) {
+            break;
+        }
+        let window_start = sync
+            .next_times
+            .lock()
+            .unwrap()
+            .iter()
+            .copied()
+            .fold(f64::INFINITY, f64::min);
+        if window_start == f64::INFINITY || window_start > until {
+            break;
+        }
+
+        // process events in the window and send remote events emitted in it
+        if let Some(current) = domain.as_mut() {
+            let window_end = window_start + lookahead;
+            match catch_unwind(AssertUnwindSafe(|| current.process_window(window_end, until))) {
+                Ok(()) => current.send_remote_events(&sync),
+                Err(e) => {
+                    sync.aborted.store(true, Ordering::SeqCst);
+                    panic = Some(e);
+                    domain = None;
+                }
+            }
+        }
+        sync.barrier.wait();
+    }
+
+    if let Some(e) = panic {
+        return Err(e);
+    }
+    // the domain is dropped only on panic
+    let Domain { mut sim, link, output } = domain.unwrap();
+    let output = catch_unwind(AssertUnwindSafe(|| output(&mut sim)))?;
+    Ok(DomainResult {
+        name: link.domain().to_owned(),
+        time: sim.time(),
+        processed_events: sim.processed_event_count(),
+        remote_events: link.state.sent.get(),
+        output,
+    })
+}
@@ -0,0 +1,238 @@
+//! Profiling of simulation components.
+//!
+//! When profiling is enabled via [`Simulation::enable_profiling()`](crate::Simulation::enable_profiling()),
+//! the simulation collects per-component counters: the number of handled events and the time spent
+//! in their processing by event type, and the number of emitted events. The collected counters are returned
+//! as [`ProfileReport`], which can be printed as a summary table or exported as a flame graph.
+
+use std::any::TypeId;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use std::rc::Rc;
+use std::time::Duration;
+
+use serde_type_name::type_name;
+
+use crate::component::Id;
+use crate::event::Event;
+
+struct TypeCounters {
+    type_name: String,
+    count: u64,
+    time: Duration,
+}
+
+#[derive(Default)]
+struct ComponentCounters {
+    emitted: u64,
+    handled: HashMap<TypeId, TypeCounters>,
+}
+
+/// Collects per-component counters while the simulation runs.
+pub(crate) struct Profiler {
+    names: Rc<RefCell<Vec<String>>>,
+    components: Vec<ComponentCounters>,
+}
+
+/// Identifies the counters of handled event, obtained before passing the event to the handler.
+pub(crate) type ProfileKey = (Id, TypeId);
+
+impl Profiler {
+    pub fn new(names: Rc<RefCell<Vec<String>>>) -> Self {
+        Self {
+            names,
+            components: Vec::new(),
+        }
+    }
+
+    fn component(&mut self, id: Id) -> &mut ComponentCounters {
+        let index = id as usize;
+        if index >= self.components.len() {
+            self.components.resize_with(index + 1, Default::default);
+        }
+        &mut self.components[index]
+    }
+
+    pub fn on_emit(&mut self, src: Id) {
+        self.component(src).emitted += 1;
+    }
+
+    /// Returns the key for recording the processing time of the event.
+    pub fn event_key(&mut self, event: &Event) -> ProfileKey {
+        let type_id = (*event.data).as_any().type_id();
+        // the type name is obtained only once for each component and event type to keep the overhead low
+        self.component(event.dst)
+            .handled
+            .entry(type_id)
+            .or_insert_with(|| TypeCounters {
+                type_name: type_name(&event.data).unwrap().to_owned(),
+                count: 0,
+                time: Duration::ZERO,
+            });
+        (event.dst, type_id)
+    }
+
+    pub fn on_handled(&mut self, key: ProfileKey, time: Duration) {
+        let counters = self.component(key.0).handled.get_mut(&key.1).unwrap();
+        counters.count += 1;
+        counters.time += time;
+    }
+
+    pub fn report(&self) -> ProfileReport {
+        let names = self.names.borrow();
+        let mut components: Vec<ComponentProfile> = self
+            .components
+            .iter()
+            .enumerate()
+            .filter(|(_, counters)| counters.emitted > 0 || !counters.handled.is_empty())
+            .map(|(id, counters)| {
+                let mut event_types: Vec<EventTypeProfile> = counters
+                    .handled
+                    .values()
+                    .map(|c| EventTypeProfile {
+                        type_name: c.type_name.clone(),
+                        events_handled: c.count,
+                        handler_time: c.time,
+                    })
+                    .collect();
+                event_types.sort_by(|a, b| {
+                    b.handler_time
+                        .cmp(&a.handler_time)
+                        .then_with(|| a.type_name.cmp(&b.type_name))
+                });
+                ComponentProfile {
+                    id: id as Id,
+                    name: names.get(id).cloned().unwrap_or_else(|| id.to_string()),
+                    events_handled: event_types.iter().map(|t| t.events_handled).sum(),
+                    events_emitted: counters.emitted,
+                    handler_time: event_types.iter().map(|t| t.handler_time).sum(),
+                    event_types,
+                }
+            })
+            .collect();
+        components.sort_by(|a, b| b.handler_time.cmp(&a.handler_time).then_with(|| a.id.cmp(&b.id)));
+        ProfileReport { components }
+    }
+}
+
+/// Counters of events of some type handled by a component.
+#[derive(Clone, Debug)]
+pub struct EventTypeProfile {
+    /// Name of event type.
+    pub type_name: String,
+    /// Number of handled events of this type.
+    pub events_handled: u64,
+    /// Total time spent in processing of events of this type.
+    pub handler_time: Duration,
+}
+
+/// Counters of a simulation component.
+#[derive(Clone, Debug)]
+pub struct ComponentProfile {
+    /// Component identifier.
+    pub id: Id,
+    /// Component name.
+    pub name: String,
+    /// Number of events handled by the component.
+    pub events_handled: u64,
+    /// Number of events emitted by the component.
+    pub events_emitted: u64,
+    /// Total time spent in processing of events by the component.
+    pub handler_time: Duration,
+    /// Counters of handled events by type, sorted by the processing time in descending order.
+    pub event_types: Vec<EventTypeProfile>,
+}
+
+/// Summary of per-component counters collected during the simulation.
+///
+/// The processing time of an event includes the execution of the component's event handler and the async tasks
+/// resumed by this event. Implements [`Display`] to print the summary as a table.
+#[derive(Clone, Debug)]
+pub struct ProfileReport {
+    components: Vec<ComponentProfile>,
+}
+
+impl ProfileReport {
+    /// Returns the counters of components sorted by the processing time in descending order.
+    pub fn components(&self) -> &[ComponentProfile] {
+        &self.components
+    }
+
+    /// Returns the counters of component with specified name, if it handled or emitted any events.
+    pub fn component(&self, name: &str) -> Option<&ComponentProfile> {
+        self.components.iter().find(|c| c.name == name)
+    }
+
+    /// Returns the total time spent in processing of events by all components.
+    pub fn total_handler_time(&self) -> Duration {
+        self.components.iter().map(|c| c.handler_time).sum()
+    }
+
+    /// Returns the report in the collapsed stack format (`component;event_type microseconds` per line),
+    /// which can be rendered as a flame graph with tools such as `inferno-flamegraph`, `flamegraph.pl`
+    /// or [speedscope](https://www.speedscope.app).
+    pub fn flame_report(&self) -> String {
+        let mut report = String::new();
+        for component in &self.components {
+            for event_type in &component.event_types {
+                report.push_str(&format!(
+                    "{};{} {}\n",
+                    component.name,
+                    event_type.type_name,
+                    event_type.handler_time.as_micros()
+                ));
+            }
+        }
+        report
+    }
+
+    /// Writes the report in the collapsed stack format (see [`flame_report()`](Self::flame_report())) to a file.
+    pub fn write_flame_report<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        File::create(path)?.write_all(self.flame_report().as_bytes())
+    }
+}
+
+impl Display for ProfileReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let total = self.total_handler_time().as_secs_f64();
+        writeln!(
+            f,
+            "{:<30} {:>12} {:>12} {:>12} {:>7}",
+            "component / event type", "handled", "emitted", "time (ms)", "time %"
+        )?;
+        for component in &self.components {
+            let share = |time: Duration| {
+                if total > 0. {
+                    time.as_secs_f64() / total * 100.
+                } else {
+                    0.
+                }
+            };
+            writeln!(
+                f,
+                "{:<30} {:>12} {:>12} {:>12.3} {:>7.2}",
+                component.name,
+                component.events_handled,
+                component.events_emitted,
+                component.handler_time.as_secs_f64() * 1e3,
+                share(component.handler_time)
+            )?;
+            for event_type in &component.event_types {
+                writeln!(
+                    f,
+                    "  {:<28} {:>12} {:>12} {:>12.3} {:>7.2}",
+                    event_type.type_name,
+                    event_type.events_handled,
+                    "",
+                    event_type.handler_time.as_secs_f64() * 1e3,
+                    share(event_type.handler_time)
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
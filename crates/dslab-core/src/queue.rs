@@ -0,0 +1,270 @@
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+use std::mem;
+
+use crate::component::Id;
+use crate::event::{Event, EventId, EventOrdering};
+
+const BUCKET_COUNT: usize = 129;
+
+/// Priority queue of events ordered by time and id, implemented as a radix heap.
+///
+/// The radix heap exploits the fact that simulation time never decreases, i.e. new events are almost always not
+/// earlier than the last extracted one. Each event is identified by 128-bit key (time bits and id), and stored in
+/// the bucket corresponding to the highest bit in which its key differs from the key of the last extracted event.
+/// Push is O(1), pop is amortized O(log U) where U is the key range. In contrast to a binary heap, the pending events
+/// are not moved on each operation, which makes the queue much more cache-friendly for millions of pending events.
+///
+/// If an event with a key smaller than the last extracted key is pushed (which can happen after the simulation time
+/// is set explicitly), only the small buckets are merged to restore the invariant.
+struct RadixHeap {
+    buckets: Vec<Vec<Event>>,
+    // bit i - 1 is set if bucket i > 0 is not empty
+    occupied: u128,
+    last: u128,
+    len: usize,
+    // reused buffer for redistributed events to avoid reallocations
+    buffer: Vec<Event>,
+}
+
+fn key(event: &Event) -> u128 {
+    // non-negative floats are ordered as their bit representations,
+    // adding zero converts negative zero to positive zero
+    (((event.time + 0.).to_bits() as u128) << 64) | event.id as u128
+}
+
+fn bucket_index(diff: u128) -> usize {
+    (128 - diff.leading_zeros()) as usize
+}
+
+impl RadixHeap {
+    fn new() -> Self {
+        Self {
+            buckets: (0..BUCKET_COUNT).map(|_| Vec::new()).collect(),
+            occupied: 0,
+            last: 0,
+            len: 0,
+            buffer: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, event: Event) {
+        let key = key(&event);
+        if key < self.last {
+            // All events in buckets below `j` share the bits above `j` with the new key,
+            // so they are moved to bucket `j` relative to the new key, while other buckets stay the same.
+            let j = bucket_index(key ^ self.last);
+            let (lower, upper) = self.buckets.split_at_mut(j);
+            for bucket in lower.iter_mut() {
+                upper[0].append(bucket);
+            }
+            self.occupied &= !((1u128 << (j - 1)) - 1);
+            if !self.buckets[j].is_empty() {
+                self.occupied |= 1 << (j - 1);
+            }
+            self.last = key;
+        }
+        self.insert(key, event);
+        self.len += 1;
+    }
+
+    fn insert(&mut self, key: u128, event: Event) {
+        let index = bucket_index(key ^ self.last);
+        if index > 0 {
+            self.occupied |= 1 << (index - 1);
+        }
+        self.buckets[index].push(event);
+    }
+
+    fn pop(&mut self) -> Option<Event> {
+        self.refill();
+        let event = self.buckets[0].pop();
+        if event.is_some() {
+            self.len -= 1;
+        }
+        event
+    }
+
+    fn peek(&mut self) -> Option<&Event> {
+        self.refill();
+        self.buckets[0].last()
+    }
+
+    /// Moves the next event to bucket 0 by redistributing the smallest non-empty bucket.
+    fn refill(&mut self) {
+        if !self.buckets[0].is_empty() || self.occupied == 0 {
+            return;
+        }
+        let i = self.occupied.trailing_zeros() as usize + 1;
+        self.occupied &= !(1 << (i - 1));
+        mem::swap(&mut self.buffer, &mut self.buckets[i]);
+        self.last = self.buffer.iter().map(key).min().unwrap();
+        let mut buffer = mem::take(&mut self.buffer);
+        for event in buffer.drain(..) {
+            self.insert(key(&event), event);
+        }
+        self.buffer = buffer;
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &Event> {
+        self.buckets.iter().flatten()
+    }
+}
+
+/// Event with the tie-breaking key, ordered as min-heap entry by the event priority, the key and event id.
+struct BatchEntry {
+    priority: i64,
+    key: i64,
+    event: Event,
+}
+
+impl PartialEq for BatchEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.event.id == other.event.id
+    }
+}
+
+impl Eq for BatchEntry {}
+
+impl Ord for BatchEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .priority
+            .cmp(&self.priority)
+            .then_with(|| other.key.cmp(&self.key))
+            .then_with(|| other.event.id.cmp(&self.event.id))
+    }
+}
+
+impl PartialOrd for BatchEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Priority queue of events ordered by time, with the configurable ordering of simultaneous events.
+///
+/// With the default insertion ordering, the events are ordered by time and id in [`RadixHeap`].
+/// With other orderings, the events with the time of the next event are moved from the radix heap to a separate
+/// batch heap ordered by the tie-breaking key and id. While the batch is not empty, the new events with the same time
+/// (i.e. emitted with zero delay) are added to the batch directly, so they are ordered with the rest of the batch.
+/// The same approach is used when some pending events have explicit priorities, which take precedence over
+/// the ordering policy.
+pub(crate) struct EventQueue {
+    heap: RadixHeap,
+    ordering: EventOrdering,
+    priorities: HashMap<Id, i64>,
+    event_priorities: HashMap<EventId, i64>,
+    batch: BinaryHeap<BatchEntry>,
+    batch_time: f64,
+}
+
+impl EventQueue {
+    pub fn new() -> Self {
+        Self {
+            heap: RadixHeap::new(),
+            ordering: EventOrdering::InsertionOrder,
+            priorities: HashMap::new(),
+            event_priorities: HashMap::new(),
+            batch: BinaryHeap::new(),
+            batch_time: 0.,
+        }
+    }
+
+    pub fn set_ordering(&mut self, ordering: EventOrdering) {
+        for entry in mem::take(&mut self.batch) {
+            self.heap.push(entry.event);
+        }
+        self.ordering = ordering;
+    }
+
+    pub fn set_priority(&mut self, component: Id, priority: i64) {
+        self.priorities.insert(component, priority);
+    }
+
+    fn tie_breaking_key(&self, event: &Event) -> i64 {
+        match &self.ordering {
+            EventOrdering::InsertionOrder => 0,
+            EventOrdering::ComponentPriority => self.priorities.get(&event.dst).copied().unwrap_or(0),
+            EventOrdering::Custom(key) => key(event),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.heap.len + self.batch.len()
+    }
+
+    pub fn push(&mut self, event: Event) {
+        if !self.batch.is_empty() && event.time == self.batch_time {
+            self.push_to_batch(event);
+        } else {
+            self.heap.push(event);
+        }
+    }
+
+    /// Adds an event with explicit priority, which determines its order among the events with the same time
+    /// (lower value first).
+    pub fn push_with_priority(&mut self, event: Event, priority: i64) {
+        if priority != 0 {
+            self.event_priorities.insert(event.id, priority);
+        }
+        self.push(event);
+    }
+
+    /// Returns the explicit priority of pending event (0 by default).
+    pub fn event_priority(&self, event_id: EventId) -> i64 {
+        self.event_priorities.get(&event_id).copied().unwrap_or(0)
+    }
+
+    fn push_to_batch(&mut self, event: Event) {
+        let priority = self.event_priority(event.id);
+        let key = self.tie_breaking_key(&event);
+        self.batch.push(BatchEntry { priority, key, event });
+    }
+
+    /// Returns true if the simultaneous events should be ordered via batch.
+    fn uses_batch(&self) -> bool {
+        !self.batch.is_empty()
+            || !matches!(self.ordering, EventOrdering::InsertionOrder)
+            || !self.event_priorities.is_empty()
+    }
+
+    /// Moves all events with the time of the next event to the batch, if the batch is empty.
+    fn fill_batch(&mut self) {
+        if !self.batch.is_empty() {
+            return;
+        }
+        if let Some(event) = self.heap.pop() {
+            self.batch_time = event.time;
+            self.push_to_batch(event);
+            while self.heap.peek().map_or(false, |event| event.time == self.batch_time) {
+                let event = self.heap.pop().unwrap();
+                self.push_to_batch(event);
+            }
+        }
+    }
+
+    pub fn pop(&mut self) -> Option<Event> {
+        if !self.uses_batch() {
+            return self.heap.pop();
+        }
+        self.fill_batch();
+        let event = self.batch.pop().map(|entry| entry.event)?;
+        if !self.event_priorities.is_empty() {
+            self.event_priorities.remove(&event.id);
+        }
+        Some(event)
+    }
+
+    pub fn peek(&mut self) -> Option<&Event> {
+        if !self.uses_batch() {
+            return self.heap.peek();
+        }
+        self.fill_batch();
+        self.batch.peek().map(|entry| &entry.event)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Event> {
+        self.heap.iter().chain(self.batch.iter().map(|entry| &entry.event))
+    }
+}
@@ -0,0 +1,225 @@
+//! Recording and replay of input events.
+//!
+//! The results of comparing two policies in separate runs are affected by the randomness of the workload,
+//! e.g. the request arrivals generated by a random workload generator. To isolate the policy changes,
+//! the input events (arrivals of requests, messages, failures, etc) emitted by the workload components in one run
+//! can be extracted from the event trace into [`InputTrace`] and replayed exactly in another run via
+//! [`Simulation::replay_input_trace()`](crate::Simulation::replay_input_trace()), where the workload components are not created.
+//!
+//! The input events are extracted from the event trace in [`JsonLines`](crate::trace::TraceFormat::JsonLines)
+//! format with payloads, which is recorded via [`Simulation::enable_event_tracing()`](crate::Simulation::enable_event_tracing()). To recreate the events
+//! from the serialized payloads, the payload types should implement `Deserialize` and be registered in
+//! [`EventDecoders`].
+//!
+//! # Examples
+//!
+//! ```rust
+//! use std::cell::RefCell;
+//! use std::rc::Rc;
+//! use serde::{Deserialize, Serialize};
+//! use dslab_core::replay::{EventDecoders, InputTrace};
+//! use dslab_core::trace::TraceFormat;
+//! use dslab_core::{cast, Event, EventHandler, Simulation};
+//!
+//! #[derive(Clone, Serialize, Deserialize)]
+//! pub struct Request {
+//!     size: u32,
+//! }
+//!
+//! #[derive(Default)]
+//! pub struct Server {
+//!     requests: Vec<(f64, u32)>,
+//! }
+//!
+//! impl EventHandler for Server {
+//!     fn on(&mut self, event: Event) {
+//!         cast!(match event.data {
+//!             Request { size } => {
+//!                 self.requests.push((event.time, size));
+//!             }
+//!         })
+//!     }
+//! }
+//!
+//! // the original run with random workload
+//! let path = std::env::temp_dir().join("dslab-core-replay-example.jsonl");
+//! let mut sim = Simulation::new(123);
+//! sim.enable_event_tracing(&path, TraceFormat::JsonLines, true).unwrap();
+//! let server = Rc::new(RefCell::new(Server::default()));
+//! let server_id = sim.add_handler("server", server.clone());
+//! let workload_ctx = sim.create_context("workload");
+//! for _ in 0..10 {
+//!     let size = workload_ctx.gen_range(1..100);
+//!     workload_ctx.emit(Request { size }, server_id, workload_ctx.gen_range(0.0..10.0));
+//! }
+//! sim.step_until_no_events();
+//! sim.finish_event_tracing();
+//!
+//! // the run with the replayed workload and different seed
+//! let trace = InputTrace::from_event_trace(&path, &["workload"]).unwrap();
+//! assert_eq!(trace.events().len(), 10);
+//! let mut decoders = EventDecoders::new();
+//! decoders.register::<Request>();
+//! let mut sim = Simulation::new(456);
+//! let replayed_server = Rc::new(RefCell::new(Server::default()));
+//! sim.add_handler("server", replayed_server.clone());
+//! assert_eq!(sim.replay_input_trace(&trace, &decoders), Ok(10));
+//! sim.step_until_no_events();
+//! assert_eq!(replayed_server.borrow().requests, server.borrow().requests);
+//! ```
+
+use std::collections::{BTreeSet, HashMap};
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::event::EventData;
+
+/// Input event extracted from the event trace.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct InputEvent {
+    /// Event delivery time.
+    pub time: f64,
+    /// Name of the source component.
+    pub src: String,
+    /// Name of the destination component.
+    pub dst: String,
+    /// Name of the payload type.
+    #[serde(rename = "type")]
+    pub event_type: String,
+    /// Serialized payload.
+    pub data: Value,
+}
+
+/// Sequence of input events ordered by their delivery time.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct InputTrace {
+    events: Vec<InputEvent>,
+}
+
+impl InputTrace {
+    /// Creates a trace from the given events.
+    pub fn new(mut events: Vec<InputEvent>) -> Self {
+        events.sort_by(|a, b| a.time.total_cmp(&b.time));
+        Self { events }
+    }
+
+    /// Extracts the events emitted by the specified source components from the event trace file.
+    ///
+    /// The trace should be recorded in [`JsonLines`](crate::trace::TraceFormat::JsonLines) format with payloads.
+    pub fn from_event_trace<P: AsRef<Path>>(path: P, sources: &[&str]) -> Result<Self, String> {
+        let path = path.as_ref();
+        let sources: BTreeSet<&str> = sources.iter().copied().collect();
+        let mut events = Vec::new();
+        for (i, record) in Self::read_lines(path)?.into_iter().enumerate() {
+            if record["kind"] != "emit" || !record["src"].as_str().map_or(false, |src| sources.contains(src)) {
+                continue;
+            }
+            let event = Self::parse_emit_record(&record)
+                .ok_or_else(|| format!("Invalid event trace record at {}:{}", path.display(), i + 1))?;
+            events.push(event);
+        }
+        Ok(Self::new(events))
+    }
+
+    /// Reads the trace previously saved via [`save()`](Self::save()).
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let path = path.as_ref();
+        let events = Self::read_lines(path)?
+            .into_iter()
+            .enumerate()
+            .map(|(i, record)| {
+                serde_json::from_value(record)
+                    .map_err(|e| format!("Invalid input event at {}:{}: {}", path.display(), i + 1, e))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self::new(events))
+    }
+
+    /// Saves the trace to the file with one JSON object per line.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        for event in self.events.iter() {
+            serde_json::to_writer(&mut writer, event)?;
+            writer.write_all(b"\n")?;
+        }
+        writer.flush()
+    }
+
+    /// Returns the events in the order of their delivery time.
+    pub fn events(&self) -> &[InputEvent] {
+        &self.events
+    }
+
+    fn read_lines(path: &Path) -> Result<Vec<Value>, String> {
+        let file = File::open(path).map_err(|e| format!("Can't open {}: {}", path.display(), e))?;
+        let mut records = Vec::new();
+        for (i, line) in BufReader::new(file).lines().enumerate() {
+            let line = line.map_err(|e| format!("Can't read {}: {}", path.display(), e))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let record = serde_json::from_str(&line)
+                .map_err(|e| format!("Invalid JSON at {}:{}: {}", path.display(), i + 1, e))?;
+            records.push(record);
+        }
+        Ok(records)
+    }
+
+    fn parse_emit_record(record: &Value) -> Option<InputEvent> {
+        Some(InputEvent {
+            time: record["delivery_time"].as_f64()?,
+            src: record["src"].as_str()?.to_string(),
+            dst: record["dst"].as_str()?.to_string(),
+            event_type: record["type"].as_str()?.to_string(),
+            data: record.get("data")?.clone(),
+        })
+    }
+}
+
+type DecodeFn = Box<dyn Fn(Value) -> Result<Box<dyn EventData>, String>>;
+
+/// Registry of functions which recreate the event payloads of different types from their serialized form.
+#[derive(Default)]
+pub struct EventDecoders {
+    decoders: HashMap<String, DecodeFn>,
+}
+
+impl EventDecoders {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the payload type under its name without the module path, which is used in the event trace
+    /// unless the type is renamed via serde attributes.
+    pub fn register<T: EventData + DeserializeOwned>(&mut self) {
+        let name = std::any::type_name::<T>().rsplit("::").next().unwrap();
+        self.register_as::<T>(name);
+    }
+
+    /// Registers the payload type under the specified name.
+    pub fn register_as<T: EventData + DeserializeOwned>(&mut self, name: &str) {
+        self.decoders.insert(
+            name.to_string(),
+            Box::new(|data| {
+                serde_json::from_value::<T>(data)
+                    .map(|payload| Box::new(payload) as Box<dyn EventData>)
+                    .map_err(|e| e.to_string())
+            }),
+        );
+    }
+
+    /// Recreates the payload of the input event.
+    pub fn decode(&self, event: &InputEvent) -> Result<Box<dyn EventData>, String> {
+        let decoder = self
+            .decoders
+            .get(&event.event_type)
+            .ok_or_else(|| format!("Unknown event type: {}", event.event_type))?;
+        decoder(event.data.clone()).map_err(|e| format!("Can't decode {} event: {}", event.event_type, e))
+    }
+}
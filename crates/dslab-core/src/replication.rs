@@ -0,0 +1,267 @@
+//! Running replications of a simulation scenario with different seeds.
+//!
+//! The results of a single simulation run depend on the random seed, so the output metrics are usually
+//! estimated from several runs with different seeds. [`Replications`] runs the user-defined scenario
+//! with the specified seeds, optionally in several OS threads (one simulation per thread at a time),
+//! and aggregates the collected metrics across the runs. See [`statistics`](crate::statistics) module for
+//! the underlying statistical tools.
+
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use crate::statistics::{
+    confidence_interval, paired_t_test, sample_variance, student_t_quantile, welch_t_test, ConfidenceInterval,
+    TestResult,
+};
+
+/// Output metrics of a single run, mapping metric names to their values.
+pub type Metrics = BTreeMap<String, f64>;
+
+/// Results of a single run.
+#[derive(Clone, Debug)]
+pub struct RunResult {
+    /// Seed used in the run.
+    pub seed: u64,
+    /// Metrics returned by the scenario.
+    pub metrics: Metrics,
+}
+
+/// Summary statistics of a metric across the runs.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MetricSummary {
+    /// Number of runs which reported the metric.
+    pub count: usize,
+    /// Mean value.
+    pub mean: f64,
+    /// Sample standard deviation (zero for a single run).
+    pub std_dev: f64,
+    /// Minimum value.
+    pub min: f64,
+    /// Maximum value.
+    pub max: f64,
+    /// Half-width of 95% confidence interval for the mean based on Student's t-distribution
+    /// (zero for a single run).
+    pub ci95: f64,
+}
+
+impl MetricSummary {
+    /// Computes the summary of the given values.
+    ///
+    /// Panics if the values are empty.
+    pub fn from_values(values: &[f64]) -> Self {
+        assert!(!values.is_empty(), "Cannot summarize empty values");
+        let count = values.len();
+        let mean = values.iter().sum::<f64>() / count as f64;
+        let std_dev = sample_variance(values).sqrt();
+        let ci95 = confidence_interval(values, 0.95).half_width;
+        Self {
+            count,
+            mean,
+            std_dev,
+            min: values.iter().copied().fold(f64::INFINITY, f64::min),
+            max: values.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+            ci95,
+        }
+    }
+
+    /// Returns the confidence interval for the mean with the specified confidence level, e.g. 0.99.
+    pub fn confidence_interval(&self, level: f64) -> ConfidenceInterval {
+        let half_width = if self.count > 1 {
+            student_t_quantile(level, (self.count - 1) as f64) * self.std_dev / (self.count as f64).sqrt()
+        } else {
+            0.
+        };
+        ConfidenceInterval {
+            mean: self.mean,
+            half_width,
+            level,
+        }
+    }
+}
+
+/// Results of all runs.
+#[derive(Clone, Debug)]
+pub struct ReplicationResults {
+    runs: Vec<RunResult>,
+}
+
+impl ReplicationResults {
+    /// Returns the results of individual runs in the order of seeds.
+    pub fn runs(&self) -> &[RunResult] {
+        &self.runs
+    }
+
+    /// Returns the values of the metric in the order of seeds, skipping the runs which did not report it.
+    pub fn values(&self, metric: &str) -> Vec<f64> {
+        self.runs
+            .iter()
+            .filter_map(|run| run.metrics.get(metric).copied())
+            .collect()
+    }
+
+    /// Returns the summary of the metric across the runs, or `None` if no run reported it.
+    pub fn summary(&self, metric: &str) -> Option<MetricSummary> {
+        let values = self.values(metric);
+        if values.is_empty() {
+            None
+        } else {
+            Some(MetricSummary::from_values(&values))
+        }
+    }
+
+    /// Returns the summaries of all metrics reported by the runs.
+    pub fn summaries(&self) -> BTreeMap<String, MetricSummary> {
+        let mut names: Vec<&String> = self.runs.iter().flat_map(|run| run.metrics.keys()).collect();
+        names.sort();
+        names.dedup();
+        names
+            .into_iter()
+            .map(|name| (name.clone(), self.summary(name).unwrap()))
+            .collect()
+    }
+
+    /// Tests whether the mean of the metric differs from its mean in other results (e.g. obtained with another
+    /// policy). If both results were obtained with the same seeds, the paired t-test is used, otherwise
+    /// the Welch's t-test is used. Returns `None` if some results have less than two values of the metric.
+    pub fn compare(&self, other: &ReplicationResults, metric: &str) -> Option<TestResult> {
+        let values = self.values(metric);
+        let other_values = other.values(metric);
+        if values.len() < 2 || other_values.len() < 2 {
+            return None;
+        }
+        let paired = values.len() == self.runs.len()
+            && other_values.len() == other.runs.len()
+            && self.runs.iter().map(|r| r.seed).eq(other.runs.iter().map(|r| r.seed));
+        if paired {
+            Some(paired_t_test(&values, &other_values))
+        } else {
+            Some(welch_t_test(&values, &other_values))
+        }
+    }
+}
+
+/// Runner of simulation scenario with several seeds.
+///
+/// # Examples
+///
+/// ```rust
+/// use serde::Serialize;
+/// use dslab_core::replication::{Metrics, Replications};
+/// use dslab_core::Simulation;
+///
+/// #[derive(Clone, Serialize)]
+/// pub struct Arrival {
+/// }
+///
+/// // the scenario creates and runs the simulation with the given seed and returns the output metrics
+/// fn scenario(seed: u64) -> Metrics {
+///     let mut sim = Simulation::new(seed);
+///     let ctx = sim.create_context("source");
+///     for _ in 0..100 {
+///         let delay = ctx.gen_range(0.0..10.0);
+///         ctx.emit_self(Arrival {}, delay);
+///     }
+///     sim.step_until_no_events();
+///     Metrics::from([("last_arrival".to_string(), sim.time())])
+/// }
+///
+/// let results = Replications::new(8, 123).threads(4).run(scenario);
+/// assert_eq!(results.runs().len(), 8);
+/// assert_eq!(results.runs()[0].seed, 123);
+/// let summary = results.summary("last_arrival").unwrap();
+/// assert_eq!(summary.count, 8);
+/// assert!(summary.min > 9. && summary.max < 10.);
+/// assert!(summary.ci95 > 0.);
+///
+/// // the results do not depend on the number of threads
+/// let sequential = Replications::new(8, 123).run(scenario);
+/// assert_eq!(sequential.values("last_arrival"), results.values("last_arrival"));
+/// ```
+pub struct Replications {
+    seeds: Vec<u64>,
+    threads: usize,
+}
+
+impl Replications {
+    /// Creates the runner for the specified number of runs with seeds `base_seed`, `base_seed + 1`, etc.
+    pub fn new(runs: usize, base_seed: u64) -> Self {
+        Self::from_seeds((0..runs as u64).map(|i| base_seed.wrapping_add(i)).collect())
+    }
+
+    /// Creates the runner with the specified seeds, one run per seed.
+    pub fn from_seeds(seeds: Vec<u64>) -> Self {
+        Self { seeds, threads: 1 }
+    }
+
+    /// Sets the number of OS threads used to execute the runs (one by default).
+    pub fn threads(mut self, threads: usize) -> Self {
+        assert!(threads > 0, "Number of threads must be positive");
+        self.threads = threads;
+        self
+    }
+
+    /// Runs the scenario with each seed and collects the returned metrics.
+    ///
+    /// The scenario is invoked with the seed and should create and run its own simulation. If the scenario panics,
+    /// the panic is propagated after other threads finish.
+    pub fn run<F>(&self, scenario: F) -> ReplicationResults
+    where
+        F: Fn(u64) -> Metrics + Send + Sync + 'static,
+    {
+        let threads = self.threads.min(self.seeds.len());
+        if threads <= 1 {
+            let runs = self
+                .seeds
+                .iter()
+                .map(|&seed| RunResult {
+                    seed,
+                    metrics: scenario(seed),
+                })
+                .collect();
+            return ReplicationResults { runs };
+        }
+
+        let scenario = Arc::new(scenario);
+        let seeds = Arc::new(self.seeds.clone());
+        let next = Arc::new(AtomicUsize::new(0));
+        let results = Arc::new(Mutex::new(vec![None; seeds.len()]));
+        let handles: Vec<_> = (0..threads)
+            .map(|_| {
+                let scenario = scenario.clone();
+                let seeds = seeds.clone();
+                let next = next.clone();
+                let results = results.clone();
+                thread::spawn(move || loop {
+                    let index = next.fetch_add(1, Ordering::SeqCst);
+                    if index >= seeds.len() {
+                        break;
+                    }
+                    let metrics = scenario(seeds[index]);
+                    results.lock().unwrap()[index] = Some(metrics);
+                })
+            })
+            .collect();
+        let mut panicked = None;
+        for handle in handles {
+            if let Err(e) = handle.join() {
+                panicked = Some(e);
+            }
+        }
+        if let Some(e) = panicked {
+            std::panic::resume_unwind(e);
+        }
+        let metrics = Arc::try_unwrap(results).unwrap().into_inner().unwrap();
+        let runs = self
+            .seeds
+            .iter()
+            .zip(metrics)
+            .map(|(&seed, metrics)| RunResult {
+                seed,
+                metrics: metrics.unwrap(),
+            })
+            .collect();
+        ReplicationResults { runs }
+    }
+}
@@ -1,8 +1,11 @@
 //! Simulation configuration and execution.
 
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::future::Future;
+use std::io::{BufRead, Write};
+use std::path::Path;
 use std::rc::Rc;
+use std::time::Instant;
 
 use log::Level::Trace;
 use log::{debug, log_enabled, trace};
@@ -11,43 +14,52 @@ use rand::prelude::Distribution;
 use serde_json::json;
 use serde_type_name::type_name;
 
-use crate::component::Id;
+use crate::async_mode::Runtime;
+use crate::component::{ComponentRegistry, Id, PendingEvents, Route};
 use crate::context::SimulationContext;
+use crate::debug::{run_console, Breakpoint, BreakpointHit, BreakpointId, Breakpoints};
+use crate::event::{EventData, EventId, EventOrdering};
 use crate::handler::EventHandler;
-use crate::log::log_undelivered_event;
+use crate::hashing::{Divergence, EventHashLog, EventHasher};
+use crate::log::{log_dropped_event, log_undelivered_event};
+use crate::pacing::RealTimePacer;
+use crate::profiling::{ProfileReport, Profiler};
+use crate::replay::{EventDecoders, InputTrace};
 use crate::state::SimulationState;
+use crate::summary::{RunStats, RunSummary};
+use crate::trace::{EventTracer, TraceFormat};
+use crate::units::LogTime;
 use crate::Event;
 
 /// Represents a simulation, provides methods for its configuration and execution.
 pub struct Simulation {
     sim_state: Rc<RefCell<SimulationState>>,
-    name_to_id: HashMap<String, Id>,
     names: Rc<RefCell<Vec<String>>>,
-    handlers: Vec<Option<Rc<RefCell<dyn EventHandler>>>>,
+    registry: Rc<RefCell<ComponentRegistry>>,
+    runtime: Rc<RefCell<Runtime>>,
+    pacer: Option<RealTimePacer>,
+    stats: Option<RunStats>,
+    pub(crate) breakpoints: Breakpoints,
 }
 
 impl Simulation {
     /// Creates a new simulation with specified random seed.
     pub fn new(seed: u64) -> Self {
+        let sim_state = Rc::new(RefCell::new(SimulationState::new(seed)));
+        let names = Rc::new(RefCell::new(Vec::new()));
+        let registry = ComponentRegistry::new(sim_state.clone(), names.clone());
+        let runtime = Runtime::new(sim_state.clone());
         Self {
-            sim_state: Rc::new(RefCell::new(SimulationState::new(seed))),
-            name_to_id: HashMap::new(),
-            names: Rc::new(RefCell::new(Vec::new())),
-            handlers: Vec::new(),
+            sim_state,
+            names,
+            registry: Rc::new(RefCell::new(registry)),
+            runtime: Rc::new(RefCell::new(runtime)),
+            pacer: None,
+            stats: None,
+            breakpoints: Breakpoints::default(),
         }
     }
 
-    fn register(&mut self, name: &str) -> Id {
-        if let Some(&id) = self.name_to_id.get(name) {
-            return id;
-        }
-        let id = self.name_to_id.len() as Id;
-        self.name_to_id.insert(name.to_owned(), id);
-        self.names.borrow_mut().push(name.to_owned());
-        self.handlers.push(None);
-        id
-    }
-
     /// Returns the identifier of component by its name.
     ///
     /// Panics if component with such name does not exist.
@@ -71,7 +83,7 @@ impl Simulation {
     /// let comp1_id = sim.lookup_id("comp1");
     /// ```
     pub fn lookup_id(&self, name: &str) -> Id {
-        *self.name_to_id.get(name).unwrap()
+        self.registry.borrow().lookup_id(name).unwrap()
     }
 
     /// Returns the name of component by its identifier.
@@ -116,16 +128,19 @@ impl Simulation {
     where
         S: AsRef<str>,
     {
+        let id = self.registry.borrow_mut().register(name.as_ref());
         let ctx = SimulationContext::new(
-            self.register(name.as_ref()),
+            id,
             name.as_ref(),
             self.sim_state.clone(),
             self.names.clone(),
+            self.registry.clone(),
+            self.runtime.clone(),
         );
         debug!(
             target: "simulation",
             "[{:.3} {} simulation] Created context: {}",
-            self.time(),
+            LogTime(self.time()),
             crate::log::get_colored("DEBUG", colored::Color::Blue),
             json!({"name": ctx.name(), "id": ctx.id()})
         );
@@ -222,16 +237,106 @@ impl Simulation {
     where
         S: AsRef<str>,
     {
-        let id = self.register(name.as_ref());
-        self.handlers[id as usize] = Some(handler);
-        debug!(
-            target: "simulation",
-            "[{:.3} {} simulation] Added handler: {}",
-            self.time(),
-            crate::log::get_colored("DEBUG", colored::Color::Blue),
-            json!({"name": name.as_ref(), "id": id})
-        );
-        id
+        self.registry.borrow_mut().add_handler(name.as_ref(), handler)
+    }
+
+    /// Registers the component instance with specified name, so that it can be looked up by name via
+    /// [`component()`](Self::component()), returns the component Id.
+    ///
+    /// This allows to access shared components, e.g. network model, from other components by calling their methods
+    /// directly without passing the component instances through all constructors. If the component is also
+    /// an event handler, use [`add_component()`](Self::add_component()) instead.
+    pub fn register_component<S, T>(&mut self, name: S, component: Rc<RefCell<T>>) -> Id
+    where
+        S: AsRef<str>,
+        T: 'static,
+    {
+        self.registry.borrow_mut().register_instance(name.as_ref(), component)
+    }
+
+    /// Registers the component instance with specified name as both the event handler
+    /// (see [`add_handler()`](Self::add_handler())) and the instance available for lookup by name
+    /// (see [`register_component()`](Self::register_component())), returns the component Id.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::cell::RefCell;
+    /// use std::rc::Rc;
+    /// use serde::Serialize;
+    /// use dslab_core::{cast, Event, EventHandler, Simulation, SimulationContext};
+    ///
+    /// #[derive(Clone, Serialize)]
+    /// pub struct Start {
+    /// }
+    ///
+    /// pub struct Network {
+    ///     transferred: f64,
+    /// }
+    ///
+    /// impl Network {
+    ///     pub fn transfer_data(&mut self, size: f64) {
+    ///         self.transferred += size;
+    ///     }
+    /// }
+    ///
+    /// impl EventHandler for Network {
+    ///     fn on(&mut self, _event: Event) {}
+    /// }
+    ///
+    /// pub struct Client {
+    ///     ctx: SimulationContext,
+    /// }
+    ///
+    /// impl EventHandler for Client {
+    ///     fn on(&mut self, event: Event) {
+    ///         cast!(match event.data {
+    ///             Start {} => {
+    ///                 // the network is looked up by name instead of being passed to the constructor
+    ///                 self.ctx.component::<Network>("net").borrow_mut().transfer_data(100.);
+    ///             }
+    ///         })
+    ///     }
+    /// }
+    ///
+    /// let mut sim = Simulation::new(123);
+    /// sim.add_component("net", Rc::new(RefCell::new(Network { transferred: 0. })));
+    /// let client_ctx = sim.create_context("client");
+    /// client_ctx.emit_self(Start {}, 1.);
+    /// sim.add_component("client", Rc::new(RefCell::new(Client { ctx: client_ctx })));
+    /// sim.step_until_no_events();
+    ///
+    /// assert_eq!(sim.component::<Network>("net").borrow().transferred, 100.);
+    /// // the lookup fails if the component has different type
+    /// assert!(sim.try_component::<Client>("net").is_none());
+    /// assert!(sim.try_component::<Network>("unknown").is_none());
+    /// ```
+    pub fn add_component<S, T>(&mut self, name: S, component: Rc<RefCell<T>>) -> Id
+    where
+        S: AsRef<str>,
+        T: EventHandler + 'static,
+    {
+        self.register_component(name.as_ref(), component.clone());
+        self.add_handler(name, component)
+    }
+
+    /// Returns the typed handle of component instance registered with specified name.
+    ///
+    /// Panics if there is no registered component with such name and type.
+    pub fn component<T: 'static>(&self, name: &str) -> Rc<RefCell<T>> {
+        self.try_component(name).unwrap_or_else(|| {
+            panic!(
+                "Component {} of type {} is not registered",
+                name,
+                std::any::type_name::<T>()
+            )
+        })
+    }
+
+    /// Returns the typed handle of component instance registered with specified name,
+    /// or `None` if there is no registered component with such name and type.
+    pub fn try_component<T: 'static>(&self, name: &str) -> Option<Rc<RefCell<T>>> {
+        self.registry.borrow().instance(name)
     }
 
     /// Removes the event handler for component with specified name.
@@ -276,15 +381,504 @@ impl Simulation {
     where
         S: AsRef<str>,
     {
-        let id = self.lookup_id(name.as_ref());
-        self.handlers[id as usize] = None;
-        debug!(
-            target: "simulation",
-            "[{:.3} {} simulation] Removed handler: {}",
-            self.time(),
-            crate::log::get_colored("DEBUG", colored::Color::Blue),
-            json!({"name": name.as_ref(), "id": id})
-        );
+        self.registry.borrow_mut().remove_handler(name.as_ref());
+    }
+
+    /// Retires the component with specified name by removing its event handler,
+    /// and specifies what happens with the pending and future events destined for this component.
+    ///
+    /// In contrast to [`remove_handler()`](Self::remove_handler()), after which the events destined for the component
+    /// are logged as undelivered errors, the events destined for the retired component are either silently dropped
+    /// or redirected to another component. The events are handled this way at the moment of their delivery,
+    /// so retiring a component does not require scanning the event queue.
+    ///
+    /// The component can be brought back by adding its handler again.
+    /// Components can also be retired while the simulation is running via
+    /// [`SimulationContext::retire_component()`](crate::SimulationContext::retire_component()).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::cell::RefCell;
+    /// use std::rc::Rc;
+    /// use serde::Serialize;
+    /// use dslab_core::component::PendingEvents;
+    /// use dslab_core::{cast, Event, EventHandler, Simulation, SimulationContext};
+    ///
+    /// #[derive(Clone, Serialize)]
+    /// pub struct SomeEvent {
+    /// }
+    ///
+    /// pub struct Component {
+    ///     received: Vec<u32>,
+    /// }
+    ///
+    /// impl EventHandler for Component {
+    ///     fn on(&mut self, event: Event) {
+    ///         cast!(match event.data {
+    ///             SomeEvent { } => {
+    ///                 self.received.push(event.dst);
+    ///             }
+    ///         })
+    ///     }
+    /// }
+    ///
+    /// let mut sim = Simulation::new(123);
+    /// let client_ctx = sim.create_context("client");
+    /// let worker1 = Rc::new(RefCell::new(Component { received: Vec::new() }));
+    /// let worker1_id = sim.add_handler("worker1", worker1.clone());
+    /// let worker2 = Rc::new(RefCell::new(Component { received: Vec::new() }));
+    /// let worker2_id = sim.add_handler("worker2", worker2.clone());
+    ///
+    /// client_ctx.emit(SomeEvent {}, worker1_id, 1.0);
+    /// client_ctx.emit(SomeEvent {}, worker2_id, 1.0);
+    /// sim.retire_component("worker1", PendingEvents::Redirect(worker2_id));
+    /// sim.retire_component("worker2", PendingEvents::Drop);
+    /// sim.step_until_no_events();
+    /// // the first event is redirected to worker2, which is then retired, so both events are dropped
+    /// assert!(worker1.borrow().received.is_empty());
+    /// assert!(worker2.borrow().received.is_empty());
+    ///
+    /// sim.add_handler("worker2", worker2.clone());
+    /// client_ctx.emit(SomeEvent {}, worker1_id, 1.0);
+    /// sim.step_until_no_events();
+    /// assert_eq!(worker2.borrow().received, vec![worker2_id]);
+    /// ```
+    pub fn retire_component<S>(&mut self, name: S, pending: PendingEvents)
+    where
+        S: AsRef<str>,
+    {
+        self.registry.borrow_mut().retire(name.as_ref(), pending);
+    }
+
+    /// Enables recording of emitted and processed events to the trace file in the specified format.
+    ///
+    /// Each emitted event is recorded with its emission time, identifier, source, destination and payload type.
+    /// Each processed event is recorded when it is taken from the queue, i.e. before it is passed to the handler.
+    /// The serialized event payloads are included in the trace if `with_payloads` is true.
+    ///
+    /// The trace is written to the file while the simulation runs. It is completed when the tracing is disabled via
+    /// [`finish_event_tracing()`](Self::finish_event_tracing()) or the simulation is dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use serde::Serialize;
+    /// use dslab_core::trace::TraceFormat;
+    /// use dslab_core::Simulation;
+    ///
+    /// #[derive(Clone, Serialize)]
+    /// pub struct SomeEvent {
+    ///     some_field: u32,
+    /// }
+    ///
+    /// let path = std::env::temp_dir().join("dslab-core-trace-example.jsonl");
+    /// let mut sim = Simulation::new(123);
+    /// sim.enable_event_tracing(&path, TraceFormat::JsonLines, true).unwrap();
+    /// let comp1_ctx = sim.create_context("comp1");
+    /// let comp2_ctx = sim.create_context("comp2");
+    /// comp1_ctx.emit(SomeEvent { some_field: 16 }, comp2_ctx.id(), 1.2);
+    /// sim.step_until_no_events();
+    /// sim.finish_event_tracing();
+    ///
+    /// let trace = std::fs::read_to_string(&path).unwrap();
+    /// let records: Vec<serde_json::Value> = trace.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+    /// assert_eq!(records.len(), 2);
+    /// assert_eq!(records[0]["kind"], "emit");
+    /// assert_eq!(records[0]["src"], "comp1");
+    /// assert_eq!(records[0]["delivery_time"], 1.2);
+    /// assert_eq!(records[1]["kind"], "process");
+    /// assert_eq!(records[1]["type"], "SomeEvent");
+    /// assert_eq!(records[1]["data"]["some_field"], 16);
+    /// ```
+    ///
+    /// ```rust
+    /// use serde::Serialize;
+    /// use dslab_core::trace::TraceFormat;
+    /// use dslab_core::Simulation;
+    ///
+    /// #[derive(Clone, Serialize)]
+    /// pub struct SomeEvent {
+    /// }
+    ///
+    /// let path = std::env::temp_dir().join("dslab-core-trace-example.json");
+    /// let mut sim = Simulation::new(123);
+    /// sim.enable_event_tracing(&path, TraceFormat::Chrome, false).unwrap();
+    /// let comp_ctx = sim.create_context("comp");
+    /// comp_ctx.emit_self(SomeEvent {}, 1.0);
+    /// comp_ctx.emit_self(SomeEvent {}, 2.0);
+    /// sim.step_until_no_events();
+    /// sim.finish_event_tracing();
+    ///
+    /// let trace: Vec<serde_json::Value> = serde_json::from_reader(std::fs::File::open(&path).unwrap()).unwrap();
+    /// let slices: Vec<_> = trace.iter().filter(|r| r["ph"] == "X").collect();
+    /// assert_eq!(slices.len(), 2);
+    /// assert_eq!(slices[1]["ts"], 2e6);
+    /// assert!(trace.iter().any(|r| r["ph"] == "M" && r["args"]["name"] == "comp"));
+    /// ```
+    pub fn enable_event_tracing<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+        format: TraceFormat,
+        with_payloads: bool,
+    ) -> std::io::Result<()> {
+        let mut tracer = EventTracer::to_file(path, format, self.names.clone())?;
+        tracer.set_payloads(with_payloads);
+        self.sim_state.borrow_mut().set_tracer(Some(tracer));
+        Ok(())
+    }
+
+    /// Disables event tracing and completes the trace file.
+    pub fn finish_event_tracing(&mut self) {
+        self.sim_state.borrow_mut().set_tracer(None);
+    }
+
+    /// Schedules the input events from the trace for delivery at their recorded times.
+    ///
+    /// The destination components are looked up by name and should be registered before calling this method.
+    /// The source components which do not exist in the simulation (e.g. the workload generators of the original run)
+    /// are registered as components without handlers. Returns the number of scheduled events.
+    ///
+    /// See [`replay`](crate::replay) module for an example.
+    pub fn replay_input_trace(&mut self, trace: &InputTrace, decoders: &EventDecoders) -> Result<usize, String> {
+        let mut events = Vec::with_capacity(trace.events().len());
+        for event in trace.events() {
+            let dst = self
+                .lookup_component(&event.dst)
+                .ok_or_else(|| format!("Unknown destination component: {}", event.dst))?;
+            if event.time < self.time() {
+                return Err(format!(
+                    "Input event {} at time {} is in the past",
+                    event.event_type, event.time
+                ));
+            }
+            events.push((decoders.decode(event)?, event.src.as_str(), dst, event.time));
+        }
+        let count = events.len();
+        for (data, src, dst, time) in events {
+            let src = match self.lookup_component(src) {
+                Some(id) => id,
+                None => self.create_context(src).id(),
+            };
+            self.add_event_at(data, src, dst, time);
+        }
+        Ok(count)
+    }
+
+    /// Enables real-time pacing, which throttles the simulation progress to the wall-clock time.
+    ///
+    /// With pacing enabled, each step waits until the wall-clock time corresponding to the next event time,
+    /// so that one unit of simulation time takes `1 / speed` seconds (`speed = 1.0` means real time).
+    /// [`step_until_time()`](Self::step_until_time()) also waits before advancing the time to the specified value.
+    /// The wall-clock time is counted from the moment of this call, so the simulation catches up without waiting
+    /// if it was not stepped for some time or falls behind because of slow event handlers.
+    ///
+    /// This mode is intended for demos, dashboards and interaction with external processes expecting
+    /// real-time behavior. Panics if the speed is not positive and finite.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::time::Instant;
+    /// use serde::Serialize;
+    /// use dslab_core::Simulation;
+    ///
+    /// #[derive(Clone, Serialize)]
+    /// pub struct SomeEvent {
+    /// }
+    ///
+    /// let mut sim = Simulation::new(123);
+    /// let comp_ctx = sim.create_context("comp");
+    /// comp_ctx.emit_self(SomeEvent {}, 1.0);
+    /// comp_ctx.emit_self(SomeEvent {}, 2.0);
+    /// // run 100 times faster than real time
+    /// sim.enable_real_time_pacing(100.);
+    /// let start = Instant::now();
+    /// sim.step_until_no_events();
+    /// assert!(start.elapsed().as_secs_f64() >= 0.02);
+    /// assert_eq!(sim.time(), 2.0);
+    /// ```
+    pub fn enable_real_time_pacing(&mut self, speed: f64) {
+        self.pacer = Some(RealTimePacer::new(speed, self.time()));
+    }
+
+    /// Disables real-time pacing, so that the simulation runs as fast as possible.
+    pub fn disable_real_time_pacing(&mut self) {
+        self.pacer = None;
+    }
+
+    /// Enables collection of per-component counters: handled events and their processing time by event type,
+    /// and emitted events.
+    ///
+    /// The collected counters can be obtained at any time via [`profile_report()`](Self::profile_report()).
+    /// Enabling the profiling again resets the counters.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use serde::Serialize;
+    /// use dslab_core::{cast, Event, EventHandler, Simulation, SimulationContext};
+    ///
+    /// #[derive(Clone, Serialize)]
+    /// pub struct Ping {
+    /// }
+    ///
+    /// pub struct Process {
+    ///     ctx: SimulationContext,
+    /// }
+    ///
+    /// impl EventHandler for Process {
+    ///     fn on(&mut self, event: Event) {
+    ///         cast!(match event.data {
+    ///             Ping {} => {
+    ///                 if self.ctx.time() < 10. {
+    ///                     self.ctx.emit(Ping {}, event.src, 1.);
+    ///                 }
+    ///             }
+    ///         })
+    ///     }
+    /// }
+    ///
+    /// let mut sim = Simulation::new(123);
+    /// sim.enable_profiling();
+    /// let ctx1 = sim.create_context("proc1");
+    /// let ctx2 = sim.create_context("proc2");
+    /// ctx1.emit(Ping {}, ctx2.id(), 1.);
+    /// sim.add_handler("proc1", std::rc::Rc::new(std::cell::RefCell::new(Process { ctx: ctx1 })));
+    /// sim.add_handler("proc2", std::rc::Rc::new(std::cell::RefCell::new(Process { ctx: ctx2 })));
+    /// sim.step_until_no_events();
+    ///
+    /// let report = sim.profile_report();
+    /// let proc1 = report.component("proc1").unwrap();
+    /// assert_eq!(proc1.events_handled, 5);
+    /// assert_eq!(proc1.events_emitted, 5);
+    /// assert_eq!(proc1.event_types[0].type_name, "Ping");
+    /// let proc2 = report.component("proc2").unwrap();
+    /// assert_eq!(proc2.events_handled, 5);
+    /// assert_eq!(proc2.events_emitted, 5);
+    /// assert!(report.flame_report().contains("proc2;Ping "));
+    /// println!("{}", report);
+    /// ```
+    pub fn enable_profiling(&mut self) {
+        let profiler = Profiler::new(self.names.clone());
+        self.sim_state.borrow_mut().set_profiler(Some(profiler));
+    }
+
+    /// Disables collection of per-component counters and discards the collected counters.
+    pub fn disable_profiling(&mut self) {
+        self.sim_state.borrow_mut().set_profiler(None);
+    }
+
+    /// Returns the summary of per-component counters collected since the profiling was enabled.
+    ///
+    /// Panics if the profiling is not enabled.
+    pub fn profile_report(&self) -> ProfileReport {
+        self.sim_state
+            .borrow()
+            .profiler()
+            .expect("Profiling is not enabled")
+            .report()
+    }
+
+    /// Enables computing of the rolling hash of processed events, which covers the time, payload type, source
+    /// and destination of each event.
+    ///
+    /// Two runs of a deterministic simulation with the same seed produce the same hash, which can be obtained via
+    /// [`event_hash()`](Self::event_hash()). If `record` is true, the hash after each processed event is also
+    /// recorded, so that the log obtained via [`event_hash_log()`](Self::event_hash_log()) can be used to find
+    /// the first divergent event in another run (see [`check_event_hashes()`](Self::check_event_hashes())).
+    /// Enabling the hashing again resets the hash.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use serde::Serialize;
+    /// use dslab_core::Simulation;
+    ///
+    /// #[derive(Clone, Serialize)]
+    /// pub struct SomeEvent {
+    /// }
+    ///
+    /// fn run(seed: u64, extra_event: bool) -> Simulation {
+    ///     let mut sim = Simulation::new(seed);
+    ///     sim.enable_event_hashing(true);
+    ///     let comp1_ctx = sim.create_context("comp1");
+    ///     let comp2_ctx = sim.create_context("comp2");
+    ///     for i in 0..10 {
+    ///         comp1_ctx.emit(SomeEvent {}, comp2_ctx.id(), i as f64);
+    ///     }
+    ///     if extra_event {
+    ///         comp2_ctx.emit(SomeEvent {}, comp1_ctx.id(), 4.5);
+    ///     }
+    ///     sim.step_until_no_events();
+    ///     sim
+    /// }
+    ///
+    /// let sim1 = run(123, false);
+    /// let sim2 = run(123, false);
+    /// assert_eq!(sim1.event_hash(), sim2.event_hash());
+    /// let sim3 = run(123, true);
+    /// assert_ne!(sim1.event_hash(), sim3.event_hash());
+    /// // the sixth processed event differs
+    /// assert_eq!(sim1.event_hash_log().first_divergence(&sim3.event_hash_log()), Some(5));
+    /// ```
+    pub fn enable_event_hashing(&mut self, record: bool) {
+        self.sim_state
+            .borrow_mut()
+            .set_event_hasher(Some(EventHasher::new(record, None)));
+    }
+
+    /// Enables computing of the rolling hash of processed events and checking it against the reference log
+    /// recorded in another run (see [`enable_event_hashing()`](Self::enable_event_hashing())).
+    ///
+    /// The first processed event at which the hash differs from the reference is logged at `ERROR` level
+    /// and can be obtained via [`event_divergence()`](Self::event_divergence()).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use serde::Serialize;
+    /// use dslab_core::Simulation;
+    ///
+    /// #[derive(Clone, Serialize)]
+    /// pub struct SomeEvent {
+    /// }
+    ///
+    /// #[derive(Clone, Serialize)]
+    /// pub struct OtherEvent {
+    /// }
+    ///
+    /// let mut sim1 = Simulation::new(123);
+    /// sim1.enable_event_hashing(true);
+    /// let ctx1 = sim1.create_context("comp");
+    /// for i in 0..10 {
+    ///     ctx1.emit_self(SomeEvent {}, i as f64);
+    /// }
+    /// sim1.step_until_no_events();
+    /// let path = std::env::temp_dir().join("dslab-core-event-hashes.txt");
+    /// sim1.event_hash_log().save(&path).unwrap();
+    ///
+    /// let mut sim2 = Simulation::new(123);
+    /// sim2.check_event_hashes(dslab_core::hashing::EventHashLog::load(&path).unwrap());
+    /// let ctx2 = sim2.create_context("comp");
+    /// for i in 0..10 {
+    ///     if i == 7 {
+    ///         ctx2.emit_self(OtherEvent {}, i as f64);
+    ///     } else {
+    ///         ctx2.emit_self(SomeEvent {}, i as f64);
+    ///     }
+    /// }
+    /// sim2.step_until_no_events();
+    /// let divergence = sim2.event_divergence().unwrap();
+    /// assert_eq!(divergence.index, 7);
+    /// assert_eq!(divergence.time, 7.);
+    /// assert_eq!(divergence.event_type, "OtherEvent");
+    /// ```
+    pub fn check_event_hashes(&mut self, reference: EventHashLog) {
+        self.sim_state
+            .borrow_mut()
+            .set_event_hasher(Some(EventHasher::new(false, Some(reference))));
+    }
+
+    /// Disables computing of the hash of processed events.
+    pub fn disable_event_hashing(&mut self) {
+        self.sim_state.borrow_mut().set_event_hasher(None);
+    }
+
+    /// Returns the rolling hash of events processed since the hashing was enabled.
+    ///
+    /// Panics if the event hashing is not enabled.
+    pub fn event_hash(&self) -> u64 {
+        self.sim_state
+            .borrow()
+            .event_hasher()
+            .expect("Event hashing is not enabled")
+            .hash()
+    }
+
+    /// Returns the log of rolling hashes after each processed event.
+    ///
+    /// Panics if the event hashing is not enabled with recording.
+    pub fn event_hash_log(&self) -> EventHashLog {
+        self.sim_state
+            .borrow()
+            .event_hasher()
+            .and_then(|hasher| hasher.log())
+            .expect("Event hash recording is not enabled")
+    }
+
+    /// Returns the first processed event at which the simulation diverged from the reference hash log,
+    /// if any (see [`check_event_hashes()`](Self::check_event_hashes())).
+    pub fn event_divergence(&self) -> Option<Divergence> {
+        self.sim_state
+            .borrow()
+            .event_hasher()
+            .and_then(|hasher| hasher.divergence().cloned())
+    }
+
+    /// Sets the ordering of events scheduled at the same time.
+    ///
+    /// By default, the simultaneous events are processed in the order of their creation. See [`EventOrdering`]
+    /// for other options. The ordering can be changed at any time and applies to all pending events.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::cell::RefCell;
+    /// use std::rc::Rc;
+    /// use serde::Serialize;
+    /// use dslab_core::event::EventOrdering;
+    /// use dslab_core::{cast, Event, EventHandler, Simulation};
+    ///
+    /// #[derive(Clone, Serialize)]
+    /// pub struct SomeEvent {
+    ///     value: i64,
+    /// }
+    ///
+    /// pub struct Component {
+    ///     log: Rc<RefCell<Vec<(u32, i64)>>>,
+    /// }
+    ///
+    /// impl EventHandler for Component {
+    ///     fn on(&mut self, event: Event) {
+    ///         cast!(match event.data {
+    ///             SomeEvent { value } => {
+    ///                 self.log.borrow_mut().push((event.dst, value));
+    ///             }
+    ///         })
+    ///     }
+    /// }
+    ///
+    /// let mut sim = Simulation::new(123);
+    /// let log = Rc::new(RefCell::new(Vec::new()));
+    /// let comp1 = sim.add_handler("comp1", Rc::new(RefCell::new(Component { log: log.clone() })));
+    /// let comp2 = sim.add_handler("comp2", Rc::new(RefCell::new(Component { log: log.clone() })));
+    /// let ctx = sim.create_context("client");
+    ///
+    /// sim.set_event_ordering(EventOrdering::ComponentPriority);
+    /// sim.set_component_priority(comp2, -1);
+    /// ctx.emit(SomeEvent { value: 1 }, comp1, 1.);
+    /// ctx.emit(SomeEvent { value: 2 }, comp2, 1.);
+    /// sim.step_until_no_events();
+    /// assert_eq!(*log.borrow(), vec![(comp2, 2), (comp1, 1)]);
+    ///
+    /// log.borrow_mut().clear();
+    /// sim.set_event_ordering(EventOrdering::Custom(Box::new(|event| {
+    ///     -event.data.downcast_ref::<SomeEvent>().unwrap().value
+    /// })));
+    /// for value in 0..3 {
+    ///     ctx.emit(SomeEvent { value }, comp1, 1.);
+    /// }
+    /// sim.step_until_no_events();
+    /// assert_eq!(*log.borrow(), vec![(comp1, 2), (comp1, 1), (comp1, 0)]);
+    /// ```
+    pub fn set_event_ordering(&mut self, ordering: EventOrdering) {
+        self.sim_state.borrow_mut().set_event_ordering(ordering);
+    }
+
+    /// Sets the priority of component used by [`EventOrdering::ComponentPriority`], lower value means higher priority.
+    pub fn set_component_priority(&mut self, id: Id, priority: i64) {
+        self.sim_state.borrow_mut().set_component_priority(id, priority);
     }
 
     /// Returns the current simulation time.
@@ -340,35 +934,92 @@ impl Simulation {
     /// assert!(!status);
     /// ```
     pub fn step(&mut self) -> bool {
+        let start = self.start_wall_clock();
+        let result = self.process_next_event();
+        self.stop_wall_clock(start);
+        result
+    }
+
+    fn process_next_event(&mut self) -> bool {
+        // run the tasks spawned outside of event handlers
+        Runtime::run_ready_tasks(&self.runtime);
+        if let Some(pacer) = &self.pacer {
+            if let Some(time) = self.next_event_time() {
+                pacer.wait_until(time);
+            }
+        }
         let next = self.sim_state.borrow_mut().next_event();
-        if let Some(event) = next {
-            if let Some(handler_opt) = self.handlers.get(event.dst as usize) {
+        if let Some(mut event) = next {
+            let type_id = (*event.data).as_any().type_id();
+            if let Some(stats) = self.stats.as_mut() {
+                stats.on_event(&event, type_id);
+            }
+            event.dst = self.registry.borrow().resolve_event_route(event.dst, type_id);
+            let profile_key = self
+                .sim_state
+                .borrow_mut()
+                .profiler_mut()
+                .map(|profiler| profiler.event_key(&event));
+            let start = profile_key.map(|_| Instant::now());
+            let event = self.runtime.borrow_mut().try_complete(event);
+            if let Some(event) = event {
+                self.deliver(event);
+            }
+            Runtime::run_ready_tasks(&self.runtime);
+            if let (Some(key), Some(start)) = (profile_key, start) {
+                if let Some(profiler) = self.sim_state.borrow_mut().profiler_mut() {
+                    profiler.on_handled(key, start.elapsed());
+                }
+            }
+            true
+        } else {
+            false
+        }
+    }
+
+    fn deliver(&mut self, mut event: Event) {
+        let route = self.registry.borrow().route(event.dst);
+        match route {
+            Route::Deliver(dst, handler) => {
+                event.dst = dst;
                 if log_enabled!(Trace) {
                     let src_name = self.lookup_name(event.src);
                     let dst_name = self.lookup_name(event.dst);
                     trace!(
                         target: &dst_name,
                         "[{:.3} {} {}] {}",
-                        event.time,
+                        LogTime(event.time),
                         crate::log::get_colored("EVENT", colored::Color::BrightBlack),
                         dst_name,
                         json!({"type": type_name(&event.data).unwrap(), "data": event.data, "src": src_name})
                     );
                 }
-                if let Some(handler) = handler_opt {
-                    handler.borrow_mut().on(event);
-                } else {
-                    log_undelivered_event(event);
-                }
-            } else {
-                log_undelivered_event(event);
+                handler.borrow_mut().on(event);
             }
-            true
-        } else {
-            false
+            Route::Drop => log_dropped_event(event),
+            Route::Undelivered => log_undelivered_event(event),
         }
     }
 
+    /// Returns the start time of the stepping method if the run statistics are enabled.
+    fn start_wall_clock(&self) -> Option<Instant> {
+        self.stats.as_ref().map(|_| Instant::now())
+    }
+
+    /// Adds the time elapsed since the start of the stepping method to the run statistics.
+    fn stop_wall_clock(&mut self, start: Option<Instant>) {
+        if let (Some(stats), Some(start)) = (self.stats.as_mut(), start) {
+            stats.add_wall_time(start.elapsed());
+        }
+    }
+
+    /// Spawns an asynchronous task, which is executed by the simulation.
+    ///
+    /// See [`SimulationContext::spawn()`](crate::SimulationContext::spawn()) for details.
+    pub fn spawn(&mut self, future: impl Future<Output = ()> + 'static) {
+        self.runtime.borrow_mut().spawn(future);
+    }
+
     /// Performs the specified number of steps through the simulation.
     ///
     /// This is a convenient wrapper around [`step()`](Self::step()), which invokes this method until the specified number of
@@ -400,12 +1051,16 @@ impl Simulation {
     /// assert_eq!(sim.time(), 1.4);
     /// ```
     pub fn steps(&mut self, step_count: u64) -> bool {
+        let start = self.start_wall_clock();
+        let mut result = true;
         for _ in 0..step_count {
-            if !self.step() {
-                return false;
+            if !self.process_next_event() {
+                result = false;
+                break;
             }
         }
-        true
+        self.stop_wall_clock(start);
+        result
     }
 
     /// Steps through the simulation until there are no pending events left.
@@ -432,7 +1087,9 @@ impl Simulation {
     /// assert_eq!(sim.time(), 1.4);
     /// ```
     pub fn step_until_no_events(&mut self) {
-        while self.step() {}
+        let start = self.start_wall_clock();
+        while self.process_next_event() {}
+        self.stop_wall_clock(start);
     }
 
     /// Steps through the simulation with duration limit.
@@ -507,9 +1164,10 @@ impl Simulation {
     /// assert!(!status); // there are no more events
     /// ```
     pub fn step_until_time(&mut self, time: f64) -> bool {
+        let start = self.start_wall_clock();
         let mut result = true;
         loop {
-            if let Some(event) = self.sim_state.borrow().peek_event() {
+            if let Some(event) = self.sim_state.borrow_mut().peek_event() {
                 if event.time > time {
                     break;
                 }
@@ -517,12 +1175,257 @@ impl Simulation {
                 result = false;
                 break;
             }
-            self.step();
+            self.process_next_event();
+        }
+        if let Some(pacer) = &self.pacer {
+            pacer.wait_until(time);
         }
         self.sim_state.borrow_mut().set_time(time);
+        self.stop_wall_clock(start);
+        result
+    }
+
+    /// Enables or disables the shared random number generator for all components.
+    ///
+    /// By default, the random methods of [`SimulationContext`] use a separate generator for each component, which
+    /// is seeded from the simulation seed and the component name. This way adding a new random component or changing
+    /// the random calls of some component does not perturb the random numbers observed by the other components.
+    ///
+    /// This option is provided for compatibility with the results obtained with earlier versions of the library,
+    /// where all components share the simulation-wide random number generator, so the numbers obtained by
+    /// a component depend on the random calls made by other components.
+    ///
+    /// The random methods of [`Simulation`] always use the simulation-wide generator.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use dslab_core::Simulation;
+    ///
+    /// let mut sim1 = Simulation::new(123);
+    /// let comp_ctx1 = sim1.create_context("comp");
+    ///
+    /// let mut sim2 = Simulation::new(123);
+    /// let other_ctx = sim2.create_context("other");
+    /// let comp_ctx2 = sim2.create_context("comp");
+    ///
+    /// // random calls made by another component do not affect the numbers observed by "comp"
+    /// let _ = other_ctx.rand();
+    /// for _ in 0..10 {
+    ///     assert_eq!(comp_ctx1.rand(), comp_ctx2.rand());
+    /// }
+    /// assert_ne!(other_ctx.rand(), comp_ctx2.rand());
+    ///
+    /// // with the shared generator the numbers depend on the calls made by all components
+    /// let mut sim3 = Simulation::new(123);
+    /// sim3.set_shared_rng(true);
+    /// let comp_ctx3 = sim3.create_context("comp");
+    /// let mut sim4 = Simulation::new(123);
+    /// assert_eq!(comp_ctx3.rand(), sim4.rand());
+    /// ```
+    pub fn set_shared_rng(&mut self, enabled: bool) {
+        self.sim_state.borrow_mut().set_shared_rng(enabled);
+    }
+
+    /// Returns whether the shared random number generator is used by all components.
+    pub fn shared_rng(&self) -> bool {
+        self.sim_state.borrow().shared_rng()
+    }
+
+    /// Steps through the simulation until the specified condition is satisfied or there are no pending events left.
+    ///
+    /// The condition is checked before each step, so the simulation is not advanced if the condition is already
+    /// satisfied. Ready-made conditions, such as the limits on simulation time or number of processed events and
+    /// detection of quiescence, are provided in the [`stop`](crate::stop) module.
+    ///
+    /// Returns `true` if the condition is satisfied and `false` if there are no more pending events.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use serde::Serialize;
+    /// use dslab_core::Simulation;
+    ///
+    /// #[derive(Clone, Serialize)]
+    /// pub struct SomeEvent {
+    /// }
+    ///
+    /// let mut sim = Simulation::new(123);
+    /// let comp_ctx = sim.create_context("comp");
+    /// for i in 1..=10 {
+    ///     comp_ctx.emit_self(SomeEvent {}, i as f64);
+    /// }
+    /// assert!(sim.run_until(|sim| sim.processed_event_count() == 3));
+    /// assert_eq!(sim.time(), 3.);
+    /// assert!(sim.run_until(|sim| sim.time() >= 5.));
+    /// assert_eq!(sim.time(), 5.);
+    /// assert!(!sim.run_until(|_| false));
+    /// assert_eq!(sim.time(), 10.);
+    /// ```
+    pub fn run_until<F>(&mut self, mut condition: F) -> bool
+    where
+        F: FnMut(&Simulation) -> bool,
+    {
+        let start = self.start_wall_clock();
+        let result = loop {
+            if condition(self) {
+                break true;
+            }
+            if !self.process_next_event() {
+                break false;
+            }
+        };
+        self.stop_wall_clock(start);
         result
     }
 
+    /// Returns the time of the next pending event, if any.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use serde::Serialize;
+    /// use dslab_core::Simulation;
+    ///
+    /// #[derive(Clone, Serialize)]
+    /// pub struct SomeEvent {
+    /// }
+    ///
+    /// let mut sim = Simulation::new(123);
+    /// let comp_ctx = sim.create_context("comp");
+    /// assert_eq!(sim.next_event_time(), None);
+    /// comp_ctx.emit_self(SomeEvent {}, 1.5);
+    /// assert_eq!(sim.next_event_time(), Some(1.5));
+    /// ```
+    pub fn next_event_time(&self) -> Option<f64> {
+        self.sim_state.borrow_mut().peek_event().map(|event| event.time)
+    }
+
+    pub(crate) fn lookup_component(&self, name: &str) -> Option<Id> {
+        self.registry.borrow().lookup_id(name)
+    }
+
+    pub(crate) fn add_event_at(&mut self, data: Box<dyn EventData>, src: Id, dst: Id, time: f64) -> EventId {
+        let mut state = self.sim_state.borrow_mut();
+        let delay = time - state.time();
+        state.add_boxed_event(data, src, dst, delay, 0)
+    }
+
+    /// Returns a copy of the next pending event, if any.
+    pub fn peek_event(&self) -> Option<Event> {
+        self.sim_state.borrow_mut().peek_event().cloned()
+    }
+
+    /// Adds a breakpoint, which stops [`run_to_breakpoint()`](Self::run_to_breakpoint()) before processing
+    /// the matching event. Returns the breakpoint identifier.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use serde::Serialize;
+    /// use dslab_core::debug::Breakpoint;
+    /// use dslab_core::Simulation;
+    ///
+    /// #[derive(Clone, Serialize)]
+    /// pub struct Tick {
+    /// }
+    ///
+    /// #[derive(Clone, Serialize)]
+    /// pub struct Failure {
+    /// }
+    ///
+    /// let mut sim = Simulation::new(123);
+    /// let comp1_ctx = sim.create_context("comp1");
+    /// let comp2_ctx = sim.create_context("comp2");
+    /// for i in 1..=10 {
+    ///     comp1_ctx.emit_self(Tick {}, i as f64);
+    /// }
+    /// comp1_ctx.emit(Failure {}, comp2_ctx.id(), 7.5);
+    ///
+    /// let bp1 = sim.add_breakpoint(Breakpoint::Time(3.));
+    /// let bp2 = sim.add_breakpoint(Breakpoint::EventType("Failure".to_string()));
+    /// let hit = sim.run_to_breakpoint().unwrap();
+    /// assert_eq!((hit.breakpoint, hit.event.time), (bp1, 3.));
+    /// // the event matching the breakpoint is not processed yet
+    /// assert_eq!(sim.time(), 2.);
+    /// assert_eq!(sim.peek_event().unwrap().id, hit.event.id);
+    ///
+    /// let hit = sim.run_to_breakpoint().unwrap();
+    /// assert_eq!((hit.breakpoint, hit.event.dst), (bp2, comp2_ctx.id()));
+    /// assert_eq!(sim.dump_events().len(), 4);
+    ///
+    /// sim.remove_breakpoint(bp2);
+    /// sim.add_breakpoint(Breakpoint::Condition(Box::new(|e| e.id == 9)));
+    /// assert_eq!(sim.run_to_breakpoint().unwrap().event.time, 10.);
+    /// sim.step();
+    /// assert!(sim.run_to_breakpoint().is_none());
+    /// ```
+    pub fn add_breakpoint(&mut self, breakpoint: Breakpoint) -> BreakpointId {
+        self.breakpoints.add(breakpoint)
+    }
+
+    /// Removes the breakpoint, returns `false` if there is no such breakpoint.
+    pub fn remove_breakpoint(&mut self, id: BreakpointId) -> bool {
+        self.breakpoints.remove(id)
+    }
+
+    /// Steps through the simulation until the next pending event matches some breakpoint
+    /// or there are no pending events left.
+    ///
+    /// Returns the hit breakpoint and the matching event, which is left in the queue, or `None` if there are
+    /// no more pending events. When called again, the simulation is resumed from the matching event without
+    /// stopping on it.
+    pub fn run_to_breakpoint(&mut self) -> Option<BreakpointHit> {
+        loop {
+            Runtime::run_ready_tasks(&self.runtime);
+            {
+                let mut state = self.sim_state.borrow_mut();
+                let event = state.peek_event()?;
+                if let Some(breakpoint) = self.breakpoints.check(event) {
+                    return Some(BreakpointHit {
+                        breakpoint,
+                        event: event.clone(),
+                    });
+                }
+            }
+            self.step();
+        }
+    }
+
+    /// Runs the interactive debug console, which reads the commands from `input` and writes the output to `output`
+    /// until `exit` command or the end of input.
+    ///
+    /// The console allows to step through the simulation event by event, manage breakpoints and inspect
+    /// the pending events. Type `help` to see the list of commands. The console is usually started
+    /// with standard input and output, e.g. `sim.debug_console(std::io::stdin().lock(), std::io::stdout())`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use serde::Serialize;
+    /// use dslab_core::Simulation;
+    ///
+    /// #[derive(Clone, Serialize)]
+    /// pub struct Tick {
+    /// }
+    ///
+    /// let mut sim = Simulation::new(123);
+    /// let comp_ctx = sim.create_context("comp");
+    /// for i in 1..=10 {
+    ///     comp_ctx.emit_self(Tick {}, i as f64);
+    /// }
+    /// let commands = "step 2\nb time 5\ncontinue\nqueue 1\nexit\n";
+    /// let mut output = Vec::new();
+    /// sim.debug_console(commands.as_bytes(), &mut output).unwrap();
+    /// let output = String::from_utf8(output).unwrap();
+    /// assert!(output.contains("Breakpoint 0 hit before event #4 5.000 comp -> comp Tick {}"));
+    /// assert!(output.contains("6 pending events"));
+    /// assert_eq!(sim.time(), 4.);
+    /// ```
+    pub fn debug_console<R: BufRead, W: Write>(&mut self, input: R, output: W) -> std::io::Result<()> {
+        run_console(self, input, output)
+    }
+
     /// Returns a random float in the range _[0, 1)_
     /// using the simulation-wide random number generator.
     ///
@@ -536,7 +1439,7 @@ impl Simulation {
     /// assert!(f >= 0.0 && f < 1.0);
     /// ```
     pub fn rand(&mut self) -> f64 {
-        self.sim_state.borrow_mut().rand()
+        self.sim_state.borrow_mut().rand(None)
     }
 
     /// Returns a random number in the specified range
@@ -558,19 +1461,19 @@ impl Simulation {
         T: SampleUniform,
         R: SampleRange<T>,
     {
-        self.sim_state.borrow_mut().gen_range(range)
+        self.sim_state.borrow_mut().gen_range(None, range)
     }
 
     /// Returns a random value from the specified distribution
     /// using the simulation-wide random number generator.
     pub fn sample_from_distribution<T, Dist: Distribution<T>>(&mut self, dist: &Dist) -> T {
-        self.sim_state.borrow_mut().sample_from_distribution(dist)
+        self.sim_state.borrow_mut().sample_from_distribution(None, dist)
     }
 
     /// Returns a random alphanumeric string of specified length
     /// using the simulation-wide random number generator.
     pub fn random_string(&mut self, len: usize) -> String {
-        self.sim_state.borrow_mut().random_string(len)
+        self.sim_state.borrow_mut().random_string(None, len)
     }
 
     /// Returns the total number of created events.
@@ -599,6 +1502,114 @@ impl Simulation {
         self.sim_state.borrow().event_count()
     }
 
+    /// Returns the total number of processed events.
+    ///
+    /// In contrast to [`event_count()`](Self::event_count()), only the events taken from the queue are counted here,
+    /// including the events which were not delivered to any component.
+    pub fn processed_event_count(&self) -> u64 {
+        self.sim_state.borrow().processed_event_count()
+    }
+
+    /// Enables collection of the run statistics reported by [`run_summary()`](Self::run_summary()).
+    ///
+    /// The statistics are not collected by default to avoid the overhead of measuring the wall-clock time
+    /// and counting the events by type in the event loop. Enabling the collection again resets the statistics.
+    pub fn enable_run_stats(&mut self) {
+        self.stats = Some(RunStats::default());
+    }
+
+    /// Disables collection of the run statistics and discards the collected statistics.
+    pub fn disable_run_stats(&mut self) {
+        self.stats = None;
+    }
+
+    /// Returns the summary statistics of the simulation run since the run statistics were enabled.
+    ///
+    /// The wall-clock time includes the time spent in the stepping methods, such as [`step()`](Self::step())
+    /// and [`step_until_no_events()`](Self::step_until_no_events()), but not in the model setup.
+    /// The numbers of processed and emitted events and the peak queue size cover the whole run.
+    ///
+    /// Panics if the run statistics are not enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use serde::Serialize;
+    /// use dslab_core::Simulation;
+    ///
+    /// #[derive(Clone, Serialize)]
+    /// pub struct Request {
+    /// }
+    ///
+    /// #[derive(Clone, Serialize)]
+    /// pub struct Response {
+    /// }
+    ///
+    /// let mut sim = Simulation::new(123);
+    /// sim.enable_run_stats();
+    /// let comp_ctx = sim.create_context("comp");
+    /// for i in 1..=3 {
+    ///     comp_ctx.emit_self(Request {}, i as f64);
+    /// }
+    /// comp_ctx.emit_self(Response {}, 5.);
+    /// sim.step_until_no_events();
+    ///
+    /// let summary = sim.run_summary();
+    /// assert_eq!(summary.processed_events, 4);
+    /// assert_eq!(summary.events_by_type["Request"], 3);
+    /// assert_eq!(summary.events_by_type["Response"], 1);
+    /// assert_eq!(summary.first_event_time, Some(1.));
+    /// assert_eq!(summary.last_event_time, Some(5.));
+    /// assert_eq!(summary.peak_queue_size, 4);
+    /// assert!(summary.wall_time > 0.);
+    /// let json = serde_json::to_string(&summary).unwrap();
+    /// assert!(json.contains("\"events_by_type\":{\"Request\":3,\"Response\":1}"));
+    /// ```
+    pub fn run_summary(&self) -> RunSummary {
+        let state = self.sim_state.borrow();
+        self.stats.as_ref().expect("Run statistics are not enabled").summary(
+            state.processed_event_count(),
+            state.event_count(),
+            state.time(),
+            state.peak_queue_size(),
+        )
+    }
+
+    /// Cancels the specified event.
+    ///
+    /// Use [`EventId`](crate::event::EventId) obtained when creating the event to cancel it.
+    /// The canceled event is removed from the queue and will not be delivered.
+    /// Cancelling an already processed event has no effect.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use serde::Serialize;
+    /// use dslab_core::Simulation;
+    ///
+    /// #[derive(Clone, Serialize)]
+    /// pub struct SomeEvent {
+    /// }
+    ///
+    /// let mut sim = Simulation::new(123);
+    /// let mut comp_ctx = sim.create_context("comp");
+    /// let event1 = comp_ctx.emit_self(SomeEvent{}, 1.0);
+    /// let event2 = comp_ctx.emit_self(SomeEvent{}, 3.0);
+    /// sim.cancel_event(event1);
+    /// // the canceled event does not cause processing of events beyond the specified time
+    /// assert!(sim.step_until_time(2.0));
+    /// assert_eq!(sim.event_count(), 2);
+    /// assert_eq!(sim.dump_events().len(), 1);
+    /// sim.step();
+    /// assert_eq!(sim.time(), 3.0);
+    /// // cancelling processed event is ignored
+    /// sim.cancel_event(event2);
+    /// assert!(!sim.step());
+    /// ```
+    pub fn cancel_event(&mut self, id: EventId) {
+        self.sim_state.borrow_mut().cancel_event(id);
+    }
+
     /// Cancels events that satisfy the given predicate function.
     ///
     /// Note that already processed events cannot be cancelled.
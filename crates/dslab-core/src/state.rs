@@ -1,4 +1,4 @@
-use std::collections::{BinaryHeap, HashSet, VecDeque};
+use std::collections::{HashMap, HashSet, VecDeque};
 
 use rand::distributions::uniform::{SampleRange, SampleUniform};
 use rand::distributions::{Alphanumeric, DistString};
@@ -6,31 +6,55 @@ use rand::prelude::*;
 use rand_pcg::Pcg64;
 
 use crate::component::Id;
-use crate::event::{Event, EventData, EventId};
+use crate::event::{Event, EventData, EventId, EventOrdering};
+use crate::hashing::EventHasher;
 use crate::log::log_incorrect_event;
+use crate::profiling::Profiler;
+use crate::queue::EventQueue;
+use crate::timer::{Timer, TimerId, TimerState};
+use crate::trace::EventTracer;
 
 /// Epsilon to compare floating point values for equality.
 pub const EPSILON: f64 = 1e-12;
 
-#[derive(Clone)]
 pub struct SimulationState {
     clock: f64,
+    seed: u64,
     rand: Pcg64,
-    events: BinaryHeap<Event>,
+    component_rngs: Vec<Pcg64>,
+    shared_rng: bool,
+    events: EventQueue,
     ordered_events: VecDeque<Event>,
     canceled_events: HashSet<EventId>,
     event_count: u64,
+    processed_event_count: u64,
+    peak_queue_size: usize,
+    tracer: Option<EventTracer>,
+    profiler: Option<Profiler>,
+    event_hasher: Option<EventHasher>,
+    timers: HashMap<TimerId, TimerState>,
+    timer_count: u64,
 }
 
 impl SimulationState {
     pub fn new(seed: u64) -> Self {
         Self {
             clock: 0.0,
+            seed,
             rand: Pcg64::seed_from_u64(seed),
-            events: BinaryHeap::new(),
+            component_rngs: Vec::new(),
+            shared_rng: false,
+            events: EventQueue::new(),
             ordered_events: VecDeque::new(),
             canceled_events: HashSet::new(),
             event_count: 0,
+            processed_event_count: 0,
+            peak_queue_size: 0,
+            tracer: None,
+            profiler: None,
+            event_hasher: None,
+            timers: HashMap::new(),
+            timer_count: 0,
         }
     }
 
@@ -42,40 +66,123 @@ impl SimulationState {
         self.clock = time;
     }
 
-    pub fn rand(&mut self) -> f64 {
-        self.rand.gen_range(0.0..1.0)
+    /// Creates random number generator stream for a new component.
+    ///
+    /// The stream is seeded from the simulation seed and the component name,
+    /// so it does not depend on the other components and the order of their registration.
+    pub fn add_component_rng(&mut self, name: &str) {
+        self.component_rngs
+            .push(Pcg64::seed_from_u64(component_seed(self.seed, name)));
     }
 
-    pub fn gen_range<T, R>(&mut self, range: R) -> T
+    pub fn set_shared_rng(&mut self, enabled: bool) {
+        self.shared_rng = enabled;
+    }
+
+    pub fn shared_rng(&self) -> bool {
+        self.shared_rng
+    }
+
+    /// Returns the random number generator stream of the specified component,
+    /// or the simulation-wide generator if the component is not specified or the shared generator is enabled.
+    fn rng(&mut self, component: Option<Id>) -> &mut Pcg64 {
+        match component {
+            Some(id) if !self.shared_rng => &mut self.component_rngs[id as usize],
+            _ => &mut self.rand,
+        }
+    }
+
+    pub fn set_event_ordering(&mut self, ordering: EventOrdering) {
+        self.events.set_ordering(ordering);
+    }
+
+    pub fn set_component_priority(&mut self, component: Id, priority: i64) {
+        self.events.set_priority(component, priority);
+    }
+
+    pub fn set_profiler(&mut self, profiler: Option<Profiler>) {
+        self.profiler = profiler;
+    }
+
+    pub fn profiler(&self) -> Option<&Profiler> {
+        self.profiler.as_ref()
+    }
+
+    pub fn profiler_mut(&mut self) -> Option<&mut Profiler> {
+        self.profiler.as_mut()
+    }
+
+    pub fn set_event_hasher(&mut self, hasher: Option<EventHasher>) {
+        self.event_hasher = hasher;
+    }
+
+    pub fn event_hasher(&self) -> Option<&EventHasher> {
+        self.event_hasher.as_ref()
+    }
+
+    pub fn set_tracer(&mut self, tracer: Option<EventTracer>) {
+        self.tracer = tracer;
+    }
+
+    pub fn rand(&mut self, component: Option<Id>) -> f64 {
+        self.rng(component).gen_range(0.0..1.0)
+    }
+
+    pub fn gen_range<T, R>(&mut self, component: Option<Id>, range: R) -> T
     where
         T: SampleUniform,
         R: SampleRange<T>,
     {
-        self.rand.gen_range(range)
+        self.rng(component).gen_range(range)
     }
 
-    pub fn sample_from_distribution<T, Dist: Distribution<T>>(&mut self, dist: &Dist) -> T {
-        dist.sample(&mut self.rand)
+    pub fn sample_from_distribution<T, Dist: Distribution<T>>(&mut self, component: Option<Id>, dist: &Dist) -> T {
+        dist.sample(self.rng(component))
     }
 
-    pub fn random_string(&mut self, len: usize) -> String {
-        Alphanumeric.sample_string(&mut self.rand, len)
+    pub fn random_string(&mut self, component: Option<Id>, len: usize) -> String {
+        Alphanumeric.sample_string(self.rng(component), len)
     }
 
     pub fn add_event<T>(&mut self, data: T, src: Id, dst: Id, delay: f64) -> EventId
     where
         T: EventData,
     {
+        self.add_event_with_priority(data, src, dst, delay, 0)
+    }
+
+    pub fn add_event_with_priority<T>(&mut self, data: T, src: Id, dst: Id, delay: f64, priority: i64) -> EventId
+    where
+        T: EventData,
+    {
+        self.add_boxed_event(Box::new(data), src, dst, delay, priority)
+    }
+
+    pub fn add_boxed_event(
+        &mut self,
+        data: Box<dyn EventData>,
+        src: Id,
+        dst: Id,
+        delay: f64,
+        priority: i64,
+    ) -> EventId {
         let event_id = self.event_count;
         let event = Event {
             id: event_id,
             time: self.clock + delay.max(0.),
             src,
             dst,
-            data: Box::new(data),
+            data,
         };
         if delay >= -EPSILON {
-            self.events.push(event);
+            if let Some(tracer) = self.tracer.as_mut() {
+                tracer.on_emit(self.clock, &event);
+            }
+            if let Some(profiler) = self.profiler.as_mut() {
+                profiler.on_emit(src);
+            }
+            self.events.push_with_priority(event, priority);
+            self.update_peak_queue_size();
             self.event_count += 1;
             event_id
         } else {
@@ -102,7 +209,14 @@ impl SimulationState {
             data: Box::new(data),
         };
         if delay >= 0. {
+            if let Some(tracer) = self.tracer.as_mut() {
+                tracer.on_emit(self.clock, &event);
+            }
+            if let Some(profiler) = self.profiler.as_mut() {
+                profiler.on_emit(src);
+            }
             self.ordered_events.push_back(event);
+            self.update_peak_queue_size();
             self.event_count += 1;
             event_id
         } else {
@@ -121,20 +235,54 @@ impl SimulationState {
         true
     }
 
+    /// Returns true if the next event should be taken from the main queue rather than from `ordered_events`.
+    ///
+    /// Simultaneous events from both queues are ordered by their priorities and then by ids,
+    /// the ordered events have the default priority 0.
+    fn next_event_is_unordered(&mut self) -> bool {
+        let (time, id) = match self.events.peek() {
+            Some(event) => (event.time, event.id),
+            None => return false,
+        };
+        match self.ordered_events.front() {
+            Some(ordered) => time
+                .total_cmp(&ordered.time)
+                .then_with(|| self.events.event_priority(id).cmp(&0))
+                .then_with(|| id.cmp(&ordered.id))
+                .is_lt(),
+            None => true,
+        }
+    }
+
     pub fn next_event(&mut self) -> Option<Event> {
         loop {
-            let maybe_heap = self.events.peek();
-            let maybe_deque = self.ordered_events.front();
-            if maybe_heap.is_some() && (maybe_deque.is_none() || maybe_heap.unwrap() > maybe_deque.unwrap()) {
+            if self.next_event_is_unordered() {
                 let event = self.events.pop().unwrap();
-                if !self.canceled_events.remove(&event.id) {
+                if self.canceled_events.is_empty() || !self.canceled_events.remove(&event.id) {
                     self.clock = event.time;
+                    self.processed_event_count += 1;
+                    if let Some(tracer) = self.tracer.as_mut() {
+                        tracer.on_process(&event);
+                    }
+                    if let Some(hasher) = self.event_hasher.as_mut() {
+                        hasher.on_process(&event);
+                    }
+                    if !self.timers.is_empty() {
+                        self.on_timer_event(&event);
+                    }
                     return Some(event);
                 }
-            } else if maybe_deque.is_some() {
+            } else if !self.ordered_events.is_empty() {
                 let event = self.ordered_events.pop_front().unwrap();
-                if !self.canceled_events.remove(&event.id) {
+                if self.canceled_events.is_empty() || !self.canceled_events.remove(&event.id) {
                     self.clock = event.time;
+                    self.processed_event_count += 1;
+                    if let Some(tracer) = self.tracer.as_mut() {
+                        tracer.on_process(&event);
+                    }
+                    if let Some(hasher) = self.event_hasher.as_mut() {
+                        hasher.on_process(&event);
+                    }
                     return Some(event);
                 }
             } else {
@@ -143,18 +291,108 @@ impl SimulationState {
         }
     }
 
-    pub fn peek_event(&self) -> Option<&Event> {
-        let maybe_heap = self.events.peek();
-        let maybe_deque = self.ordered_events.front();
-        if maybe_heap.is_some() && (maybe_deque.is_none() || maybe_heap.unwrap() > maybe_deque.unwrap()) {
-            maybe_heap
+    pub fn set_timer(&mut self, owner: Id, delay: f64, interval: Option<f64>) -> TimerId {
+        if let Some(interval) = interval {
+            assert!(interval > 0., "Timer interval must be positive, got {}", interval);
+        }
+        let id = self.timer_count;
+        self.timer_count += 1;
+        let event_id = self.add_event(Timer { id }, owner, owner, delay);
+        self.timers.insert(
+            id,
+            TimerState {
+                owner,
+                interval,
+                event_id,
+            },
+        );
+        id
+    }
+
+    pub fn cancel_timer(&mut self, id: TimerId) {
+        if let Some(timer) = self.timers.remove(&id) {
+            self.cancel_event(timer.event_id);
+        }
+    }
+
+    pub fn cancel_component_timers(&mut self, owner: Id) {
+        let ids: Vec<TimerId> = self
+            .timers
+            .iter()
+            .filter(|(_, timer)| timer.owner == owner)
+            .map(|(&id, _)| id)
+            .collect();
+        for id in ids {
+            self.cancel_timer(id);
+        }
+    }
+
+    /// Schedules the next firing of periodic timer or removes the fired one-shot timer.
+    fn on_timer_event(&mut self, event: &Event) {
+        let id = match event.data.downcast_ref::<Timer>() {
+            Some(timer) => timer.id,
+            None => return,
+        };
+        let (owner, interval) = match self.timers.get(&id) {
+            Some(timer) if timer.event_id == event.id => (timer.owner, timer.interval),
+            _ => return,
+        };
+        match interval {
+            Some(interval) => {
+                let event_id = self.add_event(Timer { id }, owner, owner, interval);
+                self.timers.get_mut(&id).unwrap().event_id = event_id;
+            }
+            None => {
+                self.timers.remove(&id);
+            }
+        }
+    }
+
+    pub fn peek_event(&mut self) -> Option<&Event> {
+        self.discard_canceled_events();
+        if self.next_event_is_unordered() {
+            self.events.peek()
         } else {
-            maybe_deque
+            self.ordered_events.front()
+        }
+    }
+
+    /// Removes canceled events from the front of event queues,
+    /// so that the next pending event is not hidden behind canceled ones.
+    fn discard_canceled_events(&mut self) {
+        if self.canceled_events.is_empty() {
+            return;
+        }
+        while let Some(event) = self.events.peek() {
+            if !self.canceled_events.remove(&event.id) {
+                break;
+            }
+            self.events.pop();
+        }
+        while let Some(event) = self.ordered_events.front() {
+            if !self.canceled_events.remove(&event.id) {
+                break;
+            }
+            self.ordered_events.pop_front();
         }
     }
 
     pub fn cancel_event(&mut self, id: EventId) {
+        if id >= self.event_count {
+            return;
+        }
         self.canceled_events.insert(id);
+        // Ids of already processed events are never removed from canceled_events on their own,
+        // so such ids are purged once the set grows noticeably larger than the event queue.
+        if self.canceled_events.len() > 2 * (self.events.len() + self.ordered_events.len()) + 16 {
+            let pending: HashSet<EventId> = self
+                .events
+                .iter()
+                .chain(self.ordered_events.iter())
+                .map(|event| event.id)
+                .collect();
+            self.canceled_events.retain(|id| pending.contains(id));
+        }
     }
 
     pub fn cancel_events<F>(&mut self, pred: F)
@@ -209,6 +447,18 @@ impl SimulationState {
         self.event_count
     }
 
+    pub fn processed_event_count(&self) -> u64 {
+        self.processed_event_count
+    }
+
+    pub fn peak_queue_size(&self) -> usize {
+        self.peak_queue_size
+    }
+
+    fn update_peak_queue_size(&mut self) {
+        self.peak_queue_size = self.peak_queue_size.max(self.events.len() + self.ordered_events.len());
+    }
+
     pub fn dump_events(&self) -> Vec<Event> {
         let mut output = Vec::new();
         for event in self.events.iter() {
@@ -227,3 +477,17 @@ impl SimulationState {
         output
     }
 }
+
+/// Derives the seed of component random number generator from the simulation seed and component name
+/// using FNV-1a hash of the name followed by SplitMix64 finalizer.
+fn component_seed(seed: u64, name: &str) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in name.bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    let mut z = seed ^ hash;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
@@ -0,0 +1,274 @@
+//! Statistical analysis of simulation output.
+//!
+//! The module provides the common tools for analysis of metrics collected from replicated simulation runs:
+//! confidence intervals for the mean, removal of the initial transient (warm-up) period from output time series,
+//! and significance tests for comparing two configurations (e.g. two scheduling policies).
+
+/// Returns the arithmetic mean of the values (NaN for empty values).
+pub fn mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+/// Returns the sample (unbiased) variance of the values, or zero if there are less than two values.
+pub fn sample_variance(values: &[f64]) -> f64 {
+    if values.len() < 2 {
+        return 0.;
+    }
+    let mean = mean(values);
+    values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (values.len() - 1) as f64
+}
+
+// Natural logarithm of the gamma function (Lanczos approximation).
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFS: [f64; 6] = [
+        76.18009172947146,
+        -86.50532032941677,
+        24.01409824083091,
+        -1.231739572450155,
+        0.1208650973866179e-2,
+        -0.5395239384953e-5,
+    ];
+    let tmp = x + 5.5;
+    let tmp = tmp - (x + 0.5) * tmp.ln();
+    let mut y = x;
+    let mut series = 1.000000000190015;
+    for coeff in COEFFS {
+        y += 1.;
+        series += coeff / y;
+    }
+    -tmp + (2.5066282746310005 * series / x).ln()
+}
+
+// Continued fraction for the incomplete beta function (modified Lentz's method).
+fn beta_continued_fraction(a: f64, b: f64, x: f64) -> f64 {
+    const MAX_ITERATIONS: usize = 300;
+    const EPS: f64 = 1e-14;
+    const TINY: f64 = 1e-300;
+    let clamp = |v: f64| if v.abs() < TINY { TINY } else { v };
+
+    let mut c = 1.;
+    let mut d = 1. / clamp(1. - (a + b) * x / (a + 1.));
+    let mut result = d;
+    for m in 1..=MAX_ITERATIONS {
+        let m = m as f64;
+        let numerator = m * (b - m) * x / ((a - 1. + 2. * m) * (a + 2. * m));
+        d = 1. / clamp(1. + numerator * d);
+        c = clamp(1. + numerator / c);
+        result *= d * c;
+        let numerator = -(a + m) * (a + b + m) * x / ((a + 2. * m) * (a + 1. + 2. * m));
+        d = 1. / clamp(1. + numerator * d);
+        c = clamp(1. + numerator / c);
+        let delta = d * c;
+        result *= delta;
+        if (delta - 1.).abs() < EPS {
+            break;
+        }
+    }
+    result
+}
+
+// Regularized incomplete beta function I_x(a, b).
+fn incomplete_beta(a: f64, b: f64, x: f64) -> f64 {
+    if x <= 0. {
+        return 0.;
+    }
+    if x >= 1. {
+        return 1.;
+    }
+    let front = (ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1. - x).ln()).exp();
+    if x < (a + 1.) / (a + b + 2.) {
+        front * beta_continued_fraction(a, b, x) / a
+    } else {
+        1. - front * beta_continued_fraction(b, a, 1. - x) / b
+    }
+}
+
+/// Returns the two-sided tail probability `P(|T| >= |t|)` of Student's t-distribution
+/// with `df` degrees of freedom.
+pub fn student_t_two_sided_p(t: f64, df: f64) -> f64 {
+    assert!(df > 0., "Degrees of freedom must be positive");
+    if t.is_infinite() {
+        return 0.;
+    }
+    incomplete_beta(df / 2., 0.5, df / (df + t * t))
+}
+
+/// Returns the critical value `t` of Student's t-distribution with `df` degrees of freedom
+/// such that `P(|T| <= t) = level`, e.g. 12.706 for `level = 0.95` and `df = 1`.
+pub fn student_t_quantile(level: f64, df: f64) -> f64 {
+    assert!(level > 0. && level < 1., "Confidence level must be in (0, 1)");
+    let alpha = 1. - level;
+    let mut low = 0.;
+    let mut high = 1.;
+    while student_t_two_sided_p(high, df) > alpha {
+        low = high;
+        high *= 2.;
+    }
+    for _ in 0..100 {
+        let mid = (low + high) / 2.;
+        if student_t_two_sided_p(mid, df) > alpha {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    (low + high) / 2.
+}
+
+/// Confidence interval for the mean.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ConfidenceInterval {
+    /// Sample mean.
+    pub mean: f64,
+    /// Half-width of the interval.
+    pub half_width: f64,
+    /// Confidence level, e.g. 0.95.
+    pub level: f64,
+}
+
+impl ConfidenceInterval {
+    /// Returns the lower bound of the interval.
+    pub fn lower(&self) -> f64 {
+        self.mean - self.half_width
+    }
+
+    /// Returns the upper bound of the interval.
+    pub fn upper(&self) -> f64 {
+        self.mean + self.half_width
+    }
+
+    /// Checks whether the interval contains the value.
+    pub fn contains(&self, value: f64) -> bool {
+        self.lower() <= value && value <= self.upper()
+    }
+
+    /// Returns the half-width relative to the absolute value of the mean, which is commonly used
+    /// to decide whether the number of replications is sufficient.
+    pub fn relative_half_width(&self) -> f64 {
+        self.half_width / self.mean.abs()
+    }
+}
+
+/// Computes the confidence interval for the mean of independent observations (e.g. the values of a metric
+/// in replicated runs) based on Student's t-distribution. The half-width is zero for a single value.
+///
+/// Panics if the values are empty.
+pub fn confidence_interval(values: &[f64], level: f64) -> ConfidenceInterval {
+    assert!(!values.is_empty(), "Cannot compute confidence interval of empty values");
+    let count = values.len();
+    let half_width = if count > 1 {
+        let t = student_t_quantile(level, (count - 1) as f64);
+        t * (sample_variance(values) / count as f64).sqrt()
+    } else {
+        0.
+    };
+    ConfidenceInterval {
+        mean: mean(values),
+        half_width,
+        level,
+    }
+}
+
+/// Returns the number of initial observations of the output series which should be discarded as the warm-up
+/// period according to the MSER-5 rule.
+///
+/// The observations are grouped into batches of 5, and the truncation point is selected to minimize
+/// the marginal standard error of the remaining batch means. Only the first half of the series is considered,
+/// because the truncation in the second half indicates that the run is too short to reach the steady state.
+pub fn mser5_truncation(values: &[f64]) -> usize {
+    const BATCH_SIZE: usize = 5;
+    let batches: Vec<f64> = values.chunks_exact(BATCH_SIZE).map(mean).collect();
+    if batches.len() < 2 {
+        return 0;
+    }
+    let mut best = (f64::INFINITY, 0);
+    for skip in 0..=batches.len() / 2 {
+        let rest = &batches[skip..];
+        let rest_mean = mean(rest);
+        let error = rest.iter().map(|v| (v - rest_mean).powi(2)).sum::<f64>() / (rest.len() as f64).powi(2);
+        if error < best.0 {
+            best = (error, skip);
+        }
+    }
+    best.1 * BATCH_SIZE
+}
+
+/// Returns the output series without the warm-up period detected by [`mser5_truncation`].
+pub fn truncate_warmup(values: &[f64]) -> &[f64] {
+    &values[mser5_truncation(values)..]
+}
+
+/// Returns the values of `(time, value)` samples observed at or after the end of fixed warm-up period.
+pub fn truncate_by_time(samples: &[(f64, f64)], warmup: f64) -> Vec<f64> {
+    samples
+        .iter()
+        .filter(|(time, _)| *time >= warmup)
+        .map(|(_, value)| *value)
+        .collect()
+}
+
+/// Result of significance test comparing the means of two samples.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TestResult {
+    /// Difference of the sample means (first minus second).
+    pub mean_difference: f64,
+    /// Value of t statistic.
+    pub statistic: f64,
+    /// Degrees of freedom of the t-distribution.
+    pub df: f64,
+    /// Two-sided p-value for the null hypothesis of equal means.
+    pub p_value: f64,
+}
+
+impl TestResult {
+    fn new(mean_difference: f64, std_error: f64, df: f64) -> Self {
+        let (statistic, p_value) = if std_error > 0. {
+            let statistic = mean_difference / std_error;
+            (statistic, student_t_two_sided_p(statistic, df))
+        } else if mean_difference == 0. {
+            (0., 1.)
+        } else {
+            (mean_difference.signum() * f64::INFINITY, 0.)
+        };
+        Self {
+            mean_difference,
+            statistic,
+            df,
+            p_value,
+        }
+    }
+
+    /// Checks whether the difference of means is significant at the specified level, e.g. 0.05.
+    pub fn is_significant(&self, alpha: f64) -> bool {
+        self.p_value < alpha
+    }
+}
+
+/// Welch's t-test for the difference of means of two independent samples with possibly different variances,
+/// e.g. the values of a metric obtained with two policies using different seeds.
+///
+/// Panics if some sample has less than two values.
+pub fn welch_t_test(a: &[f64], b: &[f64]) -> TestResult {
+    assert!(a.len() > 1 && b.len() > 1, "Each sample must have at least two values");
+    let (na, nb) = (a.len() as f64, b.len() as f64);
+    let (va, vb) = (sample_variance(a) / na, sample_variance(b) / nb);
+    let df = if va + vb > 0. {
+        (va + vb).powi(2) / (va.powi(2) / (na - 1.) + vb.powi(2) / (nb - 1.))
+    } else {
+        na + nb - 2.
+    };
+    TestResult::new(mean(a) - mean(b), (va + vb).sqrt(), df)
+}
+
+/// Paired t-test for the difference of means of two samples with matched values, e.g. the values of a metric
+/// obtained with two policies using the same seeds (common random numbers), which usually requires fewer runs
+/// to detect the difference than [`welch_t_test`].
+///
+/// Panics if the samples have different lengths or less than two values.
+pub fn paired_t_test(a: &[f64], b: &[f64]) -> TestResult {
+    assert_eq!(a.len(), b.len(), "Paired samples must have equal lengths");
+    assert!(a.len() > 1, "Each sample must have at least two values");
+    let differences: Vec<f64> = a.iter().zip(b).map(|(x, y)| x - y).collect();
+    let n = differences.len() as f64;
+    TestResult::new(mean(&differences), (sample_variance(&differences) / n).sqrt(), n - 1.)
+}
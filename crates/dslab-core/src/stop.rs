@@ -0,0 +1,92 @@
+//! Conditions for stopping the simulation with [`Simulation::run_until()`].
+//!
+//! Each function returns a condition which can be passed to `run_until()` directly or combined with other conditions
+//! using the ordinary boolean operators inside a closure.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use std::cell::Cell;
+//! use std::rc::Rc;
+//! use serde::Serialize;
+//! use dslab_core::{stop, Simulation};
+//!
+//! #[derive(Clone, Serialize)]
+//! pub struct SomeEvent {
+//! }
+//!
+//! let mut sim = Simulation::new(123);
+//! let comp_ctx = sim.create_context("comp");
+//! for i in 1..=100 {
+//!     comp_ctx.emit_self(SomeEvent {}, i as f64);
+//! }
+//! let mut max_time = stop::max_time(50.);
+//! let mut max_events = stop::max_events(20);
+//! assert!(sim.run_until(|sim| max_time(sim) || max_events(sim)));
+//! assert_eq!(sim.time(), 20.);
+//!
+//! // the counter is updated by some component, e.g. the number of completed tasks
+//! let completed = Rc::new(Cell::new(0));
+//! completed.set(5);
+//! assert!(sim.run_until(stop::counter_reaches(completed.clone(), 5)));
+//! assert_eq!(sim.time(), 20.);
+//! ```
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use crate::simulation::Simulation;
+
+/// Stops before processing the first event with time greater than the specified time.
+///
+/// Note that in contrast to [`Simulation::step_until_time()`] the simulation time is not advanced to the specified
+/// time when the condition is satisfied.
+pub fn max_time(time: f64) -> impl FnMut(&Simulation) -> bool {
+    move |sim| sim.next_event_time().map_or(false, |next_time| next_time > time)
+}
+
+/// Stops when the specified number of events is processed since the condition creation.
+pub fn max_events(count: u64) -> impl FnMut(&Simulation) -> bool {
+    let mut start = None;
+    move |sim| {
+        let processed = sim.processed_event_count();
+        processed - *start.get_or_insert(processed) >= count
+    }
+}
+
+/// Stops when the simulation becomes quiescent, i.e. there are no pending events within the specified duration
+/// from the current time.
+///
+/// This allows to stop simulations which never run out of events, e.g. due to the periodic events with period
+/// exceeding the specified duration, once the rest of the activity is over.
+///
+/// # Examples
+///
+/// ```rust
+/// use serde::Serialize;
+/// use dslab_core::{stop, Simulation};
+///
+/// #[derive(Clone, Serialize)]
+/// pub struct SomeEvent {
+/// }
+///
+/// let mut sim = Simulation::new(123);
+/// let comp_ctx = sim.create_context("comp");
+/// for i in 1..=10 {
+///     comp_ctx.emit_self(SomeEvent {}, i as f64 * 0.1);
+/// }
+/// comp_ctx.emit_self(SomeEvent {}, 100.);
+/// assert!(sim.run_until(stop::quiescence(10.)));
+/// assert_eq!(sim.time(), 1.);
+/// ```
+pub fn quiescence(duration: f64) -> impl FnMut(&Simulation) -> bool {
+    move |sim| {
+        sim.next_event_time()
+            .map_or(true, |next_time| next_time - sim.time() > duration)
+    }
+}
+
+/// Stops when the value of the external counter reaches the specified value.
+pub fn counter_reaches(counter: Rc<Cell<u64>>, value: u64) -> impl FnMut(&Simulation) -> bool {
+    move |_| counter.get() >= value
+}
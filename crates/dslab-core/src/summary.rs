@@ -0,0 +1,91 @@
+//! Summary statistics of simulation run.
+
+use std::any::TypeId;
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+
+use serde::Serialize;
+use serde_type_name::type_name;
+
+use crate::event::Event;
+
+/// Basic statistics of simulation run returned by [`Simulation::run_summary()`](crate::Simulation::run_summary()).
+///
+/// The summary can be serialized (e.g. to JSON) and stored along with the experiment results.
+#[derive(Clone, Debug, Serialize)]
+pub struct RunSummary {
+    /// Total number of processed events.
+    pub processed_events: u64,
+    /// Total number of emitted events, including the pending and cancelled ones.
+    pub emitted_events: u64,
+    /// Number of processed events by payload type name.
+    pub events_by_type: BTreeMap<String, u64>,
+    /// Time of the first processed event.
+    pub first_event_time: Option<f64>,
+    /// Time of the last processed event.
+    pub last_event_time: Option<f64>,
+    /// Current simulation time.
+    pub sim_time: f64,
+    /// Wall-clock time in seconds spent in the simulation stepping methods.
+    pub wall_time: f64,
+    /// Number of processed events per second of wall-clock time.
+    pub events_per_second: f64,
+    /// Maximum number of pending events in the queue (including the cancelled events not yet removed from it).
+    pub peak_queue_size: usize,
+}
+
+/// Accumulates the run statistics collected by the simulation.
+#[derive(Default)]
+pub(crate) struct RunStats {
+    events_by_type: HashMap<TypeId, (String, u64)>,
+    first_event_time: Option<f64>,
+    last_event_time: Option<f64>,
+    wall_time: Duration,
+}
+
+impl RunStats {
+    pub fn on_event(&mut self, event: &Event, type_id: TypeId) {
+        self.events_by_type
+            .entry(type_id)
+            .or_insert_with(|| (type_name(&event.data).unwrap().to_owned(), 0))
+            .1 += 1;
+        if self.first_event_time.is_none() {
+            self.first_event_time = Some(event.time);
+        }
+        self.last_event_time = Some(event.time);
+    }
+
+    pub fn add_wall_time(&mut self, duration: Duration) {
+        self.wall_time += duration;
+    }
+
+    pub fn summary(
+        &self,
+        processed_events: u64,
+        emitted_events: u64,
+        sim_time: f64,
+        peak_queue_size: usize,
+    ) -> RunSummary {
+        let mut events_by_type = BTreeMap::new();
+        for (name, count) in self.events_by_type.values() {
+            // different types can have the same name if they are defined in different modules
+            *events_by_type.entry(name.clone()).or_insert(0) += count;
+        }
+        let wall_time = self.wall_time.as_secs_f64();
+        RunSummary {
+            processed_events,
+            emitted_events,
+            events_by_type,
+            first_event_time: self.first_event_time,
+            last_event_time: self.last_event_time,
+            sim_time,
+            wall_time,
+            events_per_second: if wall_time > 0. {
+                processed_events as f64 / wall_time
+            } else {
+                0.
+            },
+            peak_queue_size,
+        }
+    }
+}
@@ -0,0 +1,27 @@
+//! Timers.
+
+use serde::Serialize;
+
+use crate::component::Id;
+use crate::event::EventId;
+
+/// Identifier of timer.
+pub type TimerId = u64;
+
+/// Event delivered to the component when its timer fires.
+///
+/// Timers are created via [`SimulationContext::set_timer()`](crate::SimulationContext::set_timer())
+/// and [`SimulationContext::set_periodic_timer()`](crate::SimulationContext::set_periodic_timer()).
+/// The event source and destination are set to the component which created the timer.
+#[derive(Clone, Serialize)]
+pub struct Timer {
+    /// Identifier of fired timer.
+    pub id: TimerId,
+}
+
+/// State of an active timer.
+pub(crate) struct TimerState {
+    pub owner: Id,
+    pub interval: Option<f64>,
+    pub event_id: EventId,
+}
@@ -0,0 +1,209 @@
+//! Tracing of simulation events.
+
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::rc::Rc;
+
+use serde_json::{json, Value};
+use serde_type_name::type_name;
+
+use crate::component::Id;
+use crate::event::Event;
+
+/// Format of event trace file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TraceFormat {
+    /// Each line contains a JSON object describing the emitted or processed event.
+    ///
+    /// Emitted events are recorded as `{"kind": "emit", "time", "id", "src", "dst", "type", "data", "delivery_time"}`,
+    /// processed events are recorded as `{"kind": "process", "time", "id", "src", "dst", "type", "data"}`.
+    JsonLines,
+    /// [Chrome trace event format](https://docs.google.com/document/d/1CvAClvFfyA5R-PhYUmn5OOQtYMH4h6I0nSsKchNAySU),
+    /// which can be opened in [Perfetto](https://ui.perfetto.dev) or `chrome://tracing`.
+    ///
+    /// Each component is represented as a separate thread. Processed events are shown as zero-duration slices
+    /// on the thread of destination component, and each event is connected with the slice of event which emitted it
+    /// by a flow arrow. Timestamps correspond to the simulation time (one simulation time unit is shown as a second).
+    Chrome,
+}
+
+/// Records emitted and processed events to a structured trace file.
+///
+/// The trace is written incrementally while the simulation runs, so it does not keep the events in memory.
+pub struct EventTracer {
+    writer: Box<dyn Write>,
+    format: TraceFormat,
+    names: Rc<RefCell<Vec<String>>>,
+    payloads: bool,
+    named_threads: HashSet<Id>,
+    record_count: u64,
+    finished: bool,
+}
+
+impl EventTracer {
+    pub(crate) fn new(writer: Box<dyn Write>, format: TraceFormat, names: Rc<RefCell<Vec<String>>>) -> Self {
+        let mut tracer = Self {
+            writer,
+            format,
+            names,
+            payloads: true,
+            named_threads: HashSet::new(),
+            record_count: 0,
+            finished: false,
+        };
+        if format == TraceFormat::Chrome {
+            tracer.write_raw("[\n");
+        }
+        tracer
+    }
+
+    pub(crate) fn to_file<P: AsRef<Path>>(
+        path: P,
+        format: TraceFormat,
+        names: Rc<RefCell<Vec<String>>>,
+    ) -> std::io::Result<Self> {
+        let file = File::create(path)?;
+        Ok(Self::new(Box::new(BufWriter::new(file)), format, names))
+    }
+
+    pub(crate) fn set_payloads(&mut self, payloads: bool) {
+        self.payloads = payloads;
+    }
+
+    /// Records an event emitted at the specified time.
+    pub(crate) fn on_emit(&mut self, time: f64, event: &Event) {
+        match self.format {
+            TraceFormat::JsonLines => {
+                let mut record = self.json_record("emit", time, event);
+                record["delivery_time"] = json!(event.time);
+                self.write_record(record);
+            }
+            TraceFormat::Chrome => {
+                self.name_thread(event.src);
+                self.write_record(json!({
+                    "ph": "s",
+                    "name": type_name(&event.data).unwrap(),
+                    "cat": "event",
+                    "id": event.id,
+                    "pid": 0,
+                    "tid": event.src,
+                    "ts": time * 1e6,
+                }));
+            }
+        }
+    }
+
+    /// Records an event taken from the queue for processing.
+    pub(crate) fn on_process(&mut self, event: &Event) {
+        match self.format {
+            TraceFormat::JsonLines => {
+                let record = self.json_record("process", event.time, event);
+                self.write_record(record);
+            }
+            TraceFormat::Chrome => {
+                self.name_thread(event.dst);
+                let event_type = type_name(&event.data).unwrap();
+                let mut args = json!({"id": event.id, "src": self.name(event.src)});
+                if self.payloads {
+                    args["data"] = json!(event.data);
+                }
+                self.write_record(json!({
+                    "ph": "X",
+                    "name": event_type,
+                    "cat": "event",
+                    "pid": 0,
+                    "tid": event.dst,
+                    "ts": event.time * 1e6,
+                    "dur": 0,
+                    "args": args,
+                }));
+                self.write_record(json!({
+                    "ph": "f",
+                    "bp": "e",
+                    "name": event_type,
+                    "cat": "event",
+                    "id": event.id,
+                    "pid": 0,
+                    "tid": event.dst,
+                    "ts": event.time * 1e6,
+                }));
+            }
+        }
+    }
+
+    /// Completes the trace file and flushes the written data.
+    ///
+    /// Called automatically when the tracer is dropped.
+    pub(crate) fn finish(&mut self) {
+        if self.finished {
+            return;
+        }
+        if self.format == TraceFormat::Chrome {
+            self.write_raw("\n]\n");
+        }
+        self.writer.flush().expect("Failed to write event trace");
+        self.finished = true;
+    }
+
+    fn name(&self, id: Id) -> String {
+        self.names
+            .borrow()
+            .get(id as usize)
+            .cloned()
+            .unwrap_or_else(|| id.to_string())
+    }
+
+    fn json_record(&self, kind: &str, time: f64, event: &Event) -> Value {
+        let mut record = json!({
+            "kind": kind,
+            "time": time,
+            "id": event.id,
+            "src": self.name(event.src),
+            "dst": self.name(event.dst),
+            "type": type_name(&event.data).unwrap(),
+        });
+        if self.payloads {
+            record["data"] = json!(event.data);
+        }
+        record
+    }
+
+    fn name_thread(&mut self, id: Id) {
+        if self.named_threads.insert(id) {
+            let name = self.name(id);
+            self.write_record(json!({
+                "ph": "M",
+                "name": "thread_name",
+                "pid": 0,
+                "tid": id,
+                "args": {"name": name},
+            }));
+        }
+    }
+
+    fn write_record(&mut self, record: Value) {
+        if self.format == TraceFormat::Chrome && self.record_count > 0 {
+            self.write_raw(",\n");
+        }
+        serde_json::to_writer(&mut self.writer, &record).expect("Failed to write event trace");
+        if self.format == TraceFormat::JsonLines {
+            self.write_raw("\n");
+        }
+        self.record_count += 1;
+    }
+
+    fn write_raw(&mut self, s: &str) {
+        self.writer
+            .write_all(s.as_bytes())
+            .expect("Failed to write event trace");
+    }
+}
+
+impl Drop for EventTracer {
+    fn drop(&mut self) {
+        self.finish();
+    }
+}
@@ -0,0 +1,207 @@
+//! Simulation time units and formatting.
+//!
+//! The simulation time is represented as `f64` value. By convention, all DSLab modules measure the time
+//! in **seconds**, i.e. the simulation time `1.0` corresponds to one second of the modeled system. The helpers
+//! in this module convert the values in other units to seconds and back, which makes the units explicit
+//! in the model code and configs:
+//!
+//! ```rust
+//! use dslab_core::units::{hours, ms, secs, to_ms};
+//!
+//! assert_eq!(ms(1500.), secs(1.5));
+//! assert_eq!(hours(2.), 7200.);
+//! assert_eq!(to_ms(0.25), 250.);
+//! ```
+//!
+//! The time can be formatted in different styles via [`format_time()`]. The style used for the simulation time
+//! in log messages is configured via [`set_log_time_format()`].
+
+use std::cell::Cell;
+use std::fmt::{Display, Formatter};
+
+/// One nanosecond in simulation time units.
+pub const NANOSECOND: f64 = 1e-9;
+/// One microsecond in simulation time units.
+pub const MICROSECOND: f64 = 1e-6;
+/// One millisecond in simulation time units.
+pub const MILLISECOND: f64 = 1e-3;
+/// One second in simulation time units.
+pub const SECOND: f64 = 1.;
+/// One minute in simulation time units.
+pub const MINUTE: f64 = 60.;
+/// One hour in simulation time units.
+pub const HOUR: f64 = 3600.;
+/// One day in simulation time units.
+pub const DAY: f64 = 86400.;
+
+/// Converts nanoseconds to simulation time.
+pub fn ns(value: f64) -> f64 {
+    value * NANOSECOND
+}
+
+/// Converts microseconds to simulation time.
+pub fn us(value: f64) -> f64 {
+    value * MICROSECOND
+}
+
+/// Converts milliseconds to simulation time.
+pub fn ms(value: f64) -> f64 {
+    value * MILLISECOND
+}
+
+/// Converts seconds to simulation time.
+pub fn secs(value: f64) -> f64 {
+    value * SECOND
+}
+
+/// Converts minutes to simulation time.
+pub fn mins(value: f64) -> f64 {
+    value * MINUTE
+}
+
+/// Converts hours to simulation time.
+pub fn hours(value: f64) -> f64 {
+    value * HOUR
+}
+
+/// Converts days to simulation time.
+pub fn days(value: f64) -> f64 {
+    value * DAY
+}
+
+/// Converts simulation time to milliseconds.
+pub fn to_ms(time: f64) -> f64 {
+    time / MILLISECOND
+}
+
+/// Converts simulation time to seconds.
+pub fn to_secs(time: f64) -> f64 {
+    time / SECOND
+}
+
+/// Converts simulation time to minutes.
+pub fn to_mins(time: f64) -> f64 {
+    time / MINUTE
+}
+
+/// Converts simulation time to hours.
+pub fn to_hours(time: f64) -> f64 {
+    time / HOUR
+}
+
+/// Style of simulation time formatting.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TimeFormat {
+    /// Number of seconds with millisecond precision, e.g. `3723.500`.
+    #[default]
+    Seconds,
+    /// Clock time with millisecond precision, e.g. `01:02:03.500`. The hours are not wrapped at 24.
+    Clock,
+    /// Human-readable time with the largest suitable units, e.g. `1h 2m 3.500s`, `250.000ms`.
+    Human,
+}
+
+/// Formats the simulation time in the specified style.
+///
+/// # Examples
+///
+/// ```rust
+/// use dslab_core::units::{format_time, TimeFormat};
+///
+/// assert_eq!(format_time(3723.5, TimeFormat::Seconds), "3723.500");
+/// assert_eq!(format_time(3723.5, TimeFormat::Clock), "01:02:03.500");
+/// assert_eq!(format_time(3723.5, TimeFormat::Human), "1h 2m 3.500s");
+/// assert_eq!(format_time(0.25, TimeFormat::Human), "250.000ms");
+/// assert_eq!(format_time(90061., TimeFormat::Human), "1d 1h 1m 1.000s");
+/// ```
+pub fn format_time(time: f64, format: TimeFormat) -> String {
+    format_time_with_precision(time, format, 3)
+}
+
+fn format_time_with_precision(time: f64, format: TimeFormat, precision: usize) -> String {
+    if !time.is_finite() || time < 0. {
+        return format!("{:.*}", precision, time);
+    }
+    match format {
+        TimeFormat::Seconds => format!("{:.*}", precision, time),
+        TimeFormat::Clock => {
+            let hours = (time / HOUR).floor();
+            let minutes = ((time - hours * HOUR) / MINUTE).floor();
+            let seconds = time - hours * HOUR - minutes * MINUTE;
+            // width of seconds includes two digits, dot and fractional part
+            let width = if precision > 0 { precision + 3 } else { 2 };
+            format!(
+                "{:02}:{:02}:{:0width$.prec$}",
+                hours,
+                minutes,
+                seconds,
+                width = width,
+                prec = precision
+            )
+        }
+        TimeFormat::Human => {
+            if time == 0. {
+                return format!("{:.*}s", precision, 0.);
+            }
+            if time < MICROSECOND {
+                return format!("{:.*}ns", precision, time / NANOSECOND);
+            }
+            if time < MILLISECOND {
+                return format!("{:.*}us", precision, time / MICROSECOND);
+            }
+            if time < SECOND {
+                return format!("{:.*}ms", precision, time / MILLISECOND);
+            }
+            let mut result = String::new();
+            let mut rest = time;
+            for (unit, suffix) in [(DAY, "d"), (HOUR, "h"), (MINUTE, "m")] {
+                if rest >= unit || !result.is_empty() {
+                    let count = (rest / unit).floor();
+                    rest -= count * unit;
+                    result.push_str(&format!("{}{} ", count, suffix));
+                }
+            }
+            result.push_str(&format!("{:.*}s", precision, rest));
+            result
+        }
+    }
+}
+
+thread_local! {
+    static LOG_TIME_FORMAT: Cell<TimeFormat> = const { Cell::new(TimeFormat::Seconds) };
+}
+
+/// Sets the style of simulation time in log messages produced by the current thread.
+///
+/// The default style is [`TimeFormat::Seconds`].
+///
+/// # Examples
+///
+/// ```rust
+/// use dslab_core::units::{set_log_time_format, LogTime, TimeFormat};
+///
+/// set_log_time_format(TimeFormat::Clock);
+/// assert_eq!(format!("[{:.3}]", LogTime(3723.5)), "[01:02:03.500]");
+/// set_log_time_format(TimeFormat::Seconds);
+/// assert_eq!(format!("[{:.3}]", LogTime(3723.5)), "[3723.500]");
+/// ```
+pub fn set_log_time_format(format: TimeFormat) {
+    LOG_TIME_FORMAT.with(|f| f.set(format));
+}
+
+/// Returns the style of simulation time in log messages produced by the current thread.
+pub fn log_time_format() -> TimeFormat {
+    LOG_TIME_FORMAT.with(|f| f.get())
+}
+
+/// Simulation time displayed in the style configured via [`set_log_time_format()`], used in log messages.
+///
+/// The precision of formatting (e.g. `{:.3}`) sets the number of fractional digits of seconds.
+pub struct LogTime(pub f64);
+
+impl Display for LogTime {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let precision = f.precision().unwrap_or(3);
+        f.write_str(&format_time_with_precision(self.0, log_time_format(), precision))
+    }
+}
@@ -0,0 +1,118 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::thread;
+
+use serde::Serialize;
+
+use dslab_core::alloc::PoolAllocator;
+use dslab_core::{cast, Event, EventHandler, Simulation, SimulationContext};
+
+#[global_allocator]
+static ALLOCATOR: PoolAllocator = PoolAllocator;
+
+#[derive(Clone, Serialize)]
+struct Message {
+    payload: [u64; 4],
+}
+
+struct Process {
+    ctx: SimulationContext,
+    received: u64,
+}
+
+impl EventHandler for Process {
+    fn on(&mut self, event: Event) {
+        cast!(match event.data {
+            Message { payload } => {
+                assert_eq!(payload, [self.received; 4]);
+                self.received += 1;
+                if self.received < 10000 {
+                    let value = self.received;
+                    self.ctx.emit_self(Message { payload: [value; 4] }, 1.);
+                }
+            }
+        })
+    }
+}
+
+#[test]
+// Small and large blocks of different sizes keep their contents when the freed blocks are reused.
+fn test_allocations() {
+    let mut blocks: Vec<(u8, Box<[u8]>)> = Vec::new();
+    for round in 0..10 {
+        for size in 0..300 {
+            let value = (size + round) as u8;
+            blocks.push((value, vec![value; size].into_boxed_slice()));
+        }
+        for (value, block) in blocks.iter() {
+            assert!(block.iter().all(|x| x == value));
+        }
+        // free every other block to mix freed and live blocks
+        let mut i = 0;
+        blocks.retain(|_| {
+            i += 1;
+            i % 2 == 0
+        });
+    }
+    let mut v: Vec<u64> = Vec::new();
+    for i in 0..100000 {
+        v.push(i);
+    }
+    assert_eq!(v.iter().sum::<u64>(), 100000 * 99999 / 2);
+}
+
+#[test]
+// Blocks allocated in one thread can be freed in another thread.
+fn test_cross_thread_free() {
+    let handles: Vec<_> = (0..4)
+        .map(|t| {
+            let data: Vec<Box<u64>> = (0..10000).map(|i| Box::new(i * t)).collect();
+            thread::spawn(move || {
+                let sum: u64 = data.iter().map(|b| **b).sum();
+                drop(data);
+                let more: Vec<Box<u64>> = (0..10000).map(Box::new).collect();
+                (sum, more.len())
+            })
+        })
+        .collect();
+    for (t, handle) in handles.into_iter().enumerate() {
+        let (sum, len) = handle.join().unwrap();
+        assert_eq!(sum, t as u64 * 10000 * 9999 / 2);
+        assert_eq!(len, 10000);
+    }
+}
+
+#[test]
+// Pooled blocks are returned to the system allocator when the threads exit.
+fn test_thread_exit() {
+    for _ in 0..4 {
+        let handles: Vec<_> = (0..8)
+            .map(|t| {
+                thread::spawn(move || {
+                    let mut total = 0;
+                    for size in 1..=128 {
+                        let blocks: Vec<Vec<u8>> = (0..1000).map(|_| vec![t as u8; size]).collect();
+                        total += blocks.iter().map(|b| b.len()).sum::<usize>();
+                    }
+                    total
+                })
+            })
+            .collect();
+        for handle in handles {
+            assert_eq!(handle.join().unwrap(), 1000 * 128 * 129 / 2);
+        }
+    }
+}
+
+#[test]
+// Simulation works with the pool allocator.
+fn test_simulation() {
+    let mut sim = Simulation::new(123);
+    let ctx = sim.create_context("process");
+    ctx.emit_self(Message { payload: [0; 4] }, 1.);
+    let process = Rc::new(RefCell::new(Process { ctx, received: 0 }));
+    sim.add_handler("process", process.clone());
+    sim.step_until_no_events();
+    assert_eq!(process.borrow().received, 10000);
+    assert_eq!(sim.time(), 10000.);
+}
@@ -0,0 +1,87 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use serde::Serialize;
+
+use dslab_core::{cast, Event, EventHandler, Simulation};
+
+#[derive(Clone, Serialize)]
+struct TransferCompleted {
+    transfer_id: u32,
+}
+
+struct Counter {
+    received: Rc<RefCell<Vec<u32>>>,
+}
+
+impl EventHandler for Counter {
+    fn on(&mut self, event: Event) {
+        cast!(match event.data {
+            TransferCompleted { transfer_id } => {
+                self.received.borrow_mut().push(transfer_id);
+            }
+        })
+    }
+}
+
+#[test]
+// Each task receives the event matching its predicate, other events are passed to the event handler.
+fn test_recv_event_by() {
+    let mut sim = Simulation::new(123);
+    let ctx = Rc::new(sim.create_context("comp"));
+    let handled = Rc::new(RefCell::new(Vec::new()));
+    sim.add_handler(
+        "comp",
+        Rc::new(RefCell::new(Counter {
+            received: handled.clone(),
+        })),
+    );
+    let awaited = Rc::new(RefCell::new(Vec::new()));
+    for transfer_id in [2, 1] {
+        let ctx = ctx.clone();
+        let awaited = awaited.clone();
+        sim.spawn(async move {
+            let event = ctx
+                .recv_event_by::<TransferCompleted, _>(move |e| e.transfer_id == transfer_id)
+                .await;
+            awaited.borrow_mut().push((ctx.time(), event.data.transfer_id));
+        });
+    }
+    // tasks are started at the beginning of the next step before processing the events
+    for transfer_id in 0..4 {
+        ctx.emit_self(TransferCompleted { transfer_id }, transfer_id as f64);
+    }
+    sim.step_until_no_events();
+    assert_eq!(*awaited.borrow(), vec![(1., 1), (2., 2)]);
+    assert_eq!(*handled.borrow(), vec![0, 3]);
+}
+
+#[test]
+// Dropped futures do not intercept events, and timeouts of completed awaits do not fire.
+fn test_dropped_await() {
+    let mut sim = Simulation::new(123);
+    let ctx = Rc::new(sim.create_context("comp"));
+    let handled = Rc::new(RefCell::new(Vec::new()));
+    sim.add_handler(
+        "comp",
+        Rc::new(RefCell::new(Counter {
+            received: handled.clone(),
+        })),
+    );
+    let task_ctx = ctx.clone();
+    let result = Rc::new(RefCell::new(None));
+    let task_result = result.clone();
+    sim.spawn(async move {
+        drop(task_ctx.recv_event::<TransferCompleted>());
+        drop(task_ctx.sleep(5.));
+        let event = task_ctx.recv_event::<TransferCompleted>().with_timeout(10.).await;
+        *task_result.borrow_mut() = event.map(|e| (task_ctx.time(), e.data.transfer_id));
+    });
+    ctx.emit_self(TransferCompleted { transfer_id: 1 }, 1.);
+    ctx.emit_self(TransferCompleted { transfer_id: 2 }, 2.);
+    sim.step_until_no_events();
+    assert_eq!(*result.borrow(), Some((1., 1)));
+    assert_eq!(*handled.borrow(), vec![2]);
+    // the cancelled sleep and timeout timers are not processed
+    assert_eq!(sim.time(), 2.);
+}
@@ -0,0 +1,83 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use dslab_core::faults::{FaultInjected, FaultInjector, FaultKind, FaultRecovered, FaultScenario};
+use dslab_core::{cast, Event, EventHandler, Simulation};
+
+#[derive(Default)]
+struct Target {
+    injected: Vec<(f64, FaultKind)>,
+    recovered: Vec<f64>,
+}
+
+impl EventHandler for Target {
+    fn on(&mut self, event: Event) {
+        cast!(match event.data {
+            FaultInjected { kind, .. } => {
+                self.injected.push((event.time, kind));
+            }
+            FaultRecovered { .. } => {
+                self.recovered.push(event.time);
+            }
+        })
+    }
+}
+
+const SCENARIO: &str = r#"
+faults:
+  - target: host
+    fault:
+      type: Crash
+    trigger:
+      type: At
+      time: 1
+  - target: network
+    fault:
+      type: MessageDrop
+      probability: 0.5
+    trigger:
+      type: Poisson
+      rate: 1
+      start: 10
+      max_count: 5
+    duration: 0.1
+"#;
+
+#[test]
+fn test_scenario_from_yaml() {
+    let mut sim = Simulation::new(123);
+    let host = Rc::new(RefCell::new(Target::default()));
+    let network = Rc::new(RefCell::new(Target::default()));
+    sim.add_handler("host", host.clone());
+    sim.add_handler("network", network.clone());
+
+    let scenario = FaultScenario::from_yaml(SCENARIO).unwrap();
+    let injector = FaultInjector::install(&mut sim, &scenario).unwrap();
+    sim.step_until_no_events();
+
+    // crash-stop fault is never recovered
+    assert_eq!(host.borrow().injected, vec![(1., FaultKind::Crash)]);
+    assert!(host.borrow().recovered.is_empty());
+
+    let network = network.borrow();
+    assert_eq!(network.injected.len() + 1, injector.borrow().records().len());
+    assert!(network.injected.len() <= 5);
+    for (time, kind) in network.injected.iter() {
+        assert!(*time >= 10.);
+        assert_eq!(*kind, FaultKind::MessageDrop { probability: 0.5 });
+    }
+    assert_eq!(network.recovered.len(), network.injected.len());
+    for record in injector.borrow().records().iter().skip(1) {
+        assert_eq!(record.target, "network");
+        assert!((record.recovered_at.unwrap() - record.injected_at - 0.1).abs() < 1e-12);
+    }
+    assert_eq!(injector.borrow().active_fault_count(), 0);
+}
+
+#[test]
+fn test_unknown_target() {
+    let mut sim = Simulation::new(123);
+    let scenario = FaultScenario::from_yaml(SCENARIO).unwrap();
+    let result = FaultInjector::install(&mut sim, &scenario);
+    assert_eq!(result.err(), Some("Unknown fault target: host".to_string()));
+}
@@ -0,0 +1,124 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use serde::Serialize;
+
+use dslab_core::parallel::{DomainLink, DomainResult, ParallelSimulation};
+use dslab_core::{cast, Event, EventHandler, SimulationContext};
+
+const DOMAINS: usize = 4;
+const LOOKAHEAD: f64 = 0.5;
+
+#[derive(Clone, Serialize)]
+struct Token {
+    id: u64,
+}
+
+#[derive(Clone, Serialize)]
+struct LocalWork {}
+
+struct Node {
+    ctx: SimulationContext,
+    link: DomainLink,
+    next_domain: String,
+    log: Vec<(f64, String, u64)>,
+}
+
+impl EventHandler for Node {
+    fn on(&mut self, event: Event) {
+        cast!(match event.data {
+            Token { id } => {
+                self.log.push((self.ctx.time(), self.ctx.lookup_name(event.src), id));
+                let delay = LOOKAHEAD + self.ctx.gen_range(0.0..1.0);
+                self.link
+                    .emit(&self.ctx, Token { id: id + 1 }, &self.next_domain, "node", delay);
+                self.ctx.emit_self(LocalWork {}, self.ctx.gen_range(0.0..0.3));
+            }
+            LocalWork {} => {
+                self.log.push((self.ctx.time(), "local".to_string(), 0));
+            }
+        })
+    }
+}
+
+fn run_ring(seed: u64, until: f64) -> Vec<DomainResult<Vec<(f64, String, u64)>>> {
+    let mut sim = ParallelSimulation::new(seed, LOOKAHEAD);
+    for i in 0..DOMAINS {
+        let name = format!("domain{}", i);
+        let next_domain = format!("domain{}", (i + 1) % DOMAINS);
+        sim.add_domain(&name, move |sim, link| {
+            let ctx = sim.create_context("node");
+            // every domain starts its own token
+            link.emit(&ctx, Token { id: 0 }, &next_domain, "node", LOOKAHEAD);
+            let node = Rc::new(RefCell::new(Node {
+                ctx,
+                link,
+                next_domain,
+                log: Vec::new(),
+            }));
+            sim.add_handler("node", node.clone());
+            Box::new(move |_| node.borrow().log.clone())
+        });
+    }
+    sim.run_until_time(until)
+}
+
+#[test]
+// Remote events arrive at the expected domains and the results do not depend on the thread scheduling.
+fn test_ring() {
+    let results = run_ring(123, 20.);
+    assert_eq!(results.len(), DOMAINS);
+    for (i, result) in results.iter().enumerate() {
+        assert_eq!(result.name, format!("domain{}", i));
+        let tokens: Vec<_> = result.output.iter().filter(|(_, src, _)| src != "local").collect();
+        assert!(tokens.len() > 5);
+        let prev_domain = format!("domain{}::node", (i + DOMAINS - 1) % DOMAINS);
+        assert!(tokens.iter().all(|(_, src, _)| *src == prev_domain));
+        // events are processed in time order and no later than the end time
+        assert!(result.output.windows(2).all(|w| w[0].0 <= w[1].0));
+        assert!(result.output.iter().all(|(time, _, _)| *time <= 20.));
+        assert_eq!(result.processed_events, result.output.len() as u64);
+        assert_eq!(result.remote_events as usize, tokens.len() + 1);
+    }
+
+    for _ in 0..5 {
+        let other = run_ring(123, 20.);
+        for (a, b) in results.iter().zip(other.iter()) {
+            assert_eq!(a.output, b.output);
+        }
+    }
+}
+
+#[test]
+#[should_panic(expected = "less than lookahead")]
+// The panic in a domain is propagated to the caller.
+fn test_small_delay() {
+    let mut sim = ParallelSimulation::new(123, LOOKAHEAD);
+    for name in ["a", "b"] {
+        sim.add_domain(name, move |sim, link| {
+            let ctx = sim.create_context("node");
+            if name == "b" {
+                link.emit(&ctx, Token { id: 0 }, "a", "node", LOOKAHEAD / 2.);
+            }
+            Box::new(|_| ())
+        });
+    }
+    sim.run();
+}
+
+#[test]
+#[should_panic(expected = "less than lookahead")]
+// The delay slightly less than the lookahead is not accepted, since the event could be delivered into the past.
+fn test_delay_below_lookahead() {
+    let mut sim = ParallelSimulation::new(123, LOOKAHEAD);
+    for name in ["a", "b"] {
+        sim.add_domain(name, move |sim, link| {
+            let ctx = sim.create_context("node");
+            if name == "b" {
+                link.emit(&ctx, Token { id: 0 }, "a", "node", LOOKAHEAD - 1e-13);
+            }
+            Box::new(|_| ())
+        });
+    }
+    sim.run();
+}
@@ -0,0 +1,147 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use serde::Serialize;
+
+use dslab_core::{cast, Event, EventHandler, Simulation, SimulationContext};
+
+#[derive(Clone, Serialize)]
+struct Message {
+    expected_time: f64,
+}
+
+struct Process {
+    ctx: SimulationContext,
+    delivered: Rc<RefCell<Vec<(f64, u64)>>>,
+}
+
+impl EventHandler for Process {
+    fn on(&mut self, event: Event) {
+        cast!(match event.data {
+            Message { expected_time } => {
+                assert_eq!(self.ctx.time(), expected_time);
+                self.delivered.borrow_mut().push((event.time, event.id));
+                if self.ctx.rand() < 0.9 {
+                    // many events share the same time to check ordering by id
+                    let delay = [0., 0., 0.5, self.ctx.gen_range(0.0..10.0)][self.ctx.gen_range(0..4)];
+                    let expected_time = self.ctx.time() + delay;
+                    let event_id = self.ctx.emit(Message { expected_time }, event.src, delay);
+                    if self.ctx.rand() < 0.1 {
+                        self.ctx.cancel_event(event_id);
+                    }
+                }
+            }
+        })
+    }
+}
+
+fn assert_ordered(delivered: &[(f64, u64)]) {
+    for pair in delivered.windows(2) {
+        assert!(pair[0] < pair[1], "events are delivered out of order: {:?}", pair);
+    }
+}
+
+#[test]
+// Events are delivered in the order of their time and id, cancelled events are not delivered.
+fn test_event_order() {
+    let mut sim = Simulation::new(123);
+    let delivered = Rc::new(RefCell::new(Vec::new()));
+    let mut contexts = Vec::new();
+    for i in 0..10 {
+        let name = format!("process-{}", i);
+        let ctx = sim.create_context(&name);
+        contexts.push(sim.create_context(&name));
+        let process = Rc::new(RefCell::new(Process {
+            ctx,
+            delivered: delivered.clone(),
+        }));
+        sim.add_handler(&name, process);
+    }
+    let mut cancelled = 0;
+    for i in 0..10000 {
+        let ctx = &contexts[i % 10];
+        let delay = sim.gen_range(0..100) as f64 / 10.;
+        let event_id = ctx.emit(Message { expected_time: delay }, contexts[(i + 1) % 10].id(), delay);
+        if i % 7 == 0 {
+            ctx.cancel_event(event_id);
+            cancelled += 1;
+        }
+    }
+    sim.step_until_no_events();
+    let delivered = delivered.borrow();
+    assert_ordered(&delivered);
+    assert!(delivered.len() >= 10000 - cancelled);
+    assert!(delivered.iter().all(|(_, id)| *id % 7 != 0 || *id >= 10000));
+}
+
+#[test]
+// Events emitted after the simulation time is set explicitly are delivered in the correct order,
+// including events with time less than the time of already peeked pending events.
+fn test_event_order_with_explicit_time() {
+    let mut sim = Simulation::new(123);
+    let delivered = Rc::new(RefCell::new(Vec::new()));
+    let ctx = sim.create_context("process");
+    let process = Rc::new(RefCell::new(Process {
+        ctx: sim.create_context("process"),
+        delivered: delivered.clone(),
+    }));
+    sim.add_handler("process", process);
+    for i in 0..100 {
+        ctx.emit_self(
+            Message {
+                expected_time: 1000. + i as f64,
+            },
+            1000. + i as f64,
+        );
+    }
+    for step in 0..100 {
+        let time = step as f64 * 3.;
+        sim.step_until_time(time);
+        for _ in 0..10 {
+            let delay = sim.gen_range(0.0..5.0);
+            ctx.emit_self(
+                Message {
+                    expected_time: time + delay,
+                },
+                delay,
+            );
+        }
+    }
+    sim.step_until_no_events();
+    let delivered = delivered.borrow();
+    assert_ordered(&delivered);
+    assert!(delivered.len() >= 1100);
+}
+
+struct Recorder {
+    delivered: Rc<RefCell<Vec<u64>>>,
+}
+
+impl EventHandler for Recorder {
+    fn on(&mut self, event: Event) {
+        self.delivered.borrow_mut().push(event.id);
+    }
+}
+
+#[test]
+// Event priorities are respected among simultaneous ordered and unordered events,
+// while the events with the same priority are delivered in the order of their ids.
+fn test_event_priority_with_ordered_events() {
+    let mut sim = Simulation::new(123);
+    let delivered = Rc::new(RefCell::new(Vec::new()));
+    let recorder_id = sim.add_handler(
+        "recorder",
+        Rc::new(RefCell::new(Recorder {
+            delivered: delivered.clone(),
+        })),
+    );
+    let ctx = sim.create_context("sender");
+    let expected_time = 1.;
+    let ordered1 = ctx.emit_ordered(Message { expected_time }, recorder_id, 1.);
+    let regular = ctx.emit(Message { expected_time }, recorder_id, 1.);
+    let low = ctx.emit_with_priority(Message { expected_time }, recorder_id, 1., 1);
+    let ordered2 = ctx.emit_ordered(Message { expected_time }, recorder_id, 1.);
+    let high = ctx.emit_with_priority(Message { expected_time }, recorder_id, 1., -1);
+    sim.step_until_no_events();
+    assert_eq!(*delivered.borrow(), vec![high, ordered1, regular, ordered2, low]);
+}
@@ -0,0 +1,99 @@
+use dslab_core::replication::{Metrics, Replications};
+use dslab_core::statistics::{
+    confidence_interval, mser5_truncation, paired_t_test, student_t_quantile, student_t_two_sided_p, truncate_by_time,
+    truncate_warmup, welch_t_test,
+};
+
+fn assert_close(actual: f64, expected: f64, eps: f64) {
+    assert!(
+        (actual - expected).abs() < eps,
+        "actual value {} differs from expected {}",
+        actual,
+        expected
+    );
+}
+
+#[test]
+fn test_student_t() {
+    assert_close(student_t_quantile(0.95, 1.), 12.706, 1e-3);
+    assert_close(student_t_quantile(0.95, 10.), 2.228, 1e-3);
+    assert_close(student_t_quantile(0.99, 5.), 4.032, 1e-3);
+    assert_close(student_t_quantile(0.95, 1000.), 1.962, 1e-3);
+    assert_close(student_t_two_sided_p(2.228, 10.), 0.05, 1e-4);
+    assert_close(student_t_two_sided_p(0., 3.), 1., 1e-12);
+}
+
+#[test]
+fn test_confidence_interval() {
+    // mean = 3, standard error = sqrt(2.5 / 5), t = 2.776 for 4 degrees of freedom
+    let ci = confidence_interval(&[1., 2., 3., 4., 5.], 0.95);
+    assert_eq!(ci.mean, 3.);
+    assert_close(ci.half_width, 1.963, 1e-3);
+    assert!(ci.contains(1.5) && !ci.contains(1.));
+    assert_close(ci.relative_half_width(), 0.654, 1e-3);
+    assert!(confidence_interval(&[1., 2., 3., 4., 5.], 0.99).half_width > ci.half_width);
+    assert_eq!(confidence_interval(&[7.], 0.95).half_width, 0.);
+}
+
+#[test]
+// The first 20 observations belong to the transient period with high values.
+fn test_warmup_truncation() {
+    let mut series = vec![10.; 20];
+    series.extend((0..100).map(|i| if i % 2 == 0 { 1. } else { -1. }));
+    assert_eq!(mser5_truncation(&series), 20);
+    assert_eq!(truncate_warmup(&series).len(), 100);
+    assert_eq!(mser5_truncation(&[1.; 50]), 0);
+    assert_eq!(mser5_truncation(&[1., 2., 3.]), 0);
+
+    let samples = [(0., 5.), (5., 4.), (10., 1.), (15., 2.)];
+    assert_eq!(truncate_by_time(&samples, 10.), vec![1., 2.]);
+}
+
+#[test]
+fn test_significance() {
+    // the difference of means is -5 with standard error 1 and 8 degrees of freedom
+    let result = welch_t_test(&[1., 2., 3., 4., 5.], &[6., 7., 8., 9., 10.]);
+    assert_eq!(result.mean_difference, -5.);
+    assert_close(result.statistic, -5., 1e-12);
+    assert_close(result.df, 8., 1e-12);
+    assert_close(result.p_value, 0.00105, 1e-5);
+    assert!(result.is_significant(0.01));
+
+    let result = welch_t_test(&[1., 2., 3., 4., 5.], &[2., 1., 4., 3., 5.]);
+    assert_eq!(result.p_value, 1.);
+
+    // small but consistent difference is detected only by paired test
+    let a = [10., 12., 14., 16.];
+    let b = [9.9, 11.8, 13.9, 15.8];
+    assert!(!welch_t_test(&a, &b).is_significant(0.05));
+    let paired = paired_t_test(&a, &b);
+    assert_close(paired.mean_difference, 0.15, 1e-12);
+    assert_close(paired.p_value, 0.0138, 1e-4);
+    assert!(paired.is_significant(0.05));
+
+    assert_eq!(paired_t_test(&[1., 2.], &[0., 1.]).p_value, 0.);
+}
+
+#[test]
+fn test_compare_replications() {
+    let baseline = |seed: u64| Metrics::from([("latency".to_string(), seed as f64)]);
+    let improved = |seed: u64| Metrics::from([("latency".to_string(), seed as f64 - 0.5 - 0.1 * (seed % 2) as f64)]);
+
+    let results = Replications::new(6, 1).run(baseline);
+    let summary = results.summary("latency").unwrap();
+    assert_close(summary.confidence_interval(0.95).half_width, summary.ci95, 1e-12);
+    assert_close(summary.ci95, 1.964, 1e-3);
+
+    // common random numbers make the difference significant
+    let same_seeds = Replications::new(6, 1).run(improved);
+    let result = results.compare(&same_seeds, "latency").unwrap();
+    assert_close(result.mean_difference, 0.55, 1e-12);
+    assert!(result.is_significant(0.001));
+
+    let other_seeds = Replications::new(6, 101).run(move |seed| improved(seed - 100));
+    let result = results.compare(&other_seeds, "latency").unwrap();
+    assert_close(result.mean_difference, 0.55, 1e-12);
+    assert!(!result.is_significant(0.05));
+
+    assert!(results.compare(&same_seeds, "throughput").is_none());
+}
@@ -1,9 +1,11 @@
 //! Simulation configuration and execution.
 
 use std::cell::RefCell;
+use std::collections::HashMap;
 use std::rc::Rc;
 
 use dslab_compute::multicore::{Compute, CoresDependency};
+use dslab_compute::performance::PerformanceModel;
 use dslab_core::simulation::Simulation;
 
 use crate::dag::DAG;
@@ -17,6 +19,7 @@ use crate::task::ResourceRestriction;
 pub struct DagSimulation {
     pub sim: Simulation,
     resource_configs: Vec<ResourceConfig>,
+    performance_models: HashMap<String, PerformanceModel>,
     network_config: NetworkConfig,
     scheduler: Rc<RefCell<dyn Scheduler>>,
     config: Config,
@@ -34,6 +37,7 @@ impl DagSimulation {
         DagSimulation {
             sim: Simulation::new(seed),
             resource_configs: resources,
+            performance_models: HashMap::new(),
             network_config,
             scheduler,
             config,
@@ -50,6 +54,14 @@ impl DagSimulation {
         });
     }
 
+    /// Sets the performance model of resource with provided name.
+    ///
+    /// The model affects only the actual task execution times, while the schedulers observe the nominal resource
+    /// speed, which allows to evaluate the scheduling algorithms under heterogeneous and variable performance.
+    pub fn set_performance_model(&mut self, resource: &str, model: PerformanceModel) {
+        self.performance_models.insert(resource.to_string(), model);
+    }
+
     /// Initializes DAG simulation.
     pub fn init(&mut self, mut dag: DAG) -> Rc<RefCell<DAGRunner>> {
         let net_ctx = self.sim.create_context("net");
@@ -68,6 +80,9 @@ impl DagSimulation {
                     r.memory,
                     self.sim.create_context(&r.name),
                 )));
+                if let Some(model) = self.performance_models.get(&r.name) {
+                    compute.borrow_mut().set_performance_model(model.clone());
+                }
                 let id = self.sim.add_handler(&r.name, compute.clone());
                 Resource {
                     id,
@@ -15,6 +15,7 @@ use crate::runner::Config;
 use crate::system::System;
 use crate::task::TaskState;
 
+use crate::schedulers::cpop::CpopScheduler;
 use crate::schedulers::dls::DlsScheduler;
 use crate::schedulers::dynamic_list::DynamicListScheduler;
 use crate::schedulers::heft::HeftScheduler;
@@ -165,6 +166,7 @@ pub fn default_scheduler_resolver(params: &SchedulerParams) -> Option<RcSchedule
         "Simple" => Some(Rc::new(RefCell::new(SimpleScheduler::new()))),
         "DynamicList" => Some(Rc::new(RefCell::new(DynamicListScheduler::from_params(params)))),
         "HEFT" => Some(Rc::new(RefCell::new(HeftScheduler::from_params(params)))),
+        "CPOP" => Some(Rc::new(RefCell::new(CpopScheduler::from_params(params)))),
         "Lookahead" => Some(Rc::new(RefCell::new(LookaheadScheduler::from_params(params)))),
         "PEFT" => Some(Rc::new(RefCell::new(PeftScheduler::from_params(params)))),
         "DLS" => Some(Rc::new(RefCell::new(DlsScheduler::from_params(params)))),
@@ -258,6 +258,15 @@ pub fn task_successors(v: usize, dag: &DAG) -> Vec<(usize, f64)> {
     result
 }
 
+pub fn task_predecessors(v: usize, dag: &DAG) -> Vec<(usize, f64)> {
+    dag.get_task(v)
+        .inputs
+        .iter()
+        .map(|&data_item_id| dag.get_data_item(data_item_id))
+        .filter_map(|data_item| data_item.producer.map(|producer| (producer, data_item.size)))
+        .collect()
+}
+
 fn calc_rank(v: usize, avg_flop_time: f64, avg_net_time: f64, dag: &DAG, ranks: &mut Vec<f64>, used: &mut Vec<bool>) {
     if used[v] {
         return;
@@ -0,0 +1,251 @@
+use std::collections::{BTreeSet, HashMap};
+
+use dslab_core::context::SimulationContext;
+use dslab_core::log_warn;
+use dslab_core::Id;
+
+use crate::dag::DAG;
+use crate::data_item::{DataTransferMode, DataTransferStrategy};
+use crate::runner::Config;
+use crate::scheduler::{Action, Scheduler, SchedulerParams, TimeSpan};
+use crate::schedulers::common::*;
+use crate::schedulers::treap::Treap;
+use crate::system::System;
+
+/// Critical-Path-on-a-Processor (CPOP) scheduler by Topcuoglu et al.
+///
+/// Tasks are prioritized by the sum of upward and downward ranks. The tasks on the critical path
+/// (the ones with maximal priority) are assigned to a single resource minimizing the critical path length,
+/// while the rest of the tasks are assigned to the resources minimizing their finish time.
+pub struct CpopScheduler {
+    data_transfer_strategy: DataTransferStrategy,
+}
+
+impl CpopScheduler {
+    pub fn new() -> Self {
+        Self {
+            data_transfer_strategy: DataTransferStrategy::Eager,
+        }
+    }
+
+    pub fn from_params(params: &SchedulerParams) -> Self {
+        Self {
+            data_transfer_strategy: params
+                .get("data_transfer_strategy")
+                .unwrap_or(DataTransferStrategy::Eager),
+        }
+    }
+
+    pub fn with_data_transfer_strategy(mut self, data_transfer_strategy: DataTransferStrategy) -> Self {
+        self.data_transfer_strategy = data_transfer_strategy;
+        self
+    }
+
+    fn schedule(&self, dag: &DAG, system: System, config: Config, ctx: &SimulationContext) -> Vec<Action> {
+        let resources = system.resources;
+        let network = system.network;
+
+        let avg_flop_time = system.avg_flop_time();
+        let avg_net_time = system.avg_net_time(ctx.id(), &config.data_transfer_mode);
+
+        let task_count = dag.get_tasks().len();
+
+        let upward_ranks = calc_ranks(avg_flop_time, avg_net_time, dag);
+        let mut downward_ranks = vec![0.; task_count];
+        for task_id in topsort(dag) {
+            for (succ, weight) in task_successors(task_id, dag) {
+                downward_ranks[succ] = f64::max(
+                    downward_ranks[succ],
+                    downward_ranks[task_id] + dag.get_task(task_id).flops * avg_flop_time + weight * avg_net_time,
+                );
+            }
+        }
+        let priorities = (0..task_count)
+            .map(|task_id| upward_ranks[task_id] + downward_ranks[task_id])
+            .collect::<Vec<_>>();
+
+        // critical path starts from the entry task with maximal priority and follows the successors
+        // with the same priority
+        let mut critical_path = vec![false; task_count];
+        let entry = (0..task_count)
+            .filter(|&task_id| task_predecessors(task_id, dag).is_empty())
+            .max_by(|&a, &b| priorities[a].total_cmp(&priorities[b]).then(b.cmp(&a)));
+        if let Some(entry) = entry {
+            let cp_length = priorities[entry];
+            let eps = cp_length.abs() * 1e-9;
+            let mut task_id = entry;
+            loop {
+                critical_path[task_id] = true;
+                let next = task_successors(task_id, dag)
+                    .into_iter()
+                    .map(|(succ, _)| succ)
+                    .filter(|&succ| (priorities[succ] - cp_length).abs() <= eps)
+                    .min();
+                match next {
+                    Some(next) => task_id = next,
+                    None => break,
+                }
+            }
+        }
+
+        // resource which can execute all critical path tasks and minimizes the total execution time of these tasks
+        let critical_resource = (0..resources.len())
+            .filter(|&resource| {
+                (0..task_count)
+                    .filter(|&task_id| critical_path[task_id])
+                    .all(|task_id| {
+                        let task = dag.get_task(task_id);
+                        resources[resource].compute.borrow().cores_total() >= task.min_cores
+                            && resources[resource].compute.borrow().memory_total() >= task.memory
+                            && task.is_allowed_on(resource)
+                    })
+            })
+            .map(|resource| {
+                let time = (0..task_count)
+                    .filter(|&task_id| critical_path[task_id])
+                    .map(|task_id| {
+                        let task = dag.get_task(task_id);
+                        task.flops / resources[resource].speed / task.cores_dependency.speedup(task.min_cores)
+                    })
+                    .sum::<f64>();
+                (resource, time)
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(resource, _)| resource);
+        if critical_resource.is_none() {
+            log_warn!(
+                ctx,
+                "no resource can execute all critical path tasks, CPOP will schedule them as other tasks"
+            );
+        }
+
+        let mut task_finish_times = vec![0.; task_count];
+        let mut scheduled_tasks: Vec<Vec<BTreeSet<ScheduledTask>>> = resources
+            .iter()
+            .map(|resource| (0..resource.cores_available).map(|_| BTreeSet::new()).collect())
+            .collect();
+        let mut memory_usage: Vec<Treap> = (0..resources.len()).map(|_| Treap::new()).collect();
+        let mut data_locations: HashMap<usize, Id> = HashMap::new();
+        let mut task_locations: HashMap<usize, Id> = HashMap::new();
+
+        let mut result: Vec<(f64, Action)> = Vec::new();
+
+        let mut pending_predecessors = (0..task_count)
+            .map(|task_id| task_predecessors(task_id, dag).len())
+            .collect::<Vec<_>>();
+        let mut ready_tasks = (0..task_count)
+            .filter(|&task_id| pending_predecessors[task_id] == 0)
+            .collect::<Vec<_>>();
+
+        for _ in 0..task_count {
+            // ready task with maximal priority
+            let (pos, &task_id) = ready_tasks
+                .iter()
+                .enumerate()
+                .max_by(|(_, &a), (_, &b)| priorities[a].total_cmp(&priorities[b]).then(b.cmp(&a)))
+                .unwrap();
+            ready_tasks.swap_remove(pos);
+            for (succ, _) in task_successors(task_id, dag) {
+                pending_predecessors[succ] -= 1;
+                if pending_predecessors[succ] == 0 {
+                    ready_tasks.push(succ);
+                }
+            }
+
+            let candidates = match critical_resource {
+                Some(resource) if critical_path[task_id] => vec![resource],
+                _ => (0..resources.len()).collect(),
+            };
+            let mut best_finish = -1.;
+            let mut best_start = -1.;
+            let mut best_resource = 0;
+            let mut best_cores: Vec<u32> = Vec::new();
+            for resource in candidates {
+                let res = evaluate_assignment(
+                    task_id,
+                    resource,
+                    &task_finish_times,
+                    &scheduled_tasks,
+                    &memory_usage,
+                    &data_locations,
+                    &task_locations,
+                    &self.data_transfer_strategy,
+                    dag,
+                    resources,
+                    network,
+                    &config,
+                    ctx,
+                );
+                if res.is_none() {
+                    continue;
+                }
+                let (start_time, finish_time, cores) = res.unwrap();
+
+                if best_finish == -1. || best_finish > finish_time {
+                    best_start = start_time;
+                    best_finish = finish_time;
+                    best_resource = resource;
+                    best_cores = cores;
+                }
+            }
+
+            assert_ne!(best_finish, -1.);
+
+            task_finish_times[task_id] = best_finish;
+            for &core in best_cores.iter() {
+                scheduled_tasks[best_resource][core as usize].insert(ScheduledTask::new(
+                    best_start,
+                    best_finish,
+                    task_id,
+                ));
+            }
+            memory_usage[best_resource].add(best_start, best_finish, dag.get_task(task_id).memory);
+            for &output in dag.get_task(task_id).outputs.iter() {
+                data_locations.insert(output, resources[best_resource].id);
+            }
+            task_locations.insert(task_id, resources[best_resource].id);
+
+            result.push((
+                best_start,
+                Action::ScheduleTaskOnCores {
+                    task: task_id,
+                    resource: best_resource,
+                    cores: best_cores,
+                    expected_span: Some(TimeSpan::new(best_start, best_finish)),
+                },
+            ));
+        }
+
+        result.sort_by(|a, b| a.0.total_cmp(&b.0));
+        result.into_iter().map(|(_, b)| b).collect()
+    }
+}
+
+impl Scheduler for CpopScheduler {
+    fn start(&mut self, dag: &DAG, system: System, config: Config, ctx: &SimulationContext) -> Vec<Action> {
+        assert_ne!(
+            config.data_transfer_mode,
+            DataTransferMode::Manual,
+            "CpopScheduler doesn't support DataTransferMode::Manual"
+        );
+
+        if dag.get_tasks().iter().any(|task| task.min_cores != task.max_cores) {
+            log_warn!(
+                ctx,
+                "some tasks support different number of cores, but CPOP will always use min_cores"
+            );
+        }
+
+        self.schedule(dag, system, config, ctx)
+    }
+
+    fn is_static(&self) -> bool {
+        true
+    }
+}
+
+impl Default for CpopScheduler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,6 +1,7 @@
 //! Scheduler implementations.
 
 pub mod common;
+pub mod cpop;
 pub mod dls;
 pub mod dynamic_list;
 pub mod heft;
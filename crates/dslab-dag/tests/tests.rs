@@ -5,6 +5,7 @@ use rand::prelude::*;
 use rand_pcg::Pcg64;
 
 use dslab_compute::multicore::CoresDependency;
+use dslab_compute::performance::{PerformanceModel, SlowdownModel};
 use dslab_core::EPSILON;
 
 use dslab_dag::dag::DAG;
@@ -14,11 +15,13 @@ use dslab_dag::network::NetworkConfig;
 use dslab_dag::resource::ResourceConfig;
 use dslab_dag::runner::Config;
 use dslab_dag::scheduler::Scheduler;
+use dslab_dag::schedulers::cpop::CpopScheduler;
 use dslab_dag::schedulers::dls::DlsScheduler;
 use dslab_dag::schedulers::heft::HeftScheduler;
 use dslab_dag::schedulers::lookahead::LookaheadScheduler;
 use dslab_dag::schedulers::peft::PeftScheduler;
 use dslab_dag::schedulers::simple_scheduler::SimpleScheduler;
+use dslab_models::queueing::TimeDistribution;
 
 const PRECISION: f64 = 1. / ((1 << 20) as f64);
 
@@ -194,6 +197,29 @@ fn test_3() {
     assert_float_eq(result, 104.166267395019531, EPSILON);
 }
 
+#[test]
+fn test_cpop() {
+    let mut rng = Pcg64::seed_from_u64(1);
+    let dag = gen_dag(&mut rng, 1000, 5000);
+
+    let mut sim = DagSimulation::new(
+        123,
+        gen_resources(&mut rng, 10, true),
+        gen_network(&mut rng),
+        Rc::new(RefCell::new(CpopScheduler::new())),
+        Config {
+            data_transfer_mode: DataTransferMode::Direct,
+        },
+    );
+    let runner = sim.init(dag);
+    sim.step_until_no_events();
+    assert!(runner.borrow().is_completed());
+
+    assert!(sim.time() >= runner.borrow().makespan_lower_bound());
+    let result = (sim.time() / PRECISION).round() * PRECISION;
+    assert_float_eq(result, 39.78235149383545, EPSILON);
+}
+
 #[test]
 fn test_4() {
     let mut dag = DAG::new();
@@ -266,6 +292,9 @@ fn test_4() {
     let peft_makespan = run_scheduler(PeftScheduler::new().with_original_network_estimation(), dag.clone());
     assert_float_eq(peft_makespan, 95.0, EPSILON);
 
+    let cpop_makespan = run_scheduler(CpopScheduler::new(), dag.clone());
+    assert_float_eq(cpop_makespan, 113.0, EPSILON);
+
     let simple_makespan = run_scheduler(SimpleScheduler::new(), dag);
     assert_float_eq(simple_makespan, 256.0, EPSILON);
 }
@@ -394,3 +423,72 @@ fn test_fork_join() {
     let result = sim.time();
     assert_float_eq(result, correct_result, EPSILON);
 }
+
+fn run_fork_join_with_performance(models: Vec<(usize, PerformanceModel)>, seed: u64) -> f64 {
+    let mut dag = DAG::new();
+    let root = dag.add_task("root", 10., 32, 1, 1, CoresDependency::Linear);
+    let end = dag.add_task("end", 10., 32, 1, 1, CoresDependency::Linear);
+    for i in 0..5 {
+        let data_id = dag.add_task_output(root, &i.to_string(), 100.);
+        let task_id = dag.add_task(&i.to_string(), 50., 32, 1, 1, CoresDependency::Linear);
+        dag.add_data_dependency(data_id, task_id);
+        let data_id = dag.add_task_output(task_id, &(i.to_string() + "_"), 200.);
+        dag.add_data_dependency(data_id, end);
+    }
+
+    let mut sim = DagSimulation::new(
+        seed,
+        Vec::new(),
+        NetworkConfig::constant(10., 0.1 * 1e6),
+        Rc::new(RefCell::new(SimpleScheduler::new())),
+        Config {
+            data_transfer_mode: DataTransferMode::Direct,
+        },
+    );
+    for i in 0..5 {
+        sim.add_resource(&i.to_string(), 5., 1, 1024);
+    }
+    for (resource, model) in models {
+        sim.set_performance_model(&resource.to_string(), model);
+    }
+    let runner = sim.init(dag);
+    sim.step_until_no_events();
+    assert!(runner.borrow().is_completed());
+    sim.time()
+}
+
+#[test]
+fn test_performance_model() {
+    let network_time = 100. / 10. + 0.1 + 200. / 10. + 0.1;
+    let nominal = run_fork_join_with_performance(Vec::new(), 123);
+    assert_float_eq(nominal, (10. + 50. + 10.) / 5. + network_time, EPSILON);
+
+    // the slowest resource determines the makespan of fork-join
+    let heterogeneous = run_fork_join_with_performance(
+        vec![(2, PerformanceModel::new(0.5)), (3, PerformanceModel::new(2.))],
+        123,
+    );
+    assert_float_eq(heterogeneous, nominal + 50. / 5., EPSILON);
+
+    // every task is slowed down 3 times
+    let slowdown = SlowdownModel::new(1., TimeDistribution::Constant(3.));
+    let models = (0..5)
+        .map(|i| (i, PerformanceModel::default().with_slowdown(slowdown.clone())))
+        .collect();
+    let slowed_down = run_fork_join_with_performance(models, 123);
+    assert_float_eq(slowed_down, nominal + 2. * 70. / 5., EPSILON);
+
+    // rare heavy-tailed stragglers increase makespan in some runs only
+    let slowdown = SlowdownModel::new(0.1, TimeDistribution::Pareto { scale: 2., shape: 1.5 });
+    let makespans: Vec<f64> = (0..20)
+        .map(|seed| {
+            let models = (0..5)
+                .map(|i| (i, PerformanceModel::default().with_slowdown(slowdown.clone())))
+                .collect();
+            run_fork_join_with_performance(models, seed)
+        })
+        .collect();
+    assert!(makespans.iter().all(|m| *m >= nominal - EPSILON));
+    assert!(makespans.iter().any(|m| (*m - nominal).abs() < EPSILON));
+    assert!(makespans.iter().any(|m| *m > nominal + 10.));
+}
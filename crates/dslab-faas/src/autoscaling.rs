@@ -0,0 +1,122 @@
+//! Autoscaling policies that adjust the number of instances (containers) of each application.
+use std::boxed::Box;
+use std::collections::HashMap;
+
+use crate::config::parse_options;
+use crate::function::Application;
+
+/// State of application instances observed by the controller when making a scaling decision.
+#[derive(Clone, Copy, Default, Debug, PartialEq)]
+pub struct ScalingState {
+    /// Number of application containers, including the ones being deployed.
+    pub instances: usize,
+    /// Number of idle application containers.
+    pub idle_instances: usize,
+    /// Number of invocations running on application containers or waiting for them to be deployed.
+    pub active_invocations: usize,
+    /// Number of application invocations arrived since the previous scaling decision.
+    pub arrivals: u64,
+}
+
+/// A policy that periodically chooses the desired number of instances of each application.
+///
+/// If there are less instances than desired, the controller deploys new containers on the hosts chosen by
+/// [`crate::deployer::IdleDeployer`]. If there are more instances than desired, the controller terminates idle
+/// containers, the running containers are never terminated. The decisions are made every [`Self::interval`]
+/// time units while there are active or arriving invocations. Note that the idle containers are still subject
+/// to [`crate::coldstart::ColdStartPolicy`] keepalive decisions.
+pub trait AutoscalingPolicy {
+    /// Returns the interval between scaling decisions. Infinite interval disables autoscaling.
+    fn interval(&self) -> f64;
+    /// Returns the desired number of application instances or `None` to leave the instances unchanged.
+    fn desired_instances(&mut self, app: &Application, state: &ScalingState, time: f64) -> Option<usize>;
+
+    /// Returns a string with policy description.
+    fn to_string(&self) -> String {
+        "STUB AUTOSCALING POLICY NAME".to_string()
+    }
+}
+
+/// Disables autoscaling, so the containers are deployed only on demand and by prewarming.
+pub struct NoAutoscaling {}
+
+impl AutoscalingPolicy for NoAutoscaling {
+    fn interval(&self) -> f64 {
+        f64::INFINITY
+    }
+
+    fn desired_instances(&mut self, _app: &Application, _state: &ScalingState, _time: f64) -> Option<usize> {
+        None
+    }
+
+    fn to_string(&self) -> String {
+        "NoAutoscaling".to_string()
+    }
+}
+
+/// Scales the instances of each application proportionally to the number of its active invocations,
+/// so that each instance serves `target` invocations on average (similar to concurrency-based autoscaling in Knative).
+/// The number of instances is kept within `[min_instances, max_instances]`, non-zero `min_instances` keeps
+/// the application warm.
+pub struct ConcurrencyAutoscaler {
+    interval: f64,
+    target: f64,
+    min_instances: usize,
+    max_instances: usize,
+}
+
+impl ConcurrencyAutoscaler {
+    /// Creates new ConcurrencyAutoscaler.
+    pub fn new(interval: f64, target: f64, min_instances: usize, max_instances: usize) -> Self {
+        assert!(target > 0., "Target concurrency must be positive");
+        assert!(min_instances <= max_instances);
+        Self {
+            interval,
+            target,
+            min_instances,
+            max_instances,
+        }
+    }
+
+    /// Creates policy from a map of strings containing policy parameters.
+    pub fn from_options_map(options: &HashMap<String, String>) -> Self {
+        let interval = options.get("interval").unwrap().parse::<f64>().unwrap();
+        let target = options.get("target").unwrap().parse::<f64>().unwrap();
+        let min_instances = options.get("min").map(|x| x.parse::<usize>().unwrap()).unwrap_or(0);
+        let max_instances = options
+            .get("max")
+            .map(|x| x.parse::<usize>().unwrap())
+            .unwrap_or(usize::MAX);
+        Self::new(interval, target, min_instances, max_instances)
+    }
+}
+
+impl AutoscalingPolicy for ConcurrencyAutoscaler {
+    fn interval(&self) -> f64 {
+        self.interval
+    }
+
+    fn desired_instances(&mut self, _app: &Application, state: &ScalingState, _time: f64) -> Option<usize> {
+        let desired = (state.active_invocations as f64 / self.target).ceil() as usize;
+        Some(desired.clamp(self.min_instances, self.max_instances))
+    }
+
+    fn to_string(&self) -> String {
+        format!(
+            "ConcurrencyAutoscaler[interval={:.2},target={:.2},min={},max={}]",
+            self.interval, self.target, self.min_instances, self.max_instances
+        )
+    }
+}
+
+/// Creates [`AutoscalingPolicy`] from a string containing its name and parameters.
+pub fn default_autoscaling_policy_resolver(s: &str) -> Box<dyn AutoscalingPolicy> {
+    if s.len() >= 23 && &s[0..22] == "ConcurrencyAutoscaler[" && s.ends_with(']') {
+        let opts = parse_options(&s[22..s.len() - 1]);
+        return Box::new(ConcurrencyAutoscaler::from_options_map(&opts));
+    }
+    if s == "NoAutoscaling" {
+        return Box::new(NoAutoscaling {});
+    }
+    panic!("Can't resolve: {}", s);
+}
@@ -8,6 +8,7 @@ use std::path::Path;
 
 use serde::{Deserialize, Serialize};
 
+use crate::autoscaling::{default_autoscaling_policy_resolver, AutoscalingPolicy, NoAutoscaling};
 use crate::coldstart::{default_coldstart_policy_resolver, ColdStartPolicy, FixedTimeColdStartPolicy};
 use crate::cpu::{default_cpu_policy_resolver, ContendedCpuPolicy, CpuPolicy};
 use crate::deployer::{default_idle_deployer_resolver, BasicDeployer, IdleDeployer};
@@ -23,6 +24,8 @@ pub struct HostConfig {
     pub resources: Vec<(String, u64)>,
     /// Host CPU cores.
     pub cores: u32,
+    /// Maximum number of invocations concurrently passed to the invoker (see [`crate::host::Host::set_invocation_limit`]).
+    pub invocation_limit: Option<usize>,
 }
 
 impl From<ParallelHostConfig> for HostConfig {
@@ -31,6 +34,7 @@ impl From<ParallelHostConfig> for HostConfig {
             invoker: value.invoker,
             resources: value.resources,
             cores: value.cores,
+            invocation_limit: value.invocation_limit,
         }
     }
 }
@@ -41,6 +45,7 @@ impl Default for HostConfig {
             invoker: Box::new(FIFOInvoker::new()),
             resources: Vec::new(),
             cores: 1,
+            invocation_limit: None,
         }
     }
 }
@@ -49,6 +54,7 @@ impl From<ParallelConfig> for Config {
     fn from(value: ParallelConfig) -> Self {
         let mut hosts = value.hosts;
         Self {
+            autoscaling_policy: value.autoscaling_policy,
             coldstart_policy: value.coldstart_policy,
             cpu_policy: value.cpu_policy,
             idle_deployer: value.idle_deployer,
@@ -84,11 +90,17 @@ pub struct RawHostConfig {
     /// Number of such hosts in the system.
     #[serde(default = "default_one")]
     pub count: u32,
+    /// Maximum number of invocations concurrently passed to the invoker.
+    #[serde(default)]
+    pub invocation_limit: Option<usize>,
 }
 
 /// YAML-serializable config
 #[derive(Clone, Serialize, Deserialize)]
 pub struct RawConfig {
+    /// [`crate::autoscaling::AutoscalingPolicy`] name.
+    #[serde(default)]
+    pub autoscaling_policy: String,
     /// [`crate::coldstart::ColdStartPolicy`] name.
     #[serde(default)]
     pub coldstart_policy: String,
@@ -120,6 +132,8 @@ pub fn parse_options(s: &str) -> HashMap<String, String> {
 
 /// Functions that create algorithm implementation from a string containing algorithm name and options.
 pub struct ConfigParamResolvers {
+    /// Creates [`crate::autoscaling::AutoscalingPolicy`] from a string.
+    pub autoscaling_policy_resolver: Box<dyn Fn(&str) -> Box<dyn AutoscalingPolicy> + Send + Sync>,
     /// Creates [`crate::coldstart::ColdStartPolicy`] from a string.
     pub coldstart_policy_resolver: Box<dyn Fn(&str) -> Box<dyn ColdStartPolicy> + Send + Sync>,
     /// Creates [`crate::cpu::CpuPolicy`] from a string.
@@ -135,6 +149,7 @@ pub struct ConfigParamResolvers {
 impl Default for ConfigParamResolvers {
     fn default() -> Self {
         Self {
+            autoscaling_policy_resolver: Box::new(default_autoscaling_policy_resolver),
             coldstart_policy_resolver: Box::new(default_coldstart_policy_resolver),
             cpu_policy_resolver: Box::new(default_cpu_policy_resolver),
             idle_deployer_resolver: Box::new(default_idle_deployer_resolver),
@@ -146,6 +161,8 @@ impl Default for ConfigParamResolvers {
 
 /// Simulation config. It implements Default trait so that you can create default config and change only the fields you need.
 pub struct Config {
+    /// [`crate::autoscaling::AutoscalingPolicy`] implementation.
+    pub autoscaling_policy: Box<dyn AutoscalingPolicy>,
     /// [`crate::coldstart::ColdStartPolicy`] implementation.
     pub coldstart_policy: Box<dyn ColdStartPolicy>,
     /// [`crate::cpu::CpuPolicy`] implementation.
@@ -161,6 +178,7 @@ pub struct Config {
 impl Default for Config {
     fn default() -> Self {
         Self {
+            autoscaling_policy: Box::new(NoAutoscaling {}),
             coldstart_policy: Box::new(FixedTimeColdStartPolicy::new(0.0, 0.0, false)),
             cpu_policy: Box::<ContendedCpuPolicy>::default(),
             idle_deployer: Box::new(BasicDeployer {}),
@@ -175,6 +193,7 @@ impl Config {
     pub fn from_raw(raw: RawConfig, resolvers: ConfigParamResolvers) -> Self {
         Self::from_raw_split_resolvers(
             raw,
+            resolvers.autoscaling_policy_resolver.as_ref(),
             resolvers.coldstart_policy_resolver.as_ref(),
             resolvers.cpu_policy_resolver.as_ref(),
             resolvers.idle_deployer_resolver.as_ref(),
@@ -186,6 +205,7 @@ impl Config {
     /// Similar to [`Self::from_raw`], but takes resolvers as separate functions.
     pub fn from_raw_split_resolvers(
         raw: RawConfig,
+        autoscaling_policy_resolver: &(dyn Fn(&str) -> Box<dyn AutoscalingPolicy> + Send + Sync),
         coldstart_policy_resolver: &(dyn Fn(&str) -> Box<dyn ColdStartPolicy> + Send + Sync),
         cpu_policy_resolver: &(dyn Fn(&str) -> Box<dyn CpuPolicy> + Send + Sync),
         idle_deployer_resolver: &(dyn Fn(&str) -> Box<dyn IdleDeployer> + Send + Sync),
@@ -193,6 +213,9 @@ impl Config {
         invoker_resolver: &(dyn Fn(&str) -> Box<dyn Invoker> + Send + Sync),
     ) -> Self {
         let mut me: Self = Default::default();
+        if !raw.autoscaling_policy.is_empty() {
+            me.autoscaling_policy = autoscaling_policy_resolver(&raw.autoscaling_policy);
+        }
         if !raw.coldstart_policy.is_empty() {
             me.coldstart_policy = coldstart_policy_resolver(&raw.coldstart_policy);
         }
@@ -220,6 +243,7 @@ impl Config {
                     invoker,
                     resources: resources.clone(),
                     cores: host.cores,
+                    invocation_limit: host.invocation_limit,
                 };
                 me.hosts.push(curr);
             }
@@ -140,6 +140,11 @@ impl ContainerManager {
         &mut self.containers
     }
 
+    /// Returns an iterator over all existing containers.
+    pub fn containers(&self) -> impl Iterator<Item = &Container> {
+        self.containers.values()
+    }
+
     /// Returns an iterator over running containers that can accommodate one more invocation of given app.
     /// If `allow_deploying` is true, also returns containers that are being deployed.
     pub fn get_possible_containers(&self, app: &Application, allow_deploying: bool) -> PossibleContainerIterator<'_> {
@@ -5,24 +5,30 @@ use std::cell::RefCell;
 use std::rc::Rc;
 
 use dslab_core::cast;
+use dslab_core::context::SimulationContext;
 use dslab_core::event::Event;
 use dslab_core::handler::EventHandler;
 
+use crate::autoscaling::{AutoscalingPolicy, ScalingState};
 use crate::deployer::IdleDeployer;
-use crate::event::{IdleDeployEvent, InvocationStartEvent, SimulationEndEvent};
+use crate::event::{AutoscalingEvent, IdleDeployEvent, InvocationStartEvent, SimulationEndEvent};
 use crate::function::FunctionRegistry;
 use crate::host::Host;
 use crate::invoker::InvokerDecision;
 use crate::scheduler::Scheduler;
 use crate::stats::Stats;
 
-/// Responsible for handling incoming invocation requests and deploying prewarmed containers.
+/// Responsible for handling incoming invocation requests, deploying prewarmed containers and autoscaling.
 pub struct Controller {
+    autoscaler: Box<dyn AutoscalingPolicy>,
+    autoscaling_scheduled: bool,
+    arrivals: Vec<u64>,
     function_registry: Rc<RefCell<FunctionRegistry>>,
     hosts: Vec<Rc<RefCell<Host>>>,
     idle_deployer: Box<dyn IdleDeployer>,
     scheduler: Box<dyn Scheduler>,
     stats: Rc<RefCell<Stats>>,
+    ctx: SimulationContext,
 }
 
 impl Controller {
@@ -31,14 +37,80 @@ impl Controller {
         function_registry: Rc<RefCell<FunctionRegistry>>,
         idle_deployer: Box<dyn IdleDeployer>,
         scheduler: Box<dyn Scheduler>,
+        autoscaler: Box<dyn AutoscalingPolicy>,
         stats: Rc<RefCell<Stats>>,
+        ctx: SimulationContext,
     ) -> Self {
-        Self {
+        let mut controller = Self {
+            autoscaler,
+            autoscaling_scheduled: false,
+            arrivals: Vec::new(),
             function_registry,
             hosts: Vec::new(),
             idle_deployer,
             scheduler,
             stats,
+            ctx,
+        };
+        // the first scaling decision is made at the start of simulation, e.g. to deploy the minimum instances
+        controller.schedule_autoscaling(0.);
+        controller
+    }
+
+    fn schedule_autoscaling(&mut self, delay: f64) {
+        if !self.autoscaling_scheduled && self.autoscaler.interval().is_finite() {
+            self.ctx.emit_self(AutoscalingEvent {}, delay);
+            self.autoscaling_scheduled = true;
+        }
+    }
+
+    /// Makes scaling decision for each application.
+    /// The next decision is scheduled only if there are active or arrived invocations, otherwise it is scheduled
+    /// upon the next arrival, so that the simulation can finish.
+    fn autoscale(&mut self, time: f64) {
+        self.autoscaling_scheduled = false;
+        let reg = self.function_registry.borrow();
+        let mut active = false;
+        for app_id in 0..reg.app_count() {
+            let app = reg.get_app(app_id).unwrap();
+            let mut state = ScalingState {
+                arrivals: self.arrivals.get(app_id).copied().unwrap_or(0),
+                ..Default::default()
+            };
+            for host in self.hosts.iter() {
+                let host_state = host.borrow().scaling_state(app_id);
+                state.instances += host_state.instances;
+                state.idle_instances += host_state.idle_instances;
+                state.active_invocations += host_state.active_invocations;
+            }
+            active |= state.active_invocations > 0 || state.arrivals > 0;
+            match self.autoscaler.desired_instances(app, &state, time) {
+                Some(desired) if desired > state.instances => {
+                    for _ in state.instances..desired {
+                        if let Some(host) = self.idle_deployer.deploy(app, &self.hosts) {
+                            self.hosts[host].borrow_mut().try_deploy(app, time);
+                        } else {
+                            break;
+                        }
+                    }
+                }
+                Some(desired) if desired < state.instances => {
+                    let mut excess = state.instances - desired;
+                    for host in self.hosts.iter() {
+                        if excess == 0 {
+                            break;
+                        }
+                        excess -= host.borrow_mut().terminate_idle_containers(app_id, excess);
+                    }
+                }
+                _ => {}
+            }
+        }
+        drop(reg);
+        self.arrivals.clear();
+        if active {
+            let interval = self.autoscaler.interval();
+            self.schedule_autoscaling(interval);
         }
     }
 
@@ -58,8 +130,16 @@ impl Controller {
     fn invoke(&mut self, id: usize, func_id: usize, time: f64) -> InvokerDecision {
         let reg = self.function_registry.borrow();
         let app = reg.get_app_by_function(func_id).unwrap();
+        if self.arrivals.len() <= app.id {
+            self.arrivals.resize(app.id + 1, 0);
+        }
+        self.arrivals[app.id] += 1;
         let host = self.scheduler.select_host(app, &self.hosts);
-        self.hosts[host].borrow_mut().invoke(id, time)
+        let decision = self.hosts[host].borrow_mut().invoke(id, time);
+        drop(reg);
+        let interval = self.autoscaler.interval();
+        self.schedule_autoscaling(interval);
+        decision
     }
 
     /// Registers a new host in the controller.
@@ -67,6 +147,11 @@ impl Controller {
         self.hosts.push(host);
     }
 
+    /// Returns the host by its id.
+    pub fn get_host(&self, id: usize) -> Rc<RefCell<Host>> {
+        self.hosts[id].clone()
+    }
+
     fn update_end_metrics(&mut self, time: f64) {
         for host in &mut self.hosts {
             host.borrow_mut().update_end_metrics(time);
@@ -77,6 +162,9 @@ impl Controller {
 impl EventHandler for Controller {
     fn on(&mut self, event: Event) {
         cast!(match event.data {
+            AutoscalingEvent {} => {
+                self.autoscale(event.time);
+            }
             IdleDeployEvent {
                 id,
                 expected_invocation,
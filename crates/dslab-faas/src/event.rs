@@ -1,6 +1,10 @@
 //! Simulation events.
 use serde::Serialize;
 
+/// The controller makes a scaling decision.
+#[derive(Clone, Serialize)]
+pub struct AutoscalingEvent {}
+
 /// An idle container must be destroyed.
 #[derive(Clone, Serialize)]
 pub struct ContainerEndEvent {
@@ -98,6 +98,11 @@ impl FunctionRegistry {
         }
     }
 
+    /// Returns the number of applications.
+    pub fn app_count(&self) -> usize {
+        self.apps.len()
+    }
+
     /// Adds a new [`Function`] and returns its `id`.
     pub fn add_function(&mut self, f: Function) -> usize {
         let id = self.functions.len();
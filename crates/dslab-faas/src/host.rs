@@ -6,6 +6,7 @@
 //! - [Invoker][crate::invoker::Invoker] -- a component that routes invocation requests to appropriate containers and creates
 //! new containers if needed.
 use std::cell::RefCell;
+use std::collections::VecDeque;
 use std::rc::Rc;
 
 use dslab_core::cast;
@@ -13,6 +14,7 @@ use dslab_core::context::SimulationContext;
 use dslab_core::event::Event;
 use dslab_core::handler::EventHandler;
 
+use crate::autoscaling::ScalingState;
 use crate::coldstart::{ColdStartPolicy, KeepaliveDecision};
 use crate::container::{ContainerManager, ContainerStatus};
 use crate::cpu::{Cpu, CpuPolicy};
@@ -36,6 +38,9 @@ pub struct Host {
     controller_id: HandlerId,
     stats: Rc<RefCell<Stats>>,
     ctx: Rc<RefCell<SimulationContext>>,
+    invocation_limit: Option<usize>,
+    admitted_invocations: usize,
+    limit_queue: VecDeque<usize>,
 }
 
 impl Host {
@@ -66,9 +71,26 @@ impl Host {
             controller_id,
             stats,
             ctx,
+            invocation_limit: None,
+            admitted_invocations: 0,
+            limit_queue: VecDeque::new(),
         }
     }
 
+    /// Sets the maximum number of invocations concurrently passed to the [`crate::invoker::Invoker`] (unlimited by default).
+    ///
+    /// The invocations admitted to the invoker include the running ones and the ones waiting for a container
+    /// in the invoker. The invocations exceeding the limit are queued on the host and admitted in FIFO order
+    /// when the admitted invocations finish. The limit should be set before submitting invocations.
+    pub fn set_invocation_limit(&mut self, limit: Option<usize>) {
+        self.invocation_limit = limit;
+    }
+
+    /// Returns the maximum number of invocations concurrently passed to the [`crate::invoker::Invoker`].
+    pub fn invocation_limit(&self) -> Option<usize> {
+        self.invocation_limit
+    }
+
     /// Checks whether the host can allocate given resources.
     pub fn can_allocate(&self, resources: &ResourceConsumer) -> bool {
         self.container_manager.can_allocate(resources)
@@ -89,7 +111,7 @@ impl Host {
 
     /// Returns the amount of queued invocations on this host.
     pub fn queued_invocation_count(&self) -> usize {
-        self.invoker.queue_len()
+        self.invoker.queue_len() + self.limit_queue.len()
     }
 
     /// Returns the amount of all existing (active + queued) invocations on this host.
@@ -113,11 +135,38 @@ impl Host {
     }
 
     /// Passes an invocation to the [`crate::invoker::Invoker`], which either assigns it to a container or puts it in queue.
+    /// If the host invocation limit is reached, the invocation is queued on the host instead.
     pub fn invoke(&mut self, id: usize, time: f64) -> InvokerDecision {
         let mut ir = self.invocation_registry.borrow_mut();
         let invocation = &mut ir[id];
         invocation.host_id = Some(self.id);
         self.container_manager.inc_active_invocations();
+        self.stats
+            .borrow_mut()
+            .on_new_invocation(invocation.app_id, invocation.func_id);
+        if self
+            .invocation_limit
+            .is_some_and(|limit| self.admitted_invocations >= limit)
+        {
+            invocation.status = InvocationStatus::Queued;
+            self.limit_queue.push_back(id);
+            return InvokerDecision::Queued;
+        }
+        drop(ir);
+        self.admit(id, time, false)
+    }
+
+    /// Passes an invocation admitted under the host invocation limit to the [`crate::invoker::Invoker`].
+    /// The waiting time of invocation queued on the host is accounted in the same way as in the invoker queue.
+    fn admit(&mut self, id: usize, time: f64, was_queued: bool) -> InvokerDecision {
+        self.admitted_invocations += 1;
+        let mut ir = self.invocation_registry.borrow_mut();
+        let invocation = &mut ir[id];
+        let wait = if was_queued {
+            time - invocation.arrival_time
+        } else {
+            0.0
+        };
         let concurrency_limit = self
             .function_registry
             .borrow()
@@ -131,9 +180,14 @@ impl Host {
             time,
         );
         let mut stats = self.stats.borrow_mut();
-        stats.on_new_invocation(invocation.app_id, invocation.func_id);
+        if was_queued && status != InvokerDecision::Queued {
+            stats.update_queueing_time(invocation.app_id, invocation.func_id, wait);
+        }
         match status {
             InvokerDecision::Warm(container_id) => {
+                if was_queued {
+                    stats.on_cold_start(invocation.app_id, invocation.func_id, wait);
+                }
                 drop(stats);
                 drop(ir);
                 self.start_invocation(container_id, id, time);
@@ -151,7 +205,7 @@ impl Host {
             InvokerDecision::Cold((container_id, delay)) => {
                 invocation.status = InvocationStatus::WaitingForContainer;
                 invocation.container_id = Some(container_id);
-                stats.on_cold_start(invocation.app_id, invocation.func_id, delay);
+                stats.on_cold_start(invocation.app_id, invocation.func_id, delay + wait);
                 drop(stats);
                 self.container_manager.reserve_container(container_id, id);
                 if self.container_manager.count_reservations(container_id) == concurrency_limit {
@@ -170,6 +224,42 @@ impl Host {
         self.container_manager.try_deploy(app, time)
     }
 
+    /// Returns the state of application instances on this host, the arrivals are not counted here.
+    pub fn scaling_state(&self, app_id: usize) -> ScalingState {
+        let mut state = ScalingState::default();
+        for container in self
+            .container_manager
+            .containers()
+            .filter(|c| c.app_id == app_id && c.status != ContainerStatus::Terminated)
+        {
+            state.instances += 1;
+            if container.status == ContainerStatus::Idle {
+                state.idle_instances += 1;
+            }
+            state.active_invocations +=
+                container.invocations.len() + self.container_manager.count_reservations(container.id);
+        }
+        state
+    }
+
+    /// Terminates up to `count` idle containers of the application and returns the number of terminated containers.
+    pub fn terminate_idle_containers(&mut self, app_id: usize, count: usize) -> usize {
+        let ids: Vec<usize> = self
+            .container_manager
+            .containers()
+            .filter(|c| c.app_id == app_id && c.status == ContainerStatus::Idle)
+            .map(|c| c.id)
+            .take(count)
+            .collect();
+        for &id in ids.iter() {
+            if let Some(event_id) = self.container_manager.get_container(id).unwrap().end_event {
+                self.ctx.borrow_mut().cancel_event(event_id);
+            }
+            self.new_container_end_event(id, 0.0);
+        }
+        ids.len()
+    }
+
     /// Updates wasted resources for idle containers.
     pub fn update_end_metrics(&mut self, time: f64) {
         let mut stats = self.stats.borrow_mut();
@@ -253,6 +343,7 @@ impl Host {
             .borrow_mut()
             .update(invocation, self.function_registry.borrow().get_app(app_id).unwrap());
         self.container_manager.dec_active_invocations();
+        self.admitted_invocations -= 1;
         self.container_manager.try_move_container_to_free(cont_id);
         let container = self.container_manager.get_container_mut(cont_id).unwrap();
         container.end_invocation(id, time);
@@ -318,9 +409,6 @@ impl Host {
             &mut self.stats.borrow_mut(),
             time,
         );
-        if reqs.is_empty() {
-            return;
-        }
         for req in reqs.drain(..) {
             let mut ir = self.invocation_registry.borrow_mut();
             let invocation = &mut ir[req.id];
@@ -334,6 +422,17 @@ impl Host {
                 invocation.status = InvocationStatus::WaitingForContainer;
             }
         }
+        while self
+            .invocation_limit
+            .is_some_and(|limit| self.admitted_invocations < limit)
+        {
+            match self.limit_queue.pop_front() {
+                Some(id) => {
+                    self.admit(id, time, true);
+                }
+                None => break,
+            }
+        }
     }
 }
 
@@ -1,6 +1,7 @@
 #![warn(missing_docs)]
 #![doc = include_str!("../readme.md")]
 
+pub mod autoscaling;
 pub mod coldstart;
 pub mod config;
 pub mod container;
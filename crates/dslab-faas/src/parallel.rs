@@ -12,6 +12,7 @@ use threadpool::ThreadPool;
 
 use dslab_core::simulation::Simulation;
 
+use crate::autoscaling::{AutoscalingPolicy, NoAutoscaling};
 use crate::coldstart::{ColdStartPolicy, FixedTimeColdStartPolicy};
 use crate::config::{Config, ConfigParamResolvers, RawConfig};
 use crate::cpu::{ContendedCpuPolicy, CpuPolicy};
@@ -30,6 +31,8 @@ pub struct ParallelHostConfig {
     pub resources: Vec<(String, u64)>,
     /// Host CPU cores.
     pub cores: u32,
+    /// Maximum number of invocations concurrently passed to the invoker (see [`crate::host::Host::set_invocation_limit`]).
+    pub invocation_limit: Option<usize>,
 }
 
 impl Default for ParallelHostConfig {
@@ -38,12 +41,15 @@ impl Default for ParallelHostConfig {
             invoker: Box::new(FIFOInvoker::new()),
             resources: Vec::new(),
             cores: 1,
+            invocation_limit: None,
         }
     }
 }
 
 /// Similar to [`crate::config::Config`], but ensures that all simulation components implement `Send` trait.
 pub struct ParallelConfig {
+    /// [`crate::autoscaling::AutoscalingPolicy`] implementation.
+    pub autoscaling_policy: Box<dyn AutoscalingPolicy + Send>,
     /// [`crate::coldstart::ColdStartPolicy`] implementation.
     pub coldstart_policy: Box<dyn ColdStartPolicy + Send>,
     /// [`crate::cpu::CpuPolicy`] implementation.
@@ -59,6 +65,7 @@ pub struct ParallelConfig {
 impl Default for ParallelConfig {
     fn default() -> Self {
         Self {
+            autoscaling_policy: Box::new(NoAutoscaling {}),
             coldstart_policy: Box::new(FixedTimeColdStartPolicy::new(0.0, 0.0, false)),
             cpu_policy: Box::<ContendedCpuPolicy>::default(),
             idle_deployer: Box::new(BasicDeployer {}),
@@ -158,6 +165,8 @@ pub fn parallel_simulation_raw_n_workers(
         let seed = seeds[0];
         seeds = vec![seed; configs.len()];
     }
+    let autoscaling_policy_resolver1: Arc<dyn Fn(&str) -> Box<dyn AutoscalingPolicy> + Send + Sync> =
+        Arc::from(resolvers.autoscaling_policy_resolver);
     let coldstart_policy_resolver1: Arc<dyn Fn(&str) -> Box<dyn ColdStartPolicy> + Send + Sync> =
         Arc::from(resolvers.coldstart_policy_resolver);
     let cpu_policy_resolver1: Arc<dyn Fn(&str) -> Box<dyn CpuPolicy> + Send + Sync> =
@@ -172,6 +181,7 @@ pub fn parallel_simulation_raw_n_workers(
     let len = configs.len();
     for (id, raw_config, trace, seed) in izip!(0..len, configs.drain(..), traces_arc.drain(..), seeds.drain(..)) {
         let tx = tx.clone();
+        let autoscaling_policy_resolver = autoscaling_policy_resolver1.clone();
         let coldstart_policy_resolver = coldstart_policy_resolver1.clone();
         let cpu_policy_resolver = cpu_policy_resolver1.clone();
         let idle_deployer_resolver = idle_deployer_resolver1.clone();
@@ -180,6 +190,7 @@ pub fn parallel_simulation_raw_n_workers(
         pool.execute(move || {
             let config = Config::from_raw_split_resolvers(
                 raw_config,
+                autoscaling_policy_resolver.as_ref(),
                 coldstart_policy_resolver.as_ref(),
                 cpu_policy_resolver.as_ref(),
                 idle_deployer_resolver.as_ref(),
@@ -49,7 +49,9 @@ impl ServerlessSimulation {
             function_registry.clone(),
             config.idle_deployer,
             config.scheduler,
+            config.autoscaling_policy,
             stats.clone(),
+            sim.create_context("controller"),
         )));
         let controller_id = sim.add_handler("controller", controller.clone());
         let mut this_sim = Self {
@@ -71,7 +73,8 @@ impl ServerlessSimulation {
                 .iter()
                 .map(|x| this_sim.create_resource(&x.0, x.1))
                 .collect();
-            this_sim.add_host(Some(host.invoker), ResourceProvider::new(resources), host.cores);
+            let id = this_sim.add_host(Some(host.invoker), ResourceProvider::new(resources), host.cores);
+            this_sim.set_host_invocation_limit(id, host.invocation_limit);
         }
         this_sim
     }
@@ -116,8 +119,8 @@ impl ServerlessSimulation {
         self.stats.borrow().global_stats.invocation_stats.clone()
     }
 
-    /// Adds a new [`crate::host::Host`].
-    pub fn add_host(&mut self, invoker: Option<Box<dyn Invoker>>, resources: ResourceProvider, cores: u32) {
+    /// Adds a new [`crate::host::Host`] and returns its id.
+    pub fn add_host(&mut self, invoker: Option<Box<dyn Invoker>>, resources: ResourceProvider, cores: u32) -> usize {
         let id = self.host_ctr.increment();
         let real_invoker = invoker.unwrap_or_else(|| Box::new(FIFOInvoker::new()));
         let ctx = self.sim.create_context(format!("host_{}", id));
@@ -136,6 +139,17 @@ impl ServerlessSimulation {
         )));
         self.sim.add_handler(format!("host_{}", id), host.clone());
         self.controller.borrow_mut().add_host(host);
+        id
+    }
+
+    /// Sets the maximum number of invocations concurrently passed to the invoker of the host
+    /// (see [`crate::host::Host::set_invocation_limit`]).
+    pub fn set_host_invocation_limit(&mut self, host_id: usize, limit: Option<usize>) {
+        self.controller
+            .borrow()
+            .get_host(host_id)
+            .borrow_mut()
+            .set_invocation_limit(limit);
     }
 
     /// Adds a new [`crate::function::Function`].
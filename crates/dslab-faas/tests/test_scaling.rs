@@ -0,0 +1,118 @@
+mod common;
+use common::assert_float_eq;
+
+use std::boxed::Box;
+
+use dslab_core::simulation::Simulation;
+use dslab_faas::autoscaling::{default_autoscaling_policy_resolver, ConcurrencyAutoscaler};
+use dslab_faas::coldstart::FixedTimeColdStartPolicy;
+use dslab_faas::config::Config;
+use dslab_faas::function::Application;
+use dslab_faas::resource::{ResourceConsumer, ResourceProvider};
+use dslab_faas::simulation::ServerlessSimulation;
+
+#[test]
+fn test_host_invocation_limit() {
+    // host has enough memory for 2 containers, but only one invocation may be passed to the invoker at a time,
+    // so the second invocation is admitted when the first one ends and reuses its warm container,
+    // like with invoker queueing, the waiting time is counted as cold start latency
+    let config = Config {
+        coldstart_policy: Box::new(FixedTimeColdStartPolicy::new(10.0, 0.0, true)),
+        ..Default::default()
+    };
+    let mut sim = ServerlessSimulation::new(Simulation::new(1), config);
+    let host = {
+        let mem = sim.create_resource("mem", 2);
+        sim.add_host(None, ResourceProvider::new(vec![mem]), 2)
+    };
+    sim.set_host_invocation_limit(host, Some(1));
+    let mem = sim.create_resource_requirement("mem", 1);
+    let f = sim.add_app_with_single_function(Application::new(1, 1., 1., ResourceConsumer::new(vec![mem])));
+    sim.send_invocation_request(f, 1.0, 0.0);
+    sim.send_invocation_request(f, 1.0, 0.0);
+    sim.step_until_no_events();
+    let stats = sim.stats();
+    let inv_stats = &stats.global_stats.invocation_stats;
+    assert_eq!(inv_stats.invocations, 2);
+    assert_eq!(inv_stats.cold_starts, 2);
+    assert_float_eq(inv_stats.cold_start_latency.max().unwrap(), 2.0, 1e-9);
+    assert_eq!(inv_stats.queueing_time.len(), 1);
+    assert_float_eq(inv_stats.queueing_time.max().unwrap(), 2.0, 1e-9);
+    assert_float_eq(inv_stats.abs_total_slowdown.max().unwrap(), 2.0, 1e-9);
+}
+
+#[test]
+fn test_host_invocation_limit_from_config() {
+    // same as above, but the limit is set in the host config
+    let config_str = r#"
+        coldstart_policy: FixedTimeColdStartPolicy[keepalive=10.0,prewarm=0.0]
+        hosts:
+          - resources: [["mem", 2]]
+            cores: 2
+            invocation_limit: 1
+    "#;
+    let config = Config::from_raw(serde_yaml::from_str(config_str).unwrap(), Default::default());
+    assert_eq!(config.hosts[0].invocation_limit, Some(1));
+    let mut sim = ServerlessSimulation::new(Simulation::new(1), config);
+    let mem = sim.create_resource_requirement("mem", 1);
+    let f = sim.add_app_with_single_function(Application::new(1, 1., 1., ResourceConsumer::new(vec![mem])));
+    sim.send_invocation_request(f, 1.0, 0.0);
+    sim.send_invocation_request(f, 1.0, 0.0);
+    sim.step_until_no_events();
+    let inv_stats = &sim.stats().global_stats.invocation_stats;
+    assert_eq!(inv_stats.invocations, 2);
+    assert_eq!(inv_stats.queueing_time.len(), 1);
+    assert_float_eq(inv_stats.queueing_time.max().unwrap(), 2.0, 1e-9);
+}
+
+#[test]
+fn test_autoscaling_min_instances() {
+    // autoscaler keeps one instance of the app deployed from the start, so the invocation at time 2 is warm
+    let config = Config {
+        coldstart_policy: Box::new(FixedTimeColdStartPolicy::new(100.0, 0.0, true)),
+        autoscaling_policy: Box::new(ConcurrencyAutoscaler::new(1.0, 1.0, 1, 10)),
+        ..Default::default()
+    };
+    let mut sim = ServerlessSimulation::new(Simulation::new(1), config);
+    {
+        let mem = sim.create_resource("mem", 4);
+        sim.add_host(None, ResourceProvider::new(vec![mem]), 4);
+    }
+    let mem = sim.create_resource_requirement("mem", 1);
+    let f = sim.add_app_with_single_function(Application::new(1, 1., 1., ResourceConsumer::new(vec![mem])));
+    sim.send_invocation_request(f, 1.0, 2.0);
+    sim.step_until_no_events();
+    let inv_stats = &sim.stats().global_stats.invocation_stats;
+    assert_eq!(inv_stats.invocations, 1);
+    assert_eq!(inv_stats.cold_starts, 0);
+}
+
+#[test]
+fn test_autoscaling_scale_out_and_in() {
+    // 4 long invocations arrive at time 0 and get 4 containers, at time 1 the autoscaler deploys one more
+    // instance (the target concurrency of 0.5 asks for 8, but the maximum is 5), so the invocation arriving
+    // at time 2 is warm, after the load is gone the idle instances are terminated long before keepalive expires
+    let config = Config {
+        coldstart_policy: Box::new(FixedTimeColdStartPolicy::new(1000.0, 0.0, true)),
+        autoscaling_policy: default_autoscaling_policy_resolver("ConcurrencyAutoscaler[interval=1.0,target=0.5,max=5]"),
+        ..Default::default()
+    };
+    let mut sim = ServerlessSimulation::new(Simulation::new(1), config);
+    {
+        let mem = sim.create_resource("mem", 8);
+        sim.add_host(None, ResourceProvider::new(vec![mem]), 8);
+    }
+    let mem = sim.create_resource_requirement("mem", 1);
+    let f = sim.add_app_with_single_function(Application::new(1, 0.5, 1., ResourceConsumer::new(vec![mem])));
+    for _ in 0..4 {
+        sim.send_invocation_request(f, 5.0, 0.0);
+    }
+    sim.send_invocation_request(f, 1.0, 2.0);
+    sim.step_until_no_events();
+    let stats = sim.stats();
+    let inv_stats = &stats.global_stats.invocation_stats;
+    assert_eq!(inv_stats.invocations, 5);
+    assert_eq!(inv_stats.cold_starts, 4);
+    // idle containers live until the scaling decision at time 6 instead of the keepalive period
+    assert!(stats.global_stats.wasted_resource_time[0].sum() < 10.0);
+}
@@ -0,0 +1,457 @@
+//! Python bindings for DSLab IaaS.
+//!
+//! The bindings allow to configure and run cloud simulations from Python. The simulation is created from
+//! a simulation config or a scenario description (hosts, schedulers and network) in YAML format, and can be
+//! further extended by adding hosts, schedulers and VMs. The VM placement algorithms can be implemented
+//! in Python as callables, which receive the VM allocation request and the current resource pool state and
+//! return the ID of selected host or `None`.
+//!
+//! The results are returned as tables in the form of dicts mapping column names to lists of values,
+//! which can be converted to pandas dataframes via the corresponding `*_dataframe` methods.
+//!
+//! ```python
+//! from dslab_iaas import CloudSimulation
+//!
+//! def first_fit(vm, hosts):
+//!     for host in hosts:
+//!         if host["cpu_available"] >= vm["cpu"] and host["memory_available"] >= vm["memory"]:
+//!             return host["id"]
+//!     return None
+//!
+//! sim = CloudSimulation("config.yaml", seed=123, algorithms={"PyFirstFit": first_fit})
+//! sim.add_host("h1", 16, 32)
+//! sim.add_scheduler("s", "PyFirstFit")
+//! sim.spawn_vm(4, 8, 100.0, "s")
+//! sim.step_until_time(200.0)
+//! print(sim.vm_dataframe())
+//! ```
+
+// The impl blocks generated by pyo3 0.19 macros trigger this lint on recent compilers.
+#![allow(non_local_definitions)]
+
+use pyo3::exceptions::{PyIOError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+
+use dslab_core::simulation::Simulation;
+use dslab_iaas::core::common::Allocation;
+use dslab_iaas::core::config::sim_config::SimulationConfig;
+use dslab_iaas::core::logger::StdoutLogger;
+use dslab_iaas::core::monitoring::Monitoring;
+use dslab_iaas::core::resource_pool::ResourcePoolState;
+use dslab_iaas::core::vm::ResourceConsumer;
+use dslab_iaas::core::vm_placement_algorithm::{
+    PlacementAlgorithmRegistry, SingleVMPlacementAlgorithm, VMPlacementAlgorithm,
+};
+use dslab_iaas::scenario::ScenarioBuilder;
+use dslab_iaas::simulation::CloudSimulation;
+
+#[cfg(test)]
+mod tests;
+
+/// VM placement algorithm implemented as Python callable.
+///
+/// The callable is invoked as `callback(vm, hosts)`, where `vm` is a dict with `id`, `cpu` and `memory` keys,
+/// and `hosts` is a list of dicts describing the hosts from resource pool state (see [`host_info`]).
+/// The callable should return the ID of selected host or `None` if there is no suitable host.
+pub struct PyPlacementAlgorithm {
+    callback: PyObject,
+}
+
+impl PyPlacementAlgorithm {
+    pub fn new(callback: PyObject) -> Self {
+        Self { callback }
+    }
+}
+
+impl SingleVMPlacementAlgorithm for PyPlacementAlgorithm {
+    fn select_host(&self, alloc: &Allocation, pool_state: &ResourcePoolState, monitoring: &Monitoring) -> Option<u32> {
+        Python::with_gil(|py| {
+            let result = (|| -> PyResult<Option<u32>> {
+                let vm = PyDict::new(py);
+                vm.set_item("id", alloc.id)?;
+                vm.set_item("cpu", alloc.cpu_usage)?;
+                vm.set_item("memory", alloc.memory_usage)?;
+                let hosts = PyList::empty(py);
+                for host_id in pool_state.get_host_ids() {
+                    hosts.append(host_info(py, host_id, pool_state, monitoring)?)?;
+                }
+                self.callback.call1(py, (vm, hosts))?.extract(py)
+            })();
+            result.unwrap_or_else(|e| {
+                e.print(py);
+                panic!("Error in Python placement algorithm: {}", e);
+            })
+        })
+    }
+}
+
+/// Returns the host description passed to Python placement algorithm.
+fn host_info<'py>(
+    py: Python<'py>,
+    host_id: u32,
+    pool_state: &ResourcePoolState,
+    monitoring: &Monitoring,
+) -> PyResult<&'py PyDict> {
+    let host = pool_state.get_host(host_id);
+    let host_state = monitoring.get_host_state(host_id);
+    let info = PyDict::new(py);
+    info.set_item("id", host_id)?;
+    info.set_item("type", host.host_type.as_deref())?;
+    info.set_item("rack", host.rack_id)?;
+    info.set_item("cpu_total", host.cpu_total)?;
+    info.set_item("memory_total", host.memory_total)?;
+    info.set_item("cpu_available", host.cpu_available)?;
+    info.set_item("memory_available", host.memory_available)?;
+    info.set_item("cpu_load", host_state.cpu_load)?;
+    info.set_item("memory_load", host_state.memory_load)?;
+    info.set_item("vm_count", host.allocations.len())?;
+    Ok(info)
+}
+
+/// Creates the registry of built-in placement algorithms extended with the specified Python algorithms.
+fn placement_algorithms(algorithms: Option<&PyDict>) -> PyResult<PlacementAlgorithmRegistry> {
+    let mut registry = PlacementAlgorithmRegistry::new();
+    for (name, callback) in algorithms.into_iter().flatten() {
+        let name: String = name.extract()?;
+        let callback = checked_callback(&name, callback)?;
+        registry.register(&name, move |_| {
+            VMPlacementAlgorithm::single(PyPlacementAlgorithm::new(callback.clone()))
+        });
+    }
+    Ok(registry)
+}
+
+fn checked_callback(name: &str, callback: &PyAny) -> PyResult<PyObject> {
+    if !callback.is_callable() {
+        return Err(PyValueError::new_err(format!(
+            "Placement algorithm {} is not callable",
+            name
+        )));
+    }
+    Ok(callback.into())
+}
+
+/// Converts the table to pandas dataframe.
+fn to_dataframe(py: Python, table: &PyDict) -> PyResult<PyObject> {
+    let pandas = py.import("pandas")?;
+    Ok(pandas.getattr("DataFrame")?.call1((table,))?.into())
+}
+
+/// Python wrapper of [`CloudSimulation`].
+#[pyclass(unsendable, name = "CloudSimulation")]
+pub struct PyCloudSimulation {
+    cloud: CloudSimulation,
+}
+
+impl PyCloudSimulation {
+    /// Returns the reference to wrapped cloud simulation.
+    pub fn cloud(&mut self) -> &mut CloudSimulation {
+        &mut self.cloud
+    }
+
+    fn component_id(&self, name: &str, ids: impl IntoIterator<Item = u32>) -> Option<u32> {
+        ids.into_iter().find(|id| self.cloud.context().lookup_name(*id) == name)
+    }
+
+    fn scheduler_id(&self, name: &str) -> PyResult<u32> {
+        self.component_id(name, self.cloud.schedulers().into_keys())
+            .ok_or_else(|| PyValueError::new_err(format!("Scheduler {} is not found", name)))
+    }
+}
+
+#[pymethods]
+impl PyCloudSimulation {
+    /// Creates simulation from simulation config file.
+    ///
+    /// The `algorithms` dict maps names to Python placement algorithms,
+    /// which can be referenced in config along with the built-in algorithms.
+    #[new]
+    #[pyo3(signature = (config, seed = 123, algorithms = None))]
+    pub fn new(config: &str, seed: u64, algorithms: Option<&PyDict>) -> PyResult<Self> {
+        let sim_config = SimulationConfig::try_from_file(config).map_err(PyValueError::new_err)?;
+        let cloud = CloudSimulation::with_placement_algorithms(
+            Simulation::new(seed),
+            sim_config,
+            Box::new(StdoutLogger::new()),
+            placement_algorithms(algorithms)?,
+        );
+        Ok(Self { cloud })
+    }
+
+    /// Creates simulation from scenario description file (see [`ScenarioBuilder::from_file`]).
+    #[staticmethod]
+    #[pyo3(signature = (scenario, seed = 123, algorithms = None))]
+    pub fn from_scenario(scenario: &str, seed: u64, algorithms: Option<&PyDict>) -> PyResult<Self> {
+        let registry = placement_algorithms(algorithms)?;
+        let scenario = ScenarioBuilder::from_file(scenario)
+            .and_then(|builder| builder.placement_algorithms(registry).build(Simulation::new(seed)))
+            .map_err(PyValueError::new_err)?;
+        Ok(Self { cloud: scenario.cloud })
+    }
+
+    /// Registers Python placement algorithm, which can be then used in schedulers added via
+    /// [`add_scheduler`](Self::add_scheduler).
+    pub fn register_placement_algorithm(&mut self, name: &str, callback: &PyAny) -> PyResult<()> {
+        let callback = checked_callback(name, callback)?;
+        self.cloud.register_placement_algorithm(name, move |_| {
+            VMPlacementAlgorithm::single(PyPlacementAlgorithm::new(callback.clone()))
+        });
+        Ok(())
+    }
+
+    /// Adds host with specified name, resource capacity and optional type, returns host ID.
+    #[pyo3(signature = (name, cpus, memory, host_type = None))]
+    pub fn add_host(&mut self, name: &str, cpus: u32, memory: u64, host_type: Option<&str>) -> u32 {
+        match host_type {
+            Some(host_type) => self.cloud.add_host_with_type(name, cpus, memory, host_type),
+            None => self.cloud.add_host(name, cpus, memory),
+        }
+    }
+
+    /// Adds scheduler with placement algorithm specified as config value string, e.g. `BestFit`
+    /// or the name of registered Python algorithm, returns scheduler ID.
+    pub fn add_scheduler(&mut self, name: &str, algorithm: &str) -> u32 {
+        let algorithm = self.cloud.resolve_placement_algorithm(algorithm);
+        self.cloud.add_scheduler(name, algorithm)
+    }
+
+    /// Submits VM with specified resource requirements and lifetime to the scheduler with specified name.
+    /// The VM resource utilization is constant and specified as fraction of requested resources. Returns VM ID.
+    #[pyo3(signature = (cpus, memory, lifetime, scheduler, cpu_load = 1.0, memory_load = 1.0, delay = 0.0))]
+    #[allow(clippy::too_many_arguments)]
+    pub fn spawn_vm(
+        &mut self,
+        cpus: u32,
+        memory: u64,
+        lifetime: f64,
+        scheduler: &str,
+        cpu_load: f64,
+        memory_load: f64,
+        delay: f64,
+    ) -> PyResult<u32> {
+        let scheduler_id = self.scheduler_id(scheduler)?;
+        let consumer = ResourceConsumer::with_const_load(cpus, memory, cpu_load, memory_load);
+        Ok(self
+            .cloud
+            .spawn_vm_with_delay(consumer, lifetime, None, scheduler_id, delay))
+    }
+
+    /// Runs the simulation until the specified time.
+    pub fn step_until_time(&mut self, time: f64) {
+        self.cloud.step_until_time(time);
+    }
+
+    /// Runs the simulation for the specified duration.
+    pub fn step_for_duration(&mut self, duration: f64) {
+        self.cloud.step_for_duration(duration);
+    }
+
+    /// Returns the current simulation time.
+    pub fn current_time(&mut self) -> f64 {
+        self.cloud.current_time()
+    }
+
+    /// Returns the status of specified VM.
+    pub fn vm_status(&self, vm_id: u32) -> String {
+        self.cloud.vm_status(vm_id).to_string()
+    }
+
+    /// Returns the name of host running the specified VM.
+    pub fn vm_location(&self, vm_id: u32) -> Option<String> {
+        self.cloud
+            .vm_location(vm_id)
+            .map(|host_id| self.cloud.context().lookup_name(host_id))
+    }
+
+    /// Enables recording of the timeline (see [`dslab_iaas::core::timeline`]).
+    pub fn enable_timeline(&mut self) {
+        self.cloud.enable_timeline();
+    }
+
+    /// Returns the recorded timeline in JSON format or `None` if the timeline is not enabled.
+    pub fn timeline_json(&mut self) -> Option<String> {
+        let time = self.cloud.current_time();
+        self.cloud.timeline().map(|timeline| timeline.borrow().to_json(time))
+    }
+
+    /// Saves the recorded timeline as HTML page with Gantt chart.
+    pub fn save_timeline_html(&mut self, path: &str) -> PyResult<()> {
+        let time = self.cloud.current_time();
+        let timeline = self
+            .cloud
+            .timeline()
+            .ok_or_else(|| PyValueError::new_err("Timeline is not enabled"))?;
+        let result = timeline.borrow().save_html(path, time);
+        result.map_err(|e| PyIOError::new_err(e.to_string()))
+    }
+
+    /// Returns the table with current state and energy consumption of hosts.
+    pub fn host_table<'py>(&mut self, py: Python<'py>) -> PyResult<&'py PyDict> {
+        let time = self.cloud.current_time();
+        let hosts = self.cloud.hosts();
+        let mut ids = Vec::new();
+        let mut names = Vec::new();
+        let mut types = Vec::new();
+        let mut cpu_total = Vec::new();
+        let mut memory_total = Vec::new();
+        let mut cpu_allocated = Vec::new();
+        let mut memory_allocated = Vec::new();
+        let mut cpu_load = Vec::new();
+        let mut memory_load = Vec::new();
+        let mut power_state = Vec::new();
+        let mut energy = Vec::new();
+        for (id, host) in hosts {
+            let mut host = host.borrow_mut();
+            ids.push(id);
+            names.push(self.cloud.context().lookup_name(id));
+            types.push(host.host_type.clone());
+            cpu_total.push(host.cpu_total());
+            memory_total.push(host.memory_total());
+            cpu_allocated.push(host.cpu_allocated());
+            memory_allocated.push(host.memory_allocated());
+            cpu_load.push(host.cpu_load(time));
+            memory_load.push(host.memory_load(time));
+            power_state.push(format!("{:?}", host.power_state()));
+            energy.push(host.get_total_energy_consumed(time));
+        }
+        let table = PyDict::new(py);
+        table.set_item("id", ids)?;
+        table.set_item("name", names)?;
+        table.set_item("type", types)?;
+        table.set_item("cpu_total", cpu_total)?;
+        table.set_item("memory_total", memory_total)?;
+        table.set_item("cpu_allocated", cpu_allocated)?;
+        table.set_item("memory_allocated", memory_allocated)?;
+        table.set_item("cpu_load", cpu_load)?;
+        table.set_item("memory_load", memory_load)?;
+        table.set_item("power_state", power_state)?;
+        table.set_item("energy", energy)?;
+        Ok(table)
+    }
+
+    /// Returns the table with properties and current status of VMs.
+    pub fn vm_table<'py>(&self, py: Python<'py>) -> PyResult<&'py PyDict> {
+        let vm_api = self.cloud.vm_api();
+        let vm_api = vm_api.borrow();
+        let mut ids = Vec::new();
+        let mut cpu = Vec::new();
+        let mut memory = Vec::new();
+        let mut lifetime = Vec::new();
+        let mut status = Vec::new();
+        let mut host = Vec::new();
+        let mut allocation_start_time = Vec::new();
+        let mut start_time = Vec::new();
+        let mut migration_downtime = Vec::new();
+        for vm_id in vm_api.get_vm_ids() {
+            let vm = vm_api.get_vm(vm_id);
+            let vm = vm.borrow();
+            ids.push(vm_id);
+            cpu.push(vm.cpu_usage);
+            memory.push(vm.memory_usage);
+            lifetime.push(vm.lifetime());
+            status.push(vm_api.get_vm_status(vm_id).to_string());
+            host.push(
+                vm_api
+                    .find_host_by_vm(vm_id)
+                    .map(|host_id| self.cloud.context().lookup_name(host_id)),
+            );
+            allocation_start_time.push(vm.allocation_start_time);
+            start_time.push(vm.start_time());
+            migration_downtime.push(vm.migration_downtime);
+        }
+        let table = PyDict::new(py);
+        table.set_item("id", ids)?;
+        table.set_item("cpu", cpu)?;
+        table.set_item("memory", memory)?;
+        table.set_item("lifetime", lifetime)?;
+        table.set_item("status", status)?;
+        table.set_item("host", host)?;
+        table.set_item("allocation_start_time", allocation_start_time)?;
+        table.set_item("start_time", start_time)?;
+        table.set_item("migration_downtime", migration_downtime)?;
+        Ok(table)
+    }
+
+    /// Returns the table with energy consumption of hosts split into components.
+    pub fn energy_table<'py>(&self, py: Python<'py>) -> PyResult<&'py PyDict> {
+        let report = self.cloud.energy_report();
+        let mut hosts = Vec::new();
+        let mut idle = Vec::new();
+        let mut dynamic = Vec::new();
+        let mut inactive = Vec::new();
+        let mut transition = Vec::new();
+        let mut cooling = Vec::new();
+        let mut total = Vec::new();
+        for (host, breakdown) in report.hosts {
+            hosts.push(host);
+            idle.push(breakdown.idle);
+            dynamic.push(breakdown.dynamic);
+            inactive.push(breakdown.inactive);
+            transition.push(breakdown.transition);
+            cooling.push(breakdown.cooling);
+            total.push(breakdown.total());
+        }
+        let table = PyDict::new(py);
+        table.set_item("host", hosts)?;
+        table.set_item("idle", idle)?;
+        table.set_item("dynamic", dynamic)?;
+        table.set_item("inactive", inactive)?;
+        table.set_item("transition", transition)?;
+        table.set_item("cooling", cooling)?;
+        table.set_item("total", total)?;
+        Ok(table)
+    }
+
+    /// Returns the table with mean and peak resource utilization of hosts.
+    pub fn utilization_table<'py>(&self, py: Python<'py>) -> PyResult<&'py PyDict> {
+        let report = self.cloud.utilization_report();
+        let mut hosts = Vec::new();
+        let mut mean_cpu = Vec::new();
+        let mut peak_cpu = Vec::new();
+        let mut mean_memory = Vec::new();
+        let mut peak_memory = Vec::new();
+        for (host_id, summary) in report.hosts {
+            hosts.push(self.cloud.context().lookup_name(host_id));
+            mean_cpu.push(summary.mean_cpu_utilization);
+            peak_cpu.push(summary.peak_cpu_utilization);
+            mean_memory.push(summary.mean_memory_utilization);
+            peak_memory.push(summary.peak_memory_utilization);
+        }
+        let table = PyDict::new(py);
+        table.set_item("host", hosts)?;
+        table.set_item("mean_cpu_utilization", mean_cpu)?;
+        table.set_item("peak_cpu_utilization", peak_cpu)?;
+        table.set_item("mean_memory_utilization", mean_memory)?;
+        table.set_item("peak_memory_utilization", peak_memory)?;
+        Ok(table)
+    }
+
+    /// Same as [`host_table`](Self::host_table), but returns pandas dataframe.
+    pub fn host_dataframe(&mut self, py: Python) -> PyResult<PyObject> {
+        to_dataframe(py, self.host_table(py)?)
+    }
+
+    /// Same as [`vm_table`](Self::vm_table), but returns pandas dataframe.
+    pub fn vm_dataframe(&self, py: Python) -> PyResult<PyObject> {
+        to_dataframe(py, self.vm_table(py)?)
+    }
+
+    /// Same as [`energy_table`](Self::energy_table), but returns pandas dataframe.
+    pub fn energy_dataframe(&self, py: Python) -> PyResult<PyObject> {
+        to_dataframe(py, self.energy_table(py)?)
+    }
+
+    /// Same as [`utilization_table`](Self::utilization_table), but returns pandas dataframe.
+    pub fn utilization_dataframe(&self, py: Python) -> PyResult<PyObject> {
+        to_dataframe(py, self.utilization_table(py)?)
+    }
+}
+
+/// Python module with DSLab IaaS bindings.
+#[pymodule]
+#[pyo3(name = "dslab_iaas")]
+pub fn py_module(_py: Python, m: &PyModule) -> PyResult<()> {
+    m.add_class::<PyCloudSimulation>()?;
+    Ok(())
+}
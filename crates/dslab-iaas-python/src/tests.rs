@@ -0,0 +1,116 @@
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyModule};
+
+use crate::{py_module, PyCloudSimulation};
+
+const CONFIG: &str = "../dslab-iaas/test-configs/config_zero_latency.yaml";
+const SCENARIO: &str = "../dslab-iaas/test-configs/scenario.yaml";
+
+// Selects the host with maximum ID among the hosts with enough resources.
+const LAST_FIT: &str = r#"
+def last_fit(vm, hosts):
+    suitable = [h for h in hosts if h["cpu_available"] >= vm["cpu"] and h["memory_available"] >= vm["memory"]]
+    return max(h["id"] for h in suitable) if suitable else None
+"#;
+
+#[test]
+fn test_python_placement_algorithm() {
+    Python::with_gil(|py| {
+        let module = PyModule::from_code(py, LAST_FIT, "last_fit.py", "last_fit").unwrap();
+        let algorithms = PyDict::new(py);
+        algorithms
+            .set_item("LastFit", module.getattr("last_fit").unwrap())
+            .unwrap();
+
+        let mut sim = PyCloudSimulation::new(CONFIG, 123, Some(algorithms)).unwrap();
+        sim.add_host("h1", 10, 10, None);
+        sim.add_host("h2", 10, 10, None);
+        sim.add_scheduler("s", "LastFit");
+        let vm1 = sim.spawn_vm(8, 8, 10., "s", 1., 1., 0.).unwrap();
+        let vm2 = sim.spawn_vm(8, 8, 10., "s", 1., 1., 0.).unwrap();
+        let vm3 = sim.spawn_vm(8, 8, 10., "s", 1., 1., 0.).unwrap();
+        sim.step_for_duration(5.);
+
+        assert_eq!(sim.vm_status(vm1), "running");
+        assert_eq!(sim.vm_location(vm1), Some("h2".to_string()));
+        assert_eq!(sim.vm_location(vm2), Some("h1".to_string()));
+        assert_eq!(sim.vm_status(vm3), "initializing");
+        assert_eq!(sim.vm_location(vm3), None);
+    });
+}
+
+#[test]
+fn test_unknown_scheduler() {
+    Python::with_gil(|py| {
+        let mut sim = PyCloudSimulation::new(CONFIG, 123, None).unwrap();
+        let err = sim.spawn_vm(1, 1, 10., "s", 1., 1., 0.).unwrap_err();
+        assert_eq!(err.value(py).to_string(), "Scheduler s is not found");
+    });
+}
+
+#[test]
+fn test_python_api() {
+    Python::with_gil(|py| {
+        let module = PyModule::new(py, "dslab_iaas").unwrap();
+        py_module(py, module).unwrap();
+        let globals = PyDict::new(py);
+        globals.set_item("dslab_iaas", module).unwrap();
+        globals.set_item("CONFIG", CONFIG).unwrap();
+        py.run(
+            r#"
+import json
+
+sim = dslab_iaas.CloudSimulation(CONFIG, seed=123)
+sim.register_placement_algorithm("First", lambda vm, hosts: hosts[0]["id"])
+sim.add_host("h1", 16, 16, host_type="big")
+sim.add_scheduler("s", "First")
+sim.enable_timeline()
+vm = sim.spawn_vm(4, 8, 10.0, "s", cpu_load=0.5, delay=1.0)
+sim.step_until_time(20.0)
+assert sim.current_time() <= 20.0
+
+hosts = sim.host_table()
+assert hosts["name"] == ["h1"]
+assert hosts["type"] == ["big"]
+assert hosts["cpu_allocated"] == [0.0]
+
+vms = sim.vm_table()
+assert vms["id"] == [vm]
+assert vms["status"] == ["finished"]
+assert vms["allocation_start_time"] == [1.0]
+assert vms["lifetime"] == [10.0]
+
+energy = sim.energy_table()
+assert energy["host"] == ["h1"]
+assert energy["total"][0] > 0
+
+utilization = sim.utilization_table()
+assert utilization["host"] == ["h1"]
+assert 0 < utilization["peak_cpu_utilization"][0] <= 0.125
+
+timeline = json.loads(sim.timeline_json())
+assert [host["name"] for host in timeline["hosts"]] == ["h1"]
+assert timeline["vm_intervals"][0]["vm_id"] == vm
+"#,
+            Some(globals),
+            None,
+        )
+        .unwrap_or_else(|e| {
+            e.print(py);
+            panic!("Python test failed");
+        });
+    });
+}
+
+#[test]
+fn test_from_scenario() {
+    Python::with_gil(|py| {
+        let mut sim = PyCloudSimulation::from_scenario(SCENARIO, 123, None).unwrap();
+        let hosts = sim.host_table(py).unwrap();
+        let names: Vec<String> = hosts.get_item("name").unwrap().extract().unwrap();
+        assert_eq!(names, vec!["h1", "h2", "h3"]);
+        let vm = sim.spawn_vm(2, 2, 10., "s", 1., 1., 0.).unwrap();
+        sim.step_for_duration(1.);
+        assert_eq!(sim.vm_status(vm), "running");
+    });
+}
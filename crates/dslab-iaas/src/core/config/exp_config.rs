@@ -9,10 +9,14 @@ use serde::{Deserialize, Serialize};
 use sugars::{rc, refcell};
 
 use crate::core::config::dynamic_variable::{DynVar, GenericDynVar, GenericValues, NumericValues};
-use crate::core::config::sim_config::{HostConfig, SchedulerConfig, SimulationConfig, VmDatasetConfig};
+use crate::core::config::sim_config::{
+    ControlPlaneConfig, HostConfig, SchedulerConfig, SimulationConfig, TenantConfig, VmDatasetConfig,
+};
+use crate::core::config::yaml::parse_yaml_config;
 
 /// Holds raw experiment config parsed from YAML file.
 #[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 struct RawExperimentConfig {
     pub send_stats_period: Option<NumericValues<f64>>,
     pub message_delay: Option<NumericValues<f64>>,
@@ -21,16 +25,21 @@ struct RawExperimentConfig {
     pub vm_stop_duration: Option<NumericValues<f64>>,
     pub allow_vm_overcommit: Option<bool>,
     pub network_throughput: Option<NumericValues<u64>>,
+    pub migration_max_rounds: Option<u32>,
+    pub migration_max_downtime: Option<f64>,
     pub simulation_length: Option<NumericValues<f64>>,
     pub step_duration: Option<NumericValues<f64>>,
     pub vm_allocation_timeout: Option<NumericValues<f64>>,
     pub trace: Option<GenericValues<VmDatasetConfig>>,
     pub hosts: Option<Vec<HostConfig>>,
     pub schedulers: Option<Vec<RawSchedulerConfig>>,
+    pub tenants: Option<Vec<TenantConfig>>,
+    pub control_plane: Option<ControlPlaneConfig>,
 }
 
 /// Holds raw scheduler config read from YAML file.
 #[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 struct RawSchedulerConfig {
     /// Scheduler name. Should be set if count = 1
     pub name: Option<String>,
@@ -41,6 +50,12 @@ struct RawSchedulerConfig {
     pub algorithm: GenericValues<String>,
     /// number of such schedulers
     pub count: Option<NumericValues<u32>>,
+    /// Retry policy for failed placements
+    pub retry_policy: Option<GenericValues<String>>,
+    /// Policy for preemption of lower-priority VMs
+    pub preemption_policy: Option<String>,
+    /// Whether to dispatch requests according to Dominant Resource Fairness
+    pub fair_sharing: Option<bool>,
 }
 
 /// Internal structure holding the current experiment config state,
@@ -54,22 +69,29 @@ struct ConfigState {
     pub vm_stop_duration: Rc<RefCell<GenericDynVar<f64>>>,
     pub allow_vm_overcommit: bool,
     pub network_throughput: Rc<RefCell<GenericDynVar<u64>>>,
+    pub migration_max_rounds: u32,
+    pub migration_max_downtime: f64,
     pub simulation_length: Rc<RefCell<GenericDynVar<f64>>>,
     pub step_duration: Rc<RefCell<GenericDynVar<f64>>>,
     pub vm_allocation_timeout: Rc<RefCell<GenericDynVar<f64>>>,
     pub trace: Option<Rc<RefCell<GenericDynVar<VmDatasetConfig>>>>,
     pub hosts: Vec<HostConfig>,
     pub schedulers: Vec<SchedulerConfigState>,
+    pub tenants: Vec<TenantConfig>,
+    pub control_plane: ControlPlaneConfig,
 }
 
 /// Internal structure holding the current scheduler config state,
-/// including dynamic variables for scheduler algorithm and count.
+/// including dynamic variables for scheduler algorithm, count and retry policy.
 #[derive(Debug)]
 struct SchedulerConfigState {
     pub name: Option<String>,
     pub name_prefix: Option<String>,
     pub algorithm: Rc<RefCell<GenericDynVar<String>>>,
     pub count: Rc<RefCell<GenericDynVar<u32>>>,
+    pub retry_policy: Option<Rc<RefCell<GenericDynVar<String>>>>,
+    pub preemption_policy: Option<String>,
+    pub fair_sharing: Option<bool>,
 }
 
 /// Represents experiment configuration and allows to obtain configurations of simulation runs.
@@ -82,11 +104,11 @@ pub struct ExperimentConfig {
 impl ExperimentConfig {
     /// Creates experiment config by reading parameter values from YAML file
     /// (uses default values if some parameters are absent).
+    ///
+    /// The file can include other config files, see [`yaml`](crate::core::config::yaml) module for details.
     pub fn from_file(file_name: &str) -> Self {
-        let current_state_raw: RawExperimentConfig = serde_yaml::from_str(
-            &std::fs::read_to_string(file_name).unwrap_or_else(|_| panic!("Can't read file {}", file_name)),
-        )
-        .unwrap_or_else(|err| panic!("Can't parse YAML from file {}: {}", file_name, err));
+        let current_state_raw: RawExperimentConfig =
+            parse_yaml_config(file_name).unwrap_or_else(|err| panic!("{}", err));
 
         let mut dyn_vars = Vec::<Rc<RefCell<dyn DynVar>>>::new();
 
@@ -187,12 +209,23 @@ impl ExperimentConfig {
             if count.borrow().has_multiple_values() {
                 dyn_vars.push(count.clone());
             }
+            let retry_policy = scheduler
+                .retry_policy
+                .map(|values| rc!(refcell!(GenericDynVar::new("retry_policy", values))));
+            if let Some(retry_policy) = &retry_policy {
+                if retry_policy.borrow().has_multiple_values() {
+                    dyn_vars.push(retry_policy.clone());
+                }
+            }
 
             schedulers.push(SchedulerConfigState {
                 name: scheduler.name,
                 name_prefix: scheduler.name_prefix,
                 algorithm,
                 count,
+                retry_policy,
+                preemption_policy: scheduler.preemption_policy,
+                fair_sharing: scheduler.fair_sharing,
             });
         }
 
@@ -204,12 +237,16 @@ impl ExperimentConfig {
             vm_stop_duration,
             allow_vm_overcommit: current_state_raw.allow_vm_overcommit.unwrap_or(false),
             network_throughput,
+            migration_max_rounds: current_state_raw.migration_max_rounds.unwrap_or(30),
+            migration_max_downtime: current_state_raw.migration_max_downtime.unwrap_or(0.3),
             simulation_length,
             step_duration,
             vm_allocation_timeout,
             trace,
             hosts: current_state_raw.hosts.unwrap_or_default(),
             schedulers,
+            tenants: current_state_raw.tenants.unwrap_or_default(),
+            control_plane: current_state_raw.control_plane.unwrap_or_default(),
         };
 
         Self {
@@ -232,6 +269,9 @@ impl ExperimentConfig {
                 name_prefix: scheduler.name_prefix.clone(),
                 algorithm: scheduler.algorithm.borrow().value(),
                 count: Some(scheduler.count.borrow().value()),
+                retry_policy: scheduler.retry_policy.as_ref().map(|p| p.borrow().value()),
+                preemption_policy: scheduler.preemption_policy.clone(),
+                fair_sharing: scheduler.fair_sharing,
             });
         }
 
@@ -240,7 +280,7 @@ impl ExperimentConfig {
             trace = Some(self.current_state.trace.as_ref().unwrap().borrow().value());
         }
 
-        Some(SimulationConfig {
+        let config = SimulationConfig {
             send_stats_period: self.current_state.send_stats_period.borrow().value(),
             message_delay: self.current_state.message_delay.borrow().value(),
             allocation_retry_period: self.current_state.allocation_retry_period.borrow().value(),
@@ -248,13 +288,21 @@ impl ExperimentConfig {
             vm_stop_duration: self.current_state.vm_stop_duration.borrow().value(),
             allow_vm_overcommit: self.current_state.allow_vm_overcommit,
             network_throughput: self.current_state.network_throughput.borrow().value(),
+            migration_max_rounds: self.current_state.migration_max_rounds,
+            migration_max_downtime: self.current_state.migration_max_downtime,
             simulation_length: self.current_state.simulation_length.borrow().value(),
             step_duration: self.current_state.step_duration.borrow().value(),
             vm_allocation_timeout: self.current_state.vm_allocation_timeout.borrow().value(),
             trace,
             hosts: self.current_state.hosts.clone(),
             schedulers,
-        })
+            tenants: self.current_state.tenants.clone(),
+            control_plane: self.current_state.control_plane.clone(),
+        };
+        if let Err(err) = config.validate() {
+            panic!("Invalid experiment config for run {:?}: {}", self, err);
+        }
+        Some(config)
     }
 
     /// Switches to next simulation run.
@@ -2,3 +2,4 @@ mod dynamic_variable;
 pub mod exp_config;
 pub mod options;
 pub mod sim_config;
+pub mod yaml;
@@ -2,10 +2,15 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::core::config::yaml::parse_yaml_config;
+use crate::core::control_plane::Delay;
+use crate::core::quota::{QuotaExceededAction, TenantQuota};
+use crate::core::rate_limit::{ClientRetryPolicy, RateLimit};
 use crate::extensions::dataset_type::VmDatasetType;
 
 /// Holds raw simulation config parsed from YAML file.
 #[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
 struct RawSimulationConfig {
     pub send_stats_period: Option<f64>,
     pub message_delay: Option<f64>,
@@ -14,16 +19,21 @@ struct RawSimulationConfig {
     pub vm_stop_duration: Option<f64>,
     pub allow_vm_overcommit: Option<bool>,
     pub network_throughput: Option<u64>,
+    pub migration_max_rounds: Option<u32>,
+    pub migration_max_downtime: Option<f64>,
     pub simulation_length: Option<f64>,
     pub step_duration: Option<f64>,
     pub vm_allocation_timeout: Option<f64>,
     pub trace: Option<VmDatasetConfig>,
     pub hosts: Option<Vec<HostConfig>>,
     pub schedulers: Option<Vec<SchedulerConfig>>,
+    pub tenants: Option<Vec<TenantConfig>>,
+    pub control_plane: Option<ControlPlaneConfig>,
 }
 
 /// Holds information about the used VM trace dataset.
 #[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
 pub struct VmDatasetConfig {
     /// Dataset type.
     pub r#type: VmDatasetType,
@@ -39,6 +49,7 @@ impl std::fmt::Display for VmDatasetConfig {
 
 /// Holds configuration of a single physical host or a set of identical hosts.
 #[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
 pub struct HostConfig {
     /// Host name.
     /// Should be set if count = 1.
@@ -53,10 +64,17 @@ pub struct HostConfig {
     pub memory: u64,
     /// Number of such hosts.
     pub count: Option<u32>,
+    /// Host type, which can be used to distinguish groups of hosts with different characteristics.
+    /// If not set, the host is not assigned to any type.
+    pub host_type: Option<String>,
+    /// Host power model specified as config value string, e.g. `Linear[min_power=0.4,max_power=1]`.
+    /// If not set, the default host power model of the simulation is used.
+    pub power_model: Option<String>,
 }
 
 /// Holds configuration of a single scheduler or a set of identically configured schedulers.
 #[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
 pub struct SchedulerConfig {
     /// Scheduler name.
     /// Should be set if count = 1.
@@ -65,10 +83,104 @@ pub struct SchedulerConfig {
     /// Full name is produced by appending scheduler instance number to the prefix.
     /// Should be set if count > 1.
     pub name_prefix: Option<String>,
-    /// VM placement algorithm used by scheduler(s).
+    /// VM placement algorithm used by scheduler(s) specified as config value string, e.g. `BestFit`.
+    /// Custom algorithms can be referenced by name after registering them in [`PlacementAlgorithmRegistry`].
+    ///
+    /// [`PlacementAlgorithmRegistry`]: crate::core::vm_placement_algorithm::PlacementAlgorithmRegistry
     pub algorithm: String,
     /// Number of such schedulers.
     pub count: Option<u32>,
+    /// Retry policy for failed placements specified as config value string,
+    /// e.g. `ExponentialBackoff[initial_delay=1,multiplier=2,max_delay=60,max_attempts=10]`.
+    /// If not set, failed requests are retried after `allocation_retry_period`.
+    pub retry_policy: Option<String>,
+    /// Policy for preemption of lower-priority VMs specified as config value string, e.g. `Migrate[max_victims=2]`.
+    /// If not set, preemption is disabled.
+    pub preemption_policy: Option<String>,
+    /// Whether the scheduler dispatches requests according to Dominant Resource Fairness.
+    /// If not set, fair sharing is disabled.
+    pub fair_sharing: Option<bool>,
+}
+
+/// Holds configuration of tenant quota and API rate limit.
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct TenantConfig {
+    /// Tenant name.
+    pub name: String,
+    /// Maximum total number of vCPUs used by tenant VMs (unlimited if not set).
+    pub max_cpu: Option<u32>,
+    /// Maximum total memory used by tenant VMs (unlimited if not set).
+    pub max_memory: Option<u64>,
+    /// Maximum number of tenant VMs (unlimited if not set).
+    pub max_vm_count: Option<u32>,
+    /// Action applied to requests exceeding the quota (`Reject` or `Queue`, `Reject` by default).
+    pub on_quota_exceeded: Option<QuotaExceededAction>,
+    /// Sustained number of API requests per second (unlimited if not set).
+    pub api_rate_limit: Option<f64>,
+    /// Maximum number of API requests accepted in a burst, 1 by default.
+    pub api_burst: Option<f64>,
+    /// Behavior of tenant clients upon API throttling (see [`ClientRetryPolicy`] for defaults).
+    pub api_retry_policy: Option<ClientRetryPolicy>,
+}
+
+/// Holds configuration of control-plane latencies.
+///
+/// Each delay is specified as config value string, e.g. `0.1`, `Uniform[min=0.1,max=0.2]`, `Exponential[mean=0.1]`
+/// or `LogNormal[mu=-2,sigma=0.5]` (see [`Delay`]).
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ControlPlaneConfig {
+    /// Time spent by scheduler to process a single allocation request (zero by default).
+    /// Scheduler processes requests sequentially, so the requests arriving while scheduler is busy are delayed.
+    pub scheduling_time: Option<String>,
+    /// VM start (boot) duration, `vm_start_duration` by default.
+    pub vm_start_duration: Option<String>,
+    /// Delay of messages sent by schedulers to placement store and other components, `message_delay` by default.
+    pub scheduler_delay: Option<String>,
+    /// Delay of messages sent by placement store to hosts and schedulers, `message_delay` by default.
+    pub placement_store_delay: Option<String>,
+    /// Delay of messages sent by hosts to placement store and VM API, `message_delay` by default.
+    pub host_delay: Option<String>,
+}
+
+impl ControlPlaneConfig {
+    /// Checks that all configured delays can be parsed.
+    pub fn validate(&self) -> Result<(), String> {
+        let delays = [
+            ("scheduling_time", &self.scheduling_time),
+            ("vm_start_duration", &self.vm_start_duration),
+            ("scheduler_delay", &self.scheduler_delay),
+            ("placement_store_delay", &self.placement_store_delay),
+            ("host_delay", &self.host_delay),
+        ];
+        for (key, value) in delays {
+            if let Some(value) = value {
+                Delay::parse(value).map_err(|err| format!("`control_plane.{}`: {}", key, err))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl TenantConfig {
+    /// Returns tenant quota defined by this config.
+    pub fn quota(&self) -> TenantQuota {
+        TenantQuota {
+            max_cpu: self.max_cpu,
+            max_memory: self.max_memory,
+            max_vm_count: self.max_vm_count,
+            on_exceeded: self.on_quota_exceeded.unwrap_or_default(),
+        }
+    }
+
+    /// Returns tenant API rate limit defined by this config (if any).
+    pub fn rate_limit(&self) -> Option<RateLimit> {
+        self.api_rate_limit.map(|rate| {
+            RateLimit::new(rate, self.api_burst.unwrap_or(1.))
+                .with_retry_policy(self.api_retry_policy.clone().unwrap_or_default())
+        })
+    }
 }
 
 /// Represents simulation configuration.
@@ -87,8 +199,13 @@ pub struct SimulationConfig {
     /// Whether to schedule VMs based on real resource utilization instead of allocated resources.
     pub allow_vm_overcommit: bool,
     /// Network throughput in GB/s.
-    /// Currently used to compute VM migration duration.
+    /// Used to compute VM migration duration if the hosts are not connected via network.
     pub network_throughput: u64,
+    /// Maximum number of pre-copy rounds during VM live migration.
+    pub migration_max_rounds: u32,
+    /// Target downtime in seconds of VM live migration,
+    /// the pre-copy phase stops when the remaining memory can be copied within this time.
+    pub migration_max_downtime: f64,
     /// Length of simulation in seconds (for public datasets only).
     pub simulation_length: f64,
     /// Duration in seconds between simulation steps.
@@ -101,18 +218,27 @@ pub struct SimulationConfig {
     pub hosts: Vec<HostConfig>,
     /// Configurations of VM schedulers.
     pub schedulers: Vec<SchedulerConfig>,
+    /// Tenant quotas.
+    pub tenants: Vec<TenantConfig>,
+    /// Control-plane latencies.
+    pub control_plane: ControlPlaneConfig,
 }
 
 impl SimulationConfig {
-    /// Creates simulation config by reading parameter values from YAM file
+    /// Creates simulation config by reading parameter values from YAML file
     /// (uses default values if some parameters are absent).
+    ///
+    /// The file can include other config files, see [`yaml`](crate::core::config::yaml) module for details.
+    /// Panics with a message pointing to the offending key if the config is invalid.
     pub fn from_file(file_name: &str) -> Self {
-        let raw: RawSimulationConfig = serde_yaml::from_str(
-            &std::fs::read_to_string(file_name).unwrap_or_else(|_| panic!("Can't read file {}", file_name)),
-        )
-        .unwrap_or_else(|_| panic!("Can't parse YAML from file {}", file_name));
+        Self::try_from_file(file_name).unwrap_or_else(|err| panic!("{}", err))
+    }
 
-        Self {
+    /// Same as [`from_file`](Self::from_file), but returns an error instead of panicking.
+    pub fn try_from_file(file_name: &str) -> Result<Self, String> {
+        let raw: RawSimulationConfig = parse_yaml_config(file_name)?;
+
+        let config = Self {
             send_stats_period: raw.send_stats_period.unwrap_or(0.5),
             message_delay: raw.message_delay.unwrap_or(0.2),
             allocation_retry_period: raw.allocation_retry_period.unwrap_or(1.0),
@@ -120,12 +246,97 @@ impl SimulationConfig {
             vm_stop_duration: raw.vm_stop_duration.unwrap_or(0.5),
             allow_vm_overcommit: raw.allow_vm_overcommit.unwrap_or(false),
             network_throughput: raw.network_throughput.unwrap_or(1),
+            migration_max_rounds: raw.migration_max_rounds.unwrap_or(30),
+            migration_max_downtime: raw.migration_max_downtime.unwrap_or(0.3),
             simulation_length: raw.simulation_length.unwrap_or(0.),
             step_duration: raw.step_duration.unwrap_or(500.),
             vm_allocation_timeout: raw.vm_allocation_timeout.unwrap_or(50.),
             trace: raw.trace,
             hosts: raw.hosts.unwrap_or_default(),
             schedulers: raw.schedulers.unwrap_or_default(),
+            tenants: raw.tenants.unwrap_or_default(),
+            control_plane: raw.control_plane.unwrap_or_default(),
+        };
+        config
+            .validate()
+            .map_err(|err| format!("Invalid config {}: {}", file_name, err))?;
+        Ok(config)
+    }
+
+    /// Checks the config values for consistency and returns the error pointing to the offending key if any.
+    pub fn validate(&self) -> Result<(), String> {
+        let non_negative = [
+            ("send_stats_period", self.send_stats_period),
+            ("message_delay", self.message_delay),
+            ("allocation_retry_period", self.allocation_retry_period),
+            ("vm_start_duration", self.vm_start_duration),
+            ("vm_stop_duration", self.vm_stop_duration),
+            ("simulation_length", self.simulation_length),
+            ("step_duration", self.step_duration),
+            ("vm_allocation_timeout", self.vm_allocation_timeout),
+            ("migration_max_downtime", self.migration_max_downtime),
+        ];
+        for (key, value) in non_negative {
+            if value < 0. || value.is_nan() {
+                return Err(format!("`{}` should be non-negative, got {}", key, value));
+            }
+        }
+        if self.send_stats_period == 0. {
+            return Err("`send_stats_period` should be positive".to_string());
+        }
+        if self.network_throughput == 0 {
+            return Err("`network_throughput` should be positive".to_string());
         }
+        if self.migration_max_rounds == 0 {
+            return Err("`migration_max_rounds` should be positive".to_string());
+        }
+        for (i, host) in self.hosts.iter().enumerate() {
+            let key = format!("hosts[{}]", i);
+            validate_naming(&key, &host.name, &host.name_prefix, host.count)?;
+            if host.cpus == 0 {
+                return Err(format!("`{}.cpus` should be positive", key));
+            }
+            if host.memory == 0 {
+                return Err(format!("`{}.memory` should be positive", key));
+            }
+            if host.power_model.is_some() && host.host_type.is_none() {
+                return Err(format!("`{}.host_type` should be set along with `power_model`", key));
+            }
+        }
+        self.control_plane.validate()?;
+        for (i, tenant) in self.tenants.iter().enumerate() {
+            if tenant.name.is_empty() {
+                return Err(format!("`tenants[{}].name` should not be empty", i));
+            }
+            if tenant.api_rate_limit.is_some_and(|rate| rate <= 0.) {
+                return Err(format!("`tenants[{}].api_rate_limit` should be positive", i));
+            }
+            if tenant.api_burst.is_some_and(|burst| burst < 1.) {
+                return Err(format!("`tenants[{}].api_burst` should be at least 1", i));
+            }
+        }
+        for (i, scheduler) in self.schedulers.iter().enumerate() {
+            let key = format!("schedulers[{}]", i);
+            validate_naming(&key, &scheduler.name, &scheduler.name_prefix, scheduler.count)?;
+            if scheduler.algorithm.is_empty() {
+                return Err(format!("`{}.algorithm` should not be empty", key));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Checks that name is set for a single entity and name prefix is set for multiple entities.
+fn validate_naming(
+    key: &str,
+    name: &Option<String>,
+    name_prefix: &Option<String>,
+    count: Option<u32>,
+) -> Result<(), String> {
+    match count.unwrap_or(1) {
+        0 => Err(format!("`{}.count` should be positive", key)),
+        1 if name.is_none() => Err(format!("`{}.name` should be set if count = 1", key)),
+        c if c > 1 && name_prefix.is_none() => Err(format!("`{}.name_prefix` should be set if count > 1", key)),
+        _ => Ok(()),
     }
 }
@@ -0,0 +1,108 @@
+//! YAML config loading with includes and overrides.
+//!
+//! A config file can include other config files via the top-level `include` key,
+//! which holds a file path or a list of file paths (relative paths are resolved against the including file).
+//! Included files are merged in the listed order, and then the values from the including file are applied on top,
+//! i.e. the including file overrides values from included ones.
+//! Nested mappings are merged recursively, while other values (including lists) are replaced as a whole.
+
+use std::path::{Path, PathBuf};
+
+use serde::de::DeserializeOwned;
+use serde_yaml::{Mapping, Value};
+
+/// Name of the top-level key used to include other config files.
+pub const INCLUDE_KEY: &str = "include";
+
+/// Reads YAML file, resolves its includes and returns the resulting YAML value.
+pub fn load_yaml(file_name: &str) -> Result<Value, String> {
+    let mut stack = Vec::new();
+    load_yaml_recursive(Path::new(file_name), &mut stack)
+}
+
+/// Reads YAML file, resolves its includes and deserializes the result into the specified type.
+///
+/// Returned error describes the problem and includes the config file name.
+pub fn parse_yaml_config<T: DeserializeOwned>(file_name: &str) -> Result<T, String> {
+    let value = load_yaml(file_name)?;
+    serde_yaml::from_value(value).map_err(|err| format!("Invalid config {}: {}", file_name, err))
+}
+
+/// Recursively merges `overrides` into `base`.
+///
+/// Mappings are merged key by key, all other values from `overrides` replace the values in `base`.
+pub fn merge_yaml(base: &mut Value, overrides: Value) {
+    match (base, overrides) {
+        (Value::Mapping(base_map), Value::Mapping(override_map)) => {
+            for (key, value) in override_map {
+                match base_map.get_mut(&key) {
+                    Some(base_value) => merge_yaml(base_value, value),
+                    None => {
+                        base_map.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overrides) => *base = overrides,
+    }
+}
+
+fn load_yaml_recursive(path: &Path, stack: &mut Vec<PathBuf>) -> Result<Value, String> {
+    let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    if stack.contains(&canonical) {
+        return Err(format!("Circular include of config file {}", path.display()));
+    }
+    let content =
+        std::fs::read_to_string(path).map_err(|err| format!("Can't read file {}: {}", path.display(), err))?;
+    let mut value: Value = serde_yaml::from_str(&content)
+        .map_err(|err| format!("Can't parse YAML from file {}: {}", path.display(), err))?;
+    if value.is_null() {
+        value = Value::Mapping(Mapping::new());
+    }
+
+    let includes = match value.as_mapping_mut() {
+        Some(map) => map.remove(&Value::String(INCLUDE_KEY.to_string())),
+        None => {
+            return Err(format!(
+                "Invalid config {}: top-level value should be a mapping",
+                path.display()
+            ))
+        }
+    };
+    let include_paths = match includes {
+        None => Vec::new(),
+        Some(Value::String(include)) => vec![include],
+        Some(Value::Sequence(includes)) => includes
+            .into_iter()
+            .map(|include| match include {
+                Value::String(include) => Ok(include),
+                _ => Err(format!(
+                    "Invalid config {}: `{}` should contain file paths",
+                    path.display(),
+                    INCLUDE_KEY
+                )),
+            })
+            .collect::<Result<Vec<_>, _>>()?,
+        Some(_) => {
+            return Err(format!(
+                "Invalid config {}: `{}` should be a file path or a list of file paths",
+                path.display(),
+                INCLUDE_KEY
+            ))
+        }
+    };
+    if include_paths.is_empty() {
+        return Ok(value);
+    }
+
+    stack.push(canonical);
+    let base_dir = path.parent().unwrap_or_else(|| Path::new(""));
+    let mut result = Value::Mapping(Mapping::new());
+    for include in include_paths {
+        let included = load_yaml_recursive(&base_dir.join(include), stack)?;
+        merge_yaml(&mut result, included);
+    }
+    stack.pop();
+    merge_yaml(&mut result, value);
+    Ok(result)
+}
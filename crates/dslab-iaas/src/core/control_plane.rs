@@ -0,0 +1,136 @@
+//! Latency model of control-plane actions.
+
+use std::f64::consts::PI;
+
+use dslab_core::context::SimulationContext;
+
+use crate::core::config::options::{parse_config_value, parse_options};
+use crate::core::config::sim_config::SimulationConfig;
+
+/// Random delay with the specified distribution.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Delay {
+    /// Constant delay.
+    Const(f64),
+    /// Delay uniformly distributed in the range `[min, max]`.
+    Uniform { min: f64, max: f64 },
+    /// Exponentially distributed delay with the specified mean.
+    Exponential { mean: f64 },
+    /// Log-normally distributed delay, i.e. the delay logarithm is normally distributed
+    /// with mean `mu` and standard deviation `sigma`.
+    LogNormal { mu: f64, sigma: f64 },
+}
+
+impl Delay {
+    /// Parses delay from config value string, e.g. `0.1`, `Const[value=0.1]`, `Uniform[min=0.1,max=0.2]`,
+    /// `Exponential[mean=0.1]` or `LogNormal[mu=-2,sigma=0.5]`.
+    pub fn parse(config_str: &str) -> Result<Self, String> {
+        if let Ok(value) = config_str.parse::<f64>() {
+            return Self::Const(value).validated(config_str);
+        }
+        let (name, options_str) = parse_config_value(config_str);
+        let options = parse_options(&options_str.unwrap_or_default());
+        let option = |name: &str| -> Result<f64, String> {
+            options
+                .get(name)
+                .ok_or_else(|| format!("option {} is not set in delay {}", name, config_str))?
+                .parse::<f64>()
+                .map_err(|_| format!("can't parse option {} in delay {}", name, config_str))
+        };
+        let delay = match name.as_str() {
+            "Const" => Self::Const(option("value")?),
+            "Uniform" => Self::Uniform {
+                min: option("min")?,
+                max: option("max")?,
+            },
+            "Exponential" => Self::Exponential { mean: option("mean")? },
+            "LogNormal" => Self::LogNormal {
+                mu: option("mu")?,
+                sigma: option("sigma")?,
+            },
+            _ => return Err(format!("unknown delay distribution {}", config_str)),
+        };
+        delay.validated(config_str)
+    }
+
+    fn validated(self, config_str: &str) -> Result<Self, String> {
+        let valid = match self {
+            Self::Const(value) => value >= 0.,
+            Self::Uniform { min, max } => min >= 0. && min <= max,
+            Self::Exponential { mean } => mean >= 0.,
+            Self::LogNormal { sigma, .. } => sigma >= 0.,
+        };
+        if valid {
+            Ok(self)
+        } else {
+            Err(format!("invalid delay parameters {}", config_str))
+        }
+    }
+
+    /// Samples the delay value using the simulation random number generator.
+    ///
+    /// Constant delay does not consume random numbers.
+    pub fn sample(&self, ctx: &SimulationContext) -> f64 {
+        match *self {
+            Self::Const(value) => value,
+            Self::Uniform { min, max } => min + (max - min) * ctx.rand(),
+            Self::Exponential { mean } => -mean * (1. - ctx.rand()).ln(),
+            Self::LogNormal { mu, sigma } => {
+                // Box-Muller transform
+                let z = (-2. * (1. - ctx.rand()).ln()).sqrt() * (2. * PI * ctx.rand()).cos();
+                (mu + sigma * z).exp()
+            }
+        }
+    }
+
+    /// Returns the mean delay value.
+    pub fn mean(&self) -> f64 {
+        match *self {
+            Self::Const(value) => value,
+            Self::Uniform { min, max } => (min + max) / 2.,
+            Self::Exponential { mean } => mean,
+            Self::LogNormal { mu, sigma } => (mu + sigma * sigma / 2.).exp(),
+        }
+    }
+}
+
+/// Delays of control-plane actions used by cloud components, resolved from [`ControlPlaneConfig`].
+///
+/// [`ControlPlaneConfig`]: crate::core::config::sim_config::ControlPlaneConfig
+///
+/// The message delays are applied to messages sent by the corresponding component. If some delay is not configured,
+/// it defaults to the constant `message_delay` (or `vm_start_duration`) from the simulation config,
+/// while the scheduling time defaults to zero.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ControlPlaneLatency {
+    /// Time spent by scheduler to process a single allocation request.
+    pub scheduling_time: Delay,
+    /// VM start (boot) duration, not including the time to attach remote volumes.
+    pub vm_start_duration: Delay,
+    /// Delay of messages sent by schedulers.
+    pub scheduler_delay: Delay,
+    /// Delay of messages sent by placement store.
+    pub placement_store_delay: Delay,
+    /// Delay of messages sent by hosts.
+    pub host_delay: Delay,
+}
+
+impl ControlPlaneLatency {
+    /// Resolves control-plane delays from the simulation config.
+    pub fn from_config(sim_config: &SimulationConfig) -> Result<Self, String> {
+        let config = &sim_config.control_plane;
+        let resolve = |value: &Option<String>, default: f64| -> Result<Delay, String> {
+            match value {
+                Some(value) => Delay::parse(value),
+                None => Ok(Delay::Const(default)),
+            }
+        };
+        Ok(Self {
+            scheduling_time: resolve(&config.scheduling_time, 0.)?,
+            vm_start_duration: resolve(&config.vm_start_duration, sim_config.vm_start_duration)?,
+            scheduler_delay: resolve(&config.scheduler_delay, sim_config.message_delay)?,
+            placement_store_delay: resolve(&config.placement_store_delay, sim_config.message_delay)?,
+            host_delay: resolve(&config.host_delay, sim_config.message_delay)?,
+        })
+    }
+}
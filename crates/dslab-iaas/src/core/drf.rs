@@ -0,0 +1,124 @@
+//! Dominant Resource Fairness (DRF) accounting of tenant allocations.
+//!
+//! The dominant share of tenant is the maximum of its CPU and memory shares, i.e. the fractions of the resource
+//! pool capacity allocated to the tenant VMs, divided by the tenant weight. DRF allocates the resources to the tenant
+//! with the lowest dominant share first, see "Dominant Resource Fairness: Fair Allocation of Multiple Resource Types"
+//! (Ghodsi et al., NSDI 2011).
+
+use std::collections::{BTreeMap, HashMap};
+
+use crate::core::common::Allocation;
+
+/// Resources allocated to tenant VMs.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TenantAllocation {
+    pub cpu: u64,
+    pub memory: u64,
+    pub vm_count: u32,
+}
+
+/// Tracks the resources allocated to tenants and their dominant shares over time.
+///
+/// VMs without tenant are not accounted.
+#[derive(Default)]
+pub struct DominantShareTracker {
+    weights: HashMap<String, f64>,
+    vms: HashMap<u32, (String, u32, Allocation)>,
+    allocated: BTreeMap<String, TenantAllocation>,
+    history: BTreeMap<String, Vec<(f64, f64)>>,
+}
+
+impl DominantShareTracker {
+    /// Creates tracker without allocations.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Sets the weight of the specified tenant (1 by default), the tenant with weight `w` is entitled to `w` times
+    /// larger dominant share.
+    pub fn set_weight(&mut self, tenant: &str, weight: f64) {
+        assert!(weight > 0., "tenant weight should be positive");
+        self.weights.insert(tenant.to_string(), weight);
+    }
+
+    /// Returns the weight of the specified tenant.
+    pub fn weight(&self, tenant: &str) -> f64 {
+        self.weights.get(tenant).copied().unwrap_or(1.)
+    }
+
+    /// Accounts the VM allocation on the specified host. Repeated calls for the same VM and host are ignored.
+    pub fn allocate(&mut self, tenant: &str, host_id: u32, alloc: &Allocation, time: f64, capacity: (u64, u64)) {
+        if self.vms.get(&alloc.id).is_some_and(|(_, host, _)| *host == host_id) {
+            return;
+        }
+        self.release(alloc.id, None, time, capacity);
+        let allocated = self.allocated.entry(tenant.to_string()).or_default();
+        allocated.cpu += alloc.cpu_usage as u64;
+        allocated.memory += alloc.memory_usage;
+        allocated.vm_count += 1;
+        self.vms.insert(alloc.id, (tenant.to_string(), host_id, alloc.clone()));
+        self.record(tenant, time, capacity);
+    }
+
+    /// Removes the VM allocation on the specified host (or on any host if not specified).
+    pub fn release(&mut self, vm_id: u32, host_id: Option<u32>, time: f64, capacity: (u64, u64)) {
+        match self.vms.get(&vm_id) {
+            Some((_, host, _)) if host_id.is_none_or(|id| id == *host) => {}
+            _ => return,
+        }
+        let (tenant, _, alloc) = self.vms.remove(&vm_id).unwrap();
+        let allocated = self.allocated.get_mut(&tenant).unwrap();
+        allocated.cpu -= alloc.cpu_usage as u64;
+        allocated.memory -= alloc.memory_usage;
+        allocated.vm_count -= 1;
+        self.record(&tenant, time, capacity);
+    }
+
+    /// Returns the dominant share of the specified tenant given the resource pool capacity (CPU, memory).
+    pub fn dominant_share(&self, tenant: &str, capacity: (u64, u64)) -> f64 {
+        let allocated = match self.allocated.get(tenant) {
+            Some(allocated) => allocated,
+            None => return 0.,
+        };
+        let cpu_share = if capacity.0 > 0 {
+            allocated.cpu as f64 / capacity.0 as f64
+        } else {
+            0.
+        };
+        let memory_share = if capacity.1 > 0 {
+            allocated.memory as f64 / capacity.1 as f64
+        } else {
+            0.
+        };
+        cpu_share.max(memory_share) / self.weight(tenant)
+    }
+
+    /// Returns the current dominant shares of all tenants with allocations.
+    pub fn dominant_shares(&self, capacity: (u64, u64)) -> BTreeMap<String, f64> {
+        self.allocated
+            .keys()
+            .map(|tenant| (tenant.clone(), self.dominant_share(tenant, capacity)))
+            .collect()
+    }
+
+    /// Returns the resources allocated to tenants.
+    pub fn allocations(&self) -> &BTreeMap<String, TenantAllocation> {
+        &self.allocated
+    }
+
+    /// Returns the history of dominant share of the specified tenant as (time, share) pairs
+    /// recorded upon each change of tenant allocations.
+    pub fn history(&self, tenant: &str) -> &[(f64, f64)] {
+        self.history.get(tenant).map_or(&[], |history| history.as_slice())
+    }
+
+    fn record(&mut self, tenant: &str, time: f64, capacity: (u64, u64)) {
+        let share = self.dominant_share(tenant, capacity);
+        let history = self.history.entry(tenant.to_string()).or_default();
+        // keep the last value for simultaneous changes
+        if history.last().is_some_and(|(last_time, _)| *last_time == time) {
+            history.pop();
+        }
+        history.push((time, share));
+    }
+}
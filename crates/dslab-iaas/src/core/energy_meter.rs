@@ -1,10 +1,137 @@
 //! Energy meter calculates the host energy consumption.
 
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+/// Host power consumption split into components.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PowerComponents {
+    /// Static power consumed by an active host regardless of its load, i.e. the power at zero CPU load.
+    pub idle: f64,
+    /// Load-proportional power consumed by an active host in addition to the idle power.
+    pub dynamic: f64,
+    /// Power consumed by a host in off or sleep state.
+    pub inactive: f64,
+}
+
+impl PowerComponents {
+    /// Returns the total power.
+    pub fn total(&self) -> f64 {
+        self.idle + self.dynamic + self.inactive
+    }
+}
+
+/// Energy consumption split into components.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize)]
+pub struct EnergyBreakdown {
+    /// Energy consumed by active hosts at zero load.
+    pub idle: f64,
+    /// Load-proportional energy consumed by active hosts in addition to the idle energy.
+    pub dynamic: f64,
+    /// Energy consumed by hosts in off or sleep state.
+    pub inactive: f64,
+    /// Energy consumed during host power state transitions.
+    pub transition: f64,
+    /// Energy consumed to cool hosts.
+    pub cooling: f64,
+}
+
+impl EnergyBreakdown {
+    /// Returns the total energy consumption including cooling.
+    pub fn total(&self) -> f64 {
+        self.idle + self.dynamic + self.inactive + self.transition + self.cooling
+    }
+
+    /// Adds the energy components of another breakdown to this one.
+    pub fn add(&mut self, other: &EnergyBreakdown) {
+        self.idle += other.idle;
+        self.dynamic += other.dynamic;
+        self.inactive += other.inactive;
+        self.transition += other.transition;
+        self.cooling += other.cooling;
+    }
+}
+
+/// Energy breakdown of a simulation run per host, per host type and in total.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct EnergyReport {
+    /// Breakdowns for each host by host name.
+    pub hosts: BTreeMap<String, EnergyBreakdown>,
+    /// Breakdowns for hosts of each type.
+    pub host_types: BTreeMap<String, EnergyBreakdown>,
+    /// Breakdown for all hosts.
+    pub total: EnergyBreakdown,
+}
+
+/// Single row of the energy breakdown table.
+#[derive(Serialize)]
+struct EnergyReportRecord<'a> {
+    scope: &'a str,
+    name: &'a str,
+    idle: f64,
+    dynamic: f64,
+    inactive: f64,
+    transition: f64,
+    cooling: f64,
+    total: f64,
+}
+
+impl EnergyReport {
+    /// Adds the breakdown of the specified host to the report.
+    pub fn add_host(&mut self, name: &str, host_type: Option<&str>, breakdown: EnergyBreakdown) {
+        self.total.add(&breakdown);
+        if let Some(host_type) = host_type {
+            self.host_types
+                .entry(host_type.to_string())
+                .or_default()
+                .add(&breakdown);
+        }
+        self.hosts.insert(name.to_string(), breakdown);
+    }
+
+    /// Saves the report to CSV file with a row per host, per host type and a total row.
+    pub fn save_csv(&self, path: &str) -> Result<(), csv::Error> {
+        let mut writer = csv::Writer::from_path(path)?;
+        let rows = self
+            .hosts
+            .iter()
+            .map(|(name, breakdown)| ("host", name.as_str(), breakdown))
+            .chain(
+                self.host_types
+                    .iter()
+                    .map(|(name, breakdown)| ("host_type", name.as_str(), breakdown)),
+            )
+            .chain([("total", "", &self.total)]);
+        for (scope, name, breakdown) in rows {
+            writer.serialize(EnergyReportRecord {
+                scope,
+                name,
+                idle: breakdown.idle,
+                dynamic: breakdown.dynamic,
+                inactive: breakdown.inactive,
+                transition: breakdown.transition,
+                cooling: breakdown.cooling,
+                total: breakdown.total(),
+            })?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
+
 /// Energy meter structure.
+///
+/// Besides the energy consumed by the host itself, it also tracks the energy consumed to cool the host.
+/// The consumed energy is also split into components (see [`EnergyBreakdown`]).
 #[derive(Debug, Default, Clone)]
 pub struct EnergyMeter {
     energy_consumed: f64,
     current_power: f64,
+    cooling_energy_consumed: f64,
+    current_cooling_power: f64,
+    breakdown: EnergyBreakdown,
+    current_power_components: PowerComponents,
     prev_time: f64,
 }
 
@@ -16,13 +143,63 @@ impl EnergyMeter {
 
     /// Invoked each time the host power consumption is changed to update the total energy consumption.
     pub fn update(&mut self, time: f64, power: f64) {
-        self.energy_consumed += (time - self.prev_time) * self.current_power;
-        self.current_power = power;
+        self.update_with_cooling(time, power, 0.);
+    }
+
+    /// Same as [`update`](Self::update), but also updates the cooling power consumption.
+    ///
+    /// The whole power is accounted as dynamic in the energy breakdown.
+    pub fn update_with_cooling(&mut self, time: f64, power: f64, cooling_power: f64) {
+        let components = PowerComponents {
+            dynamic: power,
+            ..Default::default()
+        };
+        self.update_components(time, components, cooling_power);
+    }
+
+    /// Same as [`update_with_cooling`](Self::update_with_cooling), but with power split into components.
+    pub fn update_components(&mut self, time: f64, power: PowerComponents, cooling_power: f64) {
+        let duration = time - self.prev_time;
+        self.energy_consumed += duration * self.current_power;
+        self.cooling_energy_consumed += duration * self.current_cooling_power;
+        self.breakdown.idle += duration * self.current_power_components.idle;
+        self.breakdown.dynamic += duration * self.current_power_components.dynamic;
+        self.breakdown.inactive += duration * self.current_power_components.inactive;
+        self.breakdown.cooling += duration * self.current_cooling_power;
+        self.current_power = power.total();
+        self.current_power_components = power;
+        self.current_cooling_power = cooling_power;
         self.prev_time = time;
     }
 
-    /// Returns the total energy consumption.
+    /// Adds the energy consumed instantly or during a period not covered by the power model
+    /// (e.g. host power state transition).
+    ///
+    /// This energy is accounted as transition energy in the energy breakdown.
+    pub fn add_energy(&mut self, energy: f64, cooling_energy: f64) {
+        self.energy_consumed += energy;
+        self.cooling_energy_consumed += cooling_energy;
+        self.breakdown.transition += energy;
+        self.breakdown.cooling += cooling_energy;
+    }
+
+    /// Returns the total energy consumption (without cooling).
     pub fn energy_consumed(&self) -> f64 {
         self.energy_consumed
     }
+
+    /// Returns the total energy consumed for cooling.
+    pub fn cooling_energy_consumed(&self) -> f64 {
+        self.cooling_energy_consumed
+    }
+
+    /// Returns the total energy consumption including cooling.
+    pub fn total_energy_consumed(&self) -> f64 {
+        self.energy_consumed + self.cooling_energy_consumed
+    }
+
+    /// Returns the consumed energy split into components.
+    pub fn energy_breakdown(&self) -> &EnergyBreakdown {
+        &self.breakdown
+    }
 }
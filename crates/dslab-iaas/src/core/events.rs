@@ -5,11 +5,21 @@
 pub mod allocation {
     use serde::Serialize;
 
+    use crate::core::preemption::PreemptionVictim;
+
     #[derive(Clone, Serialize)]
     pub struct AllocationRequest {
         pub vm_ids: Vec<u32>,
     }
 
+    #[derive(Clone, Serialize)]
+    pub struct BatchAllocationRequest {
+        pub vm_ids: Vec<u32>,
+    }
+
+    #[derive(Clone, Serialize)]
+    pub struct RetryQueuedRequests {}
+
     #[derive(Clone, Serialize)]
     pub struct AllocationCommitRequest {
         pub vm_ids: Vec<u32>,
@@ -40,6 +50,41 @@ pub mod allocation {
         pub host_id: u32,
     }
 
+    #[derive(Clone, Serialize)]
+    pub struct ReservationRequest {
+        pub vm_id: u32,
+        pub start_time: f64,
+        pub end_time: f64,
+    }
+
+    #[derive(Clone, Serialize)]
+    pub struct ReservationCommitRequest {
+        pub vm_id: u32,
+        pub host_id: u32,
+        pub start_time: f64,
+        pub end_time: f64,
+    }
+
+    #[derive(Clone, Serialize)]
+    pub struct ReservationCommitSucceeded {
+        pub vm_id: u32,
+        pub host_id: u32,
+        pub start_time: f64,
+        pub end_time: f64,
+    }
+
+    #[derive(Clone, Serialize)]
+    pub struct ReservationCommitFailed {
+        pub vm_id: u32,
+        pub host_id: u32,
+    }
+
+    #[derive(Clone, Serialize)]
+    pub struct ReservationStarted {
+        pub vm_id: u32,
+        pub host_id: u32,
+    }
+
     #[derive(Clone, Serialize)]
     pub struct VmCreateRequest {
         pub vm_id: u32,
@@ -51,6 +96,37 @@ pub mod allocation {
         pub is_migrating: bool,
     }
 
+    #[derive(Clone, Serialize)]
+    pub struct BatchReleaseRequest {
+        pub vm_ids: Vec<u32>,
+    }
+
+    #[derive(Clone, Serialize)]
+    pub struct PreemptionCommitRequest {
+        pub vm_ids: Vec<u32>,
+        pub host_ids: Vec<u32>,
+        pub victims: Vec<PreemptionVictim>,
+    }
+
+    #[derive(Clone, Serialize)]
+    pub struct PreemptionCommitted {
+        pub vm_ids: Vec<u32>,
+        pub victims: Vec<PreemptionVictim>,
+    }
+
+    #[derive(Clone, Serialize)]
+    pub struct PreemptionCommitFailed {
+        pub vm_ids: Vec<u32>,
+        pub host_ids: Vec<u32>,
+        pub victims: Vec<PreemptionVictim>,
+    }
+
+    #[derive(Clone, Serialize)]
+    pub struct VmPreemptRequest {
+        pub vm_id: u32,
+        pub is_migrating: bool,
+    }
+
     #[derive(Clone, Serialize)]
     pub struct MigrationRequest {
         pub source_host: u32,
@@ -72,6 +148,11 @@ pub mod vm {
     pub struct VMDeleted {
         pub vm_id: u32,
     }
+
+    #[derive(Clone, Serialize)]
+    pub struct ImageFetched {
+        pub image: String,
+    }
 }
 
 // MONITORING EVENTS ///////////////////////////////////////////////////////////////////////////////
@@ -79,16 +160,44 @@ pub mod vm {
 pub mod monitoring {
     use serde::Serialize;
 
+    use crate::core::power_state::HostPowerState;
+
     #[derive(Clone, Serialize)]
     pub struct HostStateUpdate {
         pub host_id: u32,
         pub cpu_load: f64,
         pub memory_load: f64,
+        pub cpu_allocated: u32,
+        pub memory_allocated: u64,
+        pub power: f64,
+        pub power_state: HostPowerState,
         pub recently_added_vms: Vec<u32>,
         pub recently_removed_vms: Vec<u32>,
     }
 }
 
+// HOST POWER EVENTS ///////////////////////////////////////////////////////////////////////////////
+
+pub mod power {
+    use serde::Serialize;
+
+    use crate::core::power_state::HostPowerState;
+
+    #[derive(Clone, Serialize)]
+    pub struct PowerOnRequest {}
+
+    #[derive(Clone, Serialize)]
+    pub struct PowerOffRequest {}
+
+    #[derive(Clone, Serialize)]
+    pub struct SleepRequest {}
+
+    #[derive(Clone, Serialize)]
+    pub struct PowerStateTransitionCompleted {
+        pub state: HostPowerState,
+    }
+}
+
 pub mod vm_api {
     use serde::Serialize;
 
@@ -98,4 +207,20 @@ pub mod vm_api {
         pub vm_id: u32,
         pub status: VmStatus,
     }
+
+    #[derive(Clone, Serialize)]
+    pub struct VmPreempted {
+        pub vm_id: u32,
+        pub host_id: u32,
+        pub preempted_by: u32,
+        pub target_host: Option<u32>,
+    }
+
+    #[derive(Clone, Serialize)]
+    pub struct ApiAllocationRequest {
+        pub vm_ids: Vec<u32>,
+        pub scheduler_id: u32,
+        pub attempt: u32,
+        pub submit_time: f64,
+    }
 }
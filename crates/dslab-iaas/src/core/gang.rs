@@ -0,0 +1,65 @@
+//! Gang scheduling of VM groups.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::monitoring::Monitoring;
+
+/// Topology constraint on the placement of VMs from a gang.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GangTopology {
+    /// VMs can be placed on any hosts.
+    #[default]
+    Any,
+    /// All VMs must be placed on hosts from the same rack.
+    /// Host without a rack is considered to be the only host in its rack.
+    SameRack,
+    /// All VMs must be placed on the same host.
+    SameHost,
+    /// Each VM must be placed on a separate host.
+    DistinctHosts,
+}
+
+impl GangTopology {
+    /// Splits the specified hosts into groups, such that the whole gang must be placed within a single group.
+    ///
+    /// Returns a single group with all hosts for constraints which do not restrict the gang to a subset of hosts.
+    pub fn host_groups(&self, host_ids: &[u32], monitoring: &Monitoring) -> Vec<Vec<u32>> {
+        match self {
+            GangTopology::SameRack => {
+                let mut racks: BTreeMap<u32, Vec<u32>> = BTreeMap::new();
+                let mut rackless = Vec::new();
+                for host_id in host_ids {
+                    match monitoring.get_host_state(*host_id).rack_id {
+                        Some(rack_id) => racks.entry(rack_id).or_default().push(*host_id),
+                        None => rackless.push(vec![*host_id]),
+                    }
+                }
+                racks.into_values().chain(rackless).collect()
+            }
+            GangTopology::SameHost => host_ids.iter().map(|host_id| vec![*host_id]).collect(),
+            GangTopology::Any | GangTopology::DistinctHosts => vec![host_ids.to_vec()],
+        }
+    }
+}
+
+/// Gang scheduling requirements of a multi-VM request.
+///
+/// All VMs from a gang are placed together or none of them. If the gang cannot be placed within the specified
+/// time window since its submission, all its VMs fail to allocate.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct GangSpec {
+    /// Maximum time from the gang submission to its placement decision.
+    /// If not set, the VM allocation timeout from the simulation config is used.
+    pub window: Option<f64>,
+    /// Topology constraint on the placement of gang VMs.
+    pub topology: GangTopology,
+}
+
+impl GangSpec {
+    /// Creates gang spec with specified time window and topology constraint.
+    pub fn new(window: Option<f64>, topology: GangTopology) -> Self {
+        Self { window, topology }
+    }
+}
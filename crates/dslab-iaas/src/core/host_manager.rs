@@ -1,6 +1,7 @@
 //! Host manager representing a physical machine.
 
 use std::cell::RefCell;
+use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::mem;
@@ -10,21 +11,30 @@ use serde::Serialize;
 
 use dslab_core::cast;
 use dslab_core::context::SimulationContext;
-use dslab_core::event::Event;
+use dslab_core::event::{Event, EventId};
 use dslab_core::handler::EventHandler;
 use dslab_models::power::host::{HostPowerModel, HostState};
+use dslab_network::{DataTransferCompleted, Network};
 
 use crate::core::common::AllocationVerdict;
 use crate::core::config::sim_config::SimulationConfig;
-use crate::core::energy_meter::EnergyMeter;
+use crate::core::control_plane::ControlPlaneLatency;
+use crate::core::energy_meter::{EnergyBreakdown, EnergyMeter, PowerComponents};
 use crate::core::events::allocation::{
-    AllocationFailed, AllocationReleaseRequest, AllocationReleased, MigrationRequest, VmCreateRequest,
+    AllocationFailed, AllocationReleaseRequest, AllocationReleased, BatchReleaseRequest, MigrationRequest,
+    VmCreateRequest, VmPreemptRequest,
 };
 use crate::core::events::monitoring::HostStateUpdate;
-use crate::core::events::vm::{VMDeleted, VMStarted};
+use crate::core::events::power::{PowerOffRequest, PowerOnRequest, PowerStateTransitionCompleted, SleepRequest};
+use crate::core::events::vm::{ImageFetched, VMDeleted, VMStarted};
 use crate::core::events::vm_api::VmStatusChanged;
+use crate::core::image::{ImageCache, ImageRegistry};
+use crate::core::interference::InterferenceModel;
 use crate::core::logger::Logger;
+use crate::core::power_state::{HostPowerState, HostPowerStateConfig};
 use crate::core::slav_metric::HostSLAVMetric;
+use crate::core::thermal_model::ThermalModel;
+use crate::core::timeline::{Timeline, VmIntervalEnd};
 use crate::core::vm::{VirtualMachine, VmStatus};
 use crate::core::vm_api::VmAPI;
 
@@ -33,10 +43,26 @@ use crate::core::vm_api::VmAPI;
 /// and migration, and reports the VM status changes to VM API component. Host manager periodically computes its
 /// current load, as the sum of loads produced by currently running VMs, and reports it to the monitoring component.
 /// Host manager also records the total energy consumption of the host computed using the power model
-/// defined as a function of CPU load.
+/// defined as a function of CPU load. If the thermal model is set, the energy consumed to cool the host is recorded too.
+///
+/// Host can be switched off or put to sleep when it has no VMs, and switched back on, which takes time and energy
+/// according to the configured [`HostPowerStateConfig`]. VMs allocated on a host which is not active are started
+/// after the host is switched on (which happens automatically upon allocation).
+///
+/// VMs preempted by higher-priority VMs are stopped immediately. Their resources are released without notifying
+/// the placement store, which has already accounted for the preemption.
+///
+/// If the interference model is set, the running VMs are slowed down according to their memory bandwidth demands,
+/// so that the VM execution takes longer than its lifetime. The slowdowns and the VM completion times are updated
+/// each time a VM is started or stopped on the host.
+///
+/// VM started from an image is booted only after the image is present on the host. If the image is not found in
+/// the host image cache, it is fetched from the image registry (over the network if both are bound to it) and added
+/// to the cache. Concurrent starts of VMs from the same image share a single fetch. Migrated VMs do not need images.
 pub struct HostManager {
     pub id: u32,
     pub rack_id: Option<u32>,
+    pub host_type: Option<String>,
     name: String,
 
     cpu_total: u32,
@@ -62,8 +88,29 @@ pub struct HostManager {
 
     allow_vm_overcommit: bool,
     power_model: HostPowerModel,
+    thermal_model: Option<ThermalModel>,
     slav_metric: Box<dyn HostSLAVMetric>,
 
+    power_state: HostPowerState,
+    power_state_config: HostPowerStateConfig,
+    pending_vms: Vec<u32>,
+    power_on_requested: bool,
+
+    network: Option<Rc<RefCell<Network>>>,
+    migration_downtimes: HashMap<u32, f64>,
+    control_plane: ControlPlaneLatency,
+    timeline: Option<Rc<RefCell<Timeline>>>,
+
+    interference_model: Option<Box<dyn InterferenceModel>>,
+    running_vms: BTreeMap<u32, RunningVm>,
+    vm_running_time: f64,
+    interference_delay: f64,
+
+    image_registry: Option<Rc<RefCell<ImageRegistry>>>,
+    image_cache: ImageCache,
+    image_fetches: HashMap<String, Vec<(u32, f64)>>,
+    image_transfers: HashMap<usize, String>,
+
     ctx: SimulationContext,
     logger: Rc<RefCell<Box<dyn Logger>>>,
     sim_config: Rc<SimulationConfig>,
@@ -74,6 +121,7 @@ impl HostManager {
     #[allow(clippy::too_many_arguments)]
     pub fn new(
         rack_id: Option<u32>,
+        host_type: Option<String>,
         cpu_total: u32,
         memory_total: u64,
         monitoring_id: u32,
@@ -89,6 +137,7 @@ impl HostManager {
         Self {
             id: ctx.id(),
             rack_id,
+            host_type,
             name: ctx.name().to_string(),
             cpu_total,
             memory_total,
@@ -108,13 +157,305 @@ impl HostManager {
             vm_api,
             allow_vm_overcommit,
             power_model,
+            thermal_model: None,
             slav_metric,
+            power_state: HostPowerState::Active,
+            power_state_config: HostPowerStateConfig::default(),
+            pending_vms: Vec::new(),
+            power_on_requested: false,
+            network: None,
+            migration_downtimes: HashMap::new(),
+            control_plane: ControlPlaneLatency::from_config(&sim_config).unwrap(),
+            timeline: None,
+            interference_model: None,
+            running_vms: BTreeMap::new(),
+            vm_running_time: 0.,
+            interference_delay: 0.,
+            image_registry: None,
+            image_cache: ImageCache::default(),
+            image_fetches: HashMap::new(),
+            image_transfers: HashMap::new(),
             ctx,
             logger,
             sim_config,
         }
     }
 
+    /// Sets the thermal model used to compute the cooling power of this host.
+    pub fn set_thermal_model(&mut self, thermal_model: ThermalModel) {
+        self.thermal_model = Some(thermal_model);
+    }
+
+    /// Sets the network connecting the hosts, which is used to obtain the bandwidth for VM migrations.
+    pub fn set_network(&mut self, network: Rc<RefCell<Network>>) {
+        self.network = Some(network);
+    }
+
+    /// Sets the timeline used to record VM intervals, migrations and power states of this host.
+    pub fn set_timeline(&mut self, timeline: Rc<RefCell<Timeline>>) {
+        timeline.borrow_mut().add_host(
+            &self.name,
+            self.cpu_total,
+            self.memory_total,
+            self.power_state,
+            self.ctx.time(),
+        );
+        self.timeline = Some(timeline);
+    }
+
+    /// Sets the model of performance interference between VMs running on this host.
+    ///
+    /// Should be called before starting VMs on this host.
+    pub fn set_interference_model(&mut self, interference_model: Box<dyn InterferenceModel>) {
+        self.interference_model = Some(interference_model);
+    }
+
+    /// Returns the current slowdown of the specified VM caused by interference with co-located VMs,
+    /// or `None` if the VM is not running on this host or the interference model is not set.
+    pub fn vm_slowdown(&self, vm_id: u32) -> Option<f64> {
+        self.running_vms.get(&vm_id).map(|vm| vm.slowdown)
+    }
+
+    /// Returns the total time during which VMs running on this host did not progress due to interference.
+    pub fn get_interference_delay(&mut self, time: f64) -> f64 {
+        self.advance_vm_progress(time);
+        self.interference_delay
+    }
+
+    /// Returns the total running time of VMs on this host tracked by the interference model.
+    pub fn get_vm_running_time(&mut self, time: f64) -> f64 {
+        self.advance_vm_progress(time);
+        self.vm_running_time
+    }
+
+    /// Updates the remaining execution time of running VMs according to their current slowdowns.
+    fn advance_vm_progress(&mut self, time: f64) {
+        for (vm_id, running) in self.running_vms.iter_mut() {
+            let elapsed = time - running.last_update;
+            let delay = elapsed * (1. - 1. / running.slowdown);
+            running.remaining -= elapsed - delay;
+            running.delay += delay;
+            running.last_update = time;
+            self.vm_running_time += elapsed;
+            self.interference_delay += delay;
+            self.vm_api.borrow().get_vm(*vm_id).borrow_mut().interference_delay += delay;
+        }
+    }
+
+    /// Recomputes the slowdowns of running VMs and reschedules their completion if the slowdown is changed.
+    fn update_vm_slowdowns(&mut self) {
+        let model = match self.interference_model.as_ref() {
+            Some(model) => model,
+            None => return,
+        };
+        let vm_api = self.vm_api.borrow();
+        let demands = self
+            .running_vms
+            .keys()
+            .map(|vm_id| (*vm_id, vm_api.get_vm(*vm_id).borrow().memory_bandwidth))
+            .collect::<Vec<_>>();
+        let total_demand = demands.iter().map(|(_, demand)| demand).sum::<f64>();
+        for (vm_id, demand) in demands {
+            let slowdown = model.slowdown(demand, total_demand).max(1.);
+            let running = self.running_vms.get_mut(&vm_id).unwrap();
+            if running.release_event.is_some() && running.slowdown == slowdown {
+                continue;
+            }
+            running.slowdown = slowdown;
+            if let Some(event_id) = running.release_event.take() {
+                self.ctx.cancel_event(event_id);
+            }
+            running.release_event = Some(self.ctx.emit_self(
+                AllocationReleaseRequest {
+                    vm_id,
+                    is_migrating: false,
+                },
+                running.remaining * slowdown,
+            ));
+        }
+    }
+
+    /// Stops tracking the execution of VM which is no longer running on this host.
+    ///
+    /// The VM did not progress during the interference delay, so its lifetime is extended by this delay
+    /// (similar to the migration downtime), which is needed to compute the remaining lifetime after migration.
+    fn stop_vm_progress(&mut self, vm_id: u32) {
+        if !self.running_vms.contains_key(&vm_id) {
+            return;
+        }
+        self.advance_vm_progress(self.ctx.time());
+        let running = self.running_vms.remove(&vm_id).unwrap();
+        if let Some(event_id) = running.release_event {
+            self.ctx.cancel_event(event_id);
+        }
+        let vm = self.vm_api.borrow().get_vm(vm_id);
+        let lifetime = vm.borrow().lifetime();
+        vm.borrow_mut().set_lifetime(lifetime + running.delay);
+        self.update_vm_slowdowns();
+    }
+
+    /// Returns the delay of message sent by host.
+    /// Sets the registry of VM images.
+    pub fn set_image_registry(&mut self, image_registry: Rc<RefCell<ImageRegistry>>) {
+        self.image_registry = Some(image_registry);
+    }
+
+    /// Sets the image cache of this host.
+    pub fn set_image_cache(&mut self, image_cache: ImageCache) {
+        self.image_cache = image_cache;
+    }
+
+    /// Returns the image cache of this host.
+    pub fn image_cache(&self) -> &ImageCache {
+        &self.image_cache
+    }
+
+    /// Puts the specified image from registry to the host image cache.
+    pub fn preload_image(&mut self, name: &str) {
+        let image = self
+            .image_registry
+            .as_ref()
+            .and_then(|registry| registry.borrow().get_image(name).cloned())
+            .unwrap_or_else(|| panic!("Image {} is not found in registry", name));
+        self.image_cache.insert(&image, self.ctx.time(), 0);
+    }
+
+    /// Starts the allocated VM after its image is present on the host.
+    fn start_vm(&mut self, vm_id: u32) {
+        let image = self.vm_api.borrow().get_vm(vm_id).borrow().image.clone();
+        let time = self.ctx.time();
+        match image {
+            Some(name) if self.image_registry.is_some() && !self.image_cache.access(&name, time) => {
+                if let Some(waiting_vms) = self.image_fetches.get_mut(&name) {
+                    waiting_vms.push((vm_id, time));
+                } else {
+                    self.image_fetches.insert(name.clone(), vec![(vm_id, time)]);
+                    self.fetch_image(&name);
+                }
+            }
+            _ => {
+                let start_duration = self.vm_start_duration(&self.vm_api.borrow().get_vm(vm_id).borrow());
+                self.ctx.emit_self(VMStarted { vm_id }, start_duration);
+            }
+        }
+    }
+
+    /// Starts fetching the image from registry.
+    ///
+    /// If both the registry and the host are bound to the network, the image is transferred over the network,
+    /// otherwise the network throughput from the simulation config is used.
+    fn fetch_image(&mut self, name: &str) {
+        let registry = self.image_registry.as_ref().unwrap().borrow();
+        let image = registry
+            .get_image(name)
+            .unwrap_or_else(|| panic!("Image {} is not found in registry", name))
+            .clone();
+        self.image_cache.on_fetch_started(&image);
+        self.logger.borrow_mut().log_debug(
+            &self.ctx,
+            format!("host {} fetches image {} of size {}", self.name, image.name, image.size),
+        );
+        if let (Some(network), Some(registry_id)) = (self.network.as_ref(), registry.get_id()) {
+            let mut network = network.borrow_mut();
+            if network.get_location_opt(registry_id).is_some() && network.get_location_opt(self.id).is_some() {
+                let transfer_id = network.transfer_data(registry_id, self.id, image.size, self.id);
+                self.image_transfers.insert(transfer_id, image.name);
+                return;
+            }
+        }
+        self.ctx.emit_self(
+            ImageFetched { image: image.name },
+            image.size / self.sim_config.network_throughput as f64,
+        );
+    }
+
+    /// Invoked upon completion of image fetch, adds the image to the cache and starts the waiting VMs.
+    fn on_image_fetched(&mut self, name: String) {
+        let time = self.ctx.time();
+        let waiting_vms = self.image_fetches.remove(&name).unwrap_or_default();
+        let image = self
+            .image_registry
+            .as_ref()
+            .unwrap()
+            .borrow()
+            .get_image(&name)
+            .unwrap()
+            .clone();
+        self.image_cache.insert(&image, time, waiting_vms.len() as u64);
+        self.logger
+            .borrow_mut()
+            .log_debug(&self.ctx, format!("host {} fetched image {}", self.name, name));
+        for (vm_id, wait_start) in waiting_vms {
+            if !self.vms.contains(&vm_id) {
+                // VM was preempted while waiting for the image
+                continue;
+            }
+            let vm = self.vm_api.borrow().get_vm(vm_id);
+            vm.borrow_mut().image_fetch_delay += time - wait_start;
+            let start_duration = self.vm_start_duration(&vm.borrow());
+            self.ctx.emit_self(VMStarted { vm_id }, start_duration);
+        }
+    }
+
+    fn message_delay(&self) -> f64 {
+        self.control_plane.host_delay.sample(&self.ctx)
+    }
+
+    /// Returns the start duration of the specified VM sampled from the configured VM start duration distribution
+    /// along with the time needed to attach remote volumes.
+    fn vm_start_duration(&self, vm: &VirtualMachine) -> f64 {
+        self.control_plane.vm_start_duration.sample(&self.ctx) + vm.volume_attach_latency
+    }
+
+    /// Returns the bandwidth available for migrating VM from the specified host to this host.
+    ///
+    /// If both hosts are connected to the network, the network bandwidth between them is used,
+    /// otherwise the network throughput from the simulation config is used.
+    fn migration_bandwidth(&self, source_host: u32) -> f64 {
+        if let Some(network) = self.network.as_ref() {
+            let network = network.borrow();
+            if network.get_location_opt(source_host).is_some() && network.get_location_opt(self.id).is_some() {
+                return network.bandwidth(source_host, self.id);
+            }
+        }
+        self.sim_config.network_throughput as f64
+    }
+
+    /// Sets the durations and energy costs of host power state transitions.
+    pub fn set_power_state_config(&mut self, power_state_config: HostPowerStateConfig) {
+        self.power_state_config = power_state_config;
+    }
+
+    /// Returns the current host power state.
+    pub fn power_state(&self) -> HostPowerState {
+        self.power_state
+    }
+
+    /// Updates energy meter with the current power consumption.
+    ///
+    /// The power of active host is split into idle power (at zero CPU load) and dynamic power.
+    fn update_energy(&mut self, time: f64, power: f64) {
+        let cooling_power = self
+            .thermal_model
+            .as_ref()
+            .map_or(0., |model| model.cooling_power(power));
+        let components = match self.power_state {
+            HostPowerState::Active => {
+                let idle = self.power_model.get_power(HostState::cpu_util(0.)).min(power);
+                PowerComponents {
+                    idle,
+                    dynamic: power - idle,
+                    inactive: 0.,
+                }
+            }
+            _ => PowerComponents {
+                inactive: power,
+                ..Default::default()
+            },
+        };
+        self.energy_meter.update_components(time, components, cooling_power);
+    }
+
     /// Checks if incoming VM can be allocated on this host.
     fn can_allocate(&self, vm_id: u32) -> AllocationVerdict {
         let vm = self.vm_api.borrow().get_vm(vm_id).borrow().clone();
@@ -153,7 +494,7 @@ impl HostManager {
         self.vms.insert(vm.id);
         let cpu_load = self.cpu_load(time);
         let power = self.current_power(cpu_load);
-        self.energy_meter.update(time, power);
+        self.update_energy(time, power);
         self.slav_metric.update(time, cpu_load);
     }
 
@@ -179,7 +520,7 @@ impl HostManager {
         self.recently_removed_vms.push(vm.id);
         let cpu_load = self.cpu_load(time);
         let power = self.current_power(cpu_load);
-        self.energy_meter.update(time, power);
+        self.update_energy(time, power);
         self.slav_metric.update(time, cpu_load);
     }
 
@@ -225,19 +566,45 @@ impl HostManager {
 
     /// Returns the current power consumption.
     pub fn current_power(&self, cpu_load: f64) -> f64 {
-        // CPU utilization is capped by 100%
-        let cpu_util = cpu_load.min(1.);
-        self.power_model.get_power(HostState::cpu_util(cpu_util))
+        match self.power_state {
+            HostPowerState::Active => {
+                // CPU utilization is capped by 100%
+                let cpu_util = cpu_load.min(1.);
+                self.power_model.get_power(HostState::cpu_util(cpu_util))
+            }
+            HostPowerState::Off => self.power_state_config.off_power,
+            HostPowerState::Sleep => self.power_state_config.sleep_power,
+            // transition energy is accounted separately
+            HostPowerState::Booting | HostPowerState::ShuttingDown => 0.,
+        }
     }
 
     /// Returns the total energy consumption.
     pub fn get_energy_consumed(&mut self, time: f64) -> f64 {
         let cpu_load = self.cpu_load(time);
         let power = self.current_power(cpu_load);
-        self.energy_meter.update(time, power);
+        self.update_energy(time, power);
         self.energy_meter.energy_consumed()
     }
 
+    /// Returns the total energy consumed for cooling the host.
+    pub fn get_cooling_energy_consumed(&mut self, time: f64) -> f64 {
+        self.get_energy_consumed(time);
+        self.energy_meter.cooling_energy_consumed()
+    }
+
+    /// Returns the total energy consumption including cooling.
+    pub fn get_total_energy_consumed(&mut self, time: f64) -> f64 {
+        self.get_energy_consumed(time);
+        self.energy_meter.total_energy_consumed()
+    }
+
+    /// Returns the consumed energy split into idle, dynamic, inactive, transition and cooling components.
+    pub fn get_energy_breakdown(&mut self, time: f64) -> EnergyBreakdown {
+        self.get_energy_consumed(time);
+        *self.energy_meter.energy_breakdown()
+    }
+
     /// Returns the total SLAV value.
     pub fn get_accumulated_slav(&mut self, time: f64) -> f64 {
         let cpu_load = self.cpu_load(time);
@@ -249,13 +616,29 @@ impl HostManager {
     fn on_allocation_request(&mut self, vm_id: u32) -> bool {
         if self.can_allocate(vm_id) == AllocationVerdict::Success {
             let vm = self.vm_api.borrow().get_vm(vm_id);
-            let start_duration = vm.borrow().start_duration();
+            // VM which was started before is restarted after preemption
+            let status = if vm.borrow().start_time() != -1. {
+                VmStatus::Migrating
+            } else {
+                VmStatus::Initializing
+            };
             self.allocate(self.ctx.time(), vm);
-            self.recent_vm_status_changes.insert(vm_id, VmStatus::Initializing);
+            self.recent_vm_status_changes.insert(vm_id, status);
+            if let Some(timeline) = self.timeline.as_ref() {
+                timeline
+                    .borrow_mut()
+                    .on_vm_allocated(vm_id, &self.name, self.ctx.time());
+            }
             self.logger
                 .borrow_mut()
                 .log_debug(&self.ctx, format!("vm {} allocated on host {}", vm_id, self.name));
-            self.ctx.emit_self(VMStarted { vm_id }, start_duration);
+            if self.power_state == HostPowerState::Active {
+                self.start_vm(vm_id);
+            } else {
+                // VM will be started after the host is switched on
+                self.pending_vms.push(vm_id);
+                self.power_on();
+            }
             true
         } else {
             self.logger.borrow_mut().log_debug(
@@ -268,23 +651,58 @@ impl HostManager {
                     host_id: self.id,
                 },
                 self.placement_store_id,
-                self.sim_config.message_delay,
+                self.message_delay(),
             );
             false
         }
     }
 
     /// Processes migration request (as migration target), allocates resources to start new VM, updates VM status.
+    ///
+    /// The migration duration and downtime are estimated using the pre-copy live migration model from the VM memory
+    /// size, its current dirty rate and the bandwidth between the hosts. The VM continues running on the source host
+    /// except for the downtime, which does not count towards the VM lifetime.
     fn on_migration_request(&mut self, source_host: u32, vm_id: u32) {
+        if self.power_state != HostPowerState::Active {
+            self.logger.borrow_mut().log_debug(
+                &self.ctx,
+                format!("host {} is not active, migration of vm {} failed", self.name, vm_id),
+            );
+            return;
+        }
+        if !self.vm_api.borrow().is_host_eligible(vm_id, self.id) {
+            self.logger.borrow_mut().log_debug(
+                &self.ctx,
+                format!(
+                    "vm {} uses local volumes of another host, migration to host {} failed",
+                    vm_id, self.name
+                ),
+            );
+            return;
+        }
         if self.can_allocate(vm_id) == AllocationVerdict::Success {
             let vm = self.vm_api.borrow().get_vm(vm_id);
-            let migration_duration = (vm.borrow().memory_usage as f64) / (self.sim_config.network_throughput as f64);
-            let start_duration = vm.borrow().start_duration();
+            let estimate = vm
+                .borrow()
+                .estimate_migration(self.ctx.time(), self.migration_bandwidth(source_host));
+            let migration_duration = estimate.duration;
+            let start_duration = self.vm_start_duration(&vm.borrow());
+            vm.borrow_mut().migration_downtime += estimate.downtime;
+            self.migration_downtimes.insert(vm_id, estimate.downtime);
 
             self.allocate(self.ctx.time(), vm);
+            if let Some(timeline) = self.timeline.as_ref() {
+                let mut timeline = timeline.borrow_mut();
+                let time = self.ctx.time();
+                timeline.on_vm_allocated(vm_id, &self.name, time);
+                timeline.on_migration_started(vm_id, &self.ctx.lookup_name(source_host), &self.name, time);
+            }
             self.logger.borrow_mut().log_debug(
                 &self.ctx,
-                format!("vm {} allocated on host {}, start migration", vm_id, self.name),
+                format!(
+                    "vm {} allocated on host {}, start migration (duration {:.3}, downtime {:.3}, {} rounds)",
+                    vm_id, self.name, estimate.duration, estimate.downtime, estimate.rounds
+                ),
             );
             self.recent_vm_status_changes.insert(vm_id, VmStatus::Migrating);
 
@@ -319,6 +737,7 @@ impl HostManager {
             if !is_migrating {
                 self.recent_vm_status_changes.insert(vm_id, VmStatus::Finished);
             }
+            self.stop_vm_progress(vm_id);
             let vm = self.vm_api.borrow().get_vm(vm_id).borrow().clone();
             self.ctx.emit_self(VMDeleted { vm_id }, vm.stop_duration());
         } else {
@@ -329,8 +748,41 @@ impl HostManager {
         }
     }
 
+    /// Processes VM preemption request by stopping the VM and releasing its resources immediately.
+    ///
+    /// If the VM is migrating, it is restarted on another host, so its status is not changed here.
+    fn on_vm_preempt_request(&mut self, vm_id: u32, is_migrating: bool) {
+        if self.vms.contains(&vm_id) {
+            self.logger
+                .borrow_mut()
+                .log_debug(&self.ctx, format!("vm {} preempted on host {}", vm_id, self.name));
+            self.pending_vms.retain(|id| *id != vm_id);
+            self.stop_vm_progress(vm_id);
+            self.release(self.ctx.time(), vm_id);
+            if let Some(timeline) = self.timeline.as_ref() {
+                let end = if is_migrating {
+                    VmIntervalEnd::Migrated
+                } else {
+                    VmIntervalEnd::Preempted
+                };
+                timeline
+                    .borrow_mut()
+                    .on_vm_released(vm_id, &self.name, self.ctx.time(), end);
+            }
+            if is_migrating {
+                self.recent_vm_status_changes.remove(&vm_id);
+            } else {
+                self.recent_vm_status_changes.insert(vm_id, VmStatus::Preempted);
+            }
+        }
+    }
+
     /// Invoked upon VM startup, updates VM status and schedules VM release event according to its lifetime.
     fn on_vm_started(&mut self, vm_id: u32) {
+        if !self.vms.contains(&vm_id) {
+            // VM was preempted before startup
+            return;
+        }
         self.logger
             .borrow_mut()
             .log_debug(&self.ctx, format!("vm {} started and running", vm_id));
@@ -338,20 +790,40 @@ impl HostManager {
         let start_time = vm.borrow().start_time();
 
         if start_time != -1. {
-            // reduce lifetime due to migration
-            let new_lifetime = vm.borrow().lifetime() - (self.ctx.time() - start_time);
+            // reduce lifetime due to migration, the VM does not progress during the migration downtime
+            let downtime = self.migration_downtimes.remove(&vm_id).unwrap_or(0.);
+            let new_lifetime = vm.borrow().lifetime() - (self.ctx.time() - start_time - downtime);
             vm.borrow_mut().set_lifetime(new_lifetime);
         }
 
         vm.borrow_mut().set_start_time(self.ctx.time());
         self.recent_vm_status_changes.insert(vm_id, VmStatus::Running);
-        self.ctx.emit_self(
-            AllocationReleaseRequest {
+        if let Some(timeline) = self.timeline.as_ref() {
+            timeline.borrow_mut().on_vm_started(vm_id, &self.name, self.ctx.time());
+        }
+        if self.interference_model.is_some() {
+            let time = self.ctx.time();
+            self.advance_vm_progress(time);
+            self.running_vms.insert(
                 vm_id,
-                is_migrating: false,
-            },
-            vm.borrow().lifetime(),
-        );
+                RunningVm {
+                    remaining: vm.borrow().lifetime(),
+                    slowdown: 1.,
+                    delay: 0.,
+                    last_update: time,
+                    release_event: None,
+                },
+            );
+            self.update_vm_slowdowns();
+        } else {
+            self.ctx.emit_self(
+                AllocationReleaseRequest {
+                    vm_id,
+                    is_migrating: false,
+                },
+                vm.borrow().lifetime(),
+            );
+        }
     }
 
     /// Invoked upon VM deletion to release the allocated resources and notify placement store.
@@ -361,17 +833,116 @@ impl HostManager {
                 .borrow_mut()
                 .log_debug(&self.ctx, format!("vm {} deleted", vm_id));
             self.release(self.ctx.time(), vm_id);
+            if let Some(timeline) = self.timeline.as_ref() {
+                timeline
+                    .borrow_mut()
+                    .on_vm_released(vm_id, &self.name, self.ctx.time(), VmIntervalEnd::Finished);
+            }
             self.ctx.emit(
                 AllocationReleased {
                     vm_id,
                     host_id: self.id,
                 },
                 self.placement_store_id,
-                self.sim_config.message_delay,
+                self.message_delay(),
             );
         }
     }
 
+    /// Switches the host on from off or sleep state.
+    ///
+    /// If the host is shutting down, it will be switched on after the shutdown is completed.
+    fn power_on(&mut self) {
+        let config = &self.power_state_config;
+        match self.power_state {
+            HostPowerState::Off => {
+                let (duration, energy) = (config.boot_duration, config.boot_energy);
+                self.start_power_state_transition(HostPowerState::Booting, HostPowerState::Active, duration, energy);
+            }
+            HostPowerState::Sleep => {
+                let (duration, energy) = (config.wake_up_duration, config.wake_up_energy);
+                self.start_power_state_transition(HostPowerState::Booting, HostPowerState::Active, duration, energy);
+            }
+            HostPowerState::ShuttingDown => {
+                self.power_on_requested = true;
+            }
+            HostPowerState::Booting | HostPowerState::Active => {}
+        }
+    }
+
+    /// Switches the host off or puts it to sleep if it is active and has no VMs.
+    fn power_off(&mut self, target_state: HostPowerState) {
+        if self.power_state != HostPowerState::Active || !self.vms.is_empty() {
+            self.logger.borrow_mut().log_debug(
+                &self.ctx,
+                format!(
+                    "host {} can't be switched to {:?} state as it is not active or has VMs",
+                    self.name, target_state
+                ),
+            );
+            return;
+        }
+        let config = &self.power_state_config;
+        let (duration, energy) = match target_state {
+            HostPowerState::Sleep => (config.sleep_duration, config.sleep_energy),
+            _ => (config.shutdown_duration, config.shutdown_energy),
+        };
+        self.start_power_state_transition(HostPowerState::ShuttingDown, target_state, duration, energy);
+    }
+
+    /// Switches host to the transitional power state and schedules its completion.
+    fn start_power_state_transition(
+        &mut self,
+        transition_state: HostPowerState,
+        target_state: HostPowerState,
+        duration: f64,
+        energy: f64,
+    ) {
+        self.logger.borrow_mut().log_debug(
+            &self.ctx,
+            format!("host {} switches to {:?} state", self.name, target_state),
+        );
+        let time = self.ctx.time();
+        let power = self.current_power(self.cpu_load(time));
+        self.update_energy(time, power);
+        self.power_state = transition_state;
+        self.update_energy(time, 0.);
+        if let Some(timeline) = self.timeline.as_ref() {
+            timeline
+                .borrow_mut()
+                .on_power_state_changed(&self.name, transition_state, time);
+        }
+        let cooling_energy = self
+            .thermal_model
+            .as_ref()
+            .map_or(0., |model| model.cooling_power(energy));
+        self.energy_meter.add_energy(energy, cooling_energy);
+        self.ctx
+            .emit_self(PowerStateTransitionCompleted { state: target_state }, duration);
+    }
+
+    /// Invoked upon completion of power state transition.
+    fn on_power_state_transition_completed(&mut self, state: HostPowerState) {
+        self.logger
+            .borrow_mut()
+            .log_debug(&self.ctx, format!("host {} switched to {:?} state", self.name, state));
+        let time = self.ctx.time();
+        self.power_state = state;
+        let power = self.current_power(self.cpu_load(time));
+        self.update_energy(time, power);
+        if let Some(timeline) = self.timeline.as_ref() {
+            timeline.borrow_mut().on_power_state_changed(&self.name, state, time);
+        }
+        if state == HostPowerState::Active {
+            for vm_id in mem::take(&mut self.pending_vms) {
+                self.start_vm(vm_id);
+            }
+        } else if self.power_on_requested || !self.pending_vms.is_empty() {
+            self.power_on_requested = false;
+            self.power_on();
+        }
+    }
+
     /// Invoked periodically to report the current host state to Monitoring and VM status updates to VM API.
     fn send_host_state(&mut self) {
         self.logger
@@ -380,7 +951,7 @@ impl HostManager {
         let time = self.ctx.time();
         let cpu_load = self.cpu_load(time);
         let power = self.current_power(cpu_load);
-        self.energy_meter.update(time, power);
+        self.update_energy(time, power);
         self.slav_metric.update(time, cpu_load);
 
         self.ctx.emit(
@@ -388,17 +959,24 @@ impl HostManager {
                 host_id: self.id,
                 cpu_load,
                 memory_load: self.memory_load(time),
+                cpu_allocated: self.cpu_allocated,
+                memory_allocated: self.memory_allocated,
+                power,
+                power_state: self.power_state,
                 recently_added_vms: mem::take(&mut self.recently_added_vms),
                 recently_removed_vms: mem::take(&mut self.recently_removed_vms),
             },
             self.monitoring_id,
-            self.sim_config.message_delay,
+            self.message_delay(),
         );
-        for (vm_id, status) in self.recent_vm_status_changes.drain() {
+        // sort status changes to sample message delays in deterministic order
+        let mut status_changes: Vec<(u32, VmStatus)> = self.recent_vm_status_changes.drain().collect();
+        status_changes.sort_by_key(|(vm_id, _)| *vm_id);
+        for (vm_id, status) in status_changes {
             self.ctx.emit(
                 VmStatusChanged { vm_id, status },
                 self.vm_api.borrow().get_id(),
-                self.sim_config.message_delay,
+                self.message_delay(),
             );
         }
         self.ctx.emit_self(SendHostState {}, self.sim_config.send_stats_period);
@@ -408,6 +986,17 @@ impl HostManager {
 #[derive(Clone, Serialize)]
 pub struct SendHostState {}
 
+/// Execution state of VM running on host with interference model.
+struct RunningVm {
+    /// Remaining execution time at the nominal speed.
+    remaining: f64,
+    slowdown: f64,
+    /// Time lost due to interference on this host.
+    delay: f64,
+    last_update: f64,
+    release_event: Option<EventId>,
+}
+
 impl EventHandler for HostManager {
     fn on(&mut self, event: Event) {
         cast!(match event.data {
@@ -418,17 +1007,51 @@ impl EventHandler for HostManager {
                 self.on_migration_request(source_host, vm_id);
             }
             AllocationReleaseRequest { vm_id, is_migrating } => {
+                // the scheduled VM completion is delivered and should not be cancelled
+                if let Some(running) = self.running_vms.get_mut(&vm_id) {
+                    if running.release_event == Some(event.id) {
+                        running.release_event = None;
+                    }
+                }
                 self.on_allocation_release_request(vm_id, is_migrating);
             }
+            BatchReleaseRequest { vm_ids } => {
+                for vm_id in vm_ids {
+                    self.on_allocation_release_request(vm_id, false);
+                }
+            }
+            VmPreemptRequest { vm_id, is_migrating } => {
+                self.on_vm_preempt_request(vm_id, is_migrating);
+            }
             VMStarted { vm_id } => {
                 self.on_vm_started(vm_id);
             }
             VMDeleted { vm_id } => {
                 self.on_vm_deleted(vm_id);
             }
+            ImageFetched { image } => {
+                self.on_image_fetched(image);
+            }
+            DataTransferCompleted { dt } => {
+                if let Some(image) = self.image_transfers.remove(&dt.id) {
+                    self.on_image_fetched(image);
+                }
+            }
             SendHostState {} => {
                 self.send_host_state();
             }
+            PowerOnRequest {} => {
+                self.power_on();
+            }
+            PowerOffRequest {} => {
+                self.power_off(HostPowerState::Off);
+            }
+            SleepRequest {} => {
+                self.power_off(HostPowerState::Sleep);
+            }
+            PowerStateTransitionCompleted { state } => {
+                self.on_power_state_transition_completed(state);
+            }
         })
     }
 }
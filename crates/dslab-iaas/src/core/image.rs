@@ -0,0 +1,262 @@
+//! VM images, image registry and host image caches.
+
+use std::collections::HashMap;
+
+use dyn_clone::{clone_trait_object, DynClone};
+
+/// VM image, which should be present on host before starting VM from it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct VmImage {
+    /// Image name.
+    pub name: String,
+    /// Image size in GB.
+    pub size: f64,
+}
+
+/// Registry storing all VM images.
+///
+/// If the registry is bound to the network node, the images are fetched by hosts over the network, so the fetch
+/// duration depends on the network load. Otherwise, the fetch duration is computed from the image size and
+/// the network throughput from the simulation config.
+#[derive(Default)]
+pub struct ImageRegistry {
+    images: HashMap<String, VmImage>,
+    id: Option<u32>,
+}
+
+impl ImageRegistry {
+    /// Creates empty registry.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Adds image with specified name and size.
+    pub fn add_image(&mut self, name: &str, size: f64) {
+        assert!(size >= 0., "image size should be non-negative");
+        self.images.insert(
+            name.to_string(),
+            VmImage {
+                name: name.to_string(),
+                size,
+            },
+        );
+    }
+
+    /// Returns image by its name.
+    pub fn get_image(&self, name: &str) -> Option<&VmImage> {
+        self.images.get(name)
+    }
+
+    /// Returns the number of images.
+    pub fn image_count(&self) -> usize {
+        self.images.len()
+    }
+
+    /// Returns ID of the registry component used as the source of image transfers over the network (if bound).
+    pub fn get_id(&self) -> Option<u32> {
+        self.id
+    }
+
+    pub(crate) fn set_id(&mut self, id: u32) {
+        self.id = Some(id);
+    }
+}
+
+/// Image stored in host image cache.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CachedImage {
+    pub name: String,
+    pub size: f64,
+    /// Time when image was added to the cache.
+    pub added_time: f64,
+    /// Time of the last image use.
+    pub last_access_time: f64,
+    /// Number of image uses, including the one caused the image fetch.
+    pub access_count: u64,
+}
+
+/// Trait for implementation of host image cache eviction policy.
+pub trait ImageEvictionPolicy: DynClone {
+    /// Returns the name of eviction policy.
+    fn name(&self) -> &str;
+
+    /// Returns the index of image to evict from the non-empty list of cached images.
+    fn select_victim(&self, images: &[CachedImage]) -> usize;
+}
+
+clone_trait_object!(ImageEvictionPolicy);
+
+/// Evicts the least recently used image.
+#[derive(Clone, Default)]
+pub struct LruEviction;
+
+impl ImageEvictionPolicy for LruEviction {
+    fn name(&self) -> &str {
+        "LRU"
+    }
+
+    fn select_victim(&self, images: &[CachedImage]) -> usize {
+        (0..images.len())
+            .min_by(|a, b| images[*a].last_access_time.total_cmp(&images[*b].last_access_time))
+            .unwrap()
+    }
+}
+
+/// Evicts the least frequently used image, the ties are broken by the last access time.
+#[derive(Clone, Default)]
+pub struct LfuEviction;
+
+impl ImageEvictionPolicy for LfuEviction {
+    fn name(&self) -> &str {
+        "LFU"
+    }
+
+    fn select_victim(&self, images: &[CachedImage]) -> usize {
+        (0..images.len())
+            .min_by(|a, b| {
+                images[*a]
+                    .access_count
+                    .cmp(&images[*b].access_count)
+                    .then(images[*a].last_access_time.total_cmp(&images[*b].last_access_time))
+            })
+            .unwrap()
+    }
+}
+
+/// Evicts the image which was added to the cache first.
+#[derive(Clone, Default)]
+pub struct FifoEviction;
+
+impl ImageEvictionPolicy for FifoEviction {
+    fn name(&self) -> &str {
+        "FIFO"
+    }
+
+    fn select_victim(&self, images: &[CachedImage]) -> usize {
+        (0..images.len())
+            .min_by(|a, b| images[*a].added_time.total_cmp(&images[*b].added_time))
+            .unwrap()
+    }
+}
+
+/// Statistics of host image cache.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ImageCacheStats {
+    /// Number of VM starts with image found in the cache.
+    pub hits: u64,
+    /// Number of VM starts which required the image fetch, including the ones waiting for the fetch
+    /// started by another VM.
+    pub misses: u64,
+    /// Number of image fetches.
+    pub fetches: u64,
+    /// Total size of fetched images.
+    pub fetched_size: f64,
+    /// Number of evicted images.
+    pub evictions: u64,
+}
+
+impl ImageCacheStats {
+    /// Returns the fraction of VM starts with image found in the cache.
+    pub fn hit_ratio(&self) -> f64 {
+        if self.hits + self.misses > 0 {
+            self.hits as f64 / (self.hits + self.misses) as f64
+        } else {
+            0.
+        }
+    }
+}
+
+/// Host image cache with limited capacity and pluggable eviction policy.
+///
+/// The images larger than the cache capacity are not cached.
+#[derive(Clone)]
+pub struct ImageCache {
+    capacity: f64,
+    used: f64,
+    images: Vec<CachedImage>,
+    eviction_policy: Box<dyn ImageEvictionPolicy>,
+    stats: ImageCacheStats,
+}
+
+impl ImageCache {
+    /// Creates empty cache with specified capacity (in GB) and eviction policy.
+    pub fn new(capacity: f64, eviction_policy: Box<dyn ImageEvictionPolicy>) -> Self {
+        Self {
+            capacity,
+            used: 0.,
+            images: Vec::new(),
+            eviction_policy,
+            stats: ImageCacheStats::default(),
+        }
+    }
+
+    /// Returns the cache capacity.
+    pub fn capacity(&self) -> f64 {
+        self.capacity
+    }
+
+    /// Returns the total size of cached images.
+    pub fn used(&self) -> f64 {
+        self.used
+    }
+
+    /// Checks if the image is cached.
+    pub fn contains(&self, name: &str) -> bool {
+        self.images.iter().any(|image| image.name == name)
+    }
+
+    /// Returns the names of cached images.
+    pub fn image_names(&self) -> Vec<String> {
+        self.images.iter().map(|image| image.name.clone()).collect()
+    }
+
+    /// Looks up the image upon VM start and updates its access statistics. Returns whether the image is cached.
+    pub fn access(&mut self, name: &str, time: f64) -> bool {
+        if let Some(image) = self.images.iter_mut().find(|image| image.name == name) {
+            image.last_access_time = time;
+            image.access_count += 1;
+            self.stats.hits += 1;
+            true
+        } else {
+            self.stats.misses += 1;
+            false
+        }
+    }
+
+    /// Adds the fetched image to the cache evicting other images if needed.
+    pub fn insert(&mut self, image: &VmImage, time: f64, access_count: u64) {
+        if image.size > self.capacity || self.contains(&image.name) {
+            return;
+        }
+        while self.used + image.size > self.capacity {
+            let victim = self.eviction_policy.select_victim(&self.images);
+            self.used -= self.images.remove(victim).size;
+            self.stats.evictions += 1;
+        }
+        self.used += image.size;
+        self.images.push(CachedImage {
+            name: image.name.clone(),
+            size: image.size,
+            added_time: time,
+            last_access_time: time,
+            access_count,
+        });
+    }
+
+    pub(crate) fn on_fetch_started(&mut self, image: &VmImage) {
+        self.stats.fetches += 1;
+        self.stats.fetched_size += image.size;
+    }
+
+    /// Returns the cache statistics.
+    pub fn stats(&self) -> &ImageCacheStats {
+        &self.stats
+    }
+}
+
+impl Default for ImageCache {
+    /// Creates cache with zero capacity, i.e. the image is fetched upon each VM start.
+    fn default() -> Self {
+        Self::new(0., Box::new(LruEviction))
+    }
+}
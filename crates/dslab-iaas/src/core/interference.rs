@@ -0,0 +1,78 @@
+//! Models of performance interference between co-located VMs.
+
+use dyn_clone::{clone_trait_object, DynClone};
+
+/// Trait for implementation of model of performance interference between VMs running on the same host.
+///
+/// Co-located VMs compete for the host resources which are not partitioned by the allocation, such as memory
+/// bandwidth and last-level cache, and degrade each other's effective compute speed. The model computes the slowdown
+/// of VM from its memory bandwidth demand and the total demand of all VMs running on the host (including this VM).
+pub trait InterferenceModel: DynClone {
+    /// Returns the slowdown factor of VM, i.e. the ratio of its execution time to the nominal one.
+    ///
+    /// The values below 1 are ignored.
+    fn slowdown(&self, vm_demand: f64, total_demand: f64) -> f64;
+}
+
+clone_trait_object!(InterferenceModel);
+
+/// Memory bandwidth saturation model.
+///
+/// When the total demand exceeds the host memory bandwidth, the bandwidth is shared proportionally to the demands,
+/// so each VM with non-zero demand is slowed down by the ratio of the total demand to the bandwidth.
+/// VMs without memory bandwidth demand are not affected.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MemoryBandwidthContention {
+    /// Host memory bandwidth.
+    pub bandwidth: f64,
+}
+
+impl MemoryBandwidthContention {
+    /// Creates model with specified host memory bandwidth.
+    pub fn new(bandwidth: f64) -> Self {
+        assert!(bandwidth > 0., "memory bandwidth should be positive");
+        Self { bandwidth }
+    }
+}
+
+impl InterferenceModel for MemoryBandwidthContention {
+    fn slowdown(&self, vm_demand: f64, total_demand: f64) -> f64 {
+        if vm_demand > 0. {
+            (total_demand / self.bandwidth).max(1.)
+        } else {
+            1.
+        }
+    }
+}
+
+/// Linear model of shared cache contention.
+///
+/// In contrast to the bandwidth saturation, the cache contention slows down VMs even when the memory bandwidth
+/// is not saturated. The slowdown of VM with non-zero demand grows linearly with the memory bandwidth demand
+/// of its neighbors: `slowdown = 1 + sensitivity * (total_demand - vm_demand) / bandwidth`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CacheContention {
+    /// Host memory bandwidth.
+    pub bandwidth: f64,
+    /// Slowdown increase when the neighbors' demand equals to the host memory bandwidth.
+    pub sensitivity: f64,
+}
+
+impl CacheContention {
+    /// Creates model with specified host memory bandwidth and sensitivity of VMs to the neighbors' demand.
+    pub fn new(bandwidth: f64, sensitivity: f64) -> Self {
+        assert!(bandwidth > 0., "memory bandwidth should be positive");
+        assert!(sensitivity >= 0., "sensitivity should be non-negative");
+        Self { bandwidth, sensitivity }
+    }
+}
+
+impl InterferenceModel for CacheContention {
+    fn slowdown(&self, vm_demand: f64, total_demand: f64) -> f64 {
+        if vm_demand > 0. {
+            1. + self.sensitivity * (total_demand - vm_demand) / self.bandwidth
+        } else {
+            1.
+        }
+    }
+}
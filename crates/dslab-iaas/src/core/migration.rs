@@ -0,0 +1,123 @@
+//! Models of VM memory dirtying and live migration duration.
+
+use dyn_clone::{clone_trait_object, DynClone};
+
+use crate::core::config::options::{parse_config_value, parse_options};
+
+/// A dirty rate model defines the rate at which VM modifies its memory pages (in memory units per second),
+/// which determines the amount of memory re-transferred during the live migration.
+pub trait DirtyRateModel: DynClone {
+    /// Returns the dirty rate of VM with specified current CPU load.
+    fn dirty_rate(&self, time: f64, cpu_load: f64) -> f64;
+}
+
+clone_trait_object!(DirtyRateModel);
+
+pub fn dirty_rate_model_resolver(config_str: String) -> Box<dyn DirtyRateModel> {
+    let (model_name, options) = parse_config_value(&config_str);
+    match model_name.as_str() {
+        "Const" => Box::new(ConstantDirtyRate::from_str(&options.unwrap())),
+        "LoadDependent" => Box::new(LoadDependentDirtyRate::from_str(&options.unwrap())),
+        _ => panic!("Can't resolve: {}", config_str),
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// VM dirties the memory at a constant rate.
+#[derive(Clone)]
+pub struct ConstantDirtyRate {
+    rate: f64,
+}
+
+impl ConstantDirtyRate {
+    pub fn new(rate: f64) -> Self {
+        Self { rate }
+    }
+
+    fn from_str(s: &str) -> Self {
+        let options = parse_options(s);
+        let rate = options.get("rate").unwrap().parse::<f64>().unwrap();
+        Self { rate }
+    }
+}
+
+impl DirtyRateModel for ConstantDirtyRate {
+    fn dirty_rate(&self, _time: f64, _cpu_load: f64) -> f64 {
+        self.rate
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// VM dirties the memory at a rate proportional to its CPU load, reaching the maximum rate at full load.
+#[derive(Clone)]
+pub struct LoadDependentDirtyRate {
+    max_rate: f64,
+}
+
+impl LoadDependentDirtyRate {
+    pub fn new(max_rate: f64) -> Self {
+        Self { max_rate }
+    }
+
+    fn from_str(s: &str) -> Self {
+        let options = parse_options(s);
+        let max_rate = options.get("max_rate").unwrap().parse::<f64>().unwrap();
+        Self { max_rate }
+    }
+}
+
+impl DirtyRateModel for LoadDependentDirtyRate {
+    fn dirty_rate(&self, _time: f64, cpu_load: f64) -> f64 {
+        self.max_rate * cpu_load.min(1.)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Estimated characteristics of VM live migration.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct MigrationEstimate {
+    /// Total migration duration including the downtime.
+    pub duration: f64,
+    /// Duration of the final stop-and-copy phase, during which the VM is paused.
+    pub downtime: f64,
+    /// Total amount of transferred memory.
+    pub transferred: f64,
+    /// Number of pre-copy rounds (including the first full memory copy).
+    pub rounds: u32,
+}
+
+/// Estimates the duration and downtime of pre-copy live migration.
+///
+/// The whole VM memory is copied in the first round, and each next round copies the memory dirtied during
+/// the previous round. The pre-copy phase stops when the remaining dirty memory can be copied within `max_downtime`,
+/// when the number of rounds reaches `max_rounds` or when the dirty memory stops shrinking (the dirty rate is not
+/// lower than the bandwidth). The remaining memory is then copied while the VM is paused.
+pub fn estimate_precopy_migration(
+    memory: f64,
+    dirty_rate: f64,
+    bandwidth: f64,
+    max_rounds: u32,
+    max_downtime: f64,
+) -> MigrationEstimate {
+    let mut estimate = MigrationEstimate::default();
+    let mut remaining = memory;
+    while estimate.rounds < max_rounds.max(1) {
+        let round_duration = remaining / bandwidth;
+        estimate.duration += round_duration;
+        estimate.transferred += remaining;
+        estimate.rounds += 1;
+        let dirtied = (dirty_rate * round_duration).min(memory);
+        let converging = dirtied < remaining;
+        remaining = dirtied;
+        if remaining / bandwidth <= max_downtime || !converging {
+            break;
+        }
+    }
+    estimate.downtime = remaining / bandwidth;
+    estimate.duration += estimate.downtime;
+    estimate.transferred += remaining;
+    estimate
+}
@@ -1,16 +1,34 @@
 pub mod common;
 pub mod config;
+pub mod control_plane;
+pub mod drf;
 pub mod energy_meter;
 pub mod events;
+pub mod gang;
 pub mod host_manager;
+pub mod image;
+pub mod interference;
 pub mod load_model;
 pub mod logger;
+pub mod migration;
 pub mod monitoring;
 pub mod placement_store;
+pub mod power_model;
+pub mod power_state;
+pub mod preemption;
+pub mod quota;
+pub mod rate_limit;
+pub mod reservation;
 pub mod resource_pool;
+pub mod retry_policy;
 pub mod scheduler;
 pub mod slav_metric;
+pub mod thermal_model;
+pub mod timeline;
+pub mod traffic;
+pub mod utilization;
 pub mod vm;
 pub mod vm_api;
 pub mod vm_placement_algorithm;
 pub mod vm_placement_algorithms;
+pub mod volume;
@@ -11,36 +11,77 @@ use dslab_core::cast;
 use dslab_core::context::SimulationContext;
 use dslab_core::event::Event;
 use dslab_core::handler::EventHandler;
+use dslab_network::{LinkId, Network};
 
 use crate::core::events::monitoring::HostStateUpdate;
 use crate::core::logger::Logger;
+use crate::core::power_state::HostPowerState;
+use crate::core::slav_metric::{TrafficSLAVMetric, UndeliveredTrafficFraction};
+use crate::core::thermal_model::ThermalModel;
+use crate::core::traffic::{TrafficFlow, TrafficMatrix, TrafficRouting};
+use crate::core::utilization::{ResourceUsage, UtilizationReport, UtilizationTracker};
+use crate::core::vm_api::VmAPI;
 
 /// Host state contains resource capacity and current actual load. In addition a set of active VMs is stored.
+///
+/// If the thermal model is set for the host, the host inlet temperature is computed from the reported power
+/// consumption of all hosts in the same rack.
 #[derive(Clone)]
 pub struct HostState {
     pub cpu_load: f64,
     pub memory_load: f64,
+    pub cpu_allocated: u32,
+    pub memory_allocated: u64,
     pub cpu_total: u32,
     pub memory_total: u64,
+    pub host_type: Option<String>,
+    pub rack_id: Option<u32>,
+    pub power: f64,
+    pub power_state: HostPowerState,
+    pub inlet_temperature: Option<f64>,
     pub vms: BTreeSet<u32>,
 }
 
 /// This component stores the information about current host states received from host managers and provides this
 /// information to other components such as scheduler. Just like in a real system, the information arrives to the
 /// monitoring with some delay, so it can be outdated.
+///
+/// If the network is set, monitoring also provides the network latency and bandwidth between hosts
+/// (hosts should be bound to network nodes) along with the traffic matrix between VMs,
+/// which can be used by network-aware placement algorithms.
+///
+/// The traffic between VMs is routed over the network whenever the VM placement or the traffic matrix is changed.
+/// The resulting link utilization and the traffic delivered to VMs are used to compute the traffic SLAV metric.
+///
+/// Monitoring also aggregates the reported resource utilization and allocation per host, host type, tenant and
+/// datacenter, and provides the summary of these values over time (see [`UtilizationReport`]).
 pub struct Monitoring {
     host_states: BTreeMap<u32, HostState>,
+    thermal_models: BTreeMap<u32, ThermalModel>,
+    network: Option<Rc<RefCell<Network>>>,
+    traffic: TrafficMatrix,
+    traffic_routing: TrafficRouting,
+    traffic_slav_metric: Box<dyn TrafficSLAVMetric>,
+    utilization: UtilizationTracker,
+    vm_api: Rc<RefCell<VmAPI>>,
     ctx: SimulationContext,
     logger: Rc<RefCell<Box<dyn Logger>>>,
 }
 
 impl HostState {
-    pub fn new(cpu_total: u32, memory_total: u64) -> Self {
+    pub fn new(cpu_total: u32, memory_total: u64, host_type: Option<String>, rack_id: Option<u32>) -> Self {
         Self {
             cpu_load: 0.,
             memory_load: 0.,
+            cpu_allocated: 0,
+            memory_allocated: 0,
             cpu_total,
             memory_total,
+            host_type,
+            rack_id,
+            power: 0.,
+            power_state: HostPowerState::Active,
+            inlet_temperature: None,
             vms: BTreeSet::new(),
         }
     }
@@ -48,9 +89,16 @@ impl HostState {
 
 impl Monitoring {
     /// Creates component.
-    pub fn new(ctx: SimulationContext, logger: Rc<RefCell<Box<dyn Logger>>>) -> Self {
+    pub fn new(vm_api: Rc<RefCell<VmAPI>>, ctx: SimulationContext, logger: Rc<RefCell<Box<dyn Logger>>>) -> Self {
         Self {
             host_states: BTreeMap::new(),
+            thermal_models: BTreeMap::new(),
+            network: None,
+            traffic: TrafficMatrix::new(),
+            traffic_routing: TrafficRouting::default(),
+            traffic_slav_metric: Box::new(UndeliveredTrafficFraction::new()),
+            utilization: UtilizationTracker::new(),
+            vm_api,
             ctx,
             logger,
         }
@@ -81,18 +129,197 @@ impl Monitoring {
         &self.host_states
     }
 
+    /// Returns IDs of hosts with the specified type.
+    pub fn get_hosts_by_type(&self, host_type: &str) -> Vec<u32> {
+        self.host_states
+            .iter()
+            .filter(|(_, state)| state.host_type.as_deref() == Some(host_type))
+            .map(|(id, _)| *id)
+            .collect()
+    }
+
+    /// Returns the (possibly outdated) inlet temperature of the specified host, if its thermal model is set.
+    pub fn get_host_inlet_temperature(&self, host: u32) -> Option<f64> {
+        self.host_states[&host].inlet_temperature
+    }
+
+    /// Sets the network connecting the hosts.
+    pub fn set_network(&mut self, network: Rc<RefCell<Network>>) {
+        self.network = Some(network);
+        self.update_traffic_routing();
+    }
+
+    /// Returns the network connecting the hosts (if set).
+    pub fn network(&self) -> Option<Rc<RefCell<Network>>> {
+        self.network.clone()
+    }
+
+    /// Returns the network latency between two hosts.
+    ///
+    /// Returns None if the network is not set or some of the hosts is not bound to a network node.
+    pub fn get_network_latency(&self, host1: u32, host2: u32) -> Option<f64> {
+        let network = self.network.as_ref()?.borrow();
+        if network.get_location_opt(host1).is_none() || network.get_location_opt(host2).is_none() {
+            return None;
+        }
+        Some(network.latency(host1, host2))
+    }
+
+    /// Returns the network bandwidth between two hosts.
+    ///
+    /// Returns None if the network is not set or some of the hosts is not bound to a network node.
+    pub fn get_network_bandwidth(&self, host1: u32, host2: u32) -> Option<f64> {
+        let network = self.network.as_ref()?.borrow();
+        if network.get_location_opt(host1).is_none() || network.get_location_opt(host2).is_none() {
+            return None;
+        }
+        Some(network.bandwidth(host1, host2))
+    }
+
+    /// Returns the traffic matrix between VMs.
+    pub fn get_traffic_matrix(&self) -> &TrafficMatrix {
+        &self.traffic
+    }
+
+    /// Sets the traffic rate between two VMs.
+    pub fn set_vm_traffic(&mut self, vm1: u32, vm2: u32, rate: f64) {
+        self.traffic.set(vm1, vm2, rate);
+        self.update_traffic_routing();
+    }
+
+    /// Returns the current traffic flows between VMs placed on different hosts.
+    pub fn get_traffic_flows(&self) -> &[TrafficFlow] {
+        &self.traffic_routing.flows
+    }
+
+    /// Returns the current utilization of the specified network link by the traffic between VMs.
+    pub fn get_link_utilization(&self, link_id: LinkId) -> f64 {
+        self.traffic_routing
+            .link_utilization
+            .get(&link_id)
+            .copied()
+            .unwrap_or(0.)
+    }
+
+    /// Overrides the used traffic SLAV metric.
+    pub fn set_traffic_slav_metric(&mut self, traffic_slav_metric: Box<dyn TrafficSLAVMetric>) {
+        self.traffic_slav_metric = traffic_slav_metric;
+    }
+
+    /// Returns the total traffic SLAV value.
+    pub fn get_accumulated_traffic_slav(&mut self, time: f64) -> f64 {
+        self.traffic_slav_metric.update(time, &self.traffic_routing.flows);
+        self.traffic_slav_metric.value()
+    }
+
+    /// Returns the traffic SLAV value for the specified VM.
+    pub fn get_vm_traffic_slav(&mut self, vm_id: u32, time: f64) -> f64 {
+        self.traffic_slav_metric.update(time, &self.traffic_routing.flows);
+        self.traffic_slav_metric.vm_value(vm_id)
+    }
+
+    /// Routes the traffic between VMs according to the current VM placement and updates the traffic SLAV metric.
+    fn update_traffic_routing(&mut self) {
+        let Some(network) = self.network.as_ref() else {
+            return;
+        };
+        if self.traffic.is_empty() && self.traffic_routing.flows.is_empty() {
+            return;
+        }
+        let mut vm_hosts = BTreeMap::new();
+        for (host_id, state) in self.host_states.iter() {
+            for vm_id in state.vms.iter() {
+                vm_hosts.insert(*vm_id, *host_id);
+            }
+        }
+        self.traffic_routing = self.traffic.route(&vm_hosts, &network.borrow());
+        self.traffic_slav_metric
+            .update(self.ctx.time(), &self.traffic_routing.flows);
+    }
+
     /// Adds new host to internal storage.
-    pub fn add_host(&mut self, host_id: u32, cpu_total: u32, memory_total: u64) {
+    pub fn add_host(
+        &mut self,
+        host_id: u32,
+        cpu_total: u32,
+        memory_total: u64,
+        host_type: Option<String>,
+        rack_id: Option<u32>,
+    ) {
+        self.utilization
+            .add_host(host_id, host_type.clone(), cpu_total, memory_total);
         self.host_states
-            .insert(host_id, HostState::new(cpu_total, memory_total));
+            .insert(host_id, HostState::new(cpu_total, memory_total, host_type, rack_id));
+    }
+
+    /// Returns the tracker of current resource usage per host type, tenant and datacenter.
+    pub fn utilization(&self) -> &UtilizationTracker {
+        &self.utilization
+    }
+
+    /// Returns the summary of resource utilization and allocation from the simulation start to the current time.
+    pub fn utilization_report(&self) -> UtilizationReport {
+        self.utilization.report(self.ctx.time())
+    }
+
+    /// Returns the resource usage and allocation of VMs of each tenant on the specified host.
+    fn host_tenant_usage(&self, host_id: u32) -> BTreeMap<String, ResourceUsage> {
+        let time = self.ctx.time();
+        let vm_api = self.vm_api.borrow();
+        let mut result: BTreeMap<String, ResourceUsage> = BTreeMap::new();
+        for vm_id in self.host_states[&host_id].vms.iter() {
+            let vm = vm_api.get_vm(*vm_id);
+            let vm = vm.borrow();
+            if let Some(tenant) = vm.tenant.as_ref() {
+                let usage = result.entry(tenant.clone()).or_default();
+                usage.cpu_used += vm.cpu_usage as f64 * vm.get_cpu_load(time);
+                usage.cpu_allocated += vm.cpu_usage as f64;
+                usage.memory_used += vm.memory_usage as f64 * vm.get_memory_load(time);
+                usage.memory_allocated += vm.memory_usage as f64;
+            }
+        }
+        result
+    }
+
+    /// Sets the thermal model used to compute the inlet temperature of the specified host.
+    pub fn set_host_thermal_model(&mut self, host_id: u32, thermal_model: ThermalModel) {
+        self.thermal_models.insert(host_id, thermal_model);
+        self.update_inlet_temperatures(host_id);
+    }
+
+    /// Recomputes the inlet temperatures of hosts located in the same rack as the specified host.
+    ///
+    /// Host without a rack is considered to be the only host in its rack.
+    fn update_inlet_temperatures(&mut self, host_id: u32) {
+        let rack_id = self.host_states[&host_id].rack_id;
+        let rack_hosts: Vec<u32> = match rack_id {
+            Some(rack_id) => self
+                .host_states
+                .iter()
+                .filter(|(_, state)| state.rack_id == Some(rack_id))
+                .map(|(id, _)| *id)
+                .collect(),
+            None => vec![host_id],
+        };
+        let rack_power: f64 = rack_hosts.iter().map(|id| self.host_states[id].power).sum();
+        for id in rack_hosts {
+            if let Some(model) = self.thermal_models.get(&id) {
+                self.host_states.get_mut(&id).unwrap().inlet_temperature = Some(model.inlet_temperature(rack_power));
+            }
+        }
     }
 
     /// Processes periodic host state updates received from host manages.
+    #[allow(clippy::too_many_arguments)]
     fn update_host_state(
         &mut self,
         host_id: u32,
         cpu_load: f64,
         memory_load: f64,
+        cpu_allocated: u32,
+        memory_allocated: u64,
+        power: f64,
+        power_state: HostPowerState,
         recently_added_vms: Vec<u32>,
         recently_removed_vms: Vec<u32>,
     ) {
@@ -102,7 +329,12 @@ impl Monitoring {
         if let Some(host) = self.host_states.get_mut(&host_id) {
             host.cpu_load = cpu_load;
             host.memory_load = memory_load;
+            host.cpu_allocated = cpu_allocated;
+            host.memory_allocated = memory_allocated;
+            host.power = power;
+            host.power_state = power_state;
 
+            let placement_changed = !recently_added_vms.is_empty() || !recently_removed_vms.is_empty();
             for vm_id in recently_added_vms {
                 host.vms.insert(vm_id);
             }
@@ -110,6 +342,21 @@ impl Monitoring {
             for vm_id in recently_removed_vms {
                 host.vms.remove(&vm_id);
             }
+            let usage = ResourceUsage {
+                cpu_used: host.cpu_load * host.cpu_total as f64,
+                cpu_allocated: host.cpu_allocated as f64,
+                cpu_total: host.cpu_total as f64,
+                memory_used: host.memory_load * host.memory_total as f64,
+                memory_allocated: host.memory_allocated as f64,
+                memory_total: host.memory_total as f64,
+            };
+            self.update_inlet_temperatures(host_id);
+            let tenant_usage = self.host_tenant_usage(host_id);
+            self.utilization
+                .update_host(self.ctx.time(), host_id, usage, tenant_usage);
+            if placement_changed {
+                self.update_traffic_routing();
+            }
         }
     }
 }
@@ -121,10 +368,24 @@ impl EventHandler for Monitoring {
                 host_id,
                 cpu_load,
                 memory_load,
+                cpu_allocated,
+                memory_allocated,
+                power,
+                power_state,
                 recently_added_vms,
                 recently_removed_vms,
             } => {
-                self.update_host_state(host_id, cpu_load, memory_load, recently_added_vms, recently_removed_vms);
+                self.update_host_state(
+                    host_id,
+                    cpu_load,
+                    memory_load,
+                    cpu_allocated,
+                    memory_allocated,
+                    power,
+                    power_state,
+                    recently_added_vms,
+                    recently_removed_vms,
+                );
             }
         })
     }
@@ -1,5 +1,6 @@
 //! Component managing the authoritative copy of resource pool state.
 
+use std::borrow::Cow;
 use std::cell::RefCell;
 use std::collections::HashSet;
 use std::rc::Rc;
@@ -11,11 +12,16 @@ use dslab_core::handler::EventHandler;
 
 use crate::core::common::{Allocation, AllocationVerdict};
 use crate::core::config::sim_config::SimulationConfig;
+use crate::core::control_plane::ControlPlaneLatency;
 use crate::core::events::allocation::{
     AllocationCommitFailed, AllocationCommitRequest, AllocationCommitSucceeded, AllocationFailed, AllocationReleased,
-    AllocationRequest, VmCreateRequest,
+    PreemptionCommitFailed, PreemptionCommitRequest, PreemptionCommitted, ReservationCommitFailed,
+    ReservationCommitRequest, ReservationCommitSucceeded, ReservationStarted, VmCreateRequest, VmPreemptRequest,
 };
+use crate::core::events::vm_api::VmPreempted;
 use crate::core::logger::Logger;
+use crate::core::preemption::PreemptionVictim;
+use crate::core::reservation::{Reservation, ReservationTable};
 use crate::core::resource_pool::ResourcePoolState;
 use crate::core::vm_api::VmAPI;
 
@@ -28,19 +34,30 @@ use crate::core::vm_api::VmAPI;
 /// schedulers, which in turn update their local pool states. PS also passes the committed decision to the host which
 /// has been selected for VM execution.
 ///
-/// If a conflict is detected, PS rejects the update, notifies about it the corresponding scheduler and resubmits the
-/// failed allocation request with configurable retry delay.
+/// If a conflict is detected, PS rejects the update and notifies about it the corresponding scheduler, which retries
+/// the failed allocation request according to its retry policy.
+///
+/// Advance reservations of capacity are committed in the same way. PS keeps the authoritative reservation table and
+/// allocates the reserved VM at the reservation start time. The capacity reserved by future reservations is not
+/// available for on-demand VMs.
+///
+/// Placements which require preemption of lower-priority VMs are committed only if all victims are still allocated
+/// on their hosts and the placements (including the new locations of migrated victims) fit after their release.
+/// In this case PS asks the hosts to stop the victims, restarts the migrated victims on their target hosts,
+/// and notifies VM API about the preemptions.
 ///
 /// A user can configure the message delay for communication between schedulers and PS, which influences the staleness
-/// of scheduler states and conflict rate.
+/// of scheduler states and conflict rate. The delay of messages sent by PS can be also configured separately as
+/// a random distribution via control-plane config (see [`ControlPlaneLatency`]).
 pub struct PlacementStore {
     allow_vm_overcommit: bool,
     pool_state: ResourcePoolState,
+    reservations: ReservationTable,
     schedulers: HashSet<u32>,
     vm_api: Rc<RefCell<VmAPI>>,
     ctx: SimulationContext,
     logger: Rc<RefCell<Box<dyn Logger>>>,
-    sim_config: SimulationConfig,
+    control_plane: ControlPlaneLatency,
 }
 
 impl PlacementStore {
@@ -55,23 +72,36 @@ impl PlacementStore {
         Self {
             allow_vm_overcommit,
             pool_state: ResourcePoolState::new(),
+            reservations: ReservationTable::new(),
             schedulers: HashSet::new(),
             vm_api,
             ctx,
             logger,
-            sim_config,
+            control_plane: ControlPlaneLatency::from_config(&sim_config).unwrap(),
         }
     }
 
+    /// Returns the delay of message sent by placement store.
+    fn message_delay(&self) -> f64 {
+        self.control_plane.placement_store_delay.sample(&self.ctx)
+    }
+
     /// Returns component ID.
     pub fn get_id(&self) -> u32 {
         self.ctx.id()
     }
 
     /// Adds new host to resource pool state.
-    pub fn add_host(&mut self, id: u32, cpu_total: u32, memory_total: u64, rack_id: Option<u32>) {
+    pub fn add_host(
+        &mut self,
+        id: u32,
+        cpu_total: u32,
+        memory_total: u64,
+        rack_id: Option<u32>,
+        host_type: Option<String>,
+    ) {
         self.pool_state
-            .add_host(id, cpu_total, memory_total, cpu_total, memory_total, rack_id);
+            .add_host(id, cpu_total, memory_total, cpu_total, memory_total, rack_id, host_type);
     }
 
     /// Registers scheduler so that PS can notify it about allocation events.
@@ -99,8 +129,13 @@ impl PlacementStore {
         // check if all placements can be committed
         let mut can_be_committed = true;
         let mut pool_state_copy = self.pool_state.clone();
+        let pool_state = if self.reservations.is_empty() {
+            Cow::Borrowed(&self.pool_state)
+        } else {
+            Cow::Owned(self.reservations.apply_to_pool_state(&self.pool_state, self.ctx.time()))
+        };
         for (alloc, &host_id) in allocations.iter().zip(host_ids.iter()) {
-            if self.pool_state.can_allocate(alloc, host_id, self.allow_vm_overcommit) == AllocationVerdict::Success {
+            if pool_state.can_allocate(alloc, host_id, self.allow_vm_overcommit) == AllocationVerdict::Success {
                 pool_state_copy.allocate(alloc, host_id);
             } else {
                 self.logger.borrow_mut().log_debug(
@@ -118,55 +153,240 @@ impl PlacementStore {
 
         // commit placements or reject request
         if can_be_committed {
-            for (alloc, &host_id) in allocations.iter().zip(host_ids.iter()) {
-                self.pool_state.allocate(alloc, host_id);
-                self.logger.borrow_mut().log_debug(
-                    &self.ctx,
-                    format!(
-                        "committed placement of vm {} to host {}",
-                        alloc.id,
-                        self.ctx.lookup_name(host_id)
-                    ),
+            self.commit_placements(vm_ids, host_ids, allocations);
+        } else {
+            self.logger.borrow_mut().log_debug(
+                &self.ctx,
+                format!("rejected allocation commit request with {} vms", vm_ids.len()),
+            );
+            if let Some(scheduler) = from_scheduler {
+                self.ctx.emit(
+                    AllocationCommitFailed { vm_ids, host_ids },
+                    scheduler,
+                    self.message_delay(),
                 );
+            }
+        }
+    }
+
+    /// Applies placements to the resource pool state, passes them to hosts and notifies schedulers.
+    fn commit_placements(&mut self, vm_ids: Vec<u32>, host_ids: Vec<u32>, allocations: Vec<Allocation>) {
+        for (alloc, &host_id) in allocations.iter().zip(host_ids.iter()) {
+            self.pool_state.allocate(alloc, host_id);
+            self.logger.borrow_mut().log_debug(
+                &self.ctx,
+                format!(
+                    "committed placement of vm {} to host {}",
+                    alloc.id,
+                    self.ctx.lookup_name(host_id)
+                ),
+            );
+            self.ctx
+                .emit(VmCreateRequest { vm_id: alloc.id }, host_id, self.message_delay());
+        }
+        for scheduler in self.schedulers.iter() {
+            self.ctx.emit(
+                AllocationCommitSucceeded {
+                    vm_ids: vm_ids.clone(),
+                    host_ids: host_ids.clone(),
+                },
+                *scheduler,
+                self.message_delay(),
+            );
+        }
+    }
+
+    /// Processes commit requests for placements which require preemption of lower-priority VMs.
+    fn on_preemption_commit_request(
+        &mut self,
+        vm_ids: Vec<u32>,
+        host_ids: Vec<u32>,
+        victims: Vec<PreemptionVictim>,
+        scheduler: u32,
+    ) {
+        let allocations: Vec<Allocation> = vm_ids
+            .iter()
+            .map(|vm_id| self.vm_api.borrow().get_vm_allocation(*vm_id))
+            .collect();
+
+        // check if preemption can be committed
+        let mut pool_state = self.reservations.apply_to_pool_state(&self.pool_state, self.ctx.time());
+        let mut can_be_committed = victims
+            .iter()
+            .all(|v| pool_state.get_host(v.host_id).allocations.contains_key(&v.vm_id));
+        if can_be_committed {
+            for victim in victims.iter() {
+                let alloc = self.vm_api.borrow().get_vm_allocation(victim.vm_id);
+                pool_state.release(&alloc, victim.host_id);
+            }
+            let victim_placements = victims.iter().filter_map(|v| {
+                v.target_host
+                    .map(|target| (self.vm_api.borrow().get_vm_allocation(v.vm_id), target))
+            });
+            for (alloc, host_id) in allocations
+                .iter()
+                .cloned()
+                .zip(host_ids.iter().cloned())
+                .chain(victim_placements)
+            {
+                if pool_state.can_allocate(&alloc, host_id, self.allow_vm_overcommit) == AllocationVerdict::Success {
+                    pool_state.allocate(&alloc, host_id);
+                } else {
+                    can_be_committed = false;
+                    break;
+                }
+            }
+        }
+
+        if !can_be_committed {
+            self.logger.borrow_mut().log_debug(
+                &self.ctx,
+                format!(
+                    "rejected preemption commit request with {} vms and {} victims",
+                    vm_ids.len(),
+                    victims.len()
+                ),
+            );
+            self.ctx.emit(
+                PreemptionCommitFailed {
+                    vm_ids,
+                    host_ids,
+                    victims,
+                },
+                scheduler,
+                self.message_delay(),
+            );
+            return;
+        }
+
+        // stop victims and restart the migrated ones on target hosts
+        for victim in victims.iter() {
+            let alloc = self.vm_api.borrow().get_vm_allocation(victim.vm_id);
+            self.pool_state.release(&alloc, victim.host_id);
+            self.logger.borrow_mut().log_debug(
+                &self.ctx,
+                format!(
+                    "committed preemption of vm {} on host {}",
+                    victim.vm_id,
+                    self.ctx.lookup_name(victim.host_id)
+                ),
+            );
+            self.ctx.emit(
+                VmPreemptRequest {
+                    vm_id: victim.vm_id,
+                    is_migrating: victim.target_host.is_some(),
+                },
+                victim.host_id,
+                self.message_delay(),
+            );
+            if let Some(target_host) = victim.target_host {
+                self.pool_state.allocate(&alloc, target_host);
                 self.ctx.emit(
-                    VmCreateRequest { vm_id: alloc.id },
-                    host_id,
-                    self.sim_config.message_delay,
+                    VmCreateRequest { vm_id: victim.vm_id },
+                    target_host,
+                    self.message_delay(),
                 );
             }
+            self.ctx.emit(
+                VmPreempted {
+                    vm_id: victim.vm_id,
+                    host_id: victim.host_id,
+                    preempted_by: vm_ids[0],
+                    target_host: victim.target_host,
+                },
+                self.vm_api.borrow().get_id(),
+                self.message_delay(),
+            );
+        }
+        for scheduler in self.schedulers.iter() {
+            self.ctx.emit(
+                PreemptionCommitted {
+                    vm_ids: vm_ids.clone(),
+                    victims: victims.clone(),
+                },
+                *scheduler,
+                self.message_delay(),
+            );
+        }
+        self.commit_placements(vm_ids, host_ids, allocations);
+    }
+
+    /// Processes reservation commit requests from schedulers.
+    ///
+    /// If the reservation doesn't conflict with the current allocations and other reservations, it is added to
+    /// the reservation table and all schedulers are notified. Otherwise, the reservation is rejected.
+    fn on_reservation_commit_request(
+        &mut self,
+        vm_id: u32,
+        host_id: u32,
+        start_time: f64,
+        end_time: f64,
+        scheduler: u32,
+    ) {
+        let alloc = self.vm_api.borrow().get_vm_allocation(vm_id);
+        if self
+            .reservations
+            .can_reserve(&self.pool_state, &alloc, host_id, start_time, end_time)
+        {
+            self.logger.borrow_mut().log_debug(
+                &self.ctx,
+                format!(
+                    "committed reservation of vm {} on host {} from {} to {}",
+                    vm_id,
+                    self.ctx.lookup_name(host_id),
+                    start_time,
+                    end_time
+                ),
+            );
+            self.reservations.add(Reservation {
+                vm_id,
+                host_id,
+                start_time,
+                end_time,
+                cpu_usage: alloc.cpu_usage,
+                memory_usage: alloc.memory_usage,
+                started: false,
+            });
             for scheduler in self.schedulers.iter() {
                 self.ctx.emit(
-                    AllocationCommitSucceeded {
-                        vm_ids: vm_ids.clone(),
-                        host_ids: host_ids.clone(),
+                    ReservationCommitSucceeded {
+                        vm_id,
+                        host_id,
+                        start_time,
+                        end_time,
                     },
                     *scheduler,
-                    self.sim_config.message_delay,
+                    self.message_delay(),
                 );
             }
+            self.ctx.emit_self(
+                ReservationStarted { vm_id, host_id },
+                (start_time - self.ctx.time()).max(0.),
+            );
         } else {
             self.logger.borrow_mut().log_debug(
                 &self.ctx,
-                format!("rejected allocation commit request with {} vms", vm_ids.len()),
+                format!(
+                    "rejected reservation of vm {} on host {} due to conflict",
+                    vm_id,
+                    self.ctx.lookup_name(host_id)
+                ),
+            );
+            self.ctx.emit(
+                ReservationCommitFailed { vm_id, host_id },
+                scheduler,
+                self.message_delay(),
             );
-            if let Some(scheduler) = from_scheduler {
-                self.ctx.emit(
-                    AllocationCommitFailed {
-                        vm_ids: vm_ids.clone(),
-                        host_ids,
-                    },
-                    scheduler,
-                    self.sim_config.message_delay,
-                );
-                self.ctx.emit(
-                    AllocationRequest { vm_ids },
-                    scheduler,
-                    self.sim_config.message_delay + self.sim_config.allocation_retry_period,
-                );
-            }
         }
     }
 
+    /// Allocates the reserved VM at the reservation start time.
+    fn on_reservation_started(&mut self, vm_id: u32, host_id: u32) {
+        self.reservations.mark_started(vm_id);
+        let alloc = self.vm_api.borrow().get_vm_allocation(vm_id);
+        self.commit_placements(vec![vm_id], vec![host_id], vec![alloc]);
+    }
+
     /// Processes AllocationFailed events from host managers.
     ///
     /// If host allocation fails, usually that means that the host is overloaded.
@@ -174,12 +394,10 @@ impl PlacementStore {
     fn on_allocation_failed(&mut self, vm_id: u32, host_id: u32) {
         let alloc = self.vm_api.borrow().get_vm_allocation(vm_id);
         self.pool_state.release(&alloc, host_id);
+        self.reservations.remove(vm_id);
         for scheduler in self.schedulers.iter() {
-            self.ctx.emit(
-                AllocationFailed { vm_id, host_id },
-                *scheduler,
-                self.sim_config.message_delay,
-            );
+            self.ctx
+                .emit(AllocationFailed { vm_id, host_id }, *scheduler, self.message_delay());
         }
     }
 
@@ -189,12 +407,10 @@ impl PlacementStore {
     fn on_allocation_released(&mut self, vm_id: u32, host_id: u32) {
         let alloc = self.vm_api.borrow().get_vm_allocation(vm_id);
         self.pool_state.release(&alloc, host_id);
+        self.reservations.remove(vm_id);
         for scheduler in self.schedulers.iter() {
-            self.ctx.emit(
-                AllocationReleased { vm_id, host_id },
-                *scheduler,
-                self.sim_config.message_delay,
-            );
+            self.ctx
+                .emit(AllocationReleased { vm_id, host_id }, *scheduler, self.message_delay());
         }
     }
 }
@@ -211,6 +427,24 @@ impl EventHandler for PlacementStore {
             AllocationReleased { vm_id, host_id } => {
                 self.on_allocation_released(vm_id, host_id)
             }
+            ReservationCommitRequest {
+                vm_id,
+                host_id,
+                start_time,
+                end_time,
+            } => {
+                self.on_reservation_commit_request(vm_id, host_id, start_time, end_time, event.src)
+            }
+            ReservationStarted { vm_id, host_id } => {
+                self.on_reservation_started(vm_id, host_id)
+            }
+            PreemptionCommitRequest {
+                vm_ids,
+                host_ids,
+                victims,
+            } => {
+                self.on_preemption_commit_request(vm_ids, host_ids, victims, event.src)
+            }
         })
     }
 }
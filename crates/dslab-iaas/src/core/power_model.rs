@@ -0,0 +1,58 @@
+//! Host power models configuration.
+
+use dslab_models::power::cpu::CpuPowerModel;
+use dslab_models::power::cpu_models::asymptotic::AsymptoticCpuPowerModel;
+use dslab_models::power::cpu_models::constant::ConstantCpuPowerModel;
+use dslab_models::power::cpu_models::cubic::CubicCpuPowerModel;
+use dslab_models::power::cpu_models::linear::LinearCpuPowerModel;
+use dslab_models::power::cpu_models::mse::MseCpuPowerModel;
+use dslab_models::power::cpu_models::square::SquareCpuPowerModel;
+use dslab_models::power::host::{HostPowerModel, HostPowerModelBuilder};
+
+use crate::core::config::options::{parse_config_value, parse_options};
+
+/// Creates host power model from config value string.
+///
+/// The model name selects the CPU power model, while options specify its parameters.
+/// Supported models: `Constant[power=...]`, `Linear[min_power=...,max_power=...]`,
+/// `Square[min_power=...,max_power=...]`, `Cubic[min_power=...,max_power=...]`,
+/// `Asymptotic[min_power=...,max_power=...,tau=...]`, `Mse[min_power=...,max_power=...,r=...]`.
+///
+/// Each model also accepts optional `idle` (CPU power at zero utilization)
+/// and `other` (power consumption of other host components) options.
+pub fn power_model_resolver(config_str: String) -> HostPowerModel {
+    let (model_name, options_str) = parse_config_value(&config_str);
+    let options = parse_options(&options_str.unwrap_or_default());
+    let option = |name: &str| -> f64 {
+        options
+            .get(name)
+            .unwrap_or_else(|| panic!("Option {} is not set in power model config: {}", name, config_str))
+            .parse::<f64>()
+            .unwrap_or_else(|_| panic!("Can't parse option {} in power model config: {}", name, config_str))
+    };
+    let cpu_model: Box<dyn CpuPowerModel> = match model_name.as_str() {
+        "Constant" => Box::new(ConstantCpuPowerModel::new(option("power"))),
+        "Linear" => Box::new(LinearCpuPowerModel::new(option("min_power"), option("max_power"))),
+        "Square" => Box::new(SquareCpuPowerModel::new(option("min_power"), option("max_power"))),
+        "Cubic" => Box::new(CubicCpuPowerModel::new(option("min_power"), option("max_power"))),
+        "Asymptotic" => Box::new(AsymptoticCpuPowerModel::new(
+            option("min_power"),
+            option("max_power"),
+            option("tau"),
+        )),
+        "Mse" => Box::new(MseCpuPowerModel::new(
+            option("min_power"),
+            option("max_power"),
+            option("r"),
+        )),
+        _ => panic!("Can't resolve: {}", config_str),
+    };
+    let mut builder = HostPowerModelBuilder::new().cpu(cpu_model);
+    if options.contains_key("idle") {
+        builder = builder.cpu_idle(option("idle"));
+    }
+    if options.contains_key("other") {
+        builder = builder.other(option("other"));
+    }
+    builder.build()
+}
@@ -0,0 +1,46 @@
+//! Host power states and transitions between them.
+
+use serde::{Deserialize, Serialize};
+
+/// Host power state.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HostPowerState {
+    /// Host is switched off.
+    Off,
+    /// Host is being switched on (from off state) or woken up (from sleep state).
+    Booting,
+    /// Host is switched on and can run VMs.
+    Active,
+    /// Host is being switched off or put to sleep.
+    ShuttingDown,
+    /// Host is in low-power sleep state.
+    Sleep,
+}
+
+/// Durations and energy costs of host power state transitions.
+///
+/// The transition energy includes all energy consumed by the host during the transition,
+/// i.e. the power model is not applied while the host is booting or shutting down.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct HostPowerStateConfig {
+    /// Duration of switching the host on from off state.
+    pub boot_duration: f64,
+    /// Energy consumed to switch the host on from off state.
+    pub boot_energy: f64,
+    /// Duration of switching the host off.
+    pub shutdown_duration: f64,
+    /// Energy consumed to switch the host off.
+    pub shutdown_energy: f64,
+    /// Duration of waking the host up from sleep state.
+    pub wake_up_duration: f64,
+    /// Energy consumed to wake the host up from sleep state.
+    pub wake_up_energy: f64,
+    /// Duration of putting the host to sleep.
+    pub sleep_duration: f64,
+    /// Energy consumed to put the host to sleep.
+    pub sleep_energy: f64,
+    /// Power consumed by the host in sleep state.
+    pub sleep_power: f64,
+    /// Power consumed by the host in off state.
+    pub off_power: f64,
+}
@@ -0,0 +1,208 @@
+//! Preemption of lower-priority VMs.
+
+use serde::Serialize;
+
+use crate::core::common::{Allocation, AllocationVerdict};
+use crate::core::config::options::{parse_config_value, parse_options};
+use crate::core::resource_pool::ResourcePoolState;
+use crate::core::vm::VmStatus;
+use crate::core::vm_api::VmAPI;
+
+/// Action applied to VMs preempted to free resources for a higher-priority VM.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PreemptionAction {
+    /// Preempted VM is stopped and becomes `Preempted`.
+    Stop,
+    /// Preempted VM is stopped and restarted on another host with enough free resources.
+    /// If there is no such host, the VM is stopped.
+    Migrate,
+}
+
+/// Defines whether and how scheduler preempts lower-priority VMs when a request cannot be placed otherwise.
+///
+/// Only running VMs with strictly lower priority than the request can be preempted.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PreemptionPolicy {
+    pub action: PreemptionAction,
+    /// Maximum number of VMs preempted on a single host to place a VM (unlimited if not set).
+    pub max_victims: Option<usize>,
+}
+
+/// VM preempted to place a higher-priority VM.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct PreemptionVictim {
+    pub vm_id: u32,
+    pub host_id: u32,
+    /// Host where the VM is restarted (if it is migrated).
+    pub target_host: Option<u32>,
+}
+
+/// Placements of a request along with the VMs which should be preempted to apply them.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PreemptionPlan {
+    pub host_ids: Vec<u32>,
+    pub victims: Vec<PreemptionVictim>,
+}
+
+/// Information about VM preemption reported to VM owners.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct PreemptionRecord {
+    pub time: f64,
+    pub vm_id: u32,
+    pub host_id: u32,
+    /// ID of the first VM from the request which caused the preemption.
+    pub preempted_by: u32,
+    /// Host where the VM is restarted (if it is migrated).
+    pub target_host: Option<u32>,
+}
+
+impl PreemptionPolicy {
+    /// Creates policy which stops the preempted VMs.
+    pub fn stop() -> Self {
+        Self {
+            action: PreemptionAction::Stop,
+            max_victims: None,
+        }
+    }
+
+    /// Creates policy which migrates the preempted VMs to other hosts if possible.
+    pub fn migrate() -> Self {
+        Self {
+            action: PreemptionAction::Migrate,
+            max_victims: None,
+        }
+    }
+
+    /// Sets the maximum number of VMs preempted on a single host to place a VM.
+    pub fn with_max_victims(mut self, max_victims: usize) -> Self {
+        self.max_victims = Some(max_victims);
+        self
+    }
+
+    /// Computes the placements of the allocations with specified priority which become possible after preempting
+    /// some lower-priority VMs.
+    ///
+    /// Each VM is placed on the host requiring the least number of preempted VMs (with the lowest total priority
+    /// among such hosts). On each host, the VMs with the lowest priority (and the largest size among them) are
+    /// preempted first. Returns None if it is not possible to place all allocations.
+    pub fn plan(
+        &self,
+        allocations: &[Allocation],
+        priority: u32,
+        pool_state: &ResourcePoolState,
+        vm_api: &VmAPI,
+    ) -> Option<PreemptionPlan> {
+        let mut state = pool_state.clone();
+        let mut host_ids = Vec::new();
+        let mut victims = Vec::new();
+        for alloc in allocations {
+            let mut best: Option<(u32, Vec<Allocation>, (usize, u64))> = None;
+            for host in state.get_hosts() {
+                if !vm_api.is_host_eligible(alloc.id, host.id) {
+                    continue;
+                }
+                let mut candidates: Vec<(u32, Allocation)> = host
+                    .allocations
+                    .values()
+                    .filter(|a| {
+                        vm_api.find_host_by_vm(a.id) == Some(host.id) && vm_api.get_vm_status(a.id) == VmStatus::Running
+                    })
+                    .map(|a| (vm_api.get_vm(a.id).borrow().priority, a.clone()))
+                    .filter(|(p, _)| *p < priority)
+                    .collect();
+                candidates.sort_by_key(|(p, a)| (*p, u32::MAX - a.cpu_usage, a.id));
+
+                // free resources of the host after releasing the selected VMs, taking into account overcommit
+                let mut cpu = (host.cpu_available, host.cpu_overcommit);
+                let mut memory = (host.memory_available, host.memory_overcommit);
+                let mut selected = Vec::new();
+                let mut total_priority = 0;
+                let mut candidates = candidates.into_iter();
+                while cpu.0 < alloc.cpu_usage || memory.0 < alloc.memory_usage {
+                    match candidates.next() {
+                        Some((p, victim)) if self.max_victims.is_none_or(|max| selected.len() < max) => {
+                            cpu = release_usage(cpu, victim.cpu_usage);
+                            memory = release_usage(memory, victim.memory_usage);
+                            total_priority += p as u64;
+                            selected.push(victim);
+                        }
+                        _ => break,
+                    }
+                }
+                if cpu.0 < alloc.cpu_usage || memory.0 < alloc.memory_usage {
+                    continue;
+                }
+                let score = (selected.len(), total_priority);
+                if best.as_ref().is_none_or(|(_, _, best_score)| score < *best_score) {
+                    best = Some((host.id, selected, score));
+                }
+            }
+
+            let (host_id, selected, _) = best?;
+            for victim in selected.iter() {
+                state.release(victim, host_id);
+            }
+            state.allocate(alloc, host_id);
+            host_ids.push(host_id);
+            victims.extend(selected.into_iter().map(|victim| (victim, host_id)));
+        }
+
+        // select target hosts for migrated VMs
+        let victims = victims
+            .into_iter()
+            .map(|(victim, host_id)| {
+                let target_host = match self.action {
+                    PreemptionAction::Stop => None,
+                    PreemptionAction::Migrate => state.get_host_ids().into_iter().find(|target| {
+                        *target != host_id
+                            && vm_api.is_host_eligible(victim.id, *target)
+                            && state.can_allocate(&victim, *target, false) == AllocationVerdict::Success
+                    }),
+                };
+                if let Some(target) = target_host {
+                    state.allocate(&victim, target);
+                }
+                PreemptionVictim {
+                    vm_id: victim.id,
+                    host_id,
+                    target_host,
+                }
+            })
+            .collect();
+        Some(PreemptionPlan { host_ids, victims })
+    }
+}
+
+/// Returns (available, overcommit) resource amounts after releasing the specified usage.
+fn release_usage<T>((available, overcommit): (T, T), usage: T) -> (T, T)
+where
+    T: Copy + Default + PartialOrd + std::ops::Add<Output = T> + std::ops::Sub<Output = T>,
+{
+    if overcommit >= usage {
+        (available, overcommit - usage)
+    } else {
+        (available + usage - overcommit, T::default())
+    }
+}
+
+/// Creates preemption policy from config value string.
+///
+/// Supported policies: `Stop` and `Migrate`, both accept optional `max_victims` option, e.g. `Stop[max_victims=2]`.
+pub fn preemption_policy_resolver(config_str: String) -> PreemptionPolicy {
+    let (name, options_str) = parse_config_value(&config_str);
+    let options = parse_options(&options_str.unwrap_or_default());
+    let mut policy = match name.as_str() {
+        "Stop" => PreemptionPolicy::stop(),
+        "Migrate" => PreemptionPolicy::migrate(),
+        _ => panic!("Can't resolve: {}", config_str),
+    };
+    if let Some(max_victims) = options.get("max_victims") {
+        policy = policy.with_max_victims(max_victims.parse::<usize>().unwrap_or_else(|_| {
+            panic!(
+                "Can't parse option max_victims in preemption policy config: {}",
+                config_str
+            )
+        }));
+    }
+    policy
+}
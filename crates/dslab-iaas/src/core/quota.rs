@@ -0,0 +1,166 @@
+//! Per-tenant resource quotas and usage accounting.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::common::Allocation;
+
+/// Action applied to allocation requests exceeding the tenant quota.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum QuotaExceededAction {
+    /// Request is rejected, i.e. its VMs become `FailedToAllocate`.
+    #[default]
+    Reject,
+    /// Request is held by the scheduler and rechecked after `allocation_retry_period`
+    /// until it fits into the quota or the allocation timeout expires.
+    Queue,
+}
+
+/// Limits on the total resources requested by active (not finished or failed) VMs of a tenant.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct TenantQuota {
+    /// Maximum total number of vCPUs (unlimited if not set).
+    pub max_cpu: Option<u32>,
+    /// Maximum total memory (unlimited if not set).
+    pub max_memory: Option<u64>,
+    /// Maximum number of VMs (unlimited if not set).
+    pub max_vm_count: Option<u32>,
+    /// Action applied to requests exceeding the quota.
+    #[serde(default)]
+    pub on_exceeded: QuotaExceededAction,
+}
+
+/// Current resource usage of a tenant.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TenantUsage {
+    pub cpu: u32,
+    pub memory: u64,
+    pub vm_count: u32,
+}
+
+/// Usage and admission statistics of a tenant.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TenantStats {
+    /// Resources requested by currently active VMs.
+    pub usage: TenantUsage,
+    /// Maximum observed usage of each resource.
+    pub peak_usage: TenantUsage,
+    /// Number of admitted VMs.
+    pub admitted_vms: u64,
+    /// Number of VMs rejected due to quota.
+    pub rejected_vms: u64,
+    /// Number of times the requests were held due to quota.
+    pub queued_requests: u64,
+}
+
+/// Result of checking the allocation request against tenant quotas.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AdmissionVerdict {
+    Admitted,
+    Rejected,
+    Queued,
+}
+
+/// Tracks the resource usage of tenants and checks allocation requests against their quotas.
+///
+/// The usage is charged when the request is admitted and is released when VM is finished or failed.
+/// VMs without tenant are always admitted.
+#[derive(Default)]
+pub struct QuotaManager {
+    quotas: HashMap<String, TenantQuota>,
+    stats: BTreeMap<String, TenantStats>,
+    charged_vms: HashMap<u32, (String, Allocation)>,
+}
+
+impl QuotaManager {
+    /// Creates quota manager without quotas.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Sets the quota of the specified tenant.
+    pub fn set_quota(&mut self, tenant: &str, quota: TenantQuota) {
+        self.quotas.insert(tenant.to_string(), quota);
+        self.stats.entry(tenant.to_string()).or_default();
+    }
+
+    /// Returns the quota of the specified tenant (if set).
+    pub fn get_quota(&self, tenant: &str) -> Option<&TenantQuota> {
+        self.quotas.get(tenant)
+    }
+
+    /// Checks if the request with specified tenant VM allocations fits into the tenant quotas and charges
+    /// the allocations if so. The request is admitted only if all its VMs fit into the quotas.
+    ///
+    /// Already charged VMs (e.g. when the admitted request is retried) are skipped.
+    pub fn admit(&mut self, requests: &[(Option<String>, Allocation)]) -> AdmissionVerdict {
+        let mut requested: BTreeMap<&str, TenantUsage> = BTreeMap::new();
+        for (tenant, alloc) in requests {
+            if let Some(tenant) = tenant {
+                if self.charged_vms.contains_key(&alloc.id) {
+                    continue;
+                }
+                let usage = requested.entry(tenant).or_default();
+                usage.cpu += alloc.cpu_usage;
+                usage.memory += alloc.memory_usage;
+                usage.vm_count += 1;
+            }
+        }
+        for (tenant, usage) in requested.iter() {
+            if let Some(quota) = self.quotas.get(*tenant) {
+                let current = self.stats.get(*tenant).map(|s| s.usage.clone()).unwrap_or_default();
+                let exceeded = quota.max_cpu.is_some_and(|max| current.cpu + usage.cpu > max)
+                    || quota.max_memory.is_some_and(|max| current.memory + usage.memory > max)
+                    || quota
+                        .max_vm_count
+                        .is_some_and(|max| current.vm_count + usage.vm_count > max);
+                if exceeded {
+                    let stats = self.stats.entry(tenant.to_string()).or_default();
+                    return match quota.on_exceeded {
+                        QuotaExceededAction::Reject => {
+                            stats.rejected_vms += usage.vm_count as u64;
+                            AdmissionVerdict::Rejected
+                        }
+                        QuotaExceededAction::Queue => {
+                            stats.queued_requests += 1;
+                            AdmissionVerdict::Queued
+                        }
+                    };
+                }
+            }
+        }
+        for (tenant, alloc) in requests {
+            if let Some(tenant) = tenant {
+                if self.charged_vms.contains_key(&alloc.id) {
+                    continue;
+                }
+                let stats = self.stats.entry(tenant.clone()).or_default();
+                stats.usage.cpu += alloc.cpu_usage;
+                stats.usage.memory += alloc.memory_usage;
+                stats.usage.vm_count += 1;
+                stats.peak_usage.cpu = stats.peak_usage.cpu.max(stats.usage.cpu);
+                stats.peak_usage.memory = stats.peak_usage.memory.max(stats.usage.memory);
+                stats.peak_usage.vm_count = stats.peak_usage.vm_count.max(stats.usage.vm_count);
+                stats.admitted_vms += 1;
+                self.charged_vms.insert(alloc.id, (tenant.clone(), alloc.clone()));
+            }
+        }
+        AdmissionVerdict::Admitted
+    }
+
+    /// Releases the usage charged for the specified VM.
+    pub fn release(&mut self, vm_id: u32) {
+        if let Some((tenant, alloc)) = self.charged_vms.remove(&vm_id) {
+            let usage = &mut self.stats.get_mut(&tenant).unwrap().usage;
+            usage.cpu -= alloc.cpu_usage;
+            usage.memory -= alloc.memory_usage;
+            usage.vm_count -= 1;
+        }
+    }
+
+    /// Returns the statistics of all tenants, which have quotas or admitted VMs.
+    pub fn stats(&self) -> &BTreeMap<String, TenantStats> {
+        &self.stats
+    }
+}
@@ -0,0 +1,258 @@
+//! Per-tenant rate limiting of cloud API requests.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+/// Behavior of API client receiving 429 (Too Many Requests) response.
+///
+/// The client retries the throttled request with exponential backoff: the `n`-th retry is performed after
+/// `min(initial_backoff * backoff_multiplier^(n-1), max_backoff)` seconds or after the `Retry-After` interval returned
+/// by the API if it is longer and `respect_retry_after` is set. After `max_retries` throttled retries the request
+/// is dropped and its VMs become `FailedToAllocate`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ClientRetryPolicy {
+    /// Maximum number of retries of throttled request.
+    pub max_retries: u32,
+    /// Delay before the first retry.
+    pub initial_backoff: f64,
+    /// Multiplier of delay between the consecutive retries.
+    pub backoff_multiplier: f64,
+    /// Maximum delay between the retries.
+    pub max_backoff: f64,
+    /// Whether to wait at least the `Retry-After` interval returned by the API.
+    pub respect_retry_after: bool,
+}
+
+impl Default for ClientRetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 5,
+            initial_backoff: 1.,
+            backoff_multiplier: 2.,
+            max_backoff: 30.,
+            respect_retry_after: true,
+        }
+    }
+}
+
+impl ClientRetryPolicy {
+    /// Policy which drops the request after the first throttled response.
+    pub fn no_retries() -> Self {
+        Self {
+            max_retries: 0,
+            ..Default::default()
+        }
+    }
+
+    /// Returns the delay before the specified retry (starting from 1) given the `Retry-After` interval.
+    pub fn backoff(&self, retry: u32, retry_after: f64) -> f64 {
+        let backoff = (self.initial_backoff * self.backoff_multiplier.powi(retry as i32 - 1)).min(self.max_backoff);
+        if self.respect_retry_after {
+            backoff.max(retry_after)
+        } else {
+            backoff
+        }
+    }
+}
+
+/// Rate limit of tenant API requests implemented as a token bucket.
+///
+/// The bucket holds up to `burst` tokens and is refilled with `rate` tokens per second. Each request (including
+/// the retries) consumes one token, and the request arriving at empty bucket is throttled.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RateLimit {
+    /// Sustained number of requests per second.
+    pub rate: f64,
+    /// Maximum number of requests accepted in a burst.
+    pub burst: f64,
+    /// Behavior of tenant clients upon throttling.
+    #[serde(default)]
+    pub retry_policy: ClientRetryPolicy,
+}
+
+impl RateLimit {
+    /// Creates rate limit with specified rate and burst size and default client retry policy.
+    pub fn new(rate: f64, burst: f64) -> Self {
+        assert!(rate > 0., "rate should be positive");
+        assert!(burst >= 1., "burst should be at least 1");
+        Self {
+            rate,
+            burst,
+            retry_policy: ClientRetryPolicy::default(),
+        }
+    }
+
+    /// Sets the client retry policy.
+    pub fn with_retry_policy(mut self, retry_policy: ClientRetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+}
+
+/// Token bucket with lazy refill.
+#[derive(Clone, Debug)]
+pub struct TokenBucket {
+    rate: f64,
+    capacity: f64,
+    tokens: f64,
+    last_update: f64,
+}
+
+impl TokenBucket {
+    /// Creates full bucket.
+    pub fn new(rate: f64, capacity: f64, time: f64) -> Self {
+        Self {
+            rate,
+            capacity,
+            tokens: capacity,
+            last_update: time,
+        }
+    }
+
+    /// Returns the number of available tokens at the specified time.
+    pub fn tokens(&mut self, time: f64) -> f64 {
+        self.tokens = (self.tokens + (time - self.last_update) * self.rate).min(self.capacity);
+        self.last_update = time;
+        self.tokens
+    }
+
+    /// Tries to consume a token. Returns `Err` with the time until the next token is available if the bucket is empty.
+    pub fn try_acquire(&mut self, time: f64) -> Result<(), f64> {
+        // tolerate rounding errors of the refill
+        if self.tokens(time) >= 1. - 1e-9 {
+            self.tokens = (self.tokens - 1.).max(0.);
+            Ok(())
+        } else {
+            Err((1. - self.tokens) / self.rate)
+        }
+    }
+}
+
+/// Throttling statistics of a tenant.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ThrottlingStats {
+    /// Number of submitted requests (not including the retries).
+    pub requests: u64,
+    /// Number of requests accepted by the API.
+    pub accepted_requests: u64,
+    /// Number of 429 responses, i.e. throttled request attempts.
+    pub throttled_responses: u64,
+    /// Number of retries performed by the clients.
+    pub retries: u64,
+    /// Number of requests dropped after exhausting the retries.
+    pub dropped_requests: u64,
+    /// Number of VMs from the dropped requests.
+    pub dropped_vms: u64,
+    /// Total delay of accepted requests caused by throttling.
+    pub throttling_delay: f64,
+}
+
+impl ThrottlingStats {
+    /// Returns the fraction of request attempts which were throttled.
+    pub fn throttled_fraction(&self) -> f64 {
+        let attempts = self.accepted_requests + self.throttled_responses;
+        if attempts > 0 {
+            self.throttled_responses as f64 / attempts as f64
+        } else {
+            0.
+        }
+    }
+
+    /// Returns the average delay of accepted requests caused by throttling.
+    pub fn mean_throttling_delay(&self) -> f64 {
+        if self.accepted_requests > 0 {
+            self.throttling_delay / self.accepted_requests as f64
+        } else {
+            0.
+        }
+    }
+}
+
+/// Decision of rate limiter on the request attempt.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ThrottlingDecision {
+    /// Request is accepted and should be passed to the scheduler.
+    Accepted,
+    /// Request is throttled, the client retries it after the specified delay.
+    Retry(f64),
+    /// Request is throttled and dropped by the client.
+    Dropped,
+}
+
+/// Checks API requests of tenants against their rate limits and models the client retries.
+///
+/// Requests of tenants without rate limit are always accepted and not accounted.
+#[derive(Default)]
+pub struct RateLimiter {
+    limits: HashMap<String, RateLimit>,
+    buckets: HashMap<String, TokenBucket>,
+    stats: BTreeMap<String, ThrottlingStats>,
+}
+
+impl RateLimiter {
+    /// Creates rate limiter without limits.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Sets the rate limit of the specified tenant, the tenant token bucket is reset to full.
+    pub fn set_limit(&mut self, tenant: &str, limit: RateLimit, time: f64) {
+        self.buckets
+            .insert(tenant.to_string(), TokenBucket::new(limit.rate, limit.burst, time));
+        self.limits.insert(tenant.to_string(), limit);
+        self.stats.entry(tenant.to_string()).or_default();
+    }
+
+    /// Returns the rate limit of the specified tenant (if set).
+    pub fn get_limit(&self, tenant: &str) -> Option<&RateLimit> {
+        self.limits.get(tenant)
+    }
+
+    /// Checks the request attempt of the specified tenant.
+    ///
+    /// `attempt` is the number of previous throttled attempts of the request and `submit_time` is the time
+    /// of its first attempt.
+    pub fn check(
+        &mut self,
+        tenant: &str,
+        vm_count: usize,
+        attempt: u32,
+        submit_time: f64,
+        time: f64,
+    ) -> ThrottlingDecision {
+        let limit = match self.limits.get(tenant) {
+            Some(limit) => limit,
+            None => return ThrottlingDecision::Accepted,
+        };
+        let stats = self.stats.get_mut(tenant).unwrap();
+        if attempt == 0 {
+            stats.requests += 1;
+        } else {
+            stats.retries += 1;
+        }
+        match self.buckets.get_mut(tenant).unwrap().try_acquire(time) {
+            Ok(()) => {
+                stats.accepted_requests += 1;
+                stats.throttling_delay += time - submit_time;
+                ThrottlingDecision::Accepted
+            }
+            Err(retry_after) => {
+                stats.throttled_responses += 1;
+                if attempt < limit.retry_policy.max_retries {
+                    ThrottlingDecision::Retry(limit.retry_policy.backoff(attempt + 1, retry_after))
+                } else {
+                    stats.dropped_requests += 1;
+                    stats.dropped_vms += vm_count as u64;
+                    ThrottlingDecision::Dropped
+                }
+            }
+        }
+    }
+
+    /// Returns the statistics of all tenants with rate limits.
+    pub fn stats(&self) -> &BTreeMap<String, ThrottlingStats> {
+        &self.stats
+    }
+}
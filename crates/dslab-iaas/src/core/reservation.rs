@@ -0,0 +1,139 @@
+//! Advance reservations of capacity.
+
+use std::collections::BTreeMap;
+
+use crate::core::common::Allocation;
+use crate::core::resource_pool::ResourcePoolState;
+
+/// Reservation of host capacity for a VM during the specified time interval.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Reservation {
+    pub vm_id: u32,
+    pub host_id: u32,
+    pub start_time: f64,
+    pub end_time: f64,
+    pub cpu_usage: u32,
+    pub memory_usage: u64,
+    /// Whether the reserved VM is already allocated on the host, i.e. is present in the resource pool state.
+    pub started: bool,
+}
+
+/// Stores reservations and checks the feasibility of new reservations and allocations.
+///
+/// Since the duration of regular (on-demand) VMs is unknown, they are assumed to occupy their hosts indefinitely.
+/// Therefore a reservation is feasible only if it fits on the host along with all current on-demand VMs and
+/// other reservations overlapping with it, and an on-demand VM can be allocated only if it doesn't take the capacity
+/// reserved by any future reservation on the host.
+#[derive(Clone, Default)]
+pub struct ReservationTable {
+    reservations: BTreeMap<u32, Reservation>,
+}
+
+impl ReservationTable {
+    /// Creates empty reservation table.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Adds reservation to the table.
+    pub fn add(&mut self, reservation: Reservation) {
+        self.reservations.insert(reservation.vm_id, reservation);
+    }
+
+    /// Removes reservation of the specified VM from the table.
+    pub fn remove(&mut self, vm_id: u32) -> Option<Reservation> {
+        self.reservations.remove(&vm_id)
+    }
+
+    /// Returns reservation of the specified VM.
+    pub fn get(&self, vm_id: u32) -> Option<&Reservation> {
+        self.reservations.get(&vm_id)
+    }
+
+    /// Marks reservation of the specified VM as started.
+    pub fn mark_started(&mut self, vm_id: u32) {
+        if let Some(reservation) = self.reservations.get_mut(&vm_id) {
+            reservation.started = true;
+        }
+    }
+
+    /// Returns true if there are no reservations.
+    pub fn is_empty(&self) -> bool {
+        self.reservations.is_empty()
+    }
+
+    /// Returns the number of reservations.
+    pub fn len(&self) -> usize {
+        self.reservations.len()
+    }
+
+    fn host_reservations(&self, host_id: u32) -> impl Iterator<Item = &Reservation> {
+        self.reservations.values().filter(move |r| r.host_id == host_id)
+    }
+
+    /// Returns the maximum total usage of reservations on the host over time interval [from, to),
+    /// optionally excluding the started reservations.
+    fn max_reserved_usage(&self, host_id: u32, from: f64, to: f64, include_started: bool) -> (u32, u64) {
+        let reservations: Vec<&Reservation> = self
+            .host_reservations(host_id)
+            .filter(|r| include_started || !r.started)
+            .filter(|r| r.start_time < to && r.end_time > from)
+            .collect();
+        // the usage can only increase at the interval start or reservation start times
+        let mut points: Vec<f64> = reservations.iter().map(|r| r.start_time.max(from)).collect();
+        points.push(from);
+        let mut max_usage = (0, 0);
+        for t in points {
+            let mut usage = (0, 0);
+            for r in reservations.iter().filter(|r| r.start_time <= t && t < r.end_time) {
+                usage.0 += r.cpu_usage;
+                usage.1 += r.memory_usage;
+            }
+            max_usage.0 = max_usage.0.max(usage.0);
+            max_usage.1 = max_usage.1.max(usage.1);
+        }
+        max_usage
+    }
+
+    /// Returns the usage of started reservations on the host.
+    fn started_usage(&self, host_id: u32) -> (u32, u64) {
+        self.host_reservations(host_id)
+            .filter(|r| r.started)
+            .fold((0, 0), |acc, r| (acc.0 + r.cpu_usage, acc.1 + r.memory_usage))
+    }
+
+    /// Checks if the specified allocation can be reserved on the host during time interval [start_time, end_time).
+    pub fn can_reserve(
+        &self,
+        pool_state: &ResourcePoolState,
+        alloc: &Allocation,
+        host_id: u32,
+        start_time: f64,
+        end_time: f64,
+    ) -> bool {
+        let started = self.started_usage(host_id);
+        let on_demand_cpu = pool_state.get_allocated_cpu(host_id).saturating_sub(started.0);
+        let on_demand_memory = pool_state.get_allocated_memory(host_id).saturating_sub(started.1);
+        let reserved = self.max_reserved_usage(host_id, start_time, end_time, true);
+        on_demand_cpu + reserved.0 + alloc.cpu_usage <= pool_state.get_total_cpu(host_id)
+            && on_demand_memory + reserved.1 + alloc.memory_usage <= pool_state.get_total_memory(host_id)
+    }
+
+    /// Returns a copy of resource pool state, where the capacity reserved by future reservations starting from
+    /// the specified time is marked as allocated. This state can be used to place on-demand VMs.
+    pub fn apply_to_pool_state(&self, pool_state: &ResourcePoolState, time: f64) -> ResourcePoolState {
+        let mut result = pool_state.clone();
+        let hosts: Vec<u32> = self.reservations.values().map(|r| r.host_id).collect();
+        for host_id in hosts {
+            let (cpu_usage, memory_usage) = self.max_reserved_usage(host_id, time, f64::INFINITY, false);
+            // a reserved allocation ID which doesn't clash with VM IDs
+            let alloc = Allocation {
+                id: u32::MAX - host_id,
+                cpu_usage,
+                memory_usage,
+            };
+            result.allocate(&alloc, host_id);
+        }
+        result
+    }
+}
@@ -22,6 +22,7 @@ pub struct HostInfo {
     pub allocations: BTreeMap<u32, Allocation>,
 
     pub rack_id: Option<u32>,
+    pub host_type: Option<String>,
 }
 
 impl HostInfo {
@@ -33,6 +34,7 @@ impl HostInfo {
         cpu_available: u32,
         memory_available: u64,
         rack_id: Option<u32>,
+        host_type: Option<String>,
     ) -> Self {
         Self {
             id,
@@ -44,6 +46,7 @@ impl HostInfo {
             memory_overcommit: 0,
             allocations: BTreeMap::new(),
             rack_id,
+            host_type,
         }
     }
 }
@@ -60,6 +63,7 @@ impl ResourcePoolState {
     }
 
     /// Adds host to resource pool.
+    #[allow(clippy::too_many_arguments)]
     pub fn add_host(
         &mut self,
         id: u32,
@@ -68,10 +72,19 @@ impl ResourcePoolState {
         cpu_available: u32,
         memory_available: u64,
         rack_id: Option<u32>,
+        host_type: Option<String>,
     ) {
         self.hosts.insert(
             id,
-            HostInfo::new(id, cpu_total, memory_total, cpu_available, memory_available, rack_id),
+            HostInfo::new(
+                id,
+                cpu_total,
+                memory_total,
+                cpu_available,
+                memory_available,
+                rack_id,
+                host_type,
+            ),
         );
     }
 
@@ -85,11 +98,28 @@ impl ResourcePoolState {
         self.hosts.values()
     }
 
+    /// Returns the resource pool state which includes only the hosts matching the specified predicate.
+    pub fn filter_hosts<F: Fn(u32) -> bool>(&self, predicate: F) -> Self {
+        Self {
+            hosts: self
+                .hosts
+                .iter()
+                .filter(|(id, _)| predicate(**id))
+                .map(|(id, host)| (*id, host.clone()))
+                .collect(),
+        }
+    }
+
     /// Returns host info by its ID.
     pub fn get_host(&self, host_id: u32) -> &HostInfo {
         self.hosts.get(&host_id).unwrap()
     }
 
+    /// Returns the type of the specified host (if set).
+    pub fn get_host_type(&self, host_id: u32) -> Option<&str> {
+        self.hosts[&host_id].host_type.as_deref()
+    }
+
     /// Returns the number of hosts.
     pub fn get_host_count(&self) -> u32 {
         self.hosts.len() as u32
@@ -138,6 +168,9 @@ impl ResourcePoolState {
     /// Removes the specified allocation on the specified host.
     pub fn release(&mut self, alloc: &Allocation, host_id: u32) {
         if let Some(host) = self.hosts.get_mut(&host_id) {
+            if !host.allocations.contains_key(&alloc.id) {
+                return;
+            }
             if host.cpu_overcommit >= alloc.cpu_usage {
                 host.cpu_overcommit -= alloc.cpu_usage;
             } else {
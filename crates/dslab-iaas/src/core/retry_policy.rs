@@ -0,0 +1,166 @@
+//! Policies for retrying failed VM placements.
+
+use crate::core::common::Allocation;
+use crate::core::config::options::{parse_config_value, parse_options};
+
+/// Defines the delay before retrying a failed placement.
+#[derive(Clone, Debug, PartialEq)]
+pub enum RetryDelay {
+    /// Request is retried as soon as some resources are released in the scheduler's resource pool state.
+    Immediate,
+    /// Request is retried after the fixed delay.
+    Fixed(f64),
+    /// Request is retried after the delay, which starts from `initial_delay` and is multiplied by `multiplier`
+    /// after each failed attempt, but does not exceed `max_delay`.
+    ExponentialBackoff {
+        initial_delay: f64,
+        multiplier: f64,
+        max_delay: f64,
+    },
+}
+
+/// Order in which the queued requests are retried.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum QueueOrdering {
+    /// Requests are retried in the order of their arrival.
+    Fifo,
+    /// Requests with the smallest total CPU usage are retried first.
+    SmallestFirst,
+    /// Requests with the largest total CPU usage are retried first.
+    LargestFirst,
+}
+
+/// Bounded queue of failed requests waiting for retry.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RetryQueue {
+    /// Maximum number of requests in the queue, requests failed when the queue is full are rejected.
+    pub capacity: usize,
+    /// Order in which the queued requests are retried.
+    pub ordering: QueueOrdering,
+}
+
+/// Defines how scheduler handles requests which it failed to place (no suitable host found)
+/// or whose placement was rejected by the placement store.
+///
+/// Without queue, each failed request is retried independently after the delay computed from its number of attempts.
+/// With queue, failed requests are kept in the scheduler queue and all queued requests are retried in the queue order
+/// after the retry delay or when some resources are released. In both cases the request is rejected
+/// (its VMs become `FailedToAllocate`) after `max_attempts` failed attempts, if set.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RetryPolicy {
+    pub delay: RetryDelay,
+    pub max_attempts: Option<u32>,
+    pub queue: Option<RetryQueue>,
+}
+
+impl RetryPolicy {
+    /// Creates policy which retries failed requests after the fixed delay without limit on attempts.
+    pub fn fixed(delay: f64) -> Self {
+        Self {
+            delay: RetryDelay::Fixed(delay),
+            max_attempts: None,
+            queue: None,
+        }
+    }
+
+    /// Creates policy which retries failed requests as soon as resources are released.
+    pub fn immediate() -> Self {
+        Self {
+            delay: RetryDelay::Immediate,
+            max_attempts: None,
+            queue: None,
+        }
+    }
+
+    /// Creates policy which retries failed requests with exponentially growing delay.
+    pub fn exponential_backoff(initial_delay: f64, multiplier: f64, max_delay: f64) -> Self {
+        Self {
+            delay: RetryDelay::ExponentialBackoff {
+                initial_delay,
+                multiplier,
+                max_delay,
+            },
+            max_attempts: None,
+            queue: None,
+        }
+    }
+
+    /// Sets the maximum number of placement attempts after which the request is rejected.
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = Some(max_attempts);
+        self
+    }
+
+    /// Sets the bounded queue for failed requests.
+    pub fn with_queue(mut self, capacity: usize, ordering: QueueOrdering) -> Self {
+        self.queue = Some(RetryQueue { capacity, ordering });
+        self
+    }
+
+    /// Returns the delay before the next retry after the specified number of failed attempts (starting from 1).
+    ///
+    /// Returns None if the request should be retried only upon the release of resources.
+    pub fn retry_delay(&self, attempts: u32) -> Option<f64> {
+        match &self.delay {
+            RetryDelay::Immediate => None,
+            RetryDelay::Fixed(delay) => Some(*delay),
+            RetryDelay::ExponentialBackoff {
+                initial_delay,
+                multiplier,
+                max_delay,
+            } => Some((initial_delay * multiplier.powi(attempts as i32 - 1)).min(*max_delay)),
+        }
+    }
+
+    /// Returns the key used to order the queued requests (smaller keys are retried first).
+    pub(crate) fn ordering_key(&self, allocations: &[Allocation]) -> i64 {
+        let cpu_usage: i64 = allocations.iter().map(|a| a.cpu_usage as i64).sum();
+        match self.queue.as_ref().map(|q| q.ordering) {
+            Some(QueueOrdering::SmallestFirst) => cpu_usage,
+            Some(QueueOrdering::LargestFirst) => -cpu_usage,
+            Some(QueueOrdering::Fifo) | None => 0,
+        }
+    }
+}
+
+/// Creates retry policy from config value string.
+///
+/// Supported delays: `Immediate`, `Fixed[delay=...]`,
+/// `ExponentialBackoff[initial_delay=...,multiplier=...,max_delay=...]`.
+/// Each policy also accepts optional `max_attempts`, `queue_capacity`
+/// and `queue_ordering` (`Fifo`, `SmallestFirst` or `LargestFirst`) options.
+pub fn retry_policy_resolver(config_str: String) -> RetryPolicy {
+    let (name, options_str) = parse_config_value(&config_str);
+    let options = parse_options(&options_str.unwrap_or_default());
+    let option = |name: &str| -> f64 {
+        options
+            .get(name)
+            .unwrap_or_else(|| panic!("Option {} is not set in retry policy config: {}", name, config_str))
+            .parse::<f64>()
+            .unwrap_or_else(|_| panic!("Can't parse option {} in retry policy config: {}", name, config_str))
+    };
+    let mut policy = match name.as_str() {
+        "Immediate" => RetryPolicy::immediate(),
+        "Fixed" => RetryPolicy::fixed(option("delay")),
+        "ExponentialBackoff" => {
+            RetryPolicy::exponential_backoff(option("initial_delay"), option("multiplier"), option("max_delay"))
+        }
+        _ => panic!("Can't resolve: {}", config_str),
+    };
+    if options.contains_key("max_attempts") {
+        policy = policy.with_max_attempts(option("max_attempts") as u32);
+    }
+    if options.contains_key("queue_capacity") {
+        let ordering = match options.get("queue_ordering").map(|s| s.as_str()) {
+            None | Some("Fifo") => QueueOrdering::Fifo,
+            Some("SmallestFirst") => QueueOrdering::SmallestFirst,
+            Some("LargestFirst") => QueueOrdering::LargestFirst,
+            Some(other) => panic!(
+                "Unknown queue ordering {} in retry policy config: {}",
+                other, config_str
+            ),
+        };
+        policy = policy.with_queue(option("queue_capacity") as usize, ordering);
+    }
+    policy
+}
@@ -1,6 +1,9 @@
 //! Component performing allocation of resources for new VMs.
 
+use std::borrow::Cow;
 use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+use std::mem;
 use std::rc::Rc;
 
 use dslab_core::cast;
@@ -10,18 +13,85 @@ use dslab_core::handler::EventHandler;
 
 use crate::core::common::Allocation;
 use crate::core::config::sim_config::SimulationConfig;
+use crate::core::control_plane::ControlPlaneLatency;
+use crate::core::drf::DominantShareTracker;
 use crate::core::events::allocation::{
     AllocationCommitFailed, AllocationCommitRequest, AllocationCommitSucceeded, AllocationFailed, AllocationReleased,
-    AllocationRequest,
+    AllocationRequest, BatchAllocationRequest, PreemptionCommitFailed, PreemptionCommitRequest, PreemptionCommitted,
+    ReservationCommitFailed, ReservationCommitRequest, ReservationCommitSucceeded, ReservationRequest,
+    RetryQueuedRequests,
 };
 use crate::core::events::vm_api::VmStatusChanged;
+use crate::core::gang::GangTopology;
 use crate::core::logger::Logger;
 use crate::core::monitoring::Monitoring;
+use crate::core::preemption::{PreemptionPolicy, PreemptionVictim};
+use crate::core::quota::AdmissionVerdict;
+use crate::core::reservation::{Reservation, ReservationTable};
 use crate::core::resource_pool::ResourcePoolState;
+use crate::core::retry_policy::RetryPolicy;
 use crate::core::vm::VmStatus;
 use crate::core::vm_api::VmAPI;
 use crate::core::vm_placement_algorithm::VMPlacementAlgorithm;
 
+/// Statistics of scheduler operation.
+#[derive(Clone, Debug, Default)]
+pub struct SchedulerStats {
+    /// Number of processed allocation requests (including retries).
+    pub processed_requests: u64,
+    /// Number of VMs for which a placement decision was made.
+    pub placed_vms: u64,
+    /// Number of VMs for which a placement attempt failed and was rescheduled for retry.
+    pub placement_failures: u64,
+    /// Number of VMs whose placement decision was rejected by the placement store.
+    pub commit_failures: u64,
+    /// Number of VMs failed due to allocation timeout.
+    pub timed_out_vms: u64,
+    /// Number of VMs rejected by the retry policy (after too many attempts or due to full queue).
+    pub rejected_vms: u64,
+    /// Number of VMs whose placement was successfully committed.
+    pub committed_vms: u64,
+    /// Total queueing delay of committed VMs, i.e. the time from VM allocation request to the placement decision.
+    pub total_queueing_delay: f64,
+    /// Maximum queueing delay of committed VMs.
+    pub max_queueing_delay: f64,
+    /// Number of VMs rejected due to tenant quotas.
+    pub quota_rejected_vms: u64,
+    /// Number of reservations committed by this scheduler.
+    pub accepted_reservations: u64,
+    /// Number of reservations rejected due to insufficient capacity, conflict with other reservations or tenant quota.
+    pub rejected_reservations: u64,
+    /// Number of VMs placed by preempting lower-priority VMs.
+    pub preempting_vms: u64,
+    /// Number of VMs stopped due to preemption.
+    pub preempted_vms: u64,
+    /// Number of VMs migrated to other hosts due to preemption.
+    pub migrated_vms: u64,
+    /// Number of gangs whose placement was successfully committed.
+    pub placed_gangs: u64,
+    /// Number of gangs failed because they could not be placed within their time window.
+    pub expired_gangs: u64,
+}
+
+impl SchedulerStats {
+    /// Returns the mean queueing delay of committed VMs.
+    pub fn mean_queueing_delay(&self) -> f64 {
+        if self.committed_vms == 0 {
+            0.
+        } else {
+            self.total_queueing_delay / self.committed_vms as f64
+        }
+    }
+}
+
+/// State of allocation request being processed by scheduler, identified by the ID of its first VM.
+struct RequestState {
+    attempts: u32,
+    arrival_seq: u64,
+    decision_time: Option<f64>,
+    dispatched: bool,
+}
+
 /// Scheduler processes VM allocation requests by selecting hosts for running new VMs.
 ///
 /// It stores a local copy of resource pool state, which includes current resource allocations on each host.
@@ -33,6 +103,36 @@ use crate::core::vm_placement_algorithm::VMPlacementAlgorithm;
 /// produce conflicts. For example, both schedulers have decided to place the corresponding VMs on the same host,
 /// which cannot accommodate both of these VMs. The resolution of such conflicts and synchronization of scheduler
 /// states is performed via `PlacementStore` component.
+///
+/// The handling of requests which failed to be placed or committed is defined by the configured [`RetryPolicy`]
+/// (by default, such requests are retried after `allocation_retry_period` from the simulation config).
+///
+/// Scheduler also processes advance reservation requests, which specify the future time interval for running VM.
+/// The reserved host is selected as the first host that can accommodate the VM during this interval along with
+/// the other reservations (see [`ReservationTable`]), and the reservation is committed in the placement store.
+/// The requests that conflict with existing reservations are rejected without retries.
+///
+/// If the [`PreemptionPolicy`] is set, the requests which cannot be placed otherwise can preempt (stop or migrate)
+/// running VMs with lower priority. The placements along with preempted VMs are committed in the placement store.
+///
+/// Multi-VM requests with registered [`GangSpec`](crate::core::gang::GangSpec) are placed according to the gang
+/// topology constraint and fail as a whole if not placed within the gang time window.
+///
+/// The time spent to process each allocation request can be configured via control-plane config
+/// (see [`ControlPlaneLatency`]). Scheduler processes requests sequentially, so the placement decisions are delayed
+/// while the scheduler is busy, which increases the queueing delay of requests.
+/// Gangs with topology constraints do not preempt other VMs.
+///
+/// Scheduler tracks the resources allocated to tenants in its resource pool and their dominant shares
+/// (see [`DominantShareTracker`]). In fair sharing mode, the scheduler implements Dominant Resource Fairness (DRF):
+/// all arrived requests are added to the pending queue, which is dispatched by progressive filling upon each arrival
+/// and release of resources. The next dispatched request is the earliest request of the tenant with the lowest
+/// dominant share, taking into account the placements made in the current round, and the requests of VMs without
+/// tenant are dispatched first. The requests which cannot be placed remain in the pending queue, so the released
+/// resources are offered to the tenants in the order of their dominant shares. The retry policy is used only to
+/// limit the number of attempts and to periodically dispatch the pending requests if its retry delay is set.
+/// A placement attempt is counted upon the request arrival and upon each periodic dispatch, while the dispatches
+/// triggered by other arrivals and releases of resources do not count as attempts.
 pub struct Scheduler {
     pub id: u32,
     pool_state: ResourcePoolState,
@@ -43,6 +143,20 @@ pub struct Scheduler {
     ctx: SimulationContext,
     logger: Rc<RefCell<Box<dyn Logger>>>,
     sim_config: Rc<SimulationConfig>,
+    control_plane: ControlPlaneLatency,
+    busy_until: f64,
+    stats: SchedulerStats,
+    retry_policy: RetryPolicy,
+    requests: HashMap<u32, RequestState>,
+    retry_queue: Vec<Vec<u32>>,
+    queue_retry_scheduled: bool,
+    fair_sharing: bool,
+    pending_requests: BTreeMap<Option<String>, VecDeque<Vec<u32>>>,
+    next_request_seq: u64,
+    reservations: ReservationTable,
+    preemption_policy: Option<PreemptionPolicy>,
+    allowed_hosts: Option<BTreeSet<u32>>,
+    shares: DominantShareTracker,
 }
 
 impl Scheduler {
@@ -58,6 +172,8 @@ impl Scheduler {
         logger: Rc<RefCell<Box<dyn Logger>>>,
         sim_config: Rc<SimulationConfig>,
     ) -> Self {
+        let retry_policy = RetryPolicy::fixed(sim_config.allocation_retry_period);
+        let control_plane = ControlPlaneLatency::from_config(&sim_config).unwrap();
         Self {
             id: ctx.id(),
             pool_state: snapshot,
@@ -68,28 +184,159 @@ impl Scheduler {
             ctx,
             logger,
             sim_config,
+            control_plane,
+            busy_until: 0.,
+            stats: SchedulerStats::default(),
+            retry_policy,
+            requests: HashMap::new(),
+            retry_queue: Vec::new(),
+            queue_retry_scheduled: false,
+            fair_sharing: false,
+            pending_requests: BTreeMap::new(),
+            next_request_seq: 0,
+            reservations: ReservationTable::new(),
+            preemption_policy: None,
+            allowed_hosts: None,
+            shares: DominantShareTracker::new(),
         }
     }
 
+    /// Sets the policy used to handle failed placements.
+    pub fn set_retry_policy(&mut self, retry_policy: RetryPolicy) {
+        self.retry_policy = retry_policy;
+    }
+
+    /// Sets the policy used to preempt lower-priority VMs (preemption is disabled by default).
+    pub fn set_preemption_policy(&mut self, preemption_policy: PreemptionPolicy) {
+        self.preemption_policy = Some(preemption_policy);
+    }
+
+    /// Restricts the placement of VMs to the specified hosts (all hosts are used by default).
+    ///
+    /// This allows to model several independent resource pools, e.g. clouds of different providers, each managed
+    /// by its own scheduler.
+    pub fn set_allowed_hosts(&mut self, hosts: &[u32]) {
+        self.allowed_hosts = Some(hosts.iter().copied().collect());
+    }
+
+    /// Enables or disables fair sharing mode (disabled by default).
+    ///
+    /// Should be called before submitting requests to the scheduler.
+    pub fn set_fair_sharing(&mut self, enabled: bool) {
+        self.fair_sharing = enabled;
+    }
+
+    /// Sets the weight of the specified tenant used to compute its dominant share (1 by default).
+    pub fn set_tenant_weight(&mut self, tenant: &str, weight: f64) {
+        self.shares.set_weight(tenant, weight);
+    }
+
+    /// Returns the current dominant shares of tenants with VMs allocated in the scheduler resource pool.
+    pub fn dominant_shares(&self) -> BTreeMap<String, f64> {
+        self.shares.dominant_shares(self.pool_capacity())
+    }
+
+    /// Returns the history of dominant share of the specified tenant as (time, share) pairs.
+    pub fn dominant_share_history(&self, tenant: &str) -> &[(f64, f64)] {
+        self.shares.history(tenant)
+    }
+
+    /// Returns the number of requests waiting for retry in the scheduler queue
+    /// (or waiting for dispatch in fair sharing mode).
+    pub fn queue_length(&self) -> usize {
+        self.retry_queue.len() + self.pending_requests.values().map(|queue| queue.len()).sum::<usize>()
+    }
+
+    /// Returns the scheduler statistics.
+    pub fn stats(&self) -> &SchedulerStats {
+        &self.stats
+    }
+
+    /// Returns the delay of message sent by scheduler.
+    fn message_delay(&self) -> f64 {
+        self.control_plane.scheduler_delay.sample(&self.ctx)
+    }
+
+    /// Occupies the scheduler for processing a single request and returns the delay until the processing is completed.
+    ///
+    /// Requests are processed sequentially, so the processing starts after the previous requests are processed.
+    fn take_scheduling_time(&mut self) -> f64 {
+        let time = self.ctx.time();
+        let scheduling_time = self.control_plane.scheduling_time.sample(&self.ctx);
+        self.busy_until = self.busy_until.max(time) + scheduling_time;
+        self.busy_until - time
+    }
+
+    /// Returns the total CPU and memory capacity of hosts used by this scheduler.
+    fn pool_capacity(&self) -> (u64, u64) {
+        self.pool_state
+            .get_hosts()
+            .filter(|host| self.is_host_allowed(host.id))
+            .fold((0, 0), |(cpu, memory), host| {
+                (cpu + host.cpu_total as u64, memory + host.memory_total)
+            })
+    }
+
+    /// Accounts the VM allocation on the specified host to the VM tenant.
+    fn account_allocation(&mut self, vm_id: u32, host_id: u32) {
+        let tenant = self.vm_api.borrow().get_vm(vm_id).borrow().tenant.clone();
+        if let Some(tenant) = tenant {
+            let alloc = self.vm_api.borrow().get_vm_allocation(vm_id);
+            let capacity = self.pool_capacity();
+            self.shares
+                .allocate(&tenant, host_id, &alloc, self.ctx.time(), capacity);
+        }
+    }
+
+    /// Removes the VM allocation on the specified host (or on any host) from the tenant allocations.
+    fn account_release(&mut self, vm_id: u32, host_id: Option<u32>) {
+        let capacity = self.pool_capacity();
+        self.shares.release(vm_id, host_id, self.ctx.time(), capacity);
+    }
+
     /// Adds host to local resource pool state.
-    pub fn add_host(&mut self, id: u32, cpu_total: u32, memory_total: u64, rack_id: Option<u32>) {
+    pub fn add_host(
+        &mut self,
+        id: u32,
+        cpu_total: u32,
+        memory_total: u64,
+        rack_id: Option<u32>,
+        host_type: Option<String>,
+    ) {
         self.pool_state
-            .add_host(id, cpu_total, memory_total, cpu_total, memory_total, rack_id);
+            .add_host(id, cpu_total, memory_total, cpu_total, memory_total, rack_id, host_type);
     }
 
     /// Computes the placements (hosts) for a set of allocations using the configured placement algorithm.
     ///
     /// Returns None is it is not possible to satisfy all allocations.
-    fn compute_placements(&mut self, allocations: &[Allocation]) -> Option<Vec<u32>> {
+    /// The capacity reserved by future reservations is excluded from the resource pool state passed to the algorithm.
+    fn compute_placements(&self, allocations: &[Allocation]) -> Option<Vec<u32>> {
+        self.compute_placements_in_pool(allocations, self.available_pool_state())
+    }
+
+    /// Computes the placements for a set of allocations using only the hosts from the specified resource pool state.
+    fn compute_placements_in_pool(
+        &self,
+        allocations: &[Allocation],
+        pool_state: Cow<'_, ResourcePoolState>,
+    ) -> Option<Vec<u32>> {
+        let required_hosts: Vec<Option<u32>> = allocations
+            .iter()
+            .map(|alloc| self.vm_api.borrow().volumes().required_host(alloc.id))
+            .collect();
+        if required_hosts.iter().any(|host| host.is_some()) {
+            return self.compute_constrained_placements(allocations, &required_hosts, pool_state.into_owned());
+        }
         match &self.vm_placement_algorithm {
             VMPlacementAlgorithm::Single(alg) => {
                 if allocations.len() == 1 {
-                    alg.select_host(&allocations[0], &self.pool_state, &self.monitoring.borrow())
+                    alg.select_host(&allocations[0], &pool_state, &self.monitoring.borrow())
                         .map(|h| vec![h])
                 } else {
                     // schedule VMs from multi-VM request one-by-one
                     let mut result = Vec::new();
-                    let mut pool_state_copy = self.pool_state.clone();
+                    let mut pool_state_copy = pool_state.into_owned();
                     for alloc in allocations.iter() {
                         if let Some(host) = alg.select_host(alloc, &pool_state_copy, &self.monitoring.borrow()) {
                             pool_state_copy.allocate(alloc, host);
@@ -101,41 +348,233 @@ impl Scheduler {
                     Some(result)
                 }
             }
+            VMPlacementAlgorithm::Multi(alg) => alg.select_hosts(allocations, &pool_state, &self.monitoring.borrow()),
+        }
+    }
+
+    /// Computes the placements for a gang of VMs satisfying the specified topology constraint.
+    ///
+    /// For constraints restricting the gang to a group of hosts (rack or host), the groups are tried in order
+    /// and the first group which can accommodate the whole gang is selected. For the distinct hosts constraint,
+    /// VMs are placed one-by-one excluding the hosts used by the previously placed VMs.
+    fn compute_gang_placements(&self, allocations: &[Allocation], topology: GangTopology) -> Option<Vec<u32>> {
+        let pool_state = self.available_pool_state();
+        match topology {
+            GangTopology::Any => self.compute_placements_in_pool(allocations, pool_state),
+            GangTopology::SameRack | GangTopology::SameHost => {
+                let groups = topology.host_groups(&pool_state.get_host_ids(), &self.monitoring.borrow());
+                groups.into_iter().find_map(|group| {
+                    let group_pool_state = pool_state.filter_hosts(|id| group.contains(&id));
+                    self.compute_placements_in_pool(allocations, Cow::Owned(group_pool_state))
+                })
+            }
+            GangTopology::DistinctHosts => {
+                let mut result = Vec::new();
+                let mut pool_state = pool_state.into_owned();
+                for alloc in allocations.iter() {
+                    let host =
+                        self.compute_placements_in_pool(std::slice::from_ref(alloc), Cow::Borrowed(&pool_state))?[0];
+                    pool_state = pool_state.filter_hosts(|id| id != host);
+                    result.push(host);
+                }
+                Some(result)
+            }
+        }
+    }
+
+    /// Computes placements for the request with VMs using local volumes, which can be placed only on the hosts
+    /// storing these volumes.
+    ///
+    /// Multi-VM placement algorithms are applied only if all such VMs require the same host.
+    fn compute_constrained_placements(
+        &self,
+        allocations: &[Allocation],
+        required_hosts: &[Option<u32>],
+        mut pool_state: ResourcePoolState,
+    ) -> Option<Vec<u32>> {
+        let monitoring = self.monitoring.borrow();
+        match &self.vm_placement_algorithm {
+            VMPlacementAlgorithm::Single(alg) => {
+                let mut result = Vec::new();
+                for (alloc, required_host) in allocations.iter().zip(required_hosts) {
+                    let host = match required_host {
+                        Some(required) => {
+                            alg.select_host(alloc, &pool_state.filter_hosts(|id| id == *required), &monitoring)?
+                        }
+                        None => alg.select_host(alloc, &pool_state, &monitoring)?,
+                    };
+                    pool_state.allocate(alloc, host);
+                    result.push(host);
+                }
+                Some(result)
+            }
             VMPlacementAlgorithm::Multi(alg) => {
-                alg.select_hosts(allocations, &self.pool_state, &self.monitoring.borrow())
+                let mut hosts: Vec<u32> = required_hosts.iter().flatten().copied().collect();
+                hosts.sort();
+                hosts.dedup();
+                if hosts.len() > 1 {
+                    return None;
+                }
+                alg.select_hosts(allocations, &pool_state.filter_hosts(|id| id == hosts[0]), &monitoring)
             }
         }
     }
 
+    /// Returns the local resource pool state, where the capacity reserved by future reservations is marked as allocated.
+    /// The hosts not allowed for this scheduler are excluded.
+    fn available_pool_state(&self) -> Cow<'_, ResourcePoolState> {
+        let pool_state = if self.reservations.is_empty() {
+            Cow::Borrowed(&self.pool_state)
+        } else {
+            Cow::Owned(self.reservations.apply_to_pool_state(&self.pool_state, self.ctx.time()))
+        };
+        match &self.allowed_hosts {
+            Some(hosts) => Cow::Owned(pool_state.filter_hosts(|id| hosts.contains(&id))),
+            None => pool_state,
+        }
+    }
+
+    fn is_host_allowed(&self, host_id: u32) -> bool {
+        self.allowed_hosts.as_ref().is_none_or(|hosts| hosts.contains(&host_id))
+    }
+
+    /// Tries to place the request by preempting lower-priority VMs according to the preemption policy.
+    ///
+    /// If it is possible, the scheduler updates its local state and sends the commit request to the placement store.
+    /// The request priority is the lowest priority of its VMs.
+    fn try_preempt(&mut self, vm_ids: &[u32], allocations: &[Allocation], decision_delay: f64) -> bool {
+        let Some(policy) = &self.preemption_policy else {
+            return false;
+        };
+        let priority = vm_ids
+            .iter()
+            .map(|vm_id| self.vm_api.borrow().get_vm(*vm_id).borrow().priority)
+            .min()
+            .unwrap_or(0);
+        if priority == 0 {
+            return false;
+        }
+        let plan = policy.plan(
+            allocations,
+            priority,
+            &self.available_pool_state(),
+            &self.vm_api.borrow(),
+        );
+        let Some(plan) = plan else {
+            return false;
+        };
+        for victim in plan.victims.iter() {
+            self.logger.borrow_mut().log_debug(
+                &self.ctx,
+                format!(
+                    "decided to preempt vm {} on host {}",
+                    victim.vm_id,
+                    self.ctx.lookup_name(victim.host_id)
+                ),
+            );
+            let alloc = self.vm_api.borrow().get_vm_allocation(victim.vm_id);
+            self.pool_state.release(&alloc, victim.host_id);
+            if let Some(target_host) = victim.target_host {
+                self.pool_state.allocate(&alloc, target_host);
+            }
+        }
+        for (host, alloc) in plan.host_ids.iter().zip(allocations.iter()) {
+            self.logger.borrow_mut().log_debug(
+                &self.ctx,
+                format!(
+                    "decided to place vm {} on host {} with preemption",
+                    alloc.id,
+                    self.ctx.lookup_name(*host)
+                ),
+            );
+            self.pool_state.allocate(alloc, *host);
+        }
+        self.ctx.emit(
+            PreemptionCommitRequest {
+                vm_ids: vm_ids.to_vec(),
+                host_ids: plan.host_ids,
+                victims: plan.victims,
+            },
+            self.placement_store_id,
+            decision_delay + self.message_delay(),
+        );
+        true
+    }
+
     /// Processes allocation request by selecting host for running each VM.
     ///
     /// Host selection is performed by invoking the configured VM placement algorithm.
     /// If a suitable host is found, the scheduler updates its local state with new allocation and tries to commit its
     /// decision in the placement store.
-    /// If a suitable host is not found, the request is rescheduled for retry after the configured period.
+    /// If a suitable host is not found, the scheduler tries to preempt lower-priority VMs (if the preemption policy is
+    /// set). Otherwise, the request is retried according to the configured retry policy.
     fn on_allocation_request(&mut self, vm_ids: Vec<u32>) {
+        self.stats.processed_requests += 1;
+        if let Some(vm_ids) = self.try_place_request(vm_ids) {
+            self.on_placement_failure(vm_ids);
+        }
+    }
+
+    /// Tries to place the request and returns it back if a suitable placement is not found.
+    fn try_place_request(&mut self, vm_ids: Vec<u32>) -> Option<Vec<u32>> {
         // check if request is timed out
         let start_time = self.vm_api.borrow().get_vm(vm_ids[0]).borrow().allocation_start_time;
-        if self.ctx.time() > start_time + self.sim_config.vm_allocation_timeout {
-            for vm_id in vm_ids {
-                self.ctx.emit(
-                    VmStatusChanged {
-                        vm_id,
-                        status: VmStatus::FailedToAllocate,
-                    },
-                    self.vm_api.borrow().get_id(),
-                    self.sim_config.message_delay,
+        let gang = self.vm_api.borrow().get_gang(vm_ids[0]).cloned();
+        let timeout = match gang.as_ref().and_then(|gang| gang.window) {
+            Some(window) => window.min(self.sim_config.vm_allocation_timeout),
+            None => self.sim_config.vm_allocation_timeout,
+        };
+        if self.ctx.time() > start_time + timeout {
+            if gang.is_some() {
+                self.logger.borrow_mut().log_debug(
+                    &self.ctx,
+                    format!("gang with {} vms is not placed within its time window", vm_ids.len()),
                 );
+                self.stats.expired_gangs += 1;
             }
-            return;
+            self.stats.timed_out_vms += vm_ids.len() as u64;
+            self.fail_request(vm_ids);
+            return None;
         }
+        // check tenant quotas
+        let verdict = self.vm_api.borrow_mut().admit(&vm_ids);
+        match verdict {
+            AdmissionVerdict::Admitted => {}
+            AdmissionVerdict::Rejected => {
+                self.logger.borrow_mut().log_debug(
+                    &self.ctx,
+                    format!("rejected request with {} vms due to tenant quota", vm_ids.len()),
+                );
+                self.stats.quota_rejected_vms += vm_ids.len() as u64;
+                self.fail_request(vm_ids);
+                return None;
+            }
+            AdmissionVerdict::Queued => {
+                self.logger.borrow_mut().log_debug(
+                    &self.ctx,
+                    format!("request with {} vms is held due to tenant quota", vm_ids.len()),
+                );
+                self.ctx
+                    .emit_self(AllocationRequest { vm_ids }, self.sim_config.allocation_retry_period);
+                return None;
+            }
+        }
+        self.register_request(vm_ids[0]);
 
         let allocations: Vec<Allocation> = vm_ids
             .iter()
             .map(|vm_id| self.vm_api.borrow().get_vm_allocation(*vm_id))
             .collect();
         // try to find placements using the placement algorithm
-        if let Some(placements) = self.compute_placements(&allocations) {
+        let decision_delay = self.take_scheduling_time();
+        let decision_time = self.ctx.time() + decision_delay;
+        let placements = match gang.as_ref() {
+            Some(gang) => self.compute_gang_placements(&allocations, gang.topology),
+            None => self.compute_placements(&allocations),
+        };
+        if let Some(placements) = placements {
+            self.stats.placed_vms += placements.len() as u64;
+            self.requests.get_mut(&vm_ids[0]).unwrap().decision_time = Some(decision_time);
             for (host, alloc) in placements.iter().zip(allocations.iter()) {
                 self.logger.borrow_mut().log_debug(
                     &self.ctx,
@@ -146,6 +585,7 @@ impl Scheduler {
                     ),
                 );
                 self.pool_state.allocate(alloc, *host);
+                self.account_allocation(alloc.id, *host);
             }
             self.ctx.emit(
                 AllocationCommitRequest {
@@ -153,30 +593,383 @@ impl Scheduler {
                     host_ids: placements,
                 },
                 self.placement_store_id,
-                self.sim_config.message_delay,
+                decision_delay + self.message_delay(),
+            );
+        } else if gang.as_ref().is_none_or(|gang| gang.topology == GangTopology::Any)
+            && self.try_preempt(&vm_ids, &allocations, decision_delay)
+        {
+            self.stats.placed_vms += vm_ids.len() as u64;
+            self.requests.get_mut(&vm_ids[0]).unwrap().decision_time = Some(decision_time);
+        } else {
+            return Some(vm_ids);
+        }
+        None
+    }
+
+    /// Handles failed placement of the request.
+    fn on_placement_failure(&mut self, vm_ids: Vec<u32>) {
+        self.stats.placement_failures += vm_ids.len() as u64;
+        self.logger
+            .borrow_mut()
+            .log_debug(&self.ctx, format!("failed to place {} vms", vm_ids.len()));
+        self.retry_request(vm_ids);
+    }
+
+    /// Registers the state of request upon its first arrival.
+    fn register_request(&mut self, vm_id: u32) {
+        if !self.requests.contains_key(&vm_id) {
+            self.requests.insert(
+                vm_id,
+                RequestState {
+                    attempts: 0,
+                    arrival_seq: self.next_request_seq,
+                    decision_time: None,
+                    dispatched: false,
+                },
+            );
+            self.next_request_seq += 1;
+        }
+    }
+
+    /// Adds the arrived requests to the pending queue and dispatches the pending requests (in fair sharing mode).
+    fn on_fair_allocation_requests(&mut self, requests: Vec<Vec<u32>>) {
+        for vm_ids in requests {
+            self.register_request(vm_ids[0]);
+            self.add_pending_request(vm_ids);
+        }
+        self.dispatch_pending_requests(false);
+    }
+
+    /// Adds the request to the pending queue of its tenant, keeping the queue ordered by the request arrival.
+    fn add_pending_request(&mut self, vm_ids: Vec<u32>) {
+        let tenant = self.vm_api.borrow().get_vm(vm_ids[0]).borrow().tenant.clone();
+        let requests = &self.requests;
+        let arrival_seq = |vm_ids: &Vec<u32>| requests.get(&vm_ids[0]).map_or(0, |r| r.arrival_seq);
+        let seq = arrival_seq(&vm_ids);
+        let queue = self.pending_requests.entry(tenant).or_default();
+        let pos = queue.partition_point(|other| arrival_seq(other) < seq);
+        queue.insert(pos, vm_ids);
+    }
+
+    /// Processes batch of independent single-VM allocation requests submitted as a single event.
+    ///
+    /// In contrast to multi-VM request, the VMs from the batch are placed, retried and failed independently.
+    fn on_batch_allocation_request(&mut self, vm_ids: Vec<u32>) {
+        self.logger.borrow_mut().log_debug(
+            &self.ctx,
+            format!("received batch allocation request with {} vms", vm_ids.len()),
+        );
+        if self.fair_sharing {
+            self.on_fair_allocation_requests(vm_ids.into_iter().map(|vm_id| vec![vm_id]).collect());
+            return;
+        }
+        for vm_id in vm_ids {
+            self.on_allocation_request(vec![vm_id]);
+        }
+    }
+
+    /// Handles failed placement attempt according to the retry policy.
+    fn retry_request(&mut self, vm_ids: Vec<u32>) {
+        let request = self.requests.get_mut(&vm_ids[0]).unwrap();
+        request.attempts += 1;
+        request.decision_time = None;
+        let attempts = request.attempts;
+        if self.retry_policy.max_attempts.is_some_and(|max| attempts >= max) {
+            self.reject_request(vm_ids, format!("after {} attempts", attempts));
+            return;
+        }
+        let delay = self.retry_policy.retry_delay(attempts);
+        if self.fair_sharing {
+            // dispatched again upon the release of resources or after the retry delay
+            self.add_pending_request(vm_ids);
+            if let Some(delay) = delay {
+                if !self.queue_retry_scheduled {
+                    self.queue_retry_scheduled = true;
+                    self.ctx.emit_self(RetryQueuedRequests {}, delay);
+                }
+            }
+            return;
+        }
+        match &self.retry_policy.queue {
+            Some(queue) => {
+                if self.retry_queue.len() >= queue.capacity {
+                    self.reject_request(vm_ids, "as retry queue is full".to_string());
+                    return;
+                }
+                self.retry_queue.push(vm_ids);
+                if let Some(delay) = delay {
+                    if !self.queue_retry_scheduled {
+                        self.queue_retry_scheduled = true;
+                        self.ctx.emit_self(RetryQueuedRequests {}, delay);
+                    }
+                }
+            }
+            None => match delay {
+                Some(delay) => {
+                    self.ctx.emit_self(AllocationRequest { vm_ids }, delay);
+                }
+                // retried upon the release of resources
+                None => self.retry_queue.push(vm_ids),
+            },
+        }
+    }
+
+    /// Retries all queued requests in the order defined by the retry policy.
+    fn retry_queued_requests(&mut self) {
+        let mut queue = mem::take(&mut self.retry_queue);
+        let vm_api = self.vm_api.clone();
+        queue.sort_by_cached_key(|vm_ids| {
+            let allocations: Vec<Allocation> = vm_ids
+                .iter()
+                .map(|vm_id| vm_api.borrow().get_vm_allocation(*vm_id))
+                .collect();
+            let seq = self.requests.get(&vm_ids[0]).map_or(0, |r| r.arrival_seq);
+            (self.retry_policy.ordering_key(&allocations), seq)
+        });
+        for vm_ids in queue {
+            self.on_allocation_request(vm_ids);
+        }
+    }
+
+    /// Dispatches the pending requests by progressive filling, i.e. each time selects the earliest request
+    /// of the tenant with the lowest dominant share. The requests which cannot be placed are returned to the queue.
+    ///
+    /// The failed placement is counted as an attempt only for periodic dispatch (`retry` is true) and for the first
+    /// dispatch of the request after its arrival.
+    fn dispatch_pending_requests(&mut self, retry: bool) {
+        let mut queues = mem::take(&mut self.pending_requests);
+        let capacity = self.pool_capacity();
+        let mut shares: BTreeMap<Option<String>, f64> = queues
+            .keys()
+            .map(|tenant| {
+                let share = tenant
+                    .as_ref()
+                    .map_or(0., |tenant| self.shares.dominant_share(tenant, capacity));
+                (tenant.clone(), share)
+            })
+            .collect();
+        loop {
+            // among the tenants with the lowest share, the one with the earliest request is selected
+            let tenant = queues
+                .iter()
+                .filter_map(|(tenant, queue)| queue.front().map(|vm_ids| (tenant, vm_ids)))
+                .min_by(|(t1, r1), (t2, r2)| {
+                    shares[*t1].total_cmp(&shares[*t2]).then_with(|| {
+                        self.requests[&r1[0]]
+                            .arrival_seq
+                            .cmp(&self.requests[&r2[0]].arrival_seq)
+                    })
+                })
+                .map(|(tenant, _)| tenant.clone());
+            let Some(tenant) = tenant else {
+                break;
+            };
+            let vm_ids = queues.get_mut(&tenant).unwrap().pop_front().unwrap();
+            let request = self.requests.get_mut(&vm_ids[0]).unwrap();
+            let count_attempt = retry || !request.dispatched;
+            request.dispatched = true;
+            match self.try_place_request(vm_ids) {
+                None => {
+                    self.stats.processed_requests += 1;
+                    if let Some(tenant) = tenant.as_ref() {
+                        shares.insert(Some(tenant.clone()), self.shares.dominant_share(tenant, capacity));
+                    }
+                }
+                Some(vm_ids) if count_attempt => {
+                    self.stats.processed_requests += 1;
+                    self.on_placement_failure(vm_ids);
+                }
+                Some(vm_ids) => self.add_pending_request(vm_ids),
+            }
+        }
+    }
+
+    /// Rejects the request after failed placement.
+    fn reject_request(&mut self, vm_ids: Vec<u32>, reason: String) {
+        self.logger.borrow_mut().log_debug(
+            &self.ctx,
+            format!("rejected request with {} vms {}", vm_ids.len(), reason),
+        );
+        self.stats.rejected_vms += vm_ids.len() as u64;
+        self.fail_request(vm_ids);
+    }
+
+    /// Marks VMs from the request as failed to allocate.
+    fn fail_request(&mut self, vm_ids: Vec<u32>) {
+        self.requests.remove(&vm_ids[0]);
+        for vm_id in vm_ids {
+            self.ctx.emit(
+                VmStatusChanged {
+                    vm_id,
+                    status: VmStatus::FailedToAllocate,
+                },
+                self.vm_api.borrow().get_id(),
+                self.message_delay(),
+            );
+        }
+    }
+
+    /// Processes advance reservation request by selecting the host for running VM during the specified interval.
+    ///
+    /// If a suitable host is found, the scheduler stores the reservation locally and tries to commit it in the
+    /// placement store. Otherwise, the reservation is rejected.
+    fn on_reservation_request(&mut self, vm_id: u32, start_time: f64, end_time: f64) {
+        self.stats.processed_requests += 1;
+        let verdict = self.vm_api.borrow_mut().admit(&[vm_id]);
+        if verdict != AdmissionVerdict::Admitted {
+            self.stats.quota_rejected_vms += 1;
+            self.reject_reservation(vm_id);
+            return;
+        }
+        let alloc = self.vm_api.borrow().get_vm_allocation(vm_id);
+        let host = self.pool_state.get_host_ids().into_iter().find(|host_id| {
+            self.is_host_allowed(*host_id)
+                && self
+                    .reservations
+                    .can_reserve(&self.pool_state, &alloc, *host_id, start_time, end_time)
+        });
+        if let Some(host_id) = host {
+            self.logger.borrow_mut().log_debug(
+                &self.ctx,
+                format!(
+                    "decided to reserve host {} for vm {} from {} to {}",
+                    self.ctx.lookup_name(host_id),
+                    vm_id,
+                    start_time,
+                    end_time
+                ),
+            );
+            self.reservations.add(Reservation {
+                vm_id,
+                host_id,
+                start_time,
+                end_time,
+                cpu_usage: alloc.cpu_usage,
+                memory_usage: alloc.memory_usage,
+                started: false,
+            });
+            self.ctx.emit(
+                ReservationCommitRequest {
+                    vm_id,
+                    host_id,
+                    start_time,
+                    end_time,
+                },
+                self.placement_store_id,
+                self.message_delay(),
             );
         } else {
-            self.logger
-                .borrow_mut()
-                .log_debug(&self.ctx, format!("failed to place {} vms", vm_ids.len()));
-            self.ctx
-                .emit_self(AllocationRequest { vm_ids }, self.sim_config.allocation_retry_period);
+            self.reject_reservation(vm_id);
         }
     }
 
+    /// Rejects the reservation and marks its VM as failed to allocate.
+    fn reject_reservation(&mut self, vm_id: u32) {
+        self.logger
+            .borrow_mut()
+            .log_debug(&self.ctx, format!("rejected reservation of vm {}", vm_id));
+        self.stats.rejected_reservations += 1;
+        self.fail_request(vec![vm_id]);
+    }
+
+    /// Adds committed reservation to the local reservation table.
+    fn on_reservation_commit_succeeded(&mut self, reservation: Reservation) {
+        if self.reservations.get(reservation.vm_id).is_some() {
+            self.stats.accepted_reservations += 1;
+        }
+        self.reservations.add(reservation);
+    }
+
+    /// Removes reservation failed during commit from the local reservation table and rejects it.
+    fn on_reservation_commit_failed(&mut self, vm_id: u32) {
+        self.reservations.remove(vm_id);
+        self.reject_reservation(vm_id);
+    }
+
     /// Applies committed allocation to the local resource pool state.
     fn on_allocation_commit_succeeded(&mut self, vm_ids: Vec<u32>, host_ids: Vec<u32>) {
         for (&vm_id, &host_id) in vm_ids.iter().zip(host_ids.iter()) {
             let alloc = self.vm_api.borrow().get_vm_allocation(vm_id);
             self.pool_state.allocate(&alloc, host_id);
+            self.reservations.mark_started(vm_id);
+            self.account_allocation(vm_id, host_id);
+        }
+        // update queueing delay statistics for own requests
+        if let Some(request) = self.requests.remove(&vm_ids[0]) {
+            if self.vm_api.borrow().get_gang(vm_ids[0]).is_some() {
+                self.stats.placed_gangs += 1;
+            }
+            if let Some(decision_time) = request.decision_time {
+                for vm_id in vm_ids.iter() {
+                    let start_time = self.vm_api.borrow().get_vm(*vm_id).borrow().allocation_start_time;
+                    let delay = decision_time - start_time;
+                    self.stats.committed_vms += 1;
+                    self.stats.total_queueing_delay += delay;
+                    self.stats.max_queueing_delay = self.stats.max_queueing_delay.max(delay);
+                }
+            }
         }
     }
 
-    /// Removes allocation failed during commit from the local resource pool state.
+    /// Removes allocation failed during commit from the local resource pool state and retries the request.
     fn on_allocation_commit_failed(&mut self, vm_ids: Vec<u32>, host_ids: Vec<u32>) {
+        self.stats.commit_failures += vm_ids.len() as u64;
         for (&vm_id, &host_id) in vm_ids.iter().zip(host_ids.iter()) {
             let alloc = self.vm_api.borrow().get_vm_allocation(vm_id);
             self.pool_state.release(&alloc, host_id);
+            self.account_release(vm_id, Some(host_id));
+        }
+        if self.requests.contains_key(&vm_ids[0]) {
+            self.retry_request(vm_ids);
+        }
+    }
+
+    /// Applies committed preemption to the local resource pool state.
+    fn on_preemption_committed(&mut self, vm_ids: Vec<u32>, victims: Vec<PreemptionVictim>) {
+        let own_request = self.requests.contains_key(&vm_ids[0]);
+        for victim in victims {
+            let alloc = self.vm_api.borrow().get_vm_allocation(victim.vm_id);
+            self.pool_state.release(&alloc, victim.host_id);
+            self.account_release(victim.vm_id, Some(victim.host_id));
+            if let Some(target_host) = victim.target_host {
+                self.pool_state.allocate(&alloc, target_host);
+                self.account_allocation(victim.vm_id, target_host);
+            }
+            if own_request {
+                if victim.target_host.is_some() {
+                    self.stats.migrated_vms += 1;
+                } else {
+                    self.stats.preempted_vms += 1;
+                }
+            }
+        }
+        if own_request {
+            self.stats.preempting_vms += vm_ids.len() as u64;
+        }
+    }
+
+    /// Reverts the local changes made for the rejected preemption and retries the request.
+    fn on_preemption_commit_failed(&mut self, vm_ids: Vec<u32>, host_ids: Vec<u32>, victims: Vec<PreemptionVictim>) {
+        self.stats.commit_failures += vm_ids.len() as u64;
+        for (&vm_id, &host_id) in vm_ids.iter().zip(host_ids.iter()) {
+            let alloc = self.vm_api.borrow().get_vm_allocation(vm_id);
+            self.pool_state.release(&alloc, host_id);
+        }
+        for victim in victims {
+            let alloc = self.vm_api.borrow().get_vm_allocation(victim.vm_id);
+            if let Some(target_host) = victim.target_host {
+                self.pool_state.release(&alloc, target_host);
+            }
+            // restore victim unless it has already been released
+            let vm_api = self.vm_api.borrow();
+            if vm_api.get_vm_status(victim.vm_id) == VmStatus::Running
+                && vm_api.find_host_by_vm(victim.vm_id) == Some(victim.host_id)
+            {
+                self.pool_state.allocate(&alloc, victim.host_id);
+            }
+        }
+        if self.requests.contains_key(&vm_ids[0]) {
+            self.retry_request(vm_ids);
         }
     }
 
@@ -184,12 +977,28 @@ impl Scheduler {
     fn on_allocation_released(&mut self, vm_id: u32, host_id: u32) {
         let alloc = self.vm_api.borrow().get_vm_allocation(vm_id);
         self.pool_state.release(&alloc, host_id);
+        self.account_release(vm_id, Some(host_id));
+        self.reservations.remove(vm_id);
+        self.on_resources_released();
     }
 
     /// Removes failed allocation from the local resource pool state.
     fn on_allocation_failed(&mut self, vm_id: u32, host_id: u32) {
         let alloc = self.vm_api.borrow().get_vm_allocation(vm_id);
         self.pool_state.release(&alloc, host_id);
+        self.account_release(vm_id, Some(host_id));
+        self.reservations.remove(vm_id);
+        self.on_resources_released();
+    }
+
+    /// Retries the queued requests since the released resources can be used to place them.
+    fn on_resources_released(&mut self) {
+        if !self.pending_requests.is_empty() {
+            self.dispatch_pending_requests(false);
+        }
+        if !self.retry_queue.is_empty() {
+            self.retry_queued_requests();
+        }
     }
 }
 
@@ -197,7 +1006,14 @@ impl EventHandler for Scheduler {
     fn on(&mut self, event: Event) {
         cast!(match event.data {
             AllocationRequest { vm_ids } => {
-                self.on_allocation_request(vm_ids);
+                if self.fair_sharing {
+                    self.on_fair_allocation_requests(vec![vm_ids]);
+                } else {
+                    self.on_allocation_request(vm_ids);
+                }
+            }
+            BatchAllocationRequest { vm_ids } => {
+                self.on_batch_allocation_request(vm_ids);
             }
             AllocationCommitSucceeded { vm_ids, host_ids } => {
                 self.on_allocation_commit_succeeded(vm_ids, host_ids);
@@ -211,6 +1027,51 @@ impl EventHandler for Scheduler {
             AllocationFailed { vm_id, host_id } => {
                 self.on_allocation_failed(vm_id, host_id);
             }
+            ReservationRequest {
+                vm_id,
+                start_time,
+                end_time,
+            } => {
+                self.on_reservation_request(vm_id, start_time, end_time);
+            }
+            ReservationCommitSucceeded {
+                vm_id,
+                host_id,
+                start_time,
+                end_time,
+            } => {
+                let alloc = self.vm_api.borrow().get_vm_allocation(vm_id);
+                self.on_reservation_commit_succeeded(Reservation {
+                    vm_id,
+                    host_id,
+                    start_time,
+                    end_time,
+                    cpu_usage: alloc.cpu_usage,
+                    memory_usage: alloc.memory_usage,
+                    started: false,
+                });
+            }
+            ReservationCommitFailed { vm_id, host_id: _ } => {
+                self.on_reservation_commit_failed(vm_id);
+            }
+            PreemptionCommitted { vm_ids, victims } => {
+                self.on_preemption_committed(vm_ids, victims);
+            }
+            PreemptionCommitFailed {
+                vm_ids,
+                host_ids,
+                victims,
+            } => {
+                self.on_preemption_commit_failed(vm_ids, host_ids, victims);
+            }
+            RetryQueuedRequests {} => {
+                self.queue_retry_scheduled = false;
+                if self.fair_sharing {
+                    self.dispatch_pending_requests(true);
+                } else {
+                    self.retry_queued_requests();
+                }
+            }
         })
     }
 }
@@ -1,7 +1,11 @@
 //! Service-level agreement violation metrics.
 
+use std::collections::BTreeMap;
+
 use dyn_clone::{clone_trait_object, DynClone};
 
+use crate::core::traffic::TrafficFlow;
+
 /// Trait for implementation of host-level SLA violation metric.
 ///
 /// This metric measures the amount of SLA violation caused by the host overload,
@@ -54,3 +58,76 @@ impl HostSLAVMetric for OverloadTimeFraction {
         self.total_overloaded_time / self.total_active_time
     }
 }
+
+/// Trait for implementation of application-level SLA violation metric caused by network congestion.
+///
+/// This metric measures the amount of SLA violation caused by the network, when it is not able to deliver
+/// the traffic requested by communicating VMs.
+pub trait TrafficSLAVMetric: DynClone {
+    /// Called whenever the traffic flows between VMs are changed to update the metric value.
+    fn update(&mut self, time: f64, flows: &[TrafficFlow]);
+
+    /// Returns the current metric value.
+    fn value(&self) -> f64;
+
+    /// Returns the current metric value for the specified VM.
+    fn vm_value(&self, vm_id: u32) -> f64;
+}
+
+clone_trait_object!(TrafficSLAVMetric);
+
+/// Undelivered Traffic Fraction (UTF) metric.
+///
+/// `UTF = V_undelivered / V_requested`
+/// - `V_undelivered` is the total volume of traffic requested by VMs but not delivered due to network congestion.
+/// - `V_requested` is the total volume of traffic requested by VMs placed on different hosts.
+#[derive(Clone, Default)]
+pub struct UndeliveredTrafficFraction {
+    prev_time: f64,
+    prev_flows: Vec<TrafficFlow>,
+    total: (f64, f64),
+    per_vm: BTreeMap<u32, (f64, f64)>,
+}
+
+impl UndeliveredTrafficFraction {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    fn fraction((requested, undelivered): (f64, f64)) -> f64 {
+        if requested > 0. {
+            undelivered / requested
+        } else {
+            0.
+        }
+    }
+}
+
+impl TrafficSLAVMetric for UndeliveredTrafficFraction {
+    fn update(&mut self, time: f64, flows: &[TrafficFlow]) {
+        let time_delta = time - self.prev_time;
+
+        for flow in self.prev_flows.iter() {
+            let requested = flow.demand * time_delta;
+            let undelivered = (flow.demand - flow.delivered) * time_delta;
+            self.total.0 += requested;
+            self.total.1 += undelivered;
+            for vm_id in [flow.vm1, flow.vm2] {
+                let vm_total = self.per_vm.entry(vm_id).or_default();
+                vm_total.0 += requested;
+                vm_total.1 += undelivered;
+            }
+        }
+
+        self.prev_time = time;
+        self.prev_flows = flows.to_vec();
+    }
+
+    fn value(&self) -> f64 {
+        Self::fraction(self.total)
+    }
+
+    fn vm_value(&self, vm_id: u32) -> f64 {
+        Self::fraction(self.per_vm.get(&vm_id).copied().unwrap_or_default())
+    }
+}
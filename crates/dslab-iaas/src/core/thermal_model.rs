@@ -0,0 +1,42 @@
+//! Rack thermal and cooling model.
+
+/// Simple thermal model of a rack cooled by a CRAC (computer room air conditioning) unit.
+///
+/// The inlet temperature of each host in the rack is computed as the temperature of air supplied by the CRAC unit
+/// plus the heat recirculated from the rack, which is proportional to the total IT power of the rack.
+/// The cooling power is computed as the IT power divided by the coefficient of performance (CoP) of the CRAC unit,
+/// which depends on the supply temperature according to the model of HP Labs Utility Data Center
+/// (Moore et al., "Making Scheduling Cool", 2005): CoP(T) = 0.0068 T^2 + 0.0008 T + 0.458.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ThermalModel {
+    /// Temperature of air supplied by the CRAC unit in °C.
+    pub supply_temperature: f64,
+    /// Increase of host inlet temperature in °C per unit of total rack IT power.
+    pub recirculation_coefficient: f64,
+}
+
+impl ThermalModel {
+    /// Creates thermal model with specified supply temperature and heat recirculation coefficient.
+    pub fn new(supply_temperature: f64, recirculation_coefficient: f64) -> Self {
+        Self {
+            supply_temperature,
+            recirculation_coefficient,
+        }
+    }
+
+    /// Returns the coefficient of performance of the CRAC unit.
+    pub fn cop(&self) -> f64 {
+        let t = self.supply_temperature;
+        0.0068 * t * t + 0.0008 * t + 0.458
+    }
+
+    /// Returns the power consumed to cool the equipment consuming the specified IT power.
+    pub fn cooling_power(&self, it_power: f64) -> f64 {
+        it_power / self.cop()
+    }
+
+    /// Returns the host inlet temperature for the specified total IT power of the rack.
+    pub fn inlet_temperature(&self, rack_power: f64) -> f64 {
+        self.supply_temperature + self.recirculation_coefficient * rack_power
+    }
+}
@@ -0,0 +1,461 @@
+//! Recording of VM placement intervals, migrations and host power states for visualization.
+//!
+//! The timeline is recorded by host managers when it is enabled via
+//! [`CloudSimulation::enable_timeline`](crate::simulation::CloudSimulation::enable_timeline). It can be saved
+//! as JSON for external tools or rendered as a self-contained HTML page with SVG Gantt chart.
+//!
+//! The JSON document has the following structure (all times are simulation times in seconds):
+//!
+//! ```json
+//! {
+//!   "end_time": 100.0,
+//!   "hosts": [{"name": "h1", "cpu_total": 16, "memory_total": 32}],
+//!   "vm_intervals": [
+//!     {"vm_id": 0, "host": "h1", "allocated_at": 0.2, "started_at": 1.2, "released_at": 50.7, "end": "Finished"}
+//!   ],
+//!   "migrations": [{"vm_id": 0, "source_host": "h1", "target_host": "h2", "start": 20.0, "end": 31.0}],
+//!   "power_states": [{"host": "h1", "state": "Active", "start": 0.0, "end": null}]
+//! }
+//! ```
+//!
+//! Each VM interval corresponds to the stay of VM on a single host, from the resource allocation to the resource
+//! release. The VM is starting (or migrating to the host) from `allocated_at` to `started_at` and running afterwards.
+//! The `end` field describes why the VM left the host: `Finished`, `Migrated` or `Preempted`. The end times of
+//! intervals, migrations and power states are `null` if they are not over by the time of saving.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+use serde::Serialize;
+
+use crate::core::power_state::HostPowerState;
+
+/// Reason for VM leaving the host.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub enum VmIntervalEnd {
+    /// VM lifetime is over or VM is deleted.
+    Finished,
+    /// VM is migrated to another host.
+    Migrated,
+    /// VM is preempted by higher-priority VM.
+    Preempted,
+}
+
+/// Stay of VM on a single host.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct VmInterval {
+    pub vm_id: u32,
+    pub host: String,
+    /// Time of resource allocation on the host.
+    pub allocated_at: f64,
+    /// Time of VM start on the host (after startup or migration).
+    pub started_at: Option<f64>,
+    /// Time of resource release on the host.
+    pub released_at: Option<f64>,
+    pub end: Option<VmIntervalEnd>,
+}
+
+/// Live migration of VM between hosts.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct MigrationRecord {
+    pub vm_id: u32,
+    pub source_host: String,
+    pub target_host: String,
+    pub start: f64,
+    /// Time of VM start on the target host.
+    pub end: Option<f64>,
+}
+
+/// Period of time spent by host in some power state.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct PowerStateInterval {
+    pub host: String,
+    pub state: HostPowerState,
+    pub start: f64,
+    pub end: Option<f64>,
+}
+
+/// Host description included in the timeline.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct TimelineHost {
+    pub name: String,
+    pub cpu_total: u32,
+    pub memory_total: u64,
+}
+
+#[derive(Serialize)]
+struct TimelineData<'a> {
+    end_time: f64,
+    hosts: &'a [TimelineHost],
+    vm_intervals: &'a [VmInterval],
+    migrations: &'a [MigrationRecord],
+    power_states: &'a [PowerStateInterval],
+}
+
+/// Recorded timeline of VM placement intervals, migrations and host power states.
+#[derive(Default)]
+pub struct Timeline {
+    hosts: Vec<TimelineHost>,
+    vm_intervals: Vec<VmInterval>,
+    migrations: Vec<MigrationRecord>,
+    power_states: Vec<PowerStateInterval>,
+    open_vm_intervals: HashMap<(u32, String), usize>,
+    open_migrations: HashMap<(u32, String), usize>,
+    migration_sources: HashSet<(u32, String)>,
+    open_power_states: HashMap<String, usize>,
+}
+
+impl Timeline {
+    /// Creates empty timeline.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the recorded hosts.
+    pub fn hosts(&self) -> &[TimelineHost] {
+        &self.hosts
+    }
+
+    /// Returns the recorded VM intervals in the order of resource allocation.
+    pub fn vm_intervals(&self) -> &[VmInterval] {
+        &self.vm_intervals
+    }
+
+    /// Returns the recorded migrations in the order of their start.
+    pub fn migrations(&self) -> &[MigrationRecord] {
+        &self.migrations
+    }
+
+    /// Returns the recorded host power state intervals in the order of their start.
+    pub fn power_states(&self) -> &[PowerStateInterval] {
+        &self.power_states
+    }
+
+    pub(crate) fn add_host(&mut self, name: &str, cpu_total: u32, memory_total: u64, state: HostPowerState, time: f64) {
+        self.hosts.push(TimelineHost {
+            name: name.to_string(),
+            cpu_total,
+            memory_total,
+        });
+        self.on_power_state_changed(name, state, time);
+    }
+
+    pub(crate) fn on_vm_allocated(&mut self, vm_id: u32, host: &str, time: f64) {
+        self.open_vm_intervals
+            .insert((vm_id, host.to_string()), self.vm_intervals.len());
+        self.vm_intervals.push(VmInterval {
+            vm_id,
+            host: host.to_string(),
+            allocated_at: time,
+            started_at: None,
+            released_at: None,
+            end: None,
+        });
+    }
+
+    pub(crate) fn on_vm_started(&mut self, vm_id: u32, host: &str, time: f64) {
+        let key = (vm_id, host.to_string());
+        if let Some(idx) = self.open_vm_intervals.get(&key) {
+            self.vm_intervals[*idx].started_at.get_or_insert(time);
+        }
+        if let Some(idx) = self.open_migrations.remove(&key) {
+            self.migrations[idx].end = Some(time);
+        }
+    }
+
+    pub(crate) fn on_vm_released(&mut self, vm_id: u32, host: &str, time: f64, mut end: VmIntervalEnd) {
+        let key = (vm_id, host.to_string());
+        if self.migration_sources.remove(&key) && end == VmIntervalEnd::Finished {
+            end = VmIntervalEnd::Migrated;
+        }
+        if let Some(idx) = self.open_vm_intervals.remove(&key) {
+            self.vm_intervals[idx].released_at = Some(time);
+            self.vm_intervals[idx].end = Some(end);
+        }
+        // migration to this host is interrupted
+        if let Some(idx) = self.open_migrations.remove(&key) {
+            self.migrations[idx].end = Some(time);
+        }
+    }
+
+    pub(crate) fn on_migration_started(&mut self, vm_id: u32, source_host: &str, target_host: &str, time: f64) {
+        self.open_migrations
+            .insert((vm_id, target_host.to_string()), self.migrations.len());
+        self.migration_sources.insert((vm_id, source_host.to_string()));
+        self.migrations.push(MigrationRecord {
+            vm_id,
+            source_host: source_host.to_string(),
+            target_host: target_host.to_string(),
+            start: time,
+            end: None,
+        });
+    }
+
+    pub(crate) fn on_power_state_changed(&mut self, host: &str, state: HostPowerState, time: f64) {
+        if let Some(idx) = self.open_power_states.get(host) {
+            if self.power_states[*idx].state == state {
+                return;
+            }
+            self.power_states[*idx].end = Some(time);
+        }
+        self.open_power_states.insert(host.to_string(), self.power_states.len());
+        self.power_states.push(PowerStateInterval {
+            host: host.to_string(),
+            state,
+            start: time,
+            end: None,
+        });
+    }
+
+    /// Returns the timeline as JSON document with the specified end time (see the module docs for the format).
+    pub fn to_json(&self, end_time: f64) -> String {
+        serde_json::to_string_pretty(&self.data(end_time)).unwrap()
+    }
+
+    /// Saves the timeline to JSON file.
+    pub fn save_json(&self, path: &str, end_time: f64) -> Result<(), std::io::Error> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer_pretty(&mut writer, &self.data(end_time))?;
+        writer.flush()
+    }
+
+    /// Saves the timeline to HTML file with Gantt chart.
+    pub fn save_html(&self, path: &str, end_time: f64) -> Result<(), std::io::Error> {
+        std::fs::write(path, self.to_html(end_time))
+    }
+
+    /// Renders the timeline as HTML page with SVG Gantt chart.
+    ///
+    /// Each host is shown as a row with VM intervals placed in non-overlapping lanes and a strip with host power
+    /// states below them. The starting phase of VM is shown with a lighter color, migrations are shown as dashed
+    /// lines between the source and target hosts. The details are shown in tooltips.
+    pub fn to_html(&self, end_time: f64) -> String {
+        const LABEL_WIDTH: f64 = 120.;
+        const PLOT_WIDTH: f64 = 1000.;
+        const LANE_HEIGHT: f64 = 14.;
+        const POWER_HEIGHT: f64 = 6.;
+        const ROW_PADDING: f64 = 8.;
+        const AXIS_HEIGHT: f64 = 30.;
+
+        let end_time = if end_time > 0. { end_time } else { 1. };
+        let x = |time: f64| LABEL_WIDTH + time.clamp(0., end_time) / end_time * PLOT_WIDTH;
+
+        // assign VM intervals to lanes within each host row
+        let mut lanes: Vec<usize> = vec![0; self.vm_intervals.len()];
+        let mut lane_counts: BTreeMap<&str, usize> = BTreeMap::new();
+        let mut lane_ends: HashMap<&str, Vec<f64>> = HashMap::new();
+        for (i, interval) in self.vm_intervals.iter().enumerate() {
+            let ends = lane_ends.entry(&interval.host).or_default();
+            let end = interval.released_at.unwrap_or(end_time);
+            let lane = match ends.iter().position(|e| *e <= interval.allocated_at) {
+                Some(lane) => lane,
+                None => {
+                    ends.push(0.);
+                    ends.len() - 1
+                }
+            };
+            ends[lane] = end;
+            lanes[i] = lane;
+            lane_counts.insert(&interval.host, ends.len());
+        }
+
+        let mut row_tops: HashMap<&str, f64> = HashMap::new();
+        let mut row_heights: HashMap<&str, f64> = HashMap::new();
+        let mut top = ROW_PADDING;
+        for host in self.hosts.iter() {
+            let lanes = *lane_counts.get(host.name.as_str()).unwrap_or(&1).max(&1) as f64;
+            let height = lanes * LANE_HEIGHT + POWER_HEIGHT + 2. * ROW_PADDING;
+            row_tops.insert(&host.name, top);
+            row_heights.insert(&host.name, height);
+            top += height;
+        }
+        let height = top + AXIS_HEIGHT;
+        let width = LABEL_WIDTH + PLOT_WIDTH + 20.;
+
+        let mut svg = String::new();
+        writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" font-family="sans-serif" font-size="11">"#,
+            width, height
+        )
+        .unwrap();
+        for (i, host) in self.hosts.iter().enumerate() {
+            let top = row_tops[host.name.as_str()];
+            let fill = if i % 2 == 0 { "#f7f7f7" } else { "#ffffff" };
+            writeln!(
+                svg,
+                r#"<rect x="0" y="{:.1}" width="{}" height="{:.1}" fill="{}"/><text x="4" y="{:.1}">{}</text>"#,
+                top,
+                width,
+                row_heights[host.name.as_str()],
+                fill,
+                top + ROW_PADDING + LANE_HEIGHT - 3.,
+                escape(&host.name)
+            )
+            .unwrap();
+        }
+        for state in self.power_states.iter() {
+            if let Some(top) = row_tops.get(state.host.as_str()) {
+                let y = top + row_heights[state.host.as_str()] - ROW_PADDING - POWER_HEIGHT;
+                let (x1, x2) = (x(state.start), x(state.end.unwrap_or(end_time)));
+                writeln!(
+                    svg,
+                    r#"<rect x="{:.1}" y="{:.1}" width="{:.1}" height="{}" fill="{}"><title>{}: {:?} {:.3}-{:.3}</title></rect>"#,
+                    x1,
+                    y,
+                    x2 - x1,
+                    POWER_HEIGHT,
+                    power_state_color(state.state),
+                    escape(&state.host),
+                    state.state,
+                    state.start,
+                    state.end.unwrap_or(end_time)
+                )
+                .unwrap();
+            }
+        }
+        for (interval, lane) in self.vm_intervals.iter().zip(lanes) {
+            if let Some(top) = row_tops.get(interval.host.as_str()) {
+                let y = top + ROW_PADDING + lane as f64 * LANE_HEIGHT;
+                let release = interval.released_at.unwrap_or(end_time);
+                let start = interval.started_at.unwrap_or(release).min(release);
+                let hue = (interval.vm_id as u64 * 47) % 360;
+                let title = format!(
+                    "VM {} on {}: allocated {:.3}, started {}, released {} ({})",
+                    interval.vm_id,
+                    escape(&interval.host),
+                    interval.allocated_at,
+                    interval.started_at.map_or("-".to_string(), |t| format!("{:.3}", t)),
+                    interval.released_at.map_or("-".to_string(), |t| format!("{:.3}", t)),
+                    interval.end.map_or("running".to_string(), |end| format!("{:?}", end))
+                );
+                for (from, to, lightness) in [(interval.allocated_at, start, 80), (start, release, 55)] {
+                    if to > from {
+                        writeln!(
+                            svg,
+                            r#"<rect x="{:.1}" y="{:.1}" width="{:.1}" height="{}" fill="hsl({},60%,{}%)" stroke="white" stroke-width="0.5"><title>{}</title></rect>"#,
+                            x(from),
+                            y + 1.,
+                            x(to) - x(from),
+                            LANE_HEIGHT - 2.,
+                            hue,
+                            lightness,
+                            title
+                        )
+                        .unwrap();
+                    }
+                }
+            }
+        }
+        for migration in self.migrations.iter() {
+            if let (Some(src_top), Some(dst_top)) = (
+                row_tops.get(migration.source_host.as_str()),
+                row_tops.get(migration.target_host.as_str()),
+            ) {
+                let y1 = src_top + row_heights[migration.source_host.as_str()] / 2.;
+                let y2 = dst_top + row_heights[migration.target_host.as_str()] / 2.;
+                writeln!(
+                    svg,
+                    r##"<line x1="{:.1}" y1="{:.1}" x2="{:.1}" y2="{:.1}" stroke="#d62728" stroke-width="1.5" stroke-dasharray="4 2"><title>VM {} migration {} -&gt; {}: {:.3}-{}</title></line>"##,
+                    x(migration.start),
+                    y1,
+                    x(migration.end.unwrap_or(end_time)),
+                    y2,
+                    migration.vm_id,
+                    escape(&migration.source_host),
+                    escape(&migration.target_host),
+                    migration.start,
+                    migration.end.map_or("-".to_string(), |t| format!("{:.3}", t))
+                )
+                .unwrap();
+            }
+        }
+        let axis_y = top + 4.;
+        writeln!(
+            svg,
+            r##"<line x1="{}" y1="{:.1}" x2="{}" y2="{:.1}" stroke="#333"/>"##,
+            LABEL_WIDTH,
+            axis_y,
+            LABEL_WIDTH + PLOT_WIDTH,
+            axis_y
+        )
+        .unwrap();
+        for i in 0..=10 {
+            let time = end_time * i as f64 / 10.;
+            writeln!(
+                svg,
+                r##"<line x1="{0:.1}" y1="{1:.1}" x2="{0:.1}" y2="{2:.1}" stroke="#333"/><text x="{0:.1}" y="{3:.1}" text-anchor="middle">{4}</text>"##,
+                x(time),
+                axis_y,
+                axis_y + 4.,
+                axis_y + 16.,
+                format_tick(time)
+            )
+            .unwrap();
+        }
+        svg.push_str("</svg>\n");
+
+        let mut legend = String::new();
+        for state in [
+            HostPowerState::Active,
+            HostPowerState::Booting,
+            HostPowerState::ShuttingDown,
+            HostPowerState::Sleep,
+            HostPowerState::Off,
+        ] {
+            write!(
+                legend,
+                r#"<span style="margin-right:12px"><span style="display:inline-block;width:12px;height:8px;background:{}"></span> {:?}</span>"#,
+                power_state_color(state),
+                state
+            )
+            .unwrap();
+        }
+
+        format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Simulation timeline</title>\n</head>\n\
+             <body style=\"font-family:sans-serif\">\n<h3>Simulation timeline (0-{})</h3>\n\
+             <p>Host power states: {}</p>\n{}</body>\n</html>\n",
+            format_tick(end_time),
+            legend,
+            svg
+        )
+    }
+
+    fn data(&self, end_time: f64) -> TimelineData<'_> {
+        TimelineData {
+            end_time,
+            hosts: &self.hosts,
+            vm_intervals: &self.vm_intervals,
+            migrations: &self.migrations,
+            power_states: &self.power_states,
+        }
+    }
+}
+
+fn power_state_color(state: HostPowerState) -> &'static str {
+    match state {
+        HostPowerState::Active => "#2ca02c",
+        HostPowerState::Booting => "#ffbf00",
+        HostPowerState::ShuttingDown => "#ff7f0e",
+        HostPowerState::Sleep => "#1f77b4",
+        HostPowerState::Off => "#7f7f7f",
+    }
+}
+
+fn format_tick(time: f64) -> String {
+    if time.fract() == 0. {
+        format!("{}", time)
+    } else {
+        format!("{:.2}", time)
+    }
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
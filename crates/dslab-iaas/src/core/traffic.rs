@@ -0,0 +1,159 @@
+//! Traffic between VMs.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::Serialize;
+
+use dslab_network::{LinkId, Network};
+
+/// Traffic between two VMs placed on different hosts, routed over the network.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct TrafficFlow {
+    pub vm1: u32,
+    pub vm2: u32,
+    pub host1: u32,
+    pub host2: u32,
+    /// Traffic rate requested by VMs.
+    pub demand: f64,
+    /// Traffic rate actually delivered by the network, which is less than demand if the network is congested.
+    pub delivered: f64,
+    /// Network links used by the flow (empty if the network model is not topology-aware).
+    pub links: Vec<LinkId>,
+}
+
+/// Result of routing the traffic between VMs over the network.
+#[derive(Clone, Debug, Default)]
+pub struct TrafficRouting {
+    pub flows: Vec<TrafficFlow>,
+    /// Total traffic rate requested by the flows passing through each link.
+    pub link_loads: BTreeMap<LinkId, f64>,
+    /// Ratio of link load to link bandwidth, values above 1 mean that the link is congested.
+    pub link_utilization: BTreeMap<LinkId, f64>,
+}
+
+/// Stores the rates of (symmetric) network traffic between pairs of VMs.
+#[derive(Clone, Debug, Default)]
+pub struct TrafficMatrix {
+    rates: BTreeMap<(u32, u32), f64>,
+    peers: BTreeMap<u32, BTreeSet<u32>>,
+}
+
+impl TrafficMatrix {
+    /// Creates empty traffic matrix.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    fn key(vm1: u32, vm2: u32) -> (u32, u32) {
+        (vm1.min(vm2), vm1.max(vm2))
+    }
+
+    /// Sets the traffic rate between two VMs. Zero rate removes the traffic.
+    pub fn set(&mut self, vm1: u32, vm2: u32, rate: f64) {
+        assert!(vm1 != vm2, "Traffic should be set between different VMs");
+        let key = Self::key(vm1, vm2);
+        if rate > 0. {
+            self.rates.insert(key, rate);
+            self.peers.entry(vm1).or_default().insert(vm2);
+            self.peers.entry(vm2).or_default().insert(vm1);
+        } else {
+            self.rates.remove(&key);
+            self.peers.entry(vm1).or_default().remove(&vm2);
+            self.peers.entry(vm2).or_default().remove(&vm1);
+        }
+    }
+
+    /// Returns the traffic rate between two VMs.
+    pub fn get(&self, vm1: u32, vm2: u32) -> f64 {
+        self.rates.get(&Self::key(vm1, vm2)).copied().unwrap_or(0.)
+    }
+
+    /// Returns the VMs communicating with the specified VM along with the corresponding traffic rates.
+    pub fn peers(&self, vm_id: u32) -> Vec<(u32, f64)> {
+        self.peers
+            .get(&vm_id)
+            .map(|peers| peers.iter().map(|peer| (*peer, self.get(vm_id, *peer))).collect())
+            .unwrap_or_default()
+    }
+
+    /// Returns the total traffic rate of the specified VM.
+    pub fn total_rate(&self, vm_id: u32) -> f64 {
+        self.peers(vm_id).iter().map(|(_, rate)| rate).sum()
+    }
+
+    /// Removes all traffic of the specified VM.
+    pub fn remove_vm(&mut self, vm_id: u32) {
+        if let Some(peers) = self.peers.remove(&vm_id) {
+            for peer in peers {
+                self.rates.remove(&Self::key(vm_id, peer));
+                if let Some(peer_peers) = self.peers.get_mut(&peer) {
+                    peer_peers.remove(&vm_id);
+                }
+            }
+        }
+    }
+
+    /// Returns an iterator over all VM pairs with non-zero traffic and their traffic rates.
+    pub fn iter(&self) -> impl Iterator<Item = (u32, u32, f64)> + '_ {
+        self.rates.iter().map(|((vm1, vm2), rate)| (*vm1, *vm2, *rate))
+    }
+
+    /// Returns true if there is no traffic.
+    pub fn is_empty(&self) -> bool {
+        self.rates.is_empty()
+    }
+
+    /// Routes the traffic between VMs placed on different hosts over the network.
+    ///
+    /// The traffic between two VMs is routed as a single flow along the path between their hosts.
+    /// If the total demand of flows passing through a link exceeds its bandwidth, the link bandwidth is shared
+    /// proportionally to the demands, and each flow is limited by its most congested link. If the network model
+    /// is not topology-aware, each flow is limited only by the network bandwidth between its hosts.
+    /// The traffic between VMs on the same host, as well as VMs on hosts without network location, is ignored.
+    pub fn route(&self, vm_hosts: &BTreeMap<u32, u32>, network: &Network) -> TrafficRouting {
+        let mut routing = TrafficRouting::default();
+        for (vm1, vm2, rate) in self.iter() {
+            let (Some(host1), Some(host2)) = (vm_hosts.get(&vm1).copied(), vm_hosts.get(&vm2).copied()) else {
+                continue;
+            };
+            if host1 == host2 || network.get_location_opt(host1).is_none() || network.get_location_opt(host2).is_none()
+            {
+                continue;
+            }
+            let (links, delivered) = match network.path(host1, host2) {
+                Some(links) => {
+                    for link_id in links.iter() {
+                        *routing.link_loads.entry(*link_id).or_default() += rate;
+                    }
+                    (links, rate)
+                }
+                None => (Vec::new(), rate.min(network.bandwidth(host1, host2))),
+            };
+            routing.flows.push(TrafficFlow {
+                vm1,
+                vm2,
+                host1,
+                host2,
+                demand: rate,
+                delivered,
+                links,
+            });
+        }
+
+        for (link_id, load) in routing.link_loads.iter() {
+            let bandwidth = network.link(*link_id).unwrap().bandwidth;
+            routing.link_utilization.insert(*link_id, load / bandwidth);
+        }
+        for flow in routing.flows.iter_mut() {
+            let max_utilization = flow
+                .links
+                .iter()
+                .map(|link_id| routing.link_utilization[link_id])
+                .fold(0., f64::max);
+            if max_utilization > 1. {
+                flow.delivered = flow.demand / max_utilization;
+            }
+        }
+        routing
+    }
+}
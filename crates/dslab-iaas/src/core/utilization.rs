@@ -0,0 +1,280 @@
+//! Aggregation of resource utilization and allocation per host, host type, tenant and datacenter.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::Serialize;
+
+/// Amounts of used, allocated and total resources of a host or a group of hosts.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize)]
+pub struct ResourceUsage {
+    pub cpu_used: f64,
+    pub cpu_allocated: f64,
+    pub cpu_total: f64,
+    pub memory_used: f64,
+    pub memory_allocated: f64,
+    pub memory_total: f64,
+}
+
+impl ResourceUsage {
+    fn add(&mut self, other: &ResourceUsage) {
+        self.cpu_used += other.cpu_used;
+        self.cpu_allocated += other.cpu_allocated;
+        self.cpu_total += other.cpu_total;
+        self.memory_used += other.memory_used;
+        self.memory_allocated += other.memory_allocated;
+        self.memory_total += other.memory_total;
+    }
+
+    fn sub(&mut self, other: &ResourceUsage) {
+        self.cpu_used -= other.cpu_used;
+        self.cpu_allocated -= other.cpu_allocated;
+        self.cpu_total -= other.cpu_total;
+        self.memory_used -= other.memory_used;
+        self.memory_allocated -= other.memory_allocated;
+        self.memory_total -= other.memory_total;
+    }
+
+    /// Returns (CPU utilization, memory utilization, CPU allocation, memory allocation) as fractions of the specified
+    /// total amounts of resources.
+    fn ratios(&self, cpu_total: f64, memory_total: f64) -> [f64; 4] {
+        let ratio = |value: f64, total: f64| if total > 0. { value / total } else { 0. };
+        [
+            ratio(self.cpu_used, cpu_total),
+            ratio(self.memory_used, memory_total),
+            ratio(self.cpu_allocated, cpu_total),
+            ratio(self.memory_allocated, memory_total),
+        ]
+    }
+}
+
+/// Time-weighted mean and peak of a piecewise constant value.
+#[derive(Clone, Copy, Debug, Default)]
+struct TimeWeightedStat {
+    integral: f64,
+    peak: f64,
+    prev_time: f64,
+    prev_value: f64,
+}
+
+impl TimeWeightedStat {
+    fn update(&mut self, time: f64, value: f64) {
+        self.integral += self.prev_value * (time - self.prev_time);
+        self.peak = self.peak.max(value);
+        self.prev_time = time;
+        self.prev_value = value;
+    }
+
+    fn mean(&self, time: f64) -> f64 {
+        if time > 0. {
+            (self.integral + self.prev_value * (time - self.prev_time)) / time
+        } else {
+            self.prev_value
+        }
+    }
+}
+
+/// Mean and peak values of resource utilization and allocation over the simulation time.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct UtilizationSummary {
+    pub mean_cpu_utilization: f64,
+    pub peak_cpu_utilization: f64,
+    pub mean_memory_utilization: f64,
+    pub peak_memory_utilization: f64,
+    pub mean_cpu_allocation: f64,
+    pub peak_cpu_allocation: f64,
+    pub mean_memory_allocation: f64,
+    pub peak_memory_allocation: f64,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+struct UtilizationStats {
+    stats: [TimeWeightedStat; 4],
+}
+
+impl UtilizationStats {
+    fn update(&mut self, time: f64, ratios: [f64; 4]) {
+        for (stat, value) in self.stats.iter_mut().zip(ratios) {
+            stat.update(time, value);
+        }
+    }
+
+    fn summary(&self, time: f64) -> UtilizationSummary {
+        let [cpu_utilization, memory_utilization, cpu_allocation, memory_allocation] = self.stats;
+        UtilizationSummary {
+            mean_cpu_utilization: cpu_utilization.mean(time),
+            peak_cpu_utilization: cpu_utilization.peak,
+            mean_memory_utilization: memory_utilization.mean(time),
+            peak_memory_utilization: memory_utilization.peak,
+            mean_cpu_allocation: cpu_allocation.mean(time),
+            peak_cpu_allocation: cpu_allocation.peak,
+            mean_memory_allocation: memory_allocation.mean(time),
+            peak_memory_allocation: memory_allocation.peak,
+        }
+    }
+}
+
+/// Summary of resource utilization and allocation over the simulation time.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct UtilizationReport {
+    /// Summary for all hosts in the datacenter.
+    pub datacenter: UtilizationSummary,
+    /// Summaries for hosts of each type.
+    pub host_types: BTreeMap<String, UtilizationSummary>,
+    /// Summaries for each tenant, computed as fractions of the datacenter resources used (allocated) by tenant VMs.
+    pub tenants: BTreeMap<String, UtilizationSummary>,
+    /// Summaries for each host.
+    pub hosts: BTreeMap<u32, UtilizationSummary>,
+    /// Gini coefficient of mean host CPU utilizations, from 0 (equal utilization) to 1 (maximum imbalance).
+    pub cpu_utilization_gini: f64,
+    /// Gini coefficient of mean host memory utilizations, from 0 (equal utilization) to 1 (maximum imbalance).
+    pub memory_utilization_gini: f64,
+}
+
+/// Aggregates the resource usage reported by hosts per host, host type, tenant and datacenter,
+/// and tracks the corresponding utilization statistics over time.
+#[derive(Clone, Debug, Default)]
+pub struct UtilizationTracker {
+    host_types: BTreeMap<u32, Option<String>>,
+    host_usage: BTreeMap<u32, ResourceUsage>,
+    host_tenant_usage: BTreeMap<u32, BTreeMap<String, ResourceUsage>>,
+    host_type_usage: BTreeMap<String, ResourceUsage>,
+    tenant_usage: BTreeMap<String, ResourceUsage>,
+    datacenter_usage: ResourceUsage,
+    host_stats: BTreeMap<u32, UtilizationStats>,
+    host_type_stats: BTreeMap<String, UtilizationStats>,
+    tenant_stats: BTreeMap<String, UtilizationStats>,
+    datacenter_stats: UtilizationStats,
+}
+
+impl UtilizationTracker {
+    /// Creates empty tracker.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Adds host with specified type and capacity.
+    pub fn add_host(&mut self, host_id: u32, host_type: Option<String>, cpu_total: u32, memory_total: u64) {
+        let usage = ResourceUsage {
+            cpu_total: cpu_total as f64,
+            memory_total: memory_total as f64,
+            ..Default::default()
+        };
+        self.datacenter_usage.add(&usage);
+        if let Some(host_type) = host_type.as_ref() {
+            self.host_type_usage.entry(host_type.clone()).or_default().add(&usage);
+            self.host_type_stats.entry(host_type.clone()).or_default();
+        }
+        self.host_types.insert(host_id, host_type);
+        self.host_usage.insert(host_id, usage);
+        self.host_stats.insert(host_id, UtilizationStats::default());
+    }
+
+    /// Updates the resource usage of the specified host along with the usage of tenants whose VMs run on this host.
+    pub fn update_host(
+        &mut self,
+        time: f64,
+        host_id: u32,
+        usage: ResourceUsage,
+        tenant_usage: BTreeMap<String, ResourceUsage>,
+    ) {
+        let Some(prev_usage) = self.host_usage.insert(host_id, usage) else {
+            return;
+        };
+        self.datacenter_usage.sub(&prev_usage);
+        self.datacenter_usage.add(&usage);
+        let (cpu_total, memory_total) = (self.datacenter_usage.cpu_total, self.datacenter_usage.memory_total);
+        self.datacenter_stats
+            .update(time, self.datacenter_usage.ratios(cpu_total, memory_total));
+        self.host_stats
+            .get_mut(&host_id)
+            .unwrap()
+            .update(time, usage.ratios(usage.cpu_total, usage.memory_total));
+
+        if let Some(host_type) = self.host_types[&host_id].as_ref() {
+            let group_usage = self.host_type_usage.get_mut(host_type).unwrap();
+            group_usage.sub(&prev_usage);
+            group_usage.add(&usage);
+            let ratios = group_usage.ratios(group_usage.cpu_total, group_usage.memory_total);
+            self.host_type_stats.get_mut(host_type).unwrap().update(time, ratios);
+        }
+
+        let prev_tenant_usage = self.host_tenant_usage.remove(&host_id).unwrap_or_default();
+        let tenants: BTreeSet<&String> = prev_tenant_usage.keys().chain(tenant_usage.keys()).collect();
+        for tenant in tenants {
+            let total = self.tenant_usage.entry(tenant.clone()).or_default();
+            if let Some(prev) = prev_tenant_usage.get(tenant) {
+                total.sub(prev);
+            }
+            if let Some(new) = tenant_usage.get(tenant) {
+                total.add(new);
+            }
+            let ratios = total.ratios(cpu_total, memory_total);
+            self.tenant_stats
+                .entry(tenant.clone())
+                .or_default()
+                .update(time, ratios);
+        }
+        self.host_tenant_usage.insert(host_id, tenant_usage);
+    }
+
+    /// Returns the current resource usage of all hosts.
+    pub fn datacenter_usage(&self) -> &ResourceUsage {
+        &self.datacenter_usage
+    }
+
+    /// Returns the current resource usage of hosts with specified type.
+    pub fn host_type_usage(&self, host_type: &str) -> Option<&ResourceUsage> {
+        self.host_type_usage.get(host_type)
+    }
+
+    /// Returns the current resource usage of VMs of the specified tenant.
+    pub fn tenant_usage(&self, tenant: &str) -> Option<&ResourceUsage> {
+        self.tenant_usage.get(tenant)
+    }
+
+    /// Returns the summary of resource utilization and allocation from the simulation start to the specified time.
+    pub fn report(&self, time: f64) -> UtilizationReport {
+        let hosts: BTreeMap<u32, UtilizationSummary> = self
+            .host_stats
+            .iter()
+            .map(|(host_id, stats)| (*host_id, stats.summary(time)))
+            .collect();
+        let cpu_utilizations: Vec<f64> = hosts.values().map(|s| s.mean_cpu_utilization).collect();
+        let memory_utilizations: Vec<f64> = hosts.values().map(|s| s.mean_memory_utilization).collect();
+        UtilizationReport {
+            datacenter: self.datacenter_stats.summary(time),
+            host_types: self
+                .host_type_stats
+                .iter()
+                .map(|(host_type, stats)| (host_type.clone(), stats.summary(time)))
+                .collect(),
+            tenants: self
+                .tenant_stats
+                .iter()
+                .map(|(tenant, stats)| (tenant.clone(), stats.summary(time)))
+                .collect(),
+            hosts,
+            cpu_utilization_gini: gini_coefficient(&cpu_utilizations),
+            memory_utilization_gini: gini_coefficient(&memory_utilizations),
+        }
+    }
+}
+
+/// Computes the Gini coefficient of the specified non-negative values.
+///
+/// Returns 0 if all values are equal (or zero) and approaches 1 when a single value dominates the others.
+pub fn gini_coefficient(values: &[f64]) -> f64 {
+    let n = values.len() as f64;
+    let sum: f64 = values.iter().sum();
+    if values.is_empty() || sum <= 0. {
+        return 0.;
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let weighted_sum: f64 = sorted
+        .iter()
+        .enumerate()
+        .map(|(i, value)| (2. * (i as f64 + 1.) - n - 1.) * value)
+        .sum();
+    weighted_sum / (n * sum)
+}
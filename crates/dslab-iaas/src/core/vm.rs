@@ -8,6 +8,7 @@ use serde::Serialize;
 
 use crate::core::config::sim_config::SimulationConfig;
 use crate::core::load_model::{ConstantLoadModel, LoadModel};
+use crate::core::migration::{estimate_precopy_migration, ConstantDirtyRate, DirtyRateModel, MigrationEstimate};
 
 /// Status of virtual machine.
 #[derive(Clone, Debug, PartialEq, Serialize)]
@@ -17,6 +18,7 @@ pub enum VmStatus {
     Finished,
     Migrating,
     FailedToAllocate,
+    Preempted,
 }
 
 impl Display for VmStatus {
@@ -27,6 +29,7 @@ impl Display for VmStatus {
             VmStatus::Finished => write!(f, "finished"),
             VmStatus::Migrating => write!(f, "migrating"),
             VmStatus::FailedToAllocate => write!(f, "failed_to_allocate"),
+            VmStatus::Preempted => write!(f, "preempted"),
         }
     }
 }
@@ -38,6 +41,10 @@ pub struct ResourceConsumer {
     pub memory_usage: u64,
     pub cpu_load_model: Box<dyn LoadModel>,
     pub memory_load_model: Box<dyn LoadModel>,
+    /// Model of memory dirtying used to estimate the live migration duration (zero dirty rate by default).
+    pub dirty_rate_model: Box<dyn DirtyRateModel>,
+    /// Memory bandwidth demand used by the host interference model (zero by default).
+    pub memory_bandwidth: f64,
 }
 
 impl ResourceConsumer {
@@ -53,6 +60,8 @@ impl ResourceConsumer {
             memory_usage,
             cpu_load_model,
             memory_load_model,
+            dirty_rate_model: Box::new(ConstantDirtyRate::new(0.)),
+            memory_bandwidth: 0.,
         }
     }
 
@@ -63,6 +72,8 @@ impl ResourceConsumer {
             memory_usage,
             cpu_load_model: Box::new(ConstantLoadModel::new(1.0)),
             memory_load_model: Box::new(ConstantLoadModel::new(1.0)),
+            dirty_rate_model: Box::new(ConstantDirtyRate::new(0.)),
+            memory_bandwidth: 0.,
         }
     }
 
@@ -73,8 +84,49 @@ impl ResourceConsumer {
             memory_usage,
             cpu_load_model: Box::new(ConstantLoadModel::new(cpu_load)),
             memory_load_model: Box::new(ConstantLoadModel::new(memory_load)),
+            dirty_rate_model: Box::new(ConstantDirtyRate::new(0.)),
+            memory_bandwidth: 0.,
         }
     }
+
+    /// Sets the model of memory dirtying used to estimate the live migration duration.
+    pub fn with_dirty_rate_model(mut self, dirty_rate_model: Box<dyn DirtyRateModel>) -> Self {
+        self.dirty_rate_model = dirty_rate_model;
+        self
+    }
+
+    /// Sets the memory bandwidth demand used by the host interference model.
+    pub fn with_memory_bandwidth(mut self, memory_bandwidth: f64) -> Self {
+        self.memory_bandwidth = memory_bandwidth;
+        self
+    }
+}
+
+/// Specification of VM submitted as part of a VM group (see [`CloudSimulation::spawn_vm_group`]).
+///
+/// [`CloudSimulation::spawn_vm_group`]: crate::simulation::CloudSimulation::spawn_vm_group
+pub struct VmSpec {
+    pub resource_consumer: ResourceConsumer,
+    pub lifetime: f64,
+    /// VM ID, generated automatically if not set.
+    pub vm_id: Option<u32>,
+}
+
+impl VmSpec {
+    /// Creates VM specification with automatically generated VM ID.
+    pub fn new(resource_consumer: ResourceConsumer, lifetime: f64) -> Self {
+        Self {
+            resource_consumer,
+            lifetime,
+            vm_id: None,
+        }
+    }
+
+    /// Sets VM ID.
+    pub fn with_id(mut self, vm_id: u32) -> Self {
+        self.vm_id = Some(vm_id);
+        self
+    }
 }
 
 /// Represents virtual machine (VM).
@@ -88,10 +140,31 @@ pub struct VirtualMachine {
     pub cpu_usage: u32,
     pub memory_usage: u64,
     pub allocation_start_time: f64,
+    /// Tenant owning the VM, used to enforce tenant quotas.
+    pub tenant: Option<String>,
+    /// VM priority, VMs with higher priority can preempt VMs with lower priority (see [`PreemptionPolicy`]).
+    ///
+    /// [`PreemptionPolicy`]: crate::core::preemption::PreemptionPolicy
+    pub priority: u32,
+    /// Delay needed to attach remote volumes to VM, which is added to VM start duration.
+    pub volume_attach_latency: f64,
+    /// Total time during which VM was paused due to live migrations.
+    pub migration_downtime: f64,
+    /// Memory bandwidth demand used by the host interference model.
+    pub memory_bandwidth: f64,
+    /// Total time by which VM execution was extended due to interference with co-located VMs.
+    pub interference_delay: f64,
+    /// Name of the image VM is started from (see [`ImageRegistry`]).
+    ///
+    /// [`ImageRegistry`]: crate::core::image::ImageRegistry
+    pub image: Option<String>,
+    /// Total time VM waited for its image to be fetched by hosts.
+    pub image_fetch_delay: f64,
     lifetime: f64,
     start_time: f64,
     cpu_load_model: Box<dyn LoadModel>,
     memory_load_model: Box<dyn LoadModel>,
+    dirty_rate_model: Box<dyn DirtyRateModel>,
     sim_config: Rc<SimulationConfig>,
 }
 
@@ -120,15 +193,24 @@ impl VirtualMachine {
             cpu_usage: resource_consumer.cpu_usage,
             memory_usage: resource_consumer.memory_usage,
             allocation_start_time,
+            tenant: None,
+            priority: 0,
+            volume_attach_latency: 0.,
+            migration_downtime: 0.,
+            memory_bandwidth: resource_consumer.memory_bandwidth,
+            interference_delay: 0.,
+            image: None,
+            image_fetch_delay: 0.,
             lifetime,
             start_time: -1.,
             cpu_load_model: resource_consumer.cpu_load_model,
             memory_load_model: resource_consumer.memory_load_model,
+            dirty_rate_model: resource_consumer.dirty_rate_model,
             sim_config,
         }
     }
 
-    /// Returns VM lifetime (it is updated when VM is migrated).
+    /// Returns VM lifetime (it is updated when VM is migrated or slowed down by interference).
     pub fn lifetime(&self) -> f64 {
         self.lifetime
     }
@@ -138,9 +220,10 @@ impl VirtualMachine {
         self.start_time
     }
 
-    /// Returns VM start duration (the value is taken from the simulation config).
+    /// Returns VM start duration (the value is taken from the simulation config),
+    /// including the time needed to attach remote volumes.
     pub fn start_duration(&self) -> f64 {
-        self.sim_config.vm_start_duration
+        self.sim_config.vm_start_duration + self.volume_attach_latency
     }
 
     /// Returns VM stop duration (the value is taken from the simulation config).
@@ -153,7 +236,7 @@ impl VirtualMachine {
         self.start_time = time;
     }
 
-    /// Changes VM lifetime. It is called only due to VM migration.
+    /// Changes VM lifetime. It is called due to VM migration and interference with co-located VMs.
     pub fn set_lifetime(&mut self, lifetime: f64) {
         self.lifetime = lifetime;
     }
@@ -167,4 +250,21 @@ impl VirtualMachine {
     pub fn get_memory_load(&self, time: f64) -> f64 {
         self.memory_load_model.get_resource_load(time, time - self.start_time)
     }
+
+    /// Returns the current memory dirty rate of VM by invoking the dirty rate model.
+    pub fn get_dirty_rate(&self, time: f64) -> f64 {
+        self.dirty_rate_model.dirty_rate(time, self.get_cpu_load(time))
+    }
+
+    /// Estimates the duration and downtime of live migration of this VM using the specified network bandwidth
+    /// and the migration parameters from the simulation config.
+    pub fn estimate_migration(&self, time: f64, bandwidth: f64) -> MigrationEstimate {
+        estimate_precopy_migration(
+            self.memory_usage as f64,
+            self.get_dirty_rate(time),
+            bandwidth,
+            self.sim_config.migration_max_rounds,
+            self.sim_config.migration_max_downtime,
+        )
+    }
 }
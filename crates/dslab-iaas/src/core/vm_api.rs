@@ -1,7 +1,7 @@
 //! Component that provides information about all VMs in the system.
 
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::rc::Rc;
 
 use sugars::{rc, refcell};
@@ -12,19 +12,46 @@ use dslab_core::event::Event;
 use dslab_core::handler::EventHandler;
 
 use crate::core::common::Allocation;
-use crate::core::events::vm_api::VmStatusChanged;
+use crate::core::events::allocation::AllocationRequest;
+use crate::core::events::vm_api::{ApiAllocationRequest, VmPreempted, VmStatusChanged};
+use crate::core::gang::GangSpec;
+use crate::core::preemption::PreemptionRecord;
+use crate::core::quota::{AdmissionVerdict, QuotaManager, TenantQuota, TenantStats};
+use crate::core::rate_limit::{RateLimit, RateLimiter, ThrottlingDecision, ThrottlingStats};
 use crate::core::vm::{VirtualMachine, VmStatus};
+use crate::core::volume::{VolumeLocation, VolumeManager};
 
 /// API to access information about virtual machines.
 ///
 /// This component stores the information about all VMs in the system, including VM characteristics, current status and
 /// location, and provides an access to this information to other components. A user can also query VM API to obtain
 /// the needed VM information.
+///
+/// VM API also serves as an admission control point, which enforces the per-tenant resource quotas
+/// and reports per-tenant resource usage.
+///
+/// The allocation requests of tenants with API rate limits are submitted via VM API, which throttles them
+/// using per-tenant token buckets. The throttled requests are retried by clients according to the tenant
+/// retry policy, and the requests which exhausted the retries are dropped.
+///
+/// VM preemptions are recorded by VM API and forwarded to the registered preemption listeners
+/// as `VmPreempted` events, so that VM owners can react to them.
+///
+/// Persistent volumes and their attachments to VMs are also managed by VM API. Volumes are detached from VM
+/// when it is finished, failed or preempted.
+///
+/// The gang scheduling requirements of multi-VM requests are stored by VM API and used by schedulers.
 pub struct VmAPI {
     vms: HashMap<u32, Rc<RefCell<VirtualMachine>>>,
     vm_status: HashMap<u32, VmStatus>,
     vm_location: HashMap<u32, u32>,
     vm_counter: u32,
+    quota_manager: QuotaManager,
+    rate_limiter: RateLimiter,
+    preemptions: Vec<PreemptionRecord>,
+    preemption_listeners: Vec<u32>,
+    volume_manager: VolumeManager,
+    gangs: HashMap<u32, GangSpec>,
     ctx: SimulationContext,
 }
 
@@ -36,6 +63,12 @@ impl VmAPI {
             vm_status: HashMap::new(),
             vm_location: HashMap::new(),
             vm_counter: 0,
+            quota_manager: QuotaManager::new(),
+            rate_limiter: RateLimiter::new(),
+            preemptions: Vec::new(),
+            preemption_listeners: Vec::new(),
+            volume_manager: VolumeManager::new(),
+            gangs: HashMap::new(),
             ctx,
         }
     }
@@ -56,6 +89,10 @@ impl VmAPI {
         if status == VmStatus::Running {
             self.vm_location.insert(vm_id, host_id);
         }
+        if status == VmStatus::Finished || status == VmStatus::FailedToAllocate || status == VmStatus::Preempted {
+            self.quota_manager.release(vm_id);
+            self.volume_manager.detach_all(vm_id);
+        }
         self.vm_status.insert(vm_id, status);
     }
 
@@ -76,6 +113,13 @@ impl VmAPI {
         self.vms.len()
     }
 
+    /// Returns the IDs of all registered VMs in ascending order.
+    pub fn get_vm_ids(&self) -> Vec<u32> {
+        let mut ids: Vec<u32> = self.vms.keys().copied().collect();
+        ids.sort();
+        ids
+    }
+
     /// Returns resource allocation for specified VM.
     pub fn get_vm_allocation(&self, vm_id: u32) -> Allocation {
         Allocation {
@@ -85,11 +129,174 @@ impl VmAPI {
         }
     }
 
+    /// Groups the specified running VMs by their hosts, other VMs are skipped.
+    pub fn group_running_vms_by_host(&self, vm_ids: &[u32]) -> BTreeMap<u32, Vec<u32>> {
+        let mut groups: BTreeMap<u32, Vec<u32>> = BTreeMap::new();
+        for vm_id in vm_ids {
+            if self.vm_status.get(vm_id) != Some(&VmStatus::Running) {
+                continue;
+            }
+            if let Some(host_id) = self.find_host_by_vm(*vm_id) {
+                groups.entry(host_id).or_default().push(*vm_id);
+            }
+        }
+        groups
+    }
+
+    /// Registers the gang scheduling requirements for the multi-VM request with specified VMs.
+    pub fn register_gang(&mut self, vm_ids: &[u32], spec: GangSpec) {
+        self.gangs.insert(vm_ids[0], spec);
+    }
+
+    /// Returns the gang scheduling requirements for the multi-VM request identified by its first VM.
+    pub fn get_gang(&self, first_vm_id: u32) -> Option<&GangSpec> {
+        self.gangs.get(&first_vm_id)
+    }
+
     // Returns the ID of host that runs the specified VM.
     pub fn find_host_by_vm(&self, vm_id: u32) -> Option<u32> {
         self.vm_location.get(&vm_id).copied()
     }
 
+    /// Sets the resource quota of the specified tenant.
+    pub fn set_tenant_quota(&mut self, tenant: &str, quota: TenantQuota) {
+        self.quota_manager.set_quota(tenant, quota);
+    }
+
+    /// Checks the allocation request with specified VMs against the quotas of VM tenants.
+    ///
+    /// If the request is admitted, the VM resources are charged to the tenant usage until VMs are finished or failed.
+    pub fn admit(&mut self, vm_ids: &[u32]) -> AdmissionVerdict {
+        let requests: Vec<_> = vm_ids
+            .iter()
+            .map(|vm_id| {
+                (
+                    self.get_vm(*vm_id).borrow().tenant.clone(),
+                    self.get_vm_allocation(*vm_id),
+                )
+            })
+            .collect();
+        self.quota_manager.admit(&requests)
+    }
+
+    /// Returns the resource usage and admission statistics of tenants.
+    pub fn tenant_stats(&self) -> &BTreeMap<String, TenantStats> {
+        self.quota_manager.stats()
+    }
+
+    /// Sets the API rate limit of the specified tenant.
+    pub fn set_tenant_rate_limit(&mut self, tenant: &str, limit: RateLimit) {
+        self.rate_limiter.set_limit(tenant, limit, self.ctx.time());
+    }
+
+    /// Checks if the API requests of the specified tenant are rate limited.
+    pub fn is_rate_limited(&self, tenant: &str) -> bool {
+        self.rate_limiter.get_limit(tenant).is_some()
+    }
+
+    /// Returns the API throttling statistics of tenants with rate limits.
+    pub fn throttling_stats(&self) -> &BTreeMap<String, ThrottlingStats> {
+        self.rate_limiter.stats()
+    }
+
+    /// Processes the allocation request submitted via API.
+    ///
+    /// The accepted request is passed to the scheduler, while the throttled one is either retried after the client
+    /// backoff or dropped, in which case its VMs become `FailedToAllocate`.
+    fn on_api_allocation_request(&mut self, vm_ids: Vec<u32>, scheduler_id: u32, attempt: u32, submit_time: f64) {
+        let tenant = self.get_vm(vm_ids[0]).borrow().tenant.clone().unwrap_or_default();
+        let decision = self
+            .rate_limiter
+            .check(&tenant, vm_ids.len(), attempt, submit_time, self.ctx.time());
+        match decision {
+            ThrottlingDecision::Accepted => {
+                self.ctx.emit_now(AllocationRequest { vm_ids }, scheduler_id);
+            }
+            ThrottlingDecision::Retry(delay) => {
+                self.ctx.emit_self(
+                    ApiAllocationRequest {
+                        vm_ids,
+                        scheduler_id,
+                        attempt: attempt + 1,
+                        submit_time,
+                    },
+                    delay,
+                );
+            }
+            ThrottlingDecision::Dropped => {
+                for vm_id in vm_ids {
+                    self.update_vm_status(vm_id, VmStatus::FailedToAllocate, self.ctx.id());
+                }
+            }
+        }
+    }
+
+    /// Returns the volume manager.
+    pub fn volumes(&self) -> &VolumeManager {
+        &self.volume_manager
+    }
+
+    /// Returns the mutable reference to volume manager.
+    pub fn volumes_mut(&mut self) -> &mut VolumeManager {
+        &mut self.volume_manager
+    }
+
+    /// Creates volume in the specified location. Returns volume ID.
+    pub fn create_volume(&mut self, capacity: u64, iops: u32, location: VolumeLocation) -> Result<u32, String> {
+        self.volume_manager.create_volume(capacity, iops, location)
+    }
+
+    /// Attaches volume to the specified VM, which should not be placed yet.
+    ///
+    /// The VM start duration is increased by the attach latency of remote volumes.
+    pub fn attach_volume(&mut self, volume_id: u32, vm_id: u32) -> Result<(), String> {
+        let status = self.get_vm_status(vm_id);
+        if status != VmStatus::Initializing {
+            return Err(format!("Can't attach volume to VM {} with status {}", vm_id, status));
+        }
+        self.volume_manager.attach(volume_id, vm_id)?;
+        self.get_vm(vm_id).borrow_mut().volume_attach_latency = self.volume_manager.attach_latency(vm_id);
+        Ok(())
+    }
+
+    /// Checks if the specified VM can be placed on the specified host, i.e. the host stores
+    /// all local volumes attached to the VM.
+    pub fn is_host_eligible(&self, vm_id: u32, host_id: u32) -> bool {
+        self.volume_manager.is_host_eligible(vm_id, host_id)
+    }
+
+    /// Registers component which will receive `VmPreempted` events.
+    pub fn add_preemption_listener(&mut self, component_id: u32) {
+        self.preemption_listeners.push(component_id);
+    }
+
+    /// Returns the history of VM preemptions.
+    pub fn get_preemptions(&self) -> &[PreemptionRecord] {
+        &self.preemptions
+    }
+
+    /// Records VM preemption and notifies the listeners.
+    fn on_vm_preempted(&mut self, vm_id: u32, host_id: u32, preempted_by: u32, target_host: Option<u32>) {
+        self.preemptions.push(PreemptionRecord {
+            time: self.ctx.time(),
+            vm_id,
+            host_id,
+            preempted_by,
+            target_host,
+        });
+        for listener in self.preemption_listeners.iter() {
+            self.ctx.emit_now(
+                VmPreempted {
+                    vm_id,
+                    host_id,
+                    preempted_by,
+                    target_host,
+                },
+                *listener,
+            );
+        }
+    }
+
     /// Generates new VM ID if user did not pass any.
     pub fn generate_vm_id(&mut self) -> u32 {
         self.vm_counter += 1;
@@ -98,12 +305,29 @@ impl VmAPI {
 }
 
 impl EventHandler for VmAPI {
-    /// Processes VM status change event emitted by host manager.
+    /// Processes VM status change events emitted by host managers, VM preemption events emitted by placement store
+    /// and allocation requests submitted via API.
     fn on(&mut self, event: Event) {
         cast!(match event.data {
             VmStatusChanged { vm_id, status } => {
                 self.update_vm_status(vm_id, status, event.src);
             }
+            VmPreempted {
+                vm_id,
+                host_id,
+                preempted_by,
+                target_host,
+            } => {
+                self.on_vm_preempted(vm_id, host_id, preempted_by, target_host);
+            }
+            ApiAllocationRequest {
+                vm_ids,
+                scheduler_id,
+                attempt,
+                submit_time,
+            } => {
+                self.on_api_allocation_request(vm_ids, scheduler_id, attempt, submit_time);
+            }
         })
     }
 }
@@ -1,5 +1,8 @@
 //! Virtual machine placement algorithms.
 
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
 use crate::core::common::Allocation;
 use crate::core::config::options::parse_config_value;
 use crate::core::monitoring::Monitoring;
@@ -10,8 +13,10 @@ use crate::core::vm_placement_algorithms::cosine_similarity::CosineSimilarity;
 use crate::core::vm_placement_algorithms::delta_perp_distance::DeltaPerpDistance;
 use crate::core::vm_placement_algorithms::dot_product::DotProduct;
 use crate::core::vm_placement_algorithms::first_fit::FirstFit;
+use crate::core::vm_placement_algorithms::min_inlet_temperature::MinInletTemperature;
 use crate::core::vm_placement_algorithms::norm_diff::L2NormDiff;
 use crate::core::vm_placement_algorithms::rack_anti_affinity::RackAntiAffinity;
+use crate::core::vm_placement_algorithms::traffic_aware::TrafficAware;
 use crate::core::vm_placement_algorithms::weighted_dot_product::WeightedDotProduct;
 use crate::core::vm_placement_algorithms::worst_fit::WorstFit;
 
@@ -57,19 +62,94 @@ pub trait MultiVMPlacementAlgorithm {
     ) -> Option<Vec<u32>>;
 }
 
-pub fn placement_algorithm_resolver(config_str: String) -> VMPlacementAlgorithm {
-    let (algorithm_name, options) = parse_config_value(&config_str);
-    match algorithm_name.as_str() {
-        "FirstFit" => VMPlacementAlgorithm::single(FirstFit::new()),
-        "BestFit" => VMPlacementAlgorithm::single(BestFit::new()),
-        "WorstFit" => VMPlacementAlgorithm::single(WorstFit::new()),
-        "BestFitThreshold" => VMPlacementAlgorithm::single(BestFitThreshold::from_string(&options.unwrap())),
-        "CosineSimilarity" => VMPlacementAlgorithm::single(CosineSimilarity::new()),
-        "DotProduct" => VMPlacementAlgorithm::single(DotProduct::new()),
-        "WeightedDotProduct" => VMPlacementAlgorithm::single(WeightedDotProduct::new()),
-        "L2NormDiff" => VMPlacementAlgorithm::single(L2NormDiff::new()),
-        "DeltaPerpDistance" => VMPlacementAlgorithm::single(DeltaPerpDistance::new()),
-        "RackAntiAffinity" => VMPlacementAlgorithm::multi(RackAntiAffinity::new()),
-        _ => panic!("Can't resolve: {}", config_str),
+/// Function which creates placement algorithm from the options string, e.g. `threshold=0.8`
+/// (the string is empty if options are not specified).
+pub type PlacementAlgorithmFactory = Arc<dyn Fn(&str) -> VMPlacementAlgorithm + Send + Sync>;
+
+/// Name-based registry of VM placement algorithms used to resolve the algorithms specified in config.
+///
+/// The algorithm is specified as config value string, e.g. `BestFitThreshold[threshold=0.8]`, where the name is used
+/// to find the algorithm factory, which is then invoked with the options string. The default registry contains all
+/// built-in algorithms, while custom algorithms can be registered by the user.
+#[derive(Clone)]
+pub struct PlacementAlgorithmRegistry {
+    factories: BTreeMap<String, PlacementAlgorithmFactory>,
+}
+
+impl PlacementAlgorithmRegistry {
+    /// Creates registry with all built-in algorithms.
+    pub fn new() -> Self {
+        let mut registry = Self::empty();
+        registry.register("FirstFit", |_| VMPlacementAlgorithm::single(FirstFit::new()));
+        registry.register("BestFit", |_| VMPlacementAlgorithm::single(BestFit::new()));
+        registry.register("WorstFit", |_| VMPlacementAlgorithm::single(WorstFit::new()));
+        registry.register("BestFitThreshold", |options| {
+            VMPlacementAlgorithm::single(BestFitThreshold::from_string(options))
+        });
+        registry.register("CosineSimilarity", |_| {
+            VMPlacementAlgorithm::single(CosineSimilarity::new())
+        });
+        registry.register("DotProduct", |_| VMPlacementAlgorithm::single(DotProduct::new()));
+        registry.register("WeightedDotProduct", |_| {
+            VMPlacementAlgorithm::single(WeightedDotProduct::new())
+        });
+        registry.register("L2NormDiff", |_| VMPlacementAlgorithm::single(L2NormDiff::new()));
+        registry.register("DeltaPerpDistance", |_| {
+            VMPlacementAlgorithm::single(DeltaPerpDistance::new())
+        });
+        registry.register("MinInletTemperature", |_| {
+            VMPlacementAlgorithm::single(MinInletTemperature::new())
+        });
+        registry.register("RackAntiAffinity", |_| {
+            VMPlacementAlgorithm::multi(RackAntiAffinity::new())
+        });
+        registry.register("TrafficAware", |_| VMPlacementAlgorithm::single(TrafficAware::new()));
+        registry
+    }
+
+    /// Creates registry without any algorithms.
+    pub fn empty() -> Self {
+        Self {
+            factories: BTreeMap::new(),
+        }
+    }
+
+    /// Registers algorithm factory with specified name, replacing the previously registered one (if any).
+    pub fn register<F>(&mut self, name: &str, factory: F)
+    where
+        F: Fn(&str) -> VMPlacementAlgorithm + Send + Sync + 'static,
+    {
+        self.factories.insert(name.to_string(), Arc::new(factory));
+    }
+
+    /// Returns true if the algorithm with specified name is registered.
+    pub fn contains(&self, name: &str) -> bool {
+        self.factories.contains_key(name)
+    }
+
+    /// Returns the names of registered algorithms.
+    pub fn names(&self) -> Vec<String> {
+        self.factories.keys().cloned().collect()
     }
+
+    /// Creates placement algorithm from config value string.
+    pub fn resolve(&self, config_str: &str) -> VMPlacementAlgorithm {
+        let (algorithm_name, options) = parse_config_value(config_str);
+        let factory = self
+            .factories
+            .get(&algorithm_name)
+            .unwrap_or_else(|| panic!("Can't resolve: {}", config_str));
+        factory(&options.unwrap_or_default())
+    }
+}
+
+impl Default for PlacementAlgorithmRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Creates built-in placement algorithm from config value string.
+pub fn placement_algorithm_resolver(config_str: String) -> VMPlacementAlgorithm {
+    PlacementAlgorithmRegistry::new().resolve(&config_str)
 }
@@ -0,0 +1,36 @@
+//! Thermal-aware algorithm selecting the coolest host.
+
+use crate::core::common::Allocation;
+use crate::core::common::AllocationVerdict;
+use crate::core::monitoring::Monitoring;
+use crate::core::resource_pool::ResourcePoolState;
+use crate::core::vm_placement_algorithm::SingleVMPlacementAlgorithm;
+
+/// Uses the suitable host with the minimum inlet temperature reported by monitoring.
+/// Hosts without thermal model are used only if there are no suitable hosts with known temperature.
+#[derive(Default)]
+pub struct MinInletTemperature;
+
+impl MinInletTemperature {
+    pub fn new() -> Self {
+        Default::default()
+    }
+}
+
+impl SingleVMPlacementAlgorithm for MinInletTemperature {
+    fn select_host(&self, alloc: &Allocation, pool_state: &ResourcePoolState, monitoring: &Monitoring) -> Option<u32> {
+        let mut result: Option<u32> = None;
+        let mut min_temperature = f64::INFINITY;
+
+        for host in pool_state.get_host_ids() {
+            if pool_state.can_allocate(alloc, host, false) == AllocationVerdict::Success {
+                let temperature = monitoring.get_host_inlet_temperature(host).unwrap_or(f64::INFINITY);
+                if result.is_none() || temperature < min_temperature {
+                    min_temperature = temperature;
+                    result = Some(host);
+                }
+            }
+        }
+        result
+    }
+}
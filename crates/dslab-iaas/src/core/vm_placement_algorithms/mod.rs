@@ -4,7 +4,9 @@ pub mod cosine_similarity;
 pub mod delta_perp_distance;
 pub mod dot_product;
 pub mod first_fit;
+pub mod min_inlet_temperature;
 pub mod norm_diff;
 pub mod rack_anti_affinity;
+pub mod traffic_aware;
 pub mod weighted_dot_product;
 pub mod worst_fit;
@@ -0,0 +1,59 @@
+//! Network-aware algorithm co-locating communicating VMs.
+
+use crate::core::common::Allocation;
+use crate::core::common::AllocationVerdict;
+use crate::core::monitoring::Monitoring;
+use crate::core::resource_pool::ResourcePoolState;
+use crate::core::vm_placement_algorithm::SingleVMPlacementAlgorithm;
+
+/// Uses the suitable host minimizing the network cost of VM traffic, which is computed as the sum of traffic rates
+/// to already placed peer VMs multiplied by the network latency to their hosts. The traffic between VMs on the same
+/// host is free. If the network is not set, the latency between different hosts is assumed to be 1.
+///
+/// Ties are broken by selecting the most loaded (by allocated CPU) host, as in Best Fit.
+#[derive(Default)]
+pub struct TrafficAware;
+
+impl TrafficAware {
+    pub fn new() -> Self {
+        Default::default()
+    }
+}
+
+/// Returns the host of the specified allocation in the resource pool state.
+fn find_allocation_host(pool_state: &ResourcePoolState, alloc_id: u32) -> Option<u32> {
+    pool_state
+        .get_hosts()
+        .find(|host| host.allocations.contains_key(&alloc_id))
+        .map(|host| host.id)
+}
+
+impl SingleVMPlacementAlgorithm for TrafficAware {
+    fn select_host(&self, alloc: &Allocation, pool_state: &ResourcePoolState, monitoring: &Monitoring) -> Option<u32> {
+        let peers: Vec<(u32, f64)> = monitoring
+            .get_traffic_matrix()
+            .peers(alloc.id)
+            .into_iter()
+            .filter_map(|(peer, rate)| find_allocation_host(pool_state, peer).map(|host| (host, rate)))
+            .collect();
+
+        let mut result: Option<u32> = None;
+        let mut best_score = (f64::INFINITY, u32::MAX);
+        for host in pool_state.get_host_ids() {
+            if pool_state.can_allocate(alloc, host, false) != AllocationVerdict::Success {
+                continue;
+            }
+            let cost: f64 = peers
+                .iter()
+                .filter(|(peer_host, _)| *peer_host != host)
+                .map(|(peer_host, rate)| rate * monitoring.get_network_latency(host, *peer_host).unwrap_or(1.))
+                .sum();
+            let score = (cost, pool_state.get_available_cpu(host));
+            if score < best_score {
+                best_score = score;
+                result = Some(host);
+            }
+        }
+        result
+    }
+}
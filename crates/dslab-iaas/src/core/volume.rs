@@ -0,0 +1,230 @@
+//! Persistent volumes attached to VMs.
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+/// Location of a volume.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub enum VolumeLocation {
+    /// Volume is stored on the local storage of the specified host, so VM can use it only if placed on this host.
+    Host(u32),
+    /// Volume is stored in the specified storage pool and can be attached remotely to VM placed on any host.
+    StoragePool(u32),
+}
+
+/// Persistent volume with specified capacity and IOPS.
+///
+/// Volume outlives the VMs it is attached to, so it can be attached to another VM after the previous one is finished.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Volume {
+    pub id: u32,
+    pub capacity: u64,
+    pub iops: u32,
+    pub location: VolumeLocation,
+    /// VM to which the volume is currently attached.
+    pub attached_vm: Option<u32>,
+}
+
+/// Storage capacity and IOPS of a host local storage or a storage pool.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct StorageInfo {
+    pub capacity: u64,
+    pub iops: u32,
+    pub capacity_allocated: u64,
+    pub iops_allocated: u32,
+    /// Delay added to VM start time when the volume from this storage is attached to VM.
+    pub attach_latency: f64,
+}
+
+impl StorageInfo {
+    fn new(capacity: u64, iops: u32, attach_latency: f64) -> Self {
+        Self {
+            capacity,
+            iops,
+            capacity_allocated: 0,
+            iops_allocated: 0,
+            attach_latency,
+        }
+    }
+}
+
+/// Stores volumes and storages, and tracks the volume attachments.
+#[derive(Clone, Debug, Default)]
+pub struct VolumeManager {
+    volumes: BTreeMap<u32, Volume>,
+    host_storages: BTreeMap<u32, StorageInfo>,
+    storage_pools: BTreeMap<u32, StorageInfo>,
+    storage_pool_names: BTreeMap<String, u32>,
+    vm_volumes: BTreeMap<u32, Vec<u32>>,
+    volume_counter: u32,
+}
+
+impl VolumeManager {
+    /// Creates empty volume manager.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Sets the capacity and IOPS of the local storage of the specified host.
+    pub fn set_host_storage(&mut self, host_id: u32, capacity: u64, iops: u32) {
+        self.host_storages.insert(host_id, StorageInfo::new(capacity, iops, 0.));
+    }
+
+    /// Adds storage pool with specified capacity, IOPS and attach latency. Returns storage pool ID.
+    pub fn add_storage_pool(&mut self, name: &str, capacity: u64, iops: u32, attach_latency: f64) -> u32 {
+        assert!(
+            !self.storage_pool_names.contains_key(name),
+            "Storage pool {} already exists",
+            name
+        );
+        let id = self.storage_pools.len() as u32;
+        self.storage_pools
+            .insert(id, StorageInfo::new(capacity, iops, attach_latency));
+        self.storage_pool_names.insert(name.to_string(), id);
+        id
+    }
+
+    /// Returns the ID of storage pool with specified name.
+    pub fn lookup_storage_pool(&self, name: &str) -> Option<u32> {
+        self.storage_pool_names.get(name).copied()
+    }
+
+    /// Returns the storage info for the specified volume location.
+    pub fn get_storage(&self, location: VolumeLocation) -> Option<&StorageInfo> {
+        match location {
+            VolumeLocation::Host(host_id) => self.host_storages.get(&host_id),
+            VolumeLocation::StoragePool(pool_id) => self.storage_pools.get(&pool_id),
+        }
+    }
+
+    fn get_storage_mut(&mut self, location: VolumeLocation) -> Option<&mut StorageInfo> {
+        match location {
+            VolumeLocation::Host(host_id) => self.host_storages.get_mut(&host_id),
+            VolumeLocation::StoragePool(pool_id) => self.storage_pools.get_mut(&pool_id),
+        }
+    }
+
+    /// Creates volume in the specified location. Returns volume ID.
+    ///
+    /// Fails if the storage does not exist or does not have enough free capacity or IOPS.
+    pub fn create_volume(&mut self, capacity: u64, iops: u32, location: VolumeLocation) -> Result<u32, String> {
+        let storage = self
+            .get_storage_mut(location)
+            .ok_or_else(|| format!("Storage {:?} does not exist", location))?;
+        if storage.capacity_allocated + capacity > storage.capacity {
+            return Err(format!("Not enough capacity in storage {:?}", location));
+        }
+        if storage.iops_allocated + iops > storage.iops {
+            return Err(format!("Not enough IOPS in storage {:?}", location));
+        }
+        storage.capacity_allocated += capacity;
+        storage.iops_allocated += iops;
+        let id = self.volume_counter;
+        self.volume_counter += 1;
+        self.volumes.insert(
+            id,
+            Volume {
+                id,
+                capacity,
+                iops,
+                location,
+                attached_vm: None,
+            },
+        );
+        Ok(id)
+    }
+
+    /// Deletes volume and frees its storage resources. Fails if the volume is attached to VM.
+    pub fn delete_volume(&mut self, volume_id: u32) -> Result<(), String> {
+        let volume = self
+            .volumes
+            .get(&volume_id)
+            .ok_or_else(|| format!("Volume {} does not exist", volume_id))?;
+        if let Some(vm_id) = volume.attached_vm {
+            return Err(format!("Volume {} is attached to VM {}", volume_id, vm_id));
+        }
+        let volume = self.volumes.remove(&volume_id).unwrap();
+        let storage = self.get_storage_mut(volume.location).unwrap();
+        storage.capacity_allocated -= volume.capacity;
+        storage.iops_allocated -= volume.iops;
+        Ok(())
+    }
+
+    /// Returns volume by its ID.
+    pub fn get_volume(&self, volume_id: u32) -> Option<&Volume> {
+        self.volumes.get(&volume_id)
+    }
+
+    /// Returns an iterator over all volumes.
+    pub fn get_volumes(&self) -> impl Iterator<Item = &Volume> + '_ {
+        self.volumes.values()
+    }
+
+    /// Attaches volume to the specified VM. Fails if the volume does not exist or is attached to another VM,
+    /// or if the VM already uses local volumes of another host.
+    pub fn attach(&mut self, volume_id: u32, vm_id: u32) -> Result<(), String> {
+        let volume = self
+            .volumes
+            .get(&volume_id)
+            .ok_or_else(|| format!("Volume {} does not exist", volume_id))?;
+        if let Some(other_vm) = volume.attached_vm {
+            return Err(format!("Volume {} is already attached to VM {}", volume_id, other_vm));
+        }
+        if let VolumeLocation::Host(host_id) = volume.location {
+            if self.required_host(vm_id).is_some_and(|required| required != host_id) {
+                return Err(format!(
+                    "VM {} already uses local volumes of another host than volume {}",
+                    vm_id, volume_id
+                ));
+            }
+        }
+        self.volumes.get_mut(&volume_id).unwrap().attached_vm = Some(vm_id);
+        self.vm_volumes.entry(vm_id).or_default().push(volume_id);
+        Ok(())
+    }
+
+    /// Detaches all volumes from the specified VM.
+    pub fn detach_all(&mut self, vm_id: u32) {
+        for volume_id in self.vm_volumes.remove(&vm_id).unwrap_or_default() {
+            if let Some(volume) = self.volumes.get_mut(&volume_id) {
+                volume.attached_vm = None;
+            }
+        }
+    }
+
+    /// Returns IDs of volumes attached to the specified VM.
+    pub fn get_vm_volumes(&self, vm_id: u32) -> &[u32] {
+        self.vm_volumes.get(&vm_id).map(|v| v.as_slice()).unwrap_or_default()
+    }
+
+    /// Returns the host which stores the local volumes attached to the specified VM (if any).
+    ///
+    /// VM with attached local volumes can be placed only on this host.
+    pub fn required_host(&self, vm_id: u32) -> Option<u32> {
+        self.get_vm_volumes(vm_id)
+            .iter()
+            .find_map(|volume_id| match self.volumes[volume_id].location {
+                VolumeLocation::Host(host_id) => Some(host_id),
+                VolumeLocation::StoragePool(_) => None,
+            })
+    }
+
+    /// Checks if VM with attached volumes can be placed on the specified host.
+    pub fn is_host_eligible(&self, vm_id: u32, host_id: u32) -> bool {
+        self.required_host(vm_id).is_none_or(|required| required == host_id)
+    }
+
+    /// Returns the delay needed to attach the volumes of the specified VM,
+    /// i.e. the maximum attach latency among the storage pools of remote volumes.
+    pub fn attach_latency(&self, vm_id: u32) -> f64 {
+        self.get_vm_volumes(vm_id)
+            .iter()
+            .map(|volume_id| {
+                self.get_storage(self.volumes[volume_id].location)
+                    .unwrap()
+                    .attach_latency
+            })
+            .fold(0., f64::max)
+    }
+}
@@ -15,6 +15,7 @@ use dslab_core::Simulation;
 use crate::core::config::exp_config::ExperimentConfig;
 use crate::core::config::sim_config::SimulationConfig;
 use crate::core::logger::{FileLogger, Logger, StdoutLogger};
+use crate::core::vm_placement_algorithm::{PlacementAlgorithmRegistry, VMPlacementAlgorithm};
 use crate::simulation::CloudSimulation;
 
 /// Trait for implementing custom callbacks for simulation runs within an experiment.
@@ -41,6 +42,7 @@ pub struct Experiment {
     pub callbacks: Box<dyn SimulationCallbacks>,
     pub log_dir: Option<String>,
     pub log_level: Level,
+    pub placement_algorithms: PlacementAlgorithmRegistry,
 }
 
 impl Experiment {
@@ -62,9 +64,18 @@ impl Experiment {
             callbacks,
             log_dir,
             log_level,
+            placement_algorithms: PlacementAlgorithmRegistry::new(),
         }
     }
 
+    /// Registers custom placement algorithm, which can be referenced by name in the experiment config.
+    pub fn register_placement_algorithm<F>(&mut self, name: &str, factory: F)
+    where
+        F: Fn(&str) -> VMPlacementAlgorithm + Send + Sync + 'static,
+    {
+        self.placement_algorithms.register(name, factory);
+    }
+
     /// Runs the experiment using the specified number of threads.
     pub fn run(&mut self, num_threads: usize) {
         let results = Arc::new(Mutex::new(Vec::new()));
@@ -77,14 +88,22 @@ impl Experiment {
             let log_level = self.log_level;
             let log_file = self.log_dir.clone().map(|dir| format!("{}/log_{}.csv", dir, run_id));
             let results = results.clone();
+            let placement_algorithms = self.placement_algorithms.clone();
 
             pool.execute(move || {
                 println!("RUN {}: {}", run_id, config_info);
-                let run_results = run_simulation(run_id, run_config.clone(), &mut callbacks, log_file, log_level);
+                let run_results = run_simulation(
+                    run_id,
+                    run_config.clone(),
+                    &mut callbacks,
+                    log_file,
+                    log_level,
+                    placement_algorithms,
+                );
 
                 let mut run_entry = IndexMap::<String, DictValue>::new();
                 run_entry.insert("id".to_string(), DictValue::String(format!("{}", run_id)));
-                run_entry.insert("config".to_string(), DictValue::Config(run_config));
+                run_entry.insert("config".to_string(), DictValue::Config(Box::new(run_config)));
                 run_entry.insert("results".to_string(), DictValue::StringDict(run_results));
                 results.lock().unwrap().push(run_entry);
             });
@@ -108,7 +127,7 @@ enum DictValue {
     String(String),
     Dict(IndexMap<String, DictValue>),
     StringDict(IndexMap<String, String>),
-    Config(SimulationConfig),
+    Config(Box<SimulationConfig>),
 }
 
 fn run_simulation(
@@ -117,6 +136,7 @@ fn run_simulation(
     callbacks: &mut Box<dyn SimulationCallbacks>,
     log_file: Option<String>,
     log_level: Level,
+    placement_algorithms: PlacementAlgorithmRegistry,
 ) -> IndexMap<String, String> {
     let logger: Box<dyn Logger> = if log_file.is_some() {
         Box::new(FileLogger::with_level(log_level))
@@ -125,7 +145,7 @@ fn run_simulation(
     };
 
     let sim = Simulation::new(123);
-    let mut cloud_sim = CloudSimulation::with_logger(sim, config.clone(), logger);
+    let mut cloud_sim = CloudSimulation::with_placement_algorithms(sim, config.clone(), logger, placement_algorithms);
     callbacks.on_simulation_start(&mut cloud_sim);
 
     while cloud_sim.current_time() <= config.simulation_length {
@@ -145,6 +145,8 @@ impl DatasetReader for AzureDatasetReader {
             cpu_load_model: Box::new(ConstantLoadModel::new(1.)),
             memory_load_model: Box::new(ConstantLoadModel::new(1.)),
             scheduler_name: None,
+            tenant: None,
+            priority: 0,
         })
     }
 }
@@ -0,0 +1,521 @@
+//! HPC batch job scheduler with backfilling.
+//!
+//! The scheduler manages a set of cluster nodes (the hosts of cloud simulation) and runs batch jobs, each of which
+//! exclusively occupies the requested number of nodes for its runtime. The jobs are submitted to one of the queues
+//! and wait there until they are started by the scheduler. The waiting jobs are ordered by the queue priority and
+//! then by submission time, and are started in this order (FCFS). The scheduler supports the following
+//! [backfilling](BackfillingPolicy) policies which allow to start the later jobs out of order:
+//!
+//! - EASY backfilling: the first waiting job gets a reservation for the earliest time when enough nodes will be free,
+//!   and the later jobs can be started if they do not delay this reservation.
+//! - Conservative backfilling: each waiting job gets a reservation, and the later jobs can be started if they
+//!   do not delay any of the reservations.
+//!
+//! The reservations are computed using the walltime estimates of jobs. The job whose runtime exceeds its walltime
+//! is killed when the walltime expires.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::Serialize;
+
+use dslab_core::cast;
+use dslab_core::component::Id;
+use dslab_core::context::SimulationContext;
+use dslab_core::event::Event;
+use dslab_core::handler::EventHandler;
+use dslab_core::{log_debug, log_warn};
+
+use crate::core::common::Allocation;
+use crate::core::host_manager::HostManager;
+use crate::core::resource_pool::ResourcePoolState;
+use crate::custom_component::CustomComponent;
+
+/// Batch job requesting a number of nodes for some time.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct BatchJob {
+    pub id: u32,
+    pub submit_time: f64,
+    /// Number of requested nodes.
+    pub nodes: u32,
+    /// User estimate of job runtime, the job is killed if it runs longer.
+    pub walltime: f64,
+    /// Actual job runtime.
+    pub runtime: f64,
+    /// Queue to which the job is submitted.
+    pub queue: u32,
+}
+
+/// Policy used to start the waiting jobs out of order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BackfillingPolicy {
+    /// Jobs are started strictly in the queue order.
+    None,
+    /// EASY (aggressive) backfilling with reservation for the first waiting job.
+    Easy,
+    /// Conservative backfilling with reservations for all waiting jobs.
+    Conservative,
+}
+
+/// Information about processed job.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct BatchJobRecord {
+    pub job: BatchJob,
+    pub start_time: f64,
+    pub finish_time: f64,
+    /// Nodes used by the job.
+    pub hosts: Vec<u32>,
+    /// Whether the job was killed after exceeding its walltime.
+    pub killed: bool,
+}
+
+impl BatchJobRecord {
+    /// Returns the time spent by job in the queue.
+    pub fn wait_time(&self) -> f64 {
+        self.start_time - self.job.submit_time
+    }
+
+    /// Returns the bounded slowdown of the job, i.e. the ratio of its response time to its runtime,
+    /// where the runtime is bounded from below by the specified threshold (usually 10 seconds).
+    pub fn bounded_slowdown(&self, threshold: f64) -> f64 {
+        let runtime = self.finish_time - self.start_time;
+        ((self.finish_time - self.job.submit_time) / runtime.max(threshold)).max(1.)
+    }
+}
+
+/// Statistics of jobs processed by the scheduler.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BatchStats {
+    pub submitted: u64,
+    pub completed: u64,
+    pub killed: u64,
+    /// Number of jobs rejected because they request more nodes than the cluster has.
+    pub rejected: u64,
+    pub mean_wait_time: f64,
+    pub max_wait_time: f64,
+    /// Mean bounded slowdown with threshold of 10 seconds.
+    pub mean_bounded_slowdown: f64,
+    /// Fraction of node time occupied by jobs from the first job submission until the last job completion.
+    pub utilization: f64,
+    /// Time of the last job completion.
+    pub makespan: f64,
+}
+
+/// Event signalling the job start. Source: scheduler, destination: listener.
+#[derive(Clone, Serialize)]
+pub struct BatchJobStarted {
+    pub job: BatchJob,
+    pub hosts: Vec<u32>,
+}
+
+/// Event signalling the job completion. Source: scheduler, destination: listener.
+#[derive(Clone, Serialize)]
+pub struct BatchJobFinished {
+    pub record: BatchJobRecord,
+}
+
+#[derive(Clone, Serialize)]
+pub struct BatchJobSubmitted {
+    pub job: BatchJob,
+}
+
+#[derive(Clone, Serialize)]
+pub struct BatchJobCompleted {
+    pub job_id: u32,
+}
+
+struct RunningJob {
+    job: BatchJob,
+    start_time: f64,
+    hosts: Vec<u32>,
+}
+
+impl RunningJob {
+    fn expected_end(&self) -> f64 {
+        self.start_time + self.job.walltime
+    }
+}
+
+/// Component implementing batch job scheduling on cluster nodes.
+pub struct BatchScheduler {
+    backfilling: BackfillingPolicy,
+    pool_state: ResourcePoolState,
+    queue_priorities: HashMap<u32, i32>,
+    waiting: Vec<BatchJob>,
+    running: BTreeMap<u32, RunningJob>,
+    records: Vec<BatchJobRecord>,
+    submitted: u64,
+    rejected: u64,
+    first_submit_time: Option<f64>,
+    listener: Option<Id>,
+    ctx: SimulationContext,
+}
+
+impl BatchScheduler {
+    /// Sets the backfilling policy, no backfilling (pure FCFS) is used by default.
+    pub fn patch_custom_args(&mut self, backfilling: BackfillingPolicy) {
+        self.backfilling = backfilling;
+    }
+
+    /// Adds host as cluster node.
+    pub fn add_node(&mut self, host: &HostManager) {
+        self.pool_state.add_host(
+            host.id,
+            host.cpu_total(),
+            host.memory_total(),
+            host.cpu_total(),
+            host.memory_total(),
+            host.rack_id,
+            host.host_type.clone(),
+        );
+    }
+
+    /// Adds queue with the specified priority, jobs from queues with higher priority are started first.
+    ///
+    /// The jobs submitted to unknown queues have zero priority.
+    pub fn add_queue(&mut self, queue: u32, priority: i32) {
+        self.queue_priorities.insert(queue, priority);
+    }
+
+    /// Sets the component which receives [`BatchJobStarted`] and [`BatchJobFinished`] events.
+    pub fn set_listener(&mut self, id: Id) {
+        self.listener = Some(id);
+    }
+
+    /// Submits the job at its submission time (or immediately if this time has passed).
+    pub fn submit_job(&mut self, job: BatchJob) {
+        let delay = (job.submit_time - self.ctx.time()).max(0.);
+        self.ctx.emit_self(BatchJobSubmitted { job }, delay);
+    }
+
+    /// Submits all jobs from the list, e.g. parsed from SWF trace.
+    pub fn submit_jobs(&mut self, jobs: Vec<BatchJob>) {
+        for job in jobs {
+            self.submit_job(job);
+        }
+    }
+
+    /// Returns the number of cluster nodes.
+    pub fn node_count(&self) -> u32 {
+        self.pool_state.get_host_count()
+    }
+
+    /// Returns the number of free cluster nodes.
+    pub fn free_nodes(&self) -> u32 {
+        self.pool_state.get_hosts().filter(|h| h.allocations.is_empty()).count() as u32
+    }
+
+    /// Returns the ids of waiting jobs in the order of their priority.
+    pub fn waiting_jobs(&self) -> Vec<u32> {
+        self.waiting.iter().map(|job| job.id).collect()
+    }
+
+    /// Returns the ids of running jobs.
+    pub fn running_jobs(&self) -> Vec<u32> {
+        self.running.keys().copied().collect()
+    }
+
+    /// Returns the records of finished jobs in the order of completion.
+    pub fn job_records(&self) -> &[BatchJobRecord] {
+        &self.records
+    }
+
+    /// Returns the statistics of processed jobs.
+    pub fn stats(&self) -> BatchStats {
+        let mut stats = BatchStats {
+            submitted: self.submitted,
+            completed: self.records.iter().filter(|r| !r.killed).count() as u64,
+            killed: self.records.iter().filter(|r| r.killed).count() as u64,
+            rejected: self.rejected,
+            ..Default::default()
+        };
+        if self.records.is_empty() {
+            return stats;
+        }
+        let count = self.records.len() as f64;
+        stats.mean_wait_time = self.records.iter().map(|r| r.wait_time()).sum::<f64>() / count;
+        stats.max_wait_time = self.records.iter().map(|r| r.wait_time()).fold(0., f64::max);
+        stats.mean_bounded_slowdown = self.records.iter().map(|r| r.bounded_slowdown(10.)).sum::<f64>() / count;
+        stats.makespan = self.records.iter().map(|r| r.finish_time).fold(0., f64::max);
+        let busy_time: f64 = self
+            .records
+            .iter()
+            .map(|r| r.job.nodes as f64 * (r.finish_time - r.start_time))
+            .sum();
+        let period = stats.makespan - self.first_submit_time.unwrap_or(0.);
+        if period > 0. {
+            stats.utilization = busy_time / (self.node_count() as f64 * period);
+        }
+        stats
+    }
+
+    fn on_job_submitted(&mut self, job: BatchJob) {
+        self.submitted += 1;
+        self.first_submit_time.get_or_insert(self.ctx.time());
+        if job.nodes == 0 || job.nodes > self.node_count() {
+            log_warn!(
+                self.ctx,
+                "Job {} requests {} nodes while the cluster has {}, rejecting",
+                job.id,
+                job.nodes,
+                self.node_count()
+            );
+            self.rejected += 1;
+            return;
+        }
+        log_debug!(self.ctx, "Job {} submitted to queue {}", job.id, job.queue);
+        let priority = self.priority(&job);
+        // keep the waiting jobs sorted by priority, the jobs with equal priority are sorted by submission time
+        let pos = self.waiting.partition_point(|other| self.priority(other) >= priority);
+        self.waiting.insert(pos, job);
+        self.schedule();
+    }
+
+    fn priority(&self, job: &BatchJob) -> i32 {
+        self.queue_priorities.get(&job.queue).copied().unwrap_or(0)
+    }
+
+    /// Starts the waiting jobs according to the queue order and backfilling policy.
+    fn schedule(&mut self) {
+        let mut started = Vec::new();
+        match self.backfilling {
+            BackfillingPolicy::None => {
+                let mut free = self.free_nodes();
+                for job in self.waiting.iter() {
+                    if job.nodes > free {
+                        break;
+                    }
+                    free -= job.nodes;
+                    started.push(job.id);
+                }
+            }
+            BackfillingPolicy::Easy => started = self.schedule_easy(),
+            BackfillingPolicy::Conservative => started = self.schedule_conservative(),
+        }
+        for job_id in started {
+            let pos = self.waiting.iter().position(|job| job.id == job_id).unwrap();
+            let job = self.waiting.remove(pos);
+            self.start_job(job);
+        }
+    }
+
+    fn schedule_easy(&self) -> Vec<u32> {
+        let now = self.ctx.time();
+        let mut started = Vec::new();
+        let mut free = self.free_nodes();
+        let mut ends: Vec<(f64, u32)> = self
+            .running
+            .values()
+            .map(|r| (r.expected_end().max(now), r.job.nodes))
+            .collect();
+        let mut jobs = self.waiting.iter();
+        // start jobs in order while they fit
+        let head = loop {
+            match jobs.next() {
+                Some(job) if job.nodes <= free => {
+                    free -= job.nodes;
+                    started.push(job.id);
+                    ends.push((now + job.walltime, job.nodes));
+                }
+                Some(job) => break job,
+                None => return started,
+            }
+        };
+        // reserve nodes for the first job which does not fit at the earliest time when enough nodes are released
+        ends.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let mut available = free;
+        let mut shadow_time = now;
+        for (end, nodes) in ends {
+            available += nodes;
+            if available >= head.nodes {
+                shadow_time = end;
+                break;
+            }
+        }
+        // nodes which are not needed by the reserved job at the shadow time
+        let mut extra = available - head.nodes;
+        for job in jobs {
+            if job.nodes > free {
+                continue;
+            }
+            if now + job.walltime <= shadow_time {
+                free -= job.nodes;
+                started.push(job.id);
+            } else if job.nodes <= extra {
+                free -= job.nodes;
+                extra -= job.nodes;
+                started.push(job.id);
+            }
+        }
+        started
+    }
+
+    fn schedule_conservative(&self) -> Vec<u32> {
+        let now = self.ctx.time();
+        let mut profile = AvailabilityProfile::new(now, self.free_nodes());
+        for running in self.running.values() {
+            profile.release(running.expected_end().max(now), running.job.nodes);
+        }
+        let mut started = Vec::new();
+        for job in self.waiting.iter() {
+            let start = profile.earliest_start(job.nodes, job.walltime);
+            profile.reserve(start, job.walltime, job.nodes);
+            if start <= now {
+                started.push(job.id);
+            }
+        }
+        started
+    }
+
+    fn start_job(&mut self, job: BatchJob) {
+        let hosts: Vec<u32> = self
+            .pool_state
+            .get_hosts()
+            .filter(|h| h.allocations.is_empty())
+            .take(job.nodes as usize)
+            .map(|h| h.id)
+            .collect();
+        for host_id in hosts.iter() {
+            let alloc = self.node_allocation(job.id, *host_id);
+            self.pool_state.allocate(&alloc, *host_id);
+        }
+        log_debug!(self.ctx, "Job {} started on hosts {:?}", job.id, hosts);
+        let duration = job.runtime.min(job.walltime);
+        self.ctx.emit_self(BatchJobCompleted { job_id: job.id }, duration);
+        if let Some(listener) = self.listener {
+            self.ctx.emit_now(
+                BatchJobStarted {
+                    job: job.clone(),
+                    hosts: hosts.clone(),
+                },
+                listener,
+            );
+        }
+        self.running.insert(
+            job.id,
+            RunningJob {
+                job,
+                start_time: self.ctx.time(),
+                hosts,
+            },
+        );
+    }
+
+    fn node_allocation(&self, job_id: u32, host_id: u32) -> Allocation {
+        Allocation {
+            id: job_id,
+            cpu_usage: self.pool_state.get_total_cpu(host_id),
+            memory_usage: self.pool_state.get_total_memory(host_id),
+        }
+    }
+
+    fn on_job_completed(&mut self, job_id: u32) {
+        let running = self.running.remove(&job_id).unwrap();
+        for host_id in running.hosts.iter() {
+            let alloc = self.node_allocation(job_id, *host_id);
+            self.pool_state.release(&alloc, *host_id);
+        }
+        let killed = running.job.runtime > running.job.walltime;
+        if killed {
+            log_warn!(self.ctx, "Job {} is killed after exceeding its walltime", job_id);
+        } else {
+            log_debug!(self.ctx, "Job {} completed", job_id);
+        }
+        let record = BatchJobRecord {
+            job: running.job,
+            start_time: running.start_time,
+            finish_time: self.ctx.time(),
+            hosts: running.hosts,
+            killed,
+        };
+        if let Some(listener) = self.listener {
+            self.ctx.emit_now(BatchJobFinished { record: record.clone() }, listener);
+        }
+        self.records.push(record);
+        self.schedule();
+    }
+}
+
+/// Step function of the number of free nodes over time used by conservative backfilling.
+struct AvailabilityProfile {
+    /// Points where the number of free nodes changes, sorted by time.
+    points: Vec<(f64, u32)>,
+}
+
+impl AvailabilityProfile {
+    fn new(time: f64, free: u32) -> Self {
+        Self {
+            points: vec![(time, free)],
+        }
+    }
+
+    /// Returns the index of point at the specified time, inserting it if needed.
+    fn split(&mut self, time: f64) -> usize {
+        let pos = self.points.partition_point(|p| p.0 < time);
+        if pos < self.points.len() && self.points[pos].0 == time {
+            return pos;
+        }
+        let free = self.points[pos - 1].1;
+        self.points.insert(pos, (time, free));
+        pos
+    }
+
+    fn release(&mut self, time: f64, nodes: u32) {
+        let pos = self.split(time);
+        for point in self.points[pos..].iter_mut() {
+            point.1 += nodes;
+        }
+    }
+
+    fn reserve(&mut self, start: f64, duration: f64, nodes: u32) {
+        let from = self.split(start);
+        let to = self.split(start + duration);
+        for point in self.points[from..to].iter_mut() {
+            point.1 -= nodes;
+        }
+    }
+
+    /// Returns the earliest time when the specified number of nodes is free during the specified duration.
+    fn earliest_start(&self, nodes: u32, duration: f64) -> f64 {
+        for (i, (start, _)) in self.points.iter().enumerate() {
+            let end = start + duration;
+            let fits = self.points[i..].iter().take_while(|p| p.0 < end).all(|p| p.1 >= nodes);
+            if fits {
+                return *start;
+            }
+        }
+        unreachable!("All nodes are free after the last point of availability profile")
+    }
+}
+
+impl CustomComponent for BatchScheduler {
+    fn new(ctx: SimulationContext) -> Self {
+        Self {
+            backfilling: BackfillingPolicy::None,
+            pool_state: ResourcePoolState::new(),
+            queue_priorities: HashMap::new(),
+            waiting: Vec::new(),
+            running: BTreeMap::new(),
+            records: Vec::new(),
+            submitted: 0,
+            rejected: 0,
+            first_submit_time: None,
+            listener: None,
+            ctx,
+        }
+    }
+
+    fn init(&mut self) {}
+}
+
+impl EventHandler for BatchScheduler {
+    fn on(&mut self, event: Event) {
+        cast!(match event.data {
+            BatchJobSubmitted { job } => {
+                self.on_job_submitted(job);
+            }
+            BatchJobCompleted { job_id } => {
+                self.on_job_completed(job_id);
+            }
+        })
+    }
+}
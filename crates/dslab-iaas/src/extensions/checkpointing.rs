@@ -0,0 +1,411 @@
+//! Checkpoint/restart model for long-running jobs.
+//!
+//! The job (e.g. an application running inside a VM) performs the specified amount of work and periodically saves
+//! its state to a storage. Each checkpoint takes time proportional to the state size and the storage write
+//! bandwidth, during which the job does not progress. The job component can be used as a target of
+//! [`FaultInjector`](dslab_core::faults::FaultInjector): on [`FaultKind::Crash`] the job loses the work done since
+//! the last completed checkpoint, and after the restart it reads the last checkpoint back from the storage and
+//! continues from it instead of starting from scratch. The job restarts after the fault recovery or, if the fault
+//! is permanent, right away (e.g. on a spare VM), in both cases after the configured restart delay.
+
+use serde::Serialize;
+
+use dslab_core::cast;
+use dslab_core::context::SimulationContext;
+use dslab_core::event::{Event, EventId};
+use dslab_core::faults::{FaultInjected, FaultKind, FaultRecovered};
+use dslab_core::handler::EventHandler;
+use dslab_core::{log_debug, log_info, Id};
+
+use crate::custom_component::CustomComponent;
+
+/// Returns the checkpoint interval minimizing the expected overhead according to Young's first-order approximation,
+/// given the checkpoint cost and the mean time between failures.
+pub fn young_interval(checkpoint_cost: f64, mtbf: f64) -> f64 {
+    (2. * checkpoint_cost * mtbf).sqrt()
+}
+
+/// Returns the checkpoint interval minimizing the expected overhead according to Daly's higher-order approximation,
+/// given the checkpoint cost and the mean time between failures.
+pub fn daly_interval(checkpoint_cost: f64, mtbf: f64) -> f64 {
+    if checkpoint_cost >= 2. * mtbf {
+        return mtbf;
+    }
+    let ratio = checkpoint_cost / (2. * mtbf);
+    young_interval(checkpoint_cost, mtbf) * (1. + ratio.sqrt() / 3. + ratio / 9.) - checkpoint_cost
+}
+
+/// Parameters of checkpointed job.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct CheckpointConfig {
+    /// Amount of work, i.e. the job running time without checkpoints and failures.
+    pub work: f64,
+    /// Amount of work between consecutive checkpoints, `None` disables checkpointing.
+    pub interval: Option<f64>,
+    /// Size of saved job state.
+    pub state_size: u64,
+    /// Storage bandwidth used to write the checkpoint.
+    pub write_bandwidth: f64,
+    /// Storage bandwidth used to read the checkpoint on restart.
+    pub read_bandwidth: f64,
+    /// Fixed latency added to each checkpoint write and read (e.g. to quiesce the application).
+    pub io_latency: f64,
+    /// Delay between the failure recovery and the job restart.
+    pub restart_delay: f64,
+}
+
+impl CheckpointConfig {
+    /// Creates config of job without checkpointing.
+    pub fn new(work: f64) -> Self {
+        Self {
+            work,
+            interval: None,
+            state_size: 0,
+            write_bandwidth: f64::INFINITY,
+            read_bandwidth: f64::INFINITY,
+            io_latency: 0.,
+            restart_delay: 0.,
+        }
+    }
+
+    /// Enables checkpointing with the specified interval.
+    pub fn with_interval(mut self, interval: f64) -> Self {
+        assert!(interval > 0., "checkpoint interval should be positive");
+        self.interval = Some(interval);
+        self
+    }
+
+    /// Sets the size of job state and the storage bandwidth.
+    pub fn with_state(mut self, state_size: u64, write_bandwidth: f64, read_bandwidth: f64) -> Self {
+        self.state_size = state_size;
+        self.write_bandwidth = write_bandwidth;
+        self.read_bandwidth = read_bandwidth;
+        self
+    }
+
+    /// Sets the fixed latency of checkpoint write and read.
+    pub fn with_io_latency(mut self, io_latency: f64) -> Self {
+        self.io_latency = io_latency;
+        self
+    }
+
+    /// Sets the restart delay.
+    pub fn with_restart_delay(mut self, restart_delay: f64) -> Self {
+        self.restart_delay = restart_delay;
+        self
+    }
+
+    /// Returns the time needed to write the checkpoint.
+    pub fn checkpoint_cost(&self) -> f64 {
+        self.io_latency + self.state_size as f64 / self.write_bandwidth
+    }
+
+    /// Returns the time needed to read the checkpoint.
+    pub fn restore_cost(&self) -> f64 {
+        self.io_latency + self.state_size as f64 / self.read_bandwidth
+    }
+}
+
+/// Current activity of checkpointed job.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub enum JobPhase {
+    /// Job is not started yet.
+    Idle,
+    /// Job performs the work.
+    Computing,
+    /// Job writes the checkpoint.
+    Checkpointing,
+    /// Job reads the last checkpoint after the restart.
+    Restoring,
+    /// Job is crashed and waits for the restart.
+    Down,
+    /// Job has finished all work.
+    Completed,
+}
+
+/// Statistics of checkpointed job.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct CheckpointStats {
+    /// Number of completed checkpoints.
+    pub checkpoints: u64,
+    /// Number of checkpoints interrupted by failures.
+    pub aborted_checkpoints: u64,
+    /// Number of failures.
+    pub failures: u64,
+    /// Number of restarts from checkpoint.
+    pub restores: u64,
+    /// Time spent writing checkpoints, including the aborted ones.
+    pub checkpoint_time: f64,
+    /// Time spent reading checkpoints.
+    pub restore_time: f64,
+    /// Time between failures and the restarts.
+    pub down_time: f64,
+    /// Work lost due to failures, i.e. to be re-executed.
+    pub lost_work: f64,
+    /// Amount of data written to the storage.
+    pub bytes_written: u64,
+    /// Amount of data read from the storage.
+    pub bytes_read: u64,
+    /// Time from the job start to its completion.
+    pub makespan: Option<f64>,
+}
+
+impl CheckpointStats {
+    /// Returns the fraction of makespan spent on useful work, or `None` if the job is not completed.
+    pub fn efficiency(&self, work: f64) -> Option<f64> {
+        self.makespan
+            .map(|makespan| if makespan > 0. { work / makespan } else { 1. })
+    }
+}
+
+/// Event sent to the listener when the job is completed.
+#[derive(Clone, Serialize)]
+pub struct JobCompleted {
+    pub job_id: Id,
+    pub makespan: f64,
+}
+
+#[derive(Clone, Serialize)]
+struct StartJob {}
+
+#[derive(Clone, Serialize)]
+struct SegmentCompleted {
+    work: f64,
+}
+
+#[derive(Clone, Serialize)]
+struct CheckpointCompleted {}
+
+#[derive(Clone, Serialize)]
+struct RestoreCompleted {}
+
+#[derive(Clone, Serialize)]
+struct RestartJob {}
+
+/// Component modeling long-running job with periodic checkpoints.
+pub struct CheckpointedJob {
+    config: Option<CheckpointConfig>,
+    phase: JobPhase,
+    /// Work done since the job start or the last restart, including the checkpointed work.
+    work_done: f64,
+    /// Work saved in the last completed checkpoint.
+    checkpointed_work: f64,
+    start_time: Option<f64>,
+    phase_start: f64,
+    pending_event: Option<EventId>,
+    listener: Option<Id>,
+    stats: CheckpointStats,
+    ctx: SimulationContext,
+}
+
+impl CheckpointedJob {
+    /// Used to provide the job config.
+    ///
+    /// This method should be invoked before [`init()`](CheckpointedJob::init()).
+    pub fn patch_custom_args(&mut self, config: CheckpointConfig) {
+        self.config = Some(config);
+    }
+
+    /// Sets the component which is notified about the job completion via [`JobCompleted`] event.
+    pub fn set_listener(&mut self, id: Id) {
+        self.listener = Some(id);
+    }
+
+    /// Starts the job after the specified delay.
+    pub fn start(&mut self, delay: f64) {
+        self.ctx.emit_self(StartJob {}, delay);
+    }
+
+    /// Returns the job config.
+    pub fn config(&self) -> &CheckpointConfig {
+        self.config.as_ref().unwrap()
+    }
+
+    /// Returns the current phase of the job.
+    pub fn phase(&self) -> JobPhase {
+        self.phase
+    }
+
+    /// Returns the work saved in the last completed checkpoint.
+    pub fn checkpointed_work(&self) -> f64 {
+        self.checkpointed_work
+    }
+
+    /// Returns the job statistics.
+    pub fn stats(&self) -> &CheckpointStats {
+        &self.stats
+    }
+
+    fn set_phase(&mut self, phase: JobPhase) {
+        log_debug!(self.ctx, "job phase: {:?} -> {:?}", self.phase, phase);
+        self.phase = phase;
+        self.phase_start = self.ctx.time();
+    }
+
+    fn on_start(&mut self) {
+        if self.phase != JobPhase::Idle {
+            return;
+        }
+        log_info!(self.ctx, "job started");
+        self.start_time = Some(self.ctx.time());
+        self.compute();
+    }
+
+    fn compute(&mut self) {
+        let config = self.config.as_ref().unwrap();
+        let remaining = (config.work - self.work_done).max(0.);
+        let work = config.interval.map_or(remaining, |interval| interval.min(remaining));
+        self.set_phase(JobPhase::Computing);
+        self.pending_event = Some(self.ctx.emit_self(SegmentCompleted { work }, work));
+    }
+
+    fn on_segment_completed(&mut self, work: f64) {
+        self.pending_event = None;
+        self.work_done += work;
+        let config = self.config.as_ref().unwrap();
+        if self.work_done >= config.work - 1e-9 {
+            let makespan = self.ctx.time() - self.start_time.unwrap();
+            log_info!(self.ctx, "job completed, makespan: {:.3}", makespan);
+            self.stats.makespan = Some(makespan);
+            self.set_phase(JobPhase::Completed);
+            if let Some(listener) = self.listener {
+                self.ctx.emit_now(
+                    JobCompleted {
+                        job_id: self.ctx.id(),
+                        makespan,
+                    },
+                    listener,
+                );
+            }
+            return;
+        }
+        let cost = config.checkpoint_cost();
+        self.set_phase(JobPhase::Checkpointing);
+        self.pending_event = Some(self.ctx.emit_self(CheckpointCompleted {}, cost));
+    }
+
+    fn on_checkpoint_completed(&mut self) {
+        self.pending_event = None;
+        let config = self.config.as_ref().unwrap();
+        self.stats.checkpoints += 1;
+        self.stats.checkpoint_time += self.ctx.time() - self.phase_start;
+        self.stats.bytes_written += config.state_size;
+        self.checkpointed_work = self.work_done;
+        log_debug!(self.ctx, "checkpoint saved at work {:.3}", self.checkpointed_work);
+        self.compute();
+    }
+
+    fn on_crash(&mut self, duration: Option<f64>) {
+        if matches!(self.phase, JobPhase::Idle | JobPhase::Down | JobPhase::Completed) {
+            return;
+        }
+        if let Some(event_id) = self.pending_event.take() {
+            self.ctx.cancel_event(event_id);
+        }
+        let elapsed = self.ctx.time() - self.phase_start;
+        match self.phase {
+            JobPhase::Computing => {
+                self.stats.lost_work += self.work_done - self.checkpointed_work + elapsed;
+            }
+            JobPhase::Checkpointing => {
+                self.stats.lost_work += self.work_done - self.checkpointed_work;
+                self.stats.checkpoint_time += elapsed;
+                self.stats.aborted_checkpoints += 1;
+            }
+            JobPhase::Restoring => {
+                self.stats.restore_time += elapsed;
+            }
+            _ => {}
+        }
+        self.stats.failures += 1;
+        self.work_done = self.checkpointed_work;
+        log_info!(
+            self.ctx,
+            "job crashed, restarting from work {:.3}",
+            self.checkpointed_work
+        );
+        self.set_phase(JobPhase::Down);
+        // permanent fault: the job is restarted elsewhere without waiting for recovery
+        if duration.is_none() {
+            self.schedule_restart();
+        }
+    }
+
+    fn schedule_restart(&mut self) {
+        let delay = self.config.as_ref().unwrap().restart_delay;
+        self.pending_event = Some(self.ctx.emit_self(RestartJob {}, delay));
+    }
+
+    fn on_restart(&mut self) {
+        self.pending_event = None;
+        self.stats.down_time += self.ctx.time() - self.phase_start;
+        if self.checkpointed_work > 0. {
+            let cost = self.config.as_ref().unwrap().restore_cost();
+            self.set_phase(JobPhase::Restoring);
+            self.pending_event = Some(self.ctx.emit_self(RestoreCompleted {}, cost));
+        } else {
+            self.compute();
+        }
+    }
+
+    fn on_restore_completed(&mut self) {
+        self.pending_event = None;
+        self.stats.restores += 1;
+        self.stats.restore_time += self.ctx.time() - self.phase_start;
+        self.stats.bytes_read += self.config.as_ref().unwrap().state_size;
+        self.compute();
+    }
+}
+
+impl CustomComponent for CheckpointedJob {
+    fn new(ctx: SimulationContext) -> Self {
+        Self {
+            config: None,
+            phase: JobPhase::Idle,
+            work_done: 0.,
+            checkpointed_work: 0.,
+            start_time: None,
+            phase_start: 0.,
+            pending_event: None,
+            listener: None,
+            stats: CheckpointStats::default(),
+            ctx,
+        }
+    }
+
+    fn init(&mut self) {
+        assert!(self.config.is_some(), "patch_custom_args should be invoked before init");
+    }
+}
+
+impl EventHandler for CheckpointedJob {
+    fn on(&mut self, event: Event) {
+        cast!(match event.data {
+            StartJob {} => {
+                self.on_start();
+            }
+            SegmentCompleted { work } => {
+                self.on_segment_completed(work);
+            }
+            CheckpointCompleted {} => {
+                self.on_checkpoint_completed();
+            }
+            RestoreCompleted {} => {
+                self.on_restore_completed();
+            }
+            RestartJob {} => {
+                self.on_restart();
+            }
+            FaultInjected { kind, duration, .. } => {
+                if kind == FaultKind::Crash {
+                    self.on_crash(duration);
+                }
+            }
+            FaultRecovered { kind, .. } => {
+                if kind == FaultKind::Crash && self.phase == JobPhase::Down && self.pending_event.is_none() {
+                    self.schedule_restart();
+                }
+            }
+        })
+    }
+}
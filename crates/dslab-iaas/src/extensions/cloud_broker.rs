@@ -0,0 +1,409 @@
+//! Broker distributing VM requests across federated clouds.
+//!
+//! Each cloud provider is modeled as a separate resource pool (a set of hosts) managed by its own scheduler
+//! (see [`Scheduler::set_allowed_hosts`](crate::core::scheduler::Scheduler::set_allowed_hosts)), and is characterized
+//! by the prices of resources and the network latency between the broker clients and the provider. The broker
+//! receives VM requests, selects the provider for each request using a pluggable [`BrokerPolicy`] and submits
+//! the request to the provider scheduler after the provider latency. The requests which do not fit into any provider
+//! (according to the broker accounting of VMs submitted to providers) are rejected.
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::rc::Rc;
+
+use serde::Serialize;
+
+use dslab_core::cast;
+use dslab_core::context::SimulationContext;
+use dslab_core::event::Event;
+use dslab_core::handler::EventHandler;
+use dslab_core::{log_debug, log_warn};
+
+use crate::core::config::sim_config::SimulationConfig;
+use crate::core::events::allocation::AllocationRequest;
+use crate::core::host_manager::HostManager;
+use crate::core::vm::{ResourceConsumer, VirtualMachine, VmStatus};
+use crate::core::vm_api::VmAPI;
+use crate::custom_component::CustomComponent;
+
+/// Cloud provider participating in federation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CloudProvider {
+    pub name: String,
+    /// Scheduler managing the provider hosts.
+    pub scheduler_id: u32,
+    /// Price per CPU per second.
+    pub cpu_price: f64,
+    /// Price per memory unit per second.
+    pub memory_price: f64,
+    /// Network latency between the broker clients and the provider.
+    pub latency: f64,
+}
+
+impl CloudProvider {
+    /// Creates provider with the specified scheduler, zero prices and latency.
+    pub fn new(name: &str, scheduler_id: u32) -> Self {
+        Self {
+            name: name.to_string(),
+            scheduler_id,
+            cpu_price: 0.,
+            memory_price: 0.,
+            latency: 0.,
+        }
+    }
+
+    /// Sets the prices of resources.
+    pub fn with_prices(mut self, cpu_price: f64, memory_price: f64) -> Self {
+        self.cpu_price = cpu_price;
+        self.memory_price = memory_price;
+        self
+    }
+
+    /// Sets the network latency.
+    pub fn with_latency(mut self, latency: f64) -> Self {
+        self.latency = latency;
+        self
+    }
+}
+
+/// VM request submitted to broker.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct BrokerRequest {
+    pub id: u64,
+    pub cpu: u32,
+    pub memory: u64,
+    pub lifetime: f64,
+}
+
+/// Provider state visible to broker policy.
+#[derive(Clone, Debug)]
+pub struct ProviderInfo {
+    pub index: usize,
+    pub name: String,
+    pub cpu_price: f64,
+    pub memory_price: f64,
+    pub latency: f64,
+    pub cpu_total: u64,
+    pub memory_total: u64,
+    /// CPU used by active VMs submitted to provider.
+    pub cpu_used: u64,
+    /// Memory used by active VMs submitted to provider.
+    pub memory_used: u64,
+}
+
+impl ProviderInfo {
+    /// Returns the cost of running the request in this provider.
+    pub fn cost(&self, request: &BrokerRequest) -> f64 {
+        (self.cpu_price * request.cpu as f64 + self.memory_price * request.memory as f64) * request.lifetime
+    }
+
+    /// Returns the fraction of provider CPU used by active VMs.
+    pub fn cpu_load(&self) -> f64 {
+        self.cpu_used as f64 / self.cpu_total.max(1) as f64
+    }
+
+    /// Returns whether the provider has enough free resources for the request.
+    pub fn fits(&self, request: &BrokerRequest) -> bool {
+        self.cpu_used + request.cpu as u64 <= self.cpu_total && self.memory_used + request.memory <= self.memory_total
+    }
+}
+
+/// Policy selecting the provider for VM request.
+pub trait BrokerPolicy {
+    /// Returns the policy name.
+    fn name(&self) -> &str;
+
+    /// Returns the index of selected provider among the providers which have enough free resources (non-empty).
+    fn select(&self, request: &BrokerRequest, providers: &[ProviderInfo]) -> usize;
+}
+
+fn select_min<F: Fn(&ProviderInfo) -> (f64, f64)>(providers: &[ProviderInfo], key: F) -> usize {
+    let mut best = 0;
+    for (i, provider) in providers.iter().enumerate().skip(1) {
+        if key(provider) < key(&providers[best]) {
+            best = i;
+        }
+    }
+    best
+}
+
+/// Selects the provider with the lowest cost of request, the ties are broken by latency.
+pub struct CheapestProvider;
+
+impl BrokerPolicy for CheapestProvider {
+    fn name(&self) -> &str {
+        "Cheapest"
+    }
+
+    fn select(&self, request: &BrokerRequest, providers: &[ProviderInfo]) -> usize {
+        select_min(providers, |p| (p.cost(request), p.latency))
+    }
+}
+
+/// Selects the provider with the lowest latency, the ties are broken by cost.
+pub struct NearestProvider;
+
+impl BrokerPolicy for NearestProvider {
+    fn name(&self) -> &str {
+        "Nearest"
+    }
+
+    fn select(&self, request: &BrokerRequest, providers: &[ProviderInfo]) -> usize {
+        select_min(providers, |p| (p.latency, p.cost(request)))
+    }
+}
+
+/// Selects the provider with the lowest CPU load, the ties are broken by cost.
+pub struct LeastLoadedProvider;
+
+impl BrokerPolicy for LeastLoadedProvider {
+    fn name(&self) -> &str {
+        "LeastLoaded"
+    }
+
+    fn select(&self, request: &BrokerRequest, providers: &[ProviderInfo]) -> usize {
+        select_min(providers, |p| (p.cpu_load(), p.cost(request)))
+    }
+}
+
+/// Statistics of provider.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct ProviderStats {
+    /// Number of requests submitted to provider.
+    pub requests: u64,
+    /// Number of VMs which failed to allocate.
+    pub failed: u64,
+    /// Number of currently active VMs.
+    pub active: u64,
+    /// Total cost of requests submitted to provider.
+    pub cost: f64,
+    /// Total CPU time requested from provider.
+    pub cpu_time: f64,
+    /// Average network latency experienced by requests.
+    pub mean_latency: f64,
+}
+
+/// Summary statistics of broker.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct BrokerStats {
+    pub submitted: u64,
+    pub rejected: u64,
+    pub total_cost: f64,
+    /// Statistics for each provider.
+    pub providers: BTreeMap<String, ProviderStats>,
+}
+
+#[derive(Clone, Serialize)]
+pub struct BrokerRequestSubmitted {
+    pub request: BrokerRequest,
+}
+
+struct ProviderState {
+    provider: CloudProvider,
+    cpu_total: u64,
+    memory_total: u64,
+    vms: Vec<(u32, BrokerRequest)>,
+    stats: ProviderStats,
+}
+
+/// Component implementing multi-cloud broker.
+pub struct CloudBroker {
+    policy: Option<Box<dyn BrokerPolicy>>,
+    vm_api: Option<Rc<RefCell<VmAPI>>>,
+    sim_config: Option<Rc<SimulationConfig>>,
+    providers: Vec<ProviderState>,
+    submitted: u64,
+    rejected: u64,
+    next_request_id: u64,
+    ctx: SimulationContext,
+}
+
+impl CloudBroker {
+    /// Used to provide the broker policy and the references to standard components needed for broker work.
+    ///
+    /// This method should be invoked before [`init()`](CloudBroker::init()).
+    pub fn patch_custom_args(
+        &mut self,
+        policy: Box<dyn BrokerPolicy>,
+        vm_api: Rc<RefCell<VmAPI>>,
+        sim_config: Rc<SimulationConfig>,
+    ) {
+        self.policy = Some(policy);
+        self.vm_api = Some(vm_api);
+        self.sim_config = Some(sim_config);
+    }
+
+    /// Adds provider without hosts and returns its index.
+    pub fn add_provider(&mut self, provider: CloudProvider) -> usize {
+        self.providers.push(ProviderState {
+            provider,
+            cpu_total: 0,
+            memory_total: 0,
+            vms: Vec::new(),
+            stats: ProviderStats::default(),
+        });
+        self.providers.len() - 1
+    }
+
+    /// Adds host to the resource pool of provider.
+    pub fn add_provider_host(&mut self, index: usize, host: &HostManager) {
+        let state = &mut self.providers[index];
+        state.cpu_total += host.cpu_total() as u64;
+        state.memory_total += host.memory_total();
+    }
+
+    /// Submits VM request which arrives to broker after the specified delay. Returns the request id.
+    pub fn submit_request(&mut self, cpu: u32, memory: u64, lifetime: f64, delay: f64) -> u64 {
+        let id = self.next_request_id;
+        self.next_request_id += 1;
+        let request = BrokerRequest {
+            id,
+            cpu,
+            memory,
+            lifetime,
+        };
+        self.ctx.emit_self(BrokerRequestSubmitted { request }, delay);
+        id
+    }
+
+    /// Returns the current state of providers.
+    pub fn provider_infos(&self) -> Vec<ProviderInfo> {
+        let vm_api = self.vm_api.as_ref().unwrap().borrow();
+        self.providers
+            .iter()
+            .enumerate()
+            .map(|(index, state)| {
+                let active = state.vms.iter().filter(|(vm_id, _)| is_active(&vm_api, *vm_id));
+                let (cpu_used, memory_used) =
+                    active.fold((0, 0), |(cpu, mem), (_, r)| (cpu + r.cpu as u64, mem + r.memory));
+                ProviderInfo {
+                    index,
+                    name: state.provider.name.clone(),
+                    cpu_price: state.provider.cpu_price,
+                    memory_price: state.provider.memory_price,
+                    latency: state.provider.latency,
+                    cpu_total: state.cpu_total,
+                    memory_total: state.memory_total,
+                    cpu_used,
+                    memory_used,
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the ids of VMs submitted to the provider.
+    pub fn provider_vms(&self, index: usize) -> Vec<u32> {
+        self.providers[index].vms.iter().map(|(vm_id, _)| *vm_id).collect()
+    }
+
+    /// Returns the summary statistics.
+    pub fn stats(&self) -> BrokerStats {
+        let vm_api = self.vm_api.as_ref().unwrap().borrow();
+        let mut stats = BrokerStats {
+            submitted: self.submitted,
+            rejected: self.rejected,
+            ..Default::default()
+        };
+        for state in self.providers.iter() {
+            let mut provider_stats = state.stats.clone();
+            for (vm_id, _) in state.vms.iter() {
+                match vm_api.get_vm_status(*vm_id) {
+                    VmStatus::FailedToAllocate => provider_stats.failed += 1,
+                    VmStatus::Initializing | VmStatus::Running | VmStatus::Migrating => provider_stats.active += 1,
+                    _ => {}
+                }
+            }
+            stats.total_cost += provider_stats.cost;
+            stats.providers.insert(state.provider.name.clone(), provider_stats);
+        }
+        stats
+    }
+
+    fn on_request_submitted(&mut self, request: BrokerRequest) {
+        self.submitted += 1;
+        let candidates = self
+            .provider_infos()
+            .into_iter()
+            .filter(|p| p.fits(&request))
+            .collect::<Vec<_>>();
+        if candidates.is_empty() {
+            log_warn!(self.ctx, "request {} is rejected: no provider can host it", request.id);
+            self.rejected += 1;
+            return;
+        }
+        let selected = &candidates[self.policy.as_ref().unwrap().select(&request, &candidates)];
+        let cost = selected.cost(&request);
+        let index = selected.index;
+
+        let vm_api = self.vm_api.clone().unwrap();
+        let vm_id = vm_api.borrow_mut().generate_vm_id();
+        let latency = self.providers[index].provider.latency;
+        let vm = VirtualMachine::new(
+            vm_id,
+            self.ctx.time() + latency,
+            request.lifetime,
+            ResourceConsumer::with_full_load(request.cpu, request.memory),
+            self.sim_config.clone().unwrap(),
+        );
+        vm_api.borrow_mut().register_new_vm(vm);
+
+        let state = &mut self.providers[index];
+        log_debug!(
+            self.ctx,
+            "request {} is sent to provider {} as vm {}",
+            request.id,
+            state.provider.name,
+            vm_id
+        );
+        self.ctx.emit(
+            AllocationRequest { vm_ids: vec![vm_id] },
+            state.provider.scheduler_id,
+            latency,
+        );
+        let stats = &mut state.stats;
+        stats.mean_latency = (stats.mean_latency * stats.requests as f64 + latency) / (stats.requests + 1) as f64;
+        stats.requests += 1;
+        stats.cost += cost;
+        stats.cpu_time += request.cpu as f64 * request.lifetime;
+        state.vms.push((vm_id, request));
+    }
+}
+
+fn is_active(vm_api: &VmAPI, vm_id: u32) -> bool {
+    matches!(
+        vm_api.get_vm_status(vm_id),
+        VmStatus::Initializing | VmStatus::Running | VmStatus::Migrating
+    )
+}
+
+impl CustomComponent for CloudBroker {
+    fn new(ctx: SimulationContext) -> Self {
+        Self {
+            policy: None,
+            vm_api: None,
+            sim_config: None,
+            providers: Vec::new(),
+            submitted: 0,
+            rejected: 0,
+            next_request_id: 0,
+            ctx,
+        }
+    }
+
+    fn init(&mut self) {
+        assert!(
+            self.policy.is_some() && self.vm_api.is_some(),
+            "patch_custom_args should be invoked before init"
+        );
+    }
+}
+
+impl EventHandler for CloudBroker {
+    fn on(&mut self, event: Event) {
+        cast!(match event.data {
+            BrokerRequestSubmitted { request } => {
+                self.on_request_submitted(request);
+            }
+        })
+    }
+}
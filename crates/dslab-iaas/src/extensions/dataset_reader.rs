@@ -13,6 +13,8 @@ pub struct VMRequest {
     pub cpu_load_model: Box<dyn LoadModel>,
     pub memory_load_model: Box<dyn LoadModel>,
     pub scheduler_name: Option<String>,
+    pub tenant: Option<String>,
+    pub priority: u32,
 }
 
 pub trait DatasetReader {
@@ -0,0 +1,720 @@
+//! Edge computing layer: edge sites, mobile clients and offloading of client requests.
+//!
+//! The edge and cloud sites are the hosts of cloud simulation bound to the nodes of WAN topology (see
+//! [`CloudSimulation::create_network`](crate::simulation::CloudSimulation::create_network)). Edge sites are typically
+//! resource-constrained hosts located close to the clients, while cloud sites have more resources and are located
+//! further. Mobile clients are attached to the network via access points, move between them and generate requests,
+//! which are sent to the [`EdgeOrchestrator`]. The orchestrator selects the execution site for each request using
+//! the configured [`OffloadingPolicy`], transfers the request input to the site, executes it on the site cores and
+//! transfers the output back to the client at its current location.
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::rc::Rc;
+
+use serde::Serialize;
+
+use dslab_core::cast;
+use dslab_core::component::Id;
+use dslab_core::context::SimulationContext;
+use dslab_core::event::Event;
+use dslab_core::handler::EventHandler;
+use dslab_core::{log_debug, log_warn};
+use dslab_models::queueing::TimeDistribution;
+use dslab_network::{DataTransferCompleted, Network};
+
+use crate::core::host_manager::HostManager;
+use crate::custom_component::CustomComponent;
+
+/// Kind of execution site.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub enum SiteKind {
+    Edge,
+    Cloud,
+}
+
+/// Request for execution of some computation submitted by client.
+#[derive(Clone, Debug, Serialize)]
+pub struct EdgeRequest {
+    pub id: u64,
+    pub client: Id,
+    /// Number of cores used by the request.
+    pub cores: u32,
+    /// Execution time of the request on a site with unit speed.
+    pub work: f64,
+    /// Size of request input transferred from client to site.
+    pub input_size: f64,
+    /// Size of request output transferred from site to client.
+    pub output_size: f64,
+    /// Maximum allowed latency of the request, if any.
+    pub deadline: Option<f64>,
+}
+
+/// State of execution site passed to the offloading policy.
+#[derive(Clone, Debug)]
+pub struct SiteInfo {
+    pub host_id: u32,
+    pub kind: SiteKind,
+    pub cores: u32,
+    pub free_cores: u32,
+    /// Relative execution speed of the site.
+    pub speed: f64,
+    /// Number of requests waiting for execution at the site.
+    pub queue_length: usize,
+    /// Remaining work of requests running or waiting at the site.
+    pub pending_work: f64,
+    /// Network latency between the client and the site.
+    pub latency: f64,
+    /// Network bandwidth between the client and the site.
+    pub bandwidth: f64,
+}
+
+impl SiteInfo {
+    /// Returns whether the site has enough cores to execute the request.
+    pub fn fits(&self, request: &EdgeRequest) -> bool {
+        request.cores <= self.cores
+    }
+
+    /// Returns the estimated latency of request execution at the site,
+    /// including the data transfers, waiting in the queue and execution.
+    pub fn estimated_latency(&self, request: &EdgeRequest) -> f64 {
+        let transfer_time =
+            2. * self.latency + (request.input_size + request.output_size) / self.bandwidth.max(f64::EPSILON);
+        let wait_time = if self.queue_length == 0 && request.cores <= self.free_cores {
+            0.
+        } else {
+            self.pending_work / (self.cores as f64 * self.speed)
+        };
+        transfer_time + wait_time + request.work / self.speed
+    }
+}
+
+/// Selects the site for execution of client request.
+pub trait OffloadingPolicy {
+    /// Returns the policy name.
+    fn name(&self) -> String;
+
+    /// Returns the index of selected site or `None` if the request should be rejected.
+    fn select(&mut self, request: &EdgeRequest, sites: &[SiteInfo]) -> Option<usize>;
+}
+
+fn closest_site<F>(request: &EdgeRequest, sites: &[SiteInfo], filter: F) -> Option<usize>
+where
+    F: Fn(&SiteInfo) -> bool,
+{
+    sites
+        .iter()
+        .enumerate()
+        .filter(|(_, site)| site.fits(request) && filter(site))
+        .min_by(|(_, a), (_, b)| a.latency.total_cmp(&b.latency))
+        .map(|(i, _)| i)
+}
+
+/// Executes all requests at the closest cloud site.
+#[derive(Default)]
+pub struct CloudOnly {}
+
+impl OffloadingPolicy for CloudOnly {
+    fn name(&self) -> String {
+        "CloudOnly".to_string()
+    }
+
+    fn select(&mut self, request: &EdgeRequest, sites: &[SiteInfo]) -> Option<usize> {
+        closest_site(request, sites, |site| site.kind == SiteKind::Cloud)
+    }
+}
+
+/// Executes all requests at the closest edge site, regardless of its load.
+#[derive(Default)]
+pub struct EdgeOnly {}
+
+impl OffloadingPolicy for EdgeOnly {
+    fn name(&self) -> String {
+        "EdgeOnly".to_string()
+    }
+
+    fn select(&mut self, request: &EdgeRequest, sites: &[SiteInfo]) -> Option<usize> {
+        closest_site(request, sites, |site| site.kind == SiteKind::Edge)
+    }
+}
+
+/// Executes request at the closest edge site with enough free cores,
+/// or at the closest cloud site if all edge sites are busy.
+#[derive(Default)]
+pub struct EdgeFirst {}
+
+impl OffloadingPolicy for EdgeFirst {
+    fn name(&self) -> String {
+        "EdgeFirst".to_string()
+    }
+
+    fn select(&mut self, request: &EdgeRequest, sites: &[SiteInfo]) -> Option<usize> {
+        closest_site(request, sites, |site| {
+            site.kind == SiteKind::Edge && site.queue_length == 0 && site.free_cores >= request.cores
+        })
+        .or_else(|| closest_site(request, sites, |site| site.kind == SiteKind::Cloud))
+    }
+}
+
+/// Executes request at the site with minimum estimated latency (see [`SiteInfo::estimated_latency`]).
+#[derive(Default)]
+pub struct MinEstimatedLatency {}
+
+impl OffloadingPolicy for MinEstimatedLatency {
+    fn name(&self) -> String {
+        "MinEstimatedLatency".to_string()
+    }
+
+    fn select(&mut self, request: &EdgeRequest, sites: &[SiteInfo]) -> Option<usize> {
+        sites
+            .iter()
+            .enumerate()
+            .filter(|(_, site)| site.fits(request))
+            .min_by(|(_, a), (_, b)| a.estimated_latency(request).total_cmp(&b.estimated_latency(request)))
+            .map(|(i, _)| i)
+    }
+}
+
+// Events //////////////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Submission of request by client to orchestrator.
+#[derive(Clone, Serialize)]
+pub struct EdgeRequestSubmitted {
+    pub request: EdgeRequest,
+}
+
+/// Completion of request, sent by orchestrator to client.
+#[derive(Clone, Serialize)]
+pub struct EdgeRequestCompleted {
+    pub request: EdgeRequest,
+    pub host_id: u32,
+    pub site_kind: SiteKind,
+    pub submit_time: f64,
+    pub finish_time: f64,
+}
+
+impl EdgeRequestCompleted {
+    /// Returns the request latency.
+    pub fn latency(&self) -> f64 {
+        self.finish_time - self.submit_time
+    }
+}
+
+/// Rejection of request by offloading policy, sent by orchestrator to client.
+#[derive(Clone, Serialize)]
+pub struct EdgeRequestRejected {
+    pub request: EdgeRequest,
+}
+
+#[derive(Clone, Serialize)]
+pub struct EdgeExecutionCompleted {
+    pub request_id: u64,
+}
+
+#[derive(Clone, Serialize)]
+pub struct GenerateEdgeRequest {}
+
+#[derive(Clone, Serialize)]
+pub struct MoveClient {}
+
+// Orchestrator ////////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Statistics of requests processed by orchestrator.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EdgeStats {
+    pub submitted: u64,
+    pub completed: u64,
+    pub rejected: u64,
+    /// Number of requests completed at edge sites.
+    pub edge_executions: u64,
+    /// Number of requests completed at cloud sites.
+    pub cloud_executions: u64,
+    pub mean_latency: f64,
+    pub p95_latency: f64,
+    pub max_latency: f64,
+    /// Number of completed requests which exceeded their deadlines.
+    pub deadline_misses: u64,
+}
+
+struct Site {
+    kind: SiteKind,
+    cores: u32,
+    free_cores: u32,
+    speed: f64,
+    /// Requests assigned to the site whose input is being transferred.
+    incoming: Vec<u64>,
+    queue: VecDeque<u64>,
+    running: BTreeMap<u64, f64>,
+}
+
+impl Site {
+    /// Returns the site state assuming that the incoming requests have already arrived.
+    fn info(&self, time: f64, requests: &HashMap<u64, RequestState>) -> (u32, usize, f64) {
+        let mut free_cores = self.free_cores;
+        let mut queue_length = self.queue.len();
+        for id in self.incoming.iter() {
+            let cores = requests[id].request.cores;
+            if queue_length == 0 && cores <= free_cores {
+                free_cores -= cores;
+            } else {
+                queue_length += 1;
+            }
+        }
+        (free_cores, queue_length, self.pending_work(time, requests))
+    }
+
+    fn pending_work(&self, time: f64, requests: &HashMap<u64, RequestState>) -> f64 {
+        let queued: f64 = self
+            .queue
+            .iter()
+            .chain(self.incoming.iter())
+            .map(|id| requests[id].request.work)
+            .sum();
+        let running: f64 = self
+            .running
+            .values()
+            .map(|finish_time| (finish_time - time).max(0.))
+            .sum();
+        queued + running * self.speed
+    }
+}
+
+struct RequestState {
+    request: EdgeRequest,
+    host_id: u32,
+    submit_time: f64,
+}
+
+enum TransferKind {
+    Input,
+    Output,
+}
+
+/// Component which receives client requests, selects the execution sites and executes requests at them.
+///
+/// The sites should be registered via [`add_site`](Self::add_site) and their hosts should be bound to the network.
+pub struct EdgeOrchestrator {
+    policy: Option<Box<dyn OffloadingPolicy>>,
+    network: Option<Rc<RefCell<Network>>>,
+    sites: BTreeMap<u32, Site>,
+    requests: HashMap<u64, RequestState>,
+    transfers: HashMap<usize, (u64, TransferKind)>,
+    latencies: Vec<f64>,
+    stats: EdgeStats,
+    ctx: SimulationContext,
+}
+
+impl EdgeOrchestrator {
+    /// Used to provide the network connecting the clients and the sites, and the offloading policy.
+    pub fn patch_custom_args(&mut self, network: Rc<RefCell<Network>>, policy: Box<dyn OffloadingPolicy>) {
+        self.network = Some(network);
+        self.policy = Some(policy);
+    }
+
+    /// Returns the component id.
+    pub fn id(&self) -> Id {
+        self.ctx.id()
+    }
+
+    /// Registers the host as execution site with the specified relative speed.
+    ///
+    /// All host cores are used for request execution, the host should be bound to the network node.
+    pub fn add_site(&mut self, host: &HostManager, kind: SiteKind, speed: f64) {
+        assert!(speed > 0., "Site speed should be positive");
+        self.sites.insert(
+            host.id,
+            Site {
+                kind,
+                cores: host.cpu_total(),
+                free_cores: host.cpu_total(),
+                speed,
+                queue: VecDeque::new(),
+                incoming: Vec::new(),
+                running: BTreeMap::new(),
+            },
+        );
+    }
+
+    /// Returns the statistics of processed requests.
+    pub fn stats(&self) -> EdgeStats {
+        let mut stats = self.stats.clone();
+        if !self.latencies.is_empty() {
+            let mut latencies = self.latencies.clone();
+            latencies.sort_by(|a, b| a.total_cmp(b));
+            stats.mean_latency = latencies.iter().sum::<f64>() / latencies.len() as f64;
+            let rank = (0.95 * latencies.len() as f64).ceil() as usize;
+            stats.p95_latency = latencies[rank.clamp(1, latencies.len()) - 1];
+            stats.max_latency = *latencies.last().unwrap();
+        }
+        stats
+    }
+
+    /// Returns the states of sites as seen from the specified client.
+    pub fn site_infos(&self, client: Id) -> Vec<SiteInfo> {
+        let network = self.network.as_ref().unwrap().borrow();
+        self.sites
+            .iter()
+            .map(|(host_id, site)| {
+                let (free_cores, queue_length, pending_work) = site.info(self.ctx.time(), &self.requests);
+                SiteInfo {
+                    host_id: *host_id,
+                    kind: site.kind,
+                    cores: site.cores,
+                    free_cores,
+                    speed: site.speed,
+                    queue_length,
+                    pending_work,
+                    latency: network.latency(client, *host_id),
+                    bandwidth: network.bandwidth(client, *host_id),
+                }
+            })
+            .collect()
+    }
+
+    fn on_request_submitted(&mut self, request: EdgeRequest) {
+        self.stats.submitted += 1;
+        let sites = self.site_infos(request.client);
+        let selected = self.policy.as_mut().unwrap().select(&request, &sites);
+        let host_id = match selected {
+            Some(index) if sites[index].fits(&request) => sites[index].host_id,
+            _ => {
+                log_warn!(
+                    self.ctx,
+                    "Request {} from client {} is rejected",
+                    request.id,
+                    request.client
+                );
+                self.stats.rejected += 1;
+                let client = request.client;
+                self.ctx.emit_now(EdgeRequestRejected { request }, client);
+                return;
+            }
+        };
+        log_debug!(
+            self.ctx,
+            "Request {} from client {} is offloaded to host {}",
+            request.id,
+            request.client,
+            host_id
+        );
+        let transfer_id = self.network.as_ref().unwrap().borrow_mut().transfer_data(
+            request.client,
+            host_id,
+            request.input_size,
+            self.ctx.id(),
+        );
+        self.transfers.insert(transfer_id, (request.id, TransferKind::Input));
+        self.sites.get_mut(&host_id).unwrap().incoming.push(request.id);
+        self.requests.insert(
+            request.id,
+            RequestState {
+                request,
+                host_id,
+                submit_time: self.ctx.time(),
+            },
+        );
+    }
+
+    fn on_input_transferred(&mut self, request_id: u64) {
+        let host_id = self.requests[&request_id].host_id;
+        let site = self.sites.get_mut(&host_id).unwrap();
+        site.incoming.retain(|id| *id != request_id);
+        site.queue.push_back(request_id);
+        self.start_requests(host_id);
+    }
+
+    /// Starts the queued requests in FIFO order while the site has enough free cores.
+    fn start_requests(&mut self, host_id: u32) {
+        let site = self.sites.get_mut(&host_id).unwrap();
+        while let Some(request_id) = site.queue.front() {
+            let request = &self.requests[request_id].request;
+            if request.cores > site.free_cores {
+                break;
+            }
+            let execution_time = request.work / site.speed;
+            site.free_cores -= request.cores;
+            site.running.insert(request.id, self.ctx.time() + execution_time);
+            self.ctx
+                .emit_self(EdgeExecutionCompleted { request_id: request.id }, execution_time);
+            site.queue.pop_front();
+        }
+    }
+
+    fn on_execution_completed(&mut self, request_id: u64) {
+        let state = &self.requests[&request_id];
+        let host_id = state.host_id;
+        let site = self.sites.get_mut(&host_id).unwrap();
+        site.running.remove(&request_id);
+        site.free_cores += state.request.cores;
+        let transfer_id = self.network.as_ref().unwrap().borrow_mut().transfer_data(
+            host_id,
+            state.request.client,
+            state.request.output_size,
+            self.ctx.id(),
+        );
+        self.transfers.insert(transfer_id, (request_id, TransferKind::Output));
+        self.start_requests(host_id);
+    }
+
+    fn on_output_transferred(&mut self, request_id: u64) {
+        let state = self.requests.remove(&request_id).unwrap();
+        let site_kind = self.sites[&state.host_id].kind;
+        let completed = EdgeRequestCompleted {
+            request: state.request,
+            host_id: state.host_id,
+            site_kind,
+            submit_time: state.submit_time,
+            finish_time: self.ctx.time(),
+        };
+        let latency = completed.latency();
+        self.latencies.push(latency);
+        self.stats.completed += 1;
+        match site_kind {
+            SiteKind::Edge => self.stats.edge_executions += 1,
+            SiteKind::Cloud => self.stats.cloud_executions += 1,
+        }
+        if completed.request.deadline.is_some_and(|deadline| latency > deadline) {
+            self.stats.deadline_misses += 1;
+        }
+        let client = completed.request.client;
+        self.ctx.emit_now(completed, client);
+    }
+}
+
+impl CustomComponent for EdgeOrchestrator {
+    fn new(ctx: SimulationContext) -> Self {
+        Self {
+            policy: None,
+            network: None,
+            sites: BTreeMap::new(),
+            requests: HashMap::new(),
+            transfers: HashMap::new(),
+            latencies: Vec::new(),
+            stats: EdgeStats::default(),
+            ctx,
+        }
+    }
+
+    fn init(&mut self) {
+        assert!(
+            self.network.is_some() && self.policy.is_some(),
+            "patch_custom_args should be invoked before init"
+        );
+    }
+}
+
+impl EventHandler for EdgeOrchestrator {
+    fn on(&mut self, event: Event) {
+        cast!(match event.data {
+            EdgeRequestSubmitted { request } => {
+                self.on_request_submitted(request);
+            }
+            EdgeExecutionCompleted { request_id } => {
+                self.on_execution_completed(request_id);
+            }
+            DataTransferCompleted { dt } => {
+                match self.transfers.remove(&dt.id) {
+                    Some((request_id, TransferKind::Input)) => self.on_input_transferred(request_id),
+                    Some((request_id, TransferKind::Output)) => self.on_output_transferred(request_id),
+                    None => {}
+                }
+            }
+        })
+    }
+}
+
+// Mobile client ///////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Description of requests generated by [`MobileClient`].
+#[derive(Clone, Debug)]
+pub struct EdgeWorkload {
+    pub inter_arrival_time: TimeDistribution,
+    pub cores: u32,
+    pub work: TimeDistribution,
+    pub input_size: f64,
+    pub output_size: f64,
+    pub deadline: Option<f64>,
+    /// Total number of generated requests.
+    pub request_count: u64,
+}
+
+/// Movement of client between the network access points.
+#[derive(Clone, Debug)]
+pub struct Mobility {
+    /// Names of network nodes used as access points, the client starts at the first one.
+    pub access_points: Vec<String>,
+    /// Time spent by client at the access point before moving to the next one, `None` for static client.
+    pub dwell_time: Option<TimeDistribution>,
+    /// Whether the client moves to a random access point instead of the next one in the list.
+    pub random_walk: bool,
+}
+
+/// Mobile client attached to the network via access points, which generates requests to edge orchestrator.
+///
+/// Since the client location changes over time, the requests are offloaded to the sites close to its current location.
+/// The request output is delivered to the access point where the client is located when the request is completed.
+pub struct MobileClient {
+    network: Option<Rc<RefCell<Network>>>,
+    orchestrator: Option<Id>,
+    workload: Option<EdgeWorkload>,
+    mobility: Option<Mobility>,
+    location: usize,
+    generated: u64,
+    completed: Vec<EdgeRequestCompleted>,
+    rejected: u64,
+    ctx: SimulationContext,
+}
+
+impl MobileClient {
+    /// Used to provide the network, the orchestrator, and the client workload and mobility.
+    pub fn patch_custom_args(
+        &mut self,
+        network: Rc<RefCell<Network>>,
+        orchestrator: Id,
+        workload: EdgeWorkload,
+        mobility: Mobility,
+    ) {
+        assert!(
+            !mobility.access_points.is_empty(),
+            "Client should have at least one access point"
+        );
+        network
+            .borrow_mut()
+            .set_location(self.ctx.id(), &mobility.access_points[0]);
+        self.network = Some(network);
+        self.orchestrator = Some(orchestrator);
+        self.workload = Some(workload);
+        self.mobility = Some(mobility);
+    }
+
+    /// Returns the name of network node where the client is located.
+    pub fn access_point(&self) -> &str {
+        &self.mobility.as_ref().unwrap().access_points[self.location]
+    }
+
+    /// Returns the number of generated requests.
+    pub fn generated_requests(&self) -> u64 {
+        self.generated
+    }
+
+    /// Returns the completed requests.
+    pub fn completed_requests(&self) -> &[EdgeRequestCompleted] {
+        &self.completed
+    }
+
+    /// Returns the number of rejected requests.
+    pub fn rejected_requests(&self) -> u64 {
+        self.rejected
+    }
+
+    fn schedule_request(&mut self) {
+        let workload = self.workload.as_ref().unwrap();
+        if self.generated < workload.request_count {
+            let delay = workload.inter_arrival_time.sample(&self.ctx);
+            self.ctx.emit_self(GenerateEdgeRequest {}, delay);
+        }
+    }
+
+    fn schedule_move(&mut self) {
+        if let Some(dwell_time) = &self.mobility.as_ref().unwrap().dwell_time {
+            let delay = dwell_time.sample(&self.ctx);
+            self.ctx.emit_self(MoveClient {}, delay);
+        }
+    }
+
+    fn generate_request(&mut self) {
+        let workload = self.workload.as_ref().unwrap();
+        let request = EdgeRequest {
+            id: ((self.ctx.id() as u64) << 32) | self.generated,
+            client: self.ctx.id(),
+            cores: workload.cores,
+            work: workload.work.sample(&self.ctx),
+            input_size: workload.input_size,
+            output_size: workload.output_size,
+            deadline: workload.deadline,
+        };
+        self.generated += 1;
+        self.ctx
+            .emit_now(EdgeRequestSubmitted { request }, self.orchestrator.unwrap());
+        self.schedule_request();
+    }
+
+    fn move_client(&mut self) {
+        let mobility = self.mobility.as_ref().unwrap();
+        let count = mobility.access_points.len();
+        if count > 1 {
+            self.location = if mobility.random_walk {
+                (self.location + self.ctx.gen_range(1..count)) % count
+            } else {
+                (self.location + 1) % count
+            };
+            log_debug!(self.ctx, "Client moved to {}", self.access_point());
+            let access_point = self.access_point().to_string();
+            self.network
+                .as_ref()
+                .unwrap()
+                .borrow_mut()
+                .set_location(self.ctx.id(), &access_point);
+        }
+        // the client stops moving after generating all requests
+        if self.generated < self.workload.as_ref().unwrap().request_count {
+            self.schedule_move();
+        }
+    }
+}
+
+impl CustomComponent for MobileClient {
+    fn new(ctx: SimulationContext) -> Self {
+        Self {
+            network: None,
+            orchestrator: None,
+            workload: None,
+            mobility: None,
+            location: 0,
+            generated: 0,
+            completed: Vec::new(),
+            rejected: 0,
+            ctx,
+        }
+    }
+
+    fn init(&mut self) {
+        assert!(
+            self.workload.is_some(),
+            "patch_custom_args should be invoked before init"
+        );
+        self.schedule_request();
+        self.schedule_move();
+    }
+}
+
+impl EventHandler for MobileClient {
+    fn on(&mut self, event: Event) {
+        cast!(match event.data {
+            GenerateEdgeRequest {} => {
+                self.generate_request();
+            }
+            MoveClient {} => {
+                self.move_client();
+            }
+            EdgeRequestCompleted {
+                request,
+                host_id,
+                site_kind,
+                submit_time,
+                finish_time,
+            } => {
+                self.completed.push(EdgeRequestCompleted {
+                    request,
+                    host_id,
+                    site_kind,
+                    submit_time,
+                    finish_time,
+                });
+            }
+            EdgeRequestRejected { .. } => {
+                self.rejected += 1;
+            }
+        })
+    }
+}
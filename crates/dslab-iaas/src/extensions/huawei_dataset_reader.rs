@@ -82,6 +82,8 @@ impl HuaweiDatasetReader {
                     cpu_load_model: Box::new(ConstantLoadModel::new(1.)),
                     memory_load_model: Box::new(ConstantLoadModel::new(1.)),
                     scheduler_name: None,
+                    tenant: None,
+                    priority: 0,
                 });
             }
         }
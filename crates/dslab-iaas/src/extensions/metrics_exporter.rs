@@ -0,0 +1,262 @@
+//! Component periodically exporting simulation metrics to files.
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::rc::Rc;
+
+use serde::{Deserialize, Serialize};
+
+use dslab_core::cast;
+use dslab_core::context::SimulationContext;
+use dslab_core::event::Event;
+use dslab_core::handler::EventHandler;
+use dslab_core::log_warn;
+
+use crate::core::host_manager::HostManager;
+use crate::core::monitoring::Monitoring;
+use crate::core::scheduler::Scheduler;
+use crate::custom_component::CustomComponent;
+
+#[derive(Clone, Serialize)]
+pub struct ExportMetrics {}
+
+/// Output format of exported metrics.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum MetricsFormat {
+    /// CSV files `<prefix>_hosts.csv` and `<prefix>_schedulers.csv` with a header and one row per entity per sample.
+    Csv,
+    /// Single file `<prefix>.lp` in InfluxDB line protocol with `hosts` and `schedulers` measurements.
+    /// Timestamps are simulation times in nanoseconds.
+    LineProtocol,
+}
+
+/// Single sample of host metrics.
+#[derive(Serialize)]
+struct HostRecord<'a> {
+    time: f64,
+    host: &'a str,
+    host_type: &'a str,
+    cpu_load: f64,
+    memory_load: f64,
+    cpu_allocated: f64,
+    memory_allocated: f64,
+    vm_count: usize,
+    power: f64,
+    energy: f64,
+}
+
+/// Single sample of scheduler metrics.
+#[derive(Serialize)]
+struct SchedulerRecord<'a> {
+    time: f64,
+    scheduler: &'a str,
+    processed_requests: u64,
+    placed_vms: u64,
+    placement_failures: u64,
+    commit_failures: u64,
+    timed_out_vms: u64,
+    rejected_vms: u64,
+    mean_queueing_delay: f64,
+}
+
+enum MetricsWriter {
+    Csv {
+        hosts: Box<csv::Writer<File>>,
+        schedulers: Box<csv::Writer<File>>,
+    },
+    LineProtocol(BufWriter<File>),
+}
+
+/// This component periodically samples the host states from monitoring, host energy consumption
+/// and scheduler statistics, and writes them with simulation timestamps to CSV or line protocol files.
+///
+/// Host loads are taken from monitoring, i.e. they correspond to the (possibly outdated) state known to schedulers.
+pub struct MetricsExporter {
+    interval: f64,
+    monitoring: Option<Rc<RefCell<Monitoring>>>,
+    hosts: BTreeMap<u32, Rc<RefCell<HostManager>>>,
+    schedulers: BTreeMap<u32, Rc<RefCell<Scheduler>>>,
+    writer: Option<MetricsWriter>,
+    ctx: SimulationContext,
+}
+
+impl MetricsExporter {
+    /// Used to provide the exporter settings and references to the sampled components.
+    ///
+    /// Output files are created using the specified path prefix, see [`MetricsFormat`].
+    /// This method should be invoked before [`init()`](MetricsExporter::init()).
+    pub fn patch_custom_args(
+        &mut self,
+        interval: f64,
+        output_prefix: &str,
+        format: MetricsFormat,
+        monitoring: Rc<RefCell<Monitoring>>,
+        hosts: BTreeMap<u32, Rc<RefCell<HostManager>>>,
+        schedulers: BTreeMap<u32, Rc<RefCell<Scheduler>>>,
+    ) -> Result<(), std::io::Error> {
+        self.interval = interval;
+        self.monitoring = Some(monitoring);
+        self.hosts = hosts;
+        self.schedulers = schedulers;
+        self.writer = Some(match format {
+            MetricsFormat::Csv => MetricsWriter::Csv {
+                hosts: Box::new(csv::Writer::from_path(format!("{}_hosts.csv", output_prefix))?),
+                schedulers: Box::new(csv::Writer::from_path(format!("{}_schedulers.csv", output_prefix))?),
+            },
+            MetricsFormat::LineProtocol => {
+                MetricsWriter::LineProtocol(BufWriter::new(File::create(format!("{}.lp", output_prefix))?))
+            }
+        });
+        Ok(())
+    }
+
+    /// Flushes the buffered metrics to output files.
+    pub fn flush(&mut self) -> Result<(), std::io::Error> {
+        match self.writer.as_mut() {
+            Some(MetricsWriter::Csv { hosts, schedulers }) => {
+                hosts.flush()?;
+                schedulers.flush()
+            }
+            Some(MetricsWriter::LineProtocol(writer)) => writer.flush(),
+            None => Ok(()),
+        }
+    }
+
+    /// Periodic process, which samples and writes metrics.
+    fn export_metrics(&mut self) {
+        if self.writer.is_none() || self.monitoring.is_none() {
+            log_warn!(self.ctx, "cannot export metrics as exporter is not configured");
+            return;
+        }
+        if let Err(err) = self.write_sample() {
+            log_warn!(self.ctx, "failed to export metrics: {}", err);
+        }
+        self.ctx.emit_self(ExportMetrics {}, self.interval);
+    }
+
+    fn write_sample(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let time = self.ctx.time();
+        let monitoring = self.monitoring.as_ref().unwrap().borrow();
+        let writer = self.writer.as_mut().unwrap();
+
+        for (host_id, host) in self.hosts.iter() {
+            let mut host = host.borrow_mut();
+            let state = monitoring.get_host_state(*host_id);
+            let name = self.ctx.lookup_name(*host_id);
+            let energy = host.get_energy_consumed(time);
+            let record = HostRecord {
+                time,
+                host: &name,
+                host_type: host.host_type.as_deref().unwrap_or(""),
+                cpu_load: state.cpu_load,
+                memory_load: state.memory_load,
+                cpu_allocated: host.cpu_allocated(),
+                memory_allocated: host.memory_allocated(),
+                vm_count: state.vms.len(),
+                power: host.current_power(host.cpu_load(time)),
+                energy,
+            };
+            match writer {
+                MetricsWriter::Csv { hosts, .. } => hosts.serialize(&record)?,
+                MetricsWriter::LineProtocol(writer) => {
+                    let mut tags = format!("host={}", escape_tag(record.host));
+                    if !record.host_type.is_empty() {
+                        tags += &format!(",host_type={}", escape_tag(record.host_type));
+                    }
+                    writeln!(
+                        writer,
+                        "hosts,{} cpu_load={},memory_load={},cpu_allocated={},memory_allocated={},vm_count={}i,power={},energy={} {}",
+                        tags,
+                        record.cpu_load,
+                        record.memory_load,
+                        record.cpu_allocated,
+                        record.memory_allocated,
+                        record.vm_count,
+                        record.power,
+                        record.energy,
+                        timestamp(time)
+                    )?
+                }
+            }
+        }
+
+        for (scheduler_id, scheduler) in self.schedulers.iter() {
+            let scheduler = scheduler.borrow();
+            let stats = scheduler.stats();
+            let name = self.ctx.lookup_name(*scheduler_id);
+            let record = SchedulerRecord {
+                time,
+                scheduler: &name,
+                processed_requests: stats.processed_requests,
+                placed_vms: stats.placed_vms,
+                placement_failures: stats.placement_failures,
+                commit_failures: stats.commit_failures,
+                timed_out_vms: stats.timed_out_vms,
+                rejected_vms: stats.rejected_vms,
+                mean_queueing_delay: stats.mean_queueing_delay(),
+            };
+            match writer {
+                MetricsWriter::Csv { schedulers, .. } => schedulers.serialize(&record)?,
+                MetricsWriter::LineProtocol(writer) => writeln!(
+                    writer,
+                    "schedulers,scheduler={} processed_requests={}i,placed_vms={}i,placement_failures={}i,commit_failures={}i,timed_out_vms={}i,rejected_vms={}i,mean_queueing_delay={} {}",
+                    escape_tag(record.scheduler),
+                    record.processed_requests,
+                    record.placed_vms,
+                    record.placement_failures,
+                    record.commit_failures,
+                    record.timed_out_vms,
+                    record.rejected_vms,
+                    record.mean_queueing_delay,
+                    timestamp(time)
+                )?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Converts simulation time in seconds to line protocol timestamp in nanoseconds.
+fn timestamp(time: f64) -> i64 {
+    (time * 1e9).round() as i64
+}
+
+/// Escapes special characters in line protocol tag values.
+fn escape_tag(value: &str) -> String {
+    value.replace(',', "\\,").replace('=', "\\=").replace(' ', "\\ ")
+}
+
+impl Drop for MetricsExporter {
+    fn drop(&mut self) {
+        self.flush().ok();
+    }
+}
+
+impl CustomComponent for MetricsExporter {
+    fn new(ctx: SimulationContext) -> Self {
+        Self {
+            interval: 1.,
+            monitoring: None,
+            hosts: BTreeMap::new(),
+            schedulers: BTreeMap::new(),
+            writer: None,
+            ctx,
+        }
+    }
+
+    fn init(&mut self) {
+        self.ctx.emit_self(ExportMetrics {}, 0.);
+    }
+}
+
+impl EventHandler for MetricsExporter {
+    fn on(&mut self, event: Event) {
+        cast!(match event.data {
+            ExportMetrics {} => {
+                self.export_metrics();
+            }
+        })
+    }
+}
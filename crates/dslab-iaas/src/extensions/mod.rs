@@ -1,6 +1,17 @@
 pub mod azure_dataset_reader;
+pub mod batch_scheduler;
+pub mod checkpointing;
+pub mod cloud_broker;
 pub mod dataset_reader;
 pub mod dataset_type;
+pub mod edge;
 pub mod huawei_dataset_reader;
+pub mod metrics_exporter;
+pub mod orchestration;
+pub mod overload_detection;
+pub mod spot_market;
 pub mod standard_dataset_reader;
+pub mod swf_reader;
+pub mod synthetic_workload;
 pub mod vm_migrator;
+pub mod vm_selection;
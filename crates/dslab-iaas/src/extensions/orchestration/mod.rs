@@ -0,0 +1,9 @@
+//! Kubernetes-like container orchestration on top of IaaS hosts or VMs.
+//!
+//! The [orchestrator](orchestrator::ContainerOrchestrator) runs [pods](pod::Pod) on nodes backed by hosts or VMs
+//! of the cloud simulation. The pods are placed by the [scheduler](scheduler::PodScheduler) built from filter and
+//! score plugins, and are managed either directly or via replica sets supporting scaling and rolling updates.
+
+pub mod orchestrator;
+pub mod pod;
+pub mod scheduler;
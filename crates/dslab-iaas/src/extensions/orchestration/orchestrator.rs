@@ -0,0 +1,644 @@
+//! Container orchestrator managing pods and replica sets.
+
+use std::cell::RefCell;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet};
+use std::rc::Rc;
+
+use serde::Serialize;
+
+use dslab_core::cast;
+use dslab_core::component::Id;
+use dslab_core::context::SimulationContext;
+use dslab_core::event::Event;
+use dslab_core::handler::EventHandler;
+use dslab_core::{log_debug, log_warn};
+
+use crate::core::host_manager::HostManager;
+use crate::core::vm::VmStatus;
+use crate::core::vm_api::VmAPI;
+use crate::custom_component::CustomComponent;
+use crate::extensions::orchestration::pod::{NodeInfo, NodeSource, Pod, PodSpec, PodStatus};
+use crate::extensions::orchestration::scheduler::PodScheduler;
+
+/// Observed state of replica set.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ReplicaSetStatus {
+    /// Desired number of replicas.
+    pub replicas: u32,
+    /// Number of active (pending, starting or running) pods.
+    pub current: u32,
+    /// Number of running pods.
+    pub ready: u32,
+    /// Number of active pods created from the latest template.
+    pub updated: u32,
+    /// Number of running pods created from the latest template.
+    pub updated_ready: u32,
+    /// Revision of the latest template.
+    pub revision: u32,
+}
+
+impl ReplicaSetStatus {
+    /// Returns whether all replicas are running and created from the latest template.
+    pub fn is_rolled_out(&self) -> bool {
+        self.current == self.replicas && self.updated_ready == self.replicas
+    }
+}
+
+/// Summary statistics of orchestrator.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct OrchestrationStats {
+    pub created_pods: u64,
+    pub pending_pods: u64,
+    pub running_pods: u64,
+    pub succeeded_pods: u64,
+    pub terminated_pods: u64,
+    /// Number of pods stopped due to the node failures.
+    pub failed_pods: u64,
+    /// Average time from pod creation to its assignment to node.
+    pub mean_scheduling_delay: f64,
+    /// Average time from pod creation to its start.
+    pub mean_startup_latency: f64,
+}
+
+/// Event signalling the pod status change. Source: orchestrator, destination: listener.
+#[derive(Clone, Serialize)]
+pub struct PodStatusChanged {
+    pub pod_id: u64,
+    pub status: PodStatus,
+    pub node: Option<u32>,
+}
+
+#[derive(Clone, Serialize)]
+pub struct PodStarted {
+    pub pod_id: u64,
+}
+
+#[derive(Clone, Serialize)]
+pub struct PodCompleted {
+    pub pod_id: u64,
+}
+
+#[derive(Clone, Serialize)]
+pub struct PodTerminated {
+    pub pod_id: u64,
+}
+
+#[derive(Clone, Serialize)]
+pub struct Reconcile {}
+
+#[derive(Clone, Serialize)]
+pub struct RefreshNodes {}
+
+struct ReplicaSet {
+    replicas: u32,
+    template: PodSpec,
+    revision: u32,
+    max_surge: u32,
+    max_unavailable: u32,
+}
+
+/// Component implementing Kubernetes-like container orchestration on top of IaaS hosts or VMs.
+///
+/// The orchestrator manages a set of nodes, each of which is either a physical host or a VM of the cloud simulation.
+/// The resources of host nodes are considered to be dedicated to the orchestrator, i.e. such hosts should not be used
+/// to run VMs. The VM nodes become ready when their VMs are running, and the pods are evicted from them (and marked
+/// as failed) when the VMs are finished or preempted. The state of VM nodes is refreshed periodically.
+///
+/// Pods are placed on nodes by the [`PodScheduler`] according to their resource requests, the pods which cannot be
+/// placed remain pending until some resources are released. Replica sets maintain the desired number of active pods
+/// created from the specified template, replacing the failed and completed pods. The template update is performed
+/// using rolling update controlled by the maximum number of surge pods (created above the desired number) and
+/// the maximum number of unavailable pods (not running below the desired number).
+pub struct ContainerOrchestrator {
+    vm_api: Option<Rc<RefCell<VmAPI>>>,
+    refresh_interval: f64,
+    termination_grace_period: f64,
+    scheduler: PodScheduler,
+    nodes: BTreeMap<u32, NodeInfo>,
+    pods: BTreeMap<u64, Pod>,
+    replica_sets: BTreeMap<String, ReplicaSet>,
+    reconcile_scheduled: bool,
+    next_node_id: u32,
+    next_pod_id: u64,
+    listener: Option<Id>,
+    ctx: SimulationContext,
+}
+
+impl ContainerOrchestrator {
+    /// Used to provide the VM API needed to track the state of VM nodes and the interval of their refresh.
+    ///
+    /// This method should be invoked before adding VM nodes.
+    pub fn patch_custom_args(&mut self, vm_api: Rc<RefCell<VmAPI>>, refresh_interval: f64) {
+        self.vm_api = Some(vm_api);
+        self.refresh_interval = refresh_interval;
+    }
+
+    /// Sets the pod scheduler, the scheduler with default plugins is used by default.
+    pub fn set_scheduler(&mut self, scheduler: PodScheduler) {
+        self.scheduler = scheduler;
+    }
+
+    /// Sets the time needed to stop the containers of deleted pod (zero by default).
+    pub fn set_termination_grace_period(&mut self, period: f64) {
+        self.termination_grace_period = period;
+    }
+
+    /// Sets the component which will receive [`PodStatusChanged`] events.
+    pub fn set_listener(&mut self, id: Id) {
+        self.listener = Some(id);
+    }
+
+    /// Adds host as node with the specified labels and returns the node id.
+    pub fn add_host_node(&mut self, host: &HostManager, labels: &[(&str, &str)]) -> u32 {
+        let name = format!("host-{}", host.id);
+        self.add_node(
+            name,
+            NodeSource::Host(host.id),
+            host.cpu_total() * 1000,
+            host.memory_total(),
+            labels,
+            true,
+        )
+    }
+
+    /// Adds VM as node with the specified labels and returns the node id.
+    pub fn add_vm_node(&mut self, vm_id: u32, labels: &[(&str, &str)]) -> u32 {
+        let vm_api = self
+            .vm_api
+            .as_ref()
+            .expect("patch_custom_args should be invoked before adding VM nodes");
+        let vm = vm_api.borrow().get_vm(vm_id);
+        let (cpu, memory) = (vm.borrow().cpu_usage, vm.borrow().memory_usage);
+        let ready = vm_api.borrow().get_vm_status(vm_id) == VmStatus::Running;
+        let name = format!("vm-{}", vm_id);
+        self.add_node(name, NodeSource::Vm(vm_id), cpu * 1000, memory, labels, ready)
+    }
+
+    fn add_node(
+        &mut self,
+        name: String,
+        source: NodeSource,
+        cpu_capacity: u32,
+        memory_capacity: u64,
+        labels: &[(&str, &str)],
+        ready: bool,
+    ) -> u32 {
+        let id = self.next_node_id;
+        self.next_node_id += 1;
+        self.nodes.insert(
+            id,
+            NodeInfo {
+                id,
+                name,
+                source,
+                labels: labels.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+                ready,
+                cpu_capacity,
+                memory_capacity,
+                cpu_requested: 0,
+                memory_requested: 0,
+                cpu_limits: 0,
+                memory_limits: 0,
+                pods: BTreeSet::new(),
+                owner_pods: BTreeMap::new(),
+            },
+        );
+        self.request_reconcile();
+        id
+    }
+
+    /// Creates standalone pod and returns its id.
+    pub fn create_pod(&mut self, spec: PodSpec) -> u64 {
+        let id = self.new_pod(spec, None, 0);
+        self.request_reconcile();
+        id
+    }
+
+    /// Deletes pod, the running pod is stopped after the termination grace period.
+    ///
+    /// Returns false if the pod does not exist or is already stopped.
+    pub fn delete_pod(&mut self, pod_id: u64) -> bool {
+        let status = match self.pods.get(&pod_id) {
+            Some(pod) => pod.status,
+            None => return false,
+        };
+        match status {
+            PodStatus::Pending => {
+                self.finish_pod(pod_id, PodStatus::Terminated);
+            }
+            PodStatus::Starting | PodStatus::Running => {
+                self.set_status(pod_id, PodStatus::Terminating);
+                self.ctx
+                    .emit_self(PodTerminated { pod_id }, self.termination_grace_period);
+            }
+            _ => return false,
+        }
+        self.request_reconcile();
+        true
+    }
+
+    /// Creates replica set maintaining the specified number of pods created from the template.
+    pub fn create_replica_set(&mut self, name: &str, replicas: u32, template: PodSpec) {
+        assert!(
+            !self.replica_sets.contains_key(name),
+            "Replica set {} already exists",
+            name
+        );
+        self.replica_sets.insert(
+            name.to_string(),
+            ReplicaSet {
+                replicas,
+                template,
+                revision: 1,
+                max_surge: 1,
+                max_unavailable: 0,
+            },
+        );
+        self.request_reconcile();
+    }
+
+    /// Changes the desired number of replicas.
+    pub fn scale(&mut self, name: &str, replicas: u32) {
+        self.replica_set_mut(name).replicas = replicas;
+        self.request_reconcile();
+    }
+
+    /// Starts rolling update of the replica set to the new template.
+    ///
+    /// During the update the number of active pods does not exceed `replicas + max_surge`, and the number of running
+    /// pods does not fall below `replicas - max_unavailable`.
+    pub fn rolling_update(&mut self, name: &str, template: PodSpec, max_surge: u32, max_unavailable: u32) {
+        assert!(
+            max_surge > 0 || max_unavailable > 0,
+            "max_surge and max_unavailable cannot be both zero"
+        );
+        let rs = self.replica_set_mut(name);
+        rs.template = template;
+        rs.revision += 1;
+        rs.max_surge = max_surge;
+        rs.max_unavailable = max_unavailable;
+        self.request_reconcile();
+    }
+
+    fn replica_set_mut(&mut self, name: &str) -> &mut ReplicaSet {
+        self.replica_sets
+            .get_mut(name)
+            .unwrap_or_else(|| panic!("Replica set {} does not exist", name))
+    }
+
+    /// Returns the pod state.
+    pub fn pod(&self, pod_id: u64) -> Option<&Pod> {
+        self.pods.get(&pod_id)
+    }
+
+    /// Returns all pods (including stopped ones) ordered by id.
+    pub fn pods(&self) -> impl Iterator<Item = &Pod> {
+        self.pods.values()
+    }
+
+    /// Returns the state of nodes ordered by id.
+    pub fn nodes(&self) -> impl Iterator<Item = &NodeInfo> {
+        self.nodes.values()
+    }
+
+    /// Returns the observed state of replica set.
+    pub fn replica_set_status(&self, name: &str) -> Option<ReplicaSetStatus> {
+        let rs = self.replica_sets.get(name)?;
+        let mut status = ReplicaSetStatus {
+            replicas: rs.replicas,
+            current: 0,
+            ready: 0,
+            updated: 0,
+            updated_ready: 0,
+            revision: rs.revision,
+        };
+        for pod in self.owned_pods(name) {
+            let running = pod.status == PodStatus::Running;
+            status.current += 1;
+            status.ready += running as u32;
+            if pod.revision == rs.revision {
+                status.updated += 1;
+                status.updated_ready += running as u32;
+            }
+        }
+        Some(status)
+    }
+
+    /// Returns the summary statistics.
+    pub fn stats(&self) -> OrchestrationStats {
+        let mut stats = OrchestrationStats {
+            created_pods: self.pods.len() as u64,
+            ..Default::default()
+        };
+        let mut scheduled = 0;
+        let mut started = 0;
+        for pod in self.pods.values() {
+            match pod.status {
+                PodStatus::Pending => stats.pending_pods += 1,
+                PodStatus::Running => stats.running_pods += 1,
+                PodStatus::Succeeded => stats.succeeded_pods += 1,
+                PodStatus::Terminated => stats.terminated_pods += 1,
+                PodStatus::Failed => stats.failed_pods += 1,
+                _ => {}
+            }
+            if let Some(time) = pod.scheduled_at {
+                stats.mean_scheduling_delay += time - pod.created_at;
+                scheduled += 1;
+            }
+            if let Some(time) = pod.running_at {
+                stats.mean_startup_latency += time - pod.created_at;
+                started += 1;
+            }
+        }
+        if scheduled > 0 {
+            stats.mean_scheduling_delay /= scheduled as f64;
+        }
+        if started > 0 {
+            stats.mean_startup_latency /= started as f64;
+        }
+        stats
+    }
+
+    fn owned_pods<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Pod> {
+        self.pods
+            .values()
+            .filter(move |pod| pod.status.is_active() && pod.owner.as_deref() == Some(name))
+    }
+
+    fn new_pod(&mut self, spec: PodSpec, owner: Option<String>, revision: u32) -> u64 {
+        let id = self.next_pod_id;
+        self.next_pod_id += 1;
+        self.pods.insert(
+            id,
+            Pod {
+                id,
+                spec,
+                status: PodStatus::Pending,
+                node: None,
+                owner,
+                revision,
+                created_at: self.ctx.time(),
+                scheduled_at: None,
+                running_at: None,
+                finished_at: None,
+            },
+        );
+        self.notify(id);
+        id
+    }
+
+    fn set_status(&mut self, pod_id: u64, status: PodStatus) {
+        self.pods.get_mut(&pod_id).unwrap().status = status;
+        self.notify(pod_id);
+    }
+
+    fn notify(&mut self, pod_id: u64) {
+        if let Some(listener) = self.listener {
+            let pod = &self.pods[&pod_id];
+            self.ctx.emit_now(
+                PodStatusChanged {
+                    pod_id,
+                    status: pod.status,
+                    node: pod.node,
+                },
+                listener,
+            );
+        }
+    }
+
+    /// Stops the pod and releases the node resources.
+    fn finish_pod(&mut self, pod_id: u64, status: PodStatus) {
+        let pod = self.pods.get_mut(&pod_id).unwrap();
+        pod.finished_at = Some(self.ctx.time());
+        if let Some(node_id) = pod.node {
+            self.nodes.get_mut(&node_id).unwrap().remove(pod);
+        }
+        self.set_status(pod_id, status);
+    }
+
+    fn request_reconcile(&mut self) {
+        if !self.reconcile_scheduled {
+            self.reconcile_scheduled = true;
+            self.ctx.emit_self(Reconcile {}, 0.);
+        }
+    }
+
+    fn reconcile(&mut self) {
+        self.reconcile_scheduled = false;
+        let names = self.replica_sets.keys().cloned().collect::<Vec<_>>();
+        for name in names {
+            self.reconcile_replica_set(&name);
+        }
+        self.schedule_pending();
+    }
+
+    fn reconcile_replica_set(&mut self, name: &str) {
+        let rs = &self.replica_sets[name];
+        let (replicas, revision, template) = (rs.replicas, rs.revision, rs.template.clone());
+        let (max_surge, max_unavailable) = (rs.max_surge, rs.max_unavailable);
+
+        let mut updated = Vec::new();
+        let mut old = Vec::new();
+        let mut ready = 0u32;
+        for pod in self.owned_pods(name) {
+            if pod.status == PodStatus::Running {
+                ready += 1;
+            }
+            if pod.revision == revision {
+                updated.push((pod.status, pod.id));
+            } else {
+                old.push((pod.status, pod.id));
+            }
+        }
+        if old.is_empty() {
+            let current = updated.len() as u32;
+            if current < replicas {
+                for _ in current..replicas {
+                    self.new_pod(template.clone(), Some(name.to_string()), revision);
+                }
+            } else if current > replicas {
+                let excess = (current - replicas) as usize;
+                for pod_id in deletion_order(&updated).into_iter().take(excess) {
+                    self.delete_pod(pod_id);
+                }
+            }
+            return;
+        }
+
+        // rolling update
+        let total = (updated.len() + old.len()) as u32;
+        let to_create = (replicas + max_surge)
+            .saturating_sub(total)
+            .min(replicas.saturating_sub(updated.len() as u32));
+        for _ in 0..to_create {
+            self.new_pod(template.clone(), Some(name.to_string()), revision);
+        }
+        let min_ready = replicas.saturating_sub(max_unavailable);
+        let mut can_stop_ready = ready.saturating_sub(min_ready);
+        let mut deleted = 0;
+        for pod_id in deletion_order(&old) {
+            // old pods which are not running can be deleted without affecting availability
+            if self.pods[&pod_id].status == PodStatus::Running {
+                if can_stop_ready == 0 {
+                    break;
+                }
+                can_stop_ready -= 1;
+            }
+            self.delete_pod(pod_id);
+            deleted += 1;
+        }
+        log_debug!(
+            self.ctx,
+            "rolling update of {}: {} updated, {} old, {} created, {} deleted",
+            name,
+            updated.len(),
+            old.len(),
+            to_create,
+            deleted
+        );
+    }
+
+    fn schedule_pending(&mut self) {
+        let pending = self
+            .pods
+            .values()
+            .filter(|pod| pod.status == PodStatus::Pending)
+            .map(|pod| pod.id)
+            .collect::<Vec<_>>();
+        for pod_id in pending {
+            let node_id = match self.scheduler.select_node(&self.pods[&pod_id], self.nodes.values()) {
+                Some(node_id) => node_id,
+                None => continue,
+            };
+            let time = self.ctx.time();
+            let pod = self.pods.get_mut(&pod_id).unwrap();
+            pod.node = Some(node_id);
+            pod.scheduled_at = Some(time);
+            let startup_time = pod.spec.startup_time;
+            self.nodes.get_mut(&node_id).unwrap().place(pod);
+            log_debug!(self.ctx, "pod {} is scheduled on node {}", pod_id, node_id);
+            self.set_status(pod_id, PodStatus::Starting);
+            self.ctx.emit_self(PodStarted { pod_id }, startup_time);
+        }
+    }
+
+    fn on_pod_started(&mut self, pod_id: u64) {
+        if self.pods[&pod_id].status != PodStatus::Starting {
+            return;
+        }
+        let pod = self.pods.get_mut(&pod_id).unwrap();
+        pod.running_at = Some(self.ctx.time());
+        if let Some(duration) = pod.spec.duration {
+            self.ctx.emit_self(PodCompleted { pod_id }, duration);
+        }
+        self.set_status(pod_id, PodStatus::Running);
+        self.request_reconcile();
+    }
+
+    fn on_pod_completed(&mut self, pod_id: u64) {
+        if self.pods[&pod_id].status != PodStatus::Running {
+            return;
+        }
+        self.finish_pod(pod_id, PodStatus::Succeeded);
+        self.request_reconcile();
+    }
+
+    fn on_pod_terminated(&mut self, pod_id: u64) {
+        if self.pods[&pod_id].status != PodStatus::Terminating {
+            return;
+        }
+        self.finish_pod(pod_id, PodStatus::Terminated);
+        self.request_reconcile();
+    }
+
+    /// Periodic process, which updates the readiness of VM nodes and evicts the pods from failed nodes.
+    fn refresh_nodes(&mut self) {
+        if let Some(vm_api) = self.vm_api.clone() {
+            let mut failed_nodes = Vec::new();
+            for node in self.nodes.values_mut() {
+                if let NodeSource::Vm(vm_id) = node.source {
+                    let status = vm_api.borrow().get_vm_status(vm_id);
+                    node.ready = matches!(status, VmStatus::Running | VmStatus::Migrating);
+                    if matches!(
+                        status,
+                        VmStatus::Finished | VmStatus::Preempted | VmStatus::FailedToAllocate
+                    ) && !node.pods.is_empty()
+                    {
+                        failed_nodes.push(node.id);
+                    }
+                }
+            }
+            for node_id in failed_nodes {
+                let pods = self.nodes[&node_id].pods.iter().copied().collect::<Vec<_>>();
+                log_warn!(self.ctx, "node {} failed, evicting {} pods", node_id, pods.len());
+                for pod_id in pods {
+                    self.finish_pod(pod_id, PodStatus::Failed);
+                }
+            }
+            self.request_reconcile();
+        }
+        if self.refresh_interval > 0. {
+            self.ctx.emit_self(RefreshNodes {}, self.refresh_interval);
+        }
+    }
+}
+
+/// Returns the ids of pods in the order of their deletion: pending, starting, running, the latest first.
+fn deletion_order(pods: &[(PodStatus, u64)]) -> Vec<u64> {
+    let mut pods = pods.to_vec();
+    pods.sort_by_key(|(status, id)| {
+        let rank = match status {
+            PodStatus::Pending => 0,
+            PodStatus::Starting => 1,
+            _ => 2,
+        };
+        (rank, Reverse(*id))
+    });
+    pods.into_iter().map(|(_, id)| id).collect()
+}
+
+impl CustomComponent for ContainerOrchestrator {
+    fn new(ctx: SimulationContext) -> Self {
+        Self {
+            vm_api: None,
+            refresh_interval: 1.,
+            termination_grace_period: 0.,
+            scheduler: PodScheduler::default(),
+            nodes: BTreeMap::new(),
+            pods: BTreeMap::new(),
+            replica_sets: BTreeMap::new(),
+            reconcile_scheduled: false,
+            next_node_id: 0,
+            next_pod_id: 0,
+            listener: None,
+            ctx,
+        }
+    }
+
+    fn init(&mut self) {
+        self.ctx.emit_self(RefreshNodes {}, 0.);
+    }
+}
+
+impl EventHandler for ContainerOrchestrator {
+    fn on(&mut self, event: Event) {
+        cast!(match event.data {
+            Reconcile {} => {
+                self.reconcile();
+            }
+            RefreshNodes {} => {
+                self.refresh_nodes();
+            }
+            PodStarted { pod_id } => {
+                self.on_pod_started(pod_id);
+            }
+            PodCompleted { pod_id } => {
+                self.on_pod_completed(pod_id);
+            }
+            PodTerminated { pod_id } => {
+                self.on_pod_terminated(pod_id);
+            }
+        })
+    }
+}
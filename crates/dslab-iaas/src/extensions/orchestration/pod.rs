@@ -0,0 +1,220 @@
+//! Pods and nodes of container orchestration layer.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::Serialize;
+
+/// Specification of pod (group of containers scheduled together).
+///
+/// CPU resources are specified in millicores, i.e. 1000 corresponds to a single vCPU of the node.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct PodSpec {
+    /// Application name used to identify the pods of the same service.
+    pub app: String,
+    /// Version of application (e.g. container image tag).
+    pub version: String,
+    /// CPU reserved for the pod on the node.
+    pub cpu_request: u32,
+    /// Memory reserved for the pod on the node.
+    pub memory_request: u64,
+    /// Maximum CPU which can be used by the pod (equals to request if not set).
+    pub cpu_limit: Option<u32>,
+    /// Maximum memory which can be used by the pod (equals to request if not set).
+    pub memory_limit: Option<u64>,
+    /// Labels of nodes where the pod can be placed.
+    pub node_selector: BTreeMap<String, String>,
+    /// Time needed to pull the images and start the containers.
+    pub startup_time: f64,
+    /// Running time of the pod, `None` for long-running services.
+    pub duration: Option<f64>,
+}
+
+impl PodSpec {
+    /// Creates specification of long-running pod with specified resource requests, no limits and zero startup time.
+    pub fn new(app: &str, cpu_request: u32, memory_request: u64) -> Self {
+        Self {
+            app: app.to_string(),
+            version: "v1".to_string(),
+            cpu_request,
+            memory_request,
+            cpu_limit: None,
+            memory_limit: None,
+            node_selector: BTreeMap::new(),
+            startup_time: 0.,
+            duration: None,
+        }
+    }
+
+    /// Sets application version.
+    pub fn with_version(mut self, version: &str) -> Self {
+        self.version = version.to_string();
+        self
+    }
+
+    /// Sets resource limits.
+    pub fn with_limits(mut self, cpu_limit: u32, memory_limit: u64) -> Self {
+        self.cpu_limit = Some(cpu_limit);
+        self.memory_limit = Some(memory_limit);
+        self
+    }
+
+    /// Adds node label required by the pod.
+    pub fn with_node_selector(mut self, key: &str, value: &str) -> Self {
+        self.node_selector.insert(key.to_string(), value.to_string());
+        self
+    }
+
+    /// Sets pod startup time.
+    pub fn with_startup_time(mut self, startup_time: f64) -> Self {
+        self.startup_time = startup_time;
+        self
+    }
+
+    /// Sets running time of the pod, after which it is completed.
+    pub fn with_duration(mut self, duration: f64) -> Self {
+        self.duration = Some(duration);
+        self
+    }
+
+    /// Returns CPU limit of the pod.
+    pub fn effective_cpu_limit(&self) -> u32 {
+        self.cpu_limit.unwrap_or(self.cpu_request).max(self.cpu_request)
+    }
+
+    /// Returns memory limit of the pod.
+    pub fn effective_memory_limit(&self) -> u64 {
+        self.memory_limit
+            .unwrap_or(self.memory_request)
+            .max(self.memory_request)
+    }
+}
+
+/// Status of pod.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub enum PodStatus {
+    /// Pod is waiting to be scheduled.
+    Pending,
+    /// Pod is assigned to node and its containers are starting.
+    Starting,
+    /// Pod is running and ready to serve.
+    Running,
+    /// Pod is deleted and its containers are stopping.
+    Terminating,
+    /// Pod is stopped after deletion.
+    Terminated,
+    /// Pod with finite duration has completed.
+    Succeeded,
+    /// Pod is stopped due to the node failure.
+    Failed,
+}
+
+impl PodStatus {
+    /// Returns whether the pod is not stopped or being stopped.
+    pub fn is_active(&self) -> bool {
+        matches!(self, PodStatus::Pending | PodStatus::Starting | PodStatus::Running)
+    }
+}
+
+/// Pod state.
+#[derive(Clone, Debug, Serialize)]
+pub struct Pod {
+    pub id: u64,
+    pub spec: PodSpec,
+    pub status: PodStatus,
+    /// Node where the pod is placed.
+    pub node: Option<u32>,
+    /// Replica set managing the pod.
+    pub owner: Option<String>,
+    /// Revision of the replica set template used to create the pod.
+    pub revision: u32,
+    pub created_at: f64,
+    pub scheduled_at: Option<f64>,
+    pub running_at: Option<f64>,
+    pub finished_at: Option<f64>,
+}
+
+/// Resource providing the node for running pods.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub enum NodeSource {
+    /// Physical host (bare-metal deployment).
+    Host(u32),
+    /// Virtual machine (containers-on-VMs deployment).
+    Vm(u32),
+}
+
+/// Node state visible to the pod scheduler.
+#[derive(Clone, Debug)]
+pub struct NodeInfo {
+    pub id: u32,
+    pub name: String,
+    pub source: NodeSource,
+    pub labels: BTreeMap<String, String>,
+    /// Whether the node can run new pods.
+    pub ready: bool,
+    /// CPU capacity in millicores.
+    pub cpu_capacity: u32,
+    pub memory_capacity: u64,
+    /// Sum of CPU requests of pods placed on the node.
+    pub cpu_requested: u32,
+    /// Sum of memory requests of pods placed on the node.
+    pub memory_requested: u64,
+    /// Sum of CPU limits of pods placed on the node, can exceed the capacity.
+    pub cpu_limits: u64,
+    /// Sum of memory limits of pods placed on the node, can exceed the capacity.
+    pub memory_limits: u64,
+    /// Pods placed on the node.
+    pub pods: BTreeSet<u64>,
+    /// Number of pods placed on the node for each replica set.
+    pub owner_pods: BTreeMap<String, u32>,
+}
+
+impl NodeInfo {
+    /// Returns CPU not reserved by pod requests.
+    pub fn cpu_free(&self) -> u32 {
+        self.cpu_capacity.saturating_sub(self.cpu_requested)
+    }
+
+    /// Returns memory not reserved by pod requests.
+    pub fn memory_free(&self) -> u64 {
+        self.memory_capacity.saturating_sub(self.memory_requested)
+    }
+
+    /// Returns the fraction of CPU capacity reserved by pod requests.
+    pub fn cpu_requested_ratio(&self) -> f64 {
+        self.cpu_requested as f64 / self.cpu_capacity.max(1) as f64
+    }
+
+    /// Returns the fraction of memory capacity reserved by pod requests.
+    pub fn memory_requested_ratio(&self) -> f64 {
+        self.memory_requested as f64 / self.memory_capacity.max(1) as f64
+    }
+
+    pub(crate) fn place(&mut self, pod: &Pod) {
+        self.cpu_requested += pod.spec.cpu_request;
+        self.memory_requested += pod.spec.memory_request;
+        self.cpu_limits += pod.spec.effective_cpu_limit() as u64;
+        self.memory_limits += pod.spec.effective_memory_limit();
+        self.pods.insert(pod.id);
+        if let Some(owner) = &pod.owner {
+            *self.owner_pods.entry(owner.clone()).or_default() += 1;
+        }
+    }
+
+    pub(crate) fn remove(&mut self, pod: &Pod) {
+        if !self.pods.remove(&pod.id) {
+            return;
+        }
+        self.cpu_requested -= pod.spec.cpu_request;
+        self.memory_requested -= pod.spec.memory_request;
+        self.cpu_limits -= pod.spec.effective_cpu_limit() as u64;
+        self.memory_limits -= pod.spec.effective_memory_limit();
+        if let Some(owner) = &pod.owner {
+            if let Some(count) = self.owner_pods.get_mut(owner) {
+                *count -= 1;
+                if *count == 0 {
+                    self.owner_pods.remove(owner);
+                }
+            }
+        }
+    }
+}
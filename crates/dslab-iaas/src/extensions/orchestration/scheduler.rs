@@ -0,0 +1,218 @@
+//! Pod scheduler with pluggable filter and score plugins.
+
+use crate::extensions::orchestration::pod::{NodeInfo, Pod};
+
+/// Plugin which filters out the nodes that cannot run the pod.
+pub trait FilterPlugin {
+    /// Returns the plugin name.
+    fn name(&self) -> &str;
+
+    /// Returns whether the pod can be placed on the node.
+    fn filter(&self, pod: &Pod, node: &NodeInfo) -> bool;
+}
+
+/// Plugin which ranks the feasible nodes for the pod.
+pub trait ScorePlugin {
+    /// Returns the plugin name.
+    fn name(&self) -> &str;
+
+    /// Returns the node score in the range [0, 100], the nodes with higher score are preferred.
+    fn score(&self, pod: &Pod, node: &NodeInfo) -> f64;
+}
+
+/// Filters out the nodes which are not ready.
+pub struct NodeReady;
+
+impl FilterPlugin for NodeReady {
+    fn name(&self) -> &str {
+        "NodeReady"
+    }
+
+    fn filter(&self, _pod: &Pod, node: &NodeInfo) -> bool {
+        node.ready
+    }
+}
+
+/// Filters out the nodes which do not have enough unreserved resources for the pod requests.
+pub struct NodeResourcesFit;
+
+impl FilterPlugin for NodeResourcesFit {
+    fn name(&self) -> &str {
+        "NodeResourcesFit"
+    }
+
+    fn filter(&self, pod: &Pod, node: &NodeInfo) -> bool {
+        pod.spec.cpu_request <= node.cpu_free() && pod.spec.memory_request <= node.memory_free()
+    }
+}
+
+/// Filters out the nodes whose labels do not match the pod node selector.
+pub struct NodeSelectorMatch;
+
+impl FilterPlugin for NodeSelectorMatch {
+    fn name(&self) -> &str {
+        "NodeSelectorMatch"
+    }
+
+    fn filter(&self, pod: &Pod, node: &NodeInfo) -> bool {
+        pod.spec
+            .node_selector
+            .iter()
+            .all(|(key, value)| node.labels.get(key) == Some(value))
+    }
+}
+
+/// Filters out the nodes where the sum of pod limits would exceed the node capacity by more than the specified ratio.
+pub struct LimitOvercommit {
+    pub max_ratio: f64,
+}
+
+impl FilterPlugin for LimitOvercommit {
+    fn name(&self) -> &str {
+        "LimitOvercommit"
+    }
+
+    fn filter(&self, pod: &Pod, node: &NodeInfo) -> bool {
+        let cpu_limits = node.cpu_limits + pod.spec.effective_cpu_limit() as u64;
+        let memory_limits = node.memory_limits + pod.spec.effective_memory_limit();
+        cpu_limits as f64 <= node.cpu_capacity as f64 * self.max_ratio
+            && memory_limits as f64 <= node.memory_capacity as f64 * self.max_ratio
+    }
+}
+
+fn requested_ratios(pod: &Pod, node: &NodeInfo) -> (f64, f64) {
+    let cpu = (node.cpu_requested + pod.spec.cpu_request) as f64 / node.cpu_capacity.max(1) as f64;
+    let memory = (node.memory_requested + pod.spec.memory_request) as f64 / node.memory_capacity.max(1) as f64;
+    (cpu.min(1.), memory.min(1.))
+}
+
+/// Prefers the nodes with the least reserved resources, which spreads the pods across the nodes.
+pub struct LeastAllocated;
+
+impl ScorePlugin for LeastAllocated {
+    fn name(&self) -> &str {
+        "LeastAllocated"
+    }
+
+    fn score(&self, pod: &Pod, node: &NodeInfo) -> f64 {
+        let (cpu, memory) = requested_ratios(pod, node);
+        (2. - cpu - memory) * 50.
+    }
+}
+
+/// Prefers the nodes with the most reserved resources, which packs the pods on fewer nodes.
+pub struct MostAllocated;
+
+impl ScorePlugin for MostAllocated {
+    fn name(&self) -> &str {
+        "MostAllocated"
+    }
+
+    fn score(&self, pod: &Pod, node: &NodeInfo) -> f64 {
+        let (cpu, memory) = requested_ratios(pod, node);
+        (cpu + memory) * 50.
+    }
+}
+
+/// Prefers the nodes where the reserved fractions of CPU and memory are close to each other after placing the pod.
+pub struct BalancedAllocation;
+
+impl ScorePlugin for BalancedAllocation {
+    fn name(&self) -> &str {
+        "BalancedAllocation"
+    }
+
+    fn score(&self, pod: &Pod, node: &NodeInfo) -> f64 {
+        let (cpu, memory) = requested_ratios(pod, node);
+        (1. - (cpu - memory).abs()) * 100.
+    }
+}
+
+/// Prefers the nodes with fewer pods of the same replica set, which improves the service availability.
+pub struct SpreadReplicas;
+
+impl ScorePlugin for SpreadReplicas {
+    fn name(&self) -> &str {
+        "SpreadReplicas"
+    }
+
+    fn score(&self, pod: &Pod, node: &NodeInfo) -> f64 {
+        let count = pod
+            .owner
+            .as_ref()
+            .and_then(|owner| node.owner_pods.get(owner))
+            .copied()
+            .unwrap_or(0);
+        100. / (1. + count as f64)
+    }
+}
+
+/// Pod scheduler which selects the node for pod in two phases: the filter plugins remove the infeasible nodes,
+/// and the score plugins rank the remaining nodes. The node with the highest weighted sum of scores is selected,
+/// the ties are broken in favor of the node with the lowest id.
+pub struct PodScheduler {
+    filters: Vec<Box<dyn FilterPlugin>>,
+    scores: Vec<(Box<dyn ScorePlugin>, f64)>,
+}
+
+impl PodScheduler {
+    /// Creates scheduler without plugins.
+    pub fn new() -> Self {
+        Self {
+            filters: Vec::new(),
+            scores: Vec::new(),
+        }
+    }
+
+    /// Adds filter plugin.
+    pub fn with_filter(mut self, plugin: Box<dyn FilterPlugin>) -> Self {
+        self.filters.push(plugin);
+        self
+    }
+
+    /// Adds score plugin with the specified weight.
+    pub fn with_score(mut self, plugin: Box<dyn ScorePlugin>, weight: f64) -> Self {
+        self.scores.push((plugin, weight));
+        self
+    }
+
+    /// Returns the names of filter and score plugins.
+    pub fn plugin_names(&self) -> (Vec<String>, Vec<String>) {
+        (
+            self.filters.iter().map(|p| p.name().to_string()).collect(),
+            self.scores.iter().map(|(p, _)| p.name().to_string()).collect(),
+        )
+    }
+
+    /// Returns the id of selected node or `None` if there are no feasible nodes.
+    pub fn select_node<'a, I>(&self, pod: &Pod, nodes: I) -> Option<u32>
+    where
+        I: IntoIterator<Item = &'a NodeInfo>,
+    {
+        let mut best: Option<(f64, u32)> = None;
+        for node in nodes {
+            if !self.filters.iter().all(|f| f.filter(pod, node)) {
+                continue;
+            }
+            let score: f64 = self.scores.iter().map(|(p, w)| w * p.score(pod, node)).sum();
+            if best.is_none_or(|(s, id)| score > s || (score == s && node.id < id)) {
+                best = Some((score, node.id));
+            }
+        }
+        best.map(|(_, id)| id)
+    }
+}
+
+impl Default for PodScheduler {
+    /// Creates scheduler with the default plugins: [`NodeReady`], [`NodeResourcesFit`], [`NodeSelectorMatch`] filters
+    /// and [`LeastAllocated`], [`BalancedAllocation`], [`SpreadReplicas`] scores with unit weights.
+    fn default() -> Self {
+        Self::new()
+            .with_filter(Box::new(NodeReady))
+            .with_filter(Box::new(NodeResourcesFit))
+            .with_filter(Box::new(NodeSelectorMatch))
+            .with_score(Box::new(LeastAllocated), 1.)
+            .with_score(Box::new(BalancedAllocation), 1.)
+            .with_score(Box::new(SpreadReplicas), 1.)
+    }
+}
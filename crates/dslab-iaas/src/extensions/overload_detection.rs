@@ -0,0 +1,159 @@
+//! Host overload detection policies used for dynamic VM consolidation.
+//!
+//! The policies follow the heuristics from Beloglazov and Buyya, "Optimal online deterministic algorithms and adaptive
+//! heuristics for energy and performance efficient dynamic consolidation of virtual machines in Cloud data centers"
+//! (2012). Each policy decides whether the host is overloaded based on the history of its CPU utilization.
+
+/// Trait for implementation of host overload detection policies.
+pub trait OverloadDetector {
+    /// Returns true if the host with the specified CPU utilization history is considered overloaded.
+    ///
+    /// The history is ordered from the oldest to the latest value, the last value corresponds to the current state.
+    fn is_overloaded(&self, cpu_history: &[f64]) -> bool;
+}
+
+/// Considers the host overloaded if its current CPU utilization exceeds the fixed threshold.
+pub struct StaticThreshold {
+    threshold: f64,
+}
+
+impl StaticThreshold {
+    pub fn new(threshold: f64) -> Self {
+        Self { threshold }
+    }
+}
+
+impl OverloadDetector for StaticThreshold {
+    fn is_overloaded(&self, cpu_history: &[f64]) -> bool {
+        cpu_history.last().is_some_and(|load| *load > self.threshold)
+    }
+}
+
+/// Adaptive threshold based on the median absolute deviation (MAD) of CPU utilization:
+/// the host is overloaded if its current utilization is not less than `1 - safety_parameter * MAD`.
+///
+/// If the history is shorter than `min_history_length`, the fallback static threshold is used instead.
+pub struct MedianAbsoluteDeviation {
+    safety_parameter: f64,
+    min_history_length: usize,
+    fallback: StaticThreshold,
+}
+
+impl MedianAbsoluteDeviation {
+    pub fn new(safety_parameter: f64, min_history_length: usize, fallback_threshold: f64) -> Self {
+        Self {
+            safety_parameter,
+            min_history_length,
+            fallback: StaticThreshold::new(fallback_threshold),
+        }
+    }
+}
+
+impl OverloadDetector for MedianAbsoluteDeviation {
+    fn is_overloaded(&self, cpu_history: &[f64]) -> bool {
+        if cpu_history.len() < self.min_history_length.max(1) {
+            return self.fallback.is_overloaded(cpu_history);
+        }
+        let med = median(cpu_history);
+        let deviations: Vec<f64> = cpu_history.iter().map(|x| (x - med).abs()).collect();
+        let threshold = 1. - self.safety_parameter * median(&deviations);
+        *cpu_history.last().unwrap() >= threshold
+    }
+}
+
+/// Adaptive threshold based on the interquartile range (IQR) of CPU utilization:
+/// the host is overloaded if its current utilization is not less than `1 - safety_parameter * IQR`.
+///
+/// If the history is shorter than `min_history_length`, the fallback static threshold is used instead.
+pub struct InterquartileRange {
+    safety_parameter: f64,
+    min_history_length: usize,
+    fallback: StaticThreshold,
+}
+
+impl InterquartileRange {
+    pub fn new(safety_parameter: f64, min_history_length: usize, fallback_threshold: f64) -> Self {
+        Self {
+            safety_parameter,
+            min_history_length,
+            fallback: StaticThreshold::new(fallback_threshold),
+        }
+    }
+}
+
+impl OverloadDetector for InterquartileRange {
+    fn is_overloaded(&self, cpu_history: &[f64]) -> bool {
+        if cpu_history.len() < self.min_history_length.max(1) {
+            return self.fallback.is_overloaded(cpu_history);
+        }
+        let mut sorted = cpu_history.to_vec();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+        let iqr = quantile(&sorted, 0.75) - quantile(&sorted, 0.25);
+        let threshold = 1. - self.safety_parameter * iqr;
+        *cpu_history.last().unwrap() >= threshold
+    }
+}
+
+/// Local regression (Loess) with tricube weights fitted to the last `window` utilization values,
+/// which is used to predict the next utilization value.
+/// The host is overloaded if `safety_parameter * predicted_utilization >= 1`.
+///
+/// If the history is shorter than `window`, the fallback static threshold is used instead.
+pub struct LocalRegression {
+    safety_parameter: f64,
+    window: usize,
+    fallback: StaticThreshold,
+}
+
+impl LocalRegression {
+    pub fn new(safety_parameter: f64, window: usize, fallback_threshold: f64) -> Self {
+        Self {
+            safety_parameter,
+            window: window.max(2),
+            fallback: StaticThreshold::new(fallback_threshold),
+        }
+    }
+}
+
+impl OverloadDetector for LocalRegression {
+    fn is_overloaded(&self, cpu_history: &[f64]) -> bool {
+        if cpu_history.len() < self.window {
+            return self.fallback.is_overloaded(cpu_history);
+        }
+        let values = &cpu_history[cpu_history.len() - self.window..];
+        let n = values.len() as f64;
+        // tricube weights give the highest weight to the latest observations
+        let weights: Vec<f64> = (0..values.len())
+            .map(|i| {
+                let distance = (n - 1. - i as f64) / n;
+                (1. - distance.powi(3)).powi(3)
+            })
+            .collect();
+        let w_sum: f64 = weights.iter().sum();
+        let x_mean = weights.iter().enumerate().map(|(i, w)| w * i as f64).sum::<f64>() / w_sum;
+        let y_mean = weights.iter().zip(values).map(|(w, y)| w * y).sum::<f64>() / w_sum;
+        let mut cov = 0.;
+        let mut var = 0.;
+        for (i, (w, y)) in weights.iter().zip(values).enumerate() {
+            cov += w * (i as f64 - x_mean) * (y - y_mean);
+            var += w * (i as f64 - x_mean).powi(2);
+        }
+        let slope = if var > 0. { cov / var } else { 0. };
+        let predicted = y_mean + slope * (n - x_mean);
+        self.safety_parameter * predicted >= 1.
+    }
+}
+
+fn median(values: &[f64]) -> f64 {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    quantile(&sorted, 0.5)
+}
+
+/// Computes quantile of sorted values using linear interpolation.
+fn quantile(sorted: &[f64], q: f64) -> f64 {
+    let pos = q * (sorted.len() - 1) as f64;
+    let lower = pos.floor() as usize;
+    let upper = pos.ceil() as usize;
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (pos - lower as f64)
+}
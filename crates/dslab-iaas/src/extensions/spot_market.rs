@@ -0,0 +1,632 @@
+//! Market-based allocation of spot capacity.
+//!
+//! The market manages a set of hosts dedicated to spot capacity and sells it to tenants via periodic auctions.
+//! Each tenant submits a bid specifying the requested VM size, the price per CPU per second it is willing to pay
+//! and the amount of work (VM running time) needed. The bids remain in the market until their work is completed.
+//!
+//! The market is cleared in rounds. In each round the waiting bids are processed in the order of decreasing price.
+//! The bid is accepted if its VM fits on some host, or if it fits after interrupting the running bids with lower price
+//! on some host (the host requiring the least number of interruptions is selected). The interrupted bids return
+//! to the waiting state and are resumed later with the remaining work. The clearing price is determined by the
+//! [pricing rule](PricingRule), the winning bids pay this price until the next round.
+//!
+//! The VMs of accepted bids are committed directly to the placement store, and the interrupted VMs are preempted
+//! by the placement store as part of the same commit.
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::rc::Rc;
+
+use serde::Serialize;
+
+use dslab_core::cast;
+use dslab_core::component::Id;
+use dslab_core::context::SimulationContext;
+use dslab_core::event::Event;
+use dslab_core::handler::EventHandler;
+use dslab_core::{log_debug, log_warn};
+
+use crate::core::common::Allocation;
+use crate::core::config::sim_config::SimulationConfig;
+use crate::core::events::allocation::{PreemptionCommitFailed, PreemptionCommitRequest};
+use crate::core::host_manager::HostManager;
+use crate::core::preemption::PreemptionVictim;
+use crate::core::resource_pool::ResourcePoolState;
+use crate::core::vm::{ResourceConsumer, VirtualMachine};
+use crate::core::vm_api::VmAPI;
+use crate::custom_component::CustomComponent;
+
+/// Rule used to compute the price paid by the winning bids.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PricingRule {
+    /// All winning bids pay the same clearing price equal to the highest price among the rejected
+    /// and interrupted bids (but not higher than the lowest winning price), or the reserve price if all bids won.
+    Uniform,
+    /// Each winning bid pays its own price.
+    PayAsBid,
+}
+
+/// Tenant request for spot capacity.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Bid {
+    pub tenant: String,
+    pub cpu: u32,
+    pub memory: u64,
+    /// Maximum price per CPU per second.
+    pub price: f64,
+    /// Required VM running time.
+    pub duration: f64,
+}
+
+/// Status of bid.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub enum BidStatus {
+    /// Bid is waiting for the capacity (not submitted yet, rejected or interrupted).
+    Waiting,
+    /// Bid won the auction and its VM is running.
+    Running,
+    /// Bid work is completed.
+    Completed,
+}
+
+/// Bid state.
+#[derive(Clone, Debug, Serialize)]
+pub struct BidState {
+    pub id: u32,
+    pub bid: Bid,
+    pub submit_time: f64,
+    pub status: BidStatus,
+    /// VM of the running bid.
+    pub vm_id: Option<u32>,
+    pub host_id: Option<u32>,
+    /// Price currently paid by the running bid.
+    pub current_price: f64,
+    /// VM running time accumulated before the last interruption.
+    pub work_done: f64,
+    pub interruptions: u32,
+    /// Total payment of the bid.
+    pub cost: f64,
+    pub first_start_time: Option<f64>,
+    pub completion_time: Option<f64>,
+    #[serde(skip)]
+    last_interruption: Option<Interruption>,
+}
+
+#[derive(Clone, Debug)]
+struct Interruption {
+    vm_id: u32,
+    host_id: u32,
+    price: f64,
+    work: f64,
+}
+
+/// Result of market clearing round.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct MarketRound {
+    pub time: f64,
+    /// Clearing price (for pay-as-bid pricing, the lowest price paid by winning bids).
+    pub price: f64,
+    /// Total CPU requested by active bids.
+    pub demand_cpu: u64,
+    /// Total CPU allocated to running bids.
+    pub allocated_cpu: u64,
+    pub accepted_bids: u32,
+    pub rejected_bids: u32,
+    pub interrupted_bids: u32,
+}
+
+/// Summary statistics of market.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct MarketStats {
+    pub rounds: u64,
+    pub submitted_bids: u64,
+    pub completed_bids: u64,
+    pub interruptions: u64,
+    /// Total payment received from tenants.
+    pub revenue: f64,
+    /// Social welfare, i.e. the total value of allocated capacity computed using the bid prices.
+    pub welfare: f64,
+    /// Time-weighted average of clearing price.
+    pub mean_price: f64,
+    pub max_price: f64,
+    /// Average fraction of allocated market CPU.
+    pub mean_utilization: f64,
+    /// Average time from bid submission to its completion.
+    pub mean_completion_time: f64,
+    /// Total payment of each tenant.
+    pub tenant_spend: BTreeMap<String, f64>,
+}
+
+/// Event signalling the completion of clearing round. Source: market, destination: listener.
+#[derive(Clone, Serialize)]
+pub struct MarketCleared {
+    pub round: MarketRound,
+}
+
+#[derive(Clone, Serialize)]
+pub struct ClearMarket {}
+
+/// Component implementing spot capacity market.
+pub struct SpotMarket {
+    vm_api: Option<Rc<RefCell<VmAPI>>>,
+    sim_config: Option<Rc<SimulationConfig>>,
+    placement_store_id: Id,
+    interval: f64,
+    pricing: PricingRule,
+    reserve_price: f64,
+    pool_state: ResourcePoolState,
+    total_cpu: u64,
+    bids: BTreeMap<u32, BidState>,
+    vm_to_bid: BTreeMap<u32, u32>,
+    rounds: Vec<MarketRound>,
+    last_round_time: f64,
+    revenue: f64,
+    welfare: f64,
+    listener: Option<Id>,
+    ctx: SimulationContext,
+}
+
+impl SpotMarket {
+    /// Used to provide the references to standard components needed for market work and the clearing interval.
+    ///
+    /// This method should be invoked before [`init()`](SpotMarket::init()).
+    pub fn patch_custom_args(
+        &mut self,
+        interval: f64,
+        pricing: PricingRule,
+        vm_api: Rc<RefCell<VmAPI>>,
+        sim_config: Rc<SimulationConfig>,
+        placement_store_id: Id,
+    ) {
+        self.interval = interval;
+        self.pricing = pricing;
+        self.vm_api = Some(vm_api);
+        self.sim_config = Some(sim_config);
+        self.placement_store_id = placement_store_id;
+    }
+
+    /// Sets the minimum price of capacity (zero by default), the bids with lower price are not accepted.
+    pub fn set_reserve_price(&mut self, price: f64) {
+        self.reserve_price = price;
+    }
+
+    /// Sets the component which will receive [`MarketCleared`] events.
+    pub fn set_listener(&mut self, id: Id) {
+        self.listener = Some(id);
+    }
+
+    /// Adds host to the spot capacity pool. The host should not be used by other schedulers.
+    pub fn add_host(&mut self, host: &HostManager) {
+        self.pool_state.add_host(
+            host.id,
+            host.cpu_total(),
+            host.memory_total(),
+            host.cpu_total(),
+            host.memory_total(),
+            host.rack_id,
+            host.host_type.clone(),
+        );
+        self.total_cpu += host.cpu_total() as u64;
+    }
+
+    /// Submits bid which enters the market after the specified delay. Returns the bid id.
+    pub fn submit_bid(&mut self, bid: Bid, delay: f64) -> u32 {
+        let id = self.bids.len() as u32;
+        self.bids.insert(
+            id,
+            BidState {
+                id,
+                bid,
+                submit_time: self.ctx.time() + delay,
+                status: BidStatus::Waiting,
+                vm_id: None,
+                host_id: None,
+                current_price: 0.,
+                work_done: 0.,
+                interruptions: 0,
+                cost: 0.,
+                first_start_time: None,
+                completion_time: None,
+                last_interruption: None,
+            },
+        );
+        id
+    }
+
+    /// Returns the bid state.
+    pub fn bid(&self, bid_id: u32) -> Option<&BidState> {
+        self.bids.get(&bid_id)
+    }
+
+    /// Returns the results of all clearing rounds.
+    pub fn rounds(&self) -> &[MarketRound] {
+        &self.rounds
+    }
+
+    /// Returns the current clearing price.
+    pub fn current_price(&self) -> f64 {
+        self.rounds.last().map_or(self.reserve_price, |r| r.price)
+    }
+
+    /// Returns the summary statistics.
+    pub fn stats(&self) -> MarketStats {
+        let mut stats = MarketStats {
+            rounds: self.rounds.len() as u64,
+            submitted_bids: self.bids.len() as u64,
+            revenue: self.revenue,
+            welfare: self.welfare,
+            ..Default::default()
+        };
+        for bid in self.bids.values() {
+            stats.interruptions += bid.interruptions as u64;
+            *stats.tenant_spend.entry(bid.bid.tenant.clone()).or_default() += bid.cost;
+            if let Some(time) = bid.completion_time {
+                stats.completed_bids += 1;
+                stats.mean_completion_time += time - bid.submit_time;
+            }
+        }
+        if stats.completed_bids > 0 {
+            stats.mean_completion_time /= stats.completed_bids as f64;
+        }
+        let mut total_time = 0.;
+        for (i, round) in self.rounds.iter().enumerate() {
+            let end = self
+                .rounds
+                .get(i + 1)
+                .map_or(self.last_round_time + self.interval, |r| r.time);
+            let length = end - round.time;
+            total_time += length;
+            stats.mean_price += round.price * length;
+            stats.mean_utilization += round.allocated_cpu as f64 / self.total_cpu.max(1) as f64 * length;
+            stats.max_price = stats.max_price.max(round.price);
+        }
+        if total_time > 0. {
+            stats.mean_price /= total_time;
+            stats.mean_utilization /= total_time;
+        }
+        stats
+    }
+
+    fn allocation(bid: &BidState, vm_id: u32) -> Allocation {
+        Allocation {
+            id: vm_id,
+            cpu_usage: bid.bid.cpu,
+            memory_usage: bid.bid.memory,
+        }
+    }
+
+    /// Charges the running bids for the time since the previous round and completes the finished bids.
+    fn settle(&mut self, time: f64) {
+        let vm_api = self.vm_api.clone().unwrap();
+        let mut completed = Vec::new();
+        for bid in self.bids.values_mut().filter(|b| b.status == BidStatus::Running) {
+            let vm_id = bid.vm_id.unwrap();
+            let vm = vm_api.borrow().get_vm(vm_id);
+            let (start, lifetime) = (vm.borrow().start_time(), vm.borrow().lifetime());
+            if start < 0. {
+                continue;
+            }
+            let end = time.min(start + lifetime);
+            let charged = (end - start.max(self.last_round_time)).max(0.);
+            let cost = charged * bid.current_price * bid.bid.cpu as f64;
+            bid.cost += cost;
+            self.revenue += cost;
+            self.welfare += charged * bid.bid.price * bid.bid.cpu as f64;
+            if start + lifetime <= time {
+                bid.status = BidStatus::Completed;
+                bid.work_done = bid.bid.duration;
+                bid.completion_time = Some(start + lifetime);
+                completed.push((bid.id, vm_id));
+            }
+        }
+        for (bid_id, vm_id) in completed {
+            let bid = &self.bids[&bid_id];
+            self.pool_state
+                .release(&Self::allocation(bid, vm_id), bid.host_id.unwrap());
+            self.vm_to_bid.remove(&vm_id);
+        }
+    }
+
+    /// Returns the running bids which should be interrupted to place the allocation on the host,
+    /// or `None` if it cannot be placed.
+    fn find_victims(&self, alloc: &Allocation, price: f64, host_id: u32, accepted: &[u32]) -> Option<Vec<u32>> {
+        let host = self.pool_state.get_host(host_id);
+        let mut cpu_available = host.cpu_available;
+        let mut memory_available = host.memory_available;
+        let mut candidates = host
+            .allocations
+            .keys()
+            .map(|vm_id| &self.bids[&self.vm_to_bid[vm_id]])
+            .filter(|b| b.bid.price < price && !accepted.contains(&b.id))
+            .collect::<Vec<_>>();
+        candidates.sort_by(|a, b| a.bid.price.total_cmp(&b.bid.price).then(b.id.cmp(&a.id)));
+        let mut victims = Vec::new();
+        for candidate in candidates {
+            if cpu_available >= alloc.cpu_usage && memory_available >= alloc.memory_usage {
+                break;
+            }
+            cpu_available += candidate.bid.cpu;
+            memory_available += candidate.bid.memory;
+            victims.push(candidate.id);
+        }
+        if cpu_available >= alloc.cpu_usage && memory_available >= alloc.memory_usage {
+            Some(victims)
+        } else {
+            None
+        }
+    }
+
+    /// Selects the host for bid using best fit, interrupting the minimum number of running bids with lower price.
+    fn place_bid(&self, bid: &BidState, accepted: &[u32]) -> Option<(u32, Vec<u32>)> {
+        let alloc = Self::allocation(bid, 0);
+        let mut best: Option<(usize, u32, u32)> = None;
+        for host_id in self.pool_state.get_host_ids() {
+            if let Some(victims) = self.find_victims(&alloc, bid.bid.price, host_id, accepted) {
+                let freed: u32 = victims.iter().map(|id| self.bids[id].bid.cpu).sum();
+                let remaining = self.pool_state.get_available_cpu(host_id) + freed - alloc.cpu_usage;
+                let key = (victims.len(), remaining, host_id);
+                if best.is_none_or(|b| key < b) {
+                    best = Some(key);
+                }
+            }
+        }
+        best.map(|(_, _, host_id)| {
+            let victims = self.find_victims(&alloc, bid.bid.price, host_id, accepted).unwrap();
+            (host_id, victims)
+        })
+    }
+
+    fn clear_market(&mut self) {
+        let time = self.ctx.time();
+        self.settle(time);
+
+        let mut waiting = self
+            .bids
+            .values()
+            .filter(|b| b.status == BidStatus::Waiting && b.submit_time <= time)
+            .map(|b| (b.bid.price, b.id))
+            .collect::<Vec<_>>();
+        waiting.sort_by(|a, b| b.0.total_cmp(&a.0).then(a.1.cmp(&b.1)));
+
+        let mut accepted = Vec::new();
+        let mut vm_ids = Vec::new();
+        let mut host_ids = Vec::new();
+        let mut interrupted = Vec::new();
+        let mut rejected_price: Option<f64> = None;
+        let mut rejected_count = 0;
+        for (price, bid_id) in waiting {
+            if price < self.reserve_price {
+                rejected_count += 1;
+                continue;
+            }
+            match self.place_bid(&self.bids[&bid_id], &accepted) {
+                Some((host_id, victims)) => {
+                    for victim in victims {
+                        rejected_price = Some(rejected_price.unwrap_or(0.).max(self.bids[&victim].bid.price));
+                        self.interrupt(victim);
+                        interrupted.push(victim);
+                    }
+                    vm_ids.push(self.start_bid(bid_id, host_id));
+                    host_ids.push(host_id);
+                    accepted.push(bid_id);
+                }
+                None => {
+                    rejected_price = Some(rejected_price.unwrap_or(0.).max(price));
+                    rejected_count += 1;
+                }
+            }
+        }
+        let lowest_winning = self
+            .bids
+            .values()
+            .filter(|b| b.status == BidStatus::Running)
+            .map(|b| b.bid.price)
+            .fold(f64::INFINITY, f64::min);
+        let clearing_price = match (self.pricing, rejected_price) {
+            (PricingRule::Uniform, Some(price)) => price.max(self.reserve_price).min(lowest_winning),
+            (PricingRule::Uniform, None) => self.reserve_price,
+            (PricingRule::PayAsBid, _) => {
+                if lowest_winning.is_finite() {
+                    lowest_winning
+                } else {
+                    self.reserve_price
+                }
+            }
+        };
+
+        let victims = interrupted
+            .iter()
+            .map(|id| {
+                let interruption = self.bids[id].last_interruption.as_ref().unwrap();
+                PreemptionVictim {
+                    vm_id: interruption.vm_id,
+                    host_id: interruption.host_id,
+                    target_host: None,
+                }
+            })
+            .collect::<Vec<_>>();
+        if !vm_ids.is_empty() {
+            self.ctx.emit(
+                PreemptionCommitRequest {
+                    vm_ids,
+                    host_ids,
+                    victims,
+                },
+                self.placement_store_id,
+                self.sim_config.as_ref().unwrap().message_delay,
+            );
+        }
+
+        for bid in self.bids.values_mut().filter(|b| b.status == BidStatus::Running) {
+            bid.current_price = match self.pricing {
+                PricingRule::Uniform => clearing_price,
+                PricingRule::PayAsBid => bid.bid.price,
+            };
+        }
+        let demand_cpu = self
+            .bids
+            .values()
+            .filter(|b| b.status != BidStatus::Completed && b.submit_time <= time)
+            .map(|b| b.bid.cpu as u64)
+            .sum();
+        let allocated_cpu = self
+            .bids
+            .values()
+            .filter(|b| b.status == BidStatus::Running)
+            .map(|b| b.bid.cpu as u64)
+            .sum();
+        let round = MarketRound {
+            time,
+            price: clearing_price,
+            demand_cpu,
+            allocated_cpu,
+            accepted_bids: accepted.len() as u32,
+            rejected_bids: rejected_count,
+            interrupted_bids: interrupted.len() as u32,
+        };
+        log_debug!(
+            self.ctx,
+            "market cleared at price {:.3}: {} accepted, {} rejected, {} interrupted",
+            clearing_price,
+            round.accepted_bids,
+            round.rejected_bids,
+            round.interrupted_bids
+        );
+        if let Some(listener) = self.listener {
+            self.ctx.emit_now(MarketCleared { round: round.clone() }, listener);
+        }
+        self.rounds.push(round);
+        self.last_round_time = time;
+        self.ctx.emit_self(ClearMarket {}, self.interval);
+    }
+
+    fn interrupt(&mut self, bid_id: u32) {
+        let time = self.ctx.time();
+        let bid = self.bids.get_mut(&bid_id).unwrap();
+        let vm_id = bid.vm_id.take().unwrap();
+        let host_id = bid.host_id.take().unwrap();
+        let start = self
+            .vm_api
+            .as_ref()
+            .unwrap()
+            .borrow()
+            .get_vm(vm_id)
+            .borrow()
+            .start_time();
+        let work = if start >= 0. { time - start } else { 0. };
+        bid.work_done += work;
+        bid.interruptions += 1;
+        bid.status = BidStatus::Waiting;
+        bid.last_interruption = Some(Interruption {
+            vm_id,
+            host_id,
+            price: bid.current_price,
+            work,
+        });
+        let alloc = Self::allocation(bid, vm_id);
+        self.pool_state.release(&alloc, host_id);
+        self.vm_to_bid.remove(&vm_id);
+    }
+
+    fn start_bid(&mut self, bid_id: u32, host_id: u32) -> u32 {
+        let time = self.ctx.time();
+        let vm_api = self.vm_api.clone().unwrap();
+        let vm_id = vm_api.borrow_mut().generate_vm_id();
+        let bid = self.bids.get_mut(&bid_id).unwrap();
+        let mut vm = VirtualMachine::new(
+            vm_id,
+            time,
+            bid.bid.duration - bid.work_done,
+            ResourceConsumer::with_full_load(bid.bid.cpu, bid.bid.memory),
+            self.sim_config.clone().unwrap(),
+        );
+        vm.tenant = Some(bid.bid.tenant.clone());
+        vm_api.borrow_mut().register_new_vm(vm);
+        bid.status = BidStatus::Running;
+        bid.vm_id = Some(vm_id);
+        bid.host_id = Some(host_id);
+        bid.first_start_time.get_or_insert(time);
+        let alloc = Self::allocation(bid, vm_id);
+        self.pool_state.allocate(&alloc, host_id);
+        self.vm_to_bid.insert(vm_id, bid_id);
+        vm_id
+    }
+
+    /// Rolls back the clearing decisions rejected by the placement store: the accepted bids return to the waiting
+    /// state and the interrupted bids continue running.
+    fn on_commit_failed(&mut self, vm_ids: Vec<u32>, victims: Vec<PreemptionVictim>) {
+        log_warn!(self.ctx, "commit of {} spot VMs failed", vm_ids.len());
+        for vm_id in vm_ids {
+            if let Some(bid_id) = self.vm_to_bid.remove(&vm_id) {
+                let bid = self.bids.get_mut(&bid_id).unwrap();
+                let host_id = bid.host_id.take().unwrap();
+                bid.vm_id = None;
+                bid.status = BidStatus::Waiting;
+                let alloc = Self::allocation(bid, vm_id);
+                self.pool_state.release(&alloc, host_id);
+            }
+        }
+        for victim in victims {
+            let bid = self.bids.values_mut().find(|b| {
+                b.status == BidStatus::Waiting && b.last_interruption.as_ref().is_some_and(|i| i.vm_id == victim.vm_id)
+            });
+            if let Some(bid) = bid {
+                let interruption = bid.last_interruption.take().unwrap();
+                bid.work_done -= interruption.work;
+                bid.interruptions -= 1;
+                bid.current_price = interruption.price;
+                bid.status = BidStatus::Running;
+                bid.vm_id = Some(interruption.vm_id);
+                bid.host_id = Some(interruption.host_id);
+                let alloc = Self::allocation(bid, interruption.vm_id);
+                self.pool_state.allocate(&alloc, interruption.host_id);
+                self.vm_to_bid.insert(interruption.vm_id, bid.id);
+            }
+        }
+    }
+}
+
+impl CustomComponent for SpotMarket {
+    fn new(ctx: SimulationContext) -> Self {
+        Self {
+            vm_api: None,
+            sim_config: None,
+            placement_store_id: 0,
+            interval: 1.,
+            pricing: PricingRule::Uniform,
+            reserve_price: 0.,
+            pool_state: ResourcePoolState::new(),
+            total_cpu: 0,
+            bids: BTreeMap::new(),
+            vm_to_bid: BTreeMap::new(),
+            rounds: Vec::new(),
+            last_round_time: 0.,
+            revenue: 0.,
+            welfare: 0.,
+            listener: None,
+            ctx,
+        }
+    }
+
+    fn init(&mut self) {
+        assert!(
+            self.vm_api.is_some() && self.sim_config.is_some(),
+            "patch_custom_args should be invoked before init"
+        );
+        self.last_round_time = self.ctx.time();
+        self.ctx.emit_self(ClearMarket {}, 0.);
+    }
+}
+
+impl EventHandler for SpotMarket {
+    fn on(&mut self, event: Event) {
+        cast!(match event.data {
+            ClearMarket {} => {
+                self.clear_market();
+            }
+            PreemptionCommitFailed { vm_ids, victims, .. } => {
+                self.on_commit_failed(vm_ids, victims);
+            }
+        })
+    }
+}
@@ -33,6 +33,8 @@ struct StandardVmRequest {
     pub cpu_load_model: String,
     pub memory_load_model: String,
     pub scheduler: Option<String>,
+    pub tenant: Option<String>,
+    pub priority: Option<u32>,
     pub count: Option<u32>,
 }
 
@@ -59,6 +61,8 @@ impl StandardDatasetReader {
                     cpu_load_model: load_model_resolver(dataset_request.cpu_load_model.clone()),
                     memory_load_model: load_model_resolver(dataset_request.memory_load_model.clone()),
                     scheduler_name: dataset_request.scheduler.clone(),
+                    tenant: dataset_request.tenant.clone(),
+                    priority: dataset_request.priority.unwrap_or(0),
                 });
             }
         }
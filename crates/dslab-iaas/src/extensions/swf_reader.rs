@@ -0,0 +1,72 @@
+//! Reader of batch job traces in Standard Workload Format (SWF).
+//!
+//! The format is described at <https://www.cs.huji.ac.il/labs/parallel/workload/swf.html>. Each non-comment line of
+//! the trace describes a single job using 18 whitespace-separated fields, and the missing values are denoted by -1.
+//! Comment lines start with `;`.
+
+use std::fs;
+
+use crate::extensions::batch_scheduler::BatchJob;
+
+/// Reads batch jobs from SWF traces.
+///
+/// The number of nodes requested by job is computed from the number of requested processors (or allocated processors
+/// if the former is missing) divided by the number of processors per node. The job walltime is taken from the
+/// requested time field, or equals to the job runtime if the requested time is missing. The jobs with unknown
+/// runtime or processor count (e.g. cancelled before start) are skipped.
+pub struct SwfReader {
+    cores_per_node: u32,
+}
+
+impl SwfReader {
+    /// Creates reader for cluster with the specified number of processors per node.
+    pub fn new(cores_per_node: u32) -> Self {
+        assert!(cores_per_node > 0, "Number of processors per node should be positive");
+        Self { cores_per_node }
+    }
+
+    /// Reads jobs from the trace file.
+    pub fn read_file(&self, path: &str) -> Result<Vec<BatchJob>, String> {
+        let content = fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+        self.read_str(&content)
+    }
+
+    /// Reads jobs from the trace contents.
+    pub fn read_str(&self, content: &str) -> Result<Vec<BatchJob>, String> {
+        let mut jobs = Vec::new();
+        for (line_num, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with(';') {
+                continue;
+            }
+            let fields = line
+                .split_whitespace()
+                .map(|f| f.parse::<f64>())
+                .collect::<Result<Vec<f64>, _>>()
+                .map_err(|e| format!("Invalid value at line {}: {}", line_num + 1, e))?;
+            if fields.len() < 18 {
+                return Err(format!(
+                    "Expected 18 fields at line {}, found {}",
+                    line_num + 1,
+                    fields.len()
+                ));
+            }
+            let runtime = fields[3];
+            let processors = if fields[7] > 0. { fields[7] } else { fields[4] };
+            if runtime < 0. || processors <= 0. {
+                continue;
+            }
+            let walltime = if fields[8] > 0. { fields[8] } else { runtime };
+            jobs.push(BatchJob {
+                id: fields[0] as u32,
+                submit_time: fields[1].max(0.),
+                nodes: (processors as u32).div_ceil(self.cores_per_node),
+                walltime,
+                runtime,
+                queue: fields[14].max(0.) as u32,
+            });
+        }
+        jobs.sort_by(|a, b| a.submit_time.total_cmp(&b.submit_time));
+        Ok(jobs)
+    }
+}
@@ -0,0 +1,166 @@
+//! Synthetic workload generator.
+
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::prelude::*;
+use rand_pcg::Pcg64;
+
+use crate::core::load_model::LoadModel;
+use crate::extensions::dataset_reader::{DatasetReader, VMRequest};
+
+/// Type erased version of rand::Distribution trait.
+pub trait ErasedDistribution<T> {
+    /// Generate a random value of T, using rng as the source of randomness.
+    fn sample(&self, rng: &mut dyn RngCore) -> T;
+}
+
+impl<T, D: Distribution<T> + ?Sized> ErasedDistribution<T> for D {
+    fn sample(&self, rng: &mut dyn RngCore) -> T {
+        <Self as Distribution<T>>::sample(self, rng)
+    }
+}
+
+/// Generator of VM arrival times.
+pub enum ArrivalGenerator {
+    /// Random intervals between consecutive arrivals (e.g. exponential distribution for Poisson process).
+    Random(Box<dyn ErasedDistribution<f64>>),
+    /// Equally spaced arrivals with given interval between consecutive arrivals.
+    EquallySpaced(f64),
+}
+
+/// Generator of VM lifetimes.
+pub enum LifetimeGenerator {
+    /// Random lifetimes.
+    Random(Box<dyn ErasedDistribution<f64>>),
+    /// Equal lifetimes.
+    Equal(f64),
+}
+
+/// Generator of VM sizes.
+pub enum SizeGenerator {
+    /// Sizes are chosen from the list of VM flavors `(cpu_usage, memory_usage, weight)`
+    /// with probabilities proportional to the flavor weights.
+    Flavors(Vec<(u32, u64, f64)>),
+    /// Fixed size `(cpu_usage, memory_usage)`.
+    Fixed(u32, u64),
+}
+
+/// Settings of VM requests generated for a single tenant.
+pub struct SyntheticTenantConfig {
+    /// Tenant weight which defines the probability of each arriving VM to belong to this tenant.
+    pub weight: f64,
+    /// VM size generator.
+    pub size_generator: SizeGenerator,
+    /// VM lifetime generator.
+    pub lifetime_generator: LifetimeGenerator,
+    /// CPU load model of tenant VMs.
+    pub cpu_load_model: Box<dyn LoadModel>,
+    /// Memory load model of tenant VMs.
+    pub memory_load_model: Box<dyn LoadModel>,
+    /// Name of scheduler used for tenant VMs. If not set, the default scheduler is used.
+    pub scheduler_name: Option<String>,
+    /// Tenant name assigned to generated VMs (used to enforce tenant quotas).
+    pub tenant: Option<String>,
+    /// Priority of generated VMs.
+    pub priority: u32,
+}
+
+/// Synthetic workload generation settings.
+pub struct SyntheticWorkloadConfig {
+    /// Arrival times generator (shared by all tenants).
+    pub arrival_generator: ArrivalGenerator,
+    /// Time interval that will contain all arrival times.
+    pub activity_window: (f64, f64),
+    /// Maximum number of generated VMs (unlimited if not set).
+    pub max_vm_count: Option<u64>,
+    /// Tenant mix.
+    pub tenants: Vec<SyntheticTenantConfig>,
+    /// Random generator seed.
+    pub random_seed: u64,
+}
+
+/// Generates a stream of VM requests from the statistical parameters in [`SyntheticWorkloadConfig`].
+///
+/// The requests are generated lazily and the same seed always produces the same stream.
+/// Pass the generator to [`spawn_vms_from_dataset()`](crate::simulation::CloudSimulation::spawn_vms_from_dataset)
+/// to submit the generated VMs via VM API.
+pub struct SyntheticWorkloadGenerator {
+    config: SyntheticWorkloadConfig,
+    tenant_index: WeightedIndex<f64>,
+    flavor_indices: Vec<Option<WeightedIndex<f64>>>,
+    rng: Pcg64,
+    next_arrival: f64,
+    vm_count: u64,
+}
+
+impl SyntheticWorkloadGenerator {
+    /// Creates workload generator with the specified config.
+    pub fn new(config: SyntheticWorkloadConfig) -> Self {
+        let tenant_index = WeightedIndex::new(config.tenants.iter().map(|t| t.weight))
+            .expect("Tenant weights should be non-negative with positive sum");
+        let flavor_indices = config
+            .tenants
+            .iter()
+            .map(|t| match &t.size_generator {
+                SizeGenerator::Flavors(flavors) => Some(
+                    WeightedIndex::new(flavors.iter().map(|f| f.2))
+                        .expect("Flavor weights should be non-negative with positive sum"),
+                ),
+                SizeGenerator::Fixed(..) => None,
+            })
+            .collect();
+        let rng = Pcg64::seed_from_u64(config.random_seed);
+        let next_arrival = config.activity_window.0;
+        Self {
+            config,
+            tenant_index,
+            flavor_indices,
+            rng,
+            next_arrival,
+            vm_count: 0,
+        }
+    }
+}
+
+impl DatasetReader for SyntheticWorkloadGenerator {
+    fn get_next_vm(&mut self) -> Option<VMRequest> {
+        if self.next_arrival > self.config.activity_window.1
+            || self.config.max_vm_count.is_some_and(|max| self.vm_count >= max)
+        {
+            return None;
+        }
+        let start_time = self.next_arrival;
+        self.next_arrival += match &self.config.arrival_generator {
+            ArrivalGenerator::Random(dist) => dist.sample(&mut self.rng),
+            ArrivalGenerator::EquallySpaced(interval) => *interval,
+        };
+
+        let tenant_idx = Distribution::sample(&self.tenant_index, &mut self.rng);
+        let tenant = &self.config.tenants[tenant_idx];
+        let (cpu_usage, memory_usage) = match &tenant.size_generator {
+            SizeGenerator::Flavors(flavors) => {
+                let flavor =
+                    &flavors[Distribution::sample(self.flavor_indices[tenant_idx].as_ref().unwrap(), &mut self.rng)];
+                (flavor.0, flavor.1)
+            }
+            SizeGenerator::Fixed(cpu_usage, memory_usage) => (*cpu_usage, *memory_usage),
+        };
+        let lifetime = match &tenant.lifetime_generator {
+            LifetimeGenerator::Random(dist) => dist.sample(&mut self.rng),
+            LifetimeGenerator::Equal(lifetime) => *lifetime,
+        };
+        self.vm_count += 1;
+
+        Some(VMRequest {
+            id: None,
+            cpu_usage,
+            memory_usage,
+            lifetime,
+            start_time,
+            cpu_load_model: tenant.cpu_load_model.clone(),
+            memory_load_model: tenant.memory_load_model.clone(),
+            scheduler_name: tenant.scheduler_name.clone(),
+            tenant: tenant.tenant.clone(),
+            priority: tenant.priority,
+        })
+    }
+}
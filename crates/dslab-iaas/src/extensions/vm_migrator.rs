@@ -1,17 +1,21 @@
 //! Component performing automatic migration of VMs.
 
 use std::cell::RefCell;
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet, VecDeque};
 use std::rc::Rc;
 
 use serde::Serialize;
 
 use crate::core::config::sim_config::SimulationConfig;
 use crate::core::events::allocation::MigrationRequest;
-use crate::core::monitoring::Monitoring;
+use crate::core::events::power::{PowerOffRequest, SleepRequest};
+use crate::core::monitoring::{HostState, Monitoring};
+use crate::core::power_state::HostPowerState;
 use crate::core::vm::VmStatus;
 use crate::core::vm_api::VmAPI;
 use crate::custom_component::CustomComponent;
+use crate::extensions::overload_detection::OverloadDetector;
+use crate::extensions::vm_selection::{VmCandidate, VmSelectionPolicy};
 use dslab_core::cast;
 use dslab_core::context::SimulationContext;
 use dslab_core::event::Event;
@@ -21,15 +25,38 @@ use dslab_core::{log_debug, log_info, log_trace, log_warn};
 #[derive(Clone, Serialize)]
 pub struct PerformMigrations {}
 
+/// Action applied by migrator to active hosts without VMs.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum IdleHostAction {
+    /// Switch the host off.
+    PowerOff,
+    /// Put the host to sleep.
+    Sleep,
+}
+
 /// This component performs automatic migration of VMs to improve host utilization while avoiding SLA violations.
 ///
 /// It periodically checks the state of resource pool and tries to find the overloaded and underloaded hosts.
 /// If there are any, it selects some VMs from these hosts and migrates them to other hosts in order to turn off the
 /// underloaded hosts and return the overloaded hosts to the normal state.
+///
+/// By default, the host is considered overloaded if its CPU or memory load exceeds the fixed threshold,
+/// and VMs are selected for migration in the order of their IDs. These steps can be customized by setting
+/// the overload detection policy ([`OverloadDetector`]) and VM selection policy ([`VmSelectionPolicy`]),
+/// which use the history of host and VM CPU loads collected by the migrator.
+///
+/// If the idle host action is set, the migrator also switches off or puts to sleep the active hosts without VMs.
+/// Such hosts are switched back on when new VMs are allocated on them, which takes time and energy.
 pub struct VmMigrator {
     interval: f64,
     overload_threshold: f64,
     underload_threshold: f64,
+    overload_detector: Option<Box<dyn OverloadDetector>>,
+    vm_selection_policy: Option<Box<dyn VmSelectionPolicy>>,
+    history_length: usize,
+    idle_host_action: Option<IdleHostAction>,
+    host_history: BTreeMap<u32, VecDeque<f64>>,
+    vm_history: BTreeMap<u32, VecDeque<f64>>,
     monitoring: Option<Rc<RefCell<Monitoring>>>,
     vm_api: Option<Rc<RefCell<VmAPI>>>,
     sim_config: Option<Rc<SimulationConfig>>,
@@ -53,6 +80,63 @@ impl VmMigrator {
         self.sim_config = Some(sim_config);
     }
 
+    /// Sets the policy used to detect overloaded hosts instead of the fixed threshold.
+    pub fn set_overload_detector(&mut self, overload_detector: Box<dyn OverloadDetector>) {
+        self.overload_detector = Some(overload_detector);
+    }
+
+    /// Sets the policy used to select VMs for migration from overloaded hosts.
+    pub fn set_vm_selection_policy(&mut self, vm_selection_policy: Box<dyn VmSelectionPolicy>) {
+        self.vm_selection_policy = Some(vm_selection_policy);
+    }
+
+    /// Sets the maximum number of host and VM load values stored in history (30 by default).
+    pub fn set_history_length(&mut self, history_length: usize) {
+        self.history_length = history_length;
+    }
+
+    /// Sets the action applied to active hosts without VMs (by default such hosts are left active).
+    pub fn set_idle_host_action(&mut self, idle_host_action: IdleHostAction) {
+        self.idle_host_action = Some(idle_host_action);
+    }
+
+    /// Appends the current host and VM CPU loads to the history.
+    fn update_history(&mut self, host_states: &BTreeMap<u32, HostState>, vm_api: &VmAPI) {
+        let time = self.ctx.time();
+        let mut active_vms = HashSet::new();
+        for (host, state) in host_states.iter() {
+            push_bounded(
+                self.host_history.entry(*host).or_default(),
+                state.cpu_load,
+                self.history_length,
+            );
+            for vm_id in state.vms.iter() {
+                let load = vm_api.get_vm(*vm_id).borrow().get_cpu_load(time);
+                push_bounded(self.vm_history.entry(*vm_id).or_default(), load, self.history_length);
+                active_vms.insert(*vm_id);
+            }
+        }
+        self.vm_history.retain(|vm_id, _| active_vms.contains(vm_id));
+    }
+
+    /// Checks if the host is overloaded with the specified current CPU and memory loads.
+    fn is_overloaded(&self, host: u32, cpu_load: f64, memory_load: f64) -> bool {
+        match &self.overload_detector {
+            Some(detector) => {
+                let mut history: Vec<f64> = self
+                    .host_history
+                    .get(&host)
+                    .map(|h| h.iter().cloned().collect())
+                    .unwrap_or_default();
+                // the latest value is replaced by the specified load
+                history.pop();
+                history.push(cpu_load);
+                detector.is_overloaded(&history)
+            }
+            None => cpu_load > self.overload_threshold || memory_load > self.overload_threshold,
+        }
+    }
+
     /// Periodic process, which finds hosts and performs VM migrations.
     fn perform_migrations(&mut self) {
         if self.monitoring.is_none() {
@@ -67,9 +151,10 @@ impl VmMigrator {
             log_trace!(self.ctx, "perform migrations");
         }
 
-        let vm_api = self.vm_api.as_ref().unwrap().borrow();
-        let mon = self.monitoring.as_ref().unwrap().borrow();
-        let mut host_states = mon.get_host_states().clone();
+        let vm_api_rc = self.vm_api.clone().unwrap();
+        let vm_api = vm_api_rc.borrow();
+        let mut host_states = self.monitoring.as_ref().unwrap().borrow().get_host_states().clone();
+        self.update_history(&host_states, &vm_api);
 
         // select VMs to migrate ---------------------------------------------------------------------------------------
 
@@ -100,7 +185,7 @@ impl VmMigrator {
                 }
             }
             // host is overloaded
-            if state.cpu_load > self.overload_threshold || state.memory_load > self.overload_threshold {
+            if self.is_overloaded(*host, state.cpu_load, state.memory_load) {
                 log_debug!(
                     self.ctx,
                     "host {} is overloaded ({} load, {} vms)",
@@ -112,20 +197,39 @@ impl VmMigrator {
                 let mut cpu_usage = state.cpu_load * (state.cpu_total as f64);
                 let mut memory_usage = state.memory_load * (state.memory_total as f64);
 
-                for vm_id in state.vms.iter() {
-                    let status = vm_api.get_vm_status(*vm_id);
-                    let vm = vm_api.get_vm(*vm_id).borrow().clone();
-                    if status != VmStatus::Running {
-                        continue;
-                    }
-                    vms_to_migrate.push((*vm_id, *host));
+                let mut candidates: Vec<VmCandidate> = state
+                    .vms
+                    .iter()
+                    .filter(|vm_id| vm_api.get_vm_status(**vm_id) == VmStatus::Running)
+                    .map(|vm_id| {
+                        let vm = vm_api.get_vm(*vm_id).borrow().clone();
+                        VmCandidate {
+                            vm_id: *vm_id,
+                            cpu_usage: vm.cpu_usage,
+                            memory_usage: vm.memory_usage,
+                            cpu_history: self
+                                .vm_history
+                                .get(vm_id)
+                                .map(|h| h.iter().cloned().collect())
+                                .unwrap_or_default(),
+                        }
+                    })
+                    .collect();
+
+                while !candidates.is_empty() {
+                    let idx = match &self.vm_selection_policy {
+                        Some(policy) => policy.select_vm(&candidates, &self.ctx),
+                        None => 0,
+                    };
+                    let vm = candidates.remove(idx);
+                    vms_to_migrate.push((vm.vm_id, *host));
 
                     cpu_usage -= vm.cpu_usage as f64;
                     memory_usage -= vm.memory_usage as f64;
                     let new_cpu_load = cpu_usage / (state.cpu_total as f64);
                     let new_memory_load = memory_usage / (state.memory_total as f64);
 
-                    if new_cpu_load <= self.overload_threshold && new_memory_load <= self.overload_threshold {
+                    if !self.is_overloaded(*host, new_cpu_load, new_memory_load) {
                         break;
                     }
                 }
@@ -151,7 +255,11 @@ impl VmMigrator {
             let vm = vm_api.get_vm(vm_id).borrow().clone();
 
             for (host, state) in host_states.iter() {
-                if *host == source_host {
+                if *host == source_host || state.power_state != HostPowerState::Active {
+                    continue;
+                }
+                // VM with local volumes can run only on the host storing them
+                if !vm_api.is_host_eligible(vm_id, *host) {
                     continue;
                 }
                 // do not use source hosts as targets
@@ -241,17 +349,46 @@ impl VmMigrator {
             }
         }
 
+        // switch off idle hosts ---------------------------------------------------------------------------------------
+
+        if let Some(action) = self.idle_host_action {
+            let message_delay = self.sim_config.as_ref().unwrap().message_delay;
+            for (host, state) in host_states.iter() {
+                if state.power_state != HostPowerState::Active || !state.vms.is_empty() || target_hosts.contains(host) {
+                    continue;
+                }
+                log_info!(self.ctx, "switch idle host {} to {:?} state", host, action);
+                match action {
+                    IdleHostAction::PowerOff => self.ctx.emit(PowerOffRequest {}, *host, message_delay),
+                    IdleHostAction::Sleep => self.ctx.emit(SleepRequest {}, *host, message_delay),
+                };
+            }
+        }
+
         // schedule the next migration attempt
         self.ctx.emit_self(PerformMigrations {}, self.interval);
     }
 }
 
+fn push_bounded(history: &mut VecDeque<f64>, value: f64, max_len: usize) {
+    history.push_back(value);
+    while history.len() > max_len {
+        history.pop_front();
+    }
+}
+
 impl CustomComponent for VmMigrator {
     fn new(ctx: SimulationContext) -> Self {
         Self {
             interval: 1.,
             overload_threshold: 0.8,
             underload_threshold: 0.4,
+            overload_detector: None,
+            vm_selection_policy: None,
+            history_length: 30,
+            idle_host_action: None,
+            host_history: BTreeMap::new(),
+            vm_history: BTreeMap::new(),
             monitoring: None,
             vm_api: None,
             sim_config: None,
@@ -0,0 +1,121 @@
+//! Policies selecting VMs to migrate from overloaded hosts.
+//!
+//! The policies follow the heuristics from Beloglazov and Buyya, "Optimal online deterministic algorithms and adaptive
+//! heuristics for energy and performance efficient dynamic consolidation of virtual machines in Cloud data centers"
+//! (2012).
+
+use dslab_core::context::SimulationContext;
+
+/// Information about VM which can be selected for migration.
+#[derive(Clone, Debug)]
+pub struct VmCandidate {
+    pub vm_id: u32,
+    pub cpu_usage: u32,
+    pub memory_usage: u64,
+    /// History of VM CPU load ordered from the oldest to the latest value.
+    pub cpu_history: Vec<f64>,
+}
+
+/// Trait for implementation of VM selection policies.
+pub trait VmSelectionPolicy {
+    /// Returns the index of VM selected for migration among the specified (non-empty) list of candidates.
+    fn select_vm(&self, candidates: &[VmCandidate], ctx: &SimulationContext) -> usize;
+}
+
+/// Minimum Migration Time (MMT) policy selects the VM with the least memory usage,
+/// which requires the least time to migrate.
+#[derive(Default)]
+pub struct MinimumMigrationTime;
+
+impl MinimumMigrationTime {
+    pub fn new() -> Self {
+        Default::default()
+    }
+}
+
+impl VmSelectionPolicy for MinimumMigrationTime {
+    fn select_vm(&self, candidates: &[VmCandidate], _ctx: &SimulationContext) -> usize {
+        let mut result = 0;
+        for (i, vm) in candidates.iter().enumerate() {
+            if vm.memory_usage < candidates[result].memory_usage {
+                result = i;
+            }
+        }
+        result
+    }
+}
+
+/// Random Selection (RS) policy selects a random VM using the simulation random generator.
+#[derive(Default)]
+pub struct RandomSelection;
+
+impl RandomSelection {
+    pub fn new() -> Self {
+        Default::default()
+    }
+}
+
+impl VmSelectionPolicy for RandomSelection {
+    fn select_vm(&self, candidates: &[VmCandidate], ctx: &SimulationContext) -> usize {
+        ctx.gen_range(0..candidates.len())
+    }
+}
+
+/// Maximum Correlation (MC) policy selects the VM whose CPU load is the most correlated with the load of other VMs
+/// on the host, since such VMs are more likely to cause host overload.
+///
+/// The correlation of VM is estimated as the sum of Pearson correlation coefficients between its CPU load history
+/// and the histories of other candidates (over their common latest values).
+#[derive(Default)]
+pub struct MaximumCorrelation;
+
+impl MaximumCorrelation {
+    pub fn new() -> Self {
+        Default::default()
+    }
+}
+
+impl VmSelectionPolicy for MaximumCorrelation {
+    fn select_vm(&self, candidates: &[VmCandidate], _ctx: &SimulationContext) -> usize {
+        let mut result = 0;
+        let mut max_correlation = f64::NEG_INFINITY;
+        for (i, vm) in candidates.iter().enumerate() {
+            let correlation: f64 = candidates
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .map(|(_, other)| pearson_correlation(&vm.cpu_history, &other.cpu_history))
+                .sum();
+            if correlation > max_correlation {
+                max_correlation = correlation;
+                result = i;
+            }
+        }
+        result
+    }
+}
+
+/// Computes Pearson correlation coefficient over the common latest values of two series.
+/// Returns 0 if the correlation is not defined.
+fn pearson_correlation(x: &[f64], y: &[f64]) -> f64 {
+    let n = x.len().min(y.len());
+    if n < 2 {
+        return 0.;
+    }
+    let x = &x[x.len() - n..];
+    let y = &y[y.len() - n..];
+    let x_mean = x.iter().sum::<f64>() / n as f64;
+    let y_mean = y.iter().sum::<f64>() / n as f64;
+    let mut cov = 0.;
+    let mut x_var = 0.;
+    let mut y_var = 0.;
+    for (a, b) in x.iter().zip(y) {
+        cov += (a - x_mean) * (b - y_mean);
+        x_var += (a - x_mean).powi(2);
+        y_var += (b - y_mean).powi(2);
+    }
+    if x_var == 0. || y_var == 0. {
+        return 0.;
+    }
+    cov / (x_var * y_var).sqrt()
+}
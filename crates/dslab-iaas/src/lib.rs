@@ -4,4 +4,7 @@ pub mod core;
 pub mod custom_component;
 pub mod experiment;
 pub mod extensions;
+pub mod scenario;
 pub mod simulation;
+pub mod sweep;
+pub mod validation;
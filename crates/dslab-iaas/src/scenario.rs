@@ -0,0 +1,896 @@
+//! Composition of cloud simulation with network model from a single scenario description.
+//!
+//! [`ScenarioBuilder`] creates the cloud simulation, the network connecting the hosts and binds the hosts
+//! to the network nodes. Before creating anything, it validates the cross-references between the parts of
+//! the scenario (host and node names, placement algorithms, etc), so that a misconfigured experiment fails early
+//! with a descriptive error instead of panicking in the middle of the setup. The scenario can be described
+//! in a YAML file or assembled programmatically:
+//!
+//! ```yaml
+//! # path to simulation config, relative to this file
+//! simulation: config.yaml
+//! hosts:
+//!   - name_prefix: h
+//!     cpus: 16
+//!     memory: 32
+//!     count: 2
+//! schedulers:
+//!   - name: s
+//!     algorithm: TrafficAware
+//! network:
+//!   model: TopologyAware
+//!   nodes:
+//!     - name: rack1
+//!       local_bandwidth: 1000
+//!     - name: rack2
+//!       local_bandwidth: 1000
+//!   links:
+//!     - node1: rack1
+//!       node2: rack2
+//!       bandwidth: 100
+//!       latency: 0.001
+//! host_nodes:
+//!   h1: rack1
+//!   h2: rack2
+//! # custom components created by the factories registered in the builder
+//! components:
+//!   - name: migrator
+//!     type: VmMigrator
+//!     options: interval=5
+//! workloads:
+//!   - type: Vms
+//!     scheduler: s
+//!     vms:
+//!       - cpus: 4
+//!         memory: 8
+//!         lifetime: 100
+//!         count: 10
+//!         interval: 1
+//!   - type: Synthetic
+//!     scheduler: s
+//!     arrival_rate: 0.5
+//!     window: [0, 1000]
+//!     flavors: [[2, 4, 0.7], [8, 16, 0.3]]
+//!     lifetime: 50
+//! faults:
+//!   - target: h2
+//!     fault:
+//!       type: Crash
+//!     trigger:
+//!       type: At
+//!       time: 500
+//!     duration: 100
+//! outputs:
+//!   metrics:
+//!     prefix: results/metrics
+//!     interval: 10
+//!   log: results/log.csv
+//! duration: 1000
+//! ```
+//!
+//! The parts of the experiment which cannot be described declaratively (e.g. custom components) are created
+//! by the factories registered via [`ScenarioBuilder::register_component`], and the assembled [`Scenario`]
+//! remains accessible for further programmatic setup before calling [`Scenario::run`].
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+use std::rc::Rc;
+
+use rand::distributions::Distribution;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use dslab_core::faults::{FaultInjector, FaultScenario, FaultSpec};
+use dslab_core::simulation::Simulation;
+use dslab_network::models::{ConstantBandwidthNetworkModel, SharedBandwidthNetworkModel, TopologyAwareNetworkModel};
+use dslab_network::{Link, Network, NetworkModel};
+
+use crate::core::config::options::parse_config_value;
+use crate::core::config::sim_config::{HostConfig, SchedulerConfig, SimulationConfig};
+use crate::core::config::yaml::parse_yaml_config;
+use crate::core::load_model::ConstantLoadModel;
+use crate::core::logger::{FileLogger, Logger, StdoutLogger};
+use crate::core::vm::ResourceConsumer;
+use crate::core::vm_placement_algorithm::PlacementAlgorithmRegistry;
+use crate::custom_component::CustomComponent;
+use crate::extensions::metrics_exporter::{MetricsExporter, MetricsFormat};
+use crate::extensions::synthetic_workload::{
+    ArrivalGenerator, LifetimeGenerator, SizeGenerator, SyntheticTenantConfig, SyntheticWorkloadConfig,
+    SyntheticWorkloadGenerator,
+};
+use crate::simulation::CloudSimulation;
+
+/// Names of components created by the cloud simulation itself.
+const RESERVED_NAMES: [&str; 5] = ["vm_api", "monitoring", "placement_store", "simulation", "network"];
+
+/// Type of the network model.
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone, Copy)]
+pub enum NetworkModelType {
+    /// Topology-unaware model without contention, see [`ConstantBandwidthNetworkModel`].
+    Constant,
+    /// Topology-unaware model with fair sharing of bandwidth, see [`SharedBandwidthNetworkModel`].
+    Shared,
+    /// Topology-aware model using the configured links, see [`TopologyAwareNetworkModel`].
+    TopologyAware,
+}
+
+/// Holds configuration of a network node.
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct NetworkNodeConfig {
+    /// Node name.
+    pub name: String,
+    /// Bandwidth of communications within the node.
+    pub local_bandwidth: f64,
+    /// Latency of communications within the node (zero by default).
+    pub local_latency: Option<f64>,
+}
+
+/// Holds configuration of a link between two network nodes.
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct NetworkLinkConfig {
+    /// Name of the first node.
+    pub node1: String,
+    /// Name of the second node.
+    pub node2: String,
+    /// Link bandwidth.
+    pub bandwidth: f64,
+    /// Link latency.
+    pub latency: f64,
+}
+
+/// Holds configuration of the network connecting the hosts.
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct NetworkConfig {
+    /// Network model.
+    pub model: NetworkModelType,
+    /// Network bandwidth, required by topology-unaware models.
+    pub bandwidth: Option<f64>,
+    /// Network latency, required by topology-unaware models.
+    pub latency: Option<f64>,
+    /// Network nodes.
+    pub nodes: Vec<NetworkNodeConfig>,
+    /// Links between the nodes, supported only by topology-aware model.
+    #[serde(default)]
+    pub links: Vec<NetworkLinkConfig>,
+}
+
+/// Holds configuration of a custom component created by the factory registered in [`ScenarioBuilder`].
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ComponentConfig {
+    /// Component name.
+    pub name: String,
+    /// Component type used to find the factory.
+    pub r#type: String,
+    /// Component options passed to the factory, e.g. `interval=5,threshold=0.8` (empty by default).
+    pub options: Option<String>,
+}
+
+/// Holds configuration of a group of identical VMs.
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct VmGroupConfig {
+    /// VM CPU usage.
+    pub cpus: u32,
+    /// VM memory usage.
+    pub memory: u64,
+    /// VM lifetime.
+    pub lifetime: f64,
+    /// Submission time of the first VM (zero by default).
+    pub start: Option<f64>,
+    /// Number of VMs (one by default).
+    pub count: Option<u32>,
+    /// Time between submissions of consecutive VMs (zero by default).
+    pub interval: Option<f64>,
+}
+
+/// Holds configuration of workload submitted to a scheduler.
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[serde(tag = "type")]
+pub enum WorkloadConfig {
+    /// Explicitly listed VMs fully loading their resources.
+    Vms {
+        /// Name of scheduler receiving the VM requests.
+        scheduler: String,
+        /// VM groups.
+        vms: Vec<VmGroupConfig>,
+    },
+    /// Synthetic workload generated by [`SyntheticWorkloadGenerator`] with VMs fully loading their resources.
+    Synthetic {
+        /// Name of scheduler receiving the VM requests.
+        scheduler: String,
+        /// Interval between equally spaced arrivals, exclusive with `arrival_rate`.
+        arrival_interval: Option<f64>,
+        /// Rate of Poisson arrivals, exclusive with `arrival_interval`.
+        arrival_rate: Option<f64>,
+        /// Time interval containing all arrivals.
+        window: (f64, f64),
+        /// Maximum number of generated VMs (unlimited if not set).
+        max_vm_count: Option<u64>,
+        /// VM flavors `(cpu_usage, memory_usage, weight)`.
+        flavors: Vec<(u32, u64, f64)>,
+        /// VM lifetime.
+        lifetime: f64,
+        /// Random generator seed (zero by default).
+        seed: Option<u64>,
+    },
+}
+
+/// Holds configuration of periodic metrics export, see [`MetricsExporter`].
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct MetricsOutputConfig {
+    /// Path prefix of output files.
+    pub prefix: String,
+    /// Sampling interval.
+    pub interval: f64,
+    /// Output format (`Csv` by default).
+    pub format: Option<MetricsFormat>,
+}
+
+/// Holds configuration of scenario outputs. The paths are relative to the working directory.
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct OutputsConfig {
+    /// Periodic export of host and scheduler metrics.
+    pub metrics: Option<MetricsOutputConfig>,
+    /// Path of simulation log file saved after the run.
+    pub log: Option<String>,
+}
+
+/// Holds raw scenario description parsed from YAML file.
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+struct RawScenarioConfig {
+    pub simulation: String,
+    pub hosts: Option<Vec<HostConfig>>,
+    pub schedulers: Option<Vec<SchedulerConfig>>,
+    pub network: Option<NetworkConfig>,
+    pub host_nodes: Option<BTreeMap<String, String>>,
+    pub components: Option<Vec<ComponentConfig>>,
+    pub workloads: Option<Vec<WorkloadConfig>>,
+    pub faults: Option<Vec<FaultSpec>>,
+    pub outputs: Option<OutputsConfig>,
+    pub duration: Option<f64>,
+}
+
+/// Function which creates a custom component with the specified name and options in the scenario
+/// and returns the component ID.
+pub type ComponentFactory = Box<dyn Fn(&mut Scenario, &str, &str) -> Result<u32, String>>;
+
+/// Parts of the assembled scenario.
+pub struct Scenario {
+    /// Cloud simulation.
+    pub cloud: CloudSimulation,
+    /// Network connecting the hosts, if configured.
+    pub network: Option<Rc<RefCell<Network>>>,
+    /// Fault injector, if faults are configured.
+    pub faults: Option<Rc<RefCell<FaultInjector>>>,
+    /// Exporter of metrics, if configured in outputs.
+    pub metrics_exporter: Option<Rc<RefCell<MetricsExporter>>>,
+    hosts: BTreeMap<String, u32>,
+    schedulers: BTreeMap<String, u32>,
+    components: BTreeMap<String, u32>,
+    outputs: OutputsConfig,
+    duration: Option<f64>,
+}
+
+impl Scenario {
+    /// Returns the IDs of hosts by their names.
+    pub fn hosts(&self) -> &BTreeMap<String, u32> {
+        &self.hosts
+    }
+
+    /// Returns the ID of host with specified name.
+    pub fn host_id(&self, name: &str) -> u32 {
+        *self
+            .hosts
+            .get(name)
+            .unwrap_or_else(|| panic!("Host {} is not found", name))
+    }
+
+    /// Returns the IDs of schedulers by their names.
+    pub fn schedulers(&self) -> &BTreeMap<String, u32> {
+        &self.schedulers
+    }
+
+    /// Returns the ID of scheduler with specified name.
+    pub fn scheduler_id(&self, name: &str) -> u32 {
+        *self
+            .schedulers
+            .get(name)
+            .unwrap_or_else(|| panic!("Scheduler {} is not found", name))
+    }
+
+    /// Returns the IDs of custom components by their names.
+    pub fn components(&self) -> &BTreeMap<String, u32> {
+        &self.components
+    }
+
+    /// Returns the ID of custom component with specified name.
+    pub fn component_id(&self, name: &str) -> u32 {
+        *self
+            .components
+            .get(name)
+            .unwrap_or_else(|| panic!("Component {} is not found", name))
+    }
+
+    /// Returns the scenario duration, if specified.
+    pub fn duration(&self) -> Option<f64> {
+        self.duration
+    }
+
+    /// Runs the simulation until the end of scenario duration and writes the outputs.
+    pub fn run(&mut self) -> Result<(), String> {
+        let duration = self
+            .duration
+            .ok_or_else(|| "Scenario duration is not specified".to_string())?;
+        self.cloud.step_until_time(duration);
+        self.write_outputs()
+    }
+
+    /// Flushes the exported metrics and saves the simulation log according to the outputs config.
+    pub fn write_outputs(&mut self) -> Result<(), String> {
+        if let Some(exporter) = &self.metrics_exporter {
+            exporter
+                .borrow_mut()
+                .flush()
+                .map_err(|e| format!("Cannot write metrics: {}", e))?;
+        }
+        if let Some(log) = &self.outputs.log {
+            self.cloud
+                .save_log(log)
+                .map_err(|e| format!("Cannot save log to {}: {}", log, e))?;
+        }
+        Ok(())
+    }
+}
+
+/// Builder of cloud simulation combined with network model.
+pub struct ScenarioBuilder {
+    sim_config: SimulationConfig,
+    network: Option<NetworkConfig>,
+    host_nodes: BTreeMap<String, String>,
+    components: Vec<ComponentConfig>,
+    workloads: Vec<WorkloadConfig>,
+    faults: Vec<FaultSpec>,
+    outputs: OutputsConfig,
+    duration: Option<f64>,
+    component_factories: BTreeMap<String, ComponentFactory>,
+    placement_algorithms: PlacementAlgorithmRegistry,
+    logger: Option<Box<dyn Logger>>,
+}
+
+impl ScenarioBuilder {
+    /// Creates a builder of scenario based on the specified simulation config.
+    ///
+    /// The hosts and schedulers from the config are included into the scenario.
+    pub fn new(sim_config: SimulationConfig) -> Self {
+        Self {
+            sim_config,
+            network: None,
+            host_nodes: BTreeMap::new(),
+            components: Vec::new(),
+            workloads: Vec::new(),
+            faults: Vec::new(),
+            outputs: OutputsConfig::default(),
+            duration: None,
+            component_factories: BTreeMap::new(),
+            placement_algorithms: PlacementAlgorithmRegistry::new(),
+            logger: None,
+        }
+    }
+
+    /// Creates a builder from scenario description in YAML file.
+    pub fn from_file(file_name: &str) -> Result<Self, String> {
+        let raw: RawScenarioConfig = parse_yaml_config(file_name)?;
+        let sim_config_path = Path::new(file_name)
+            .parent()
+            .unwrap_or_else(|| Path::new(""))
+            .join(&raw.simulation);
+        let sim_config = SimulationConfig::try_from_file(&sim_config_path.to_string_lossy())?;
+        let mut builder = Self::new(sim_config);
+        for host in raw.hosts.unwrap_or_default() {
+            builder = builder.host(host);
+        }
+        for scheduler in raw.schedulers.unwrap_or_default() {
+            builder = builder.scheduler(scheduler);
+        }
+        if let Some(network) = raw.network {
+            builder = builder.network(network);
+        }
+        for (host, node) in raw.host_nodes.unwrap_or_default() {
+            builder = builder.bind_host(&host, &node);
+        }
+        for component in raw.components.unwrap_or_default() {
+            builder = builder.component(component);
+        }
+        for workload in raw.workloads.unwrap_or_default() {
+            builder = builder.workload(workload);
+        }
+        for fault in raw.faults.unwrap_or_default() {
+            builder = builder.fault(fault);
+        }
+        if let Some(outputs) = raw.outputs {
+            builder = builder.outputs(outputs);
+        }
+        if let Some(duration) = raw.duration {
+            builder = builder.duration(duration);
+        }
+        Ok(builder)
+    }
+
+    /// Adds a host or a set of identical hosts.
+    pub fn host(mut self, host: HostConfig) -> Self {
+        self.sim_config.hosts.push(host);
+        self
+    }
+
+    /// Adds a scheduler or a set of identically configured schedulers.
+    pub fn scheduler(mut self, scheduler: SchedulerConfig) -> Self {
+        self.sim_config.schedulers.push(scheduler);
+        self
+    }
+
+    /// Sets the network connecting the hosts.
+    pub fn network(mut self, network: NetworkConfig) -> Self {
+        self.network = Some(network);
+        self
+    }
+
+    /// Binds the host to the network node.
+    pub fn bind_host(mut self, host: &str, node: &str) -> Self {
+        self.host_nodes.insert(host.to_string(), node.to_string());
+        self
+    }
+
+    /// Adds a custom component.
+    pub fn component(mut self, component: ComponentConfig) -> Self {
+        self.components.push(component);
+        self
+    }
+
+    /// Adds a workload.
+    pub fn workload(mut self, workload: WorkloadConfig) -> Self {
+        self.workloads.push(workload);
+        self
+    }
+
+    /// Adds a fault injected during the simulation.
+    pub fn fault(mut self, fault: FaultSpec) -> Self {
+        self.faults.push(fault);
+        self
+    }
+
+    /// Sets the scenario outputs.
+    pub fn outputs(mut self, outputs: OutputsConfig) -> Self {
+        self.outputs = outputs;
+        self
+    }
+
+    /// Sets the scenario duration used by [`Scenario::run`].
+    pub fn duration(mut self, duration: f64) -> Self {
+        self.duration = Some(duration);
+        self
+    }
+
+    /// Registers the factory of custom components of the specified type, which can be referenced
+    /// in the scenario description.
+    ///
+    /// The factory is invoked after creating the hosts, schedulers and network, and before submitting
+    /// the workloads and installing the faults, so the components can be used as fault targets.
+    pub fn register_component<F>(mut self, component_type: &str, factory: F) -> Self
+    where
+        F: Fn(&mut Scenario, &str, &str) -> Result<u32, String> + 'static,
+    {
+        self.component_factories
+            .insert(component_type.to_string(), Box::new(factory));
+        self
+    }
+
+    /// Sets the registry used to resolve the placement algorithms of schedulers.
+    pub fn placement_algorithms(mut self, placement_algorithms: PlacementAlgorithmRegistry) -> Self {
+        self.placement_algorithms = placement_algorithms;
+        self
+    }
+
+    /// Sets the logger of cloud simulation ([`StdoutLogger`] by default).
+    pub fn logger(mut self, logger: Box<dyn Logger>) -> Self {
+        self.logger = Some(logger);
+        self
+    }
+
+    /// Checks the scenario description and cross-references between its parts.
+    pub fn validate(&self) -> Result<(), String> {
+        self.sim_config.validate()?;
+
+        let mut names = BTreeSet::new();
+        let mut check_name = |kind: &str, name: &str| {
+            if RESERVED_NAMES.contains(&name) {
+                return Err(format!("{} name `{}` is reserved", kind, name));
+            }
+            if !names.insert(name.to_string()) {
+                return Err(format!("Duplicate component name `{}`", name));
+            }
+            Ok(())
+        };
+        let hosts = host_names(&self.sim_config.hosts)?;
+        for host in &hosts {
+            check_name("Host", host)?;
+        }
+        for scheduler in &self.sim_config.schedulers {
+            for name in component_names("scheduler", &scheduler.name, &scheduler.name_prefix, scheduler.count)? {
+                check_name("Scheduler", &name)?;
+            }
+            let (algorithm, _) = parse_config_value(&scheduler.algorithm);
+            if !self.placement_algorithms.contains(&algorithm) {
+                return Err(format!("Unknown placement algorithm `{}`", scheduler.algorithm));
+            }
+        }
+
+        let mut nodes = BTreeSet::new();
+        if let Some(network) = &self.network {
+            match network.model {
+                NetworkModelType::Constant | NetworkModelType::Shared => {
+                    if network.bandwidth.is_none() || network.latency.is_none() {
+                        return Err(format!(
+                            "Network model {:?} requires `bandwidth` and `latency`",
+                            network.model
+                        ));
+                    }
+                    if !network.links.is_empty() {
+                        return Err(format!("Network model {:?} does not support links", network.model));
+                    }
+                }
+                NetworkModelType::TopologyAware => {}
+            }
+            for node in &network.nodes {
+                if !nodes.insert(node.name.as_str()) {
+                    return Err(format!("Duplicate network node `{}`", node.name));
+                }
+                if node.local_bandwidth <= 0. {
+                    return Err(format!(
+                        "Local bandwidth of network node `{}` should be positive",
+                        node.name
+                    ));
+                }
+            }
+            for link in &network.links {
+                for node in [&link.node1, &link.node2] {
+                    if !nodes.contains(node.as_str()) {
+                        return Err(format!(
+                            "Link {} - {} refers to unknown network node `{}`",
+                            link.node1, link.node2, node
+                        ));
+                    }
+                }
+                if link.bandwidth <= 0. || link.latency < 0. {
+                    return Err(format!(
+                        "Link {} - {} should have positive bandwidth and non-negative latency",
+                        link.node1, link.node2
+                    ));
+                }
+            }
+        }
+        for (host, node) in &self.host_nodes {
+            if self.network.is_none() {
+                return Err(format!(
+                    "Host `{}` is bound to node `{}`, but network is not configured",
+                    host, node
+                ));
+            }
+            if !hosts.contains(host) {
+                return Err(format!("Unknown host `{}` is bound to network node `{}`", host, node));
+            }
+            if !nodes.contains(node.as_str()) {
+                return Err(format!("Host `{}` is bound to unknown network node `{}`", host, node));
+            }
+        }
+
+        for component in &self.components {
+            check_name("Component", &component.name)?;
+            if !self.component_factories.contains_key(&component.r#type) {
+                return Err(format!(
+                    "Unknown type `{}` of component `{}`",
+                    component.r#type, component.name
+                ));
+            }
+        }
+        for workload in &self.workloads {
+            validate_workload(workload, &names)?;
+        }
+        for fault in &self.faults {
+            if !names.contains(&fault.target) && !RESERVED_NAMES.contains(&fault.target.as_str()) {
+                return Err(format!("Fault refers to unknown target `{}`", fault.target));
+            }
+        }
+        if let Some(metrics) = &self.outputs.metrics {
+            if metrics.interval <= 0. {
+                return Err("Metrics export interval should be positive".to_string());
+            }
+        }
+        if self.duration.is_some_and(|duration| duration <= 0.) {
+            return Err("Scenario duration should be positive".to_string());
+        }
+        Ok(())
+    }
+
+    /// Validates the scenario and creates all its parts in the specified simulation.
+    pub fn build(self, sim: Simulation) -> Result<Scenario, String> {
+        self.validate()?;
+        let hosts = host_names(&self.sim_config.hosts)?;
+        let mut schedulers = Vec::new();
+        for scheduler in &self.sim_config.schedulers {
+            schedulers.extend(component_names(
+                "scheduler",
+                &scheduler.name,
+                &scheduler.name_prefix,
+                scheduler.count,
+            )?);
+        }
+
+        let logger = self.logger.unwrap_or_else(|| {
+            if self.outputs.log.is_some() {
+                Box::new(FileLogger::new())
+            } else {
+                Box::new(StdoutLogger::new())
+            }
+        });
+        let mut cloud =
+            CloudSimulation::with_placement_algorithms(sim, self.sim_config, logger, self.placement_algorithms);
+        let network = self.network.map(|config| {
+            let model: Box<dyn NetworkModel> = match config.model {
+                NetworkModelType::Constant => Box::new(ConstantBandwidthNetworkModel::new(
+                    config.bandwidth.unwrap(),
+                    config.latency.unwrap(),
+                )),
+                NetworkModelType::Shared => Box::new(SharedBandwidthNetworkModel::new(
+                    config.bandwidth.unwrap(),
+                    config.latency.unwrap(),
+                )),
+                NetworkModelType::TopologyAware => Box::new(TopologyAwareNetworkModel::new()),
+            };
+            let network = cloud.create_network(model);
+            {
+                let mut network = network.borrow_mut();
+                for node in &config.nodes {
+                    let local_model =
+                        SharedBandwidthNetworkModel::new(node.local_bandwidth, node.local_latency.unwrap_or(0.));
+                    network.add_node(node.name.clone(), Box::new(local_model));
+                }
+                for link in &config.links {
+                    network.add_link(&link.node1, &link.node2, Link::shared(link.bandwidth, link.latency));
+                }
+                if config.model == NetworkModelType::TopologyAware {
+                    network.init_topology();
+                }
+            }
+            network
+        });
+
+        let hosts: BTreeMap<String, u32> = hosts
+            .into_iter()
+            .map(|name| {
+                let id = cloud.lookup_id(&name);
+                (name, id)
+            })
+            .collect();
+        for (host, node) in &self.host_nodes {
+            cloud.set_host_network_node(hosts[host], node);
+        }
+        let schedulers = schedulers
+            .into_iter()
+            .map(|name| {
+                let id = cloud.lookup_id(&name);
+                (name, id)
+            })
+            .collect();
+        let mut scenario = Scenario {
+            cloud,
+            network,
+            faults: None,
+            metrics_exporter: None,
+            hosts,
+            schedulers,
+            components: BTreeMap::new(),
+            outputs: self.outputs,
+            duration: self.duration,
+        };
+
+        for component in &self.components {
+            let factory = &self.component_factories[&component.r#type];
+            let id = factory(
+                &mut scenario,
+                &component.name,
+                component.options.as_deref().unwrap_or(""),
+            )
+            .map_err(|e| format!("Cannot create component `{}`: {}", component.name, e))?;
+            scenario.components.insert(component.name.clone(), id);
+        }
+
+        if let Some(metrics) = scenario.outputs.metrics.clone() {
+            let cloud = &mut scenario.cloud;
+            let exporter = cloud.build_custom_component::<MetricsExporter>("metrics_exporter");
+            exporter
+                .borrow_mut()
+                .patch_custom_args(
+                    metrics.interval,
+                    &metrics.prefix,
+                    metrics.format.unwrap_or(MetricsFormat::Csv),
+                    cloud.monitoring(),
+                    cloud.hosts(),
+                    cloud.schedulers(),
+                )
+                .map_err(|e| format!("Cannot create metrics output files: {}", e))?;
+            exporter.borrow_mut().init();
+            scenario.metrics_exporter = Some(exporter);
+        }
+
+        for workload in self.workloads {
+            submit_workload(&mut scenario, workload);
+        }
+
+        if !self.faults.is_empty() {
+            let faults = FaultScenario { faults: self.faults };
+            scenario.faults = Some(scenario.cloud.install_faults(&faults)?);
+        }
+        Ok(scenario)
+    }
+}
+
+/// Checks the workload parameters and the reference to scheduler.
+fn validate_workload(workload: &WorkloadConfig, names: &BTreeSet<String>) -> Result<(), String> {
+    let scheduler = match workload {
+        WorkloadConfig::Vms { scheduler, .. } | WorkloadConfig::Synthetic { scheduler, .. } => scheduler,
+    };
+    if !names.contains(scheduler) {
+        return Err(format!("Workload refers to unknown scheduler `{}`", scheduler));
+    }
+    match workload {
+        WorkloadConfig::Vms { vms, .. } => {
+            for (i, vm) in vms.iter().enumerate() {
+                if vm.cpus == 0 || vm.memory == 0 || vm.lifetime <= 0. || vm.count == Some(0) {
+                    return Err(format!(
+                        "VM group {} of workload for scheduler `{}` should have positive cpus, memory, lifetime and count",
+                        i, scheduler
+                    ));
+                }
+                if vm.start.unwrap_or(0.) < 0. || vm.interval.unwrap_or(0.) < 0. {
+                    return Err(format!(
+                        "VM group {} of workload for scheduler `{}` should have non-negative start and interval",
+                        i, scheduler
+                    ));
+                }
+            }
+        }
+        WorkloadConfig::Synthetic {
+            arrival_interval,
+            arrival_rate,
+            window,
+            flavors,
+            lifetime,
+            ..
+        } => {
+            match (arrival_interval, arrival_rate) {
+                (Some(value), None) | (None, Some(value)) if *value > 0. => {}
+                _ => {
+                    return Err(format!(
+                        "Synthetic workload for scheduler `{}` should have either positive `arrival_interval` or positive `arrival_rate`",
+                        scheduler
+                    ))
+                }
+            }
+            if window.0 > window.1 {
+                return Err(format!(
+                    "Synthetic workload for scheduler `{}` has invalid window",
+                    scheduler
+                ));
+            }
+            if flavors.is_empty() || *lifetime <= 0. {
+                return Err(format!(
+                    "Synthetic workload for scheduler `{}` should have flavors and positive lifetime",
+                    scheduler
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Exponentially distributed intervals between Poisson arrivals.
+struct ExponentialIntervals {
+    rate: f64,
+}
+
+impl Distribution<f64> for ExponentialIntervals {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+        -(1. - rng.gen::<f64>()).ln() / self.rate
+    }
+}
+
+/// Submits the workload VMs to the scheduler.
+fn submit_workload(scenario: &mut Scenario, workload: WorkloadConfig) {
+    match workload {
+        WorkloadConfig::Vms { scheduler, vms } => {
+            let scheduler_id = scenario.scheduler_id(&scheduler);
+            for group in vms {
+                let start = group.start.unwrap_or(0.);
+                for i in 0..group.count.unwrap_or(1) {
+                    scenario.cloud.spawn_vm_with_delay(
+                        ResourceConsumer::with_full_load(group.cpus, group.memory),
+                        group.lifetime,
+                        None,
+                        scheduler_id,
+                        start + i as f64 * group.interval.unwrap_or(0.),
+                    );
+                }
+            }
+        }
+        WorkloadConfig::Synthetic {
+            scheduler,
+            arrival_interval,
+            arrival_rate,
+            window,
+            max_vm_count,
+            flavors,
+            lifetime,
+            seed,
+        } => {
+            let arrival_generator = match (arrival_interval, arrival_rate) {
+                (Some(interval), _) => ArrivalGenerator::EquallySpaced(interval),
+                (None, rate) => ArrivalGenerator::Random(Box::new(ExponentialIntervals { rate: rate.unwrap() })),
+            };
+            let scheduler_id = scenario.scheduler_id(&scheduler);
+            let mut generator = SyntheticWorkloadGenerator::new(SyntheticWorkloadConfig {
+                arrival_generator,
+                activity_window: window,
+                max_vm_count,
+                tenants: vec![SyntheticTenantConfig {
+                    weight: 1.,
+                    size_generator: SizeGenerator::Flavors(flavors),
+                    lifetime_generator: LifetimeGenerator::Equal(lifetime),
+                    cpu_load_model: Box::new(ConstantLoadModel::new(1.)),
+                    memory_load_model: Box::new(ConstantLoadModel::new(1.)),
+                    scheduler_name: Some(scheduler),
+                    tenant: None,
+                    priority: 0,
+                }],
+                random_seed: seed.unwrap_or(0),
+            });
+            scenario.cloud.spawn_vms_from_dataset(scheduler_id, &mut generator);
+        }
+    }
+}
+
+/// Returns the names of components created from config with the specified name, name prefix and count.
+fn component_names(
+    kind: &str,
+    name: &Option<String>,
+    name_prefix: &Option<String>,
+    count: Option<u32>,
+) -> Result<Vec<String>, String> {
+    let count = count.unwrap_or(1);
+    if count == 1 {
+        match name {
+            Some(name) => Ok(vec![name.clone()]),
+            None => Err(format!("Name of {} should be set if count = 1", kind)),
+        }
+    } else {
+        match name_prefix {
+            Some(prefix) => Ok((1..=count).map(|i| format!("{}{}", prefix, i)).collect()),
+            None => Err(format!("Name prefix of {} should be set if count > 1", kind)),
+        }
+    }
+}
+
+fn host_names(hosts: &[HostConfig]) -> Result<Vec<String>, String> {
+    let mut names = Vec::new();
+    for host in hosts {
+        names.extend(component_names("host", &host.name, &host.name_prefix, host.count)?);
+    }
+    Ok(names)
+}
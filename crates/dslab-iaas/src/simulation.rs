@@ -8,25 +8,45 @@ use std::rc::Rc;
 use sugars::{rc, refcell};
 
 use dslab_core::context::SimulationContext;
+use dslab_core::faults::{FaultInjector, FaultScenario};
 use dslab_core::simulation::Simulation;
 use dslab_core::Id;
 use dslab_models::power::cpu_models::linear::LinearCpuPowerModel;
 use dslab_models::power::host::{HostPowerModel, HostPowerModelBuilder};
+use dslab_network::{Network, NetworkModel};
 
-use crate::core::config::sim_config::SimulationConfig;
-use crate::core::events::allocation::{AllocationRequest, MigrationRequest};
+use crate::core::config::sim_config::{SchedulerConfig, SimulationConfig};
+use crate::core::energy_meter::EnergyReport;
+use crate::core::events::allocation::{
+    AllocationRequest, BatchAllocationRequest, BatchReleaseRequest, MigrationRequest, ReservationRequest,
+};
+use crate::core::events::power::{PowerOffRequest, PowerOnRequest, SleepRequest};
+use crate::core::events::vm_api::ApiAllocationRequest;
+use crate::core::gang::GangSpec;
 use crate::core::host_manager::HostManager;
 use crate::core::host_manager::SendHostState;
+use crate::core::image::{ImageCache, ImageCacheStats, ImageEvictionPolicy, ImageRegistry};
+use crate::core::interference::InterferenceModel;
 use crate::core::logger::{Logger, StdoutLogger};
 use crate::core::monitoring::Monitoring;
 use crate::core::placement_store::PlacementStore;
+use crate::core::power_model::power_model_resolver;
+use crate::core::power_state::HostPowerStateConfig;
+use crate::core::preemption::{preemption_policy_resolver, PreemptionRecord};
+use crate::core::quota::TenantQuota;
+use crate::core::rate_limit::RateLimit;
+use crate::core::retry_policy::retry_policy_resolver;
 use crate::core::scheduler::Scheduler;
 use crate::core::slav_metric::HostSLAVMetric;
 use crate::core::slav_metric::OverloadTimeFraction;
-use crate::core::vm::{ResourceConsumer, VirtualMachine, VmStatus};
+use crate::core::slav_metric::TrafficSLAVMetric;
+use crate::core::thermal_model::ThermalModel;
+use crate::core::timeline::Timeline;
+use crate::core::utilization::UtilizationReport;
+use crate::core::vm::{ResourceConsumer, VirtualMachine, VmSpec, VmStatus};
 use crate::core::vm_api::VmAPI;
-use crate::core::vm_placement_algorithm::placement_algorithm_resolver;
-use crate::core::vm_placement_algorithm::VMPlacementAlgorithm;
+use crate::core::vm_placement_algorithm::{PlacementAlgorithmRegistry, VMPlacementAlgorithm};
+use crate::core::volume::{Volume, VolumeLocation};
 use crate::custom_component::CustomComponent;
 use crate::extensions::azure_dataset_reader::AzureDatasetReader;
 use crate::extensions::dataset_reader::DatasetReader;
@@ -51,9 +71,18 @@ pub struct CloudSimulation {
     schedulers: HashMap<u32, Rc<RefCell<Scheduler>>>,
     components: HashMap<u32, Rc<RefCell<dyn CustomComponent>>>,
     host_power_model: HostPowerModel,
+    host_type_power_models: HashMap<String, HostPowerModel>,
+    thermal_model: Option<ThermalModel>,
+    host_power_state_config: HostPowerStateConfig,
+    rack_thermal_models: HashMap<u32, ThermalModel>,
+    interference_model: Option<Box<dyn InterferenceModel>>,
+    image_registry: Rc<RefCell<ImageRegistry>>,
+    image_cache: ImageCache,
+    timeline: Option<Rc<RefCell<Timeline>>>,
     slav_metric: Box<dyn HostSLAVMetric>,
     batch_mode: bool,
     batch_buffer: Vec<VMSpawnRequest>,
+    placement_algorithms: PlacementAlgorithmRegistry,
     logger: Rc<RefCell<Box<dyn Logger>>>,
     sim: Simulation,
     ctx: SimulationContext,
@@ -67,18 +96,30 @@ impl CloudSimulation {
     }
 
     /// Creates a simulation with specified config.
-    pub fn with_logger(mut sim: Simulation, sim_config: SimulationConfig, logger: Box<dyn Logger>) -> Self {
+    pub fn with_logger(sim: Simulation, sim_config: SimulationConfig, logger: Box<dyn Logger>) -> Self {
+        CloudSimulation::with_placement_algorithms(sim, sim_config, logger, PlacementAlgorithmRegistry::new())
+    }
+
+    /// Creates a simulation with specified config, where the placement algorithms of schedulers
+    /// are resolved using the specified registry (which can include custom algorithms).
+    pub fn with_placement_algorithms(
+        mut sim: Simulation,
+        sim_config: SimulationConfig,
+        logger: Box<dyn Logger>,
+        placement_algorithms: PlacementAlgorithmRegistry,
+    ) -> Self {
         let logger: Rc<RefCell<Box<dyn Logger>>> = rc!(refcell!(logger));
 
+        let vm_api = rc!(refcell!(VmAPI::new(sim.create_context("vm_api"))));
+        sim.add_handler("vm_api", vm_api.clone());
+
         let monitoring = rc!(refcell!(Monitoring::new(
+            vm_api.clone(),
             sim.create_context("monitoring"),
             logger.clone()
         )));
         sim.add_handler("monitoring", monitoring.clone());
 
-        let vm_api = rc!(refcell!(VmAPI::new(sim.create_context("vm_api"))));
-        sim.add_handler("vm_api", vm_api.clone());
-
         let placement_store = rc!(refcell!(PlacementStore::new(
             sim_config.allow_vm_overcommit,
             vm_api.clone(),
@@ -99,9 +140,18 @@ impl CloudSimulation {
             host_power_model: HostPowerModelBuilder::new()
                 .cpu(Box::new(LinearCpuPowerModel::new(0.4, 1.)))
                 .build(),
+            host_type_power_models: HashMap::new(),
+            thermal_model: None,
+            host_power_state_config: HostPowerStateConfig::default(),
+            rack_thermal_models: HashMap::new(),
+            interference_model: None,
+            image_registry: rc!(refcell!(ImageRegistry::new())),
+            image_cache: ImageCache::default(),
+            timeline: None,
             slav_metric: Box::new(OverloadTimeFraction::new()),
             batch_mode: false,
             batch_buffer: Vec::new(),
+            placement_algorithms,
             logger,
             sim,
             ctx,
@@ -110,32 +160,55 @@ impl CloudSimulation {
 
         // Add hosts from config
         for host_config in sim.sim_config.hosts.clone() {
+            if let Some(power_model) = host_config.power_model {
+                let host_type = host_config
+                    .host_type
+                    .clone()
+                    .expect("Host type should be set along with host power model");
+                sim.set_host_type_power_model(&host_type, power_model_resolver(power_model));
+            }
             let count = host_config.count.unwrap_or(1);
             if count == 1 {
                 let name = host_config.name.unwrap();
-                sim.add_host(&name, host_config.cpus, host_config.memory);
+                sim.add_host_internal(&name, host_config.cpus, host_config.memory, None, host_config.host_type);
             } else {
                 let prefix = host_config.name_prefix.unwrap();
                 for i in 0..count {
                     let name = format!("{}{}", prefix, i + 1);
-                    sim.add_host(&name, host_config.cpus, host_config.memory);
+                    sim.add_host_internal(
+                        &name,
+                        host_config.cpus,
+                        host_config.memory,
+                        None,
+                        host_config.host_type.clone(),
+                    );
                 }
             }
         }
 
+        // Set tenant quotas from config
+        for tenant_config in sim.sim_config.tenants.clone() {
+            sim.set_tenant_quota(&tenant_config.name, tenant_config.quota());
+            if let Some(limit) = tenant_config.rate_limit() {
+                sim.set_tenant_rate_limit(&tenant_config.name, limit);
+            }
+        }
+
         // Add schedulers from config
         for scheduler_config in sim.sim_config.schedulers.clone() {
             let count = scheduler_config.count.unwrap_or(1);
             if count == 1 {
-                let name = scheduler_config.name.unwrap();
-                let alg = placement_algorithm_resolver(scheduler_config.algorithm);
-                sim.add_scheduler(&name, alg);
+                let name = scheduler_config.name.clone().unwrap();
+                let alg = sim.resolve_placement_algorithm(&scheduler_config.algorithm);
+                let id = sim.add_scheduler(&name, alg);
+                sim.apply_scheduler_policies(id, &scheduler_config);
             } else {
-                let prefix = scheduler_config.name_prefix.unwrap();
+                let prefix = scheduler_config.name_prefix.clone().unwrap();
                 for i in 0..scheduler_config.count.unwrap_or(1) {
                     let name = format!("{}{}", prefix, i + 1);
-                    let alg = placement_algorithm_resolver(scheduler_config.algorithm.clone());
-                    sim.add_scheduler(&name, alg);
+                    let alg = sim.resolve_placement_algorithm(&scheduler_config.algorithm);
+                    let id = sim.add_scheduler(&name, alg);
+                    sim.apply_scheduler_policies(id, &scheduler_config);
                 }
             }
         }
@@ -168,33 +241,72 @@ impl CloudSimulation {
         sim
     }
 
-    fn add_host_internal(&mut self, name: &str, cpu_total: u32, memory_total: u64, rack_id: Option<u32>) -> u32 {
+    fn add_host_internal(
+        &mut self,
+        name: &str,
+        cpu_total: u32,
+        memory_total: u64,
+        rack_id: Option<u32>,
+        host_type: Option<String>,
+    ) -> u32 {
+        // use power model of host type if it is set
+        let power_model = host_type
+            .as_ref()
+            .and_then(|t| self.host_type_power_models.get(t))
+            .unwrap_or(&self.host_power_model)
+            .clone();
         // create host
         let host = rc!(refcell!(HostManager::new(
             rack_id,
+            host_type.clone(),
             cpu_total,
             memory_total,
             self.monitoring.borrow().get_id(),
             self.placement_store.borrow().get_id(),
             self.vm_api.clone(),
             self.sim_config.allow_vm_overcommit,
-            self.host_power_model.clone(),
+            power_model,
             self.slav_metric.clone(),
             self.sim.create_context(name),
             self.logger.clone(),
             self.sim_config.clone(),
         )));
         let id = self.sim.add_handler(name, host.clone());
+        // use thermal model of the rack if it is set
+        let thermal_model = rack_id
+            .and_then(|r| self.rack_thermal_models.get(&r))
+            .or(self.thermal_model.as_ref())
+            .cloned();
+        if let Some(thermal_model) = thermal_model.clone() {
+            host.borrow_mut().set_thermal_model(thermal_model);
+        }
+        host.borrow_mut()
+            .set_power_state_config(self.host_power_state_config.clone());
+        if let Some(interference_model) = self.interference_model.clone() {
+            host.borrow_mut().set_interference_model(interference_model);
+        }
+        host.borrow_mut().set_image_registry(self.image_registry.clone());
+        host.borrow_mut().set_image_cache(self.image_cache.clone());
+        if let Some(timeline) = self.timeline.clone() {
+            host.borrow_mut().set_timeline(timeline);
+        }
         self.hosts.insert(id, host);
         // add host to monitoring
-        self.monitoring.borrow_mut().add_host(id, cpu_total, memory_total);
+        self.monitoring
+            .borrow_mut()
+            .add_host(id, cpu_total, memory_total, host_type.clone(), rack_id);
+        if let Some(thermal_model) = thermal_model {
+            self.monitoring.borrow_mut().set_host_thermal_model(id, thermal_model);
+        }
         // add host to placement store
         self.placement_store
             .borrow_mut()
-            .add_host(id, cpu_total, memory_total, rack_id);
+            .add_host(id, cpu_total, memory_total, rack_id, host_type.clone());
         // add host to schedulers
         for scheduler in self.schedulers.values() {
-            scheduler.borrow_mut().add_host(id, cpu_total, memory_total, rack_id);
+            scheduler
+                .borrow_mut()
+                .add_host(id, cpu_total, memory_total, rack_id, host_type.clone());
         }
         // start sending host state to monitoring
         self.ctx.emit_now(SendHostState {}, id);
@@ -203,13 +315,54 @@ impl CloudSimulation {
 
     /// Creates new host with specified name and resource capacity, and returns the host ID.
     pub fn add_host(&mut self, name: &str, cpu_total: u32, memory_total: u64) -> u32 {
-        self.add_host_internal(name, cpu_total, memory_total, None)
+        self.add_host_internal(name, cpu_total, memory_total, None, None)
     }
 
     /// Creates new host with specified name and resource capacity, and returns the host ID.
     /// Also associates the host with the specified rack.
     pub fn add_host_in_rack(&mut self, name: &str, cpu_total: u32, memory_total: u64, rack_id: u32) -> u32 {
-        self.add_host_internal(name, cpu_total, memory_total, Some(rack_id))
+        self.add_host_internal(name, cpu_total, memory_total, Some(rack_id), None)
+    }
+
+    /// Creates new host with specified name, resource capacity and type, and returns the host ID.
+    ///
+    /// If the power model for this host type was set via [`set_host_type_power_model`](Self::set_host_type_power_model),
+    /// it is used instead of the default host power model.
+    pub fn add_host_with_type(&mut self, name: &str, cpu_total: u32, memory_total: u64, host_type: &str) -> u32 {
+        self.add_host_internal(name, cpu_total, memory_total, None, Some(host_type.to_string()))
+    }
+
+    /// Switches the specified host on (from off or sleep state).
+    pub fn power_on_host(&mut self, host_id: u32) {
+        self.ctx.emit_now(PowerOnRequest {}, host_id);
+    }
+
+    /// Switches the specified host off. The request is ignored if the host is not active or has VMs.
+    pub fn power_off_host(&mut self, host_id: u32) {
+        self.ctx.emit_now(PowerOffRequest {}, host_id);
+    }
+
+    /// Puts the specified host to sleep. The request is ignored if the host is not active or has VMs.
+    pub fn sleep_host(&mut self, host_id: u32) {
+        self.ctx.emit_now(SleepRequest {}, host_id);
+    }
+
+    /// Registers custom placement algorithm, which can be then referenced by name in config value strings.
+    ///
+    /// Note that the schedulers from config are created during the simulation creation, so in order to use custom
+    /// algorithms in config, they should be registered beforehand via
+    /// [`with_placement_algorithms`](Self::with_placement_algorithms).
+    pub fn register_placement_algorithm<F>(&mut self, name: &str, factory: F)
+    where
+        F: Fn(&str) -> VMPlacementAlgorithm + Send + Sync + 'static,
+    {
+        self.placement_algorithms.register(name, factory);
+    }
+
+    /// Creates placement algorithm from config value string, e.g. `BestFitThreshold[threshold=0.8]`,
+    /// using the registry of built-in and custom algorithms.
+    pub fn resolve_placement_algorithm(&self, config_str: &str) -> VMPlacementAlgorithm {
+        self.placement_algorithms.resolve(config_str)
     }
 
     /// Creates new scheduler with specified name and VM placement algorithm, and returns the scheduler ID.
@@ -233,6 +386,25 @@ impl CloudSimulation {
         id
     }
 
+    /// Sets the retry and preemption policies and fair sharing mode from scheduler config if they are specified.
+    fn apply_scheduler_policies(&mut self, scheduler_id: u32, scheduler_config: &SchedulerConfig) {
+        if let Some(retry_policy) = &scheduler_config.retry_policy {
+            self.schedulers[&scheduler_id]
+                .borrow_mut()
+                .set_retry_policy(retry_policy_resolver(retry_policy.clone()));
+        }
+        if let Some(preemption_policy) = &scheduler_config.preemption_policy {
+            self.schedulers[&scheduler_id]
+                .borrow_mut()
+                .set_preemption_policy(preemption_policy_resolver(preemption_policy.clone()));
+        }
+        if let Some(fair_sharing) = scheduler_config.fair_sharing {
+            self.schedulers[&scheduler_id]
+                .borrow_mut()
+                .set_fair_sharing(fair_sharing);
+        }
+    }
+
     /// Creates new VM with specified properties, registers it in VM API and immediately submits the allocation request
     /// to the specified scheduler. Returns VM ID.
     pub fn spawn_vm_now(
@@ -274,17 +446,172 @@ impl CloudSimulation {
         scheduler_id: u32,
         delay: f64,
     ) -> u32 {
+        let vm = self.create_vm(resource_consumer, lifetime, vm_id, delay);
+        self.submit_vm(vm, scheduler_id, delay)
+    }
+
+    /// Same as [`spawn_vm_with_delay`](Self::spawn_vm_with_delay), but the VM belongs to the specified tenant,
+    /// so the request is checked against the tenant quota and submitted via VM API if the tenant is rate limited.
+    pub fn spawn_tenant_vm(
+        &mut self,
+        tenant: &str,
+        resource_consumer: ResourceConsumer,
+        lifetime: f64,
+        vm_id: Option<u32>,
+        scheduler_id: u32,
+        delay: f64,
+    ) -> u32 {
+        let mut vm = self.create_vm(resource_consumer, lifetime, vm_id, delay);
+        vm.tenant = Some(tenant.to_string());
+        self.submit_vm(vm, scheduler_id, delay)
+    }
+
+    /// Same as [`spawn_vm_with_delay`](Self::spawn_vm_with_delay), but the VM is started from the specified image,
+    /// which is fetched from the image registry by the host if it is not cached.
+    ///
+    /// Panics if the image is not found in registry.
+    pub fn spawn_vm_with_image(
+        &mut self,
+        resource_consumer: ResourceConsumer,
+        lifetime: f64,
+        vm_id: Option<u32>,
+        scheduler_id: u32,
+        delay: f64,
+        image: &str,
+    ) -> u32 {
+        assert!(
+            self.image_registry.borrow().get_image(image).is_some(),
+            "Image {} is not found in registry",
+            image
+        );
+        let mut vm = self.create_vm(resource_consumer, lifetime, vm_id, delay);
+        vm.image = Some(image.to_string());
+        self.submit_vm(vm, scheduler_id, delay)
+    }
+
+    /// Same as [`spawn_vm_with_delay`](Self::spawn_vm_with_delay), but the VM has the specified priority,
+    /// so it can preempt VMs with lower priority if the scheduler preemption policy is set.
+    pub fn spawn_vm_with_priority(
+        &mut self,
+        resource_consumer: ResourceConsumer,
+        lifetime: f64,
+        vm_id: Option<u32>,
+        scheduler_id: u32,
+        delay: f64,
+        priority: u32,
+    ) -> u32 {
+        let mut vm = self.create_vm(resource_consumer, lifetime, vm_id, delay);
+        vm.priority = priority;
+        self.submit_vm(vm, scheduler_id, delay)
+    }
+
+    /// Same as [`spawn_vm_with_delay`](Self::spawn_vm_with_delay), but the specified volumes are attached to the VM.
+    ///
+    /// VM with local volumes is placed only on the host storing these volumes, while remote volumes from storage
+    /// pools increase the VM start duration by the storage pool attach latency.
+    /// Panics if some volume can't be attached to the VM.
+    pub fn spawn_vm_with_volumes(
+        &mut self,
+        resource_consumer: ResourceConsumer,
+        lifetime: f64,
+        vm_id: Option<u32>,
+        scheduler_id: u32,
+        delay: f64,
+        volume_ids: &[u32],
+    ) -> u32 {
+        let vm = self.create_vm(resource_consumer, lifetime, vm_id, delay);
+        let id = vm.id;
+        self.vm_api.borrow_mut().register_new_vm(vm);
+        for volume_id in volume_ids {
+            self.vm_api
+                .borrow_mut()
+                .attach_volume(*volume_id, id)
+                .unwrap_or_else(|e| panic!("Cannot spawn VM {}: {}", id, e));
+        }
+        self.ctx
+            .emit(AllocationRequest { vm_ids: vec![id] }, scheduler_id, delay);
+        id
+    }
+
+    fn create_vm(
+        &mut self,
+        resource_consumer: ResourceConsumer,
+        lifetime: f64,
+        vm_id: Option<u32>,
+        delay: f64,
+    ) -> VirtualMachine {
         let id = vm_id.unwrap_or_else(|| self.vm_api.borrow_mut().generate_vm_id());
-        let vm = VirtualMachine::new(
+        VirtualMachine::new(
             id,
             self.ctx.time() + delay,
             lifetime,
             resource_consumer,
             self.sim_config.clone(),
+        )
+    }
+
+    fn submit_vm(&mut self, vm: VirtualMachine, scheduler_id: u32, delay: f64) -> u32 {
+        let id = vm.id;
+        let rate_limited = vm
+            .tenant
+            .as_ref()
+            .is_some_and(|tenant| self.vm_api.borrow().is_rate_limited(tenant));
+        self.vm_api.borrow_mut().register_new_vm(vm);
+        if rate_limited {
+            self.ctx.emit(
+                ApiAllocationRequest {
+                    vm_ids: vec![id],
+                    scheduler_id,
+                    attempt: 0,
+                    submit_time: self.ctx.time() + delay,
+                },
+                self.vm_api.borrow().get_id(),
+                delay,
+            );
+        } else {
+            self.ctx
+                .emit(AllocationRequest { vm_ids: vec![id] }, scheduler_id, delay);
+        }
+        id
+    }
+
+    /// Creates new VM with specified properties, registers it in VM API and submits the advance reservation request
+    /// to the specified scheduler. Returns VM ID.
+    ///
+    /// The VM is started at the specified start time and runs for the specified duration. The capacity is reserved
+    /// from the start time until the VM is stopped (taking into account VM start and stop durations).
+    /// If the reservation conflicts with other reservations or there is not enough capacity,
+    /// the VM becomes `FailedToAllocate`.
+    pub fn reserve_vm(
+        &mut self,
+        resource_consumer: ResourceConsumer,
+        start_time: f64,
+        duration: f64,
+        vm_id: Option<u32>,
+        scheduler_id: u32,
+    ) -> u32 {
+        assert!(
+            start_time >= self.ctx.time(),
+            "Reservation start time should not be in the past"
+        );
+        let id = vm_id.unwrap_or_else(|| self.vm_api.borrow_mut().generate_vm_id());
+        let vm = VirtualMachine::new(
+            id,
+            self.ctx.time(),
+            duration,
+            resource_consumer,
+            self.sim_config.clone(),
         );
         self.vm_api.borrow_mut().register_new_vm(vm);
-        self.ctx
-            .emit(AllocationRequest { vm_ids: vec![id] }, scheduler_id, delay);
+        let end_time = start_time + self.sim_config.vm_start_duration + duration + self.sim_config.vm_stop_duration;
+        self.ctx.emit_now(
+            ReservationRequest {
+                vm_id: id,
+                start_time,
+                end_time,
+            },
+            scheduler_id,
+        );
         id
     }
 
@@ -348,6 +675,61 @@ impl CloudSimulation {
         vm_ids
     }
 
+    /// Creates a group of VMs with specified properties, registers them in VM API and submits the group
+    /// to the specified scheduler with the specified delay using a single request. Returns the IDs of VMs.
+    ///
+    /// If `atomic` is true, the group is submitted as a multi-VM request, i.e. either all VMs are placed or none.
+    /// Otherwise, the VMs are placed independently, as if they were submitted as separate requests.
+    pub fn spawn_vm_group(&mut self, vms: Vec<VmSpec>, scheduler_id: u32, delay: f64, atomic: bool) -> Vec<u32> {
+        assert!(!vms.is_empty(), "VM group is empty");
+        let mut vm_ids = Vec::with_capacity(vms.len());
+        for spec in vms {
+            let vm = self.create_vm(spec.resource_consumer, spec.lifetime, spec.vm_id, delay);
+            vm_ids.push(vm.id);
+            self.vm_api.borrow_mut().register_new_vm(vm);
+        }
+        if atomic {
+            self.ctx
+                .emit(AllocationRequest { vm_ids: vm_ids.clone() }, scheduler_id, delay);
+        } else {
+            self.ctx
+                .emit(BatchAllocationRequest { vm_ids: vm_ids.clone() }, scheduler_id, delay);
+        }
+        vm_ids
+    }
+
+    /// Creates a gang of VMs with specified properties and submits it to the specified scheduler
+    /// as a multi-VM request with the specified delay. Returns the IDs of VMs.
+    ///
+    /// Either all VMs from the gang are placed according to its topology constraint or none of them.
+    /// If the gang is not placed within its time window, all its VMs fail to allocate.
+    pub fn spawn_vm_gang(&mut self, vms: Vec<VmSpec>, gang: GangSpec, scheduler_id: u32, delay: f64) -> Vec<u32> {
+        assert!(!vms.is_empty(), "VM gang is empty");
+        let mut vm_ids = Vec::with_capacity(vms.len());
+        for spec in vms {
+            let vm = self.create_vm(spec.resource_consumer, spec.lifetime, spec.vm_id, delay);
+            vm_ids.push(vm.id);
+            self.vm_api.borrow_mut().register_new_vm(vm);
+        }
+        self.vm_api.borrow_mut().register_gang(&vm_ids, gang);
+        self.ctx
+            .emit(AllocationRequest { vm_ids: vm_ids.clone() }, scheduler_id, delay);
+        vm_ids
+    }
+
+    /// Stops the specified running VMs before the end of their lifetime, sending a single request to each host.
+    /// Returns the IDs of VMs to be stopped, the VMs which are not running are skipped.
+    pub fn delete_vms(&mut self, vm_ids: &[u32]) -> Vec<u32> {
+        let groups = self.vm_api.borrow().group_running_vms_by_host(vm_ids);
+        let mut deleted = Vec::new();
+        for (host_id, vm_ids) in groups {
+            deleted.extend(vm_ids.iter().copied());
+            self.ctx
+                .emit(BatchReleaseRequest { vm_ids }, host_id, self.sim_config.message_delay);
+        }
+        deleted
+    }
+
     /// Sends VM migration request to the specified target host.
     pub fn migrate_vm_to_host(&mut self, vm_id: u32, target_host: u32) {
         let vm_api = self.vm_api.borrow();
@@ -372,6 +754,13 @@ impl CloudSimulation {
         component
     }
 
+    /// Installs fault injector with the specified scenario.
+    ///
+    /// The fault targets (e.g. custom components) should be created before calling this method.
+    pub fn install_faults(&mut self, scenario: &FaultScenario) -> Result<Rc<RefCell<FaultInjector>>, String> {
+        FaultInjector::install(&mut self.sim, scenario)
+    }
+
     /// Spawns all VMs from the given dataset.
     ///
     /// The specified default scheduler is used for VM requests without scheduler information.
@@ -388,7 +777,7 @@ impl CloudSimulation {
                 scheduler_id = self.sim.lookup_id(&request.scheduler_name.unwrap());
             }
 
-            self.spawn_vm_with_delay(
+            let mut vm = self.create_vm(
                 ResourceConsumer::new(
                     request.cpu_usage,
                     request.memory_usage,
@@ -397,9 +786,11 @@ impl CloudSimulation {
                 ),
                 request.lifetime,
                 request.id,
-                scheduler_id,
                 request.start_time,
             );
+            vm.tenant = request.tenant;
+            vm.priority = request.priority;
+            self.submit_vm(vm, scheduler_id, request.start_time);
         }
     }
 
@@ -410,6 +801,218 @@ impl CloudSimulation {
         self.host_power_model = host_power_model;
     }
 
+    /// Sets the power model used for hosts of the specified type.
+    ///
+    /// Should be called before adding hosts of this type to simulation.
+    pub fn set_host_type_power_model(&mut self, host_type: &str, host_power_model: HostPowerModel) {
+        self.host_type_power_models
+            .insert(host_type.to_string(), host_power_model);
+    }
+
+    /// Registers component which will receive `VmPreempted` events upon preemption of VMs.
+    pub fn add_preemption_listener(&mut self, component_id: u32) {
+        self.vm_api.borrow_mut().add_preemption_listener(component_id);
+    }
+
+    /// Returns the history of VM preemptions.
+    pub fn preemptions(&self) -> Vec<PreemptionRecord> {
+        self.vm_api.borrow().get_preemptions().to_vec()
+    }
+
+    /// Creates the network with specified model, which connects the hosts and is used by network-aware
+    /// placement algorithms via monitoring.
+    ///
+    /// The hosts should be bound to the network nodes using [`set_host_network_node`](Self::set_host_network_node).
+    pub fn create_network(&mut self, network_model: Box<dyn NetworkModel>) -> Rc<RefCell<Network>> {
+        let network = rc!(refcell!(Network::new(
+            network_model,
+            self.sim.create_context("network")
+        )));
+        self.sim.add_handler("network", network.clone());
+        self.monitoring.borrow_mut().set_network(network.clone());
+        network
+    }
+
+    /// Binds the host to the specified node of the network.
+    ///
+    /// The network bandwidth between hosts bound to the network is also used to compute VM migration durations.
+    pub fn set_host_network_node(&mut self, host_id: u32, node: &str) {
+        let network = self
+            .monitoring
+            .borrow()
+            .network()
+            .expect("Network should be created before binding hosts");
+        network.borrow_mut().set_location(host_id, node);
+        self.hosts[&host_id].borrow_mut().set_network(network);
+    }
+
+    /// Sets the rate of network traffic between two VMs, which is used by network-aware placement algorithms.
+    ///
+    /// The traffic is routed over the network and the network congestion is reflected in the traffic SLAV metric.
+    pub fn set_vm_traffic(&mut self, vm1: u32, vm2: u32, rate: f64) {
+        self.monitoring.borrow_mut().set_vm_traffic(vm1, vm2, rate);
+    }
+
+    /// Overrides the used traffic SLAV metric.
+    pub fn set_traffic_slav_metric(&mut self, traffic_slav_metric: Box<dyn TrafficSLAVMetric>) {
+        self.monitoring
+            .borrow_mut()
+            .set_traffic_slav_metric(traffic_slav_metric);
+    }
+
+    /// Returns the total traffic SLAV value caused by network congestion.
+    pub fn traffic_slav(&mut self) -> f64 {
+        let time = self.sim.time();
+        self.monitoring.borrow_mut().get_accumulated_traffic_slav(time)
+    }
+
+    /// Returns the traffic SLAV value of the specified VM.
+    pub fn vm_traffic_slav(&mut self, vm_id: u32) -> f64 {
+        let time = self.sim.time();
+        self.monitoring.borrow_mut().get_vm_traffic_slav(vm_id, time)
+    }
+
+    /// Sets the capacity and IOPS of the local storage of the specified host, which can store local volumes.
+    pub fn set_host_storage(&mut self, host_id: u32, capacity: u64, iops: u32) {
+        self.vm_api
+            .borrow_mut()
+            .volumes_mut()
+            .set_host_storage(host_id, capacity, iops);
+    }
+
+    /// Adds storage pool with specified capacity, IOPS and attach latency, which can store remote volumes.
+    /// Returns storage pool ID.
+    pub fn add_storage_pool(&mut self, name: &str, capacity: u64, iops: u32, attach_latency: f64) -> u32 {
+        self.vm_api
+            .borrow_mut()
+            .volumes_mut()
+            .add_storage_pool(name, capacity, iops, attach_latency)
+    }
+
+    /// Creates volume with specified capacity and IOPS in the specified location. Returns volume ID.
+    ///
+    /// Fails if the storage does not have enough free capacity or IOPS.
+    pub fn create_volume(&mut self, capacity: u64, iops: u32, location: VolumeLocation) -> Result<u32, String> {
+        self.vm_api.borrow_mut().create_volume(capacity, iops, location)
+    }
+
+    /// Deletes volume which is not attached to any VM.
+    pub fn delete_volume(&mut self, volume_id: u32) -> Result<(), String> {
+        self.vm_api.borrow_mut().volumes_mut().delete_volume(volume_id)
+    }
+
+    /// Returns volume by its ID.
+    pub fn volume(&self, volume_id: u32) -> Option<Volume> {
+        self.vm_api.borrow().volumes().get_volume(volume_id).cloned()
+    }
+
+    /// Sets the resource quota of the specified tenant.
+    pub fn set_tenant_quota(&mut self, tenant: &str, quota: TenantQuota) {
+        self.vm_api.borrow_mut().set_tenant_quota(tenant, quota);
+    }
+
+    /// Sets the API rate limit of the specified tenant.
+    ///
+    /// The subsequent allocation requests of tenant VMs are submitted via VM API, which throttles them
+    /// according to the limit (see [`VmAPI`]).
+    pub fn set_tenant_rate_limit(&mut self, tenant: &str, limit: RateLimit) {
+        self.vm_api.borrow_mut().set_tenant_rate_limit(tenant, limit);
+    }
+
+    /// Sets the durations and energy costs of host power state transitions.
+    ///
+    /// Should be called before adding hosts to simulation.
+    pub fn set_host_power_state_config(&mut self, config: HostPowerStateConfig) {
+        self.host_power_state_config = config;
+    }
+
+    /// Sets the thermal model used for all hosts without a rack-specific thermal model.
+    ///
+    /// Should be called before adding hosts to simulation.
+    pub fn set_thermal_model(&mut self, thermal_model: ThermalModel) {
+        self.thermal_model = Some(thermal_model);
+    }
+
+    /// Sets the thermal model used for hosts in the specified rack.
+    ///
+    /// Should be called before adding hosts to this rack.
+    pub fn set_rack_thermal_model(&mut self, rack_id: u32, thermal_model: ThermalModel) {
+        self.rack_thermal_models.insert(rack_id, thermal_model);
+    }
+
+    /// Sets the model of performance interference between co-located VMs used for all hosts.
+    ///
+    /// Should be called before adding hosts to simulation.
+    pub fn set_interference_model(&mut self, interference_model: Box<dyn InterferenceModel>) {
+        self.interference_model = Some(interference_model);
+    }
+
+    /// Returns the fraction of total VM running time during which VMs did not progress due to interference
+    /// with co-located VMs.
+    pub fn interference_degradation(&mut self) -> f64 {
+        let time = self.ctx.time();
+        let mut delay = 0.;
+        let mut running_time = 0.;
+        for host in self.hosts.values() {
+            let mut host = host.borrow_mut();
+            delay += host.get_interference_delay(time);
+            running_time += host.get_vm_running_time(time);
+        }
+        if running_time > 0. {
+            delay / running_time
+        } else {
+            0.
+        }
+    }
+
+    /// Adds VM image with specified name and size (in GB) to the image registry.
+    pub fn add_image(&mut self, name: &str, size: f64) {
+        self.image_registry.borrow_mut().add_image(name, size);
+    }
+
+    /// Binds the image registry to the specified node of the network, so that the images are fetched by hosts
+    /// over the network.
+    pub fn set_image_registry_network_node(&mut self, node: &str) {
+        let network = self
+            .monitoring
+            .borrow()
+            .network()
+            .expect("Network should be created before binding image registry");
+        let id = match self.image_registry.borrow().get_id() {
+            Some(id) => id,
+            None => self.sim.create_context("image_registry").id(),
+        };
+        self.image_registry.borrow_mut().set_id(id);
+        network.borrow_mut().set_location(id, node);
+    }
+
+    /// Sets the capacity (in GB) and eviction policy of host image caches.
+    ///
+    /// Should be called before adding hosts to simulation. By default, the hosts do not cache images.
+    pub fn set_image_cache(&mut self, capacity: f64, eviction_policy: Box<dyn ImageEvictionPolicy>) {
+        self.image_cache = ImageCache::new(capacity, eviction_policy);
+    }
+
+    /// Puts the specified image to the cache of the specified host.
+    pub fn preload_image(&mut self, host_id: u32, name: &str) {
+        self.hosts[&host_id].borrow_mut().preload_image(name);
+    }
+
+    /// Returns the image cache statistics summed over all hosts.
+    pub fn image_cache_stats(&self) -> ImageCacheStats {
+        let mut total = ImageCacheStats::default();
+        for host in self.hosts.values() {
+            let host = host.borrow();
+            let stats = host.image_cache().stats();
+            total.hits += stats.hits;
+            total.misses += stats.misses;
+            total.fetches += stats.fetches;
+            total.fetched_size += stats.fetched_size;
+            total.evictions += stats.evictions;
+        }
+        total
+    }
+
     /// Overrides the used host-level SLAV metric.
     ///
     /// Should be called before adding hosts to simulation.
@@ -417,6 +1020,33 @@ impl CloudSimulation {
         self.slav_metric = slav_metric;
     }
 
+    /// Enables recording of VM intervals, migrations and host power states for visualization
+    /// and returns the recorded timeline (see [`Timeline`]).
+    ///
+    /// The recording starts at the current time for the existing hosts and at creation time for the new hosts.
+    pub fn enable_timeline(&mut self) -> Rc<RefCell<Timeline>> {
+        if let Some(timeline) = self.timeline.as_ref() {
+            return timeline.clone();
+        }
+        let timeline = rc!(refcell!(Timeline::new()));
+        for host in self.hosts.values() {
+            host.borrow_mut().set_timeline(timeline.clone());
+        }
+        self.timeline = Some(timeline.clone());
+        timeline
+    }
+
+    /// Returns the recorded timeline if it is enabled.
+    pub fn timeline(&self) -> Option<Rc<RefCell<Timeline>>> {
+        self.timeline.clone()
+    }
+
+    /// Returns the summary of resource utilization and allocation per host, host type, tenant and datacenter
+    /// from the simulation start to the current time.
+    pub fn utilization_report(&self) -> UtilizationReport {
+        self.monitoring.borrow().utilization_report()
+    }
+
     /// Returns the reference to monitoring component (provides actual host load).
     pub fn monitoring(&self) -> Rc<RefCell<Monitoring>> {
         self.monitoring.clone()
@@ -467,12 +1097,39 @@ impl CloudSimulation {
         self.hosts.get(&host_id).unwrap().clone()
     }
 
+    /// Returns the energy consumption of hosts split into components (idle, dynamic, inactive, transition and
+    /// cooling energy) per host, per host type and in total.
+    pub fn energy_report(&self) -> EnergyReport {
+        let time = self.ctx.time();
+        let mut report = EnergyReport::default();
+        for (id, host) in self.hosts.iter() {
+            let mut host = host.borrow_mut();
+            let breakdown = host.get_energy_breakdown(time);
+            report.add_host(&self.ctx.lookup_name(*id), host.host_type.as_deref(), breakdown);
+        }
+        report
+    }
+
+    /// Returns the IDs of hosts with the specified type.
+    pub fn hosts_by_type(&self, host_type: &str) -> Vec<u32> {
+        self.hosts
+            .iter()
+            .filter(|(_, host)| host.borrow().host_type.as_deref() == Some(host_type))
+            .map(|(id, _)| *id)
+            .collect()
+    }
+
     /// Returns the reference to host manager (host energy consumption, allocated resources etc.).
     pub fn host_by_name(&self, name: &str) -> Rc<RefCell<HostManager>> {
         let host_id = self.sim.lookup_id(name);
         self.hosts.get(&host_id).unwrap().clone()
     }
 
+    /// Returns the map with references to schedulers.
+    pub fn schedulers(&self) -> BTreeMap<u32, Rc<RefCell<Scheduler>>> {
+        self.schedulers.iter().map(|(id, s)| (*id, s.clone())).collect()
+    }
+
     /// Returns the reference to scheduler.
     pub fn scheduler(&self, scheduler_id: u32) -> Rc<RefCell<Scheduler>> {
         self.schedulers.get(&scheduler_id).unwrap().clone()
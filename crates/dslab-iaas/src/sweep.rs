@@ -0,0 +1,428 @@
+//! Parameter sweep experiments running a simulation for all combinations of parameter values and seeds.
+
+use std::fmt;
+use std::fs::File;
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+use indexmap::map::IndexMap;
+use serde::Serialize;
+use threadpool::ThreadPool;
+
+use dslab_core::replication::MetricSummary;
+use dslab_core::statistics::{paired_t_test, welch_t_test, TestResult};
+use dslab_core::Simulation;
+
+use crate::core::config::sim_config::SimulationConfig;
+use crate::core::logger::StdoutLogger;
+use crate::core::vm_placement_algorithm::{PlacementAlgorithmRegistry, VMPlacementAlgorithm};
+use crate::simulation::CloudSimulation;
+
+/// Value of swept parameter.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum ParamValue {
+    /// Boolean value.
+    Bool(bool),
+    /// Integer value.
+    Int(i64),
+    /// Floating point value.
+    Float(f64),
+    /// String value, e.g. config value string of placement algorithm.
+    String(String),
+}
+
+impl ParamValue {
+    /// Returns the value as float if it is numeric.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            ParamValue::Int(value) => Some(*value as f64),
+            ParamValue::Float(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as string slice if it is a string.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            ParamValue::String(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ParamValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParamValue::Bool(value) => write!(f, "{}", value),
+            ParamValue::Int(value) => write!(f, "{}", value),
+            ParamValue::Float(value) => write!(f, "{}", value),
+            ParamValue::String(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+impl From<bool> for ParamValue {
+    fn from(value: bool) -> Self {
+        ParamValue::Bool(value)
+    }
+}
+
+impl From<i64> for ParamValue {
+    fn from(value: i64) -> Self {
+        ParamValue::Int(value)
+    }
+}
+
+impl From<u32> for ParamValue {
+    fn from(value: u32) -> Self {
+        ParamValue::Int(value as i64)
+    }
+}
+
+impl From<u64> for ParamValue {
+    fn from(value: u64) -> Self {
+        ParamValue::Int(value as i64)
+    }
+}
+
+impl From<f64> for ParamValue {
+    fn from(value: f64) -> Self {
+        ParamValue::Float(value)
+    }
+}
+
+impl From<&str> for ParamValue {
+    fn from(value: &str) -> Self {
+        ParamValue::String(value.to_string())
+    }
+}
+
+impl From<String> for ParamValue {
+    fn from(value: String) -> Self {
+        ParamValue::String(value)
+    }
+}
+
+/// Function which applies the parameter value to the simulation config.
+pub type ConfigPatch = Arc<dyn Fn(&mut SimulationConfig, &ParamValue) + Send + Sync>;
+
+/// Metrics reported by a simulation run.
+pub type RunMetrics = IndexMap<String, f64>;
+
+struct Parameter {
+    name: String,
+    values: Vec<ParamValue>,
+    patch: Option<ConfigPatch>,
+}
+
+/// Single simulation run of the sweep.
+#[derive(Clone, Debug)]
+pub struct SweepRun {
+    /// Run id (starting from 1).
+    pub id: usize,
+    /// Values of swept parameters in the order of their definition.
+    pub params: IndexMap<String, ParamValue>,
+    /// Seed of the simulation.
+    pub seed: u64,
+    /// Simulation config with applied parameter values.
+    pub config: SimulationConfig,
+}
+
+impl SweepRun {
+    /// Returns the value of parameter.
+    pub fn param(&self, name: &str) -> &ParamValue {
+        self.params
+            .get(name)
+            .unwrap_or_else(|| panic!("unknown sweep parameter {}", name))
+    }
+}
+
+/// Result of simulation run.
+#[derive(Clone, Debug, Serialize)]
+pub struct SweepResult {
+    /// Run id.
+    pub run_id: usize,
+    /// Values of swept parameters.
+    pub params: IndexMap<String, ParamValue>,
+    /// Seed of the simulation.
+    pub seed: u64,
+    /// Metrics reported by the run.
+    pub metrics: RunMetrics,
+}
+
+/// Results of all simulation runs of the sweep ordered by run id.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct SweepResults {
+    param_names: Vec<String>,
+    results: Vec<SweepResult>,
+}
+
+impl SweepResults {
+    /// Returns the results of simulation runs.
+    pub fn results(&self) -> &[SweepResult] {
+        &self.results
+    }
+
+    /// Returns the names of swept parameters.
+    pub fn param_names(&self) -> &[String] {
+        &self.param_names
+    }
+
+    /// Returns the names of all reported metrics in the order of their first appearance.
+    pub fn metric_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = Vec::new();
+        for result in &self.results {
+            for name in result.metrics.keys() {
+                if !names.contains(name) {
+                    names.push(name.clone());
+                }
+            }
+        }
+        names
+    }
+
+    /// Returns the seeds and values of the metric in runs matching the filter, i.e. having the specified values
+    /// of the listed parameters. The runs which did not report the metric are skipped.
+    pub fn values(&self, metric: &str, filter: &[(&str, ParamValue)]) -> Vec<(u64, f64)> {
+        self.results
+            .iter()
+            .filter(|result| {
+                filter
+                    .iter()
+                    .all(|(name, value)| result.params.get(*name) == Some(value))
+            })
+            .filter_map(|result| result.metrics.get(metric).map(|value| (result.seed, *value)))
+            .collect()
+    }
+
+    /// Returns the summary of the metric across the seeds for each combination of parameter values.
+    pub fn summaries(&self, metric: &str) -> Vec<(IndexMap<String, ParamValue>, MetricSummary)> {
+        let mut groups: Vec<(IndexMap<String, ParamValue>, Vec<f64>)> = Vec::new();
+        for result in &self.results {
+            if let Some(value) = result.metrics.get(metric) {
+                match groups.iter_mut().find(|(params, _)| *params == result.params) {
+                    Some((_, values)) => values.push(*value),
+                    None => groups.push((result.params.clone(), vec![*value])),
+                }
+            }
+        }
+        groups
+            .into_iter()
+            .map(|(params, values)| (params, MetricSummary::from_values(&values)))
+            .collect()
+    }
+
+    /// Tests whether the mean of the metric differs between the runs matching two filters (e.g. two policies).
+    /// If both groups of runs used the same seeds, the paired t-test is used, otherwise the Welch's t-test is used.
+    /// Returns `None` if some group has less than two values of the metric.
+    pub fn compare(
+        &self,
+        metric: &str,
+        filter: &[(&str, ParamValue)],
+        other: &[(&str, ParamValue)],
+    ) -> Option<TestResult> {
+        let (seeds, values): (Vec<u64>, Vec<f64>) = self.values(metric, filter).into_iter().unzip();
+        let (other_seeds, other_values): (Vec<u64>, Vec<f64>) = self.values(metric, other).into_iter().unzip();
+        if values.len() < 2 || other_values.len() < 2 {
+            None
+        } else if seeds == other_seeds {
+            Some(paired_t_test(&values, &other_values))
+        } else {
+            Some(welch_t_test(&values, &other_values))
+        }
+    }
+
+    /// Writes the results as a table with one row per run and columns for run id, parameters, seed and metrics.
+    /// The metrics missing in some run are left empty.
+    pub fn write_csv<W: Write>(&self, writer: W) -> Result<(), String> {
+        let metric_names = self.metric_names();
+        let mut writer = csv::Writer::from_writer(writer);
+        let mut header = vec!["run_id".to_string()];
+        header.extend(self.param_names.iter().cloned());
+        header.push("seed".to_string());
+        header.extend(metric_names.iter().cloned());
+        writer.write_record(&header).map_err(|e| e.to_string())?;
+        for result in &self.results {
+            let mut row = vec![result.run_id.to_string()];
+            row.extend(self.param_names.iter().map(|name| result.params[name].to_string()));
+            row.push(result.seed.to_string());
+            row.extend(
+                metric_names
+                    .iter()
+                    .map(|name| result.metrics.get(name).map_or(String::new(), |v| v.to_string())),
+            );
+            writer.write_record(&row).map_err(|e| e.to_string())?;
+        }
+        writer.flush().map_err(|e| e.to_string())
+    }
+
+    /// Saves the results table to CSV file.
+    pub fn save_csv(&self, path: &str) -> Result<(), String> {
+        let file = File::create(path).map_err(|e| format!("cannot create {}: {}", path, e))?;
+        self.write_csv(file)
+    }
+}
+
+/// Parameter sweep experiment.
+///
+/// The sweep takes a base simulation config and a grid of parameter values, and runs the simulation for each
+/// combination of parameter values and seeds in a thread pool. Config parameters are applied to the base config
+/// by the specified patch functions, while other parameters (e.g. consolidation thresholds) are only passed to the
+/// run function, which is responsible for setting up the simulation, running it and reporting the metrics.
+/// The results are collected into a tidy table with one row per run.
+pub struct ParameterSweep {
+    base_config: SimulationConfig,
+    parameters: Vec<Parameter>,
+    seeds: Vec<u64>,
+    placement_algorithms: PlacementAlgorithmRegistry,
+}
+
+impl ParameterSweep {
+    /// Creates sweep with the base config, without parameters and with a single seed.
+    pub fn new(base_config: SimulationConfig) -> Self {
+        Self {
+            base_config,
+            parameters: Vec::new(),
+            seeds: vec![123],
+            placement_algorithms: PlacementAlgorithmRegistry::new(),
+        }
+    }
+
+    /// Adds parameter which is applied to the simulation config by the patch function.
+    pub fn with_config_parameter<V, F>(mut self, name: &str, values: Vec<V>, patch: F) -> Self
+    where
+        V: Into<ParamValue>,
+        F: Fn(&mut SimulationConfig, &ParamValue) + Send + Sync + 'static,
+    {
+        self.add_parameter(name, values, Some(Arc::new(patch)));
+        self
+    }
+
+    /// Adds parameter which is only passed to the run function.
+    pub fn with_parameter<V: Into<ParamValue>>(mut self, name: &str, values: Vec<V>) -> Self {
+        self.add_parameter(name, values, None);
+        self
+    }
+
+    /// Adds `algorithm` parameter which sets the VM placement algorithm of all schedulers in the config.
+    pub fn with_placement_algorithms(self, algorithms: &[&str]) -> Self {
+        self.with_config_parameter("algorithm", algorithms.to_vec(), |config, value| {
+            for scheduler in config.schedulers.iter_mut() {
+                scheduler.algorithm = value.to_string();
+            }
+        })
+    }
+
+    /// Sets the seeds, each parameter combination is run with every seed.
+    pub fn with_seeds<I: IntoIterator<Item = u64>>(mut self, seeds: I) -> Self {
+        self.seeds = seeds.into_iter().collect();
+        assert!(!self.seeds.is_empty(), "at least one seed should be specified");
+        self
+    }
+
+    /// Registers custom placement algorithm, which can be referenced by name in the config.
+    pub fn register_placement_algorithm<F>(&mut self, name: &str, factory: F)
+    where
+        F: Fn(&str) -> VMPlacementAlgorithm + Send + Sync + 'static,
+    {
+        self.placement_algorithms.register(name, factory);
+    }
+
+    fn add_parameter<V: Into<ParamValue>>(&mut self, name: &str, values: Vec<V>, patch: Option<ConfigPatch>) {
+        assert!(
+            self.parameters.iter().all(|p| p.name != name) && name != "run_id" && name != "seed",
+            "duplicate sweep parameter {}",
+            name
+        );
+        assert!(!values.is_empty(), "sweep parameter {} has no values", name);
+        self.parameters.push(Parameter {
+            name: name.to_string(),
+            values: values.into_iter().map(|v| v.into()).collect(),
+            patch,
+        });
+    }
+
+    /// Returns all runs of the sweep, where the first parameter changes the slowest and the seed changes the fastest.
+    pub fn runs(&self) -> Vec<SweepRun> {
+        let mut combinations: Vec<Vec<usize>> = vec![Vec::new()];
+        for param in &self.parameters {
+            combinations = combinations
+                .into_iter()
+                .flat_map(|combination| {
+                    (0..param.values.len()).map(move |i| {
+                        let mut next = combination.clone();
+                        next.push(i);
+                        next
+                    })
+                })
+                .collect();
+        }
+        let mut runs = Vec::new();
+        for combination in combinations {
+            let mut config = self.base_config.clone();
+            let mut params = IndexMap::new();
+            for (param, value_idx) in self.parameters.iter().zip(combination) {
+                let value = &param.values[value_idx];
+                if let Some(patch) = &param.patch {
+                    patch(&mut config, value);
+                }
+                params.insert(param.name.clone(), value.clone());
+            }
+            for seed in &self.seeds {
+                runs.push(SweepRun {
+                    id: runs.len() + 1,
+                    params: params.clone(),
+                    seed: *seed,
+                    config: config.clone(),
+                });
+            }
+        }
+        runs
+    }
+
+    /// Runs the sweep using the specified number of threads.
+    ///
+    /// For each run, the simulation is created from the run config and seed, and passed to the run function
+    /// together with the run description.
+    pub fn run<F>(&self, num_threads: usize, run_fn: F) -> SweepResults
+    where
+        F: Fn(&mut CloudSimulation, &SweepRun) -> RunMetrics + Send + Sync + 'static,
+    {
+        let run_fn = Arc::new(run_fn);
+        let results = Arc::new(Mutex::new(Vec::new()));
+        let pool = ThreadPool::new(num_threads);
+
+        for run in self.runs() {
+            let run_fn = run_fn.clone();
+            let results = results.clone();
+            let placement_algorithms = self.placement_algorithms.clone();
+
+            pool.execute(move || {
+                let sim = Simulation::new(run.seed);
+                let mut cloud_sim = CloudSimulation::with_placement_algorithms(
+                    sim,
+                    run.config.clone(),
+                    Box::new(StdoutLogger::new()),
+                    placement_algorithms,
+                );
+                let metrics = run_fn(&mut cloud_sim, &run);
+                results.lock().unwrap().push(SweepResult {
+                    run_id: run.id,
+                    params: run.params,
+                    seed: run.seed,
+                    metrics,
+                });
+            });
+        }
+
+        pool.join();
+        assert_eq!(pool.panic_count(), 0, "some sweep runs have panicked");
+        let mut results = Arc::try_unwrap(results).unwrap().into_inner().unwrap();
+        results.sort_by_key(|result| result.run_id);
+        SweepResults {
+            param_names: self.parameters.iter().map(|p| p.name.clone()).collect(),
+            results,
+        }
+    }
+}
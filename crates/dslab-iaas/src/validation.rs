@@ -0,0 +1,283 @@
+//! Validation scenarios with analytically known or reference simulator results.
+//!
+//! Each scenario builds a small canonical simulation, runs it and compares the obtained metrics with the expected
+//! values, which are either derived analytically or reproduced with CloudSim. The scenarios are asserted in tests
+//! to ensure that changes in the models do not silently alter their semantics, and can also be run by users
+//! to check custom builds via [`run_all`].
+
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+
+use sugars::{boxed, rc, refcell};
+
+use dslab_core::{cast, Event, EventHandler, Simulation};
+use dslab_models::power::cpu_models::linear::LinearCpuPowerModel;
+use dslab_models::power::host::HostPowerModelBuilder;
+use dslab_network::models::{ConstantBandwidthNetworkModel, SharedBandwidthNetworkModel};
+use dslab_network::{DataTransferCompleted, Network, NetworkModel};
+
+use crate::core::config::sim_config::{ControlPlaneConfig, SimulationConfig};
+use crate::core::vm::ResourceConsumer;
+use crate::core::vm_placement_algorithm::VMPlacementAlgorithm;
+use crate::core::vm_placement_algorithms::first_fit::FirstFit;
+use crate::simulation::CloudSimulation;
+
+/// Source of the expected values.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Reference {
+    /// Values derived analytically.
+    Analytical,
+    /// Values obtained by running the equivalent scenario in CloudSim.
+    CloudSim,
+}
+
+/// Comparison of a single metric with its expected value.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Check {
+    /// Metric name.
+    pub metric: String,
+    /// Expected value.
+    pub expected: f64,
+    /// Value obtained in simulation.
+    pub actual: f64,
+    /// Maximum allowed absolute difference.
+    pub tolerance: f64,
+}
+
+impl Check {
+    fn new(metric: &str, expected: f64, actual: f64) -> Self {
+        Self {
+            metric: metric.to_string(),
+            expected,
+            actual,
+            tolerance: 1e-9,
+        }
+    }
+
+    /// Checks whether the obtained value matches the expected one.
+    pub fn passed(&self) -> bool {
+        (self.actual - self.expected).abs() <= self.tolerance
+    }
+}
+
+/// Results of validation scenario.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ValidationCase {
+    /// Scenario name.
+    pub name: String,
+    /// Source of the expected values.
+    pub reference: Reference,
+    /// Checked metrics.
+    pub checks: Vec<Check>,
+}
+
+impl ValidationCase {
+    /// Checks whether all metrics match the expected values.
+    pub fn passed(&self) -> bool {
+        self.checks.iter().all(|check| check.passed())
+    }
+
+    /// Returns the checks which did not pass.
+    pub fn failures(&self) -> Vec<&Check> {
+        self.checks.iter().filter(|check| !check.passed()).collect()
+    }
+}
+
+impl fmt::Display for ValidationCase {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let status = if self.passed() { "OK" } else { "FAILED" };
+        writeln!(f, "{} ({:?}): {}", self.name, self.reference, status)?;
+        for check in &self.checks {
+            writeln!(
+                f,
+                "  {}: expected {}, actual {}",
+                check.metric, check.expected, check.actual
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Runs all validation scenarios.
+pub fn run_all() -> Vec<ValidationCase> {
+    vec![
+        simple_packing(),
+        single_host_energy(),
+        constant_bandwidth_transfer(),
+        shared_bandwidth_transfer(),
+    ]
+}
+
+// Config with zero control-plane latencies and VM start/stop durations,
+// so that the results depend only on the validated model.
+fn zero_latency_config() -> SimulationConfig {
+    SimulationConfig {
+        send_stats_period: 0.5,
+        message_delay: 0.,
+        allocation_retry_period: 1.,
+        vm_start_duration: 0.,
+        vm_stop_duration: 0.,
+        allow_vm_overcommit: false,
+        network_throughput: 1,
+        migration_max_rounds: 30,
+        migration_max_downtime: 0.3,
+        simulation_length: 0.,
+        step_duration: 500.,
+        vm_allocation_timeout: 50.,
+        trace: None,
+        hosts: Vec::new(),
+        schedulers: Vec::new(),
+        tenants: Vec::new(),
+        control_plane: ControlPlaneConfig::default(),
+    }
+}
+
+/// Packing of six VMs requiring 4, 4, 4, 2, 6 and 2 CPUs onto three hosts with 8 CPUs using First Fit.
+///
+/// The VMs are submitted one by one, so the expected placement is (h1, h1, h2, h2, h3, h2),
+/// which gives the CPU allocation rate of 22 / 24.
+pub fn simple_packing() -> ValidationCase {
+    let mut cloud_sim = CloudSimulation::new(Simulation::new(123), zero_latency_config());
+    let hosts: Vec<u32> = (1..=3)
+        .map(|i| cloud_sim.add_host(&format!("h{}", i), 8, 100))
+        .collect();
+    let scheduler = cloud_sim.add_scheduler("s", VMPlacementAlgorithm::single(FirstFit::new()));
+    let vms: Vec<u32> = [4, 4, 4, 2, 6, 2]
+        .iter()
+        .enumerate()
+        .map(|(i, cpu)| {
+            cloud_sim.spawn_vm_with_delay(
+                ResourceConsumer::with_full_load(*cpu, 1),
+                100.,
+                None,
+                scheduler,
+                i as f64,
+            )
+        })
+        .collect();
+    cloud_sim.step_for_duration(10.);
+
+    let expected_hosts = [0, 0, 1, 1, 2, 1];
+    let mut checks: Vec<Check> = vms
+        .iter()
+        .zip(expected_hosts)
+        .enumerate()
+        .map(|(i, (vm, host_idx))| {
+            let actual = cloud_sim
+                .vm_location(*vm)
+                .and_then(|host| hosts.iter().position(|h| *h == host))
+                .map_or(-1., |idx| idx as f64);
+            Check::new(&format!("vm{}_host", i + 1), host_idx as f64, actual)
+        })
+        .collect();
+    checks.push(Check::new(
+        "cpu_allocation_rate",
+        22. / 24.,
+        cloud_sim.cpu_allocation_rate(),
+    ));
+    ValidationCase {
+        name: "simple_packing".to_string(),
+        reference: Reference::Analytical,
+        checks,
+    }
+}
+
+/// Energy consumed by a single host with linear power model (175 W when idle, 250 W at full load, as
+/// `PowerModelLinear(250, 0.7)` in CloudSim), which runs a VM using half of its CPUs during 4 seconds
+/// and then stays idle for 6 seconds.
+///
+/// The host consumes 212.5 W while the VM is running, so the energy is 212.5 * 4 + 175 * 6 = 1900 J.
+pub fn single_host_energy() -> ValidationCase {
+    let mut cloud_sim = CloudSimulation::new(Simulation::new(123), zero_latency_config());
+    cloud_sim.set_host_power_model(
+        HostPowerModelBuilder::new()
+            .cpu(Box::new(LinearCpuPowerModel::new(175., 250.)))
+            .build(),
+    );
+    let host = cloud_sim.add_host("h", 8, 100);
+    let scheduler = cloud_sim.add_scheduler("s", VMPlacementAlgorithm::single(FirstFit::new()));
+    cloud_sim.spawn_vm_now(ResourceConsumer::with_full_load(4, 50), 4., None, scheduler);
+    cloud_sim.step_for_duration(10.);
+
+    let end_time = cloud_sim.current_time();
+    let energy = cloud_sim.host(host).borrow_mut().get_energy_consumed(end_time);
+    ValidationCase {
+        name: "single_host_energy".to_string(),
+        reference: Reference::CloudSim,
+        checks: vec![
+            Check::new("end_time", 10., end_time),
+            Check::new("energy", 1900., energy),
+        ],
+    }
+}
+
+#[derive(Default)]
+struct TransferReceiver {
+    completed: Vec<f64>,
+}
+
+impl EventHandler for TransferReceiver {
+    fn on(&mut self, event: Event) {
+        cast!(match event.data {
+            DataTransferCompleted { .. } => {
+                self.completed.push(event.time);
+            }
+        })
+    }
+}
+
+// Starts three simultaneous transfers of 500 units between two hosts and returns their completion times.
+fn run_transfers(model: Box<dyn NetworkModel>) -> Vec<f64> {
+    let mut sim = Simulation::new(123);
+    let mut network = Network::new(model, sim.create_context("net"));
+    let src = sim.create_context("src").id();
+    let dst = sim.create_context("dst").id();
+    network.add_node("a", boxed!(ConstantBandwidthNetworkModel::new(1e9, 0.)));
+    network.add_node("b", boxed!(ConstantBandwidthNetworkModel::new(1e9, 0.)));
+    network.set_location(src, "a");
+    network.set_location(dst, "b");
+    let network = rc!(refcell!(network));
+    sim.add_handler("net", network.clone());
+    let receiver: Rc<RefCell<TransferReceiver>> = rc!(refcell!(TransferReceiver::default()));
+    let receiver_id = sim.add_handler("receiver", receiver.clone());
+    for _ in 0..3 {
+        network.borrow_mut().transfer_data(src, dst, 500., receiver_id);
+    }
+    sim.step_until_no_events();
+    let completed = receiver.borrow().completed.clone();
+    completed
+}
+
+fn transfer_checks(completed: &[f64], expected: f64) -> Vec<Check> {
+    let mut checks = vec![Check::new("completed_transfers", 3., completed.len() as f64)];
+    checks.extend(
+        completed
+            .iter()
+            .enumerate()
+            .map(|(i, time)| Check::new(&format!("transfer{}_time", i + 1), expected, *time)),
+    );
+    checks
+}
+
+/// Three simultaneous transfers of 500 units over the network with constant bandwidth of 100 per transfer
+/// and latency of 0.1, where each transfer completes at 0.1 + 500 / 100 = 5.1.
+pub fn constant_bandwidth_transfer() -> ValidationCase {
+    let completed = run_transfers(boxed!(ConstantBandwidthNetworkModel::new(100., 0.1)));
+    ValidationCase {
+        name: "constant_bandwidth_transfer".to_string(),
+        reference: Reference::Analytical,
+        checks: transfer_checks(&completed, 5.1),
+    }
+}
+
+/// Three simultaneous transfers of 500 units over the network with bandwidth of 100 fairly shared between
+/// the transfers and latency of 0.1, where all transfers complete at 0.1 + 3 * 500 / 100 = 15.1.
+pub fn shared_bandwidth_transfer() -> ValidationCase {
+    let completed = run_transfers(boxed!(SharedBandwidthNetworkModel::new(100., 0.1)));
+    ValidationCase {
+        name: "shared_bandwidth_transfer".to_string(),
+        reference: Reference::Analytical,
+        checks: transfer_checks(&completed, 15.1),
+    }
+}
@@ -1,8 +1,15 @@
 use dslab_core::simulation::Simulation;
 
-use dslab_iaas::core::config::sim_config::SimulationConfig;
+use dslab_iaas::core::common::{Allocation, AllocationVerdict};
+use dslab_iaas::core::config::options::parse_options;
+use dslab_iaas::core::config::sim_config::{SchedulerConfig, SimulationConfig};
+use dslab_iaas::core::logger::StdoutLogger;
+use dslab_iaas::core::monitoring::Monitoring;
+use dslab_iaas::core::resource_pool::ResourcePoolState;
 use dslab_iaas::core::vm::ResourceConsumer;
-use dslab_iaas::core::vm_placement_algorithm::VMPlacementAlgorithm;
+use dslab_iaas::core::vm_placement_algorithm::{
+    PlacementAlgorithmRegistry, SingleVMPlacementAlgorithm, VMPlacementAlgorithm,
+};
 use dslab_iaas::core::vm_placement_algorithms::best_fit::BestFit;
 use dslab_iaas::core::vm_placement_algorithms::cosine_similarity::CosineSimilarity;
 use dslab_iaas::core::vm_placement_algorithms::delta_perp_distance::DeltaPerpDistance;
@@ -170,3 +177,58 @@ fn test_batch_rack_anti_affinity() {
         vec!["h1", "h2", "h3"],
     );
 }
+
+// Selects the n-th host which can accommodate the VM.
+struct NthFit {
+    n: usize,
+}
+
+impl SingleVMPlacementAlgorithm for NthFit {
+    fn select_host(&self, alloc: &Allocation, pool_state: &ResourcePoolState, _monitoring: &Monitoring) -> Option<u32> {
+        pool_state
+            .get_host_ids()
+            .into_iter()
+            .filter(|host| pool_state.can_allocate(alloc, *host, false) == AllocationVerdict::Success)
+            .nth(self.n)
+    }
+}
+
+#[test]
+fn test_custom_algorithm_from_config() {
+    let mut registry = PlacementAlgorithmRegistry::new();
+    registry.register("NthFit", |options| {
+        let n = parse_options(options).get("n").unwrap().parse().unwrap();
+        VMPlacementAlgorithm::single(NthFit { n })
+    });
+    assert!(registry.contains("NthFit"));
+    assert!(registry.contains("BestFit"));
+
+    let sim = Simulation::new(123);
+    let mut sim_config = SimulationConfig::from_file("test-configs/config_zero_latency.yaml");
+    sim_config.schedulers.push(SchedulerConfig {
+        name: Some("s".to_string()),
+        name_prefix: None,
+        algorithm: "NthFit[n=2]".to_string(),
+        count: None,
+        retry_policy: None,
+        preemption_policy: None,
+        fair_sharing: None,
+    });
+    let mut cloud_sim =
+        CloudSimulation::with_placement_algorithms(sim, sim_config, Box::new(StdoutLogger::new()), registry);
+    let hosts: Vec<u32> = (1..=4)
+        .map(|i| cloud_sim.add_host(&format!("h{}", i), 10, 10))
+        .collect();
+    let s = cloud_sim.lookup_id("s");
+
+    let vm = cloud_sim.spawn_vm_now(ResourceConsumer::with_full_load(5, 5), 10., None, s);
+    cloud_sim.step_for_duration(1.);
+    assert_eq!(cloud_sim.vm_location(vm), Some(hosts[2]));
+
+    // the algorithm can also be resolved by name after the simulation is created
+    cloud_sim.register_placement_algorithm("SecondFit", |_| VMPlacementAlgorithm::single(NthFit { n: 1 }));
+    let s2 = cloud_sim.add_scheduler("s2", cloud_sim.resolve_placement_algorithm("SecondFit"));
+    let vm = cloud_sim.spawn_vm_now(ResourceConsumer::with_full_load(5, 5), 10., None, s2);
+    cloud_sim.step_for_duration(1.);
+    assert_eq!(cloud_sim.vm_location(vm), Some(hosts[1]));
+}
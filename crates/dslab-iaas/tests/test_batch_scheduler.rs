@@ -0,0 +1,201 @@
+use dslab_core::simulation::Simulation;
+
+use dslab_iaas::core::config::sim_config::SimulationConfig;
+use dslab_iaas::custom_component::CustomComponent;
+use dslab_iaas::extensions::batch_scheduler::{BackfillingPolicy, BatchJob, BatchScheduler};
+use dslab_iaas::extensions::swf_reader::SwfReader;
+use dslab_iaas::simulation::CloudSimulation;
+
+fn name_wrapper(file_name: &str) -> String {
+    format!("test-configs/{}", file_name)
+}
+
+#[test]
+// The classic example where EASY backfilling starts a long job which delays the second waiting job,
+// while conservative backfilling only starts a short job which does not delay any waiting job.
+fn test_batch_scheduler_backfilling() {
+    for (backfilling, expected_starts, expected_wait) in [
+        (BackfillingPolicy::None, vec![0., 10., 20., 30., 30.], 16.),
+        (BackfillingPolicy::Easy, vec![0., 10., 28., 3., 10.], 8.2),
+        (BackfillingPolicy::Conservative, vec![0., 10., 20., 30., 4.], 10.8),
+    ] {
+        let sim = Simulation::new(123);
+        let sim_config = SimulationConfig::from_file(&name_wrapper("config_zero_latency.yaml"));
+        let mut cloud_sim = CloudSimulation::new(sim, sim_config);
+        let scheduler = cloud_sim.build_custom_component::<BatchScheduler>("batch");
+        scheduler.borrow_mut().patch_custom_args(backfilling);
+        for i in 0..4 {
+            let host = cloud_sim.add_host(&format!("node{}", i), 16, 64);
+            scheduler.borrow_mut().add_node(&cloud_sim.host(host).borrow());
+        }
+        scheduler.borrow_mut().init();
+
+        let jobs = [
+            (1, 0., 3, 10.),
+            (2, 1., 2, 10.),
+            (3, 2., 4, 10.),
+            (4, 3., 1, 25.),
+            (5, 4., 1, 5.),
+        ]
+        .into_iter()
+        .map(|(id, submit_time, nodes, walltime)| BatchJob {
+            id,
+            submit_time,
+            nodes,
+            walltime,
+            runtime: walltime,
+            queue: 0,
+        })
+        .collect();
+        scheduler.borrow_mut().submit_jobs(jobs);
+        cloud_sim.step_until_time(100.);
+
+        let scheduler = scheduler.borrow();
+        let mut records = scheduler.job_records().to_vec();
+        records.sort_by_key(|r| r.job.id);
+        let starts: Vec<f64> = records.iter().map(|r| r.start_time).collect();
+        assert_eq!(starts, expected_starts, "{:?}", backfilling);
+        let stats = scheduler.stats();
+        assert_eq!(stats.completed, 5);
+        assert!((stats.mean_wait_time - expected_wait).abs() < 1e-9);
+        assert_eq!(scheduler.free_nodes(), 4);
+    }
+}
+
+#[test]
+// Jobs from the high priority queue are started first, the job exceeding its walltime is killed,
+// and the job requesting more nodes than the cluster has is rejected.
+fn test_batch_scheduler_queues() {
+    let sim = Simulation::new(123);
+    let sim_config = SimulationConfig::from_file(&name_wrapper("config_zero_latency.yaml"));
+    let mut cloud_sim = CloudSimulation::new(sim, sim_config);
+    let scheduler = cloud_sim.build_custom_component::<BatchScheduler>("batch");
+    scheduler.borrow_mut().patch_custom_args(BackfillingPolicy::Easy);
+    for i in 0..2 {
+        let host = cloud_sim.add_host(&format!("node{}", i), 16, 64);
+        scheduler.borrow_mut().add_node(&cloud_sim.host(host).borrow());
+    }
+    scheduler.borrow_mut().init();
+    scheduler.borrow_mut().add_queue(1, 10);
+
+    scheduler.borrow_mut().submit_jobs(vec![
+        BatchJob {
+            id: 1,
+            submit_time: 0.,
+            nodes: 2,
+            walltime: 10.,
+            runtime: 15.,
+            queue: 0,
+        },
+        BatchJob {
+            id: 2,
+            submit_time: 1.,
+            nodes: 2,
+            walltime: 5.,
+            runtime: 5.,
+            queue: 0,
+        },
+        BatchJob {
+            id: 3,
+            submit_time: 2.,
+            nodes: 2,
+            walltime: 5.,
+            runtime: 5.,
+            queue: 1,
+        },
+        BatchJob {
+            id: 4,
+            submit_time: 3.,
+            nodes: 3,
+            walltime: 5.,
+            runtime: 5.,
+            queue: 0,
+        },
+    ]);
+    cloud_sim.step_until_time(5.);
+    assert_eq!(scheduler.borrow().running_jobs(), vec![1]);
+    assert_eq!(scheduler.borrow().waiting_jobs(), vec![3, 2]);
+    cloud_sim.step_until_time(100.);
+
+    let scheduler = scheduler.borrow();
+    let records: Vec<(u32, f64, f64, bool)> = scheduler
+        .job_records()
+        .iter()
+        .map(|r| (r.job.id, r.start_time, r.finish_time, r.killed))
+        .collect();
+    assert_eq!(
+        records,
+        vec![(1, 0., 10., true), (3, 10., 15., false), (2, 15., 20., false)]
+    );
+    let stats = scheduler.stats();
+    assert_eq!(
+        (stats.submitted, stats.completed, stats.killed, stats.rejected),
+        (4, 2, 1, 1)
+    );
+    assert_eq!(stats.makespan, 20.);
+    assert_eq!(stats.utilization, 1.);
+}
+
+#[test]
+fn test_swf_reader() {
+    let trace = "\
+; Version: 2.2
+; MaxNodes: 4
+1 0 5 100 8 -1 -1 8 200 -1 1 1 1 1 1 -1 -1 -1
+2 10 0 50 -1 -1 -1 4 -1 -1 1 1 1 1 2 -1 -1 -1
+3 20 0 -1 4 -1 -1 4 100 -1 5 1 1 1 1 -1 -1 -1
+4 5 0 30 16 -1 -1 -1 60 -1 1 1 1 1 1 -1 -1 -1
+";
+    let jobs = SwfReader::new(4).read_str(trace).unwrap();
+    // the cancelled job 3 is skipped, the jobs are sorted by submission time
+    assert_eq!(
+        jobs,
+        vec![
+            BatchJob {
+                id: 1,
+                submit_time: 0.,
+                nodes: 2,
+                walltime: 200.,
+                runtime: 100.,
+                queue: 1
+            },
+            BatchJob {
+                id: 4,
+                submit_time: 5.,
+                nodes: 4,
+                walltime: 60.,
+                runtime: 30.,
+                queue: 1
+            },
+            BatchJob {
+                id: 2,
+                submit_time: 10.,
+                nodes: 1,
+                walltime: 50.,
+                runtime: 50.,
+                queue: 2
+            },
+        ]
+    );
+    assert!(SwfReader::new(4).read_str("1 2 3").is_err());
+
+    let sim = Simulation::new(123);
+    let sim_config = SimulationConfig::from_file(&name_wrapper("config_zero_latency.yaml"));
+    let mut cloud_sim = CloudSimulation::new(sim, sim_config);
+    let scheduler = cloud_sim.build_custom_component::<BatchScheduler>("batch");
+    scheduler
+        .borrow_mut()
+        .patch_custom_args(BackfillingPolicy::Conservative);
+    for i in 0..4 {
+        let host = cloud_sim.add_host(&format!("node{}", i), 16, 64);
+        scheduler.borrow_mut().add_node(&cloud_sim.host(host).borrow());
+    }
+    scheduler.borrow_mut().init();
+    scheduler.borrow_mut().submit_jobs(jobs);
+    cloud_sim.step_until_time(1000.);
+    let stats = scheduler.borrow().stats();
+    assert_eq!(stats.completed, 3);
+    // job 2 is backfilled while job 4 waits for job 1, which completes earlier than its walltime
+    assert_eq!(stats.makespan, 130.);
+    assert_eq!(stats.max_wait_time, 95.);
+}
@@ -0,0 +1,123 @@
+use dslab_core::faults::{FaultKind, FaultScenario, FaultSpec, FaultTrigger};
+use dslab_core::simulation::Simulation;
+
+use dslab_iaas::core::config::sim_config::SimulationConfig;
+use dslab_iaas::custom_component::CustomComponent;
+use dslab_iaas::extensions::checkpointing::{
+    daly_interval, young_interval, CheckpointConfig, CheckpointedJob, JobPhase,
+};
+use dslab_iaas::simulation::CloudSimulation;
+
+fn name_wrapper(file_name: &str) -> String {
+    format!("test-configs/{}", file_name)
+}
+
+#[test]
+// Job with work 100 saves checkpoints every 20 units of work (2 seconds to write, 1 second to read).
+// The crash at time 50 loses 6 units of work since the checkpoint at 44,
+// the job is restarted 3 seconds after the recovery at 55 and restores the checkpoint until 59.
+fn test_checkpoint_restart() {
+    let sim = Simulation::new(123);
+    let sim_config = SimulationConfig::from_file(&name_wrapper("config_zero_latency.yaml"));
+    let mut cloud_sim = CloudSimulation::new(sim, sim_config);
+    let job = cloud_sim.build_custom_component::<CheckpointedJob>("job");
+    job.borrow_mut().patch_custom_args(
+        CheckpointConfig::new(100.)
+            .with_interval(20.)
+            .with_state(100, 50., 100.)
+            .with_restart_delay(3.),
+    );
+    job.borrow_mut().init();
+    job.borrow_mut().start(0.);
+    let fault = FaultSpec::new("job", FaultKind::Crash, FaultTrigger::At { time: 50. }).with_duration(5.);
+    cloud_sim
+        .install_faults(&FaultScenario::default().with_fault(fault))
+        .unwrap();
+    cloud_sim.step_for_duration(300.);
+
+    let job = job.borrow();
+    assert_eq!(job.phase(), JobPhase::Completed);
+    assert_eq!(job.checkpointed_work(), 80.);
+    let stats = job.stats();
+    assert_eq!(stats.makespan, Some(123.));
+    assert_eq!(stats.checkpoints, 4);
+    assert_eq!(stats.failures, 1);
+    assert_eq!(stats.restores, 1);
+    assert_eq!(stats.checkpoint_time, 8.);
+    assert_eq!(stats.restore_time, 1.);
+    assert_eq!(stats.down_time, 8.);
+    assert_eq!(stats.lost_work, 6.);
+    assert_eq!(stats.bytes_written, 400);
+    assert_eq!(stats.bytes_read, 100);
+}
+
+#[test]
+// Without checkpoints the job from the previous test loses 50 units of work and starts from scratch at 58.
+fn test_restart_without_checkpoints() {
+    let sim = Simulation::new(123);
+    let sim_config = SimulationConfig::from_file(&name_wrapper("config_zero_latency.yaml"));
+    let mut cloud_sim = CloudSimulation::new(sim, sim_config);
+    let job = cloud_sim.build_custom_component::<CheckpointedJob>("job");
+    job.borrow_mut().patch_custom_args(
+        CheckpointConfig::new(100.)
+            .with_state(100, 50., 100.)
+            .with_restart_delay(3.),
+    );
+    job.borrow_mut().init();
+    job.borrow_mut().start(0.);
+    let fault = FaultSpec::new("job", FaultKind::Crash, FaultTrigger::At { time: 50. }).with_duration(5.);
+    cloud_sim
+        .install_faults(&FaultScenario::default().with_fault(fault))
+        .unwrap();
+    cloud_sim.step_for_duration(300.);
+
+    let job = job.borrow();
+    assert_eq!(job.phase(), JobPhase::Completed);
+    let stats = job.stats();
+    assert_eq!(stats.makespan, Some(158.));
+    assert_eq!(stats.checkpoints, 0);
+    assert_eq!(stats.restores, 0);
+    assert_eq!(stats.lost_work, 50.);
+    assert_eq!(stats.efficiency(100.), Some(100. / 158.));
+}
+
+#[test]
+// The crash at time 21 interrupts the first checkpoint, so the job restarts from scratch
+// right after the permanent fault and the restart delay.
+fn test_checkpoint_aborted() {
+    let config = CheckpointConfig::new(50.)
+        .with_interval(20.)
+        .with_state(100, 50., 100.)
+        .with_io_latency(1.)
+        .with_restart_delay(2.);
+    assert_eq!(config.checkpoint_cost(), 3.);
+    assert_eq!(config.restore_cost(), 2.);
+
+    let sim = Simulation::new(123);
+    let sim_config = SimulationConfig::from_file(&name_wrapper("config_zero_latency.yaml"));
+    let mut cloud_sim = CloudSimulation::new(sim, sim_config);
+    let job = cloud_sim.build_custom_component::<CheckpointedJob>("job");
+    job.borrow_mut().patch_custom_args(config);
+    job.borrow_mut().init();
+    job.borrow_mut().start(0.);
+    let fault = FaultSpec::new("job", FaultKind::Crash, FaultTrigger::At { time: 21. });
+    cloud_sim
+        .install_faults(&FaultScenario::default().with_fault(fault))
+        .unwrap();
+    cloud_sim.step_for_duration(300.);
+
+    let job = job.borrow();
+    let stats = job.stats();
+    assert_eq!(stats.failures, 1);
+    assert_eq!(stats.aborted_checkpoints, 1);
+    assert_eq!(stats.lost_work, 20.);
+    assert_eq!(stats.restores, 0);
+    assert_eq!(stats.checkpoints, 2);
+    assert_eq!(stats.checkpoint_time, 7.);
+    // restart at 23, then 50 units of work and 2 checkpoints
+    assert_eq!(stats.makespan, Some(79.));
+
+    assert_eq!(young_interval(2., 100.), 20.);
+    assert!(daly_interval(2., 100.) > 18. && daly_interval(2., 100.) < young_interval(2., 100.));
+    assert_eq!(daly_interval(10., 4.), 4.);
+}
@@ -0,0 +1,121 @@
+use dslab_core::simulation::Simulation;
+
+use dslab_iaas::core::config::sim_config::SimulationConfig;
+use dslab_iaas::core::vm::VmStatus;
+use dslab_iaas::core::vm_placement_algorithm::VMPlacementAlgorithm;
+use dslab_iaas::core::vm_placement_algorithms::first_fit::FirstFit;
+use dslab_iaas::custom_component::CustomComponent;
+use dslab_iaas::extensions::cloud_broker::{
+    BrokerPolicy, CheapestProvider, CloudBroker, CloudProvider, LeastLoadedProvider, NearestProvider,
+};
+use dslab_iaas::simulation::CloudSimulation;
+
+fn name_wrapper(file_name: &str) -> String {
+    format!("test-configs/{}", file_name)
+}
+
+#[test]
+// Broker distributes requests across providers according to the policy and runs VMs on the provider hosts.
+// Provider "cheap" has one host with 8 cores, "near" has one host with 8 cores and the lowest latency,
+// "big" has two hosts with 8 cores and the medium price and latency.
+fn test_broker_policies() {
+    for (policy, expected_counts, expected_cost) in [
+        (Box::new(CheapestProvider) as Box<dyn BrokerPolicy>, [2, 0, 4], 4000.),
+        (Box::new(NearestProvider), [0, 2, 4], 5600.),
+        (Box::new(LeastLoadedProvider), [2, 1, 3], 4400.),
+    ] {
+        let sim = Simulation::new(123);
+        let sim_config = SimulationConfig::from_file(&name_wrapper("config_zero_latency.yaml"));
+        let mut cloud_sim = CloudSimulation::new(sim, sim_config);
+        let broker = cloud_sim.build_custom_component::<CloudBroker>("broker");
+        broker
+            .borrow_mut()
+            .patch_custom_args(policy, cloud_sim.vm_api(), cloud_sim.sim_config());
+        let mut provider_hosts = Vec::new();
+        for (name, host_count, cpu_price, latency) in
+            [("cheap", 1, 1., 0.1), ("near", 1, 3., 0.01), ("big", 2, 2., 0.05)]
+        {
+            let hosts: Vec<u32> = (0..host_count)
+                .map(|i| cloud_sim.add_host(&format!("{}-{}", name, i), 8, 64))
+                .collect();
+            let scheduler = cloud_sim.add_scheduler(name, VMPlacementAlgorithm::single(FirstFit::new()));
+            cloud_sim.scheduler(scheduler).borrow_mut().set_allowed_hosts(&hosts);
+            let index = broker.borrow_mut().add_provider(
+                CloudProvider::new(name, scheduler)
+                    .with_prices(cpu_price, 0.)
+                    .with_latency(latency),
+            );
+            for host in hosts.iter() {
+                broker
+                    .borrow_mut()
+                    .add_provider_host(index, &cloud_sim.host(*host).borrow());
+            }
+            provider_hosts.push(hosts);
+        }
+        broker.borrow_mut().init();
+
+        for i in 0..6 {
+            broker.borrow_mut().submit_request(4, 8, 100., i as f64);
+        }
+        cloud_sim.step_for_duration(10.);
+
+        let broker = broker.borrow();
+        for (index, hosts) in provider_hosts.iter().enumerate() {
+            let vms = broker.provider_vms(index);
+            assert_eq!(vms.len(), expected_counts[index]);
+            for vm_id in vms {
+                assert_eq!(cloud_sim.vm_status(vm_id), VmStatus::Running);
+                assert!(hosts.contains(&cloud_sim.vm_location(vm_id).unwrap()));
+            }
+        }
+        let stats = broker.stats();
+        assert_eq!(stats.submitted, 6);
+        assert_eq!(stats.rejected, 0);
+        assert_eq!(stats.total_cost, expected_cost);
+    }
+}
+
+#[test]
+// Requests exceeding the federation capacity are rejected until the running VMs are finished.
+fn test_broker_rejection() {
+    let sim = Simulation::new(123);
+    let sim_config = SimulationConfig::from_file(&name_wrapper("config_zero_latency.yaml"));
+    let mut cloud_sim = CloudSimulation::new(sim, sim_config);
+    let broker = cloud_sim.build_custom_component::<CloudBroker>("broker");
+    broker
+        .borrow_mut()
+        .patch_custom_args(Box::new(CheapestProvider), cloud_sim.vm_api(), cloud_sim.sim_config());
+    for (name, host_count, cpu_price, latency) in [("cheap", 1, 1., 0.1), ("near", 1, 3., 0.01), ("big", 2, 2., 0.05)] {
+        let hosts: Vec<u32> = (0..host_count)
+            .map(|i| cloud_sim.add_host(&format!("{}-{}", name, i), 8, 64))
+            .collect();
+        let scheduler = cloud_sim.add_scheduler(name, VMPlacementAlgorithm::single(FirstFit::new()));
+        cloud_sim.scheduler(scheduler).borrow_mut().set_allowed_hosts(&hosts);
+        let index = broker.borrow_mut().add_provider(
+            CloudProvider::new(name, scheduler)
+                .with_prices(cpu_price, 0.)
+                .with_latency(latency),
+        );
+        for host in hosts.iter() {
+            broker
+                .borrow_mut()
+                .add_provider_host(index, &cloud_sim.host(*host).borrow());
+        }
+    }
+    broker.borrow_mut().init();
+
+    for _ in 0..9 {
+        broker.borrow_mut().submit_request(4, 8, 100., 0.);
+    }
+    broker.borrow_mut().submit_request(4, 8, 100., 150.);
+    cloud_sim.step_for_duration(200.);
+
+    let stats = broker.borrow().stats();
+    assert_eq!(stats.submitted, 10);
+    assert_eq!(stats.rejected, 1);
+    let requests: Vec<u64> = stats.providers.values().map(|p| p.requests).collect();
+    // providers are ordered by name: big, cheap, near
+    assert_eq!(requests, vec![4, 3, 2]);
+    assert_eq!(stats.providers["near"].mean_latency, 0.01);
+    assert_eq!(stats.providers["cheap"].active, 1);
+}
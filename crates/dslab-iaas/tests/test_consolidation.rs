@@ -0,0 +1,242 @@
+use indexmap::IndexMap;
+
+use dslab_core::simulation::Simulation;
+
+use dslab_iaas::core::config::sim_config::{SchedulerConfig, SimulationConfig};
+use dslab_iaas::core::power_state::HostPowerState;
+use dslab_iaas::core::vm::ResourceConsumer;
+use dslab_iaas::custom_component::CustomComponent;
+use dslab_iaas::extensions::overload_detection::{
+    InterquartileRange, LocalRegression, MedianAbsoluteDeviation, OverloadDetector, StaticThreshold,
+};
+use dslab_iaas::extensions::vm_migrator::{IdleHostAction, VmMigrator};
+use dslab_iaas::extensions::vm_selection::{
+    MaximumCorrelation, MinimumMigrationTime, RandomSelection, VmCandidate, VmSelectionPolicy,
+};
+use dslab_iaas::simulation::CloudSimulation;
+use dslab_iaas::sweep::{ParamValue, ParameterSweep};
+
+#[test]
+fn test_static_threshold() {
+    let detector = StaticThreshold::new(0.8);
+    assert!(!detector.is_overloaded(&[]));
+    assert!(!detector.is_overloaded(&[0.9, 0.8]));
+    assert!(detector.is_overloaded(&[0.5, 0.9]));
+}
+
+#[test]
+// Stable load has zero MAD, so the threshold is 1 and the host is not overloaded.
+// Fluctuating load has MAD = 0.1, so the threshold with safety parameter 2.5 is 0.75.
+fn test_median_absolute_deviation() {
+    let detector = MedianAbsoluteDeviation::new(2.5, 5, 0.7);
+    // short history uses fallback threshold
+    assert!(detector.is_overloaded(&[0.75]));
+    assert!(!detector.is_overloaded(&[0.8, 0.8, 0.8, 0.8, 0.8]));
+    assert!(!detector.is_overloaded(&[0.5, 0.7, 0.6, 0.5, 0.7, 0.6, 0.7]));
+    assert!(detector.is_overloaded(&[0.5, 0.7, 0.6, 0.5, 0.7, 0.6, 0.8]));
+}
+
+#[test]
+// IQR of [0.4, 0.5, 0.6, 0.7, 0.8] is 0.2, so the threshold with safety parameter 1.5 is 0.7.
+// IQR of [0.4, 0.5, 0.6, 0.75, 0.8] is 0.25, so the threshold with safety parameter 1.5 is 0.625.
+fn test_interquartile_range() {
+    let detector = InterquartileRange::new(1.5, 5, 0.9);
+    assert!(!detector.is_overloaded(&[0.4, 0.8, 0.5, 0.7, 0.6]));
+    assert!(detector.is_overloaded(&[0.4, 0.8, 0.5, 0.6, 0.75]));
+}
+
+#[test]
+// Linearly growing load is predicted to exceed the capacity, while the stable load is not.
+fn test_local_regression() {
+    let detector = LocalRegression::new(1.2, 5, 0.9);
+    assert!(detector.is_overloaded(&[0.4, 0.5, 0.6, 0.7, 0.8]));
+    assert!(!detector.is_overloaded(&[0.8, 0.7, 0.6, 0.5, 0.4]));
+    assert!(!detector.is_overloaded(&[0.6, 0.6, 0.6, 0.6, 0.6]));
+}
+
+fn candidate(vm_id: u32, memory_usage: u64, cpu_history: Vec<f64>) -> VmCandidate {
+    VmCandidate {
+        vm_id,
+        cpu_usage: 1,
+        memory_usage,
+        cpu_history,
+    }
+}
+
+#[test]
+fn test_vm_selection() {
+    let mut sim = Simulation::new(123);
+    let ctx = sim.create_context("test");
+    let candidates = vec![
+        candidate(0, 8, vec![0.1, 0.5, 0.9, 0.5]),
+        candidate(1, 2, vec![0.5, 0.5, 0.5, 0.6]),
+        candidate(2, 4, vec![0.2, 0.6, 0.8, 0.4]),
+        candidate(3, 6, vec![0.3, 0.4, 0.2, 0.6]),
+    ];
+    assert_eq!(MinimumMigrationTime::new().select_vm(&candidates, &ctx), 1);
+    // sums of correlation coefficients with other VMs are 0.71, 0.62, 0.31 and 0.26
+    assert_eq!(MaximumCorrelation::new().select_vm(&candidates, &ctx), 0);
+    for _ in 0..10 {
+        assert!(RandomSelection::new().select_vm(&candidates, &ctx) < candidates.len());
+    }
+}
+
+#[test]
+// Host h1 is overloaded by three VMs (90% CPU load), the migrator uses MMT policy to select the VM with
+// the smallest memory usage for migration to host h2, which returns h1 load under the threshold.
+fn test_migrator_with_policies() {
+    let sim = Simulation::new(123);
+    let sim_config = SimulationConfig::from_file("test-configs/config_zero_latency.yaml");
+    let mut cloud_sim = CloudSimulation::new(sim, sim_config);
+
+    let h1 = cloud_sim.add_host("h1", 10, 100);
+    let h2 = cloud_sim.add_host("h2", 10, 100);
+    let vm1 = cloud_sim.spawn_vm_on_host(ResourceConsumer::with_full_load(3, 30), 100.0, None, h1);
+    let vm2 = cloud_sim.spawn_vm_on_host(ResourceConsumer::with_full_load(3, 10), 100.0, None, h1);
+    let vm3 = cloud_sim.spawn_vm_on_host(ResourceConsumer::with_full_load(3, 20), 100.0, None, h1);
+    cloud_sim.spawn_vm_on_host(ResourceConsumer::with_full_load(3, 10), 100.0, None, h2);
+    cloud_sim.step_for_duration(1.);
+
+    let migrator = cloud_sim.build_custom_component::<VmMigrator>("migrator");
+    migrator
+        .borrow_mut()
+        .patch_custom_args(5., cloud_sim.monitoring(), cloud_sim.vm_api(), cloud_sim.sim_config());
+    migrator
+        .borrow_mut()
+        .set_overload_detector(Box::new(StaticThreshold::new(0.8)));
+    migrator
+        .borrow_mut()
+        .set_vm_selection_policy(Box::new(MinimumMigrationTime::new()));
+    migrator.borrow_mut().init();
+    cloud_sim.step_for_duration(3.);
+
+    assert_eq!(cloud_sim.vm_location(vm1), Some(h1));
+    assert_eq!(cloud_sim.vm_location(vm2), Some(h2));
+    assert_eq!(cloud_sim.vm_location(vm3), Some(h1));
+}
+
+#[test]
+// Host h2 has no VMs, so the migrator puts it to sleep, while host h1 running the VM stays active.
+fn test_migrator_idle_host_action() {
+    let sim = Simulation::new(123);
+    let sim_config = SimulationConfig::from_file("test-configs/config_zero_latency.yaml");
+    let mut cloud_sim = CloudSimulation::new(sim, sim_config);
+
+    let h1 = cloud_sim.add_host("h1", 10, 100);
+    let h2 = cloud_sim.add_host("h2", 10, 100);
+    cloud_sim.spawn_vm_on_host(ResourceConsumer::with_full_load(5, 50), 100.0, None, h1);
+    cloud_sim.step_for_duration(1.);
+
+    let migrator = cloud_sim.build_custom_component::<VmMigrator>("migrator");
+    migrator
+        .borrow_mut()
+        .patch_custom_args(5., cloud_sim.monitoring(), cloud_sim.vm_api(), cloud_sim.sim_config());
+    migrator.borrow_mut().set_idle_host_action(IdleHostAction::Sleep);
+    migrator.borrow_mut().init();
+    cloud_sim.step_for_duration(3.);
+
+    assert_eq!(cloud_sim.host(h1).borrow().power_state(), HostPowerState::Active);
+    assert_eq!(cloud_sim.host(h2).borrow().power_state(), HostPowerState::Sleep);
+    assert_eq!(
+        cloud_sim.monitoring().borrow().get_host_state(h2).power_state,
+        HostPowerState::Sleep
+    );
+}
+
+#[test]
+// The migrator scenario from above is swept over two overload thresholds, two placement algorithms and two seeds.
+// Host h1 has 90% CPU load, so the VM is migrated only with the lower threshold.
+fn test_parameter_sweep() {
+    let mut base_config = SimulationConfig::from_file("test-configs/config_zero_latency.yaml");
+    base_config.schedulers.push(SchedulerConfig {
+        name: Some("s".to_string()),
+        name_prefix: None,
+        algorithm: "FirstFit".to_string(),
+        count: None,
+        retry_policy: None,
+        preemption_policy: None,
+        fair_sharing: None,
+    });
+    let sweep = ParameterSweep::new(base_config)
+        .with_parameter("threshold", vec![0.8, 0.95])
+        .with_placement_algorithms(&["FirstFit", "BestFit"])
+        .with_seeds([1, 2]);
+
+    let runs = sweep.runs();
+    assert_eq!(runs.len(), 8);
+    assert_eq!(runs[0].param("threshold"), &ParamValue::Float(0.8));
+    assert_eq!(runs[2].param("algorithm").as_str(), Some("BestFit"));
+    assert_eq!(runs[2].config.schedulers[0].algorithm, "BestFit");
+    assert_eq!(runs[3].seed, 2);
+
+    let results = sweep.run(4, |cloud_sim, run| {
+        let h1 = cloud_sim.add_host("h1", 10, 100);
+        let h2 = cloud_sim.add_host("h2", 10, 100);
+        cloud_sim.spawn_vm_on_host(ResourceConsumer::with_full_load(3, 30), 100.0, None, h1);
+        let vm = cloud_sim.spawn_vm_on_host(ResourceConsumer::with_full_load(3, 10), 100.0, None, h1);
+        cloud_sim.spawn_vm_on_host(ResourceConsumer::with_full_load(3, 20), 100.0, None, h1);
+        cloud_sim.step_for_duration(1.);
+
+        let migrator = cloud_sim.build_custom_component::<VmMigrator>("migrator");
+        migrator
+            .borrow_mut()
+            .patch_custom_args(5., cloud_sim.monitoring(), cloud_sim.vm_api(), cloud_sim.sim_config());
+        let threshold = run.param("threshold").as_f64().unwrap();
+        migrator
+            .borrow_mut()
+            .set_overload_detector(Box::new(StaticThreshold::new(threshold)));
+        migrator
+            .borrow_mut()
+            .set_vm_selection_policy(Box::new(MinimumMigrationTime::new()));
+        migrator.borrow_mut().init();
+        cloud_sim.step_for_duration(3.);
+
+        let mut metrics = IndexMap::new();
+        let migrated = cloud_sim.vm_location(vm) == Some(h2);
+        metrics.insert("migrations".to_string(), if migrated { 1. } else { 0. });
+        metrics.insert("time".to_string(), cloud_sim.current_time());
+        metrics
+    });
+
+    assert_eq!(results.param_names(), ["threshold", "algorithm"]);
+    assert_eq!(results.metric_names(), ["migrations", "time"]);
+    for (i, result) in results.results().iter().enumerate() {
+        assert_eq!(result.run_id, i + 1);
+        let expected = if i < 4 { 1. } else { 0. };
+        assert_eq!(result.metrics["migrations"], expected);
+    }
+
+    let summaries = results.summaries("migrations");
+    assert_eq!(summaries.len(), 4);
+    assert_eq!(summaries[1].0["algorithm"], ParamValue::from("BestFit"));
+    assert_eq!(summaries[1].1.count, 2);
+    assert_eq!(summaries[1].1.mean, 1.);
+    assert_eq!(summaries[3].1.mean, 0.);
+    let values = results.values("migrations", &[("threshold", 0.95.into())]);
+    assert_eq!(values, vec![(1, 0.), (2, 0.), (1, 0.), (2, 0.)]);
+    let comparison = results
+        .compare(
+            "migrations",
+            &[("threshold", 0.8.into())],
+            &[("threshold", 0.95.into())],
+        )
+        .unwrap();
+    assert_eq!(comparison.mean_difference, 1.);
+    assert!(comparison.is_significant(0.05));
+    assert!(results
+        .compare(
+            "time",
+            &[("algorithm", "FirstFit".into())],
+            &[("threshold", 0.9.into())]
+        )
+        .is_none());
+
+    let mut csv = Vec::new();
+    results.write_csv(&mut csv).unwrap();
+    let csv = String::from_utf8(csv).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines.len(), 9);
+    assert_eq!(lines[0], "run_id,threshold,algorithm,seed,migrations,time");
+    assert!(lines[1].starts_with("1,0.8,FirstFit,1,1,"));
+    assert!(lines[8].starts_with("8,0.95,BestFit,2,0,"));
+}
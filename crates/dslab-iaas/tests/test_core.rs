@@ -1,18 +1,56 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use rand::distributions::Uniform;
+
+use dslab_core::faults::{FaultKind, FaultSpec, FaultTrigger};
 use dslab_core::simulation::Simulation;
 
 use dslab_models::power::cpu_models::constant::ConstantCpuPowerModel;
+use dslab_models::power::cpu_models::linear::LinearCpuPowerModel;
 use dslab_models::power::host::HostPowerModelBuilder;
+use dslab_network::models::{SharedBandwidthNetworkModel, TopologyAwareNetworkModel};
+use dslab_network::Link;
 
 use dslab_iaas::core::common::Allocation;
-use dslab_iaas::core::config::sim_config::SimulationConfig;
+use dslab_iaas::core::config::options::parse_options;
+use dslab_iaas::core::config::sim_config::{HostConfig, SchedulerConfig, SimulationConfig};
+use dslab_iaas::core::control_plane::Delay;
+use dslab_iaas::core::gang::{GangSpec, GangTopology};
+use dslab_iaas::core::image::LruEviction;
+use dslab_iaas::core::interference::{CacheContention, InterferenceModel, MemoryBandwidthContention};
+use dslab_iaas::core::load_model::ConstantLoadModel;
+use dslab_iaas::core::migration::{estimate_precopy_migration, LoadDependentDirtyRate};
 use dslab_iaas::core::monitoring::Monitoring;
+use dslab_iaas::core::power_state::{HostPowerState, HostPowerStateConfig};
+use dslab_iaas::core::preemption::{preemption_policy_resolver, PreemptionPolicy};
+use dslab_iaas::core::quota::{QuotaExceededAction, TenantQuota, TenantUsage};
+use dslab_iaas::core::rate_limit::{ClientRetryPolicy, RateLimit};
 use dslab_iaas::core::resource_pool::ResourcePoolState;
+use dslab_iaas::core::retry_policy::{retry_policy_resolver, QueueOrdering, RetryPolicy};
 use dslab_iaas::core::slav_metric::OverloadTimeFraction;
-use dslab_iaas::core::vm::{ResourceConsumer, VmStatus};
+use dslab_iaas::core::thermal_model::ThermalModel;
+use dslab_iaas::core::timeline::VmIntervalEnd;
+use dslab_iaas::core::vm::{ResourceConsumer, VmSpec, VmStatus};
 use dslab_iaas::core::vm_placement_algorithm::{SingleVMPlacementAlgorithm, VMPlacementAlgorithm};
 use dslab_iaas::core::vm_placement_algorithms::best_fit::BestFit;
 use dslab_iaas::core::vm_placement_algorithms::best_fit_threshold::BestFitThreshold;
 use dslab_iaas::core::vm_placement_algorithms::first_fit::FirstFit;
+use dslab_iaas::core::vm_placement_algorithms::min_inlet_temperature::MinInletTemperature;
+use dslab_iaas::core::vm_placement_algorithms::traffic_aware::TrafficAware;
+use dslab_iaas::core::volume::VolumeLocation;
+use dslab_iaas::custom_component::CustomComponent;
+use dslab_iaas::extensions::checkpointing::{CheckpointConfig, CheckpointedJob, JobPhase};
+use dslab_iaas::extensions::dataset_reader::DatasetReader;
+use dslab_iaas::extensions::metrics_exporter::{MetricsExporter, MetricsFormat};
+use dslab_iaas::extensions::synthetic_workload::{
+    ArrivalGenerator, LifetimeGenerator, SizeGenerator, SyntheticTenantConfig, SyntheticWorkloadConfig,
+    SyntheticWorkloadGenerator,
+};
+use dslab_iaas::scenario::{
+    ComponentConfig, MetricsOutputConfig, NetworkConfig, NetworkModelType, NetworkNodeConfig, OutputsConfig,
+    ScenarioBuilder, WorkloadConfig,
+};
 use dslab_iaas::simulation::CloudSimulation;
 
 fn name_wrapper(file_name: &str) -> String {
@@ -376,6 +414,68 @@ fn test_double_migration() {
     assert_eq!(cloud_sim.vm_status(vm), VmStatus::Finished);
 }
 
+#[test]
+// Pre-copy migration of VM with memory size 100 and dirty rate 5 over the network with throughput 10
+// takes 6 rounds (10 + 5 + 2.5 + 1.25 + 0.625 + 0.3125 seconds) until the remaining memory can be copied
+// within the target downtime of 0.3 seconds, and the final stop-and-copy phase takes 0.15625 seconds.
+// The VM lifetime is extended by the downtime, so the VM finishes at moment 100.15625.
+fn test_migration_dirty_rate() {
+    let sim = Simulation::new(123);
+    let sim_config = SimulationConfig::from_file(&name_wrapper("config_zero_latency.yaml"));
+    let mut cloud_sim = CloudSimulation::new(sim, sim_config);
+
+    let h1 = cloud_sim.add_host("h1", 200, 200);
+    let h2 = cloud_sim.add_host("h2", 200, 200);
+    let s = cloud_sim.add_scheduler("s", VMPlacementAlgorithm::single(FirstFit::new()));
+
+    let consumer = ResourceConsumer::with_const_load(100, 100, 0.5, 1.)
+        .with_dirty_rate_model(Box::new(LoadDependentDirtyRate::new(10.)));
+    let vm = cloud_sim.spawn_vm_now(consumer, 100.0, None, s);
+    cloud_sim.step_until_time(5.);
+    assert_eq!(cloud_sim.vm_location(vm), Some(h1));
+
+    let estimate = cloud_sim
+        .vm_api()
+        .borrow()
+        .get_vm(vm)
+        .borrow()
+        .estimate_migration(5., 10.);
+    assert_eq!(estimate.rounds, 6);
+    assert_eq!(estimate.downtime, 0.15625);
+    assert_eq!(estimate.duration, 19.84375);
+
+    cloud_sim.migrate_vm_to_host(vm, h2);
+    cloud_sim.step_until_time(24.8);
+    assert_eq!(cloud_sim.vm_status(vm), VmStatus::Migrating);
+    cloud_sim.step_until_time(25.);
+    assert_eq!(cloud_sim.vm_status(vm), VmStatus::Running);
+    assert_eq!(cloud_sim.vm_location(vm), Some(h2));
+    assert_eq!(
+        cloud_sim.vm_api().borrow().get_vm(vm).borrow().migration_downtime,
+        0.15625
+    );
+
+    cloud_sim.step_until_time(100.1);
+    assert_eq!(cloud_sim.vm_status(vm), VmStatus::Running);
+    cloud_sim.step_until_time(100.6);
+    assert_eq!(cloud_sim.vm_status(vm), VmStatus::Finished);
+}
+
+#[test]
+// If the dirty rate is not lower than the bandwidth, the pre-copy phase stops after the first round
+// and the whole memory is copied again during the downtime.
+fn test_migration_not_converging() {
+    let estimate = estimate_precopy_migration(100., 20., 10., 30, 0.3);
+    assert_eq!(estimate.rounds, 1);
+    assert_eq!(estimate.downtime, 10.);
+    assert_eq!(estimate.duration, 20.);
+    assert_eq!(estimate.transferred, 200.);
+    // without dirtying, the migration takes a single copy of memory
+    let estimate = estimate_precopy_migration(100., 0., 10., 30, 0.3);
+    assert_eq!(estimate.duration, 10.);
+    assert_eq!(estimate.downtime, 0.);
+}
+
 #[test]
 // Default power model gets a result of 4.7 (test #1).
 // Override the power model with constant of 1, then the total consumption is 10.0.
@@ -454,3 +554,1686 @@ fn test_batch_request() {
     assert_eq!(cloud_sim.vm_location(vm_ids[1]), Some(h));
     assert_eq!(cloud_sim.vm_location(vm_ids[2]), Some(h));
 }
+
+#[test]
+// Atomic VM group is placed only if all VMs fit, while VMs from non-atomic group are placed independently.
+fn test_vm_group() {
+    let sim = Simulation::new(123);
+    let sim_config = SimulationConfig::from_file(&name_wrapper("config_zero_latency.yaml"));
+    let mut cloud_sim = CloudSimulation::new(sim, sim_config);
+
+    let h = cloud_sim.add_host("h", 10, 10);
+    let s = cloud_sim.add_scheduler("s", VMPlacementAlgorithm::single(FirstFit::new()));
+
+    let group = |count| {
+        (0..count)
+            .map(|_| VmSpec::new(ResourceConsumer::with_full_load(4, 4), 10.))
+            .collect::<Vec<_>>()
+    };
+    let atomic = cloud_sim.spawn_vm_group(group(3), s, 0., true);
+    cloud_sim.step_for_duration(1.);
+    for vm_id in atomic.iter() {
+        assert_eq!(cloud_sim.vm_status(*vm_id), VmStatus::Initializing);
+    }
+    assert_eq!(cloud_sim.host(h).borrow().cpu_allocated(), 0.);
+
+    let independent = cloud_sim.spawn_vm_group(group(3), s, 0., false);
+    cloud_sim.step_for_duration(1.);
+    assert_eq!(cloud_sim.vm_status(independent[0]), VmStatus::Running);
+    assert_eq!(cloud_sim.vm_status(independent[1]), VmStatus::Running);
+    assert_eq!(cloud_sim.vm_status(independent[2]), VmStatus::Initializing);
+    assert_eq!(cloud_sim.host(h).borrow().cpu_allocated(), 8.);
+    assert_eq!(cloud_sim.scheduler(s).borrow().stats().placed_vms, 2);
+
+    let vm = cloud_sim.spawn_vm_group(
+        vec![VmSpec::new(ResourceConsumer::with_full_load(1, 1), 10.).with_id(100)],
+        s,
+        0.,
+        false,
+    );
+    assert_eq!(vm, vec![100]);
+}
+
+#[test]
+// Running VMs are stopped before the end of their lifetime, other VMs are skipped.
+fn test_delete_vms() {
+    let sim = Simulation::new(123);
+    let sim_config = SimulationConfig::from_file(&name_wrapper("config_zero_latency.yaml"));
+    let mut cloud_sim = CloudSimulation::new(sim, sim_config);
+
+    let h1 = cloud_sim.add_host("h1", 10, 10);
+    let h2 = cloud_sim.add_host("h2", 10, 10);
+    let s = cloud_sim.add_scheduler("s", VMPlacementAlgorithm::single(FirstFit::new()));
+
+    let vms: Vec<_> = (0..5)
+        .map(|_| VmSpec::new(ResourceConsumer::with_full_load(4, 4), 100.))
+        .collect();
+    let vm_ids = cloud_sim.spawn_vm_group(vms, s, 0., false);
+    cloud_sim.step_for_duration(1.);
+    assert_eq!(cloud_sim.vm_location(vm_ids[1]), Some(h1));
+    assert_eq!(cloud_sim.vm_location(vm_ids[2]), Some(h2));
+    assert_eq!(cloud_sim.vm_status(vm_ids[4]), VmStatus::Initializing);
+
+    let deleted = cloud_sim.delete_vms(&[vm_ids[1], vm_ids[2], vm_ids[4]]);
+    assert_eq!(deleted, vec![vm_ids[1], vm_ids[2]]);
+    cloud_sim.step_for_duration(1.);
+    assert_eq!(cloud_sim.vm_status(vm_ids[1]), VmStatus::Finished);
+    assert_eq!(cloud_sim.vm_status(vm_ids[2]), VmStatus::Finished);
+    // the last VM is placed after resources are released
+    cloud_sim.step_for_duration(1.);
+    assert_eq!(cloud_sim.vm_status(vm_ids[4]), VmStatus::Running);
+    assert_eq!(cloud_sim.vm_location(vm_ids[4]), Some(h1));
+    assert_eq!(cloud_sim.host(h1).borrow().cpu_allocated(), 8.);
+    assert_eq!(cloud_sim.host(h2).borrow().cpu_allocated(), 4.);
+}
+
+#[test]
+// Hosts of two types with different power models are created from config.
+// The small host runs a VM during 2 seconds and consumes constant power of 1, thus its energy is 10.0.
+// The big host is idle and consumes 0.4 according to the linear model, thus its energy is 4.0.
+fn test_host_types() {
+    let sim = Simulation::new(123);
+    let sim_config = SimulationConfig::from_file(&name_wrapper("config_host_types.yaml"));
+    let mut cloud_sim = CloudSimulation::new(sim, sim_config);
+
+    let small_hosts = cloud_sim.hosts_by_type("small");
+    let big_hosts = cloud_sim.hosts_by_type("big");
+    assert_eq!(small_hosts.len(), 2);
+    assert_eq!(big_hosts.len(), 1);
+    let small = small_hosts[0];
+    let big = big_hosts[0];
+    assert_eq!(
+        cloud_sim.monitoring().borrow().get_host_state(big).host_type.as_deref(),
+        Some("big")
+    );
+    assert_eq!(cloud_sim.monitoring().borrow().get_hosts_by_type("small"), small_hosts);
+
+    let s = cloud_sim.lookup_id("s");
+    let vm = cloud_sim.spawn_vm_now(ResourceConsumer::with_full_load(10, 10), 2.0, None, s);
+    cloud_sim.step_for_duration(1.);
+    assert_eq!(cloud_sim.vm_location(vm), Some(small));
+
+    cloud_sim.step_until_time(10.);
+    let end_time = cloud_sim.current_time();
+    assert!((cloud_sim.host(small).borrow_mut().get_energy_consumed(end_time) - 10.).abs() < 1e-12);
+    assert!((cloud_sim.host(big).borrow_mut().get_energy_consumed(end_time) - 4.).abs() < 1e-12);
+}
+
+#[test]
+// Config values are taken from the included file unless overridden in the including file.
+fn test_config_include() {
+    let config = SimulationConfig::from_file(&name_wrapper("config_include.yaml"));
+    assert_eq!(config.send_stats_period, 0.5);
+    assert_eq!(config.message_delay, 0.);
+    assert_eq!(config.vm_start_duration, 0.);
+    assert_eq!(config.vm_stop_duration, 0.5);
+    assert_eq!(config.network_throughput, 10);
+    assert_eq!(config.hosts.len(), 1);
+    assert_eq!(config.hosts[0].name.as_deref(), Some("h"));
+}
+
+#[test]
+// Misspelled key results in error pointing to this key.
+fn test_config_unknown_key() {
+    let err = SimulationConfig::try_from_file(&name_wrapper("config_invalid.yaml")).unwrap_err();
+    assert!(err.contains("config_invalid.yaml"));
+    assert!(err.contains("unknown field `memroy`"));
+}
+
+#[test]
+// Scheduler spends 1 second per request and processes requests sequentially, so three simultaneous requests are
+// decided at moments 1, 2 and 3, and the commit requests reach the placement store 0.5 seconds later.
+fn test_control_plane_latency() {
+    let sim = Simulation::new(123);
+    let mut sim_config = SimulationConfig::from_file(&name_wrapper("config_zero_latency.yaml"));
+    sim_config.control_plane.scheduling_time = Some("1".to_string());
+    sim_config.control_plane.scheduler_delay = Some("Const[value=0.5]".to_string());
+    let mut cloud_sim = CloudSimulation::new(sim, sim_config);
+    cloud_sim.add_host("h", 100, 100);
+    let s = cloud_sim.add_scheduler("s", VMPlacementAlgorithm::single(FirstFit::new()));
+
+    let vms: Vec<u32> = (0..3)
+        .map(|_| cloud_sim.spawn_vm_now(ResourceConsumer::with_full_load(10, 10), 100., None, s))
+        .collect();
+    cloud_sim.step_until_time(2.2);
+    assert_eq!(cloud_sim.vm_status(vms[0]), VmStatus::Running);
+    assert_eq!(cloud_sim.vm_status(vms[1]), VmStatus::Initializing);
+    cloud_sim.step_until_time(4.);
+    assert_eq!(cloud_sim.vm_status(vms[2]), VmStatus::Running);
+
+    let stats = cloud_sim.scheduler(s).borrow().stats().clone();
+    assert_eq!(stats.mean_queueing_delay(), 2.);
+    assert_eq!(stats.max_queueing_delay, 3.);
+}
+
+#[test]
+// Control-plane delays are parsed from config value strings, invalid delays are reported by config validation.
+fn test_control_plane_delays() {
+    assert_eq!(Delay::parse("0.2"), Ok(Delay::Const(0.2)));
+    assert_eq!(Delay::parse("Uniform[min=1,max=3]").unwrap().mean(), 2.);
+    assert_eq!(Delay::parse("Exponential[mean=0.5]").unwrap().mean(), 0.5);
+    assert!(Delay::parse("Normal[mean=1]").is_err());
+
+    let mut sim_config = SimulationConfig::from_file(&name_wrapper("config_zero_latency.yaml"));
+    sim_config.control_plane.vm_start_duration = Some("Uniform[min=2,max=1]".to_string());
+    let err = sim_config.validate().unwrap_err();
+    assert!(err.contains("control_plane.vm_start_duration"));
+}
+
+fn synthetic_workload_config(random_seed: u64) -> SyntheticWorkloadConfig {
+    SyntheticWorkloadConfig {
+        arrival_generator: ArrivalGenerator::Random(Box::new(Uniform::new(0.5, 1.5))),
+        activity_window: (0., 20.),
+        max_vm_count: Some(10),
+        tenants: vec![
+            SyntheticTenantConfig {
+                weight: 1.,
+                size_generator: SizeGenerator::Fixed(1, 1),
+                lifetime_generator: LifetimeGenerator::Equal(5.),
+                cpu_load_model: Box::new(ConstantLoadModel::new(1.)),
+                memory_load_model: Box::new(ConstantLoadModel::new(1.)),
+                scheduler_name: None,
+                tenant: None,
+                priority: 0,
+            },
+            SyntheticTenantConfig {
+                weight: 3.,
+                size_generator: SizeGenerator::Flavors(vec![(2, 2, 1.), (4, 4, 1.)]),
+                lifetime_generator: LifetimeGenerator::Random(Box::new(Uniform::new(1., 10.))),
+                cpu_load_model: Box::new(ConstantLoadModel::new(0.5)),
+                memory_load_model: Box::new(ConstantLoadModel::new(0.5)),
+                scheduler_name: None,
+                tenant: None,
+                priority: 0,
+            },
+        ],
+        random_seed,
+    }
+}
+
+#[test]
+// The same seed produces the same VM request stream, which is limited by the max VM count.
+// Generated VMs are submitted via VM API.
+fn test_synthetic_workload() {
+    let mut gen1 = SyntheticWorkloadGenerator::new(synthetic_workload_config(42));
+    let mut gen2 = SyntheticWorkloadGenerator::new(synthetic_workload_config(42));
+    let mut count = 0;
+    let mut last_start_time = 0.;
+    while let Some(r1) = gen1.get_next_vm() {
+        let r2 = gen2.get_next_vm().unwrap();
+        assert_eq!(r1.start_time, r2.start_time);
+        assert_eq!(r1.lifetime, r2.lifetime);
+        assert_eq!((r1.cpu_usage, r1.memory_usage), (r2.cpu_usage, r2.memory_usage));
+        assert!(r1.start_time >= last_start_time && r1.start_time <= 20.);
+        assert!([1, 2, 4].contains(&r1.cpu_usage));
+        last_start_time = r1.start_time;
+        count += 1;
+    }
+    assert!(gen2.get_next_vm().is_none());
+    assert_eq!(count, 10);
+
+    let sim = Simulation::new(123);
+    let sim_config = SimulationConfig::from_file(&name_wrapper("config_zero_latency.yaml"));
+    let mut cloud_sim = CloudSimulation::new(sim, sim_config);
+    cloud_sim.add_host("h", 100, 100);
+    let s = cloud_sim.add_scheduler("s", VMPlacementAlgorithm::single(FirstFit::new()));
+    let mut generator = SyntheticWorkloadGenerator::new(synthetic_workload_config(42));
+    cloud_sim.spawn_vms_from_dataset(s, &mut generator);
+    assert_eq!(cloud_sim.vm_api().borrow().get_vm_count(), 10);
+}
+
+#[test]
+// Metrics are sampled every second during 3 seconds, which gives 4 samples (at times 0, 1, 2, 3).
+fn test_metrics_export() {
+    let sim = Simulation::new(123);
+    let sim_config = SimulationConfig::from_file(&name_wrapper("config_zero_latency.yaml"));
+    let mut cloud_sim = CloudSimulation::new(sim, sim_config);
+    cloud_sim.add_host("h", 30, 30);
+    let s = cloud_sim.add_scheduler("s", VMPlacementAlgorithm::single(FirstFit::new()));
+    cloud_sim.spawn_vm_now(ResourceConsumer::with_full_load(10, 10), 100.0, None, s);
+
+    let dir = std::env::temp_dir();
+    let csv_prefix = dir.join("dslab_iaas_metrics_test").to_str().unwrap().to_string();
+    let lp_prefix = dir.join("dslab_iaas_metrics_test_lp").to_str().unwrap().to_string();
+    let mut exporters = Vec::new();
+    for (name, prefix, format) in [
+        ("csv_exporter", &csv_prefix, MetricsFormat::Csv),
+        ("lp_exporter", &lp_prefix, MetricsFormat::LineProtocol),
+    ] {
+        let exporter = cloud_sim.build_custom_component::<MetricsExporter>(name);
+        exporter
+            .borrow_mut()
+            .patch_custom_args(
+                1.,
+                prefix,
+                format,
+                cloud_sim.monitoring(),
+                cloud_sim.hosts(),
+                cloud_sim.schedulers(),
+            )
+            .unwrap();
+        exporter.borrow_mut().init();
+        exporters.push(exporter);
+    }
+    cloud_sim.step_until_time(3.5);
+    for exporter in exporters {
+        exporter.borrow_mut().flush().unwrap();
+    }
+
+    let hosts_csv = std::fs::read_to_string(format!("{}_hosts.csv", csv_prefix)).unwrap();
+    let lines: Vec<&str> = hosts_csv.lines().collect();
+    assert_eq!(lines.len(), 5);
+    assert!(lines[0].starts_with("time,host,host_type,cpu_load"));
+    assert!(lines[4].starts_with("3.0,h,,"));
+    let schedulers_csv = std::fs::read_to_string(format!("{}_schedulers.csv", csv_prefix)).unwrap();
+    assert_eq!(schedulers_csv.lines().count(), 5);
+    assert!(schedulers_csv.lines().last().unwrap().starts_with("3.0,s,1,1,0,0,0"));
+
+    let lp = std::fs::read_to_string(format!("{}.lp", lp_prefix)).unwrap();
+    assert_eq!(lp.lines().filter(|l| l.starts_with("hosts,host=h ")).count(), 4);
+    assert!(lp.lines().last().unwrap().ends_with(" 3000000000"));
+}
+
+#[test]
+// Host consumes constant power of 1 during 10 seconds, thus its energy is 10.0.
+// With supply temperature of 20 the CoP of cooling system is 0.0068 * 400 + 0.0008 * 20 + 0.458 = 3.194,
+// thus the cooling energy is 10.0 / 3.194.
+fn test_cooling_energy() {
+    let sim = Simulation::new(123);
+    let sim_config = SimulationConfig::from_file(&name_wrapper("config.yaml"));
+    let mut cloud_sim = CloudSimulation::new(sim, sim_config);
+    cloud_sim.set_host_power_model(
+        HostPowerModelBuilder::new()
+            .cpu(Box::new(ConstantCpuPowerModel::new(1.)))
+            .build(),
+    );
+    cloud_sim.set_thermal_model(ThermalModel::new(20., 0.5));
+    let h = cloud_sim.add_host("h", 30, 30);
+
+    cloud_sim.step_for_duration(10.);
+    let end_time = cloud_sim.current_time();
+    let host = cloud_sim.host(h);
+    assert_eq!(host.borrow_mut().get_energy_consumed(end_time), 10.);
+    assert!((host.borrow_mut().get_cooling_energy_consumed(end_time) - 10. / 3.194).abs() < 1e-9);
+    assert!((host.borrow_mut().get_total_energy_consumed(end_time) - 10. - 10. / 3.194).abs() < 1e-9);
+    // inlet temperature is 20 + 0.5 * 1
+    assert_eq!(
+        cloud_sim.monitoring().borrow().get_host_inlet_temperature(h),
+        Some(20.5)
+    );
+}
+
+#[test]
+// VMs 1 and 2 demand the whole memory bandwidth of host h1, so both are slowed down twice, while CPU-bound VM 3
+// is not affected. VM 1 finishes at 20 instead of 10, after that VM 2 runs alone and finishes the remaining
+// 10 seconds of work at 30. VM 4 runs alone on host h2 and finishes in time.
+fn test_memory_bandwidth_interference() {
+    let sim = Simulation::new(123);
+    let sim_config = SimulationConfig::from_file(&name_wrapper("config_zero_latency.yaml"));
+    let mut cloud_sim = CloudSimulation::new(sim, sim_config);
+    cloud_sim.set_interference_model(Box::new(MemoryBandwidthContention::new(10.)));
+
+    let h1 = cloud_sim.add_host("h1", 10, 10);
+    let h2 = cloud_sim.add_host("h2", 10, 10);
+    let consumer = ResourceConsumer::with_full_load(1, 1).with_memory_bandwidth(10.);
+    let vm1 = cloud_sim.spawn_vm_on_host(consumer.clone(), 10., None, h1);
+    let vm2 = cloud_sim.spawn_vm_on_host(consumer.clone(), 20., None, h1);
+    let vm3 = cloud_sim.spawn_vm_on_host(ResourceConsumer::with_full_load(1, 1), 10., None, h1);
+    let vm4 = cloud_sim.spawn_vm_on_host(consumer, 10., None, h2);
+
+    cloud_sim.step_until_time(5.);
+    assert_eq!(cloud_sim.host(h1).borrow().vm_slowdown(vm1), Some(2.));
+    assert_eq!(cloud_sim.host(h1).borrow().vm_slowdown(vm2), Some(2.));
+    assert_eq!(cloud_sim.host(h1).borrow().vm_slowdown(vm3), Some(1.));
+    assert_eq!(cloud_sim.host(h2).borrow().vm_slowdown(vm4), Some(1.));
+
+    cloud_sim.step_until_time(15.);
+    assert_eq!(cloud_sim.vm_status(vm1), VmStatus::Running);
+    assert_eq!(cloud_sim.vm_status(vm3), VmStatus::Finished);
+    assert_eq!(cloud_sim.vm_status(vm4), VmStatus::Finished);
+
+    cloud_sim.step_until_time(25.);
+    assert_eq!(cloud_sim.vm_status(vm1), VmStatus::Finished);
+    assert_eq!(cloud_sim.vm_status(vm2), VmStatus::Running);
+    assert_eq!(cloud_sim.host(h1).borrow().vm_slowdown(vm2), Some(1.));
+    assert_eq!(cloud_sim.vm(vm1).borrow().interference_delay, 10.);
+    assert_eq!(cloud_sim.vm(vm1).borrow().lifetime(), 20.);
+
+    cloud_sim.step_until_time(35.);
+    assert_eq!(cloud_sim.vm_status(vm2), VmStatus::Finished);
+    assert_eq!(cloud_sim.vm(vm2).borrow().interference_delay, 10.);
+    assert_eq!(cloud_sim.vm(vm3).borrow().interference_delay, 0.);
+    assert_eq!(cloud_sim.vm(vm4).borrow().interference_delay, 0.);
+    // 20 seconds of delay over 70 seconds of running time
+    assert_eq!(cloud_sim.interference_degradation(), 20. / 70.);
+}
+
+#[test]
+// With cache contention the VMs are slowed down even if the memory bandwidth is not saturated.
+fn test_cache_contention() {
+    let model = CacheContention::new(10., 0.5);
+    assert_eq!(model.slowdown(2., 6.), 1.2);
+    assert_eq!(model.slowdown(0., 6.), 1.);
+    let model = MemoryBandwidthContention::new(10.);
+    assert_eq!(model.slowdown(2., 6.), 1.);
+    assert_eq!(model.slowdown(2., 20.), 2.);
+
+    let sim = Simulation::new(123);
+    let sim_config = SimulationConfig::from_file(&name_wrapper("config_zero_latency.yaml"));
+    let mut cloud_sim = CloudSimulation::new(sim, sim_config);
+    cloud_sim.set_interference_model(Box::new(CacheContention::new(10., 0.5)));
+    let h = cloud_sim.add_host("h", 10, 10);
+    let vm1 = cloud_sim.spawn_vm_on_host(
+        ResourceConsumer::with_full_load(1, 1).with_memory_bandwidth(2.),
+        10.,
+        None,
+        h,
+    );
+    let vm2 = cloud_sim.spawn_vm_on_host(
+        ResourceConsumer::with_full_load(1, 1).with_memory_bandwidth(4.),
+        10.,
+        None,
+        h,
+    );
+
+    cloud_sim.step_until_time(1.);
+    assert_eq!(cloud_sim.host(h).borrow().vm_slowdown(vm1), Some(1.2));
+    assert_eq!(cloud_sim.host(h).borrow().vm_slowdown(vm2), Some(1.1));
+    // VM 2 finishes at 11, VM 1 completes the remaining 10 - 11 / 1.2 seconds of work without slowdown
+    cloud_sim.step_until_time(20.);
+    assert_eq!(cloud_sim.vm_status(vm1), VmStatus::Finished);
+    assert!((cloud_sim.vm(vm1).borrow().interference_delay - (11. - 11. / 1.2)).abs() < 1e-9);
+    assert!((cloud_sim.vm(vm2).borrow().interference_delay - 1.).abs() < 1e-9);
+}
+
+#[test]
+// Hosts h1 and h2 are located in rack 0, and host h3 is located in rack 1.
+// The VM running on h1 increases the inlet temperature of both hosts in rack 0,
+// so the new VM is placed on the coolest host h3.
+fn test_min_inlet_temperature() {
+    let sim = Simulation::new(123);
+    let sim_config = SimulationConfig::from_file(&name_wrapper("config_zero_latency.yaml"));
+    let mut cloud_sim = CloudSimulation::new(sim, sim_config);
+    cloud_sim.set_thermal_model(ThermalModel::new(20., 1.));
+
+    let h1 = cloud_sim.add_host_in_rack("h1", 10, 10, 0);
+    cloud_sim.add_host_in_rack("h2", 10, 10, 0);
+    let h3 = cloud_sim.add_host_in_rack("h3", 10, 10, 1);
+    cloud_sim.spawn_vm_on_host(ResourceConsumer::with_full_load(5, 5), 100.0, None, h1);
+    cloud_sim.step_for_duration(1.);
+
+    let s = cloud_sim.add_scheduler("s", VMPlacementAlgorithm::single(MinInletTemperature::new()));
+    let vm = cloud_sim.spawn_vm_now(ResourceConsumer::with_full_load(1, 1), 100.0, None, s);
+    cloud_sim.step_for_duration(1.);
+
+    assert_eq!(cloud_sim.vm_location(vm), Some(h3));
+}
+
+#[test]
+// Host is switched off at time 0, which takes 2 seconds and 20 J, and then consumes 1 W in off state.
+// VM arriving at time 10 switches the host on, which takes 10 seconds and 100 J, so the VM is started at time 20.
+// After that the host consumes 10 W, and the overall energy consumed by time 25 is 20 + 8 + 100 + 50 = 178.
+fn test_host_power_states() {
+    let sim = Simulation::new(123);
+    let sim_config = SimulationConfig::from_file(&name_wrapper("config_zero_latency.yaml"));
+    let mut cloud_sim = CloudSimulation::new(sim, sim_config);
+    cloud_sim.set_host_power_model(
+        HostPowerModelBuilder::new()
+            .cpu(Box::new(ConstantCpuPowerModel::new(10.)))
+            .build(),
+    );
+    cloud_sim.set_host_power_state_config(HostPowerStateConfig {
+        boot_duration: 10.,
+        boot_energy: 100.,
+        shutdown_duration: 2.,
+        shutdown_energy: 20.,
+        off_power: 1.,
+        ..Default::default()
+    });
+    let h = cloud_sim.add_host("h", 10, 10);
+    let s = cloud_sim.add_scheduler("s", VMPlacementAlgorithm::single(FirstFit::new()));
+
+    cloud_sim.power_off_host(h);
+    cloud_sim.step_for_duration(1.);
+    assert_eq!(cloud_sim.host(h).borrow().power_state(), HostPowerState::ShuttingDown);
+    cloud_sim.step_for_duration(9.);
+    assert_eq!(cloud_sim.host(h).borrow().power_state(), HostPowerState::Off);
+    assert_eq!(cloud_sim.host(h).borrow_mut().get_energy_consumed(10.), 28.);
+
+    let vm = cloud_sim.spawn_vm_now(ResourceConsumer::with_full_load(1, 1), 100.0, None, s);
+    cloud_sim.step_for_duration(5.);
+    assert_eq!(cloud_sim.host(h).borrow().power_state(), HostPowerState::Booting);
+    assert_eq!(cloud_sim.vm_status(vm), VmStatus::Initializing);
+    cloud_sim.step_for_duration(10.);
+    assert_eq!(cloud_sim.host(h).borrow().power_state(), HostPowerState::Active);
+    assert_eq!(cloud_sim.vm_status(vm), VmStatus::Running);
+    assert_eq!(cloud_sim.host(h).borrow_mut().get_energy_consumed(25.), 178.);
+
+    // host with VMs can't be switched off
+    cloud_sim.power_off_host(h);
+    cloud_sim.step_for_duration(1.);
+    assert_eq!(cloud_sim.host(h).borrow().power_state(), HostPowerState::Active);
+}
+
+#[test]
+// The second VM doesn't fit on the host, so its placement fails at times 0, 1 and 3 (exponential backoff),
+// after which the request is rejected.
+fn test_retry_exponential_backoff() {
+    let sim = Simulation::new(123);
+    let sim_config = SimulationConfig::from_file(&name_wrapper("config_zero_latency.yaml"));
+    let mut cloud_sim = CloudSimulation::new(sim, sim_config);
+    cloud_sim.add_host("h", 10, 10);
+    let s = cloud_sim.add_scheduler("s", VMPlacementAlgorithm::single(FirstFit::new()));
+    cloud_sim
+        .scheduler(s)
+        .borrow_mut()
+        .set_retry_policy(RetryPolicy::exponential_backoff(1., 2., 10.).with_max_attempts(3));
+
+    cloud_sim.spawn_vm_now(ResourceConsumer::with_full_load(10, 10), 100.0, None, s);
+    let vm = cloud_sim.spawn_vm_now(ResourceConsumer::with_full_load(5, 5), 100.0, None, s);
+    cloud_sim.step_for_duration(2.);
+    assert_eq!(cloud_sim.vm_status(vm), VmStatus::Initializing);
+    cloud_sim.step_for_duration(2.);
+    assert_eq!(cloud_sim.vm_status(vm), VmStatus::FailedToAllocate);
+
+    let scheduler = cloud_sim.scheduler(s);
+    let stats = scheduler.borrow().stats().clone();
+    assert_eq!(stats.processed_requests, 4);
+    assert_eq!(stats.placement_failures, 3);
+    assert_eq!(stats.rejected_vms, 1);
+    assert_eq!(stats.committed_vms, 1);
+}
+
+#[test]
+// VMs arriving at time 1 don't fit on the host occupied by the first VM until time 5.
+// The third VM is rejected as the queue is full. When the first VM is finished, the queued VMs are retried
+// with the smallest one first, so the second VM remains in the queue.
+fn test_retry_queue() {
+    let sim = Simulation::new(123);
+    let sim_config = SimulationConfig::from_file(&name_wrapper("config_zero_latency.yaml"));
+    let mut cloud_sim = CloudSimulation::new(sim, sim_config);
+    cloud_sim.add_host("h", 10, 10);
+    let s = cloud_sim.add_scheduler("s", VMPlacementAlgorithm::single(FirstFit::new()));
+    cloud_sim
+        .scheduler(s)
+        .borrow_mut()
+        .set_retry_policy(RetryPolicy::immediate().with_queue(2, QueueOrdering::SmallestFirst));
+
+    cloud_sim.spawn_vm_now(ResourceConsumer::with_full_load(10, 10), 4.0, None, s);
+    cloud_sim.step_for_duration(1.);
+    let vm1 = cloud_sim.spawn_vm_now(ResourceConsumer::with_full_load(9, 1), 100.0, None, s);
+    let vm2 = cloud_sim.spawn_vm_now(ResourceConsumer::with_full_load(2, 1), 100.0, None, s);
+    let vm3 = cloud_sim.spawn_vm_now(ResourceConsumer::with_full_load(1, 1), 100.0, None, s);
+    cloud_sim.step_for_duration(1.);
+    assert_eq!(cloud_sim.vm_status(vm3), VmStatus::FailedToAllocate);
+    assert_eq!(cloud_sim.scheduler(s).borrow().queue_length(), 2);
+
+    cloud_sim.step_for_duration(5.);
+    assert_eq!(cloud_sim.vm_status(vm1), VmStatus::Initializing);
+    assert_eq!(cloud_sim.vm_status(vm2), VmStatus::Running);
+    let scheduler = cloud_sim.scheduler(s);
+    assert_eq!(scheduler.borrow().queue_length(), 1);
+    let stats = scheduler.borrow().stats().clone();
+    assert_eq!(stats.rejected_vms, 1);
+    assert_eq!(stats.committed_vms, 2);
+    assert_eq!(stats.max_queueing_delay, 3.);
+    assert_eq!(stats.mean_queueing_delay(), 1.5);
+}
+
+#[test]
+fn test_retry_policy_resolver() {
+    let policy = retry_policy_resolver(
+        "ExponentialBackoff[initial_delay=1,multiplier=2,max_delay=5,max_attempts=10,queue_capacity=100]".to_string(),
+    );
+    assert_eq!(policy.max_attempts, Some(10));
+    assert_eq!(policy.queue.as_ref().unwrap().ordering, QueueOrdering::Fifo);
+    assert_eq!(policy.retry_delay(1), Some(1.));
+    assert_eq!(policy.retry_delay(3), Some(4.));
+    assert_eq!(policy.retry_delay(4), Some(5.));
+    assert_eq!(retry_policy_resolver("Immediate".to_string()).retry_delay(1), None);
+}
+
+#[test]
+// The first reservation takes 8 CPUs from time 10 to 20, so the second reservation overlapping with it is rejected,
+// while the third one starting at time 20 is accepted. On-demand VMs can use only 2 CPUs not covered by reservations.
+fn test_advance_reservations() {
+    let sim = Simulation::new(123);
+    let sim_config = SimulationConfig::from_file(&name_wrapper("config_zero_latency.yaml"));
+    let mut cloud_sim = CloudSimulation::new(sim, sim_config);
+    let h = cloud_sim.add_host("h", 10, 10);
+    let s = cloud_sim.add_scheduler("s", VMPlacementAlgorithm::single(FirstFit::new()));
+    cloud_sim
+        .scheduler(s)
+        .borrow_mut()
+        .set_retry_policy(RetryPolicy::fixed(1.).with_max_attempts(1));
+
+    let r1 = cloud_sim.reserve_vm(ResourceConsumer::with_full_load(8, 1), 10., 10., None, s);
+    let r2 = cloud_sim.reserve_vm(ResourceConsumer::with_full_load(5, 1), 15., 10., None, s);
+    let r3 = cloud_sim.reserve_vm(ResourceConsumer::with_full_load(5, 1), 20., 5., None, s);
+    let vm1 = cloud_sim.spawn_vm_now(ResourceConsumer::with_full_load(5, 1), 100., None, s);
+    let vm2 = cloud_sim.spawn_vm_now(ResourceConsumer::with_full_load(2, 1), 100., None, s);
+    cloud_sim.step_for_duration(1.);
+    assert_eq!(cloud_sim.vm_status(r1), VmStatus::Initializing);
+    assert_eq!(cloud_sim.vm_status(r2), VmStatus::FailedToAllocate);
+    assert_eq!(cloud_sim.vm_status(vm1), VmStatus::FailedToAllocate);
+    assert_eq!(cloud_sim.vm_status(vm2), VmStatus::Running);
+
+    cloud_sim.step_until_time(11.);
+    assert_eq!(cloud_sim.vm_status(r1), VmStatus::Running);
+    assert_eq!(cloud_sim.vm_location(r1), Some(h));
+    assert_eq!(cloud_sim.vm_status(r3), VmStatus::Initializing);
+
+    cloud_sim.step_until_time(21.);
+    assert_eq!(cloud_sim.vm_status(r1), VmStatus::Finished);
+    assert_eq!(cloud_sim.vm_status(r3), VmStatus::Running);
+
+    let stats = cloud_sim.scheduler(s).borrow().stats().clone();
+    assert_eq!(stats.accepted_reservations, 2);
+    assert_eq!(stats.rejected_reservations, 1);
+}
+
+#[test]
+// Requests exceeding the tenant quota with Reject action fail immediately,
+// while the requests of other tenants and VMs without tenant are not affected.
+fn test_tenant_quota_reject() {
+    let sim = Simulation::new(123);
+    let sim_config = SimulationConfig::from_file(&name_wrapper("config_zero_latency.yaml"));
+    let mut cloud_sim = CloudSimulation::new(sim, sim_config);
+    cloud_sim.add_host("h", 100, 100);
+    let s = cloud_sim.add_scheduler("s", VMPlacementAlgorithm::single(FirstFit::new()));
+    cloud_sim.set_tenant_quota(
+        "a",
+        TenantQuota {
+            max_cpu: Some(10),
+            max_vm_count: Some(2),
+            ..Default::default()
+        },
+    );
+
+    let vm1 = cloud_sim.spawn_tenant_vm("a", ResourceConsumer::with_full_load(6, 1), 10., None, s, 0.);
+    let vm2 = cloud_sim.spawn_tenant_vm("a", ResourceConsumer::with_full_load(6, 1), 10., None, s, 0.);
+    let vm3 = cloud_sim.spawn_tenant_vm("a", ResourceConsumer::with_full_load(4, 1), 10., None, s, 0.);
+    let vm4 = cloud_sim.spawn_tenant_vm("b", ResourceConsumer::with_full_load(50, 1), 10., None, s, 0.);
+    let vm5 = cloud_sim.spawn_vm_now(ResourceConsumer::with_full_load(20, 1), 10., None, s);
+    cloud_sim.step_for_duration(1.);
+    assert_eq!(cloud_sim.vm_status(vm1), VmStatus::Running);
+    assert_eq!(cloud_sim.vm_status(vm2), VmStatus::FailedToAllocate);
+    assert_eq!(cloud_sim.vm_status(vm3), VmStatus::Running);
+    assert_eq!(cloud_sim.vm_status(vm4), VmStatus::Running);
+    assert_eq!(cloud_sim.vm_status(vm5), VmStatus::Running);
+
+    let stats = cloud_sim.vm_api().borrow().tenant_stats().clone();
+    assert_eq!(stats["a"].usage.cpu, 10);
+    assert_eq!(stats["a"].usage.vm_count, 2);
+    assert_eq!(stats["a"].admitted_vms, 2);
+    assert_eq!(stats["a"].rejected_vms, 1);
+    assert_eq!(stats["b"].admitted_vms, 1);
+    assert_eq!(cloud_sim.scheduler(s).borrow().stats().quota_rejected_vms, 1);
+
+    cloud_sim.step_until_time(20.);
+    let stats = cloud_sim.vm_api().borrow().tenant_stats().clone();
+    assert_eq!(stats["a"].usage, TenantUsage::default());
+    assert_eq!(stats["a"].peak_usage.cpu, 10);
+    assert_eq!(stats["a"].peak_usage.vm_count, 2);
+}
+
+#[test]
+// Requests exceeding the tenant quota with Queue action are held until the tenant VMs are finished.
+fn test_tenant_quota_queue() {
+    let sim = Simulation::new(123);
+    let sim_config = SimulationConfig::from_file(&name_wrapper("config_zero_latency.yaml"));
+    let mut cloud_sim = CloudSimulation::new(sim, sim_config);
+    cloud_sim.add_host("h", 100, 100);
+    let s = cloud_sim.add_scheduler("s", VMPlacementAlgorithm::single(FirstFit::new()));
+    cloud_sim.set_tenant_quota(
+        "a",
+        TenantQuota {
+            max_vm_count: Some(1),
+            on_exceeded: QuotaExceededAction::Queue,
+            ..Default::default()
+        },
+    );
+
+    let vm1 = cloud_sim.spawn_tenant_vm("a", ResourceConsumer::with_full_load(1, 1), 5., None, s, 0.);
+    let vm2 = cloud_sim.spawn_tenant_vm("a", ResourceConsumer::with_full_load(1, 1), 5., None, s, 0.);
+    cloud_sim.step_for_duration(1.);
+    assert_eq!(cloud_sim.vm_status(vm1), VmStatus::Running);
+    assert_eq!(cloud_sim.vm_status(vm2), VmStatus::Initializing);
+
+    cloud_sim.step_until_time(7.);
+    assert_eq!(cloud_sim.vm_status(vm1), VmStatus::Finished);
+    assert_eq!(cloud_sim.vm_status(vm2), VmStatus::Running);
+
+    let stats = cloud_sim.vm_api().borrow().tenant_stats().clone();
+    assert_eq!(stats["a"].admitted_vms, 2);
+    assert_eq!(stats["a"].rejected_vms, 0);
+    assert!(stats["a"].queued_requests >= 5);
+    assert_eq!(stats["a"].peak_usage.vm_count, 1);
+}
+
+#[test]
+// Classic DRF example: host with <9 CPU, 18 GB>, tenant "a" runs VMs with <1 CPU, 4 GB> and tenant "b" runs VMs
+// with <3 CPU, 1 GB>. The requests are pending until the host is freed at time 2, then progressive filling gives
+// 3 VMs to "a" and 2 VMs to "b" equalizing their dominant shares at 2/3 (FIFO would give the whole host to "b").
+// When "a" VMs are finished at time 5, the freed resources are given to "a" as it has the lowest dominant share.
+fn test_dominant_resource_fairness() {
+    let sim = Simulation::new(123);
+    let sim_config = SimulationConfig::from_file(&name_wrapper("config_zero_latency.yaml"));
+    let mut cloud_sim = CloudSimulation::new(sim, sim_config);
+    cloud_sim.add_host("h", 9, 18);
+    let s = cloud_sim.add_scheduler("s", VMPlacementAlgorithm::single(FirstFit::new()));
+    cloud_sim.scheduler(s).borrow_mut().set_fair_sharing(true);
+
+    cloud_sim.spawn_vm_now(ResourceConsumer::with_full_load(9, 18), 2.0, None, s);
+    cloud_sim.step_for_duration(0.5);
+    let vms_b: Vec<u32> = (0..5)
+        .map(|_| cloud_sim.spawn_tenant_vm("b", ResourceConsumer::with_full_load(3, 1), 100., None, s, 0.))
+        .collect();
+    let vms_a: Vec<u32> = (0..5)
+        .map(|_| cloud_sim.spawn_tenant_vm("a", ResourceConsumer::with_full_load(1, 4), 3., None, s, 0.))
+        .collect();
+    cloud_sim.step_for_duration(1.);
+    assert_eq!(cloud_sim.scheduler(s).borrow().queue_length(), 10);
+
+    let count_running = |cloud_sim: &CloudSimulation, vms: &[u32]| {
+        vms.iter()
+            .filter(|vm_id| cloud_sim.vm_status(**vm_id) == VmStatus::Running)
+            .count()
+    };
+    cloud_sim.step_until_time(3.);
+    assert_eq!(count_running(&cloud_sim, &vms_a), 3);
+    assert_eq!(count_running(&cloud_sim, &vms_b), 2);
+    assert_eq!(cloud_sim.scheduler(s).borrow().queue_length(), 5);
+    let shares = cloud_sim.scheduler(s).borrow().dominant_shares();
+    assert!((shares["a"] - 2. / 3.).abs() < 1e-9);
+    assert!((shares["b"] - 2. / 3.).abs() < 1e-9);
+
+    cloud_sim.step_until_time(6.);
+    assert_eq!(count_running(&cloud_sim, &vms_a), 2);
+    assert_eq!(count_running(&cloud_sim, &vms_b), 2);
+    assert_eq!(cloud_sim.scheduler(s).borrow().queue_length(), 3);
+    let scheduler = cloud_sim.scheduler(s);
+    let history = scheduler.borrow().dominant_share_history("a").to_vec();
+    assert_eq!(history.len(), 2);
+    assert!((history[0].1 - 2. / 3.).abs() < 1e-9);
+    assert!((history[1].1 - 4. / 9.).abs() < 1e-9);
+}
+
+#[test]
+// Tenant "b" occupies the whole host with <9 CPU, 18 GB> by 3 VMs with <3 CPU, 1 GB> and submits more such VMs
+// at times 0.5, 1.5 and 4, while tenant "a" submits VMs with <1 CPU, 4 GB> at times 1, 2 and 3.
+// When the first "b" VM is finished at time 4, the freed resources are given to "a" as it has the lowest dominant
+// share, although the earlier requests and the new arrival of "b" are pending. When the second "b" VM is finished
+// at time 8, the dominant share of "b" drops to 1/3, so its earliest pending request is placed.
+fn test_dominant_resource_fairness_arrivals() {
+    let sim = Simulation::new(123);
+    let sim_config = SimulationConfig::from_file(&name_wrapper("config_zero_latency.yaml"));
+    let mut cloud_sim = CloudSimulation::new(sim, sim_config);
+    cloud_sim.add_host("h", 9, 18);
+    let s = cloud_sim.add_scheduler("s", VMPlacementAlgorithm::single(FirstFit::new()));
+    cloud_sim.scheduler(s).borrow_mut().set_fair_sharing(true);
+
+    for lifetime in [4., 8., 100.] {
+        cloud_sim.spawn_tenant_vm("b", ResourceConsumer::with_full_load(3, 1), lifetime, None, s, 0.);
+    }
+    let vms_b: Vec<u32> = [0.5, 1.5, 4.]
+        .into_iter()
+        .map(|delay| cloud_sim.spawn_tenant_vm("b", ResourceConsumer::with_full_load(3, 1), 100., None, s, delay))
+        .collect();
+    let vms_a: Vec<u32> = [1., 2., 3.]
+        .into_iter()
+        .map(|delay| cloud_sim.spawn_tenant_vm("a", ResourceConsumer::with_full_load(1, 4), 100., None, s, delay))
+        .collect();
+
+    cloud_sim.step_until_time(3.5);
+    assert_eq!(cloud_sim.scheduler(s).borrow().queue_length(), 5);
+    assert_eq!(cloud_sim.scheduler(s).borrow().dominant_shares()["b"], 1.);
+
+    cloud_sim.step_until_time(5.);
+    assert!(vms_a
+        .iter()
+        .all(|vm_id| cloud_sim.vm_status(*vm_id) == VmStatus::Running));
+    assert!(vms_b
+        .iter()
+        .all(|vm_id| cloud_sim.vm_status(*vm_id) == VmStatus::Initializing));
+    let shares = cloud_sim.scheduler(s).borrow().dominant_shares();
+    assert!((shares["a"] - 2. / 3.).abs() < 1e-9);
+    assert!((shares["b"] - 2. / 3.).abs() < 1e-9);
+
+    cloud_sim.step_until_time(9.);
+    let statuses: Vec<VmStatus> = vms_b.iter().map(|vm_id| cloud_sim.vm_status(*vm_id)).collect();
+    assert_eq!(
+        statuses,
+        vec![VmStatus::Running, VmStatus::Initializing, VmStatus::Initializing]
+    );
+    assert_eq!(cloud_sim.scheduler(s).borrow().queue_length(), 2);
+    let scheduler = cloud_sim.scheduler(s);
+    let history = scheduler.borrow().dominant_share_history("b").to_vec();
+    let times: Vec<f64> = history.iter().map(|(time, _)| *time).collect();
+    assert_eq!(times, vec![0., 4., 8.]);
+    assert!((history[2].1 - 2. / 3.).abs() < 1e-9);
+}
+
+#[test]
+// Tenant "b" occupies the host with <4 CPU, 4 GB> by 4 VMs finishing at times 1, 2, 3 and 4, and tenant "a" submits
+// a VM occupying the whole host at time 0.5 with at most 2 placement attempts. The dispatches of pending requests
+// upon the releases of resources at times 1, 2 and 3 do not count as attempts, so the VM is placed at time 4.
+fn test_dominant_resource_fairness_attempts() {
+    let sim = Simulation::new(123);
+    let sim_config = SimulationConfig::from_file(&name_wrapper("config_zero_latency.yaml"));
+    let mut cloud_sim = CloudSimulation::new(sim, sim_config);
+    cloud_sim.add_host("h", 4, 4);
+    let s = cloud_sim.add_scheduler("s", VMPlacementAlgorithm::single(FirstFit::new()));
+    cloud_sim.scheduler(s).borrow_mut().set_fair_sharing(true);
+    cloud_sim
+        .scheduler(s)
+        .borrow_mut()
+        .set_retry_policy(RetryPolicy::immediate().with_max_attempts(2));
+
+    for lifetime in [1., 2., 3., 4.] {
+        cloud_sim.spawn_tenant_vm("b", ResourceConsumer::with_full_load(1, 1), lifetime, None, s, 0.);
+    }
+    let vm = cloud_sim.spawn_tenant_vm("a", ResourceConsumer::with_full_load(4, 4), 100., None, s, 0.5);
+
+    cloud_sim.step_until_time(3.5);
+    assert_eq!(cloud_sim.vm_status(vm), VmStatus::Initializing);
+    assert_eq!(cloud_sim.scheduler(s).borrow().queue_length(), 1);
+    cloud_sim.step_until_time(5.);
+    assert_eq!(cloud_sim.vm_status(vm), VmStatus::Running);
+    let stats = cloud_sim.scheduler(s).borrow().stats().clone();
+    assert_eq!(stats.rejected_vms, 0);
+    assert_eq!(stats.placement_failures, 1);
+    assert_eq!(stats.processed_requests, 6);
+}
+
+#[test]
+// Image "ubuntu" of size 20 is fetched in 2 seconds with network throughput 10. VMs 1 and 2 started concurrently
+// share a single fetch, VM 3 finds the image in the host cache. Image "centos" does not fit into the cache
+// with capacity 30, so the least recently used image "ubuntu" is evicted.
+fn test_vm_image_cache() {
+    let sim = Simulation::new(123);
+    let sim_config = SimulationConfig::from_file(&name_wrapper("config_zero_latency.yaml"));
+    let mut cloud_sim = CloudSimulation::new(sim, sim_config);
+    cloud_sim.set_image_cache(30., Box::new(LruEviction));
+    cloud_sim.add_image("ubuntu", 20.);
+    cloud_sim.add_image("debian", 10.);
+    cloud_sim.add_image("centos", 10.);
+    let h = cloud_sim.add_host("h", 100, 100);
+    let s = cloud_sim.add_scheduler("s", VMPlacementAlgorithm::single(FirstFit::new()));
+
+    let consumer = || ResourceConsumer::with_full_load(1, 1);
+    let vm1 = cloud_sim.spawn_vm_with_image(consumer(), 100., None, s, 0., "ubuntu");
+    let vm2 = cloud_sim.spawn_vm_with_image(consumer(), 100., None, s, 0., "ubuntu");
+    let vm3 = cloud_sim.spawn_vm_with_image(consumer(), 100., None, s, 5., "ubuntu");
+    let vm4 = cloud_sim.spawn_vm_with_image(consumer(), 100., None, s, 5., "debian");
+    let vm5 = cloud_sim.spawn_vm_with_image(consumer(), 100., None, s, 10., "centos");
+
+    cloud_sim.step_until_time(1.5);
+    assert_eq!(cloud_sim.vm_status(vm1), VmStatus::Initializing);
+    cloud_sim.step_until_time(2.5);
+    assert_eq!(cloud_sim.vm_status(vm1), VmStatus::Running);
+    assert_eq!(cloud_sim.vm_status(vm2), VmStatus::Running);
+    assert_eq!(cloud_sim.vm(vm2).borrow().image_fetch_delay, 2.);
+
+    cloud_sim.step_until_time(5.5);
+    assert_eq!(cloud_sim.vm_status(vm3), VmStatus::Running);
+    assert_eq!(cloud_sim.vm(vm3).borrow().image_fetch_delay, 0.);
+    assert_eq!(cloud_sim.vm_status(vm4), VmStatus::Initializing);
+    cloud_sim.step_until_time(6.5);
+    assert_eq!(cloud_sim.vm_status(vm4), VmStatus::Running);
+
+    cloud_sim.step_until_time(11.5);
+    assert_eq!(cloud_sim.vm_status(vm5), VmStatus::Running);
+    let host = cloud_sim.host(h);
+    assert_eq!(host.borrow().image_cache().image_names(), vec!["debian", "centos"]);
+    assert_eq!(host.borrow().image_cache().used(), 20.);
+
+    let stats = cloud_sim.image_cache_stats();
+    assert_eq!(stats.hits, 1);
+    assert_eq!(stats.misses, 4);
+    assert_eq!(stats.fetches, 3);
+    assert_eq!(stats.fetched_size, 40.);
+    assert_eq!(stats.evictions, 1);
+    assert_eq!(stats.hit_ratio(), 0.2);
+}
+
+#[test]
+// Hosts h1 and h2 fetch different images of size 20 from the registry over the shared link with bandwidth 10,
+// so both fetches take 4 seconds instead of 2. Host h3 has the image preloaded and starts its VM immediately.
+fn test_vm_image_fetch_over_network() {
+    let sim = Simulation::new(123);
+    let sim_config = SimulationConfig::from_file(&name_wrapper("config_zero_latency.yaml"));
+    let mut cloud_sim = CloudSimulation::new(sim, sim_config);
+    cloud_sim.set_image_cache(100., Box::new(LruEviction));
+    cloud_sim.add_image("a", 20.);
+    cloud_sim.add_image("b", 20.);
+    let h1 = cloud_sim.add_host("h1", 10, 10);
+    let h2 = cloud_sim.add_host("h2", 10, 10);
+    let h3 = cloud_sim.add_host("h3", 10, 10);
+    let s = cloud_sim.add_scheduler("s", VMPlacementAlgorithm::single(FirstFit::new()));
+
+    let network = cloud_sim.create_network(Box::new(TopologyAwareNetworkModel::new()));
+    for node in ["registry", "rack"] {
+        network
+            .borrow_mut()
+            .add_node(node, Box::new(SharedBandwidthNetworkModel::new(1000., 0.)));
+    }
+    network.borrow_mut().add_link("registry", "rack", Link::shared(10., 0.));
+    network.borrow_mut().init_topology();
+    for host in [h1, h2, h3] {
+        cloud_sim.set_host_network_node(host, "rack");
+    }
+    cloud_sim.set_image_registry_network_node("registry");
+    cloud_sim.preload_image(h3, "b");
+
+    let vm1 = cloud_sim.spawn_vm_with_image(ResourceConsumer::with_full_load(10, 1), 100., None, s, 0., "a");
+    let vm2 = cloud_sim.spawn_vm_with_image(ResourceConsumer::with_full_load(10, 1), 100., None, s, 0., "b");
+    let vm3 = cloud_sim.spawn_vm_with_image(ResourceConsumer::with_full_load(10, 1), 100., None, s, 0., "b");
+
+    cloud_sim.step_until_time(3.5);
+    assert_eq!(cloud_sim.vm_location(vm3), Some(h3));
+    assert_eq!(cloud_sim.vm_status(vm1), VmStatus::Initializing);
+    assert_eq!(cloud_sim.vm_status(vm2), VmStatus::Initializing);
+    assert_eq!(cloud_sim.vm_status(vm3), VmStatus::Running);
+
+    cloud_sim.step_until_time(4.5);
+    assert_eq!(cloud_sim.vm_location(vm1), Some(h1));
+    assert_eq!(cloud_sim.vm_location(vm2), Some(h2));
+    assert_eq!(cloud_sim.vm_status(vm1), VmStatus::Running);
+    assert_eq!(cloud_sim.vm(vm1).borrow().image_fetch_delay, 4.);
+    assert_eq!(cloud_sim.vm(vm2).borrow().image_fetch_delay, 4.);
+    assert!(cloud_sim.host(h2).borrow().image_cache().contains("b"));
+    assert_eq!(cloud_sim.image_cache_stats().fetched_size, 40.);
+}
+
+#[test]
+// Tenant "a" can submit 2 requests in a burst and then 1 request per second. Out of 4 requests submitted at time 0,
+// the third is retried and accepted at time 1, and the fourth is throttled again at time 1 and accepted at time 3
+// after the second backoff. Tenant "b" does not retry, so its second request is dropped.
+// Requests of tenant "c" without rate limit are not throttled.
+fn test_tenant_rate_limit() {
+    let sim = Simulation::new(123);
+    let sim_config = SimulationConfig::from_file(&name_wrapper("config_zero_latency.yaml"));
+    let mut cloud_sim = CloudSimulation::new(sim, sim_config);
+    cloud_sim.add_host("h", 100, 100);
+    let s = cloud_sim.add_scheduler("s", VMPlacementAlgorithm::single(FirstFit::new()));
+    let retry_policy = ClientRetryPolicy {
+        max_retries: 2,
+        initial_backoff: 1.,
+        backoff_multiplier: 2.,
+        max_backoff: 10.,
+        respect_retry_after: false,
+    };
+    assert_eq!(retry_policy.backoff(3, 0.), 4.);
+    assert_eq!(retry_policy.backoff(5, 0.), 10.);
+    cloud_sim.set_tenant_rate_limit("a", RateLimit::new(1., 2.).with_retry_policy(retry_policy));
+    cloud_sim.set_tenant_rate_limit(
+        "b",
+        RateLimit::new(1., 1.).with_retry_policy(ClientRetryPolicy::no_retries()),
+    );
+
+    let vms_a: Vec<u32> = (0..4)
+        .map(|_| cloud_sim.spawn_tenant_vm("a", ResourceConsumer::with_full_load(1, 1), 10., None, s, 0.))
+        .collect();
+    let vms_b: Vec<u32> = (0..2)
+        .map(|_| cloud_sim.spawn_tenant_vm("b", ResourceConsumer::with_full_load(1, 1), 10., None, s, 0.))
+        .collect();
+    let vms_c: Vec<u32> = (0..4)
+        .map(|_| cloud_sim.spawn_tenant_vm("c", ResourceConsumer::with_full_load(1, 1), 10., None, s, 0.))
+        .collect();
+
+    cloud_sim.step_until_time(0.5);
+    assert_eq!(cloud_sim.vm_status(vms_a[1]), VmStatus::Running);
+    assert_eq!(cloud_sim.vm_status(vms_a[2]), VmStatus::Initializing);
+    assert_eq!(cloud_sim.vm_status(vms_b[0]), VmStatus::Running);
+    assert_eq!(cloud_sim.vm_status(vms_b[1]), VmStatus::FailedToAllocate);
+    for vm_id in vms_c {
+        assert_eq!(cloud_sim.vm_status(vm_id), VmStatus::Running);
+    }
+
+    cloud_sim.step_until_time(2.);
+    assert_eq!(cloud_sim.vm_status(vms_a[2]), VmStatus::Running);
+    assert_eq!(cloud_sim.vm_status(vms_a[3]), VmStatus::Initializing);
+
+    cloud_sim.step_until_time(3.5);
+    assert_eq!(cloud_sim.vm_status(vms_a[3]), VmStatus::Running);
+
+    let stats = cloud_sim.vm_api().borrow().throttling_stats().clone();
+    assert_eq!(stats.len(), 2);
+    assert_eq!(stats["a"].requests, 4);
+    assert_eq!(stats["a"].accepted_requests, 4);
+    assert_eq!(stats["a"].throttled_responses, 3);
+    assert_eq!(stats["a"].retries, 3);
+    assert_eq!(stats["a"].dropped_requests, 0);
+    assert_eq!(stats["a"].throttling_delay, 4.);
+    assert_eq!(stats["a"].mean_throttling_delay(), 1.);
+    assert_eq!(stats["a"].throttled_fraction(), 3. / 7.);
+    assert_eq!(stats["b"].accepted_requests, 1);
+    assert_eq!(stats["b"].dropped_requests, 1);
+    assert_eq!(stats["b"].dropped_vms, 1);
+}
+
+#[test]
+// High-priority VM preempts the lowest-priority VM on the host, the preempted VM is stopped.
+fn test_vm_preemption_stop() {
+    let sim = Simulation::new(123);
+    let sim_config = SimulationConfig::from_file(&name_wrapper("config_zero_latency.yaml"));
+    let mut cloud_sim = CloudSimulation::new(sim, sim_config);
+    let h = cloud_sim.add_host("h", 10, 10);
+    let s = cloud_sim.add_scheduler("s", VMPlacementAlgorithm::single(FirstFit::new()));
+    cloud_sim
+        .scheduler(s)
+        .borrow_mut()
+        .set_preemption_policy(PreemptionPolicy::stop());
+
+    let vm1 = cloud_sim.spawn_vm_with_priority(ResourceConsumer::with_full_load(6, 1), 100., None, s, 0., 0);
+    let vm2 = cloud_sim.spawn_vm_with_priority(ResourceConsumer::with_full_load(4, 1), 100., None, s, 0., 1);
+    cloud_sim.step_for_duration(5.);
+    // no VM with lower priority, the request waits for resources
+    let vm3 = cloud_sim.spawn_vm_with_priority(ResourceConsumer::with_full_load(5, 1), 100., None, s, 0., 0);
+    let vm4 = cloud_sim.spawn_vm_with_priority(ResourceConsumer::with_full_load(5, 1), 100., None, s, 0., 5);
+    cloud_sim.step_for_duration(5.);
+    assert_eq!(cloud_sim.vm_status(vm1), VmStatus::Preempted);
+    assert_eq!(cloud_sim.vm_status(vm2), VmStatus::Running);
+    assert_eq!(cloud_sim.vm_status(vm3), VmStatus::Initializing);
+    assert_eq!(cloud_sim.vm_status(vm4), VmStatus::Running);
+    assert_eq!(cloud_sim.vm_location(vm4), Some(h));
+
+    let preemptions = cloud_sim.preemptions();
+    assert_eq!(preemptions.len(), 1);
+    assert_eq!(preemptions[0].vm_id, vm1);
+    assert_eq!(preemptions[0].host_id, h);
+    assert_eq!(preemptions[0].preempted_by, vm4);
+    assert_eq!(preemptions[0].target_host, None);
+
+    let stats = cloud_sim.scheduler(s).borrow().stats().clone();
+    assert_eq!(stats.preempting_vms, 1);
+    assert_eq!(stats.preempted_vms, 1);
+    assert_eq!(stats.migrated_vms, 0);
+    assert_eq!(cloud_sim.host(h).borrow().cpu_allocated(), 9.);
+}
+
+#[test]
+// Preempted VM is restarted on another host and runs there until the end of its lifetime.
+fn test_vm_preemption_migrate() {
+    let sim = Simulation::new(123);
+    let sim_config = SimulationConfig::from_file(&name_wrapper("config_zero_latency.yaml"));
+    let mut cloud_sim = CloudSimulation::new(sim, sim_config);
+    let h1 = cloud_sim.add_host("h1", 10, 10);
+    let h2 = cloud_sim.add_host("h2", 10, 10);
+    let s = cloud_sim.add_scheduler("s", VMPlacementAlgorithm::single(FirstFit::new()));
+    cloud_sim
+        .scheduler(s)
+        .borrow_mut()
+        .set_preemption_policy(PreemptionPolicy::migrate().with_max_victims(1));
+
+    let vm1 = cloud_sim.spawn_vm_with_priority(ResourceConsumer::with_full_load(5, 1), 100., None, s, 0., 10);
+    let vm2 = cloud_sim.spawn_vm_with_priority(ResourceConsumer::with_full_load(4, 1), 100., None, s, 0., 0);
+    let vm3 = cloud_sim.spawn_vm_with_priority(ResourceConsumer::with_full_load(6, 1), 100., None, s, 0., 10);
+    cloud_sim.step_for_duration(5.);
+    assert_eq!(cloud_sim.vm_location(vm2), Some(h1));
+
+    let vm4 = cloud_sim.spawn_vm_with_priority(ResourceConsumer::with_full_load(5, 1), 100., None, s, 0., 5);
+    cloud_sim.step_for_duration(5.);
+    assert_eq!(cloud_sim.vm_status(vm4), VmStatus::Running);
+    assert_eq!(cloud_sim.vm_location(vm4), Some(h1));
+    assert_eq!(cloud_sim.vm_status(vm2), VmStatus::Running);
+    assert_eq!(cloud_sim.vm_location(vm2), Some(h2));
+    assert_eq!(cloud_sim.vm_status(vm1), VmStatus::Running);
+    assert_eq!(cloud_sim.vm_status(vm3), VmStatus::Running);
+    assert_eq!(cloud_sim.preemptions()[0].target_host, Some(h2));
+
+    let stats = cloud_sim.scheduler(s).borrow().stats().clone();
+    assert_eq!(stats.preempted_vms, 0);
+    assert_eq!(stats.migrated_vms, 1);
+
+    // migrated VM keeps its original lifetime
+    cloud_sim.step_until_time(99.);
+    assert_eq!(cloud_sim.vm_status(vm2), VmStatus::Running);
+    cloud_sim.step_until_time(102.);
+    assert_eq!(cloud_sim.vm_status(vm2), VmStatus::Finished);
+}
+
+#[test]
+fn test_preemption_policy_resolver() {
+    assert_eq!(preemption_policy_resolver("Stop".to_string()), PreemptionPolicy::stop());
+    assert_eq!(
+        preemption_policy_resolver("Migrate[max_victims=2]".to_string()),
+        PreemptionPolicy::migrate().with_max_victims(2)
+    );
+}
+
+#[test]
+// Communicating VMs are co-located if possible, otherwise they are placed on the closest hosts.
+fn test_traffic_aware_placement() {
+    let sim = Simulation::new(123);
+    let sim_config = SimulationConfig::from_file(&name_wrapper("config_zero_latency.yaml"));
+    let mut cloud_sim = CloudSimulation::new(sim, sim_config);
+    let h1 = cloud_sim.add_host("h1", 10, 10);
+    let h2 = cloud_sim.add_host("h2", 10, 10);
+    let h3 = cloud_sim.add_host("h3", 10, 10);
+    let s = cloud_sim.add_scheduler("s", VMPlacementAlgorithm::single(TrafficAware::new()));
+
+    let network = cloud_sim.create_network(Box::new(TopologyAwareNetworkModel::new()));
+    for node in ["n1", "n2", "n3"] {
+        network
+            .borrow_mut()
+            .add_node(node, Box::new(SharedBandwidthNetworkModel::new(1000., 0.)));
+    }
+    network.borrow_mut().add_link("n1", "n2", Link::shared(100., 1.));
+    network.borrow_mut().add_link("n2", "n3", Link::shared(100., 1.));
+    network.borrow_mut().init_topology();
+    cloud_sim.set_host_network_node(h3, "n1");
+    cloud_sim.set_host_network_node(h2, "n2");
+    cloud_sim.set_host_network_node(h1, "n3");
+    assert_eq!(cloud_sim.monitoring().borrow().get_network_latency(h1, h3), Some(2.));
+    assert_eq!(
+        cloud_sim.monitoring().borrow().get_network_bandwidth(h1, h2),
+        Some(100.)
+    );
+
+    let vm1 = cloud_sim.spawn_vm_now(ResourceConsumer::with_full_load(8, 1), 100., None, s);
+    cloud_sim.step_for_duration(1.);
+    assert_eq!(cloud_sim.vm_location(vm1), Some(h1));
+
+    let vm2 = cloud_sim.spawn_vm_now(ResourceConsumer::with_full_load(4, 1), 100., None, s);
+    let vm3 = cloud_sim.spawn_vm_now(ResourceConsumer::with_full_load(1, 1), 100., None, s);
+    let vm4 = cloud_sim.spawn_vm_now(ResourceConsumer::with_full_load(1, 1), 100., None, s);
+    cloud_sim.set_vm_traffic(vm1, vm2, 10.);
+    cloud_sim.set_vm_traffic(vm1, vm3, 1.);
+    cloud_sim.set_vm_traffic(vm2, vm4, 5.);
+    cloud_sim.step_for_duration(1.);
+    // vm2 doesn't fit on h1, so it is placed on the host closest to h1
+    assert_eq!(cloud_sim.vm_location(vm2), Some(h2));
+    assert_eq!(cloud_sim.vm_location(vm3), Some(h1));
+    assert_eq!(cloud_sim.vm_location(vm4), Some(h2));
+    assert_eq!(
+        cloud_sim.monitoring().borrow().get_traffic_matrix().total_rate(vm1),
+        11.
+    );
+}
+
+#[test]
+// VM with local volume is placed only on the host storing the volume, the volume can be reused after VM is finished.
+fn test_local_volumes() {
+    let sim = Simulation::new(123);
+    let sim_config = SimulationConfig::from_file(&name_wrapper("config_zero_latency.yaml"));
+    let mut cloud_sim = CloudSimulation::new(sim, sim_config);
+    let h1 = cloud_sim.add_host("h1", 10, 10);
+    let h2 = cloud_sim.add_host("h2", 10, 10);
+    let s = cloud_sim.add_scheduler("s", VMPlacementAlgorithm::single(FirstFit::new()));
+    cloud_sim.set_host_storage(h2, 100, 1000);
+
+    assert!(cloud_sim.create_volume(10, 100, VolumeLocation::Host(h1)).is_err());
+    assert!(cloud_sim.create_volume(200, 100, VolumeLocation::Host(h2)).is_err());
+    let volume = cloud_sim.create_volume(50, 500, VolumeLocation::Host(h2)).unwrap();
+
+    let vm1 = cloud_sim.spawn_vm_with_volumes(ResourceConsumer::with_full_load(2, 2), 10., None, s, 0., &[volume]);
+    let vm2 = cloud_sim.spawn_vm_with_delay(ResourceConsumer::with_full_load(2, 2), 10., None, s, 0.);
+    cloud_sim.step_for_duration(5.);
+    assert_eq!(cloud_sim.vm_location(vm1), Some(h2));
+    assert_eq!(cloud_sim.vm_location(vm2), Some(h1));
+    assert_eq!(cloud_sim.volume(volume).unwrap().attached_vm, Some(vm1));
+    assert!(cloud_sim.delete_volume(volume).is_err());
+
+    // VM with local volume can't be migrated to another host
+    cloud_sim.migrate_vm_to_host(vm1, h1);
+    cloud_sim.step_for_duration(1.);
+    assert_eq!(cloud_sim.vm_location(vm1), Some(h2));
+
+    // the volume is detached after VM is finished and can be attached to a new VM
+    cloud_sim.step_for_duration(10.);
+    assert_eq!(cloud_sim.vm_status(vm1), VmStatus::Finished);
+    assert_eq!(cloud_sim.volume(volume).unwrap().attached_vm, None);
+    let vm3 = cloud_sim.spawn_vm_with_volumes(ResourceConsumer::with_full_load(9, 9), 10., None, s, 0., &[volume]);
+    cloud_sim.step_for_duration(1.);
+    assert_eq!(cloud_sim.vm_location(vm3), Some(h2));
+    assert_eq!(cloud_sim.volume(volume).unwrap().attached_vm, Some(vm3));
+}
+
+#[test]
+// Remote volume from storage pool can be used on any host, but increases VM start duration by the attach latency.
+fn test_remote_volumes() {
+    let sim = Simulation::new(123);
+    let sim_config = SimulationConfig::from_file(&name_wrapper("config_zero_latency.yaml"));
+    let mut cloud_sim = CloudSimulation::new(sim, sim_config);
+    let h = cloud_sim.add_host("h", 10, 10);
+    let s = cloud_sim.add_scheduler("s", VMPlacementAlgorithm::single(FirstFit::new()));
+    let pool = cloud_sim.add_storage_pool("pool", 100, 1000, 3.);
+    let volume1 = cloud_sim
+        .create_volume(50, 400, VolumeLocation::StoragePool(pool))
+        .unwrap();
+    let volume2 = cloud_sim
+        .create_volume(50, 400, VolumeLocation::StoragePool(pool))
+        .unwrap();
+    // not enough capacity in the storage pool
+    assert!(cloud_sim
+        .create_volume(10, 100, VolumeLocation::StoragePool(pool))
+        .is_err());
+
+    let vm = cloud_sim.spawn_vm_with_volumes(
+        ResourceConsumer::with_full_load(2, 2),
+        10.,
+        None,
+        s,
+        0.,
+        &[volume1, volume2],
+    );
+    cloud_sim.step_for_duration(2.);
+    assert_eq!(cloud_sim.vm_status(vm), VmStatus::Initializing);
+    cloud_sim.step_for_duration(2.);
+    assert_eq!(cloud_sim.vm_status(vm), VmStatus::Running);
+    assert_eq!(cloud_sim.vm_location(vm), Some(h));
+    assert_eq!(cloud_sim.vm(vm).borrow().start_time(), 3.);
+
+    cloud_sim.step_for_duration(20.);
+    assert_eq!(cloud_sim.vm_status(vm), VmStatus::Finished);
+    cloud_sim.delete_volume(volume1).unwrap();
+    assert!(cloud_sim
+        .create_volume(50, 600, VolumeLocation::StoragePool(pool))
+        .is_ok());
+}
+
+#[test]
+// Traffic between VMs is routed over the network, and congested links lead to the traffic SLA violation.
+fn test_traffic_slav() {
+    let sim = Simulation::new(123);
+    let sim_config = SimulationConfig::from_file(&name_wrapper("config_zero_latency.yaml"));
+    let mut cloud_sim = CloudSimulation::new(sim, sim_config);
+    let h1 = cloud_sim.add_host("h1", 10, 10);
+    let h2 = cloud_sim.add_host("h2", 10, 10);
+    let h3 = cloud_sim.add_host("h3", 10, 10);
+    let s = cloud_sim.add_scheduler("s", VMPlacementAlgorithm::single(FirstFit::new()));
+
+    let network = cloud_sim.create_network(Box::new(TopologyAwareNetworkModel::new()));
+    for node in ["n1", "n2", "n3"] {
+        network
+            .borrow_mut()
+            .add_node(node, Box::new(SharedBandwidthNetworkModel::new(1000., 0.)));
+    }
+    let link12 = network.borrow_mut().add_link("n1", "n2", Link::shared(300., 1.));
+    let link23 = network.borrow_mut().add_link("n2", "n3", Link::shared(100., 1.));
+    network.borrow_mut().init_topology();
+    cloud_sim.set_host_network_node(h1, "n1");
+    cloud_sim.set_host_network_node(h2, "n2");
+    cloud_sim.set_host_network_node(h3, "n3");
+
+    let vm1 = cloud_sim.spawn_vm_now(ResourceConsumer::with_full_load(10, 10), 100., None, s);
+    let vm2 = cloud_sim.spawn_vm_now(ResourceConsumer::with_full_load(10, 10), 100., None, s);
+    let vm3 = cloud_sim.spawn_vm_now(ResourceConsumer::with_full_load(10, 10), 10., None, s);
+    cloud_sim.set_vm_traffic(vm1, vm2, 100.);
+    cloud_sim.set_vm_traffic(vm1, vm3, 150.);
+    cloud_sim.set_vm_traffic(vm2, vm3, 50.);
+    cloud_sim.step_for_duration(5.);
+    assert_eq!(cloud_sim.vm_location(vm3), Some(h3));
+
+    let monitoring = cloud_sim.monitoring();
+    assert_eq!(monitoring.borrow().get_traffic_flows().len(), 3);
+    assert_eq!(monitoring.borrow().get_link_utilization(link12), 250. / 300.);
+    assert_eq!(monitoring.borrow().get_link_utilization(link23), 2.);
+    // link between n2 and n3 is congested, so the flows passing through it get half of the requested bandwidth
+    let delivered: Vec<f64> = monitoring
+        .borrow()
+        .get_traffic_flows()
+        .iter()
+        .map(|flow| flow.delivered)
+        .collect();
+    assert_eq!(delivered, vec![100., 75., 25.]);
+    assert!((cloud_sim.traffic_slav() - 1. / 3.).abs() < 1e-9);
+    assert!((cloud_sim.vm_traffic_slav(vm1) - 0.3).abs() < 1e-9);
+    assert!((cloud_sim.vm_traffic_slav(vm2) - 1. / 6.).abs() < 1e-9);
+    assert!((cloud_sim.vm_traffic_slav(vm3) - 0.5).abs() < 1e-9);
+
+    // after vm3 is finished, there is no congestion and the SLAV value decreases
+    cloud_sim.step_for_duration(10.);
+    assert_eq!(cloud_sim.vm_status(vm3), VmStatus::Finished);
+    assert_eq!(monitoring.borrow().get_traffic_flows().len(), 1);
+    assert_eq!(monitoring.borrow().get_link_utilization(link23), 0.);
+    assert!(cloud_sim.traffic_slav() < 1. / 3.);
+    assert!((cloud_sim.vm_traffic_slav(vm3) - 0.5).abs() < 1e-9);
+}
+
+#[test]
+// Utilization report aggregates resource usage per host, host type, tenant and datacenter.
+fn test_utilization_report() {
+    let sim = Simulation::new(123);
+    let sim_config = SimulationConfig::from_file(&name_wrapper("config_zero_latency.yaml"));
+    let mut cloud_sim = CloudSimulation::new(sim, sim_config);
+    let h1 = cloud_sim.add_host_with_type("h1", 10, 10, "small");
+    let h2 = cloud_sim.add_host_with_type("h2", 30, 30, "large");
+    let s = cloud_sim.add_scheduler("s", VMPlacementAlgorithm::single(FirstFit::new()));
+
+    let vm1 = cloud_sim.spawn_tenant_vm("a", ResourceConsumer::with_full_load(10, 10), 100., None, s, 0.);
+    let vm2 = cloud_sim.spawn_tenant_vm("b", ResourceConsumer::with_full_load(10, 5), 100., None, s, 0.);
+    cloud_sim.step_for_duration(10.);
+    assert_eq!(cloud_sim.vm_location(vm1), Some(h1));
+    assert_eq!(cloud_sim.vm_location(vm2), Some(h2));
+
+    let monitoring = cloud_sim.monitoring();
+    let datacenter_usage = *monitoring.borrow().utilization().datacenter_usage();
+    assert_eq!(datacenter_usage.cpu_used, 20.);
+    assert_eq!(datacenter_usage.cpu_allocated, 20.);
+    assert_eq!(datacenter_usage.cpu_total, 40.);
+    assert_eq!(datacenter_usage.memory_allocated, 15.);
+    let tenant_usage = *monitoring.borrow().utilization().tenant_usage("b").unwrap();
+    assert_eq!(tenant_usage.cpu_allocated, 10.);
+    assert_eq!(tenant_usage.memory_used, 5.);
+
+    let report = cloud_sim.utilization_report();
+    assert_eq!(report.hosts[&h1].peak_cpu_utilization, 1.);
+    assert_eq!(report.host_types["large"].peak_cpu_utilization, 1. / 3.);
+    assert_eq!(report.host_types["large"].peak_memory_allocation, 1. / 6.);
+    assert_eq!(report.datacenter.peak_cpu_utilization, 0.5);
+    assert_eq!(report.tenants["a"].peak_cpu_allocation, 0.25);
+    assert!(report.datacenter.mean_cpu_utilization < 0.5);
+    assert!(report.datacenter.mean_cpu_utilization > 0.4);
+    // host utilizations are 1 and 1/3
+    assert!((report.cpu_utilization_gini - 0.25).abs() < 1e-9);
+
+    // after VMs are finished, the tenant usage drops to zero, while the peak values are retained
+    cloud_sim.step_for_duration(100.);
+    assert_eq!(cloud_sim.vm_status(vm1), VmStatus::Finished);
+    let tenant_usage = *monitoring.borrow().utilization().tenant_usage("a").unwrap();
+    assert_eq!(tenant_usage.cpu_used, 0.);
+    let report = cloud_sim.utilization_report();
+    assert_eq!(report.tenants["a"].peak_cpu_utilization, 0.25);
+    assert!(report.tenants["a"].mean_cpu_utilization < 0.25);
+    assert_eq!(report.datacenter.peak_cpu_allocation, 0.5);
+}
+
+#[test]
+// Gangs are placed according to their topology constraints, and the gang not placed within its window fails entirely.
+fn test_gang_scheduling() {
+    let sim = Simulation::new(123);
+    let sim_config = SimulationConfig::from_file(&name_wrapper("config_zero_latency.yaml"));
+    let mut cloud_sim = CloudSimulation::new(sim, sim_config);
+    let h1 = cloud_sim.add_host_in_rack("h1", 10, 10, 0);
+    let h2 = cloud_sim.add_host_in_rack("h2", 10, 10, 0);
+    let h3 = cloud_sim.add_host_in_rack("h3", 20, 20, 1);
+    let h4 = cloud_sim.add_host_in_rack("h4", 20, 20, 1);
+    let s = cloud_sim.add_scheduler("s", VMPlacementAlgorithm::single(FirstFit::new()));
+
+    let gang = |count: usize, size: u32, lifetime: f64| -> Vec<VmSpec> {
+        (0..count)
+            .map(|_| VmSpec::new(ResourceConsumer::with_full_load(size, size as u64), lifetime))
+            .collect()
+    };
+    // rack 0 cannot accommodate the whole gang, so it is placed in rack 1
+    let vms1 = cloud_sim.spawn_vm_gang(gang(3, 8, 10.), GangSpec::new(None, GangTopology::SameRack), s, 0.);
+    let vms2 = cloud_sim.spawn_vm_gang(
+        gang(2, 5, 100.),
+        GangSpec::new(None, GangTopology::DistinctHosts),
+        s,
+        0.,
+    );
+    // these gangs can be placed only after the first gang is finished
+    let vms3 = cloud_sim.spawn_vm_gang(
+        gang(2, 10, 100.),
+        GangSpec::new(Some(5.), GangTopology::SameRack),
+        s,
+        0.,
+    );
+    let vms4 = cloud_sim.spawn_vm_gang(gang(2, 10, 100.), GangSpec::new(None, GangTopology::SameHost), s, 0.);
+    cloud_sim.step_for_duration(1.);
+    let locations = |cloud_sim: &CloudSimulation, vm_ids: &[u32]| -> Vec<Option<u32>> {
+        vm_ids.iter().map(|vm_id| cloud_sim.vm_location(*vm_id)).collect()
+    };
+    assert_eq!(locations(&cloud_sim, &vms1), vec![Some(h3), Some(h3), Some(h4)]);
+    assert_eq!(locations(&cloud_sim, &vms2), vec![Some(h1), Some(h2)]);
+    assert_eq!(locations(&cloud_sim, &vms3), vec![None, None]);
+
+    cloud_sim.step_for_duration(19.);
+    for vm_id in vms3.iter() {
+        assert_eq!(cloud_sim.vm_status(*vm_id), VmStatus::FailedToAllocate);
+    }
+    assert_eq!(locations(&cloud_sim, &vms4), vec![Some(h3), Some(h3)]);
+    let stats = cloud_sim.scheduler(s).borrow().stats().clone();
+    assert_eq!(stats.placed_gangs, 3);
+    assert_eq!(stats.expired_gangs, 1);
+}
+
+#[test]
+// Energy is split into idle, dynamic, inactive and transition components and aggregated by host type.
+// The big host runs a VM with full load during 5 seconds, while one of small hosts is switched off.
+fn test_energy_breakdown() {
+    let sim = Simulation::new(123);
+    let sim_config = SimulationConfig::from_file(&name_wrapper("config_zero_latency.yaml"));
+    let mut cloud_sim = CloudSimulation::new(sim, sim_config);
+    cloud_sim.set_host_power_state_config(HostPowerStateConfig {
+        shutdown_duration: 2.,
+        shutdown_energy: 20.,
+        off_power: 1.,
+        ..Default::default()
+    });
+    cloud_sim.set_host_type_power_model(
+        "small",
+        HostPowerModelBuilder::new()
+            .cpu(Box::new(ConstantCpuPowerModel::new(1.)))
+            .build(),
+    );
+    cloud_sim.set_host_type_power_model(
+        "big",
+        HostPowerModelBuilder::new()
+            .cpu(Box::new(LinearCpuPowerModel::new(0.4, 1.)))
+            .build(),
+    );
+    cloud_sim.add_host_with_type("s1", 10, 10, "small");
+    let s2 = cloud_sim.add_host_with_type("s2", 10, 10, "small");
+    let big = cloud_sim.add_host_with_type("big", 30, 30, "big");
+    let s = cloud_sim.add_scheduler("s", VMPlacementAlgorithm::single(FirstFit::new()));
+
+    let vm = cloud_sim.spawn_vm_now(ResourceConsumer::with_full_load(30, 30), 5., None, s);
+    cloud_sim.power_off_host(s2);
+    cloud_sim.step_until_time(10.);
+    assert_eq!(cloud_sim.vm_location(vm), Some(big));
+
+    let report = cloud_sim.energy_report();
+    let close = |a: f64, b: f64| (a - b).abs() < 1e-9;
+    assert!(close(report.hosts["s1"].idle, 10.));
+    assert!(close(report.hosts["s2"].transition, 20.));
+    assert!(close(report.hosts["s2"].inactive, 8.));
+    assert!(close(report.hosts["big"].idle, 4.));
+    assert!(close(report.hosts["big"].dynamic, 3.));
+    assert!(close(report.host_types["small"].idle, 10.));
+    assert!(close(report.host_types["small"].total(), 38.));
+    assert!(close(report.total.total(), 45.));
+    let energy: f64 = cloud_sim
+        .hosts()
+        .values()
+        .map(|host| host.borrow_mut().get_energy_consumed(10.))
+        .sum();
+    assert!(close(report.total.total(), energy));
+
+    let path = std::env::temp_dir().join("dslab_iaas_energy_report.csv");
+    report.save_csv(path.to_str().unwrap()).unwrap();
+    let content = std::fs::read_to_string(&path).unwrap();
+    // header, 3 hosts, 2 host types and total
+    assert_eq!(content.lines().count(), 7);
+    assert!(content.lines().last().unwrap().starts_with("total,,"));
+}
+
+#[test]
+// Scenario file creates the hosts, the scheduler and the network, and binds the hosts to the network nodes.
+fn test_scenario_from_file() {
+    let scenario = ScenarioBuilder::from_file(&name_wrapper("scenario.yaml"))
+        .unwrap()
+        .build(Simulation::new(123))
+        .unwrap();
+    let (h1, h2, h3) = (scenario.host_id("h1"), scenario.host_id("h2"), scenario.host_id("h3"));
+    let s = scenario.scheduler_id("s");
+    let mut cloud_sim = scenario.cloud;
+    assert_eq!(scenario.network.unwrap().borrow().get_nodes(), vec!["n1", "n2", "n3"]);
+    assert_eq!(cloud_sim.monitoring().borrow().get_network_latency(h1, h3), Some(2.));
+    assert_eq!(
+        cloud_sim.monitoring().borrow().get_network_bandwidth(h1, h2),
+        Some(100.)
+    );
+
+    let vm1 = cloud_sim.spawn_vm_now(ResourceConsumer::with_full_load(8, 1), 100., None, s);
+    cloud_sim.step_for_duration(1.);
+    assert!(cloud_sim.vm_location(vm1).is_some());
+}
+
+#[test]
+// Scenario file also defines the custom component, the workloads, the fault and the outputs.
+// The explicit VMs are submitted at 0, 1 and 2, and the synthetic VMs at 5, 15 and 25.
+// The job with work 100 loses 10 units of work since the checkpoint at 40 due to the crash at 50.
+fn test_full_scenario_from_file() {
+    let prefix = std::env::temp_dir().join("dslab_iaas_scenario_metrics");
+    let log = std::env::temp_dir().join("dslab_iaas_scenario_log.csv");
+    let jobs: Rc<RefCell<Vec<Rc<RefCell<CheckpointedJob>>>>> = Rc::new(RefCell::new(Vec::new()));
+    let created_jobs = jobs.clone();
+    let mut scenario = ScenarioBuilder::from_file(&name_wrapper("scenario_full.yaml"))
+        .unwrap()
+        .outputs(OutputsConfig {
+            metrics: Some(MetricsOutputConfig {
+                prefix: prefix.to_str().unwrap().to_string(),
+                interval: 10.,
+                format: None,
+            }),
+            log: Some(log.to_str().unwrap().to_string()),
+        })
+        .register_component("CheckpointedJob", move |scenario, name, options| {
+            let options = parse_options(options);
+            let work = options.get("work").ok_or("work is not specified")?;
+            let mut config = CheckpointConfig::new(work.parse().map_err(|_| "invalid work")?);
+            if let Some(interval) = options.get("interval") {
+                config = config.with_interval(interval.parse().map_err(|_| "invalid interval")?);
+            }
+            let job = scenario.cloud.build_custom_component::<CheckpointedJob>(name);
+            job.borrow_mut().patch_custom_args(config);
+            job.borrow_mut().init();
+            job.borrow_mut().start(0.);
+            created_jobs.borrow_mut().push(job);
+            Ok(scenario.cloud.lookup_id(name))
+        })
+        .build(Simulation::new(123))
+        .unwrap();
+    assert_eq!(scenario.duration(), Some(200.));
+    assert!(scenario.faults.is_some());
+
+    scenario.cloud.step_until_time(10.);
+    assert_eq!(scenario.cloud.cpu_allocation_rate(), 0.7);
+    scenario.cloud.step_until_time(18.);
+    assert_eq!(scenario.cloud.cpu_allocation_rate(), 0.8);
+    scenario.run().unwrap();
+    assert_eq!(scenario.cloud.current_time(), 200.);
+    assert_eq!(scenario.cloud.cpu_allocation_rate(), 0.);
+
+    assert_eq!(scenario.component_id("job"), scenario.cloud.lookup_id("job"));
+    let job = jobs.borrow()[0].clone();
+    assert_eq!(job.borrow().phase(), JobPhase::Completed);
+    assert_eq!(job.borrow().stats().failures, 1);
+
+    let hosts_csv = std::fs::read_to_string(format!("{}_hosts.csv", prefix.to_str().unwrap())).unwrap();
+    assert!(hosts_csv.lines().count() > 1);
+    assert!(log.exists());
+}
+
+#[test]
+// Invalid cross-references in scenario are reported before creating the simulation.
+fn test_scenario_validation() {
+    let sim_config = SimulationConfig::from_file(&name_wrapper("config_zero_latency.yaml"));
+    let host = |name: &str| HostConfig {
+        name: Some(name.to_string()),
+        name_prefix: None,
+        cpus: 10,
+        memory: 10,
+        count: None,
+        host_type: None,
+        power_model: None,
+    };
+    let scheduler = |algorithm: &str| SchedulerConfig {
+        name: Some("s".to_string()),
+        name_prefix: None,
+        algorithm: algorithm.to_string(),
+        count: None,
+        retry_policy: None,
+        preemption_policy: None,
+        fair_sharing: None,
+    };
+    let network = NetworkConfig {
+        model: NetworkModelType::Shared,
+        bandwidth: Some(100.),
+        latency: Some(0.1),
+        nodes: vec![NetworkNodeConfig {
+            name: "n1".to_string(),
+            local_bandwidth: 1000.,
+            local_latency: None,
+        }],
+        links: Vec::new(),
+    };
+
+    let valid = ScenarioBuilder::new(sim_config.clone())
+        .host(host("h1"))
+        .scheduler(scheduler("BestFit"))
+        .network(network.clone())
+        .bind_host("h1", "n1");
+    assert_eq!(valid.validate(), Ok(()));
+
+    let err = ScenarioBuilder::new(sim_config.clone())
+        .host(host("h1"))
+        .network(network.clone())
+        .bind_host("h1", "n2")
+        .validate()
+        .unwrap_err();
+    assert_eq!(err, "Host `h1` is bound to unknown network node `n2`");
+
+    let err = ScenarioBuilder::new(sim_config.clone())
+        .host(host("h1"))
+        .bind_host("h2", "n1")
+        .network(network)
+        .validate()
+        .unwrap_err();
+    assert_eq!(err, "Unknown host `h2` is bound to network node `n1`");
+
+    let err = ScenarioBuilder::new(sim_config.clone())
+        .scheduler(scheduler("BestestFit"))
+        .validate()
+        .unwrap_err();
+    assert_eq!(err, "Unknown placement algorithm `BestestFit`");
+
+    let err = ScenarioBuilder::new(sim_config.clone())
+        .host(host("s"))
+        .scheduler(scheduler("BestFit"))
+        .build(Simulation::new(123))
+        .err()
+        .unwrap();
+    assert_eq!(err, "Duplicate component name `s`");
+
+    let err = ScenarioBuilder::new(sim_config.clone())
+        .host(host("network"))
+        .validate()
+        .unwrap_err();
+    assert_eq!(err, "Host name `network` is reserved");
+
+    let vms = WorkloadConfig::Vms {
+        scheduler: "s2".to_string(),
+        vms: Vec::new(),
+    };
+    let err = ScenarioBuilder::new(sim_config.clone())
+        .scheduler(scheduler("BestFit"))
+        .workload(vms)
+        .validate()
+        .unwrap_err();
+    assert_eq!(err, "Workload refers to unknown scheduler `s2`");
+
+    let component = ComponentConfig {
+        name: "c".to_string(),
+        r#type: "Migrator".to_string(),
+        options: None,
+    };
+    let err = ScenarioBuilder::new(sim_config.clone())
+        .component(component.clone())
+        .validate()
+        .unwrap_err();
+    assert_eq!(err, "Unknown type `Migrator` of component `c`");
+
+    let valid = ScenarioBuilder::new(sim_config.clone())
+        .host(host("h1"))
+        .component(component)
+        .register_component("Migrator", |_, _, _| Ok(0))
+        .fault(FaultSpec::new("c", FaultKind::Crash, FaultTrigger::At { time: 1. }));
+    assert_eq!(valid.validate(), Ok(()));
+
+    let err = ScenarioBuilder::new(sim_config)
+        .host(host("h1"))
+        .fault(FaultSpec::new("h2", FaultKind::Crash, FaultTrigger::At { time: 1. }))
+        .validate()
+        .unwrap_err();
+    assert_eq!(err, "Fault refers to unknown target `h2`");
+}
+
+#[test]
+// VM is started on host h1 at time 0 and migrated to host h3 at time 5, while host h2 is switched off.
+// Migration of VM with memory size 10 and zero dirty rate takes 1 second with network throughput 10.
+fn test_timeline() {
+    let sim = Simulation::new(123);
+    let sim_config = SimulationConfig::from_file(&name_wrapper("config_zero_latency.yaml"));
+    let mut cloud_sim = CloudSimulation::new(sim, sim_config);
+    cloud_sim.set_host_power_state_config(HostPowerStateConfig {
+        shutdown_duration: 2.,
+        ..Default::default()
+    });
+    let timeline = cloud_sim.enable_timeline();
+    cloud_sim.add_host("h1", 20, 20);
+    let h2 = cloud_sim.add_host("h2", 20, 20);
+    let h3 = cloud_sim.add_host("h3", 20, 20);
+    let s = cloud_sim.add_scheduler("s", VMPlacementAlgorithm::single(FirstFit::new()));
+
+    cloud_sim.power_off_host(h2);
+    let vm = cloud_sim.spawn_vm_now(ResourceConsumer::with_full_load(10, 10), 20.0, None, s);
+    cloud_sim.step_for_duration(5.);
+    cloud_sim.migrate_vm_to_host(vm, h3);
+    cloud_sim.step_for_duration(30.);
+
+    let timeline = timeline.borrow();
+    let hosts: Vec<&str> = timeline.hosts().iter().map(|h| h.name.as_str()).collect();
+    assert_eq!(hosts, vec!["h1", "h2", "h3"]);
+
+    let intervals = timeline.vm_intervals();
+    assert_eq!(intervals.len(), 2);
+    assert_eq!(intervals[0].host, "h1");
+    assert_eq!(intervals[0].allocated_at, 0.);
+    assert_eq!(intervals[0].started_at, Some(0.));
+    assert_eq!(intervals[0].released_at, Some(6.));
+    assert_eq!(intervals[0].end, Some(VmIntervalEnd::Migrated));
+    assert_eq!(intervals[1].host, "h3");
+    assert_eq!(intervals[1].allocated_at, 5.);
+    assert_eq!(intervals[1].started_at, Some(6.));
+    // the VM does not progress during the migration downtime
+    assert!(intervals[1].released_at.unwrap() >= 20.);
+    assert_eq!(intervals[1].end, Some(VmIntervalEnd::Finished));
+
+    let migrations = timeline.migrations();
+    assert_eq!(migrations.len(), 1);
+    assert_eq!(migrations[0].source_host, "h1");
+    assert_eq!(migrations[0].target_host, "h3");
+    assert_eq!((migrations[0].start, migrations[0].end), (5., Some(6.)));
+
+    let h2_states: Vec<(HostPowerState, f64, Option<f64>)> = timeline
+        .power_states()
+        .iter()
+        .filter(|s| s.host == "h2")
+        .map(|s| (s.state, s.start, s.end))
+        .collect();
+    assert_eq!(
+        h2_states,
+        vec![
+            (HostPowerState::Active, 0., Some(0.)),
+            (HostPowerState::ShuttingDown, 0., Some(2.)),
+            (HostPowerState::Off, 2., None),
+        ]
+    );
+
+    let json: serde_json::Value = serde_json::from_str(&timeline.to_json(35.)).unwrap();
+    assert_eq!(json["end_time"], 35.);
+    assert_eq!(json["vm_intervals"][0]["end"], "Migrated");
+    assert_eq!(json["power_states"].as_array().unwrap().len(), 5);
+    let html = timeline.to_html(35.);
+    assert!(html.contains("<svg"));
+    assert!(html.contains(&format!("VM {} migration h1 -&gt; h3", vm)));
+}
+
+#[test]
+// Scheduler with allowed hosts places VMs only on these hosts.
+fn test_scheduler_allowed_hosts() {
+    let sim = Simulation::new(123);
+    let sim_config = SimulationConfig::from_file(&name_wrapper("config_zero_latency.yaml"));
+    let mut cloud_sim = CloudSimulation::new(sim, sim_config);
+
+    let h1 = cloud_sim.add_host("h1", 8, 16);
+    let h2 = cloud_sim.add_host("h2", 8, 16);
+    let s = cloud_sim.add_scheduler("s", VMPlacementAlgorithm::single(FirstFit::new()));
+    cloud_sim.scheduler(s).borrow_mut().set_allowed_hosts(&[h2]);
+
+    let vm1 = cloud_sim.spawn_vm_now(ResourceConsumer::with_full_load(4, 8), 100., None, s);
+    let vm2 = cloud_sim.spawn_vm_now(ResourceConsumer::with_full_load(4, 8), 100., None, s);
+    let vm3 = cloud_sim.spawn_vm_now(ResourceConsumer::with_full_load(4, 8), 100., None, s);
+    cloud_sim.step_for_duration(1.);
+
+    assert_eq!(cloud_sim.vm_location(vm1), Some(h2));
+    assert_eq!(cloud_sim.vm_location(vm2), Some(h2));
+    assert_eq!(cloud_sim.vm_location(vm3), None);
+    assert_eq!(cloud_sim.host(h1).borrow().cpu_allocated(), 0.);
+}
@@ -0,0 +1,244 @@
+use dslab_core::simulation::Simulation;
+
+use dslab_models::queueing::TimeDistribution;
+use dslab_network::models::{SharedBandwidthNetworkModel, TopologyAwareNetworkModel};
+use dslab_network::Link;
+
+use dslab_iaas::core::config::sim_config::SimulationConfig;
+use dslab_iaas::custom_component::CustomComponent;
+use dslab_iaas::extensions::edge::{
+    CloudOnly, EdgeFirst, EdgeOnly, EdgeOrchestrator, EdgeWorkload, MinEstimatedLatency, MobileClient, Mobility,
+    OffloadingPolicy, SiteKind,
+};
+use dslab_iaas::simulation::CloudSimulation;
+
+fn name_wrapper(file_name: &str) -> String {
+    format!("test-configs/{}", file_name)
+}
+
+#[test]
+// Single request is executed at the edge host located at the client access point or at the remote cloud host.
+// There are two edge hosts with 2 cores located at access points ap1 and ap2, and cloud host with 16 cores
+// located further in the WAN. The cloud host is two times faster than edge hosts.
+fn test_edge_offloading_latency() {
+    for (policy, expected_latency) in [
+        (Box::new(EdgeOnly::default()) as Box<dyn OffloadingPolicy>, 1.11),
+        // 10 + 100 / 100 (input) + 1 / 2 (execution) + 10 + 10 / 100 (output)
+        (Box::new(CloudOnly::default()), 21.6),
+        (Box::new(MinEstimatedLatency::default()), 1.11),
+    ] {
+        let sim = Simulation::new(123);
+        let sim_config = SimulationConfig::from_file(&name_wrapper("config_zero_latency.yaml"));
+        let mut cloud_sim = CloudSimulation::new(sim, sim_config);
+        let e1 = cloud_sim.add_host_with_type("e1", 2, 4, "edge");
+        let e2 = cloud_sim.add_host_with_type("e2", 2, 4, "edge");
+        let c = cloud_sim.add_host("c", 16, 64);
+
+        let network = cloud_sim.create_network(Box::new(TopologyAwareNetworkModel::new()));
+        for node in ["ap1", "ap2", "dc"] {
+            network
+                .borrow_mut()
+                .add_node(node, Box::new(SharedBandwidthNetworkModel::new(1000., 0.)));
+        }
+        network.borrow_mut().add_link("ap1", "ap2", Link::shared(100., 1.));
+        network.borrow_mut().add_link("ap1", "dc", Link::shared(100., 10.));
+        network.borrow_mut().add_link("ap2", "dc", Link::shared(100., 10.));
+        network.borrow_mut().init_topology();
+        cloud_sim.set_host_network_node(e1, "ap1");
+        cloud_sim.set_host_network_node(e2, "ap2");
+        cloud_sim.set_host_network_node(c, "dc");
+
+        let orchestrator = cloud_sim.build_custom_component::<EdgeOrchestrator>("orchestrator");
+        orchestrator.borrow_mut().patch_custom_args(network.clone(), policy);
+        orchestrator
+            .borrow_mut()
+            .add_site(&cloud_sim.host(e1).borrow(), SiteKind::Edge, 1.);
+        orchestrator
+            .borrow_mut()
+            .add_site(&cloud_sim.host(e2).borrow(), SiteKind::Edge, 1.);
+        orchestrator
+            .borrow_mut()
+            .add_site(&cloud_sim.host(c).borrow(), SiteKind::Cloud, 2.);
+        orchestrator.borrow_mut().init();
+
+        let client = cloud_sim.build_custom_component::<MobileClient>("client");
+        client.borrow_mut().patch_custom_args(
+            network,
+            orchestrator.borrow().id(),
+            EdgeWorkload {
+                inter_arrival_time: TimeDistribution::Constant(1.),
+                cores: 1,
+                work: TimeDistribution::Constant(1.),
+                input_size: 100.,
+                output_size: 10.,
+                deadline: Some(5.),
+                request_count: 1,
+            },
+            Mobility {
+                access_points: vec!["ap1".to_string()],
+                dwell_time: None,
+                random_walk: false,
+            },
+        );
+        client.borrow_mut().init();
+        cloud_sim.step_until_time(100.);
+
+        let client = client.borrow();
+        let completed = client.completed_requests();
+        assert_eq!(completed.len(), 1);
+        assert!((completed[0].latency() - expected_latency).abs() < 1e-9);
+        let expected_host = if expected_latency < 5. { e1 } else { c };
+        assert_eq!(completed[0].host_id, expected_host);
+        let stats = orchestrator.borrow().stats();
+        assert_eq!(stats.completed, 1);
+        assert_eq!(stats.deadline_misses, if expected_latency < 5. { 0 } else { 1 });
+    }
+}
+
+#[test]
+// Burst of requests overloads the edge host, so that the excess requests are better offloaded to the cloud.
+fn test_edge_offloading_under_load() {
+    let mut mean_latencies = Vec::new();
+    for policy in [
+        Box::new(EdgeOnly::default()) as Box<dyn OffloadingPolicy>,
+        Box::new(EdgeFirst::default()),
+        Box::new(MinEstimatedLatency::default()),
+    ] {
+        let sim = Simulation::new(123);
+        let sim_config = SimulationConfig::from_file(&name_wrapper("config_zero_latency.yaml"));
+        let mut cloud_sim = CloudSimulation::new(sim, sim_config);
+        let e1 = cloud_sim.add_host_with_type("e1", 2, 4, "edge");
+        let e2 = cloud_sim.add_host_with_type("e2", 2, 4, "edge");
+        let c = cloud_sim.add_host("c", 16, 64);
+
+        let network = cloud_sim.create_network(Box::new(TopologyAwareNetworkModel::new()));
+        for node in ["ap1", "ap2", "dc"] {
+            network
+                .borrow_mut()
+                .add_node(node, Box::new(SharedBandwidthNetworkModel::new(1000., 0.)));
+        }
+        network.borrow_mut().add_link("ap1", "ap2", Link::shared(100., 1.));
+        network.borrow_mut().add_link("ap1", "dc", Link::shared(100., 10.));
+        network.borrow_mut().add_link("ap2", "dc", Link::shared(100., 10.));
+        network.borrow_mut().init_topology();
+        cloud_sim.set_host_network_node(e1, "ap1");
+        cloud_sim.set_host_network_node(e2, "ap2");
+        cloud_sim.set_host_network_node(c, "dc");
+
+        let orchestrator = cloud_sim.build_custom_component::<EdgeOrchestrator>("orchestrator");
+        orchestrator.borrow_mut().patch_custom_args(network.clone(), policy);
+        orchestrator
+            .borrow_mut()
+            .add_site(&cloud_sim.host(e1).borrow(), SiteKind::Edge, 1.);
+        orchestrator
+            .borrow_mut()
+            .add_site(&cloud_sim.host(e2).borrow(), SiteKind::Edge, 1.);
+        orchestrator
+            .borrow_mut()
+            .add_site(&cloud_sim.host(c).borrow(), SiteKind::Cloud, 2.);
+        orchestrator.borrow_mut().init();
+
+        let client = cloud_sim.build_custom_component::<MobileClient>("client");
+        client.borrow_mut().patch_custom_args(
+            network,
+            orchestrator.borrow().id(),
+            EdgeWorkload {
+                inter_arrival_time: TimeDistribution::Constant(0.),
+                cores: 1,
+                work: TimeDistribution::Constant(1.),
+                input_size: 1.,
+                output_size: 1.,
+                deadline: None,
+                request_count: 40,
+            },
+            Mobility {
+                access_points: vec!["ap1".to_string()],
+                dwell_time: None,
+                random_walk: false,
+            },
+        );
+        client.borrow_mut().init();
+        cloud_sim.step_until_time(100.);
+
+        let stats = orchestrator.borrow().stats();
+        assert_eq!(stats.completed, 40);
+        match stats.edge_executions {
+            40 => assert_eq!(stats.cloud_executions, 0),
+            2 => assert_eq!(stats.cloud_executions, 38),
+            _ => assert!(stats.cloud_executions > 0),
+        }
+        mean_latencies.push(stats.mean_latency);
+    }
+    // EdgeOnly executes all requests at the edge, EdgeFirst offloads all requests except the first two to the cloud,
+    // MinEstimatedLatency uses both the edge and the cloud
+    assert!(mean_latencies[2] < mean_latencies[0]);
+    assert!(mean_latencies[2] < mean_latencies[1]);
+}
+
+#[test]
+// Requests are executed at the edge host closest to the current location of mobile client.
+fn test_edge_mobile_client() {
+    let sim = Simulation::new(123);
+    let sim_config = SimulationConfig::from_file(&name_wrapper("config_zero_latency.yaml"));
+    let mut cloud_sim = CloudSimulation::new(sim, sim_config);
+    let e1 = cloud_sim.add_host_with_type("e1", 2, 4, "edge");
+    let e2 = cloud_sim.add_host_with_type("e2", 2, 4, "edge");
+    let c = cloud_sim.add_host("c", 16, 64);
+
+    let network = cloud_sim.create_network(Box::new(TopologyAwareNetworkModel::new()));
+    for node in ["ap1", "ap2", "dc"] {
+        network
+            .borrow_mut()
+            .add_node(node, Box::new(SharedBandwidthNetworkModel::new(1000., 0.)));
+    }
+    network.borrow_mut().add_link("ap1", "ap2", Link::shared(100., 1.));
+    network.borrow_mut().add_link("ap1", "dc", Link::shared(100., 10.));
+    network.borrow_mut().add_link("ap2", "dc", Link::shared(100., 10.));
+    network.borrow_mut().init_topology();
+    cloud_sim.set_host_network_node(e1, "ap1");
+    cloud_sim.set_host_network_node(e2, "ap2");
+    cloud_sim.set_host_network_node(c, "dc");
+
+    let orchestrator = cloud_sim.build_custom_component::<EdgeOrchestrator>("orchestrator");
+    orchestrator
+        .borrow_mut()
+        .patch_custom_args(network.clone(), Box::new(EdgeOnly::default()));
+    orchestrator
+        .borrow_mut()
+        .add_site(&cloud_sim.host(e1).borrow(), SiteKind::Edge, 1.);
+    orchestrator
+        .borrow_mut()
+        .add_site(&cloud_sim.host(e2).borrow(), SiteKind::Edge, 1.);
+    orchestrator
+        .borrow_mut()
+        .add_site(&cloud_sim.host(c).borrow(), SiteKind::Cloud, 2.);
+    orchestrator.borrow_mut().init();
+
+    let client = cloud_sim.build_custom_component::<MobileClient>("client");
+    client.borrow_mut().patch_custom_args(
+        network,
+        orchestrator.borrow().id(),
+        EdgeWorkload {
+            inter_arrival_time: TimeDistribution::Constant(2.),
+            cores: 1,
+            work: TimeDistribution::Constant(1.),
+            input_size: 100.,
+            output_size: 10.,
+            deadline: Some(5.),
+            request_count: 4,
+        },
+        Mobility {
+            access_points: vec!["ap1".to_string(), "ap2".to_string()],
+            dwell_time: Some(TimeDistribution::Constant(5.)),
+            random_walk: false,
+        },
+    );
+    client.borrow_mut().init();
+    cloud_sim.step_until_time(100.);
+
+    let client = client.borrow();
+    let sites: Vec<u32> = client.completed_requests().iter().map(|r| r.host_id).collect();
+    assert_eq!(sites, vec![e1, e1, e2, e2]);
+    assert_eq!(client.rejected_requests(), 0);
+    assert_eq!(orchestrator.borrow().stats().edge_executions, 4);
+}
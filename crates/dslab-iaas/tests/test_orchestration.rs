@@ -0,0 +1,210 @@
+use dslab_core::simulation::Simulation;
+
+use dslab_iaas::core::config::sim_config::SimulationConfig;
+use dslab_iaas::core::vm::ResourceConsumer;
+use dslab_iaas::core::vm_placement_algorithm::VMPlacementAlgorithm;
+use dslab_iaas::core::vm_placement_algorithms::first_fit::FirstFit;
+use dslab_iaas::custom_component::CustomComponent;
+use dslab_iaas::extensions::orchestration::orchestrator::ContainerOrchestrator;
+use dslab_iaas::extensions::orchestration::pod::{PodSpec, PodStatus};
+use dslab_iaas::extensions::orchestration::scheduler::{
+    MostAllocated, NodeResourcesFit, NodeSelectorMatch, PodScheduler,
+};
+use dslab_iaas::simulation::CloudSimulation;
+
+fn name_wrapper(file_name: &str) -> String {
+    format!("test-configs/{}", file_name)
+}
+
+#[test]
+// Default scheduler spreads replicas across the nodes, while scheduler with MostAllocated plugin packs them.
+fn test_orchestration_spread_and_pack() {
+    for (scheduler, expected_nodes) in [
+        (PodScheduler::default(), vec![0, 1, 2]),
+        (
+            PodScheduler::new()
+                .with_filter(Box::new(NodeResourcesFit))
+                .with_filter(Box::new(NodeSelectorMatch))
+                .with_score(Box::new(MostAllocated), 1.),
+            vec![0, 0, 0],
+        ),
+    ] {
+        let sim = Simulation::new(123);
+        let sim_config = SimulationConfig::from_file(&name_wrapper("config_zero_latency.yaml"));
+        let mut cloud_sim = CloudSimulation::new(sim, sim_config);
+        let orchestrator = cloud_sim.build_custom_component::<ContainerOrchestrator>("orchestrator");
+        orchestrator.borrow_mut().patch_custom_args(cloud_sim.vm_api(), 1.);
+        for i in 0..3 {
+            let host = cloud_sim.add_host(&format!("node{}", i), 4, 16);
+            orchestrator
+                .borrow_mut()
+                .add_host_node(&cloud_sim.host(host).borrow(), &[("zone", "a")]);
+        }
+        orchestrator.borrow_mut().init();
+        orchestrator.borrow_mut().set_scheduler(scheduler);
+        orchestrator
+            .borrow_mut()
+            .create_replica_set("web", 3, PodSpec::new("web", 1000, 2));
+        cloud_sim.step_for_duration(1.);
+
+        let orchestrator = orchestrator.borrow();
+        let nodes: Vec<u32> = orchestrator.pods().map(|pod| pod.node.unwrap()).collect();
+        assert_eq!(nodes, expected_nodes);
+        assert!(orchestrator.pods().all(|pod| pod.status == PodStatus::Running));
+        assert!(orchestrator.replica_set_status("web").unwrap().is_rolled_out());
+    }
+}
+
+#[test]
+// Pods are placed only on nodes matching their selector and wait until enough resources are released.
+fn test_orchestration_selector_and_pending() {
+    let sim = Simulation::new(123);
+    let sim_config = SimulationConfig::from_file(&name_wrapper("config_zero_latency.yaml"));
+    let mut cloud_sim = CloudSimulation::new(sim, sim_config);
+    let orchestrator = cloud_sim.build_custom_component::<ContainerOrchestrator>("orchestrator");
+    orchestrator.borrow_mut().patch_custom_args(cloud_sim.vm_api(), 1.);
+    for (i, zone) in ["a", "a", "b"].into_iter().enumerate() {
+        let host = cloud_sim.add_host(&format!("node{}", i), 2, 8);
+        orchestrator
+            .borrow_mut()
+            .add_host_node(&cloud_sim.host(host).borrow(), &[("zone", zone)]);
+    }
+    orchestrator.borrow_mut().init();
+    orchestrator.borrow_mut().set_scheduler(
+        PodScheduler::new()
+            .with_filter(Box::new(NodeResourcesFit))
+            .with_filter(Box::new(NodeSelectorMatch))
+            .with_score(Box::new(MostAllocated), 1.),
+    );
+    orchestrator.borrow_mut().set_termination_grace_period(2.);
+
+    let spec = PodSpec::new("app", 1000, 2).with_node_selector("zone", "a");
+    let pods: Vec<u64> = (0..5)
+        .map(|_| orchestrator.borrow_mut().create_pod(spec.clone()))
+        .collect();
+    let other = orchestrator
+        .borrow_mut()
+        .create_pod(PodSpec::new("other", 1000, 2).with_node_selector("zone", "b"));
+    cloud_sim.step_for_duration(5.);
+
+    {
+        let orchestrator = orchestrator.borrow();
+        let nodes: Vec<Option<u32>> = pods.iter().map(|id| orchestrator.pod(*id).unwrap().node).collect();
+        assert_eq!(nodes, vec![Some(0), Some(0), Some(1), Some(1), None]);
+        assert_eq!(orchestrator.pod(pods[4]).unwrap().status, PodStatus::Pending);
+        assert_eq!(orchestrator.pod(other).unwrap().node, Some(2));
+    }
+
+    assert!(orchestrator.borrow_mut().delete_pod(pods[1]));
+    cloud_sim.step_for_duration(1.);
+    assert_eq!(
+        orchestrator.borrow().pod(pods[1]).unwrap().status,
+        PodStatus::Terminating
+    );
+    assert_eq!(orchestrator.borrow().pod(pods[4]).unwrap().status, PodStatus::Pending);
+    cloud_sim.step_for_duration(2.);
+
+    let orchestrator = orchestrator.borrow();
+    let pod = orchestrator.pod(pods[4]).unwrap();
+    assert_eq!(pod.status, PodStatus::Running);
+    assert_eq!(pod.node, Some(0));
+    assert_eq!(pod.scheduled_at, Some(7.));
+    let stats = orchestrator.stats();
+    assert_eq!(stats.running_pods, 5);
+    assert_eq!(stats.terminated_pods, 1);
+    assert_eq!(stats.mean_scheduling_delay, 7. / 6.);
+}
+
+#[test]
+// Pods running on VM nodes are failed when the VMs finish, and the replica set replaces them on remaining nodes.
+fn test_orchestration_vm_nodes() {
+    let sim = Simulation::new(123);
+    let sim_config = SimulationConfig::from_file(&name_wrapper("config_zero_latency.yaml"));
+    let mut cloud_sim = CloudSimulation::new(sim, sim_config);
+    let orchestrator = cloud_sim.build_custom_component::<ContainerOrchestrator>("orchestrator");
+    orchestrator.borrow_mut().patch_custom_args(cloud_sim.vm_api(), 1.);
+    orchestrator.borrow_mut().init();
+    cloud_sim.add_host("h1", 8, 16);
+    cloud_sim.add_host("h2", 8, 16);
+    let s = cloud_sim.add_scheduler("s", VMPlacementAlgorithm::single(FirstFit::new()));
+    let vms = [
+        cloud_sim.spawn_vm_now(ResourceConsumer::with_full_load(4, 8), 100., None, s),
+        cloud_sim.spawn_vm_now(ResourceConsumer::with_full_load(4, 8), 100., None, s),
+        cloud_sim.spawn_vm_now(ResourceConsumer::with_full_load(4, 8), 1000., None, s),
+    ];
+    for vm_id in vms {
+        orchestrator.borrow_mut().add_vm_node(vm_id, &[]);
+    }
+    assert!(orchestrator.borrow().nodes().all(|node| !node.ready));
+    orchestrator
+        .borrow_mut()
+        .create_replica_set("web", 4, PodSpec::new("web", 2000, 4));
+    cloud_sim.step_for_duration(50.);
+
+    {
+        let orchestrator = orchestrator.borrow();
+        assert!(orchestrator.nodes().all(|node| node.ready));
+        let nodes: Vec<u32> = orchestrator.pods().map(|pod| pod.node.unwrap()).collect();
+        assert_eq!(nodes, vec![0, 1, 2, 0]);
+        assert_eq!(orchestrator.replica_set_status("web").unwrap().ready, 4);
+    }
+
+    cloud_sim.step_for_duration(100.);
+
+    let orchestrator = orchestrator.borrow();
+    let nodes: Vec<bool> = orchestrator.nodes().map(|node| node.ready).collect();
+    assert_eq!(nodes, vec![false, false, true]);
+    let status = orchestrator.replica_set_status("web").unwrap();
+    assert_eq!(status.current, 4);
+    assert_eq!(status.ready, 2);
+    let stats = orchestrator.stats();
+    assert_eq!(stats.failed_pods, 3);
+    assert_eq!(stats.pending_pods, 2);
+    assert_eq!(orchestrator.nodes().last().unwrap().pods.len(), 2);
+}
+
+#[test]
+// Rolling update replaces all pods while respecting the surge and availability constraints.
+fn test_orchestration_rolling_update() {
+    for (max_surge, max_unavailable) in [(1, 0), (0, 1), (2, 1)] {
+        let sim = Simulation::new(123);
+        let sim_config = SimulationConfig::from_file(&name_wrapper("config_zero_latency.yaml"));
+        let mut cloud_sim = CloudSimulation::new(sim, sim_config);
+        let orchestrator = cloud_sim.build_custom_component::<ContainerOrchestrator>("orchestrator");
+        orchestrator.borrow_mut().patch_custom_args(cloud_sim.vm_api(), 1.);
+        for i in 0..3 {
+            let host = cloud_sim.add_host(&format!("node{}", i), 4, 16);
+            orchestrator
+                .borrow_mut()
+                .add_host_node(&cloud_sim.host(host).borrow(), &[("zone", "a")]);
+        }
+        orchestrator.borrow_mut().init();
+
+        let template = PodSpec::new("web", 1000, 2).with_startup_time(5.);
+        orchestrator.borrow_mut().create_replica_set("web", 3, template.clone());
+        cloud_sim.step_for_duration(10.);
+        assert!(orchestrator.borrow().replica_set_status("web").unwrap().is_rolled_out());
+
+        orchestrator
+            .borrow_mut()
+            .rolling_update("web", template.with_version("v2"), max_surge, max_unavailable);
+        for _ in 0..100 {
+            cloud_sim.step_for_duration(0.5);
+            let status = orchestrator.borrow().replica_set_status("web").unwrap();
+            assert!(status.current <= 3 + max_surge);
+            assert!(status.ready >= 3 - max_unavailable);
+        }
+
+        let orchestrator = orchestrator.borrow();
+        let status = orchestrator.replica_set_status("web").unwrap();
+        assert!(status.is_rolled_out());
+        assert_eq!(status.revision, 2);
+        let active: Vec<&str> = orchestrator
+            .pods()
+            .filter(|pod| pod.status.is_active())
+            .map(|pod| pod.spec.version.as_str())
+            .collect();
+        assert_eq!(active, vec!["v2"; 3]);
+        assert_eq!(orchestrator.stats().terminated_pods, 3);
+    }
+}
@@ -0,0 +1,143 @@
+use dslab_core::simulation::Simulation;
+
+use dslab_iaas::core::config::sim_config::SimulationConfig;
+use dslab_iaas::core::vm::VmStatus;
+use dslab_iaas::custom_component::CustomComponent;
+use dslab_iaas::extensions::spot_market::{Bid, BidStatus, PricingRule, SpotMarket};
+use dslab_iaas::simulation::CloudSimulation;
+
+fn name_wrapper(file_name: &str) -> String {
+    format!("test-configs/{}", file_name)
+}
+
+#[test]
+// The highest bids win the capacity, with uniform pricing they pay the highest losing bid price.
+fn test_spot_market_pricing() {
+    for (pricing, expected_revenue) in [(PricingRule::Uniform, 40.), (PricingRule::PayAsBid, 120.)] {
+        let sim = Simulation::new(123);
+        let sim_config = SimulationConfig::from_file(&name_wrapper("config_zero_latency.yaml"));
+        let mut cloud_sim = CloudSimulation::new(sim, sim_config);
+        let host = cloud_sim.add_host("spot", 4, 64);
+        let market = cloud_sim.build_custom_component::<SpotMarket>("market");
+        market.borrow_mut().patch_custom_args(
+            1.,
+            pricing,
+            cloud_sim.vm_api(),
+            cloud_sim.sim_config(),
+            cloud_sim.lookup_id("placement_store"),
+        );
+        market.borrow_mut().add_host(&cloud_sim.host(host).borrow());
+
+        let bids: Vec<u32> = [("a", 3.), ("b", 2.), ("c", 1.)]
+            .iter()
+            .map(|(tenant, price)| {
+                let bid = Bid {
+                    tenant: tenant.to_string(),
+                    cpu: 2,
+                    memory: 8,
+                    price: *price,
+                    duration: 10.,
+                };
+                market.borrow_mut().submit_bid(bid, 0.)
+            })
+            .collect();
+        market.borrow_mut().init();
+        cloud_sim.step_for_duration(5.);
+
+        {
+            let market = market.borrow();
+            let statuses: Vec<BidStatus> = bids.iter().map(|id| market.bid(*id).unwrap().status).collect();
+            assert_eq!(
+                statuses,
+                vec![BidStatus::Running, BidStatus::Running, BidStatus::Waiting]
+            );
+            let expected_price = if pricing == PricingRule::Uniform { 1. } else { 2. };
+            assert_eq!(market.current_price(), expected_price);
+            assert_eq!(market.rounds()[0].rejected_bids, 1);
+        }
+        assert_eq!(cloud_sim.host_by_name("spot").borrow().cpu_allocated(), 4.);
+        cloud_sim.step_for_duration(20.);
+
+        let market = market.borrow();
+        let completion_times: Vec<f64> = bids
+            .iter()
+            .map(|id| market.bid(*id).unwrap().completion_time.unwrap())
+            .collect();
+        assert_eq!(completion_times, vec![10., 10., 20.]);
+        let stats = market.stats();
+        assert_eq!(stats.completed_bids, 3);
+        assert_eq!(stats.interruptions, 0);
+        assert_eq!(stats.revenue, expected_revenue);
+        assert_eq!(stats.welfare, 120.);
+        assert_eq!(
+            stats.tenant_spend["c"],
+            if pricing == PricingRule::Uniform { 0. } else { 20. }
+        );
+    }
+}
+
+#[test]
+// Higher bid interrupts the running lower bid, which is resumed later with the remaining work.
+fn test_spot_market_interruption() {
+    let sim = Simulation::new(123);
+    let sim_config = SimulationConfig::from_file(&name_wrapper("config_zero_latency.yaml"));
+    let mut cloud_sim = CloudSimulation::new(sim, sim_config);
+    let host = cloud_sim.add_host("spot", 4, 64);
+    let market = cloud_sim.build_custom_component::<SpotMarket>("market");
+    market.borrow_mut().patch_custom_args(
+        1.,
+        PricingRule::Uniform,
+        cloud_sim.vm_api(),
+        cloud_sim.sim_config(),
+        cloud_sim.lookup_id("placement_store"),
+    );
+    market.borrow_mut().add_host(&cloud_sim.host(host).borrow());
+
+    let low = market.borrow_mut().submit_bid(
+        Bid {
+            tenant: "low".to_string(),
+            cpu: 4,
+            memory: 8,
+            price: 1.,
+            duration: 10.,
+        },
+        0.,
+    );
+    let high = market.borrow_mut().submit_bid(
+        Bid {
+            tenant: "high".to_string(),
+            cpu: 4,
+            memory: 8,
+            price: 5.,
+            duration: 5.,
+        },
+        3.,
+    );
+    market.borrow_mut().init();
+    cloud_sim.step_for_duration(1.);
+    let first_vm = market.borrow().bid(low).unwrap().vm_id.unwrap();
+    cloud_sim.step_for_duration(3.);
+
+    {
+        let market = market.borrow();
+        let low = market.bid(low).unwrap();
+        assert_eq!(low.status, BidStatus::Waiting);
+        assert_eq!(low.work_done, 3.);
+        assert_eq!(low.interruptions, 1);
+        assert_eq!(market.bid(high).unwrap().status, BidStatus::Running);
+        assert_eq!(market.current_price(), 1.);
+    }
+    assert_eq!(cloud_sim.vm_status(first_vm), VmStatus::Preempted);
+    cloud_sim.step_for_duration(16.);
+
+    let market = market.borrow();
+    assert_eq!(market.bid(high).unwrap().completion_time, Some(8.));
+    assert_eq!(market.bid(low).unwrap().completion_time, Some(15.));
+    assert_ne!(market.bid(low).unwrap().vm_id, Some(first_vm));
+    let stats = market.stats();
+    assert_eq!(stats.completed_bids, 2);
+    assert_eq!(stats.interruptions, 1);
+    assert_eq!(stats.max_price, 1.);
+    assert_eq!(stats.revenue, 20.);
+    assert_eq!(stats.welfare, 10. * 4. + 5. * 5. * 4.);
+}
@@ -0,0 +1,49 @@
+use dslab_iaas::validation::{
+    constant_bandwidth_transfer, run_all, shared_bandwidth_transfer, simple_packing, single_host_energy, Reference,
+    ValidationCase,
+};
+
+fn assert_passed(case: ValidationCase) {
+    assert!(case.passed(), "validation failed:\n{}", case);
+}
+
+#[test]
+fn test_simple_packing() {
+    let case = simple_packing();
+    assert_eq!(case.checks.len(), 7);
+    assert_passed(case);
+}
+
+#[test]
+fn test_single_host_energy() {
+    let case = single_host_energy();
+    assert_eq!(case.reference, Reference::CloudSim);
+    assert_passed(case);
+}
+
+#[test]
+fn test_constant_bandwidth_transfer() {
+    assert_passed(constant_bandwidth_transfer());
+}
+
+#[test]
+fn test_shared_bandwidth_transfer() {
+    assert_passed(shared_bandwidth_transfer());
+}
+
+#[test]
+// The report lists the mismatching metrics of failed scenario.
+fn test_validation_report() {
+    let cases = run_all();
+    assert_eq!(cases.len(), 4);
+    assert!(cases.iter().all(|case| case.passed()));
+
+    let mut case = cases[1].clone();
+    case.checks[1].actual += 1.;
+    assert!(!case.passed());
+    assert_eq!(case.failures().len(), 1);
+    assert_eq!(case.failures()[0].metric, "energy");
+    let report = case.to_string();
+    assert!(report.starts_with("single_host_energy (CloudSim): FAILED"));
+    assert!(report.contains("energy: expected 1900, actual 1901"));
+}
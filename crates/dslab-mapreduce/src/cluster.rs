@@ -0,0 +1,791 @@
+//! MapReduce cluster.
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::rc::Rc;
+
+use serde::Serialize;
+
+use dslab_core::component::Id;
+use dslab_core::context::SimulationContext;
+use dslab_core::event::{Event, EventId};
+use dslab_core::handler::EventHandler;
+use dslab_core::{cast, log_debug, log_info};
+use dslab_network::{DataTransferCompleted, Network};
+
+use crate::events::{CheckStragglers, JobArrived, JobCompleted, ScheduleTasks, TaskCompleted, TaskType};
+use crate::job::{JobSpec, JobStats};
+use crate::locality::{LocalityPolicy, LocalityStats, NodeLocalPolicy, PendingTask, SlotOffer, TaskLocality};
+use crate::speculation::{RunningTask, StragglerDetector};
+
+/// Model of straggler tasks which run slower than the other tasks, e.g. due to the host interference or failures.
+///
+/// Each task becomes a straggler with the specified probability, in this case its computation time is multiplied by
+/// a slowdown factor sampled uniformly from `[min_slowdown, max_slowdown]`.
+#[derive(Clone, Debug, Serialize)]
+pub struct StragglerModel {
+    /// Probability of task to become a straggler.
+    pub probability: f64,
+    /// Minimal slowdown factor.
+    pub min_slowdown: f64,
+    /// Maximal slowdown factor.
+    pub max_slowdown: f64,
+}
+
+impl StragglerModel {
+    /// Creates straggler model with given parameters.
+    pub fn new(probability: f64, min_slowdown: f64, max_slowdown: f64) -> Self {
+        assert!(
+            (0. ..=1.).contains(&probability),
+            "straggler probability should be in [0, 1]"
+        );
+        assert!(
+            1. <= min_slowdown && min_slowdown <= max_slowdown,
+            "straggler slowdown should satisfy 1 <= min_slowdown <= max_slowdown"
+        );
+        Self {
+            probability,
+            min_slowdown,
+            max_slowdown,
+        }
+    }
+
+    /// Creates model without stragglers.
+    pub fn none() -> Self {
+        Self::new(0., 1., 1.)
+    }
+
+    fn sample_slowdown(&self, ctx: &SimulationContext) -> Option<f64> {
+        if self.probability > 0. && ctx.rand() < self.probability {
+            if self.min_slowdown < self.max_slowdown {
+                Some(ctx.gen_range(self.min_slowdown..self.max_slowdown))
+            } else {
+                Some(self.min_slowdown)
+            }
+        } else {
+            None
+        }
+    }
+}
+
+impl Default for StragglerModel {
+    fn default() -> Self {
+        Self::none()
+    }
+}
+
+struct HostInfo {
+    id: Id,
+    speed: f64,
+    free_slots: u32,
+    rack: Option<u32>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct TaskRef {
+    job_id: u64,
+    task_type: TaskType,
+    index: usize,
+}
+
+struct Computation {
+    event_id: EventId,
+    start_time: f64,
+    duration: f64,
+}
+
+// Execution of task on some host, the task can have several attempts if it is speculated.
+struct Attempt {
+    task: TaskRef,
+    host_idx: usize,
+    speculative: bool,
+    start_time: f64,
+    input: f64,
+    fetches: usize,
+    pending_fetches: usize,
+    transfers: Vec<usize>,
+    computation: Option<Computation>,
+}
+
+impl Attempt {
+    fn progress(&self, time: f64) -> f64 {
+        let computed = match &self.computation {
+            Some(comp) if comp.duration > 0. => ((time - comp.start_time) / comp.duration).min(1.),
+            Some(_) => 1.,
+            None => 0.,
+        };
+        if self.fetches == 0 {
+            computed
+        } else if self.computation.is_some() {
+            0.5 + 0.5 * computed
+        } else {
+            0.5 * (self.fetches - self.pending_fetches) as f64 / self.fetches as f64
+        }
+    }
+}
+
+struct JobState {
+    spec: JobSpec,
+    stats: JobStats,
+    maps_left: usize,
+    reduces_left: usize,
+    shuffles_left: usize,
+    map_hosts: Vec<usize>,
+    map_outputs: Vec<f64>,
+    shuffled: Vec<bool>,
+}
+
+impl JobState {
+    fn completed_tasks(&self, task_type: TaskType) -> (usize, usize) {
+        match task_type {
+            TaskType::Map => (self.spec.splits.len() - self.maps_left, self.spec.splits.len()),
+            TaskType::Reduce => (self.spec.reduce_tasks - self.reduces_left, self.spec.reduce_tasks),
+        }
+    }
+}
+
+/// Component modeling a cluster running MapReduce jobs.
+///
+/// Each host has a number of task slots and a speed (in flop/s) used to compute the task execution times.
+/// The hosts can be grouped into racks. The cluster runs the jobs in FIFO order. The free slots are offered to
+/// the [locality policy](LocalityPolicy) which selects the pending task for the slot based on the location of its
+/// input data. By default, the slot is assigned to the first pending map task with the input split stored on the
+/// slot's host, if there is no such task the slot is assigned to the first pending task ([`NodeLocalPolicy`]).
+/// A map task running on a host without its input split first reads the split from the host storing it, preferring
+/// the hosts from the same rack.
+/// Reduce tasks are started after all job map tasks are completed and fetch the intermediate data from the hosts
+/// of map tasks. All transfers between different hosts are performed through the network.
+///
+/// If the speculative execution is enabled, the running tasks are periodically checked by the
+/// [straggler detector](StragglerDetector), and the detected stragglers are duplicated on other hosts.
+///
+/// The hosts must be registered in the network via [`Network::set_location`].
+pub struct MapReduceCluster {
+    network: Rc<RefCell<Network>>,
+    hosts: Vec<HostInfo>,
+    host_index: HashMap<Id, usize>,
+    jobs: BTreeMap<u64, JobState>,
+    next_job_id: u64,
+    pending_tasks: VecDeque<(TaskRef, f64)>,
+    attempts: BTreeMap<u64, Attempt>,
+    next_attempt_id: u64,
+    transfers: HashMap<usize, u64>,
+    straggler_model: StragglerModel,
+    locality_policy: Box<dyn LocalityPolicy>,
+    retry_time: Option<f64>,
+    speculation: Option<(Box<dyn StragglerDetector>, f64)>,
+    speculation_check: bool,
+    listener: Option<Id>,
+    ctx: SimulationContext,
+}
+
+impl MapReduceCluster {
+    /// Creates cluster without hosts which uses the specified network for data transfers.
+    pub fn new(network: Rc<RefCell<Network>>, ctx: SimulationContext) -> Self {
+        Self {
+            network,
+            hosts: Vec::new(),
+            host_index: HashMap::new(),
+            jobs: BTreeMap::new(),
+            next_job_id: 0,
+            pending_tasks: VecDeque::new(),
+            attempts: BTreeMap::new(),
+            next_attempt_id: 0,
+            transfers: HashMap::new(),
+            straggler_model: StragglerModel::none(),
+            locality_policy: Box::new(NodeLocalPolicy),
+            retry_time: None,
+            speculation: None,
+            speculation_check: false,
+            listener: None,
+            ctx,
+        }
+    }
+
+    /// Returns component id.
+    pub fn id(&self) -> Id {
+        self.ctx.id()
+    }
+
+    /// Adds host with given number of task slots and speed.
+    ///
+    /// The host is identified by the id of a simulation component bound to some network node.
+    pub fn add_host(&mut self, id: Id, slots: u32, speed: f64) {
+        assert!(!self.host_index.contains_key(&id), "host {} is already added", id);
+        assert!(slots > 0, "host should have at least one slot");
+        assert!(speed > 0., "host speed should be positive");
+        self.host_index.insert(id, self.hosts.len());
+        self.hosts.push(HostInfo {
+            id,
+            speed,
+            free_slots: slots,
+            rack: None,
+        });
+    }
+
+    /// Sets the rack of previously added host.
+    pub fn set_host_rack(&mut self, id: Id, rack: u32) {
+        let host_idx = *self.host_index.get(&id).expect("unknown host");
+        self.hosts[host_idx].rack = Some(rack);
+    }
+
+    /// Sets the policy selecting the tasks for free slots.
+    pub fn set_locality_policy(&mut self, policy: Box<dyn LocalityPolicy>) {
+        self.locality_policy = policy;
+    }
+
+    /// Sets the straggler model applied to all tasks.
+    pub fn set_straggler_model(&mut self, model: StragglerModel) {
+        self.straggler_model = model;
+    }
+
+    /// Enables the speculative execution of tasks, which are checked by the specified detector with given interval.
+    ///
+    /// Similar to Hadoop, the speculative attempts are launched only when there are no pending tasks.
+    /// Each speculative attempt runs on the host with the most free slots among the hosts not running the same task.
+    /// The task is completed by the first finished attempt, while the other attempt is killed and its data transfers
+    /// are cancelled.
+    pub fn set_speculation(&mut self, detector: Box<dyn StragglerDetector>, interval: f64) {
+        assert!(interval > 0., "speculation check interval should be positive");
+        self.speculation = Some((detector, interval));
+    }
+
+    /// Sets the component which receives [`JobCompleted`] events.
+    pub fn set_listener(&mut self, id: Id) {
+        self.listener = Some(id);
+    }
+
+    /// Submits job to the cluster after the specified delay and returns the job id.
+    pub fn submit_job(&mut self, spec: JobSpec, delay: f64) -> u64 {
+        assert!(!self.hosts.is_empty(), "cluster has no hosts");
+        for split in spec.splits.iter() {
+            assert!(
+                !split.locations.is_empty(),
+                "input split should have at least one location"
+            );
+        }
+        let job_id = self.next_job_id;
+        self.next_job_id += 1;
+        let maps = spec.splits.len();
+        let reduces = spec.reduce_tasks;
+        let stats = JobStats::new(job_id, spec.name.clone(), self.ctx.time() + delay);
+        self.jobs.insert(
+            job_id,
+            JobState {
+                spec,
+                stats,
+                maps_left: maps,
+                reduces_left: reduces,
+                shuffles_left: reduces,
+                map_hosts: vec![0; maps],
+                map_outputs: vec![0.; maps],
+                shuffled: vec![false; reduces],
+            },
+        );
+        self.ctx.emit_self(JobArrived { job_id }, delay);
+        job_id
+    }
+
+    /// Returns statistics of the specified job.
+    pub fn job_stats(&self, job_id: u64) -> Option<&JobStats> {
+        self.jobs.get(&job_id).map(|job| &job.stats)
+    }
+
+    /// Returns statistics of all submitted jobs ordered by job id.
+    pub fn all_job_stats(&self) -> Vec<JobStats> {
+        self.jobs.values().map(|job| job.stats.clone()).collect()
+    }
+
+    /// Returns the locality statistics of map tasks of all jobs.
+    pub fn locality_stats(&self) -> LocalityStats {
+        let mut stats = LocalityStats::default();
+        for job in self.jobs.values() {
+            stats.node_local += job.stats.local_map_tasks;
+            stats.rack_local += job.stats.rack_local_map_tasks;
+            stats.off_rack += job.stats.remote_map_tasks;
+        }
+        stats
+    }
+
+    /// Returns the mean completion time of completed jobs, or `None` if there are no completed jobs.
+    pub fn mean_completion_time(&self) -> Option<f64> {
+        let times = self
+            .jobs
+            .values()
+            .filter_map(|job| job.stats.completion_time())
+            .collect::<Vec<_>>();
+        if times.is_empty() {
+            None
+        } else {
+            Some(times.iter().sum::<f64>() / times.len() as f64)
+        }
+    }
+
+    fn on_job_arrived(&mut self, job_id: u64) {
+        let job = self.jobs.get_mut(&job_id).unwrap();
+        log_debug!(
+            self.ctx,
+            "job {} ({}) arrived with {} map and {} reduce tasks",
+            job_id,
+            job.spec.name,
+            job.spec.splits.len(),
+            job.spec.reduce_tasks
+        );
+        if job.spec.splits.is_empty() {
+            job.stats.map_finish_time = Some(self.ctx.time());
+            self.start_reduce_phase(job_id);
+        } else {
+            for index in 0..job.spec.splits.len() {
+                let task = TaskRef {
+                    job_id,
+                    task_type: TaskType::Map,
+                    index,
+                };
+                self.pending_tasks.push_back((task, self.ctx.time()));
+            }
+        }
+        self.schedule_tasks();
+    }
+
+    fn host_rack(&self, host: Id) -> Option<u32> {
+        self.host_index.get(&host).and_then(|idx| self.hosts[*idx].rack)
+    }
+
+    fn locality(&self, locations: &[Id], host_idx: usize) -> TaskLocality {
+        let host = &self.hosts[host_idx];
+        if locations.contains(&host.id) {
+            TaskLocality::NodeLocal
+        } else if host.rack.is_some() && locations.iter().any(|loc| self.host_rack(*loc) == host.rack) {
+            TaskLocality::RackLocal
+        } else {
+            TaskLocality::OffRack
+        }
+    }
+
+    fn pending_task_infos(&self, host_idx: usize) -> Vec<PendingTask> {
+        self.pending_tasks
+            .iter()
+            .map(|(task, pending_since)| {
+                let (locations, locality) = match task.task_type {
+                    TaskType::Map => {
+                        let locations = self.jobs[&task.job_id].spec.splits[task.index].locations.clone();
+                        let locality = self.locality(&locations, host_idx);
+                        (locations, Some(locality))
+                    }
+                    TaskType::Reduce => (Vec::new(), None),
+                };
+                PendingTask {
+                    job_id: task.job_id,
+                    task_type: task.task_type,
+                    index: task.index,
+                    locations,
+                    locality,
+                    pending_since: *pending_since,
+                }
+            })
+            .collect()
+    }
+
+    fn schedule_tasks(&mut self) {
+        let time = self.ctx.time();
+        while !self.pending_tasks.is_empty() {
+            // offer the slots of the least loaded hosts first
+            let mut offers = (0..self.hosts.len())
+                .filter(|idx| self.hosts[*idx].free_slots > 0)
+                .collect::<Vec<_>>();
+            offers.sort_by(|a, b| self.hosts[*b].free_slots.cmp(&self.hosts[*a].free_slots).then(a.cmp(b)));
+            let mut assignment = None;
+            for host_idx in offers {
+                let host = &self.hosts[host_idx];
+                let offer = SlotOffer {
+                    host: host.id,
+                    rack: host.rack,
+                    free_slots: host.free_slots,
+                };
+                let tasks = self.pending_task_infos(host_idx);
+                if let Some(pos) = self.locality_policy.select_task(&offer, &tasks, time) {
+                    assignment = Some((pos, host_idx));
+                    break;
+                }
+            }
+            match assignment {
+                Some((pos, host_idx)) => {
+                    let (task, _) = self.pending_tasks.remove(pos).unwrap();
+                    self.start_attempt(task, host_idx, false);
+                }
+                None => break,
+            }
+        }
+        if !self.pending_tasks.is_empty() && self.hosts.iter().any(|host| host.free_slots > 0) {
+            let tasks = self.pending_task_infos(0);
+            if let Some(retry_time) = self.locality_policy.retry_time(&tasks, time) {
+                if self.retry_time.is_none_or(|scheduled| retry_time < scheduled) {
+                    self.retry_time = Some(retry_time);
+                    self.ctx.emit_self(ScheduleTasks {}, retry_time - time);
+                }
+            }
+        }
+    }
+
+    fn start_attempt(&mut self, task: TaskRef, host_idx: usize, speculative: bool) {
+        self.hosts[host_idx].free_slots -= 1;
+        let host_id = self.hosts[host_idx].id;
+        let time = self.ctx.time();
+        let attempt_id = self.next_attempt_id;
+        self.next_attempt_id += 1;
+        let job = self.jobs.get_mut(&task.job_id).unwrap();
+        job.stats.start_time.get_or_insert(time);
+        log_debug!(
+            self.ctx,
+            "started {}{:?} task {} of job {} on host {}",
+            if speculative { "speculative attempt of " } else { "" },
+            task.task_type,
+            task.index,
+            task.job_id,
+            self.ctx.lookup_name(host_id)
+        );
+        let mut attempt = Attempt {
+            task,
+            host_idx,
+            speculative,
+            start_time: time,
+            input: 0.,
+            fetches: 0,
+            pending_fetches: 0,
+            transfers: Vec::new(),
+            computation: None,
+        };
+        match task.task_type {
+            TaskType::Map => {
+                let locations = job.spec.splits[task.index].locations.clone();
+                let size = job.spec.splits[task.index].size;
+                let locality = self.locality(&locations, host_idx);
+                if !speculative {
+                    let job = self.jobs.get_mut(&task.job_id).unwrap();
+                    match locality {
+                        TaskLocality::NodeLocal => job.stats.local_map_tasks += 1,
+                        TaskLocality::RackLocal => job.stats.rack_local_map_tasks += 1,
+                        TaskLocality::OffRack => job.stats.remote_map_tasks += 1,
+                    }
+                }
+                if locality != TaskLocality::NodeLocal {
+                    let rack = self.hosts[host_idx].rack;
+                    let source = locations
+                        .iter()
+                        .find(|loc| rack.is_some() && self.host_rack(**loc) == rack)
+                        .unwrap_or(&locations[0]);
+                    let transfer_id = self
+                        .network
+                        .borrow_mut()
+                        .transfer_data(*source, host_id, size, self.ctx.id());
+                    self.transfers.insert(transfer_id, attempt_id);
+                    attempt.transfers.push(transfer_id);
+                    attempt.fetches = 1;
+                }
+            }
+            TaskType::Reduce => {
+                let reduce_tasks = job.spec.reduce_tasks as f64;
+                for map in 0..job.spec.splits.len() {
+                    let size = job.map_outputs[map] / reduce_tasks;
+                    let map_host_id = self.hosts[job.map_hosts[map]].id;
+                    if map_host_id == host_id || size == 0. {
+                        attempt.input += size;
+                    } else {
+                        let transfer_id =
+                            self.network
+                                .borrow_mut()
+                                .transfer_data(map_host_id, host_id, size, self.ctx.id());
+                        self.transfers.insert(transfer_id, attempt_id);
+                        attempt.transfers.push(transfer_id);
+                        attempt.fetches += 1;
+                        job.stats.shuffled_data += size;
+                    }
+                }
+            }
+        }
+        attempt.pending_fetches = attempt.fetches;
+        let fetched = attempt.fetches == 0;
+        self.attempts.insert(attempt_id, attempt);
+        if fetched {
+            self.on_input_fetched(attempt_id);
+        }
+        self.schedule_speculation_check();
+    }
+
+    fn on_input_fetched(&mut self, attempt_id: u64) {
+        let task = self.attempts[&attempt_id].task;
+        if task.task_type == TaskType::Reduce {
+            let time = self.ctx.time();
+            let job = self.jobs.get_mut(&task.job_id).unwrap();
+            if !job.shuffled[task.index] {
+                job.shuffled[task.index] = true;
+                job.shuffles_left -= 1;
+                if job.shuffles_left == 0 {
+                    job.stats.shuffle_finish_time = Some(time);
+                }
+            }
+        }
+        self.start_computation(attempt_id);
+    }
+
+    fn start_computation(&mut self, attempt_id: u64) {
+        let attempt = self.attempts.get_mut(&attempt_id).unwrap();
+        let task = attempt.task;
+        let job = self.jobs.get_mut(&task.job_id).unwrap();
+        let flops = match task.task_type {
+            TaskType::Map => job.spec.splits[task.index].size * job.spec.map_flops_per_unit,
+            TaskType::Reduce => attempt.input * job.spec.reduce_flops_per_unit,
+        };
+        let mut duration = flops / self.hosts[attempt.host_idx].speed;
+        if let Some(slowdown) = self.straggler_model.sample_slowdown(&self.ctx) {
+            log_debug!(
+                self.ctx,
+                "{:?} task {} of job {} is a straggler with slowdown {:.2}",
+                task.task_type,
+                task.index,
+                task.job_id,
+                slowdown
+            );
+            job.stats.straggler_tasks += 1;
+            duration *= slowdown;
+        }
+        let event_id = self.ctx.emit_self(
+            TaskCompleted {
+                job_id: task.job_id,
+                task_type: task.task_type,
+                index: task.index,
+                attempt: attempt_id,
+            },
+            duration,
+        );
+        attempt.computation = Some(Computation {
+            event_id,
+            start_time: self.ctx.time(),
+            duration,
+        });
+    }
+
+    fn on_transfer_completed(&mut self, transfer_id: usize, size: f64) {
+        if let Some(attempt_id) = self.transfers.remove(&transfer_id) {
+            let attempt = self.attempts.get_mut(&attempt_id).unwrap();
+            attempt.transfers.retain(|id| *id != transfer_id);
+            if attempt.task.task_type == TaskType::Reduce {
+                attempt.input += size;
+            }
+            attempt.pending_fetches -= 1;
+            if attempt.pending_fetches == 0 {
+                self.on_input_fetched(attempt_id);
+            }
+        }
+    }
+
+    fn on_task_completed(&mut self, attempt_id: u64) {
+        let time = self.ctx.time();
+        let attempt = match self.attempts.remove(&attempt_id) {
+            Some(attempt) => attempt,
+            None => return,
+        };
+        let TaskRef {
+            job_id,
+            task_type,
+            index,
+        } = attempt.task;
+        log_debug!(self.ctx, "completed {:?} task {} of job {}", task_type, index, job_id);
+        self.hosts[attempt.host_idx].free_slots += 1;
+        let other_attempts = self
+            .attempts
+            .iter()
+            .filter(|(_, other)| other.task == attempt.task)
+            .map(|(id, _)| *id)
+            .collect::<Vec<_>>();
+        for other in other_attempts {
+            self.kill_attempt(other);
+        }
+        let job = self.jobs.get_mut(&job_id).unwrap();
+        if attempt.speculative {
+            job.stats.successful_speculations += 1;
+        }
+        match task_type {
+            TaskType::Map => {
+                job.map_hosts[index] = attempt.host_idx;
+                job.map_outputs[index] = job.spec.splits[index].size * job.spec.map_output_ratio;
+                job.maps_left -= 1;
+                if job.maps_left == 0 {
+                    job.stats.map_finish_time = Some(time);
+                    self.start_reduce_phase(job_id);
+                }
+            }
+            TaskType::Reduce => {
+                job.reduces_left -= 1;
+                if job.reduces_left == 0 {
+                    self.complete_job(job_id);
+                }
+            }
+        }
+        self.schedule_tasks();
+    }
+
+    fn kill_attempt(&mut self, attempt_id: u64) {
+        let attempt = self.attempts.remove(&attempt_id).unwrap();
+        log_debug!(
+            self.ctx,
+            "killed {}attempt of {:?} task {} of job {} on host {}",
+            if attempt.speculative { "speculative " } else { "" },
+            attempt.task.task_type,
+            attempt.task.index,
+            attempt.task.job_id,
+            self.ctx.lookup_name(self.hosts[attempt.host_idx].id)
+        );
+        if let Some(computation) = attempt.computation {
+            self.ctx.cancel_event(computation.event_id);
+        }
+        for transfer_id in attempt.transfers {
+            self.transfers.remove(&transfer_id);
+            self.network.borrow_mut().cancel_transfer(transfer_id);
+        }
+        self.hosts[attempt.host_idx].free_slots += 1;
+        self.jobs.get_mut(&attempt.task.job_id).unwrap().stats.killed_attempts += 1;
+    }
+
+    fn schedule_speculation_check(&mut self) {
+        if let Some((_, interval)) = &self.speculation {
+            if !self.speculation_check && !self.attempts.is_empty() {
+                self.speculation_check = true;
+                self.ctx.emit_self(CheckStragglers {}, *interval);
+            }
+        }
+    }
+
+    fn running_task_infos(&self) -> Vec<(TaskRef, RunningTask)> {
+        let time = self.ctx.time();
+        let mut task_attempts: BTreeMap<TaskRef, Vec<&Attempt>> = BTreeMap::new();
+        for attempt in self.attempts.values() {
+            task_attempts.entry(attempt.task).or_default().push(attempt);
+        }
+        let mut running_progress: HashMap<(u64, TaskType), f64> = HashMap::new();
+        for (task, attempts) in task_attempts.iter() {
+            let progress = attempts.iter().map(|a| a.progress(time)).fold(0., f64::max);
+            *running_progress.entry((task.job_id, task.task_type)).or_default() += progress;
+        }
+        task_attempts
+            .into_iter()
+            .map(|(task, attempts)| {
+                let original = attempts[0];
+                let (completed, total) = self.jobs[&task.job_id].completed_tasks(task.task_type);
+                let job_progress = (completed as f64 + running_progress[&(task.job_id, task.task_type)]) / total as f64;
+                let info = RunningTask {
+                    job_id: task.job_id,
+                    task_type: task.task_type,
+                    index: task.index,
+                    host: self.hosts[original.host_idx].id,
+                    elapsed: time - original.start_time,
+                    progress: original.progress(time),
+                    job_progress,
+                    speculated: attempts.len() > 1,
+                };
+                (task, info)
+            })
+            .collect()
+    }
+
+    fn on_check_stragglers(&mut self) {
+        self.speculation_check = false;
+        self.schedule_speculation_check();
+        if !self.pending_tasks.is_empty() || self.hosts.iter().all(|host| host.free_slots == 0) {
+            return;
+        }
+        let (tasks, infos): (Vec<_>, Vec<_>) = self.running_task_infos().into_iter().unzip();
+        let stragglers = match &self.speculation {
+            Some((detector, _)) => detector.select_stragglers(&infos, self.ctx.time()),
+            None => return,
+        };
+        for pos in stragglers {
+            let task = tasks[pos];
+            let busy_hosts = self
+                .attempts
+                .values()
+                .filter(|attempt| attempt.task == task)
+                .map(|attempt| attempt.host_idx)
+                .collect::<Vec<_>>();
+            let host = (0..self.hosts.len())
+                .filter(|idx| self.hosts[*idx].free_slots > 0 && !busy_hosts.contains(idx))
+                .min_by(|a, b| self.hosts[*b].free_slots.cmp(&self.hosts[*a].free_slots).then(a.cmp(b)));
+            if let Some(host_idx) = host {
+                log_debug!(
+                    self.ctx,
+                    "{:?} task {} of job {} is detected as a straggler with progress {:.2}",
+                    task.task_type,
+                    task.index,
+                    task.job_id,
+                    infos[pos].progress
+                );
+                self.jobs.get_mut(&task.job_id).unwrap().stats.speculative_tasks += 1;
+                self.start_attempt(task, host_idx, true);
+            }
+        }
+    }
+
+    fn start_reduce_phase(&mut self, job_id: u64) {
+        let reduce_tasks = self.jobs[&job_id].spec.reduce_tasks;
+        if reduce_tasks == 0 {
+            self.complete_job(job_id);
+            return;
+        }
+        for index in 0..reduce_tasks {
+            let task = TaskRef {
+                job_id,
+                task_type: TaskType::Reduce,
+                index,
+            };
+            self.pending_tasks.push_back((task, self.ctx.time()));
+        }
+    }
+
+    fn complete_job(&mut self, job_id: u64) {
+        let time = self.ctx.time();
+        let job = self.jobs.get_mut(&job_id).unwrap();
+        job.stats.finish_time = Some(time);
+        let completion_time = job.stats.completion_time().unwrap();
+        log_info!(
+            self.ctx,
+            "job {} ({}) completed in {:.3}",
+            job_id,
+            job.spec.name,
+            completion_time
+        );
+        if let Some(listener) = self.listener {
+            self.ctx.emit_now(
+                JobCompleted {
+                    job_id,
+                    completion_time,
+                },
+                listener,
+            );
+        }
+    }
+}
+
+impl EventHandler for MapReduceCluster {
+    fn on(&mut self, event: Event) {
+        cast!(match event.data {
+            JobArrived { job_id } => {
+                self.on_job_arrived(job_id);
+            }
+            TaskCompleted { attempt, .. } => {
+                self.on_task_completed(attempt);
+            }
+            ScheduleTasks {} => {
+                if self.retry_time.is_some_and(|time| time <= self.ctx.time()) {
+                    self.retry_time = None;
+                }
+                self.schedule_tasks();
+            }
+            CheckStragglers {} => {
+                self.on_check_stragglers();
+            }
+            DataTransferCompleted { dt } => {
+                self.on_transfer_completed(dt.id, dt.size);
+            }
+        })
+    }
+}
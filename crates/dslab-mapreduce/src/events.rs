@@ -0,0 +1,49 @@
+//! Events used by MapReduce cluster.
+
+use serde::Serialize;
+
+/// Type of MapReduce task.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub enum TaskType {
+    /// Map task processing an input split.
+    Map,
+    /// Reduce task processing a partition of intermediate data.
+    Reduce,
+}
+
+/// Job arrival to the cluster (internal event).
+#[derive(Clone, Serialize)]
+pub struct JobArrived {
+    /// Job id.
+    pub job_id: u64,
+}
+
+/// Repeated offer of free slots requested by the locality policy (internal event).
+#[derive(Clone, Serialize)]
+pub struct ScheduleTasks {}
+
+/// Completion of task computations (internal event).
+#[derive(Clone, Serialize)]
+pub struct TaskCompleted {
+    /// Job id.
+    pub job_id: u64,
+    /// Task type.
+    pub task_type: TaskType,
+    /// Index of task among the job tasks of the same type.
+    pub index: usize,
+    /// Id of task attempt.
+    pub attempt: u64,
+}
+
+/// Periodic check of running tasks by the straggler detector (internal event).
+#[derive(Clone, Serialize)]
+pub struct CheckStragglers {}
+
+/// Job completion, sent to the cluster listener.
+#[derive(Clone, Serialize)]
+pub struct JobCompleted {
+    /// Job id.
+    pub job_id: u64,
+    /// Job completion time measured from its submission.
+    pub completion_time: f64,
+}
@@ -0,0 +1,162 @@
+//! Job specification and statistics.
+
+use serde::Serialize;
+
+use dslab_core::component::Id;
+
+/// Part of job input data stored on one or several cluster hosts.
+#[derive(Clone, Debug, Serialize)]
+pub struct InputSplit {
+    /// Split size.
+    pub size: f64,
+    /// Hosts storing the split replicas.
+    pub locations: Vec<Id>,
+}
+
+impl InputSplit {
+    /// Creates split of given size stored on the specified hosts.
+    pub fn new(size: f64, locations: Vec<Id>) -> Self {
+        Self { size, locations }
+    }
+
+    /// Creates one split per file chunk from the chunk sizes and replica locations, e.g. as returned by
+    /// `DistributedFileSystem::chunk_locations()` from DSLab Storage.
+    pub fn from_chunks(chunks: &[(u64, Vec<Id>)]) -> Vec<Self> {
+        chunks
+            .iter()
+            .map(|(size, locations)| Self::new(*size as f64, locations.clone()))
+            .collect()
+    }
+}
+
+/// Specification of MapReduce job.
+///
+/// The job runs one map task per input split. Each map task processes its split and produces the intermediate data
+/// which is evenly partitioned among the reduce tasks. Each reduce task fetches its partition from all map tasks
+/// and processes it.
+#[derive(Clone, Debug, Serialize)]
+pub struct JobSpec {
+    /// Job name.
+    pub name: String,
+    /// Input splits.
+    pub splits: Vec<InputSplit>,
+    /// Number of reduce tasks. If zero, the job completes after the map phase.
+    pub reduce_tasks: usize,
+    /// Amount of computations (in flops) per unit of map task input.
+    pub map_flops_per_unit: f64,
+    /// Ratio of map task output size to its input size.
+    pub map_output_ratio: f64,
+    /// Amount of computations (in flops) per unit of reduce task input.
+    pub reduce_flops_per_unit: f64,
+}
+
+impl JobSpec {
+    /// Creates job specification with unit computation costs and map output ratio.
+    pub fn new(name: &str, splits: Vec<InputSplit>, reduce_tasks: usize) -> Self {
+        Self {
+            name: name.to_string(),
+            splits,
+            reduce_tasks,
+            map_flops_per_unit: 1.,
+            map_output_ratio: 1.,
+            reduce_flops_per_unit: 1.,
+        }
+    }
+
+    /// Sets the amount of computations per unit of map task input.
+    pub fn with_map_flops_per_unit(mut self, flops: f64) -> Self {
+        self.map_flops_per_unit = flops;
+        self
+    }
+
+    /// Sets the ratio of map task output size to its input size.
+    pub fn with_map_output_ratio(mut self, ratio: f64) -> Self {
+        self.map_output_ratio = ratio;
+        self
+    }
+
+    /// Sets the amount of computations per unit of reduce task input.
+    pub fn with_reduce_flops_per_unit(mut self, flops: f64) -> Self {
+        self.reduce_flops_per_unit = flops;
+        self
+    }
+
+    /// Returns the total input size.
+    pub fn input_size(&self) -> f64 {
+        self.splits.iter().map(|split| split.size).sum()
+    }
+}
+
+/// Execution statistics of MapReduce job.
+#[derive(Clone, Debug, Serialize)]
+pub struct JobStats {
+    /// Job id.
+    pub id: u64,
+    /// Job name.
+    pub name: String,
+    /// Time of job arrival.
+    pub submit_time: f64,
+    /// Start time of the first job task.
+    pub start_time: Option<f64>,
+    /// Completion time of the last map task.
+    pub map_finish_time: Option<f64>,
+    /// Time when the last reduce task finished fetching its input.
+    pub shuffle_finish_time: Option<f64>,
+    /// Job completion time.
+    pub finish_time: Option<f64>,
+    /// Number of map tasks executed on the hosts storing their input splits.
+    pub local_map_tasks: u32,
+    /// Number of map tasks which read their input splits from another host in the same rack.
+    pub rack_local_map_tasks: u32,
+    /// Number of map tasks which read their input splits from another rack
+    /// (or from another host if the racks are not specified).
+    pub remote_map_tasks: u32,
+    /// Amount of intermediate data transferred over the network during the shuffle.
+    pub shuffled_data: f64,
+    /// Number of task attempts slowed down by the straggler model.
+    pub straggler_tasks: u32,
+    /// Number of launched speculative task attempts.
+    pub speculative_tasks: u32,
+    /// Number of tasks completed by their speculative attempts.
+    pub successful_speculations: u32,
+    /// Number of task attempts killed after the completion of another attempt of the same task.
+    pub killed_attempts: u32,
+}
+
+impl JobStats {
+    pub(crate) fn new(id: u64, name: String, submit_time: f64) -> Self {
+        Self {
+            id,
+            name,
+            submit_time,
+            start_time: None,
+            map_finish_time: None,
+            shuffle_finish_time: None,
+            finish_time: None,
+            local_map_tasks: 0,
+            rack_local_map_tasks: 0,
+            remote_map_tasks: 0,
+            shuffled_data: 0.,
+            straggler_tasks: 0,
+            speculative_tasks: 0,
+            successful_speculations: 0,
+            killed_attempts: 0,
+        }
+    }
+
+    /// Returns whether the job is completed.
+    pub fn is_completed(&self) -> bool {
+        self.finish_time.is_some()
+    }
+
+    /// Returns the job completion time measured from its submission (turnaround time),
+    /// or `None` if the job is not completed yet.
+    pub fn completion_time(&self) -> Option<f64> {
+        self.finish_time.map(|finish| finish - self.submit_time)
+    }
+
+    /// Returns the time the job has waited for the first task to start.
+    pub fn wait_time(&self) -> Option<f64> {
+        self.start_time.map(|start| start - self.submit_time)
+    }
+}
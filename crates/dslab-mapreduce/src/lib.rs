@@ -0,0 +1,19 @@
+#![warn(missing_docs)]
+#![doc = include_str!("../README.md")]
+
+pub mod cluster;
+pub mod events;
+pub mod job;
+pub mod locality;
+pub mod speculation;
+
+pub use cluster::{MapReduceCluster, StragglerModel};
+pub use events::JobCompleted;
+pub use job::{InputSplit, JobSpec, JobStats};
+pub use locality::{
+    DelayScheduling, FifoPolicy, LocalityPolicy, LocalityStats, NodeLocalPolicy, RackLocalPolicy, TaskLocality,
+};
+pub use speculation::{LateDetector, ProgressScoreDetector, RunningTask, StragglerDetector};
+
+#[cfg(test)]
+mod tests;
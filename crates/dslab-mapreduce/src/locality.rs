@@ -0,0 +1,206 @@
+//! Data locality of tasks and locality-aware task placement policies.
+
+use serde::Serialize;
+
+use dslab_core::component::Id;
+
+use crate::events::TaskType;
+
+/// Locality of map task with respect to the host, i.e. the distance between the host and the task input data.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub enum TaskLocality {
+    /// Input split is stored on the host.
+    NodeLocal,
+    /// Input split is stored on another host in the same rack.
+    RackLocal,
+    /// Input split is stored only in other racks (or on other hosts if the racks are not specified).
+    OffRack,
+}
+
+/// Pending task visible to the locality policy.
+#[derive(Clone, Debug)]
+pub struct PendingTask {
+    /// Job id.
+    pub job_id: u64,
+    /// Task type.
+    pub task_type: TaskType,
+    /// Index of task among the job tasks of the same type.
+    pub index: usize,
+    /// Hosts storing the task input split, empty for reduce tasks.
+    pub locations: Vec<Id>,
+    /// Locality of task with respect to the host with free slot, `None` for tasks without input locality
+    /// (reduce tasks).
+    pub locality: Option<TaskLocality>,
+    /// Time since which the task is waiting to be started.
+    pub pending_since: f64,
+}
+
+/// Host with free slot offered to the locality policy.
+#[derive(Clone, Debug)]
+pub struct SlotOffer {
+    /// Host id.
+    pub host: Id,
+    /// Host rack, if specified.
+    pub rack: Option<u32>,
+    /// Number of free slots on the host.
+    pub free_slots: u32,
+}
+
+/// Policy which decides what pending task to run on the free slot of the host.
+///
+/// The cluster offers the free slots to the policy in the order of decreasing number of free slots on the host,
+/// and runs the selected task on the first host for which the policy has selected some task.
+pub trait LocalityPolicy {
+    /// Returns the policy name.
+    fn name(&self) -> &str;
+
+    /// Returns the index of task to run on the offered slot, or `None` to keep the slot free.
+    ///
+    /// The pending tasks are listed in FIFO order and annotated with their locality with respect to the offered host.
+    fn select_task(&self, offer: &SlotOffer, tasks: &[PendingTask], time: f64) -> Option<usize>;
+
+    /// Returns the time when the policy can select some task it has rejected now, if the free slots should be
+    /// offered again at this time regardless of other events in the cluster.
+    fn retry_time(&self, _tasks: &[PendingTask], _time: f64) -> Option<f64> {
+        None
+    }
+}
+
+fn first_with_locality(tasks: &[PendingTask], max_locality: TaskLocality) -> Option<usize> {
+    tasks
+        .iter()
+        .position(|task| task.locality.is_none_or(|locality| locality <= max_locality))
+}
+
+/// Locality-unaware policy which runs the pending tasks in FIFO order.
+pub struct FifoPolicy;
+
+impl LocalityPolicy for FifoPolicy {
+    fn name(&self) -> &str {
+        "Fifo"
+    }
+
+    fn select_task(&self, _offer: &SlotOffer, tasks: &[PendingTask], _time: f64) -> Option<usize> {
+        if tasks.is_empty() {
+            None
+        } else {
+            Some(0)
+        }
+    }
+}
+
+/// Prefers the first node-local task, otherwise runs the first pending task.
+pub struct NodeLocalPolicy;
+
+impl LocalityPolicy for NodeLocalPolicy {
+    fn name(&self) -> &str {
+        "NodeLocal"
+    }
+
+    fn select_task(&self, offer: &SlotOffer, tasks: &[PendingTask], time: f64) -> Option<usize> {
+        first_with_locality(tasks, TaskLocality::NodeLocal).or_else(|| FifoPolicy.select_task(offer, tasks, time))
+    }
+}
+
+/// Prefers the first node-local task, then the first rack-local task, otherwise runs the first pending task.
+pub struct RackLocalPolicy;
+
+impl LocalityPolicy for RackLocalPolicy {
+    fn name(&self) -> &str {
+        "RackLocal"
+    }
+
+    fn select_task(&self, offer: &SlotOffer, tasks: &[PendingTask], time: f64) -> Option<usize> {
+        first_with_locality(tasks, TaskLocality::NodeLocal)
+            .or_else(|| first_with_locality(tasks, TaskLocality::RackLocal))
+            .or_else(|| FifoPolicy.select_task(offer, tasks, time))
+    }
+}
+
+/// Delay scheduling: the task waits for a node-local slot for at most `node_delay`, then for a rack-local slot
+/// for at most `rack_delay`, after which it can run on any host.
+///
+/// In contrast to the original algorithm, where the delays are tracked per job, here each task has its own delays
+/// counted from the time it became pending.
+pub struct DelayScheduling {
+    /// Maximum time to wait for a node-local slot.
+    pub node_delay: f64,
+    /// Maximum time to wait for a rack-local slot after the node delay is expired.
+    pub rack_delay: f64,
+}
+
+impl DelayScheduling {
+    /// Creates policy with given delays.
+    pub fn new(node_delay: f64, rack_delay: f64) -> Self {
+        Self { node_delay, rack_delay }
+    }
+
+    fn allowed_locality(&self, task: &PendingTask, time: f64) -> TaskLocality {
+        let waited = time - task.pending_since;
+        if waited < self.node_delay {
+            TaskLocality::NodeLocal
+        } else if waited < self.node_delay + self.rack_delay {
+            TaskLocality::RackLocal
+        } else {
+            TaskLocality::OffRack
+        }
+    }
+}
+
+impl LocalityPolicy for DelayScheduling {
+    fn name(&self) -> &str {
+        "DelayScheduling"
+    }
+
+    fn select_task(&self, _offer: &SlotOffer, tasks: &[PendingTask], time: f64) -> Option<usize> {
+        [TaskLocality::NodeLocal, TaskLocality::RackLocal, TaskLocality::OffRack]
+            .into_iter()
+            .find_map(|level| {
+                tasks.iter().position(|task| match task.locality {
+                    None => true,
+                    Some(locality) => locality <= level && level <= self.allowed_locality(task, time),
+                })
+            })
+    }
+
+    fn retry_time(&self, tasks: &[PendingTask], time: f64) -> Option<f64> {
+        tasks
+            .iter()
+            .flat_map(|task| {
+                [
+                    task.pending_since + self.node_delay,
+                    task.pending_since + self.node_delay + self.rack_delay,
+                ]
+            })
+            .filter(|retry| *retry > time)
+            .min_by(|a, b| a.total_cmp(b))
+    }
+}
+
+/// Locality statistics of map tasks.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct LocalityStats {
+    /// Number of node-local map tasks.
+    pub node_local: u32,
+    /// Number of rack-local map tasks.
+    pub rack_local: u32,
+    /// Number of off-rack map tasks.
+    pub off_rack: u32,
+}
+
+impl LocalityStats {
+    /// Returns the total number of started map tasks.
+    pub fn total(&self) -> u32 {
+        self.node_local + self.rack_local + self.off_rack
+    }
+
+    /// Returns the fraction of node-local map tasks (locality hit rate).
+    pub fn node_local_ratio(&self) -> f64 {
+        self.node_local as f64 / self.total().max(1) as f64
+    }
+
+    /// Returns the fraction of map tasks which read their input within the rack (node-local or rack-local).
+    pub fn rack_local_ratio(&self) -> f64 {
+        (self.node_local + self.rack_local) as f64 / self.total().max(1) as f64
+    }
+}
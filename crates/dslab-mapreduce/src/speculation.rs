@@ -0,0 +1,162 @@
+//! Detection of straggler tasks for speculative execution.
+
+use dslab_core::component::Id;
+
+use crate::events::TaskType;
+
+/// Running task visible to the straggler detector.
+#[derive(Clone, Debug)]
+pub struct RunningTask {
+    /// Job id.
+    pub job_id: u64,
+    /// Task type.
+    pub task_type: TaskType,
+    /// Index of task among the job tasks of the same type.
+    pub index: usize,
+    /// Host running the original task attempt.
+    pub host: Id,
+    /// Time elapsed since the start of the original task attempt.
+    pub elapsed: f64,
+    /// Progress of the original task attempt in `[0, 1]`.
+    ///
+    /// The progress of task without input transfers is the fraction of completed computations. Otherwise the first
+    /// half of progress corresponds to the fraction of completed input transfers and the second half to the fraction
+    /// of completed computations.
+    pub progress: f64,
+    /// Average progress of the job tasks of the same type, where the completed tasks have progress 1
+    /// and the pending tasks have progress 0.
+    pub job_progress: f64,
+    /// Whether the task already has a speculative attempt.
+    pub speculated: bool,
+}
+
+impl RunningTask {
+    /// Returns the progress rate of the task, i.e. the progress per unit of time.
+    pub fn progress_rate(&self) -> f64 {
+        if self.elapsed > 0. {
+            self.progress / self.elapsed
+        } else {
+            0.
+        }
+    }
+
+    /// Returns the estimated time left until the task completion assuming the constant progress rate.
+    pub fn estimated_time_left(&self) -> f64 {
+        let rate = self.progress_rate();
+        if rate > 0. {
+            (1. - self.progress) / rate
+        } else {
+            f64::INFINITY
+        }
+    }
+}
+
+/// Detector of straggler tasks, which are periodically checked by the cluster if the speculative execution is
+/// enabled via [`MapReduceCluster::set_speculation`](crate::MapReduceCluster::set_speculation).
+///
+/// For each selected task the cluster launches a speculative attempt on another host with a free slot.
+/// The task is completed by its first finished attempt, and the other attempt is killed.
+pub trait StragglerDetector {
+    /// Returns the detector name.
+    fn name(&self) -> &str;
+
+    /// Returns the indices of tasks to launch the speculative attempts for, in the order of priority.
+    ///
+    /// The running tasks are ordered by job id, task type and index. The cluster calls the detector only when there are
+    /// no pending tasks and some slots are free.
+    fn select_stragglers(&self, tasks: &[RunningTask], time: f64) -> Vec<usize>;
+}
+
+/// Detector used in Hadoop: the task is a straggler if its progress is less than the average progress of
+/// the job tasks of the same type minus the threshold, and the task runs for at least `min_runtime`.
+pub struct ProgressScoreDetector {
+    /// Progress difference from the average progress (0.2 in Hadoop).
+    pub threshold: f64,
+    /// Minimum running time of the task (60 seconds in Hadoop).
+    pub min_runtime: f64,
+}
+
+impl ProgressScoreDetector {
+    /// Creates detector with given parameters.
+    pub fn new(threshold: f64, min_runtime: f64) -> Self {
+        Self { threshold, min_runtime }
+    }
+}
+
+impl StragglerDetector for ProgressScoreDetector {
+    fn name(&self) -> &str {
+        "ProgressScore"
+    }
+
+    fn select_stragglers(&self, tasks: &[RunningTask], _time: f64) -> Vec<usize> {
+        let mut stragglers = (0..tasks.len())
+            .filter(|pos| {
+                let task = &tasks[*pos];
+                !task.speculated
+                    && task.elapsed >= self.min_runtime
+                    && task.progress < task.job_progress - self.threshold
+            })
+            .collect::<Vec<_>>();
+        stragglers.sort_by(|a, b| tasks[*a].progress.total_cmp(&tasks[*b].progress));
+        stragglers
+    }
+}
+
+/// LATE (Longest Approximate Time to End) detector from "Improving MapReduce Performance in Heterogeneous
+/// Environments" (Zaharia et al., OSDI 2008).
+///
+/// The task is a straggler if its progress rate is below the `slow_task_quantile` of progress rates of all running
+/// tasks. The stragglers are prioritized by their estimated time left, and the number of simultaneously running
+/// speculative attempts is limited by `max_speculative`.
+pub struct LateDetector {
+    /// Quantile of progress rates below which the task is considered slow (0.25 in the paper).
+    pub slow_task_quantile: f64,
+    /// Minimum running time of the task.
+    pub min_runtime: f64,
+    /// Maximum number of running speculative attempts (10% of slots in the paper).
+    pub max_speculative: usize,
+}
+
+impl LateDetector {
+    /// Creates detector with given parameters.
+    pub fn new(slow_task_quantile: f64, min_runtime: f64, max_speculative: usize) -> Self {
+        assert!(
+            (0. ..=1.).contains(&slow_task_quantile),
+            "slow task quantile should be in [0, 1]"
+        );
+        Self {
+            slow_task_quantile,
+            min_runtime,
+            max_speculative,
+        }
+    }
+}
+
+impl StragglerDetector for LateDetector {
+    fn name(&self) -> &str {
+        "LATE"
+    }
+
+    fn select_stragglers(&self, tasks: &[RunningTask], _time: f64) -> Vec<usize> {
+        let speculated = tasks.iter().filter(|task| task.speculated).count();
+        if tasks.is_empty() || speculated >= self.max_speculative {
+            return Vec::new();
+        }
+        let mut rates = tasks.iter().map(|task| task.progress_rate()).collect::<Vec<_>>();
+        rates.sort_by(|a, b| a.total_cmp(b));
+        let slow_rate = rates[((rates.len() - 1) as f64 * self.slow_task_quantile) as usize];
+        let mut stragglers = (0..tasks.len())
+            .filter(|pos| {
+                let task = &tasks[*pos];
+                !task.speculated && task.elapsed >= self.min_runtime && task.progress_rate() <= slow_rate
+            })
+            .collect::<Vec<_>>();
+        stragglers.sort_by(|a, b| {
+            tasks[*b]
+                .estimated_time_left()
+                .total_cmp(&tasks[*a].estimated_time_left())
+        });
+        stragglers.truncate(self.max_speculative - speculated);
+        stragglers
+    }
+}
@@ -0,0 +1,346 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use sugars::{boxed, rc, refcell};
+
+use dslab_core::component::Id;
+use dslab_core::simulation::Simulation;
+use dslab_core::{cast, Event, EventHandler};
+use dslab_network::models::ConstantBandwidthNetworkModel;
+use dslab_network::Network;
+
+use crate::cluster::{MapReduceCluster, StragglerModel};
+use crate::events::JobCompleted;
+use crate::job::{InputSplit, JobSpec};
+use crate::locality::{DelayScheduling, FifoPolicy, LocalityPolicy, NodeLocalPolicy, RackLocalPolicy};
+use crate::speculation::{LateDetector, ProgressScoreDetector};
+
+///////////////////////////////////////////////////////////////////////////////
+
+const SEED: u64 = 16;
+const BANDWIDTH: f64 = 10.;
+const HOST_SPEED: f64 = 100.;
+
+struct Setup {
+    sim: Simulation,
+    cluster: Rc<RefCell<MapReduceCluster>>,
+    hosts: Vec<Id>,
+}
+
+// Creates cluster where each host is located on a separate network node with zero latency.
+fn make_cluster(host_count: usize, slots: u32) -> Setup {
+    make_cluster_with_speeds(&vec![HOST_SPEED; host_count], slots)
+}
+
+fn make_cluster_with_speeds(speeds: &[f64], slots: u32) -> Setup {
+    let mut sim = Simulation::new(SEED);
+    let mut network = Network::new(
+        boxed!(ConstantBandwidthNetworkModel::new(BANDWIDTH, 0.)),
+        sim.create_context("net"),
+    );
+    let mut hosts = Vec::new();
+    for i in 0..speeds.len() {
+        let name = format!("host{}", i);
+        network.add_node(&name, boxed!(ConstantBandwidthNetworkModel::new(1e9, 0.)));
+        let host_id = sim.create_context(&name).id();
+        network.set_location(host_id, &name);
+        hosts.push(host_id);
+    }
+    let network = rc!(refcell!(network));
+    sim.add_handler("net", network.clone());
+    let mut cluster = MapReduceCluster::new(network, sim.create_context("cluster"));
+    for (&host, &speed) in hosts.iter().zip(speeds) {
+        cluster.add_host(host, slots, speed);
+    }
+    let cluster = rc!(refcell!(cluster));
+    sim.add_handler("cluster", cluster.clone());
+    Setup { sim, cluster, hosts }
+}
+
+#[derive(Default)]
+struct Listener {
+    completed: Vec<(u64, f64)>,
+}
+
+impl EventHandler for Listener {
+    fn on(&mut self, event: Event) {
+        cast!(match event.data {
+            JobCompleted { job_id, .. } => {
+                self.completed.push((job_id, event.time));
+            }
+        })
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn local_map_only_job() {
+    let mut setup = make_cluster(2, 1);
+    let splits = vec![
+        InputSplit::new(100., vec![setup.hosts[0]]),
+        InputSplit::new(200., vec![setup.hosts[1]]),
+    ];
+    let job_id = setup
+        .cluster
+        .borrow_mut()
+        .submit_job(JobSpec::new("job", splits, 0), 1.);
+    setup.sim.step_until_no_events();
+
+    let cluster = setup.cluster.borrow();
+    let stats = cluster.job_stats(job_id).unwrap();
+    assert_eq!(stats.submit_time, 1.);
+    assert_eq!(stats.start_time, Some(1.));
+    assert_eq!(stats.local_map_tasks, 2);
+    assert_eq!(stats.remote_map_tasks, 0);
+    // 200 flops on host with speed 100
+    assert_eq!(stats.map_finish_time, Some(3.));
+    assert_eq!(stats.finish_time, Some(3.));
+    assert_eq!(stats.completion_time(), Some(2.));
+    assert_eq!(stats.shuffled_data, 0.);
+}
+
+#[test]
+fn remote_map_task() {
+    let mut setup = make_cluster(2, 2);
+    // both splits are stored on host0 which has only 2 slots, so the third task runs on host1
+    let splits = vec![
+        InputSplit::new(100., vec![setup.hosts[0]]),
+        InputSplit::new(100., vec![setup.hosts[0]]),
+        InputSplit::new(100., vec![setup.hosts[0]]),
+    ];
+    let job_id = setup
+        .cluster
+        .borrow_mut()
+        .submit_job(JobSpec::new("job", splits, 0), 0.);
+    setup.sim.step_until_no_events();
+
+    let cluster = setup.cluster.borrow();
+    let stats = cluster.job_stats(job_id).unwrap();
+    assert_eq!(stats.local_map_tasks, 2);
+    assert_eq!(stats.remote_map_tasks, 1);
+    // reading 100 units with bandwidth 10 and processing them with speed 100
+    assert_eq!(stats.finish_time, Some(11.));
+}
+
+#[test]
+fn shuffle_and_reduce() {
+    let mut setup = make_cluster(2, 1);
+    let splits = vec![
+        InputSplit::new(100., vec![setup.hosts[0]]),
+        InputSplit::new(100., vec![setup.hosts[1]]),
+    ];
+    let spec = JobSpec::new("job", splits, 2)
+        .with_map_output_ratio(0.5)
+        .with_reduce_flops_per_unit(2.);
+    let job_id = setup.cluster.borrow_mut().submit_job(spec, 0.);
+    setup.sim.step_until_no_events();
+
+    let cluster = setup.cluster.borrow();
+    let stats = cluster.job_stats(job_id).unwrap();
+    assert_eq!(stats.map_finish_time, Some(1.));
+    // each reducer fetches 25 units from the remote host
+    assert_eq!(stats.shuffled_data, 50.);
+    assert_eq!(stats.shuffle_finish_time, Some(3.5));
+    // each reducer processes 50 units with 2 flops per unit
+    assert_eq!(stats.finish_time, Some(4.5));
+}
+
+#[test]
+fn fifo_jobs_and_listener() {
+    let mut setup = make_cluster(1, 1);
+    let listener = rc!(refcell!(Listener::default()));
+    let listener_id = setup.sim.add_handler("listener", listener.clone());
+    setup.cluster.borrow_mut().set_listener(listener_id);
+    let host = setup.hosts[0];
+    let first = setup
+        .cluster
+        .borrow_mut()
+        .submit_job(JobSpec::new("first", vec![InputSplit::new(100., vec![host])], 1), 0.);
+    let second = setup
+        .cluster
+        .borrow_mut()
+        .submit_job(JobSpec::new("second", vec![InputSplit::new(100., vec![host])], 1), 0.5);
+    setup.sim.step_until_no_events();
+
+    let cluster = setup.cluster.borrow();
+    let first_stats = cluster.job_stats(first).unwrap();
+    let second_stats = cluster.job_stats(second).unwrap();
+    assert_eq!(first_stats.map_finish_time, Some(1.));
+    // the second job map task runs before the first job reduce task
+    assert_eq!(second_stats.start_time, Some(1.));
+    assert_eq!(first_stats.finish_time, Some(3.));
+    assert_eq!(second_stats.finish_time, Some(4.));
+    assert_eq!(second_stats.wait_time(), Some(0.5));
+    assert_eq!(cluster.mean_completion_time(), Some(3.25));
+    assert_eq!(listener.borrow().completed, vec![(first, 3.), (second, 4.)]);
+}
+
+#[test]
+fn stragglers() {
+    let mut setup = make_cluster(4, 2);
+    setup
+        .cluster
+        .borrow_mut()
+        .set_straggler_model(StragglerModel::new(0.5, 2., 4.));
+    let splits = (0..8)
+        .map(|i| InputSplit::new(100., vec![setup.hosts[i % 4]]))
+        .collect();
+    let job_id = setup
+        .cluster
+        .borrow_mut()
+        .submit_job(JobSpec::new("job", splits, 0), 0.);
+    setup.sim.step_until_no_events();
+
+    let cluster = setup.cluster.borrow();
+    let stats = cluster.job_stats(job_id).unwrap();
+    assert!(stats.straggler_tasks > 0 && stats.straggler_tasks < 8);
+    // job completion is determined by the slowest straggler
+    assert!(stats.finish_time.unwrap() >= 2.);
+    assert!(stats.finish_time.unwrap() <= 4.);
+    assert_eq!(cluster.all_job_stats().len(), 1);
+}
+
+// Runs job with 4 splits on cluster of 4 hosts with 1 slot, where host3 is in a separate rack.
+fn run_locality_job(policy: Box<dyn LocalityPolicy>) -> (u32, u32, u32, f64) {
+    let mut setup = make_cluster(4, 1);
+    {
+        let mut cluster = setup.cluster.borrow_mut();
+        for (i, host) in setup.hosts.iter().enumerate() {
+            cluster.set_host_rack(*host, if i < 3 { 0 } else { 1 });
+        }
+        cluster.set_locality_policy(policy);
+    }
+    let hosts = &setup.hosts;
+    let chunks = vec![
+        (100, vec![hosts[1]]),
+        (100, vec![hosts[0]]),
+        (100, vec![hosts[3]]),
+        (100, vec![hosts[0]]),
+    ];
+    let job_id = setup
+        .cluster
+        .borrow_mut()
+        .submit_job(JobSpec::new("job", InputSplit::from_chunks(&chunks), 0), 0.);
+    setup.sim.step_until_no_events();
+
+    let cluster = setup.cluster.borrow();
+    let stats = cluster.job_stats(job_id).unwrap();
+    assert_eq!(cluster.locality_stats().total(), 4);
+    (
+        stats.local_map_tasks,
+        stats.rack_local_map_tasks,
+        stats.remote_map_tasks,
+        stats.finish_time.unwrap(),
+    )
+}
+
+#[test]
+fn locality_policies() {
+    // FIFO assigns tasks 0..3 to hosts 0..3
+    assert_eq!(run_locality_job(Box::new(FifoPolicy)), (0, 2, 2, 11.));
+    // host2 has no local tasks and takes the first pending task from another rack
+    assert_eq!(run_locality_job(Box::new(NodeLocalPolicy)), (2, 0, 2, 11.));
+    // host2 takes the rack-local task 3, so host3 can take its local task 2
+    assert_eq!(run_locality_job(Box::new(RackLocalPolicy)), (3, 1, 0, 11.));
+}
+
+#[test]
+fn delay_scheduling() {
+    // host2 waits and task 3 runs locally on host0 after task 1 is completed
+    assert_eq!(run_locality_job(Box::new(DelayScheduling::new(5., 5.))), (4, 0, 0, 2.));
+    // after the node delay is expired, task 1 runs on rack-local host1
+    let mut setup = make_cluster(2, 1);
+    let hosts = setup.hosts.clone();
+    {
+        let mut cluster = setup.cluster.borrow_mut();
+        cluster.set_host_rack(hosts[0], 0);
+        cluster.set_host_rack(hosts[1], 0);
+        cluster.set_locality_policy(Box::new(DelayScheduling::new(0.5, 5.)));
+    }
+    let splits = vec![
+        InputSplit::new(200., vec![hosts[0]]),
+        InputSplit::new(100., vec![hosts[0]]),
+    ];
+    let job_id = setup
+        .cluster
+        .borrow_mut()
+        .submit_job(JobSpec::new("job", splits, 0), 0.);
+    setup.sim.step_until_no_events();
+
+    let cluster = setup.cluster.borrow();
+    let stats = cluster.job_stats(job_id).unwrap();
+    assert_eq!((stats.local_map_tasks, stats.rack_local_map_tasks), (1, 1));
+    // task 1 starts at 0.5 and reads its split in 10 seconds
+    assert_eq!(stats.finish_time, Some(11.5));
+    let locality = cluster.locality_stats();
+    assert_eq!(locality.node_local_ratio(), 0.5);
+    assert_eq!(locality.rack_local_ratio(), 1.);
+}
+
+#[test]
+// Map task 1 runs on the slow host until 10. With speculation it is detected as a straggler after task 0 completes
+// at 1, and its speculative attempt on the fast host completes at 2.
+fn speculative_execution() {
+    let run = |speculation: bool| {
+        let mut setup = make_cluster_with_speeds(&[HOST_SPEED, HOST_SPEED / 10.], 1);
+        if speculation {
+            setup
+                .cluster
+                .borrow_mut()
+                .set_speculation(boxed!(ProgressScoreDetector::new(0.2, 0.)), 1.);
+        }
+        let hosts = setup.hosts.clone();
+        let splits = vec![
+            InputSplit::new(100., vec![hosts[0], hosts[1]]),
+            InputSplit::new(100., vec![hosts[0], hosts[1]]),
+        ];
+        let job_id = setup
+            .cluster
+            .borrow_mut()
+            .submit_job(JobSpec::new("job", splits, 1), 0.);
+        setup.sim.step_until_no_events();
+        let cluster = setup.cluster.borrow();
+        cluster.job_stats(job_id).unwrap().clone()
+    };
+
+    let stats = run(false);
+    assert_eq!(stats.map_finish_time, Some(10.));
+    assert_eq!(stats.speculative_tasks, 0);
+
+    let stats = run(true);
+    assert_eq!(stats.map_finish_time, Some(2.));
+    assert_eq!(stats.speculative_tasks, 1);
+    assert_eq!(stats.successful_speculations, 1);
+    assert_eq!(stats.killed_attempts, 1);
+    // both map outputs are on the fast host running the reduce task
+    assert_eq!(stats.shuffled_data, 0.);
+    assert_eq!(stats.finish_time, Some(4.));
+    assert_eq!(stats.local_map_tasks, 2);
+}
+
+#[test]
+// Map task 2 on the host with half speed is speculated at 1 on host0, which has to read its split
+// in 10 seconds. The original attempt completes first at 2, so the speculative attempt is killed
+// and its input transfer is cancelled.
+fn late_speculation() {
+    let mut setup = make_cluster_with_speeds(&[HOST_SPEED, HOST_SPEED, HOST_SPEED / 2.], 1);
+    setup
+        .cluster
+        .borrow_mut()
+        .set_speculation(boxed!(LateDetector::new(0.25, 0.5, 1)), 1.);
+    let splits = (0..3).map(|i| InputSplit::new(100., vec![setup.hosts[i]])).collect();
+    let job_id = setup
+        .cluster
+        .borrow_mut()
+        .submit_job(JobSpec::new("job", splits, 0), 0.);
+    setup.sim.step_until_no_events();
+
+    let cluster = setup.cluster.borrow();
+    let stats = cluster.job_stats(job_id).unwrap();
+    assert_eq!(stats.finish_time, Some(2.));
+    assert_eq!(stats.speculative_tasks, 1);
+    assert_eq!(stats.successful_speculations, 0);
+    assert_eq!(stats.killed_attempts, 1);
+    assert_eq!(setup.sim.time(), 2.);
+}
@@ -0,0 +1,78 @@
+//! Events used by master and workers.
+
+use serde::Serialize;
+
+use dslab_core::component::Id;
+
+/// Worker registration, sent by worker to master on start and after recovery from crash.
+#[derive(Clone, Serialize)]
+pub struct WorkerRegister {
+    /// Worker speed (in flop/s).
+    pub speed: f64,
+    /// Number of tasks executed by worker concurrently.
+    pub slots: u32,
+}
+
+/// Periodic heartbeat, sent by worker to master while the worker has tasks.
+#[derive(Clone, Serialize)]
+pub struct Heartbeat {}
+
+/// Task assignment, sent by master to worker.
+#[derive(Clone, Serialize)]
+pub struct TaskAssigned {
+    /// Task id.
+    pub task_id: u64,
+    /// Amount of task computations (in flops).
+    pub flops: f64,
+    /// Size of task input downloaded from master.
+    pub input_size: f64,
+    /// Size of task output uploaded to master.
+    pub output_size: f64,
+}
+
+/// Task completion, sent by worker to master after uploading the task output.
+#[derive(Clone, Serialize)]
+pub struct TaskCompleted {
+    /// Task id.
+    pub task_id: u64,
+}
+
+/// Request to give up to `count` queued tasks to an idle worker, sent by master to worker.
+#[derive(Clone, Serialize)]
+pub struct StealTasks {
+    /// Maximum number of given tasks.
+    pub count: usize,
+    /// Idle worker which receives the tasks.
+    pub thief: Id,
+}
+
+/// Tasks removed from the worker queue in response to [`StealTasks`], sent by worker to master.
+#[derive(Clone, Serialize)]
+pub struct TasksStolen {
+    /// Ids of removed tasks (possibly empty).
+    pub task_ids: Vec<u64>,
+    /// Idle worker which receives the tasks.
+    pub thief: Id,
+}
+
+/// Periodic check of worker heartbeats (internal event of master).
+#[derive(Clone, Serialize)]
+pub struct CheckWorkers {}
+
+/// Completion of task computations (internal event of worker).
+#[derive(Clone, Serialize)]
+pub struct TaskComputed {
+    /// Task id.
+    pub task_id: u64,
+}
+
+/// Timer of heartbeat sending (internal event of worker).
+#[derive(Clone, Serialize)]
+pub struct SendHeartbeat {}
+
+/// Completion of all submitted tasks, sent to the master listener.
+#[derive(Clone, Serialize)]
+pub struct AllTasksCompleted {
+    /// Number of completed tasks.
+    pub completed: u64,
+}
@@ -0,0 +1,15 @@
+#![warn(missing_docs)]
+#![doc = include_str!("../README.md")]
+
+pub mod events;
+pub mod master;
+pub mod task;
+pub mod worker;
+
+pub use events::AllTasksCompleted;
+pub use master::{DispatchPolicy, Master, MasterStats, WorkerStats};
+pub use task::{TaskSpec, TaskStats};
+pub use worker::Worker;
+
+#[cfg(test)]
+mod tests;
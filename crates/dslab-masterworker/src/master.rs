@@ -0,0 +1,569 @@
+//! Master distributing tasks among workers.
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, VecDeque};
+use std::rc::Rc;
+
+use serde::Serialize;
+
+use dslab_core::component::Id;
+use dslab_core::context::SimulationContext;
+use dslab_core::event::Event;
+use dslab_core::handler::EventHandler;
+use dslab_core::{cast, log_debug, log_info, log_warn};
+use dslab_network::Network;
+
+use crate::events::{
+    AllTasksCompleted, CheckWorkers, Heartbeat, StealTasks, TaskAssigned, TaskCompleted, TasksStolen, WorkerRegister,
+};
+use crate::task::{TaskSpec, TaskStats};
+
+/// Policy of task distribution among the workers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub enum DispatchPolicy {
+    /// Tasks are kept by master and assigned to the workers with free slots (self-scheduling).
+    /// The worker with the most free slots is preferred, then the fastest one.
+    OnDemand,
+    /// Tasks are assigned to the workers in round-robin order upon submission and queued by the workers.
+    /// This policy does not balance the load between heterogeneous workers, unless combined with work stealing.
+    Static,
+}
+
+/// Statistics of worker collected by master.
+#[derive(Clone, Debug, Serialize)]
+pub struct WorkerStats {
+    /// Worker id.
+    pub id: Id,
+    /// Number of tasks completed by the worker.
+    pub completed_tasks: u64,
+    /// Number of detected worker failures, including the restarts after crash.
+    pub failures: u32,
+    /// Number of tasks stolen from the worker queue by other workers.
+    pub stolen_from: u64,
+    /// Number of tasks stolen by the worker from other workers.
+    pub stolen_by: u64,
+}
+
+/// Overall statistics of master.
+#[derive(Clone, Debug, Serialize)]
+pub struct MasterStats {
+    /// Number of submitted tasks.
+    pub submitted: u64,
+    /// Number of completed tasks.
+    pub completed: u64,
+    /// Number of task reassignments after worker failures.
+    pub reassigned: u64,
+    /// Number of tasks moved between the workers by work stealing.
+    pub stolen: u64,
+    /// Number of detected worker failures.
+    pub worker_failures: u32,
+    /// Time between the first task submission and the last task completion,
+    /// or `None` if some task is not completed.
+    pub makespan: Option<f64>,
+    /// Mean task completion time measured from the task submission, or `None` if there are no completed tasks.
+    pub mean_completion_time: Option<f64>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum WorkerState {
+    Alive,
+    Failed,
+}
+
+struct WorkerInfo {
+    speed: f64,
+    slots: u32,
+    state: WorkerState,
+    assigned: Vec<u64>,
+    last_contact: f64,
+    stealing: bool,
+    stats: WorkerStats,
+}
+
+impl WorkerInfo {
+    fn free_slots(&self) -> usize {
+        (self.slots as usize).saturating_sub(self.assigned.len())
+    }
+
+    fn queued_tasks(&self) -> usize {
+        self.assigned.len().saturating_sub(self.slots as usize)
+    }
+}
+
+struct TaskInfo {
+    spec: TaskSpec,
+    stats: TaskStats,
+}
+
+/// Component modeling a master which distributes independent tasks among the [workers](crate::Worker).
+///
+/// The workers register at the master on start. The submitted tasks are assigned to the registered workers according
+/// to the [dispatch policy](DispatchPolicy), [`DispatchPolicy::OnDemand`] by default. If work stealing is enabled,
+/// each worker which completed all its tasks steals half of the queued tasks from the most loaded worker.
+///
+/// The workers with assigned tasks periodically send heartbeats to the master. If failure detection is enabled,
+/// a worker which has not contacted the master for longer than the failure timeout is considered failed,
+/// and its tasks are reassigned to other workers. The tasks of a worker which registers again after crash
+/// are reassigned immediately. If a task is completed by several workers (e.g. by a worker falsely considered failed),
+/// the first completion is accepted.
+///
+/// The master and the workers must be registered in the network via [`Network::set_location`].
+pub struct Master {
+    network: Rc<RefCell<Network>>,
+    workers: BTreeMap<Id, WorkerInfo>,
+    tasks: BTreeMap<u64, TaskInfo>,
+    queue: VecDeque<u64>,
+    next_task_id: u64,
+    policy: DispatchPolicy,
+    work_stealing: bool,
+    failure_timeout: Option<f64>,
+    check_scheduled: bool,
+    next_worker: usize,
+    completed: u64,
+    reassigned: u64,
+    stolen: u64,
+    worker_failures: u32,
+    first_submit_time: Option<f64>,
+    last_finish_time: Option<f64>,
+    listener: Option<Id>,
+    ctx: SimulationContext,
+}
+
+impl Master {
+    /// Creates master which uses the specified network for communication with workers.
+    pub fn new(network: Rc<RefCell<Network>>, ctx: SimulationContext) -> Self {
+        Self {
+            network,
+            workers: BTreeMap::new(),
+            tasks: BTreeMap::new(),
+            queue: VecDeque::new(),
+            next_task_id: 0,
+            policy: DispatchPolicy::OnDemand,
+            work_stealing: false,
+            failure_timeout: None,
+            check_scheduled: false,
+            next_worker: 0,
+            completed: 0,
+            reassigned: 0,
+            stolen: 0,
+            worker_failures: 0,
+            first_submit_time: None,
+            last_finish_time: None,
+            listener: None,
+            ctx,
+        }
+    }
+
+    /// Returns component id.
+    pub fn id(&self) -> Id {
+        self.ctx.id()
+    }
+
+    /// Sets the dispatch policy.
+    pub fn set_dispatch_policy(&mut self, policy: DispatchPolicy) {
+        self.policy = policy;
+    }
+
+    /// Enables or disables work stealing by idle workers.
+    pub fn set_work_stealing(&mut self, enabled: bool) {
+        self.work_stealing = enabled;
+    }
+
+    /// Enables detection of worker failures with the specified timeout, which should exceed
+    /// the heartbeat interval of the workers.
+    pub fn set_failure_timeout(&mut self, timeout: f64) {
+        assert!(timeout > 0., "failure timeout should be positive");
+        self.failure_timeout = Some(timeout);
+    }
+
+    /// Sets the component which receives [`AllTasksCompleted`] events.
+    pub fn set_listener(&mut self, id: Id) {
+        self.listener = Some(id);
+    }
+
+    /// Submits task and returns the task id.
+    pub fn submit_task(&mut self, spec: TaskSpec) -> u64 {
+        let task_id = self.next_task_id;
+        self.next_task_id += 1;
+        let time = self.ctx.time();
+        self.first_submit_time.get_or_insert(time);
+        self.tasks.insert(
+            task_id,
+            TaskInfo {
+                spec,
+                stats: TaskStats::new(task_id, time),
+            },
+        );
+        self.queue.push_back(task_id);
+        self.dispatch();
+        task_id
+    }
+
+    /// Submits several tasks and returns their ids.
+    pub fn submit_tasks(&mut self, specs: Vec<TaskSpec>) -> Vec<u64> {
+        specs.into_iter().map(|spec| self.submit_task(spec)).collect()
+    }
+
+    /// Returns statistics of the specified task.
+    pub fn task_stats(&self, task_id: u64) -> Option<&TaskStats> {
+        self.tasks.get(&task_id).map(|task| &task.stats)
+    }
+
+    /// Returns statistics of all submitted tasks ordered by task id.
+    pub fn all_task_stats(&self) -> Vec<TaskStats> {
+        self.tasks.values().map(|task| task.stats.clone()).collect()
+    }
+
+    /// Returns statistics of registered workers ordered by worker id.
+    pub fn worker_stats(&self) -> Vec<WorkerStats> {
+        self.workers.values().map(|worker| worker.stats.clone()).collect()
+    }
+
+    /// Returns the number of tasks which are not completed yet.
+    pub fn pending_tasks(&self) -> u64 {
+        self.tasks.len() as u64 - self.completed
+    }
+
+    /// Returns overall statistics.
+    pub fn stats(&self) -> MasterStats {
+        let times = self
+            .tasks
+            .values()
+            .filter_map(|task| task.stats.completion_time())
+            .collect::<Vec<_>>();
+        let makespan = if self.pending_tasks() == 0 {
+            self.first_submit_time.zip(self.last_finish_time).map(|(s, f)| f - s)
+        } else {
+            None
+        };
+        MasterStats {
+            submitted: self.tasks.len() as u64,
+            completed: self.completed,
+            reassigned: self.reassigned,
+            stolen: self.stolen,
+            worker_failures: self.worker_failures,
+            makespan,
+            mean_completion_time: if times.is_empty() {
+                None
+            } else {
+                Some(times.iter().sum::<f64>() / times.len() as f64)
+            },
+        }
+    }
+
+    fn on_worker_register(&mut self, worker_id: Id, speed: f64, slots: u32) {
+        let time = self.ctx.time();
+        log_debug!(
+            self.ctx,
+            "registered worker {} with speed {} and {} slots",
+            self.ctx.lookup_name(worker_id),
+            speed,
+            slots
+        );
+        match self.workers.get_mut(&worker_id) {
+            Some(worker) => {
+                // the worker was restarted and has lost its tasks
+                if worker.state == WorkerState::Alive && !worker.assigned.is_empty() {
+                    self.on_worker_failed(worker_id);
+                }
+                let worker = self.workers.get_mut(&worker_id).unwrap();
+                worker.state = WorkerState::Alive;
+                worker.speed = speed;
+                worker.slots = slots;
+                worker.last_contact = time;
+                worker.stealing = false;
+            }
+            None => {
+                self.workers.insert(
+                    worker_id,
+                    WorkerInfo {
+                        speed,
+                        slots,
+                        state: WorkerState::Alive,
+                        assigned: Vec::new(),
+                        last_contact: time,
+                        stealing: false,
+                        stats: WorkerStats {
+                            id: worker_id,
+                            completed_tasks: 0,
+                            failures: 0,
+                            stolen_from: 0,
+                            stolen_by: 0,
+                        },
+                    },
+                );
+            }
+        }
+        self.dispatch();
+    }
+
+    fn on_heartbeat(&mut self, worker_id: Id) {
+        if let Some(worker) = self.workers.get_mut(&worker_id) {
+            worker.last_contact = self.ctx.time();
+            if worker.state == WorkerState::Failed {
+                log_info!(self.ctx, "worker {} is alive again", self.ctx.lookup_name(worker_id));
+                worker.state = WorkerState::Alive;
+                self.dispatch();
+            }
+        }
+    }
+
+    fn on_task_completed(&mut self, worker_id: Id, task_id: u64) {
+        let time = self.ctx.time();
+        if let Some(worker) = self.workers.get_mut(&worker_id) {
+            worker.last_contact = time;
+        }
+        let task = match self.tasks.get_mut(&task_id) {
+            Some(task) if !task.stats.is_completed() => task,
+            _ => return,
+        };
+        log_debug!(
+            self.ctx,
+            "task {} is completed by worker {}",
+            task_id,
+            self.ctx.lookup_name(worker_id)
+        );
+        // the task could be reassigned to another worker or returned to the queue
+        if let Some(assignee) = task.stats.worker {
+            if let Some(worker) = self.workers.get_mut(&assignee) {
+                worker.assigned.retain(|id| *id != task_id);
+            }
+        }
+        self.queue.retain(|id| *id != task_id);
+        task.stats.finish_time = Some(time);
+        task.stats.worker = Some(worker_id);
+        if let Some(worker) = self.workers.get_mut(&worker_id) {
+            worker.stats.completed_tasks += 1;
+        }
+        self.completed += 1;
+        self.last_finish_time = Some(time);
+        if self.pending_tasks() == 0 {
+            log_info!(self.ctx, "all {} tasks are completed", self.completed);
+            if let Some(listener) = self.listener {
+                self.ctx.emit_now(
+                    AllTasksCompleted {
+                        completed: self.completed,
+                    },
+                    listener,
+                );
+            }
+        }
+        self.dispatch();
+    }
+
+    fn on_tasks_stolen(&mut self, victim_id: Id, task_ids: Vec<u64>, thief_id: Id) {
+        if let Some(thief) = self.workers.get_mut(&thief_id) {
+            thief.stealing = false;
+        }
+        let mut stolen = Vec::new();
+        for task_id in task_ids {
+            let task = &self.tasks[&task_id];
+            if task.stats.is_completed() || task.stats.worker != Some(victim_id) {
+                continue;
+            }
+            if let Some(victim) = self.workers.get_mut(&victim_id) {
+                victim.assigned.retain(|id| *id != task_id);
+                victim.stats.stolen_from += 1;
+            }
+            stolen.push(task_id);
+        }
+        log_debug!(
+            self.ctx,
+            "worker {} stole {} tasks from worker {}",
+            self.ctx.lookup_name(thief_id),
+            stolen.len(),
+            self.ctx.lookup_name(victim_id)
+        );
+        self.stolen += stolen.len() as u64;
+        let thief_alive = self.workers[&thief_id].state == WorkerState::Alive;
+        for task_id in stolen.into_iter().rev() {
+            if thief_alive {
+                self.workers.get_mut(&thief_id).unwrap().stats.stolen_by += 1;
+                self.assign_task(task_id, thief_id);
+            } else {
+                self.tasks.get_mut(&task_id).unwrap().stats.worker = None;
+                self.queue.push_front(task_id);
+            }
+        }
+        self.dispatch();
+    }
+
+    fn on_check_workers(&mut self) {
+        self.check_scheduled = false;
+        let timeout = match self.failure_timeout {
+            Some(timeout) => timeout,
+            None => return,
+        };
+        let time = self.ctx.time();
+        let failed = self
+            .workers
+            .iter()
+            .filter(|(_, w)| w.state == WorkerState::Alive && !w.assigned.is_empty())
+            .filter(|(_, w)| time - w.last_contact > timeout)
+            .map(|(id, _)| *id)
+            .collect::<Vec<_>>();
+        for worker_id in failed {
+            log_warn!(
+                self.ctx,
+                "worker {} is considered failed",
+                self.ctx.lookup_name(worker_id)
+            );
+            self.on_worker_failed(worker_id);
+            self.workers.get_mut(&worker_id).unwrap().state = WorkerState::Failed;
+        }
+        self.dispatch();
+        self.schedule_check();
+    }
+
+    // Returns the tasks of failed worker to the queue preserving their order.
+    fn on_worker_failed(&mut self, worker_id: Id) {
+        let worker = self.workers.get_mut(&worker_id).unwrap();
+        worker.stats.failures += 1;
+        worker.stealing = false;
+        let tasks = std::mem::take(&mut worker.assigned);
+        self.worker_failures += 1;
+        self.reassigned += tasks.len() as u64;
+        for task_id in tasks.into_iter().rev() {
+            self.tasks.get_mut(&task_id).unwrap().stats.worker = None;
+            self.queue.push_front(task_id);
+        }
+    }
+
+    fn schedule_check(&mut self) {
+        if let Some(timeout) = self.failure_timeout {
+            let has_assigned_tasks = self
+                .workers
+                .values()
+                .any(|w| w.state == WorkerState::Alive && !w.assigned.is_empty());
+            if !self.check_scheduled && has_assigned_tasks {
+                self.check_scheduled = true;
+                self.ctx.emit_self(CheckWorkers {}, timeout);
+            }
+        }
+    }
+
+    fn dispatch(&mut self) {
+        let alive = self
+            .workers
+            .iter()
+            .filter(|(_, w)| w.state == WorkerState::Alive)
+            .map(|(id, _)| *id)
+            .collect::<Vec<_>>();
+        if alive.is_empty() {
+            return;
+        }
+        match self.policy {
+            DispatchPolicy::OnDemand => {
+                while !self.queue.is_empty() {
+                    let best = alive
+                        .iter()
+                        .map(|id| (*id, &self.workers[id]))
+                        .filter(|(_, w)| w.free_slots() > 0)
+                        .max_by(|(id1, w1), (id2, w2)| {
+                            w1.free_slots()
+                                .cmp(&w2.free_slots())
+                                .then(w1.speed.total_cmp(&w2.speed))
+                                .then(id2.cmp(id1))
+                        })
+                        .map(|(id, _)| id);
+                    match best {
+                        Some(worker_id) => {
+                            let task_id = self.queue.pop_front().unwrap();
+                            self.assign_task(task_id, worker_id);
+                        }
+                        None => break,
+                    }
+                }
+            }
+            DispatchPolicy::Static => {
+                while let Some(task_id) = self.queue.pop_front() {
+                    let worker_id = alive[self.next_worker % alive.len()];
+                    self.next_worker += 1;
+                    self.assign_task(task_id, worker_id);
+                }
+            }
+        }
+        if self.work_stealing {
+            for thief_id in alive {
+                self.try_steal(thief_id);
+            }
+        }
+        self.schedule_check();
+    }
+
+    fn try_steal(&mut self, thief_id: Id) {
+        let thief = &self.workers[&thief_id];
+        if !thief.assigned.is_empty() || thief.stealing {
+            return;
+        }
+        let victim = self
+            .workers
+            .iter()
+            .filter(|(_, w)| w.state == WorkerState::Alive && w.queued_tasks() > 0)
+            .max_by(|(id1, w1), (id2, w2)| w1.queued_tasks().cmp(&w2.queued_tasks()).then(id2.cmp(id1)))
+            .map(|(id, w)| (*id, w.queued_tasks()));
+        if let Some((victim_id, queued)) = victim {
+            self.workers.get_mut(&thief_id).unwrap().stealing = true;
+            self.network.borrow_mut().send_event(
+                StealTasks {
+                    count: queued.div_ceil(2),
+                    thief: thief_id,
+                },
+                self.ctx.id(),
+                victim_id,
+            );
+        }
+    }
+
+    fn assign_task(&mut self, task_id: u64, worker_id: Id) {
+        let time = self.ctx.time();
+        let task = self.tasks.get_mut(&task_id).unwrap();
+        task.stats.assign_time.get_or_insert(time);
+        task.stats.worker = Some(worker_id);
+        task.stats.attempts += 1;
+        let worker = self.workers.get_mut(&worker_id).unwrap();
+        // the failure timeout of idle worker is counted from the assignment
+        if worker.assigned.is_empty() {
+            worker.last_contact = time;
+        }
+        worker.assigned.push(task_id);
+        log_debug!(
+            self.ctx,
+            "assigned task {} to worker {}",
+            task_id,
+            self.ctx.lookup_name(worker_id)
+        );
+        self.network.borrow_mut().send_event(
+            TaskAssigned {
+                task_id,
+                flops: task.spec.flops,
+                input_size: task.spec.input_size,
+                output_size: task.spec.output_size,
+            },
+            self.ctx.id(),
+            worker_id,
+        );
+    }
+}
+
+impl EventHandler for Master {
+    fn on(&mut self, event: Event) {
+        cast!(match event.data {
+            WorkerRegister { speed, slots } => {
+                self.on_worker_register(event.src, speed, slots);
+            }
+            Heartbeat {} => {
+                self.on_heartbeat(event.src);
+            }
+            TaskCompleted { task_id } => {
+                self.on_task_completed(event.src, task_id);
+            }
+            TasksStolen { task_ids, thief } => {
+                self.on_tasks_stolen(event.src, task_ids, thief);
+            }
+            CheckWorkers {} => {
+                self.on_check_workers();
+            }
+        })
+    }
+}
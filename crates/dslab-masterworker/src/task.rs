@@ -0,0 +1,79 @@
+//! Task specification and statistics.
+
+use serde::Serialize;
+
+use dslab_core::component::Id;
+
+/// Specification of independent task.
+#[derive(Clone, Debug, Serialize)]
+pub struct TaskSpec {
+    /// Amount of task computations (in flops).
+    pub flops: f64,
+    /// Size of task input stored on master.
+    pub input_size: f64,
+    /// Size of task output returned to master.
+    pub output_size: f64,
+}
+
+impl TaskSpec {
+    /// Creates task specification.
+    pub fn new(flops: f64, input_size: f64, output_size: f64) -> Self {
+        assert!(flops >= 0., "task flops should be non-negative");
+        assert!(
+            input_size >= 0. && output_size >= 0.,
+            "task input and output sizes should be non-negative"
+        );
+        Self {
+            flops,
+            input_size,
+            output_size,
+        }
+    }
+
+    /// Creates task specification without input and output data.
+    pub fn compute(flops: f64) -> Self {
+        Self::new(flops, 0., 0.)
+    }
+}
+
+/// Execution statistics of task.
+#[derive(Clone, Debug, Serialize)]
+pub struct TaskStats {
+    /// Task id.
+    pub id: u64,
+    /// Time of task submission.
+    pub submit_time: f64,
+    /// Time of the first task assignment.
+    pub assign_time: Option<f64>,
+    /// Time when master received the task completion.
+    pub finish_time: Option<f64>,
+    /// Worker which the task is currently assigned to or which completed the task.
+    pub worker: Option<Id>,
+    /// Number of task assignments, which is greater than one if the task was reassigned
+    /// after worker failure or stolen by another worker.
+    pub attempts: u32,
+}
+
+impl TaskStats {
+    pub(crate) fn new(id: u64, submit_time: f64) -> Self {
+        Self {
+            id,
+            submit_time,
+            assign_time: None,
+            finish_time: None,
+            worker: None,
+            attempts: 0,
+        }
+    }
+
+    /// Returns whether the task is completed.
+    pub fn is_completed(&self) -> bool {
+        self.finish_time.is_some()
+    }
+
+    /// Returns the task completion time measured from its submission,
+    /// or `None` if the task is not completed yet.
+    pub fn completion_time(&self) -> Option<f64> {
+        self.finish_time.map(|finish| finish - self.submit_time)
+    }
+}
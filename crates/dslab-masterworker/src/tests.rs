@@ -0,0 +1,197 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use sugars::{boxed, rc, refcell};
+
+use dslab_core::faults::{FaultInjector, FaultKind, FaultScenario, FaultSpec, FaultTrigger};
+use dslab_core::simulation::Simulation;
+use dslab_core::{cast, Event, EventHandler};
+use dslab_network::models::ConstantBandwidthNetworkModel;
+use dslab_network::Network;
+
+use crate::events::AllTasksCompleted;
+use crate::master::{DispatchPolicy, Master};
+use crate::task::TaskSpec;
+use crate::worker::Worker;
+
+///////////////////////////////////////////////////////////////////////////////
+
+const SEED: u64 = 16;
+const BANDWIDTH: f64 = 10.;
+
+struct Setup {
+    sim: Simulation,
+    master: Rc<RefCell<Master>>,
+    workers: Vec<Rc<RefCell<Worker>>>,
+}
+
+// Creates master and workers with given speeds, each located on a separate network node with zero latency.
+// The workers are registered at the master before returning.
+fn make_setup(speeds: &[f64], slots: u32) -> Setup {
+    let mut sim = Simulation::new(SEED);
+    let mut network = Network::new(
+        boxed!(ConstantBandwidthNetworkModel::new(BANDWIDTH, 0.)),
+        sim.create_context("net"),
+    );
+    let master_ctx = sim.create_context("master");
+    network.add_node("master", boxed!(ConstantBandwidthNetworkModel::new(1e9, 0.)));
+    network.set_location(master_ctx.id(), "master");
+    let worker_ctxs = (0..speeds.len())
+        .map(|i| {
+            let name = format!("worker{}", i);
+            let ctx = sim.create_context(&name);
+            network.add_node(&name, boxed!(ConstantBandwidthNetworkModel::new(1e9, 0.)));
+            network.set_location(ctx.id(), &name);
+            ctx
+        })
+        .collect::<Vec<_>>();
+    let network = rc!(refcell!(network));
+    sim.add_handler("net", network.clone());
+
+    let master_id = master_ctx.id();
+    let master = rc!(refcell!(Master::new(network.clone(), master_ctx)));
+    sim.add_handler("master", master.clone());
+    let mut workers = Vec::new();
+    for (ctx, speed) in worker_ctxs.into_iter().zip(speeds) {
+        let name = ctx.name().to_string();
+        let worker = rc!(refcell!(Worker::new(master_id, network.clone(), *speed, slots, ctx)));
+        sim.add_handler(name, worker.clone());
+        worker.borrow_mut().start();
+        workers.push(worker);
+    }
+    sim.step_until_no_events();
+    Setup { sim, master, workers }
+}
+
+#[derive(Default)]
+struct Listener {
+    completed: Vec<(u64, f64)>,
+}
+
+impl EventHandler for Listener {
+    fn on(&mut self, event: Event) {
+        cast!(match event.data {
+            AllTasksCompleted { completed } => {
+                self.completed.push((completed, event.time));
+            }
+        })
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn on_demand_dispatch() {
+    let mut setup = make_setup(&[10., 10.], 1);
+    let listener = rc!(refcell!(Listener::default()));
+    let listener_id = setup.sim.add_handler("listener", listener.clone());
+    setup.master.borrow_mut().set_listener(listener_id);
+    setup.master.borrow_mut().submit_tasks(vec![TaskSpec::compute(10.); 4]);
+    setup.sim.step_until_no_events();
+
+    let master = setup.master.borrow();
+    let stats = master.stats();
+    assert_eq!(stats.submitted, 4);
+    assert_eq!(stats.completed, 4);
+    assert_eq!(stats.makespan, Some(2.));
+    assert_eq!(stats.mean_completion_time, Some(1.5));
+    assert!(master.worker_stats().iter().all(|w| w.completed_tasks == 2));
+    assert_eq!(listener.borrow().completed, vec![(4, 2.)]);
+}
+
+#[test]
+// Each task downloads the input in 2 seconds, computes for 1 second and uploads the output in 1 second.
+// The second task is assigned when the only worker slot becomes free.
+fn data_transfers() {
+    let mut setup = make_setup(&[10.], 1);
+    setup
+        .master
+        .borrow_mut()
+        .submit_tasks(vec![TaskSpec::new(10., 20., 10.); 2]);
+    setup.sim.step_until_no_events();
+
+    let master = setup.master.borrow();
+    assert_eq!(master.stats().makespan, Some(8.));
+    assert_eq!(master.task_stats(0).unwrap().finish_time, Some(4.));
+    assert_eq!(master.task_stats(1).unwrap().assign_time, Some(4.));
+    assert_eq!(master.task_stats(1).unwrap().completion_time(), Some(8.));
+    assert_eq!(setup.workers[0].borrow().completed_tasks(), 2);
+}
+
+#[test]
+// Static policy assigns three tasks to each worker, so the slow worker finishes its tasks at 30.
+// With work stealing the fast worker steals two queued tasks of the slow worker at 3 and 4,
+// so the makespan is determined by the first task of the slow worker.
+fn work_stealing() {
+    let run = |stealing: bool| {
+        let mut setup = make_setup(&[10., 1.], 1);
+        setup.master.borrow_mut().set_dispatch_policy(DispatchPolicy::Static);
+        setup.master.borrow_mut().set_work_stealing(stealing);
+        setup.master.borrow_mut().submit_tasks(vec![TaskSpec::compute(10.); 6]);
+        setup.sim.step_until_no_events();
+        let master = setup.master.borrow();
+        (master.stats(), master.worker_stats())
+    };
+
+    let (stats, _) = run(false);
+    assert_eq!(stats.makespan, Some(30.));
+    assert_eq!(stats.stolen, 0);
+
+    let (stats, workers) = run(true);
+    assert_eq!(stats.makespan, Some(10.));
+    assert_eq!(stats.stolen, 2);
+    assert_eq!(workers[0].completed_tasks, 5);
+    assert_eq!(workers[0].stolen_by, 2);
+    assert_eq!(workers[1].stolen_from, 2);
+}
+
+#[test]
+// Worker 1 crashes at 0.5 while executing task 1. The failure is detected by the check at 5,
+// and the task is reassigned to worker 0 which is idle since 3.
+fn worker_failure() {
+    let mut setup = make_setup(&[10., 10.], 1);
+    setup.master.borrow_mut().set_failure_timeout(2.5);
+    let faults = FaultScenario::default().with_fault(FaultSpec::new(
+        "worker1",
+        FaultKind::Crash,
+        FaultTrigger::At { time: 0.5 },
+    ));
+    FaultInjector::install(&mut setup.sim, &faults).unwrap();
+    setup.master.borrow_mut().submit_tasks(vec![TaskSpec::compute(10.); 4]);
+    setup.sim.step_until_no_events();
+
+    let master = setup.master.borrow();
+    let stats = master.stats();
+    assert_eq!(stats.completed, 4);
+    assert_eq!(stats.makespan, Some(6.));
+    assert_eq!(stats.worker_failures, 1);
+    assert_eq!(stats.reassigned, 1);
+    let task = master.task_stats(1).unwrap();
+    assert_eq!(task.attempts, 2);
+    assert_eq!(task.worker, Some(setup.workers[0].borrow().id()));
+    let workers = master.worker_stats();
+    assert_eq!(workers[0].completed_tasks, 4);
+    assert_eq!(workers[1].failures, 1);
+    assert!(setup.workers[1].borrow().is_crashed());
+}
+
+#[test]
+// Worker 1 recovers at 1.5 and registers again, so its lost task is reassigned to it without waiting for timeout.
+fn worker_restart() {
+    let mut setup = make_setup(&[10., 10.], 1);
+    let faults = FaultScenario::default()
+        .with_fault(FaultSpec::new("worker1", FaultKind::Crash, FaultTrigger::At { time: 0.5 }).with_duration(1.));
+    FaultInjector::install(&mut setup.sim, &faults).unwrap();
+    setup.master.borrow_mut().submit_tasks(vec![TaskSpec::compute(10.); 4]);
+    setup.sim.step_until_no_events();
+
+    let master = setup.master.borrow();
+    let stats = master.stats();
+    assert_eq!(stats.makespan, Some(3.));
+    assert_eq!(stats.worker_failures, 1);
+    assert_eq!(stats.reassigned, 1);
+    assert_eq!(master.task_stats(1).unwrap().finish_time, Some(2.5));
+    let workers = master.worker_stats();
+    assert_eq!(workers[0].completed_tasks, 3);
+    assert_eq!(workers[1].completed_tasks, 1);
+}
@@ -0,0 +1,288 @@
+//! Worker executing tasks assigned by master.
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::rc::Rc;
+
+use dslab_core::component::Id;
+use dslab_core::context::SimulationContext;
+use dslab_core::event::{Event, EventId};
+use dslab_core::faults::{FaultInjected, FaultKind, FaultRecovered};
+use dslab_core::handler::EventHandler;
+use dslab_core::{cast, log_debug, log_info};
+use dslab_network::{DataTransferCompleted, Network};
+
+use crate::events::{
+    Heartbeat, SendHeartbeat, StealTasks, TaskAssigned, TaskCompleted, TaskComputed, TasksStolen, WorkerRegister,
+};
+
+enum Transfer {
+    Input(u64),
+    Output(u64),
+}
+
+/// Component modeling a worker which executes the tasks assigned by the [master](crate::Master).
+///
+/// The worker executes up to `slots` tasks concurrently and queues the other assigned tasks. Each task is executed
+/// by downloading its input from the master, performing its computations at the worker speed and uploading
+/// its output to the master. While the worker has tasks, it sends heartbeats to the master.
+///
+/// The worker reacts to crashes injected by [`FaultInjector`](dslab_core::faults::FaultInjector): it loses all its
+/// tasks and ignores the incoming events until the recovery, after which it registers at the master again.
+pub struct Worker {
+    master: Id,
+    network: Rc<RefCell<Network>>,
+    speed: f64,
+    slots: u32,
+    heartbeat_interval: f64,
+    queue: VecDeque<TaskAssigned>,
+    running: BTreeMap<u64, TaskAssigned>,
+    computations: HashMap<u64, EventId>,
+    transfers: HashMap<usize, Transfer>,
+    heartbeat: Option<EventId>,
+    crashed: bool,
+    completed_tasks: u64,
+    ctx: SimulationContext,
+}
+
+impl Worker {
+    /// Creates worker with given speed (in flop/s) and number of slots, which executes the tasks of specified master.
+    pub fn new(master: Id, network: Rc<RefCell<Network>>, speed: f64, slots: u32, ctx: SimulationContext) -> Self {
+        assert!(speed > 0., "worker speed should be positive");
+        assert!(slots > 0, "worker should have at least one slot");
+        Self {
+            master,
+            network,
+            speed,
+            slots,
+            heartbeat_interval: 1.,
+            queue: VecDeque::new(),
+            running: BTreeMap::new(),
+            computations: HashMap::new(),
+            transfers: HashMap::new(),
+            heartbeat: None,
+            crashed: false,
+            completed_tasks: 0,
+            ctx,
+        }
+    }
+
+    /// Returns component id.
+    pub fn id(&self) -> Id {
+        self.ctx.id()
+    }
+
+    /// Sets the interval between heartbeats (1 by default).
+    pub fn set_heartbeat_interval(&mut self, interval: f64) {
+        assert!(interval > 0., "heartbeat interval should be positive");
+        self.heartbeat_interval = interval;
+    }
+
+    /// Registers the worker at the master.
+    pub fn start(&mut self) {
+        self.register();
+    }
+
+    /// Returns the number of tasks waiting in the worker queue.
+    pub fn queued_tasks(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Returns the number of tasks being executed.
+    pub fn running_tasks(&self) -> usize {
+        self.running.len()
+    }
+
+    /// Returns the number of tasks completed by the worker, including the tasks completed
+    /// after reassignment to another worker.
+    pub fn completed_tasks(&self) -> u64 {
+        self.completed_tasks
+    }
+
+    /// Returns whether the worker is crashed.
+    pub fn is_crashed(&self) -> bool {
+        self.crashed
+    }
+
+    fn register(&mut self) {
+        self.network.borrow_mut().send_event(
+            WorkerRegister {
+                speed: self.speed,
+                slots: self.slots,
+            },
+            self.ctx.id(),
+            self.master,
+        );
+    }
+
+    fn has_tasks(&self) -> bool {
+        !self.queue.is_empty() || !self.running.is_empty()
+    }
+
+    fn on_task_assigned(&mut self, task: TaskAssigned) {
+        log_debug!(self.ctx, "received task {}", task.task_id);
+        self.queue.push_back(task);
+        self.start_tasks();
+        self.schedule_heartbeat();
+    }
+
+    fn start_tasks(&mut self) {
+        while self.running.len() < self.slots as usize {
+            let task = match self.queue.pop_front() {
+                Some(task) => task,
+                None => break,
+            };
+            let task_id = task.task_id;
+            let input_size = task.input_size;
+            self.running.insert(task_id, task);
+            if input_size > 0. {
+                let transfer_id =
+                    self.network
+                        .borrow_mut()
+                        .transfer_data(self.master, self.ctx.id(), input_size, self.ctx.id());
+                self.transfers.insert(transfer_id, Transfer::Input(task_id));
+            } else {
+                self.start_computation(task_id);
+            }
+        }
+    }
+
+    fn start_computation(&mut self, task_id: u64) {
+        let flops = self.running[&task_id].flops;
+        let event_id = self.ctx.emit_self(TaskComputed { task_id }, flops / self.speed);
+        self.computations.insert(task_id, event_id);
+    }
+
+    fn on_task_computed(&mut self, task_id: u64) {
+        self.computations.remove(&task_id);
+        let output_size = self.running[&task_id].output_size;
+        if output_size > 0. {
+            let transfer_id =
+                self.network
+                    .borrow_mut()
+                    .transfer_data(self.ctx.id(), self.master, output_size, self.ctx.id());
+            self.transfers.insert(transfer_id, Transfer::Output(task_id));
+        } else {
+            self.complete_task(task_id);
+        }
+    }
+
+    fn on_transfer_completed(&mut self, transfer_id: usize) {
+        match self.transfers.remove(&transfer_id) {
+            Some(Transfer::Input(task_id)) => self.start_computation(task_id),
+            Some(Transfer::Output(task_id)) => self.complete_task(task_id),
+            None => {}
+        }
+    }
+
+    fn complete_task(&mut self, task_id: u64) {
+        log_debug!(self.ctx, "completed task {}", task_id);
+        self.running.remove(&task_id);
+        self.completed_tasks += 1;
+        self.network
+            .borrow_mut()
+            .send_event(TaskCompleted { task_id }, self.ctx.id(), self.master);
+        self.start_tasks();
+    }
+
+    fn on_steal_tasks(&mut self, count: usize, thief: Id) {
+        let count = count.min(self.queue.len());
+        let task_ids = self
+            .queue
+            .split_off(self.queue.len() - count)
+            .into_iter()
+            .map(|task| task.task_id)
+            .collect();
+        self.network
+            .borrow_mut()
+            .send_event(TasksStolen { task_ids, thief }, self.ctx.id(), self.master);
+    }
+
+    fn schedule_heartbeat(&mut self) {
+        if self.heartbeat.is_none() && self.has_tasks() {
+            self.heartbeat = Some(self.ctx.emit_self(SendHeartbeat {}, self.heartbeat_interval));
+        }
+    }
+
+    fn on_send_heartbeat(&mut self) {
+        self.heartbeat = None;
+        if self.has_tasks() {
+            self.network
+                .borrow_mut()
+                .send_event(Heartbeat {}, self.ctx.id(), self.master);
+            self.schedule_heartbeat();
+        }
+    }
+
+    fn on_crash(&mut self) {
+        log_info!(
+            self.ctx,
+            "crashed with {} running and {} queued tasks",
+            self.running.len(),
+            self.queue.len()
+        );
+        self.crashed = true;
+        for (_, event_id) in self.computations.drain() {
+            self.ctx.cancel_event(event_id);
+        }
+        if let Some(event_id) = self.heartbeat.take() {
+            self.ctx.cancel_event(event_id);
+        }
+        self.queue.clear();
+        self.running.clear();
+        self.transfers.clear();
+    }
+
+    fn on_recovery(&mut self) {
+        log_info!(self.ctx, "recovered");
+        self.crashed = false;
+        self.register();
+    }
+}
+
+impl EventHandler for Worker {
+    fn on(&mut self, event: Event) {
+        if self.crashed {
+            cast!(match event.data {
+                FaultRecovered { kind, .. } => {
+                    if kind == FaultKind::Crash {
+                        self.on_recovery();
+                    }
+                }
+            });
+            return;
+        }
+        cast!(match event.data {
+            TaskAssigned {
+                task_id,
+                flops,
+                input_size,
+                output_size,
+            } => {
+                self.on_task_assigned(TaskAssigned {
+                    task_id,
+                    flops,
+                    input_size,
+                    output_size,
+                });
+            }
+            TaskComputed { task_id } => {
+                self.on_task_computed(task_id);
+            }
+            DataTransferCompleted { dt } => {
+                self.on_transfer_completed(dt.id);
+            }
+            StealTasks { count, thief } => {
+                self.on_steal_tasks(count, thief);
+            }
+            SendHeartbeat {} => {
+                self.on_send_heartbeat();
+            }
+            FaultInjected { kind, .. } => {
+                if kind == FaultKind::Crash {
+                    self.on_crash();
+                }
+            }
+        })
+    }
+}
@@ -2,4 +2,5 @@
 #![doc = include_str!("../README.md")]
 
 pub mod power;
+pub mod queueing;
 pub mod throughput_sharing;
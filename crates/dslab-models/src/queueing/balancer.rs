@@ -0,0 +1,206 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use dslab_core::component::Id;
+use dslab_core::{cast, Event, EventHandler, SimulationContext};
+
+use crate::queueing::events::{Job, JobArrival, JobCompleted, JobDropped};
+use crate::queueing::policies::{BackendState, DispatchPolicy};
+use crate::queueing::queue::QueueServer;
+
+/// Statistics of jobs passed through load balancer.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BalancerStats {
+    /// Name of dispatch policy.
+    pub policy: String,
+    /// Number of dispatched jobs.
+    pub dispatched: u64,
+    /// Number of completed jobs.
+    pub completed: u64,
+    /// Number of jobs dropped by backends.
+    pub dropped: u64,
+    /// Mean latency of completed jobs, i.e. the time from job arrival to the balancer until its completion is reported.
+    pub mean_latency: f64,
+    /// Median latency of completed jobs.
+    pub p50_latency: f64,
+    /// 95th percentile of latency of completed jobs.
+    pub p95_latency: f64,
+    /// 99th percentile of latency of completed jobs.
+    pub p99_latency: f64,
+    /// Maximum latency of completed jobs.
+    pub max_latency: f64,
+}
+
+struct Backend {
+    state: BackendState,
+    queue: Option<Rc<RefCell<QueueServer>>>,
+}
+
+/// Load balancer dispatching arrived jobs to several backends using pluggable [`DispatchPolicy`].
+///
+/// The balancer receives [`JobArrival`] events and forwards them to the backend selected by the policy.
+/// The backends should report the completed and dropped jobs to the balancer via [`JobCompleted`]
+/// and [`JobDropped`] events, which are used to track the number of outstanding jobs per backend and the job latency,
+/// and then forwarded to the balancer listener. The ids of jobs passing through the balancer should be unique.
+pub struct LoadBalancer {
+    policy: Box<dyn DispatchPolicy>,
+    backends: Vec<Backend>,
+    arrival_times: HashMap<u64, f64>,
+    latencies: Vec<f64>,
+    dropped: u64,
+    listener: Option<Id>,
+    ctx: SimulationContext,
+}
+
+impl LoadBalancer {
+    /// Creates load balancer with the specified dispatch policy and no backends.
+    pub fn new(policy: Box<dyn DispatchPolicy>, ctx: SimulationContext) -> Self {
+        Self {
+            policy,
+            backends: Vec::new(),
+            arrival_times: HashMap::new(),
+            latencies: Vec::new(),
+            dropped: 0,
+            listener: None,
+            ctx,
+        }
+    }
+
+    /// Returns the component id.
+    pub fn id(&self) -> Id {
+        self.ctx.id()
+    }
+
+    /// Adds backend component with the specified weight.
+    ///
+    /// The backend should report the completed and dropped jobs to the balancer.
+    pub fn add_backend(&mut self, id: Id, weight: u32) {
+        self.backends.push(Backend {
+            state: Self::backend_state(id, weight),
+            queue: None,
+        });
+    }
+
+    /// Adds queue as backend with the specified weight and sets the balancer as the queue listener.
+    pub fn add_queue(&mut self, queue: &Rc<RefCell<QueueServer>>, weight: u32) {
+        let id = {
+            let mut queue = queue.borrow_mut();
+            queue.set_listener(self.id());
+            queue.id()
+        };
+        self.backends.push(Backend {
+            state: Self::backend_state(id, weight),
+            queue: Some(queue.clone()),
+        });
+    }
+
+    /// Sets the component which receives [`JobCompleted`] and [`JobDropped`] events from the backends.
+    pub fn set_listener(&mut self, id: Id) {
+        self.listener = Some(id);
+    }
+
+    /// Returns the states of backends in the order of adding.
+    pub fn backends(&self) -> Vec<BackendState> {
+        self.backends.iter().map(|b| b.state.clone()).collect()
+    }
+
+    /// Returns the statistics of jobs passed through the balancer.
+    pub fn stats(&self) -> BalancerStats {
+        let mut stats = BalancerStats {
+            policy: self.policy.name(),
+            dispatched: self.backends.iter().map(|b| b.state.dispatched).sum(),
+            completed: self.latencies.len() as u64,
+            dropped: self.dropped,
+            ..Default::default()
+        };
+        if !self.latencies.is_empty() {
+            let mut latencies = self.latencies.clone();
+            latencies.sort_by(|a, b| a.total_cmp(b));
+            stats.mean_latency = latencies.iter().sum::<f64>() / latencies.len() as f64;
+            stats.p50_latency = percentile(&latencies, 0.5);
+            stats.p95_latency = percentile(&latencies, 0.95);
+            stats.p99_latency = percentile(&latencies, 0.99);
+            stats.max_latency = *latencies.last().unwrap();
+        }
+        stats
+    }
+
+    fn backend_state(id: Id, weight: u32) -> BackendState {
+        BackendState {
+            id,
+            weight,
+            outstanding: 0,
+            load: 0,
+            dispatched: 0,
+        }
+    }
+
+    fn on_job_arrival(&mut self, job: Job) {
+        assert!(!self.backends.is_empty(), "Load balancer has no backends");
+        for backend in self.backends.iter_mut() {
+            backend.state.load = match &backend.queue {
+                Some(queue) => queue.borrow().jobs_in_system(),
+                None => backend.state.outstanding,
+            };
+        }
+        let states: Vec<BackendState> = self.backends.iter().map(|b| b.state.clone()).collect();
+        let index = self.policy.select(&states, &self.ctx);
+        let backend = &mut self.backends[index].state;
+        backend.outstanding += 1;
+        backend.dispatched += 1;
+        self.arrival_times.insert(job.id, self.ctx.time());
+        self.ctx.emit_now(JobArrival { job }, backend.id);
+    }
+
+    fn on_job_left(&mut self, job_id: u64, backend_id: Id) -> Option<f64> {
+        if let Some(backend) = self.backends.iter_mut().find(|b| b.state.id == backend_id) {
+            backend.state.outstanding = backend.state.outstanding.saturating_sub(1);
+        }
+        self.arrival_times.remove(&job_id)
+    }
+}
+
+/// Returns the percentile of sorted values using the nearest-rank method.
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    let rank = (p * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+impl EventHandler for LoadBalancer {
+    fn on(&mut self, event: Event) {
+        cast!(match event.data {
+            JobArrival { job } => {
+                self.on_job_arrival(job);
+            }
+            JobCompleted {
+                job,
+                queue,
+                arrival_time,
+                start_time,
+                finish_time,
+            } => {
+                if let Some(dispatch_time) = self.on_job_left(job.id, queue) {
+                    self.latencies.push(self.ctx.time() - dispatch_time);
+                }
+                if let Some(listener) = self.listener {
+                    let completed = JobCompleted {
+                        job,
+                        queue,
+                        arrival_time,
+                        start_time,
+                        finish_time,
+                    };
+                    self.ctx.emit_now(completed, listener);
+                }
+            }
+            JobDropped { job, queue } => {
+                self.on_job_left(job.id, queue);
+                self.dropped += 1;
+                if let Some(listener) = self.listener {
+                    self.ctx.emit_now(JobDropped { job, queue }, listener);
+                }
+            }
+        })
+    }
+}
@@ -0,0 +1,78 @@
+use dslab_core::SimulationContext;
+
+/// Distribution of service or inter-arrival times.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TimeDistribution {
+    /// Constant value.
+    Constant(f64),
+    /// Exponential distribution with the specified rate (the mean is `1 / rate`).
+    Exponential {
+        /// Rate parameter.
+        rate: f64,
+    },
+    /// Uniform distribution on `[min, max)`.
+    Uniform {
+        /// Minimum value.
+        min: f64,
+        /// Maximum value.
+        max: f64,
+    },
+    /// Erlang distribution, i.e. the sum of `shape` exponentially distributed values with the specified rate.
+    Erlang {
+        /// Number of exponential phases.
+        shape: u32,
+        /// Rate of each phase.
+        rate: f64,
+    },
+    /// Pareto distribution with the specified minimum value and tail index, used to model heavy tails.
+    Pareto {
+        /// Minimum value.
+        scale: f64,
+        /// Tail index, smaller values correspond to heavier tails.
+        shape: f64,
+    },
+    /// Empirical distribution, where each of the specified values is selected with equal probability.
+    Empirical(Vec<f64>),
+}
+
+impl TimeDistribution {
+    /// Draws a value using the random generator of the component.
+    pub fn sample(&self, ctx: &SimulationContext) -> f64 {
+        match self {
+            Self::Constant(value) => *value,
+            Self::Exponential { rate } => sample_exponential(*rate, ctx),
+            Self::Uniform { min, max } => {
+                if min < max {
+                    ctx.gen_range(*min..*max)
+                } else {
+                    *min
+                }
+            }
+            Self::Erlang { shape, rate } => (0..*shape).map(|_| sample_exponential(*rate, ctx)).sum(),
+            Self::Pareto { scale, shape } => scale / (1. - ctx.rand()).powf(1. / shape),
+            Self::Empirical(values) => values[ctx.gen_range(0..values.len())],
+        }
+    }
+
+    /// Returns the mean value of the distribution.
+    pub fn mean(&self) -> f64 {
+        match self {
+            Self::Constant(value) => *value,
+            Self::Exponential { rate } => 1. / rate,
+            Self::Uniform { min, max } => (min + max) / 2.,
+            Self::Erlang { shape, rate } => *shape as f64 / rate,
+            Self::Pareto { scale, shape } => {
+                if *shape > 1. {
+                    shape * scale / (shape - 1.)
+                } else {
+                    f64::INFINITY
+                }
+            }
+            Self::Empirical(values) => values.iter().sum::<f64>() / values.len() as f64,
+        }
+    }
+}
+
+fn sample_exponential(rate: f64, ctx: &SimulationContext) -> f64 {
+    -(1. - ctx.rand()).ln() / rate
+}
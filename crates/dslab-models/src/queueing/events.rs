@@ -0,0 +1,89 @@
+use serde::Serialize;
+
+use dslab_core::component::Id;
+
+/// Job processed by queueing components.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Job {
+    /// Job id.
+    pub id: u64,
+    /// Job priority used by [`QueueDiscipline::Priority`](crate::queueing::QueueDiscipline::Priority),
+    /// jobs with higher value are served first.
+    pub priority: u32,
+    /// Job service time, if not set it is drawn from the service time distribution of the queue.
+    pub service_time: Option<f64>,
+}
+
+impl Job {
+    /// Creates job with zero priority and service time drawn by the queue.
+    pub fn new(id: u64) -> Self {
+        Self {
+            id,
+            priority: 0,
+            service_time: None,
+        }
+    }
+
+    /// Sets job priority.
+    pub fn with_priority(mut self, priority: u32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Sets job service time.
+    pub fn with_service_time(mut self, service_time: f64) -> Self {
+        self.service_time = Some(service_time);
+        self
+    }
+}
+
+/// Arrival of job to queue or load balancer.
+#[derive(Clone, Serialize)]
+pub struct JobArrival {
+    /// Arrived job.
+    pub job: Job,
+}
+
+/// Completion of job service, sent to the queue listener.
+#[derive(Clone, Serialize)]
+pub struct JobCompleted {
+    /// Completed job.
+    pub job: Job,
+    /// Queue (or other backend component) which served the job.
+    pub queue: Id,
+    /// Time of job arrival to the queue.
+    pub arrival_time: f64,
+    /// Time of job service start.
+    pub start_time: f64,
+    /// Time of job service completion.
+    pub finish_time: f64,
+}
+
+impl JobCompleted {
+    /// Returns the time spent by job waiting in the queue.
+    pub fn wait_time(&self) -> f64 {
+        self.start_time - self.arrival_time
+    }
+
+    /// Returns the time spent by job in the queue including service.
+    pub fn response_time(&self) -> f64 {
+        self.finish_time - self.arrival_time
+    }
+}
+
+/// Loss of job arrived to the full queue, sent to the queue listener.
+#[derive(Clone, Serialize)]
+pub struct JobDropped {
+    /// Dropped job.
+    pub job: Job,
+    /// Queue which dropped the job.
+    pub queue: Id,
+}
+
+/// Completion of job service (internal event).
+#[derive(Clone, Serialize)]
+pub(crate) struct ServiceCompleted {}
+
+/// Generation of the next job (internal event).
+#[derive(Clone, Serialize)]
+pub(crate) struct GenerateJob {}
@@ -0,0 +1,23 @@
+#![doc = include_str!("README.md")]
+
+mod balancer;
+mod distribution;
+mod events;
+mod policies;
+mod queue;
+mod source;
+mod stats;
+
+#[cfg(test)]
+mod tests;
+
+pub use balancer::{BalancerStats, LoadBalancer};
+pub use distribution::TimeDistribution;
+pub use events::{Job, JobArrival, JobCompleted, JobDropped};
+pub use policies::{
+    BackendState, DispatchPolicy, JoinShortestQueue, LeastConnections, PowerOfTwoChoices, Random, RoundRobin,
+    WeightedRoundRobin,
+};
+pub use queue::{QueueDiscipline, QueueServer};
+pub use source::JobSource;
+pub use stats::QueueStats;
@@ -0,0 +1,202 @@
+use dslab_core::component::Id;
+use dslab_core::SimulationContext;
+
+/// State of load balancer backend passed to dispatch policy.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BackendState {
+    /// Backend component id.
+    pub id: Id,
+    /// Backend weight, the backends with higher weight should receive proportionally more jobs.
+    pub weight: u32,
+    /// Number of jobs dispatched to the backend and not completed or dropped yet (active connections).
+    pub outstanding: usize,
+    /// Current number of jobs in the backend queue if the backend is a [`QueueServer`](crate::queueing::QueueServer),
+    /// otherwise the same as `outstanding`.
+    pub load: usize,
+    /// Total number of jobs dispatched to the backend.
+    pub dispatched: u64,
+}
+
+/// Policy used by load balancer to select the backend for arrived job.
+pub trait DispatchPolicy {
+    /// Returns the policy name used in statistics.
+    fn name(&self) -> String;
+
+    /// Returns the index of backend selected for the job, the list of backends is not empty.
+    fn select(&mut self, backends: &[BackendState], ctx: &SimulationContext) -> usize;
+}
+
+/// Backends are selected in cyclic order.
+#[derive(Default)]
+pub struct RoundRobin {
+    next: usize,
+}
+
+impl RoundRobin {
+    /// Creates policy.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl DispatchPolicy for RoundRobin {
+    fn name(&self) -> String {
+        "RoundRobin".to_string()
+    }
+
+    fn select(&mut self, backends: &[BackendState], _ctx: &SimulationContext) -> usize {
+        let index = self.next % backends.len();
+        self.next = index + 1;
+        index
+    }
+}
+
+/// Backends are selected in cyclic order proportionally to their weights,
+/// the selections of each backend are spread evenly over the cycle (smooth weighted round robin).
+#[derive(Default)]
+pub struct WeightedRoundRobin {
+    current: Vec<i64>,
+}
+
+impl WeightedRoundRobin {
+    /// Creates policy.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl DispatchPolicy for WeightedRoundRobin {
+    fn name(&self) -> String {
+        "WeightedRoundRobin".to_string()
+    }
+
+    fn select(&mut self, backends: &[BackendState], _ctx: &SimulationContext) -> usize {
+        self.current.resize(backends.len(), 0);
+        let mut total = 0;
+        for (current, backend) in self.current.iter_mut().zip(backends) {
+            *current += backend.weight as i64;
+            total += backend.weight as i64;
+        }
+        let mut best = 0;
+        for i in 1..backends.len() {
+            if self.current[i] > self.current[best] {
+                best = i;
+            }
+        }
+        self.current[best] -= total;
+        best
+    }
+}
+
+/// Backend is selected at random with probability proportional to its weight (uniformly if the weights are equal).
+#[derive(Default)]
+pub struct Random {}
+
+impl Random {
+    /// Creates policy.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl DispatchPolicy for Random {
+    fn name(&self) -> String {
+        "Random".to_string()
+    }
+
+    fn select(&mut self, backends: &[BackendState], ctx: &SimulationContext) -> usize {
+        let total: u64 = backends.iter().map(|b| b.weight as u64).sum();
+        if total == 0 {
+            return ctx.gen_range(0..backends.len());
+        }
+        let mut point = ctx.gen_range(0..total);
+        for (i, backend) in backends.iter().enumerate() {
+            if point < backend.weight as u64 {
+                return i;
+            }
+            point -= backend.weight as u64;
+        }
+        unreachable!()
+    }
+}
+
+/// Backend with the least number of outstanding jobs per unit of weight is selected, ties are broken by backend order.
+#[derive(Default)]
+pub struct LeastConnections {}
+
+impl LeastConnections {
+    /// Creates policy.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl DispatchPolicy for LeastConnections {
+    fn name(&self) -> String {
+        "LeastConnections".to_string()
+    }
+
+    fn select(&mut self, backends: &[BackendState], _ctx: &SimulationContext) -> usize {
+        let mut best = 0;
+        for i in 1..backends.len() {
+            // compares outstanding / weight without division
+            let lhs = backends[i].outstanding as u64 * backends[best].weight.max(1) as u64;
+            let rhs = backends[best].outstanding as u64 * backends[i].weight.max(1) as u64;
+            if lhs < rhs {
+                best = i;
+            }
+        }
+        best
+    }
+}
+
+/// Backend with the shortest queue is selected, ties are broken by backend order.
+///
+/// In contrast to [`LeastConnections`], the actual queue length is used for queue backends,
+/// which does not include the jobs still in transit to the backend or the completed jobs not yet reported.
+#[derive(Default)]
+pub struct JoinShortestQueue {}
+
+impl JoinShortestQueue {
+    /// Creates policy.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl DispatchPolicy for JoinShortestQueue {
+    fn name(&self) -> String {
+        "JoinShortestQueue".to_string()
+    }
+
+    fn select(&mut self, backends: &[BackendState], _ctx: &SimulationContext) -> usize {
+        (0..backends.len()).min_by_key(|i| backends[*i].load).unwrap()
+    }
+}
+
+/// Two backends are selected at random and the one with the shorter queue is used.
+#[derive(Default)]
+pub struct PowerOfTwoChoices {}
+
+impl PowerOfTwoChoices {
+    /// Creates policy.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl DispatchPolicy for PowerOfTwoChoices {
+    fn name(&self) -> String {
+        "PowerOfTwoChoices".to_string()
+    }
+
+    fn select(&mut self, backends: &[BackendState], ctx: &SimulationContext) -> usize {
+        let first = ctx.gen_range(0..backends.len());
+        let second = ctx.gen_range(0..backends.len());
+        if backends[second].load < backends[first].load {
+            second
+        } else {
+            first
+        }
+    }
+}
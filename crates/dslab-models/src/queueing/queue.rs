@@ -0,0 +1,183 @@
+use std::collections::VecDeque;
+
+use dslab_core::component::Id;
+use dslab_core::{cast, Event, EventHandler, SimulationContext};
+
+use crate::queueing::distribution::TimeDistribution;
+use crate::queueing::events::{Job, JobArrival, JobCompleted, JobDropped, ServiceCompleted};
+use crate::queueing::stats::{QueueStats, StatsCollector};
+
+/// Order in which the waiting jobs are served.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum QueueDiscipline {
+    /// First in, first out.
+    #[default]
+    Fifo,
+    /// Last in, first out.
+    Lifo,
+    /// Job with the highest priority first, jobs with equal priority are served in FIFO order.
+    Priority,
+    /// Job with the shortest service time first, jobs with equal service time are served in FIFO order.
+    ShortestJobFirst,
+}
+
+struct QueuedJob {
+    job: Job,
+    service_time: f64,
+    arrival_time: f64,
+}
+
+struct JobInService {
+    queued: QueuedJob,
+    start_time: f64,
+}
+
+/// Single-server queue.
+///
+/// The queue receives [`JobArrival`] events, serves the jobs one by one in the order defined by the queue discipline
+/// and reports the completed jobs to the listener via [`JobCompleted`] events. If the queue capacity is limited,
+/// the jobs arriving when the capacity is exhausted are dropped and reported via [`JobDropped`] events.
+pub struct QueueServer {
+    service_time: TimeDistribution,
+    discipline: QueueDiscipline,
+    capacity: Option<usize>,
+    queue: VecDeque<QueuedJob>,
+    in_service: Option<JobInService>,
+    listener: Option<Id>,
+    stats: StatsCollector,
+    ctx: SimulationContext,
+}
+
+impl QueueServer {
+    /// Creates FIFO queue with unlimited capacity and the specified service time distribution.
+    pub fn new(service_time: TimeDistribution, ctx: SimulationContext) -> Self {
+        Self {
+            service_time,
+            discipline: QueueDiscipline::default(),
+            capacity: None,
+            queue: VecDeque::new(),
+            in_service: None,
+            listener: None,
+            stats: StatsCollector::new(ctx.time()),
+            ctx,
+        }
+    }
+
+    /// Returns the component id.
+    pub fn id(&self) -> Id {
+        self.ctx.id()
+    }
+
+    /// Sets the queue discipline.
+    pub fn set_discipline(&mut self, discipline: QueueDiscipline) {
+        self.discipline = discipline;
+    }
+
+    /// Sets the maximum number of waiting jobs (not including the served one).
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = Some(capacity);
+    }
+
+    /// Sets the component which receives [`JobCompleted`] and [`JobDropped`] events.
+    pub fn set_listener(&mut self, id: Id) {
+        self.listener = Some(id);
+    }
+
+    /// Returns the number of waiting jobs.
+    pub fn queue_length(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Returns the number of jobs in the queue including the served one.
+    pub fn jobs_in_system(&self) -> usize {
+        self.queue.len() + self.in_service.is_some() as usize
+    }
+
+    /// Returns the statistics collected up to the current time.
+    pub fn stats(&self) -> QueueStats {
+        self.stats.stats(self.ctx.time())
+    }
+
+    fn on_job_arrival(&mut self, job: Job) {
+        self.stats.on_arrival();
+        if self.in_service.is_some() && self.capacity.is_some_and(|capacity| self.queue.len() >= capacity) {
+            self.stats.on_drop();
+            if let Some(listener) = self.listener {
+                self.ctx.emit_now(JobDropped { job, queue: self.id() }, listener);
+            }
+            return;
+        }
+        let service_time = job.service_time.unwrap_or_else(|| self.service_time.sample(&self.ctx));
+        self.queue.push_back(QueuedJob {
+            job,
+            service_time,
+            arrival_time: self.ctx.time(),
+        });
+        self.start_next_job();
+    }
+
+    fn on_service_completed(&mut self) {
+        let JobInService { queued, start_time } = self.in_service.take().unwrap();
+        let completed = JobCompleted {
+            job: queued.job,
+            queue: self.id(),
+            arrival_time: queued.arrival_time,
+            start_time,
+            finish_time: self.ctx.time(),
+        };
+        self.stats
+            .on_completion(completed.wait_time(), completed.response_time());
+        if let Some(listener) = self.listener {
+            self.ctx.emit_now(completed, listener);
+        }
+        self.start_next_job();
+    }
+
+    fn start_next_job(&mut self) {
+        if self.in_service.is_none() {
+            if let Some(index) = self.next_job_index() {
+                let queued = self.queue.remove(index).unwrap();
+                self.ctx.emit_self(ServiceCompleted {}, queued.service_time);
+                self.in_service = Some(JobInService {
+                    queued,
+                    start_time: self.ctx.time(),
+                });
+            }
+        }
+        self.stats
+            .on_state_change(self.ctx.time(), self.queue.len(), self.in_service.is_some());
+    }
+
+    // The jobs are stored in the order of arrival, so the first of the jobs with equal keys is selected.
+    fn next_job_index(&self) -> Option<usize> {
+        if self.queue.is_empty() {
+            return None;
+        }
+        let index = match self.discipline {
+            QueueDiscipline::Fifo => 0,
+            QueueDiscipline::Lifo => self.queue.len() - 1,
+            QueueDiscipline::Priority => {
+                let max_priority = self.queue.iter().map(|q| q.job.priority).max().unwrap();
+                self.queue.iter().position(|q| q.job.priority == max_priority).unwrap()
+            }
+            QueueDiscipline::ShortestJobFirst => {
+                let min_time = self.queue.iter().map(|q| q.service_time).fold(f64::INFINITY, f64::min);
+                self.queue.iter().position(|q| q.service_time == min_time).unwrap()
+            }
+        };
+        Some(index)
+    }
+}
+
+impl EventHandler for QueueServer {
+    fn on(&mut self, event: Event) {
+        cast!(match event.data {
+            JobArrival { job } => {
+                self.on_job_arrival(job);
+            }
+            ServiceCompleted {} => {
+                self.on_service_completed();
+            }
+        })
+    }
+}
@@ -0,0 +1,78 @@
+use dslab_core::component::Id;
+use dslab_core::{cast, Event, EventHandler, SimulationContext};
+
+use crate::queueing::distribution::TimeDistribution;
+use crate::queueing::events::{GenerateJob, Job, JobArrival};
+
+/// Generator of jobs with the specified inter-arrival time distribution.
+///
+/// The exponential inter-arrival times correspond to Poisson arrival process.
+pub struct JobSource {
+    inter_arrival_time: TimeDistribution,
+    target: Id,
+    max_jobs: Option<u64>,
+    priority: u32,
+    generated: u64,
+    ctx: SimulationContext,
+}
+
+impl JobSource {
+    /// Creates source sending jobs to the specified target (queue or load balancer).
+    pub fn new(inter_arrival_time: TimeDistribution, target: Id, ctx: SimulationContext) -> Self {
+        Self {
+            inter_arrival_time,
+            target,
+            max_jobs: None,
+            priority: 0,
+            generated: 0,
+            ctx,
+        }
+    }
+
+    /// Limits the number of generated jobs, by default the jobs are generated indefinitely.
+    pub fn set_max_jobs(&mut self, max_jobs: u64) {
+        self.max_jobs = Some(max_jobs);
+    }
+
+    /// Sets the priority of generated jobs.
+    pub fn set_priority(&mut self, priority: u32) {
+        self.priority = priority;
+    }
+
+    /// Returns the number of generated jobs.
+    pub fn generated_jobs(&self) -> u64 {
+        self.generated
+    }
+
+    /// Starts generation of jobs, the first job arrives after the inter-arrival time.
+    pub fn start(&mut self) {
+        self.schedule_next_job();
+    }
+
+    fn schedule_next_job(&mut self) {
+        if let Some(max_jobs) = self.max_jobs {
+            if self.generated >= max_jobs {
+                return;
+            }
+        }
+        let delay = self.inter_arrival_time.sample(&self.ctx);
+        self.ctx.emit_self(GenerateJob {}, delay);
+    }
+
+    fn generate_job(&mut self) {
+        let job = Job::new(self.generated).with_priority(self.priority);
+        self.generated += 1;
+        self.ctx.emit_now(JobArrival { job }, self.target);
+        self.schedule_next_job();
+    }
+}
+
+impl EventHandler for JobSource {
+    fn on(&mut self, event: Event) {
+        cast!(match event.data {
+            GenerateJob {} => {
+                self.generate_job();
+            }
+        })
+    }
+}
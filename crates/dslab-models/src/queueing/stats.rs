@@ -0,0 +1,108 @@
+/// Statistics of queue operation over the period from queue creation to the current time.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct QueueStats {
+    /// Number of arrived jobs.
+    pub arrived: u64,
+    /// Number of completed jobs.
+    pub completed: u64,
+    /// Number of jobs dropped due to full queue.
+    pub dropped: u64,
+    /// Mean time spent by completed jobs waiting in the queue.
+    pub mean_wait_time: f64,
+    /// Mean time spent by completed jobs in the queue including service.
+    pub mean_response_time: f64,
+    /// Maximum time spent by completed job in the queue including service.
+    pub max_response_time: f64,
+    /// Time-average number of jobs waiting in the queue.
+    pub mean_queue_length: f64,
+    /// Maximum number of jobs waiting in the queue.
+    pub max_queue_length: usize,
+    /// Time-average number of jobs in the queue including the served one.
+    pub mean_jobs_in_system: f64,
+    /// Fraction of time the server was busy.
+    pub utilization: f64,
+}
+
+impl QueueStats {
+    /// Returns the fraction of arrived jobs which were dropped.
+    pub fn loss_probability(&self) -> f64 {
+        if self.arrived == 0 {
+            0.
+        } else {
+            self.dropped as f64 / self.arrived as f64
+        }
+    }
+}
+
+/// Accumulates job times and time integrals of queue occupancy.
+#[derive(Default)]
+pub(crate) struct StatsCollector {
+    start_time: f64,
+    last_update: f64,
+    queue_length: usize,
+    busy: bool,
+    queue_length_area: f64,
+    busy_time: f64,
+    total_wait_time: f64,
+    total_response_time: f64,
+    stats: QueueStats,
+}
+
+impl StatsCollector {
+    pub fn new(start_time: f64) -> Self {
+        Self {
+            start_time,
+            last_update: start_time,
+            ..Default::default()
+        }
+    }
+
+    fn advance(&mut self, time: f64) {
+        let elapsed = time - self.last_update;
+        self.queue_length_area += self.queue_length as f64 * elapsed;
+        if self.busy {
+            self.busy_time += elapsed;
+        }
+        self.last_update = time;
+    }
+
+    pub fn on_arrival(&mut self) {
+        self.stats.arrived += 1;
+    }
+
+    pub fn on_drop(&mut self) {
+        self.stats.dropped += 1;
+    }
+
+    pub fn on_state_change(&mut self, time: f64, queue_length: usize, busy: bool) {
+        self.advance(time);
+        self.queue_length = queue_length;
+        self.busy = busy;
+        self.stats.max_queue_length = self.stats.max_queue_length.max(queue_length);
+    }
+
+    pub fn on_completion(&mut self, wait_time: f64, response_time: f64) {
+        self.stats.completed += 1;
+        self.total_wait_time += wait_time;
+        self.total_response_time += response_time;
+        self.stats.max_response_time = self.stats.max_response_time.max(response_time);
+    }
+
+    pub fn stats(&self, time: f64) -> QueueStats {
+        let mut stats = self.stats.clone();
+        if stats.completed > 0 {
+            stats.mean_wait_time = self.total_wait_time / stats.completed as f64;
+            stats.mean_response_time = self.total_response_time / stats.completed as f64;
+        }
+        let period = time - self.start_time;
+        if period > 0. {
+            let elapsed = time - self.last_update;
+            let queue_length_area = self.queue_length_area + self.queue_length as f64 * elapsed;
+            let busy_time = self.busy_time + if self.busy { elapsed } else { 0. };
+            stats.mean_queue_length = queue_length_area / period;
+            stats.utilization = busy_time / period;
+            stats.mean_jobs_in_system = stats.mean_queue_length + stats.utilization;
+        }
+        stats
+    }
+}
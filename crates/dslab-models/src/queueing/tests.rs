@@ -0,0 +1,368 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use sugars::{rc, refcell};
+
+use dslab_core::component::Id;
+use dslab_core::{cast, Event, EventHandler, Simulation, SimulationContext};
+
+use super::{
+    BackendState, DispatchPolicy, Job, JobArrival, JobCompleted, JobDropped, JobSource, JoinShortestQueue,
+    LeastConnections, LoadBalancer, PowerOfTwoChoices, QueueDiscipline, QueueServer, Random, RoundRobin,
+    TimeDistribution, WeightedRoundRobin,
+};
+
+fn assert_float_eq(x: f64, y: f64, eps: f64) {
+    assert!((x - y).abs() / y < eps, "Values do not match: {:.6} vs {:.6}", x, y);
+}
+
+#[derive(Default)]
+struct Listener {
+    completed: Vec<(u64, f64)>,
+    dropped: Vec<u64>,
+}
+
+impl EventHandler for Listener {
+    fn on(&mut self, event: Event) {
+        cast!(match event.data {
+            JobCompleted { job, finish_time, .. } => {
+                self.completed.push((job.id, finish_time));
+            }
+            JobDropped { job, .. } => {
+                self.dropped.push(job.id);
+            }
+        })
+    }
+}
+
+fn make_queue(sim: &mut Simulation, name: &str, service_time: TimeDistribution) -> Rc<RefCell<QueueServer>> {
+    let queue = rc!(refcell!(QueueServer::new(service_time, sim.create_context(name))));
+    sim.add_handler(name, queue.clone());
+    queue
+}
+
+fn make_listener(sim: &mut Simulation) -> (Rc<RefCell<Listener>>, Id) {
+    let listener = rc!(refcell!(Listener::default()));
+    let id = sim.add_handler("listener", listener.clone());
+    (listener, id)
+}
+
+fn submit(sim: &mut Simulation, jobs: Vec<Job>, target: Id) {
+    let client = sim.create_context("client");
+    for job in jobs {
+        client.emit_now(JobArrival { job }, target);
+    }
+}
+
+#[test]
+fn fifo_queue() {
+    let mut sim = Simulation::new(123);
+    let queue = make_queue(&mut sim, "queue", TimeDistribution::Constant(2.));
+    let (listener, listener_id) = make_listener(&mut sim);
+    queue.borrow_mut().set_listener(listener_id);
+    let queue_id = queue.borrow().id();
+    submit(&mut sim, (0..3).map(Job::new).collect(), queue_id);
+    sim.step_until_no_events();
+
+    assert_eq!(listener.borrow().completed, vec![(0, 2.), (1, 4.), (2, 6.)]);
+    let stats = queue.borrow().stats();
+    assert_eq!(stats.arrived, 3);
+    assert_eq!(stats.completed, 3);
+    assert_eq!(stats.mean_wait_time, 2.);
+    assert_eq!(stats.mean_response_time, 4.);
+    assert_eq!(stats.max_response_time, 6.);
+    assert_eq!(stats.max_queue_length, 2);
+    // 2 jobs wait for 2 seconds, 1 job waits for 2 more seconds
+    assert_eq!(stats.mean_queue_length, 1.);
+    assert_eq!(stats.utilization, 1.);
+    assert_eq!(stats.mean_jobs_in_system, 2.);
+}
+
+#[test]
+fn queue_disciplines() {
+    let cases = [
+        (QueueDiscipline::Lifo, vec![0, 3, 2, 1]),
+        (QueueDiscipline::Priority, vec![0, 2, 3, 1]),
+        (QueueDiscipline::ShortestJobFirst, vec![0, 3, 1, 2]),
+    ];
+    for (discipline, expected) in cases {
+        let mut sim = Simulation::new(123);
+        let queue = make_queue(&mut sim, "queue", TimeDistribution::Constant(1.));
+        let (listener, listener_id) = make_listener(&mut sim);
+        queue.borrow_mut().set_listener(listener_id);
+        queue.borrow_mut().set_discipline(discipline);
+        let queue_id = queue.borrow().id();
+        let jobs = vec![
+            Job::new(0),
+            Job::new(1).with_service_time(2.),
+            Job::new(2).with_priority(2).with_service_time(3.),
+            Job::new(3).with_priority(1),
+        ];
+        submit(&mut sim, jobs, queue_id);
+        sim.step_until_no_events();
+
+        let order: Vec<u64> = listener.borrow().completed.iter().map(|(id, _)| *id).collect();
+        assert_eq!(order, expected, "{:?}", discipline);
+    }
+}
+
+#[test]
+fn finite_buffer_with_loss() {
+    let mut sim = Simulation::new(123);
+    let queue = make_queue(&mut sim, "queue", TimeDistribution::Constant(1.));
+    let (listener, listener_id) = make_listener(&mut sim);
+    queue.borrow_mut().set_listener(listener_id);
+    queue.borrow_mut().set_capacity(1);
+    let queue_id = queue.borrow().id();
+    submit(&mut sim, (0..4).map(Job::new).collect(), queue_id);
+    sim.step_until_no_events();
+
+    assert_eq!(listener.borrow().completed, vec![(0, 1.), (1, 2.)]);
+    assert_eq!(listener.borrow().dropped, vec![2, 3]);
+    let stats = queue.borrow().stats();
+    assert_eq!(stats.dropped, 2);
+    assert_eq!(stats.loss_probability(), 0.5);
+}
+
+#[test]
+fn mm1_queue() {
+    let mut sim = Simulation::new(123);
+    let queue = make_queue(&mut sim, "queue", TimeDistribution::Exponential { rate: 1. });
+    let queue_id = queue.borrow().id();
+    let source = rc!(refcell!(JobSource::new(
+        TimeDistribution::Exponential { rate: 0.5 },
+        queue_id,
+        sim.create_context("source")
+    )));
+    sim.add_handler("source", source.clone());
+    source.borrow_mut().set_max_jobs(100000);
+    source.borrow_mut().start();
+    sim.step_until_no_events();
+
+    let stats = queue.borrow().stats();
+    assert_eq!(stats.completed, 100000);
+    // for rho = 0.5 the mean response time is 1 / (mu - lambda) = 2 and the mean number of jobs is rho / (1 - rho) = 1
+    assert_float_eq(stats.mean_response_time, 2., 0.05);
+    assert_float_eq(stats.mean_wait_time, 1., 0.05);
+    assert_float_eq(stats.utilization, 0.5, 0.05);
+    assert_float_eq(stats.mean_jobs_in_system, 1., 0.05);
+}
+
+#[test]
+fn mm1k_loss_probability() {
+    let mut sim = Simulation::new(123);
+    let queue = make_queue(&mut sim, "queue", TimeDistribution::Exponential { rate: 1. });
+    queue.borrow_mut().set_capacity(2);
+    let queue_id = queue.borrow().id();
+    let source = rc!(refcell!(JobSource::new(
+        TimeDistribution::Exponential { rate: 1. },
+        queue_id,
+        sim.create_context("source")
+    )));
+    sim.add_handler("source", source.clone());
+    source.borrow_mut().set_max_jobs(100000);
+    source.borrow_mut().start();
+    sim.step_until_no_events();
+
+    // for rho = 1 and K = 3 jobs in system the loss probability is 1 / (K + 1)
+    assert_float_eq(queue.borrow().stats().loss_probability(), 0.25, 0.05);
+}
+
+struct BalancerSetup {
+    sim: Simulation,
+    balancer: Rc<RefCell<LoadBalancer>>,
+    balancer_id: Id,
+    queues: Vec<Rc<RefCell<QueueServer>>>,
+}
+
+fn make_balancer(policy: Box<dyn DispatchPolicy>, weights: &[u32], service_time: TimeDistribution) -> BalancerSetup {
+    let mut sim = Simulation::new(123);
+    let balancer = rc!(refcell!(LoadBalancer::new(policy, sim.create_context("balancer"))));
+    let balancer_id = sim.add_handler("balancer", balancer.clone());
+    let mut queues = Vec::new();
+    for (i, weight) in weights.iter().enumerate() {
+        let queue = make_queue(&mut sim, &format!("queue{}", i), service_time.clone());
+        balancer.borrow_mut().add_queue(&queue, *weight);
+        queues.push(queue);
+    }
+    BalancerSetup {
+        sim,
+        balancer,
+        balancer_id,
+        queues,
+    }
+}
+
+fn dispatched_jobs(balancer: &Rc<RefCell<LoadBalancer>>) -> Vec<u64> {
+    balancer.borrow().backends().iter().map(|b| b.dispatched).collect()
+}
+
+#[test]
+fn round_robin_balancer() {
+    let mut setup = make_balancer(Box::new(RoundRobin::new()), &[1, 1], TimeDistribution::Constant(1.));
+    let (listener, listener_id) = make_listener(&mut setup.sim);
+    setup.balancer.borrow_mut().set_listener(listener_id);
+    submit(&mut setup.sim, (0..4).map(Job::new).collect(), setup.balancer_id);
+    setup.sim.step_until_no_events();
+
+    assert_eq!(listener.borrow().completed, vec![(0, 1.), (1, 1.), (2, 2.), (3, 2.)]);
+    assert_eq!(dispatched_jobs(&setup.balancer), vec![2, 2]);
+    assert!(setup.balancer.borrow().backends().iter().all(|b| b.outstanding == 0));
+    let stats = setup.balancer.borrow().stats();
+    assert_eq!(stats.policy, "RoundRobin");
+    assert_eq!(stats.completed, 4);
+    assert_eq!(stats.mean_latency, 1.5);
+    assert_eq!(stats.p50_latency, 1.);
+    assert_eq!(stats.p99_latency, 2.);
+    assert_eq!(stats.max_latency, 2.);
+}
+
+#[test]
+fn weighted_balancers() {
+    let mut setup = make_balancer(
+        Box::new(WeightedRoundRobin::new()),
+        &[2, 1],
+        TimeDistribution::Constant(1.),
+    );
+    let (listener, listener_id) = make_listener(&mut setup.sim);
+    setup.balancer.borrow_mut().set_listener(listener_id);
+    submit(&mut setup.sim, (0..6).map(Job::new).collect(), setup.balancer_id);
+    setup.sim.step_until_no_events();
+    assert_eq!(dispatched_jobs(&setup.balancer), vec![4, 2]);
+    // smooth weighted round robin interleaves the backends: jobs 0, 2, 3, 5 go to the first queue
+    let mut completed = listener.borrow().completed.clone();
+    completed.sort_by_key(|(id, _)| *id);
+    let finish_times: Vec<f64> = completed.iter().map(|(_, time)| *time).collect();
+    assert_eq!(finish_times, vec![1., 1., 2., 3., 2., 4.]);
+
+    let mut setup = make_balancer(Box::new(Random::new()), &[3, 1], TimeDistribution::Constant(1.));
+    submit(&mut setup.sim, (0..1000).map(Job::new).collect(), setup.balancer_id);
+    setup.sim.step_until_no_events();
+    let dispatched = dispatched_jobs(&setup.balancer);
+    assert!(dispatched[0] > 700 && dispatched[0] < 800, "{:?}", dispatched);
+}
+
+#[test]
+fn join_shortest_queue_balancer() {
+    let mut setup = make_balancer(
+        Box::new(JoinShortestQueue::new()),
+        &[1, 1],
+        TimeDistribution::Constant(1.),
+    );
+    let client = setup.sim.create_context("client");
+    // long job occupies the first queue, so the next jobs go to the second queue which is empty on their arrival
+    client.emit_now(
+        JobArrival {
+            job: Job::new(0).with_service_time(10.),
+        },
+        setup.balancer_id,
+    );
+    for i in 1..4 {
+        client.emit(JobArrival { job: Job::new(i) }, setup.balancer_id, 1.5 * i as f64);
+    }
+    setup.sim.step_until_no_events();
+
+    assert_eq!(setup.queues[0].borrow().stats().completed, 1);
+    assert_eq!(setup.queues[1].borrow().stats().completed, 3);
+}
+
+// Backend which completes each job after fixed delay without queueing.
+struct DelayBackend {
+    delay: f64,
+    ctx: SimulationContext,
+}
+
+impl EventHandler for DelayBackend {
+    fn on(&mut self, event: Event) {
+        cast!(match event.data {
+            JobArrival { job } => {
+                let completed = JobCompleted {
+                    job,
+                    queue: self.ctx.id(),
+                    arrival_time: event.time,
+                    start_time: event.time,
+                    finish_time: event.time + self.delay,
+                };
+                self.ctx.emit(completed, event.src, self.delay);
+            }
+        })
+    }
+}
+
+#[test]
+fn least_connections_with_custom_backends() {
+    let mut sim = Simulation::new(123);
+    let balancer = rc!(refcell!(LoadBalancer::new(
+        Box::new(LeastConnections::new()),
+        sim.create_context("balancer")
+    )));
+    let balancer_id = sim.add_handler("balancer", balancer.clone());
+    for (i, delay) in [10., 1.].iter().enumerate() {
+        let name = format!("backend{}", i);
+        let backend = rc!(refcell!(DelayBackend {
+            delay: *delay,
+            ctx: sim.create_context(&name)
+        }));
+        let backend_id = sim.add_handler(&name, backend);
+        balancer.borrow_mut().add_backend(backend_id, 1);
+    }
+    let client = sim.create_context("client");
+    for i in 0..6 {
+        client.emit(JobArrival { job: Job::new(i) }, balancer_id, 2. * i as f64);
+    }
+    sim.step_until_no_events();
+
+    // the slow backend holds the first job, so the other jobs go to the fast backend
+    assert_eq!(dispatched_jobs(&balancer), vec![1, 5]);
+    let stats = balancer.borrow().stats();
+    assert_eq!(stats.completed, 6);
+    assert_eq!(stats.max_latency, 10.);
+}
+
+// Always selects the last backend.
+struct LastBackend {}
+
+impl DispatchPolicy for LastBackend {
+    fn name(&self) -> String {
+        "LastBackend".to_string()
+    }
+
+    fn select(&mut self, backends: &[BackendState], _ctx: &SimulationContext) -> usize {
+        backends.len() - 1
+    }
+}
+
+#[test]
+fn custom_policy() {
+    let mut setup = make_balancer(Box::new(LastBackend {}), &[1, 1, 1], TimeDistribution::Constant(1.));
+    submit(&mut setup.sim, (0..3).map(Job::new).collect(), setup.balancer_id);
+    setup.sim.step_until_no_events();
+    assert_eq!(dispatched_jobs(&setup.balancer), vec![0, 0, 3]);
+    assert_eq!(setup.balancer.borrow().stats().policy, "LastBackend");
+}
+
+#[test]
+fn policy_comparison() {
+    let mut mean_latency = Vec::new();
+    for policy in [
+        Box::new(Random::new()) as Box<dyn DispatchPolicy>,
+        Box::new(PowerOfTwoChoices::new()),
+        Box::new(JoinShortestQueue::new()),
+    ] {
+        let mut setup = make_balancer(policy, &[1, 1, 1, 1], TimeDistribution::Exponential { rate: 1. });
+        let source = rc!(refcell!(JobSource::new(
+            TimeDistribution::Exponential { rate: 3.6 },
+            setup.balancer_id,
+            setup.sim.create_context("source")
+        )));
+        setup.sim.add_handler("source", source.clone());
+        source.borrow_mut().set_max_jobs(20000);
+        source.borrow_mut().start();
+        setup.sim.step_until_no_events();
+        mean_latency.push(setup.balancer.borrow().stats().mean_latency);
+    }
+    // with load 0.9 random dispatching corresponds to 4 independent M/M/1 queues with mean latency 10
+    assert_float_eq(mean_latency[0], 10., 0.2);
+    assert!(mean_latency[1] < mean_latency[0] / 2.);
+    assert!(mean_latency[2] < mean_latency[1]);
+}
@@ -99,6 +99,12 @@ pub enum McEvent {
         /// The id of original MessageReceived event.
         receive_event_id: McEventId,
     },
+
+    /// The event of a node crash. Created by model checking strategy.
+    NodeCrashed {
+        /// The name of the crashed node.
+        node: String,
+    },
 }
 
 impl McEvent {
@@ -187,6 +193,7 @@ impl McEvent {
                 src: src.clone(),
                 dst: dst.clone(),
             },
+            Self::NodeCrashed { node } => LogEntry::McNodeCrashed { node: node.clone() },
         }
     }
 }
@@ -197,6 +197,10 @@ impl McNode {
         self.is_crashed = state.is_crashed;
     }
 
+    pub(crate) fn is_crashed(&self) -> bool {
+        self.is_crashed
+    }
+
     pub(crate) fn crash(&mut self) {
         self.is_crashed = true;
     }
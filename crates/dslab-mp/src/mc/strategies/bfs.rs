@@ -19,6 +19,8 @@ pub struct Bfs {
     execution_mode: ExecutionMode,
     stats: McStats,
     visited: VisitedStates,
+    max_node_crashes: u64,
+    crashable_nodes: Option<Vec<String>>,
 }
 
 impl Bfs {
@@ -39,6 +41,7 @@ impl Bfs {
             for event_id in available_events {
                 self.process_event(system, event_id)?;
             }
+            self.explore_node_crashes(system)?;
         }
 
         Ok(())
@@ -56,6 +59,8 @@ impl Strategy for Bfs {
             states_queue: VecDeque::default(),
             stats: McStats::default(),
             visited: config.visited_states,
+            max_node_crashes: config.max_node_crashes,
+            crashable_nodes: config.crashable_nodes,
         }
     }
 
@@ -100,6 +105,14 @@ impl Strategy for Bfs {
         &mut self.stats
     }
 
+    fn max_node_crashes(&self) -> u64 {
+        self.max_node_crashes
+    }
+
+    fn crashable_nodes(&self) -> Option<&Vec<String>> {
+        self.crashable_nodes.as_ref()
+    }
+
     fn reset(&mut self) {
         self.states_queue.clear();
     }
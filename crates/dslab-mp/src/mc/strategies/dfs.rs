@@ -16,6 +16,8 @@ pub struct Dfs {
     execution_mode: ExecutionMode,
     stats: McStats,
     visited: VisitedStates,
+    max_node_crashes: u64,
+    crashable_nodes: Option<Vec<String>>,
 }
 
 impl Dfs {
@@ -29,7 +31,7 @@ impl Dfs {
         for event_id in available_events {
             self.process_event(system, event_id)?;
         }
-        Ok(())
+        self.explore_node_crashes(system)
     }
 }
 
@@ -43,6 +45,8 @@ impl Strategy for Dfs {
             execution_mode: config.execution_mode,
             stats: McStats::default(),
             visited: config.visited_states,
+            max_node_crashes: config.max_node_crashes,
+            crashable_nodes: config.crashable_nodes,
         }
     }
 
@@ -88,5 +92,13 @@ impl Strategy for Dfs {
         &mut self.stats
     }
 
+    fn max_node_crashes(&self) -> u64 {
+        self.max_node_crashes
+    }
+
+    fn crashable_nodes(&self) -> Option<&Vec<String>> {
+        self.crashable_nodes.as_ref()
+    }
+
     fn reset(&mut self) {}
 }
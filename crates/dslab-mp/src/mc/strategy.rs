@@ -11,7 +11,7 @@ use sugars::boxed;
 
 use crate::mc::error::McError;
 use crate::mc::events::McEvent::{
-    MessageCorrupted, MessageDropped, MessageDuplicated, MessageReceived, TimerCancelled, TimerFired,
+    MessageCorrupted, MessageDropped, MessageDuplicated, MessageReceived, NodeCrashed, TimerCancelled, TimerFired,
 };
 use crate::mc::events::{McEvent, McEventId};
 use crate::mc::network::DeliveryOptions;
@@ -29,6 +29,8 @@ pub struct StrategyConfig {
     pub(crate) collect: CollectFn,
     pub(crate) execution_mode: ExecutionMode,
     pub(crate) visited_states: VisitedStates,
+    pub(crate) max_node_crashes: u64,
+    pub(crate) crashable_nodes: Option<Vec<String>>,
 }
 
 impl Default for StrategyConfig {
@@ -40,6 +42,8 @@ impl Default for StrategyConfig {
             collect: boxed!(predicates::default_collect),
             execution_mode: ExecutionMode::Default,
             visited_states: VisitedStates::Partial(HashSet::default()),
+            max_node_crashes: 0,
+            crashable_nodes: None,
         }
     }
 }
@@ -80,6 +84,21 @@ impl StrategyConfig {
         self.visited_states = visited_states;
         self
     }
+
+    /// Sets the maximum number of node crashes explored in a single execution (0 by default).
+    ///
+    /// If set to a non-zero value, the strategy additionally explores the executions
+    /// where any of the crashable nodes crashes at any step.
+    pub fn max_node_crashes(mut self, max_node_crashes: u64) -> Self {
+        self.max_node_crashes = max_node_crashes;
+        self
+    }
+
+    /// Sets the nodes which can be crashed by the strategy (all nodes by default).
+    pub fn crashable_nodes(mut self, nodes: Vec<String>) -> Self {
+        self.crashable_nodes = Some(nodes);
+        self
+    }
 }
 
 /// Defines the mode in which the model checking algorithm is executing.
@@ -228,6 +247,25 @@ pub trait Strategy {
         Ok(())
     }
 
+    /// Explores the system states reachable from the current state after crashing one of the crashable nodes.
+    /// Does nothing if the maximum number of node crashes is already reached.
+    fn explore_node_crashes(&mut self, system: &mut McSystem) -> Result<(), McError> {
+        if system.crashed_node_count() >= self.max_node_crashes() {
+            return Ok(());
+        }
+        let mut nodes = match self.crashable_nodes() {
+            Some(nodes) => nodes.clone(),
+            None => system.nodes(),
+        };
+        nodes.sort();
+        for node in nodes {
+            if !system.node_is_crashed(&node) {
+                self.search_step(system, EventOrId::Event(NodeCrashed { node }))?;
+            }
+        }
+        Ok(())
+    }
+
     /// Applies the specified event to the system, calls `search_step_impl` with the produced state
     /// and restores the system state afterwards.
     fn search_step(&mut self, system: &mut McSystem, event: EventOrId) -> Result<(), McError> {
@@ -363,6 +401,9 @@ pub trait Strategy {
                     )
                     .blue());
                 }
+                NodeCrashed { node } => {
+                    t!(format!("{:>10} | {:>10} <-- node crashed", depth, node).red());
+                }
             }
         }
     }
@@ -458,4 +499,10 @@ pub trait Strategy {
 
     /// Returns the model checking execution stats.
     fn stats(&mut self) -> &mut McStats;
+
+    /// Returns the maximum number of node crashes explored in a single execution.
+    fn max_node_crashes(&self) -> u64;
+
+    /// Returns the nodes which can be crashed by the strategy or `None` if all nodes can be crashed.
+    fn crashable_nodes(&self) -> Option<&Vec<String>>;
 }
@@ -87,13 +87,17 @@ impl McSystem {
         self.trace_handler
             .borrow_mut()
             .push(LogEntry::McNodeCrashed { node: node.clone() });
-        self.net.disconnect_node(&node);
-        for proc in self.nodes[&node].processes.keys() {
+        self.crash_node_impl(&node);
+    }
+
+    fn crash_node_impl(&mut self, node: &str) {
+        self.net.disconnect_node(node);
+        for proc in self.nodes[node].processes.keys() {
             for destruction_event in self.events.cancel_proc_events(proc) {
                 self.trace_handler.borrow_mut().push(destruction_event.to_log_entry());
             }
         }
-        self.nodes.get_mut(&node).unwrap().crash();
+        self.nodes.get_mut(node).unwrap().crash();
     }
 
     /// Returns a mutable reference to [`McNetwork`].
@@ -127,6 +131,10 @@ impl McSystem {
                     .unwrap()
                     .on_timer_fired(proc, timer, event_time, state_hash)
             }
+            McEvent::NodeCrashed { node } => {
+                self.crash_node_impl(&node);
+                vec![]
+            }
             _ => vec![],
         };
         self.add_events(new_events);
@@ -159,6 +167,14 @@ impl McSystem {
         self.events.available_events(&self.event_ordering_mode)
     }
 
+    pub(crate) fn node_is_crashed(&self, node: &str) -> bool {
+        self.nodes[node].is_crashed()
+    }
+
+    pub(crate) fn crashed_node_count(&self) -> u64 {
+        self.nodes.values().filter(|node| node.is_crashed()).count() as u64
+    }
+
     pub(crate) fn depth(&self) -> u64 {
         self.depth
     }
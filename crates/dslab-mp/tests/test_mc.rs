@@ -1231,3 +1231,42 @@ fn crash_node(#[case] strategy_name: &str) {
     });
     assert!(result.is_ok());
 }
+
+#[rstest]
+#[case("dfs")]
+#[case("bfs")]
+fn explore_node_crashes(#[case] strategy_name: &str) {
+    let build_config = |max_node_crashes: u64| {
+        let prune = boxed!(|_: &McState| None);
+        let goal = build_no_events_left_goal();
+        let invariant = boxed!(|state: &McState| {
+            if !state.events.is_empty()
+                || !state.node_states["node2"].proc_states["process2"]
+                    .local_outbox
+                    .is_empty()
+            {
+                Ok(())
+            } else {
+                Err("message is not delivered".to_string())
+            }
+        });
+        build_strategy_config(prune, goal, invariant)
+            .max_node_crashes(max_node_crashes)
+            .crashable_nodes(str_vec!["node2"])
+    };
+
+    let result = run_mc!(build_ping_system(), build_config(0), strategy_name, |mc_sys| {
+        mc_sys.send_local_message("node1", "process1", Message::new("PING", "some_data"));
+    });
+    assert!(result.is_ok());
+
+    let result = run_mc!(build_ping_system(), build_config(1), strategy_name, |mc_sys| {
+        mc_sys.send_local_message("node1", "process1", Message::new("PING", "some_data"));
+    });
+    let err = result.unwrap_err();
+    assert_eq!(err.message(), "message is not delivered");
+    assert!(err
+        .trace()
+        .iter()
+        .any(|entry| matches!(entry, LogEntry::McNodeCrashed { node } if node == "node2")));
+}
@@ -14,6 +14,11 @@
 //! [`RoutingAlgorithm`](crate::routing::RoutingAlgorithm) to compute paths between the nodes. The link's bandwidth is
 //! shared fairly among the transfers using the link.
 //!
+//! ## Data distribution
+//!
+//! - [`P2pDistribution`](crate::p2p::P2pDistribution): BitTorrent-like distribution of data chunks among peers with
+//!   limited upload and download slots, e.g. for modeling VM image distribution or dataset dissemination across hosts.
+//!
 //! ## Examples
 //!
 //! - [network-simple](https://github.com/osukhoroslov/dslab/tree/main/examples/network-simple): demonstrates the use of
@@ -31,6 +36,7 @@ pub mod model;
 pub mod models;
 pub mod network;
 pub mod node;
+pub mod p2p;
 pub mod routing;
 pub mod topology;
 
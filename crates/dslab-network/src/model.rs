@@ -5,7 +5,7 @@ use serde::Serialize;
 use dslab_core::component::Id;
 use dslab_core::context::SimulationContext;
 
-use crate::{NodeId, Topology};
+use crate::{LinkId, NodeId, Topology};
 
 /// Represents a data transfer between two simulation components located on a network.
 #[derive(Clone, Debug, Serialize)]
@@ -62,6 +62,26 @@ pub trait NetworkModel {
     /// This is necessary since the model itself does not receive the [`DataTransferCompleted`] event.
     fn on_transfer_completion(&mut self, dt: DataTransfer, ctx: &mut SimulationContext);
 
+    /// Cancels the data transfer previously started by the model, so that the transfer stops consuming
+    /// the bandwidth and its [`DataTransferCompleted`] event is not emitted.
+    ///
+    /// Returns false if the model does not support the cancellation, in this case the transfer runs until
+    /// its completion, but the completion is not reported to the transfer recipients.
+    fn cancel_transfer(&mut self, _transfer_id: usize, _ctx: &mut SimulationContext) -> bool {
+        false
+    }
+
+    /// Returns the links on the path from node `src` to node `dst`, or `None` if there is no path.
+    ///
+    /// Must be implemented for topology-aware model.
+    fn path(&self, _src: NodeId, _dst: NodeId) -> Option<Vec<LinkId>> {
+        assert!(
+            !self.is_topology_aware(),
+            "This method must be implemented for topology-aware model"
+        );
+        None
+    }
+
     /// Returns a reference to inner network topology.
     ///
     /// Must be implemented for topology-aware model.
@@ -1,6 +1,9 @@
 //! Network model without congestion where each transfer gets the full bandwidth.
 
+use std::collections::HashMap;
+
 use dslab_core::context::SimulationContext;
+use dslab_core::event::EventId;
 
 use crate::{DataTransfer, DataTransferCompleted, NetworkModel, NodeId};
 
@@ -8,12 +11,17 @@ use crate::{DataTransfer, DataTransferCompleted, NetworkModel, NodeId};
 pub struct ConstantBandwidthNetworkModel {
     bandwidth: f64,
     latency: f64,
+    completion_events: HashMap<usize, EventId>,
 }
 
 impl ConstantBandwidthNetworkModel {
     /// Creates a new network model with specified bandwidth and latency.
     pub fn new(bandwidth: f64, latency: f64) -> ConstantBandwidthNetworkModel {
-        ConstantBandwidthNetworkModel { bandwidth, latency }
+        ConstantBandwidthNetworkModel {
+            bandwidth,
+            latency,
+            completion_events: HashMap::new(),
+        }
     }
 }
 
@@ -32,8 +40,19 @@ impl NetworkModel for ConstantBandwidthNetworkModel {
 
     fn start_transfer(&mut self, dt: DataTransfer, ctx: &mut SimulationContext) {
         let data_transfer_time = dt.size / self.bandwidth;
-        ctx.emit_self(DataTransferCompleted { dt }, data_transfer_time);
+        let transfer_id = dt.id;
+        let event_id = ctx.emit_self(DataTransferCompleted { dt }, data_transfer_time);
+        self.completion_events.insert(transfer_id, event_id);
+    }
+
+    fn on_transfer_completion(&mut self, dt: DataTransfer, _ctx: &mut SimulationContext) {
+        self.completion_events.remove(&dt.id);
     }
 
-    fn on_transfer_completion(&mut self, _dt: DataTransfer, _ctx: &mut SimulationContext) {}
+    fn cancel_transfer(&mut self, transfer_id: usize, ctx: &mut SimulationContext) -> bool {
+        if let Some(event_id) = self.completion_events.remove(&transfer_id) {
+            ctx.cancel_event(event_id);
+        }
+        true
+    }
 }
@@ -415,6 +415,12 @@ impl NetworkModel for TopologyAwareNetworkModel {
         self.topology.get_path_latency(path)
     }
 
+    fn path(&self, src: NodeId, dst: NodeId) -> Option<Vec<LinkId>> {
+        self.routing
+            .get_path_iter(src, dst, &self.topology)
+            .map(|path| path.collect())
+    }
+
     fn start_transfer(&mut self, dt: DataTransfer, ctx: &mut SimulationContext) {
         self.validate_array_lengths();
         let path = self
@@ -464,6 +470,36 @@ impl NetworkModel for TopologyAwareNetworkModel {
         self.update_next_event(ctx);
     }
 
+    fn cancel_transfer(&mut self, transfer_id: usize, ctx: &mut SimulationContext) -> bool {
+        if !self.current_transfers.contains_key(&transfer_id) {
+            return true;
+        }
+        self.validate_array_lengths();
+        let affected_transfers = if self.full_mesh_optimization {
+            let mut transfers = self.get_affected_transfers(transfer_id);
+            transfers.remove(&transfer_id);
+            transfers
+        } else {
+            HashSet::new()
+        };
+        let transfer = self.current_transfers.remove(&transfer_id).unwrap();
+        for &link in transfer.path.iter() {
+            let vec = self.transfers_through_link.get_mut(link).unwrap();
+            vec.remove(vec.iter().position(|&x| x == transfer_id).unwrap());
+        }
+        if let Some(event_id) = self.next_event.take() {
+            ctx.cancel_event(event_id);
+        }
+        self.next_event_index = None;
+        if self.full_mesh_optimization {
+            self.calc(ctx, affected_transfers);
+        } else {
+            self.calc_all(ctx);
+        }
+        self.update_next_event(ctx);
+        true
+    }
+
     fn topology(&self) -> Option<&Topology> {
         Some(&self.topology)
     }
@@ -1,7 +1,7 @@
 //! Simulation component representing a network.
 
 use indexmap::IndexMap;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::atomic::{AtomicUsize, Ordering};
 
 use serde::Serialize;
@@ -47,6 +47,9 @@ pub struct Network {
     network_model: Box<dyn NetworkModel>,
     local_models: HashMap<NodeId, Box<dyn NetworkModel>>,
     locations: HashMap<Id, NodeId>,
+    pending_transfers: HashMap<usize, EventId>,
+    active_transfers: HashMap<usize, (NodeId, NodeId)>,
+    cancelled_transfers: HashSet<usize>,
     next_dt_id: AtomicUsize,
     next_msg_id: AtomicUsize,
     topology_initialized: bool,
@@ -61,6 +64,9 @@ impl Network {
             network_model: model,
             local_models: HashMap::new(),
             locations: HashMap::new(),
+            pending_transfers: HashMap::new(),
+            active_transfers: HashMap::new(),
+            cancelled_transfers: HashSet::new(),
             next_dt_id: AtomicUsize::new(0),
             next_msg_id: AtomicUsize::new(0),
             topology_initialized: false,
@@ -215,6 +221,23 @@ impl Network {
         }
     }
 
+    /// Returns the links on the path between two simulation components.
+    ///
+    /// Returns `None` if the network model is not topology-aware or there is no path between the components.
+    pub fn path(&self, src: Id, dst: Id) -> Option<Vec<LinkId>> {
+        if !self.network_model.is_topology_aware() {
+            return None;
+        }
+        let src_node_id = self.get_location(src);
+        let dst_node_id = self.get_location(dst);
+        self.network_model.path(src_node_id, dst_node_id)
+    }
+
+    /// Returns the link by its id, or `None` if the network model is not topology-aware.
+    pub fn link(&self, link_id: LinkId) -> Option<Link> {
+        self.network_model.topology().map(|topology| *topology.link(link_id))
+    }
+
     // Operations ------------------------------------------------------------------------------------------------------
 
     /// Starts a data transfer between two simulation components, returns unique transfer id.
@@ -246,10 +269,38 @@ impl Network {
         // The fixed part of data transfer time (latency) is modeled by the delayed StartDataTransfer event.
         // The remaining part is calculated by the underlying network model (see handling of StartDataTransfer event).
         let delay = self.latency(src, dst);
-        self.ctx.emit_self(StartDataTransfer { dt }, delay);
+        let event_id = self.ctx.emit_self(StartDataTransfer { dt }, delay);
+        self.pending_transfers.insert(transfer_id, event_id);
         transfer_id
     }
 
+    /// Cancels the data transfer, so that the [`DataTransferCompleted`] event is not sent.
+    /// Returns false if the transfer is unknown or already completed.
+    ///
+    /// The cancelled transfer immediately stops consuming the network bandwidth, unless the underlying network model
+    /// does not support the cancellation (see [`NetworkModel::cancel_transfer`]).
+    pub fn cancel_transfer(&mut self, transfer_id: usize) -> bool {
+        if let Some(event_id) = self.pending_transfers.remove(&transfer_id) {
+            self.ctx.cancel_event(event_id);
+        } else if let Some((src_node_id, dst_node_id)) = self.active_transfers.remove(&transfer_id) {
+            let model = if src_node_id == dst_node_id {
+                self.local_models.get_mut(&src_node_id).unwrap()
+            } else {
+                &mut self.network_model
+            };
+            if !model.cancel_transfer(transfer_id, &mut self.ctx) {
+                self.active_transfers.insert(transfer_id, (src_node_id, dst_node_id));
+                if !self.cancelled_transfers.insert(transfer_id) {
+                    return false;
+                }
+            }
+        } else {
+            return false;
+        }
+        log_debug!(self.ctx, "cancelled data transfer {}", transfer_id);
+        true
+    }
+
     /// Sends a message between two simulation components, returns unique message id.
     ///
     /// The network locations of these components must be previously registered via [`Self::set_location`].
@@ -284,6 +335,8 @@ impl EventHandler for Network {
     fn on(&mut self, event: Event) {
         cast!(match event.data {
             StartDataTransfer { dt } => {
+                self.pending_transfers.remove(&dt.id);
+                self.active_transfers.insert(dt.id, (dt.src_node_id, dt.dst_node_id));
                 let model = if dt.src_node_id == dt.dst_node_id {
                     self.local_models.get_mut(&dt.src_node_id).unwrap()
                 } else {
@@ -306,8 +359,12 @@ impl EventHandler for Network {
                     &mut self.network_model
                 };
                 model.on_transfer_completion(dt.clone(), &mut self.ctx);
-                let notification_dst = dt.notification_dst;
-                self.ctx.emit_now(DataTransferCompleted { dt }, notification_dst);
+                self.active_transfers.remove(&dt.id);
+                // completion of cancelled transfer is not reported if the model does not support cancellation
+                if !self.cancelled_transfers.remove(&dt.id) {
+                    let notification_dst = dt.notification_dst;
+                    self.ctx.emit_now(DataTransferCompleted { dt }, notification_dst);
+                }
             }
         })
     }
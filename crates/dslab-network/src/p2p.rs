@@ -0,0 +1,372 @@
+//! Peer-to-peer data distribution model.
+//!
+//! It models the BitTorrent-like distribution of some data (e.g. VM image or dataset) among a set of peers located on
+//! the network nodes. The data is split into chunks of equal size which are exchanged between the peers: each peer
+//! downloads the missing chunks from other peers which already have them, and uploads its chunks to other peers.
+//! The distribution starts from one or more seeds having the whole data.
+//!
+//! Each peer has a limited number of upload and download slots, i.e. the maximum number of concurrent chunk transfers
+//! sent and received by the peer. The bandwidth of chunk transfers is determined by the used network model, e.g. the
+//! upload capacity of peers can be modeled by connecting each peer to the network via a separate link in
+//! [`TopologyAwareNetworkModel`](crate::models::TopologyAwareNetworkModel).
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::rc::Rc;
+
+use serde::Serialize;
+
+use dslab_core::component::Id;
+use dslab_core::context::SimulationContext;
+use dslab_core::event::Event;
+use dslab_core::handler::EventHandler;
+use dslab_core::{cast, log_debug};
+
+use crate::{DataTransferCompleted, Network};
+
+/// Event signalling that the peer has downloaded all data chunks. Source: distribution, destination: listener.
+#[derive(Clone, Serialize)]
+pub struct PeerDownloadCompleted {
+    /// Peer id.
+    pub peer: Id,
+    /// Time elapsed since the peer joined the distribution.
+    pub download_time: f64,
+}
+
+/// Policy used by peers to select the next chunk to download.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChunkSelection {
+    /// Chunk with the least number of replicas among the peers is selected first.
+    RarestFirst,
+    /// Chunk is selected randomly.
+    Random,
+    /// Chunks are downloaded in their order.
+    Sequential,
+}
+
+/// Statistics of a single peer.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PeerStats {
+    /// Peer id.
+    pub id: Id,
+    /// Whether the peer joined as a seed.
+    pub is_seed: bool,
+    /// Time when the peer joined the distribution.
+    pub join_time: f64,
+    /// Time when the peer downloaded all chunks, `None` if the download is not completed yet.
+    pub completion_time: Option<f64>,
+    /// Number of chunks downloaded by the peer.
+    pub downloaded_chunks: u64,
+    /// Number of chunks uploaded by the peer to other peers.
+    pub uploaded_chunks: u64,
+}
+
+impl PeerStats {
+    /// Returns the time spent by peer to download all chunks, `None` for seeds and peers which are still downloading.
+    pub fn download_time(&self) -> Option<f64> {
+        if self.is_seed {
+            None
+        } else {
+            self.completion_time.map(|time| time - self.join_time)
+        }
+    }
+}
+
+/// Aggregated statistics of the distribution.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DistributionStats {
+    /// Number of peers excluding seeds.
+    pub peers: usize,
+    /// Number of peers which downloaded all chunks.
+    pub completed_peers: usize,
+    /// Mean download time of completed peers.
+    pub mean_download_time: f64,
+    /// Maximum download time of completed peers.
+    pub max_download_time: f64,
+    /// Time when the last peer completed the download.
+    pub makespan: f64,
+    /// Fraction of chunk transfers uploaded by seeds.
+    pub seed_upload_ratio: f64,
+}
+
+struct Peer {
+    is_seed: bool,
+    upload_slots: usize,
+    download_slots: usize,
+    active_uploads: usize,
+    active_downloads: usize,
+    chunks: Vec<bool>,
+    chunk_count: usize,
+    requested: Vec<bool>,
+    join_time: f64,
+    completion_time: Option<f64>,
+    downloaded_chunks: u64,
+    uploaded_chunks: u64,
+}
+
+impl Peer {
+    fn is_complete(&self) -> bool {
+        self.chunk_count == self.chunks.len()
+    }
+}
+
+struct ChunkTransfer {
+    src: Id,
+    dst: Id,
+    chunk: usize,
+}
+
+/// Simulation component coordinating the exchange of data chunks among the peers.
+pub struct P2pDistribution {
+    network: Rc<RefCell<Network>>,
+    chunk_sizes: Vec<f64>,
+    chunk_selection: ChunkSelection,
+    peers: BTreeMap<Id, Peer>,
+    replica_counts: Vec<usize>,
+    transfers: HashMap<usize, ChunkTransfer>,
+    listener: Option<Id>,
+    ctx: SimulationContext,
+}
+
+impl P2pDistribution {
+    /// Creates distribution of data with the specified size, which is split into chunks of the specified size.
+    pub fn new(network: Rc<RefCell<Network>>, data_size: f64, chunk_size: f64, ctx: SimulationContext) -> Self {
+        assert!(
+            data_size > 0. && chunk_size > 0.,
+            "Data and chunk sizes should be positive"
+        );
+        let chunk_count = (data_size / chunk_size).ceil() as usize;
+        let chunk_sizes = (0..chunk_count)
+            .map(|i| chunk_size.min(data_size - i as f64 * chunk_size))
+            .collect();
+        Self {
+            network,
+            chunk_sizes,
+            chunk_selection: ChunkSelection::RarestFirst,
+            peers: BTreeMap::new(),
+            replica_counts: vec![0; chunk_count],
+            transfers: HashMap::new(),
+            listener: None,
+            ctx,
+        }
+    }
+
+    /// Returns the component id.
+    pub fn id(&self) -> Id {
+        self.ctx.id()
+    }
+
+    /// Returns the number of data chunks.
+    pub fn chunk_count(&self) -> usize {
+        self.chunk_sizes.len()
+    }
+
+    /// Sets the chunk selection policy, [`ChunkSelection::RarestFirst`] by default.
+    pub fn set_chunk_selection(&mut self, chunk_selection: ChunkSelection) {
+        self.chunk_selection = chunk_selection;
+    }
+
+    /// Sets the component which receives [`PeerDownloadCompleted`] events.
+    pub fn set_listener(&mut self, id: Id) {
+        self.listener = Some(id);
+    }
+
+    /// Adds seed having all data chunks, which can upload up to `upload_slots` chunks concurrently.
+    ///
+    /// The seed id should be bound to some network node.
+    pub fn add_seed(&mut self, id: Id, upload_slots: usize) {
+        self.add(id, true, upload_slots, 0);
+    }
+
+    /// Adds peer without data chunks which starts downloading them immediately.
+    ///
+    /// The peer can concurrently upload up to `upload_slots` chunks and download up to `download_slots` chunks.
+    /// After completing the download the peer continues to upload its chunks to other peers.
+    /// The peer id should be bound to some network node.
+    pub fn add_peer(&mut self, id: Id, upload_slots: usize, download_slots: usize) {
+        assert!(download_slots > 0, "Peer should have at least one download slot");
+        self.add(id, false, upload_slots, download_slots);
+    }
+
+    /// Returns the statistics of the peer.
+    pub fn peer_stats(&self, id: Id) -> Option<PeerStats> {
+        self.peers.get(&id).map(|peer| PeerStats {
+            id,
+            is_seed: peer.is_seed,
+            join_time: peer.join_time,
+            completion_time: peer.completion_time,
+            downloaded_chunks: peer.downloaded_chunks,
+            uploaded_chunks: peer.uploaded_chunks,
+        })
+    }
+
+    /// Returns the aggregated statistics of the distribution.
+    pub fn stats(&self) -> DistributionStats {
+        let mut stats = DistributionStats::default();
+        let download_times: Vec<f64> = self
+            .peers
+            .keys()
+            .filter_map(|id| self.peer_stats(*id).unwrap().download_time())
+            .collect();
+        stats.peers = self.peers.values().filter(|peer| !peer.is_seed).count();
+        stats.completed_peers = download_times.len();
+        if !download_times.is_empty() {
+            stats.mean_download_time = download_times.iter().sum::<f64>() / download_times.len() as f64;
+            stats.max_download_time = download_times.iter().cloned().fold(0., f64::max);
+        }
+        stats.makespan = self
+            .peers
+            .values()
+            .filter(|peer| !peer.is_seed)
+            .filter_map(|peer| peer.completion_time)
+            .fold(0., f64::max);
+        let uploads: u64 = self.peers.values().map(|peer| peer.uploaded_chunks).sum();
+        if uploads > 0 {
+            let seed_uploads: u64 = self
+                .peers
+                .values()
+                .filter(|peer| peer.is_seed)
+                .map(|peer| peer.uploaded_chunks)
+                .sum();
+            stats.seed_upload_ratio = seed_uploads as f64 / uploads as f64;
+        }
+        stats
+    }
+
+    fn add(&mut self, id: Id, is_seed: bool, upload_slots: usize, download_slots: usize) {
+        assert!(!self.peers.contains_key(&id), "Peer {} already exists", id);
+        let chunk_count = self.chunk_count();
+        if is_seed {
+            self.replica_counts.iter_mut().for_each(|count| *count += 1);
+        }
+        self.peers.insert(
+            id,
+            Peer {
+                is_seed,
+                upload_slots,
+                download_slots,
+                active_uploads: 0,
+                active_downloads: 0,
+                chunks: vec![is_seed; chunk_count],
+                chunk_count: if is_seed { chunk_count } else { 0 },
+                requested: vec![false; chunk_count],
+                join_time: self.ctx.time(),
+                completion_time: if is_seed { Some(self.ctx.time()) } else { None },
+                downloaded_chunks: 0,
+                uploaded_chunks: 0,
+            },
+        );
+        log_debug!(self.ctx, "Peer {} joined (seed: {})", id, is_seed);
+        self.schedule_transfers();
+    }
+
+    /// Starts chunk transfers while there are peers with free download slots and suitable sources.
+    fn schedule_transfers(&mut self) {
+        let peer_ids: Vec<Id> = self.peers.keys().copied().collect();
+        loop {
+            let mut started = false;
+            // each peer starts at most one transfer per round to share the upload slots fairly
+            for dst in peer_ids.iter().copied() {
+                let peer = &self.peers[&dst];
+                if peer.is_complete() || peer.active_downloads >= peer.download_slots {
+                    continue;
+                }
+                if let Some((chunk, src)) = self.select_chunk(dst) {
+                    self.start_transfer(src, dst, chunk);
+                    started = true;
+                }
+            }
+            if !started {
+                break;
+            }
+        }
+    }
+
+    /// Selects chunk to download by peer and the peer to download it from.
+    fn select_chunk(&self, dst: Id) -> Option<(usize, Id)> {
+        let peer = &self.peers[&dst];
+        // chunks which are missing on dst, along with the least loaded source having a free upload slot
+        let candidates: Vec<(usize, Id)> = (0..self.chunk_count())
+            .filter(|chunk| !peer.chunks[*chunk] && !peer.requested[*chunk])
+            .filter_map(|chunk| {
+                self.peers
+                    .iter()
+                    .filter(|(id, src)| **id != dst && src.chunks[chunk] && src.active_uploads < src.upload_slots)
+                    .min_by_key(|(_, src)| src.active_uploads)
+                    .map(|(id, _)| (chunk, *id))
+            })
+            .collect();
+        if candidates.is_empty() {
+            return None;
+        }
+        let selected = match self.chunk_selection {
+            ChunkSelection::RarestFirst => *candidates
+                .iter()
+                .min_by_key(|(chunk, _)| self.replica_counts[*chunk])
+                .unwrap(),
+            ChunkSelection::Random => candidates[self.ctx.gen_range(0..candidates.len())],
+            ChunkSelection::Sequential => candidates[0],
+        };
+        Some(selected)
+    }
+
+    fn start_transfer(&mut self, src: Id, dst: Id, chunk: usize) {
+        log_debug!(self.ctx, "Transferring chunk {} from {} to {}", chunk, src, dst);
+        self.peers.get_mut(&src).unwrap().active_uploads += 1;
+        let peer = self.peers.get_mut(&dst).unwrap();
+        peer.active_downloads += 1;
+        peer.requested[chunk] = true;
+        let transfer_id = self
+            .network
+            .borrow_mut()
+            .transfer_data(src, dst, self.chunk_sizes[chunk], self.ctx.id());
+        self.transfers.insert(transfer_id, ChunkTransfer { src, dst, chunk });
+    }
+
+    fn on_transfer_completed(&mut self, transfer_id: usize) {
+        let transfer = match self.transfers.remove(&transfer_id) {
+            Some(transfer) => transfer,
+            None => return,
+        };
+        let src = self.peers.get_mut(&transfer.src).unwrap();
+        src.active_uploads -= 1;
+        src.uploaded_chunks += 1;
+        let dst = self.peers.get_mut(&transfer.dst).unwrap();
+        dst.active_downloads -= 1;
+        dst.downloaded_chunks += 1;
+        dst.requested[transfer.chunk] = false;
+        dst.chunks[transfer.chunk] = true;
+        dst.chunk_count += 1;
+        self.replica_counts[transfer.chunk] += 1;
+        if dst.is_complete() {
+            let download_time = self.ctx.time() - dst.join_time;
+            dst.completion_time = Some(self.ctx.time());
+            log_debug!(
+                self.ctx,
+                "Peer {} completed download in {:.3}",
+                transfer.dst,
+                download_time
+            );
+            if let Some(listener) = self.listener {
+                self.ctx.emit_now(
+                    PeerDownloadCompleted {
+                        peer: transfer.dst,
+                        download_time,
+                    },
+                    listener,
+                );
+            }
+        }
+        self.schedule_transfers();
+    }
+}
+
+impl EventHandler for P2pDistribution {
+    fn on(&mut self, event: Event) {
+        cast!(match event.data {
+            DataTransferCompleted { dt } => {
+                self.on_transfer_completed(dt.id);
+            }
+        })
+    }
+}
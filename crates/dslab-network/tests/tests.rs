@@ -13,6 +13,7 @@ use dslab_core::simulation::Simulation;
 use dslab_core::EPSILON;
 
 use dslab_network::models::{ConstantBandwidthNetworkModel, TopologyAwareNetworkModel};
+use dslab_network::p2p::{ChunkSelection, P2pDistribution, PeerDownloadCompleted};
 use dslab_network::routing::{RoutingAlgorithm, ShortestPathDijkstra, ShortestPathFloydWarshall};
 use dslab_network::{DataTransferCompleted, Link, Network};
 
@@ -297,3 +298,209 @@ fn test_diamond(#[values(RoutingImpl::Dijkstra, RoutingImpl::FloydWarshall)] rou
 
     assert_float_eq(sim.time(), 10.2, EPSILON);
 }
+
+// P2P data distribution ///////////////////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Default)]
+struct P2pListener {
+    completed: Vec<(Id, f64)>,
+}
+
+impl EventHandler for P2pListener {
+    fn on(&mut self, event: Event) {
+        cast!(match event.data {
+            PeerDownloadCompleted { peer, download_time } => {
+                self.completed.push((peer, download_time));
+            }
+        })
+    }
+}
+
+// Creates distribution of data with size 100 split into 10 chunks over network with bandwidth 10 (without contention),
+// so that each chunk transfer takes 1 time unit. The seed is located on the first node.
+fn make_p2p(sim: &mut Simulation, host_count: usize) -> (Rc<RefCell<P2pDistribution>>, Vec<Id>) {
+    let mut network = Network::new(
+        Box::new(ConstantBandwidthNetworkModel::new(10.0, 0.0)),
+        sim.create_context("net"),
+    );
+    let mut hosts = Vec::new();
+    for i in 0..host_count {
+        let name = format!("host{}", i);
+        network.add_node(&name, Box::new(ConstantBandwidthNetworkModel::new(1e9, 0.0)));
+        let id = sim.create_context(&name).id();
+        network.set_location(id, &name);
+        hosts.push(id);
+    }
+    let network = Rc::new(RefCell::new(network));
+    sim.add_handler("net", network.clone());
+    let p2p = Rc::new(RefCell::new(P2pDistribution::new(
+        network,
+        100.0,
+        10.0,
+        sim.create_context("p2p"),
+    )));
+    sim.add_handler("p2p", p2p.clone());
+    (p2p, hosts)
+}
+
+#[rstest]
+#[case(1, 10.0)]
+#[case(2, 5.0)]
+#[case(10, 1.0)]
+fn test_p2p_single_peer(#[case] upload_slots: usize, #[case] expected_time: f64) {
+    let mut sim = Simulation::new(123);
+    let (p2p, hosts) = make_p2p(&mut sim, 2);
+    let listener = Rc::new(RefCell::new(P2pListener::default()));
+    let listener_id = sim.add_handler("listener", listener.clone());
+    p2p.borrow_mut().set_listener(listener_id);
+
+    p2p.borrow_mut().add_seed(hosts[0], upload_slots);
+    p2p.borrow_mut().add_peer(hosts[1], 1, 10);
+    sim.step_until_no_events();
+
+    // the download speed is limited by the upload slots of the seed
+    assert_eq!(listener.borrow().completed.len(), 1);
+    assert_eq!(listener.borrow().completed[0].0, hosts[1]);
+    assert_float_eq(listener.borrow().completed[0].1, expected_time, EPSILON);
+    let stats = p2p.borrow().peer_stats(hosts[1]).unwrap();
+    assert_eq!(stats.downloaded_chunks, 10);
+    assert_eq!(stats.download_time(), Some(expected_time));
+    assert_eq!(p2p.borrow().peer_stats(hosts[0]).unwrap().uploaded_chunks, 10);
+}
+
+#[rstest]
+fn test_p2p_peers_exchange_chunks(
+    #[values(ChunkSelection::RarestFirst, ChunkSelection::Random, ChunkSelection::Sequential)]
+    selection: ChunkSelection,
+) {
+    let mut sim = Simulation::new(123);
+    let (p2p, hosts) = make_p2p(&mut sim, 5);
+    p2p.borrow_mut().set_chunk_selection(selection);
+
+    p2p.borrow_mut().add_seed(hosts[0], 1);
+    for host in &hosts[1..] {
+        p2p.borrow_mut().add_peer(*host, 1, 2);
+    }
+    sim.step_until_no_events();
+
+    // downloading from the seed only would take 40 time units, while the peers upload part of the chunks
+    let stats = p2p.borrow().stats();
+    assert_eq!(stats.peers, 4);
+    assert_eq!(stats.completed_peers, 4);
+    assert!(stats.makespan < 40.0);
+    assert!(stats.seed_upload_ratio < 1.0);
+    for host in &hosts[1..] {
+        let peer_stats = p2p.borrow().peer_stats(*host).unwrap();
+        assert_eq!(peer_stats.downloaded_chunks, 10);
+    }
+}
+
+#[test]
+fn test_p2p_late_peer() {
+    let mut sim = Simulation::new(123);
+    let (p2p, hosts) = make_p2p(&mut sim, 3);
+
+    p2p.borrow_mut().add_seed(hosts[0], 1);
+    p2p.borrow_mut().add_peer(hosts[1], 1, 1);
+    sim.step_until_no_events();
+    assert_float_eq(sim.time(), 10.0, EPSILON);
+
+    // the late peer downloads the chunks from both the seed and the completed peer
+    p2p.borrow_mut().add_peer(hosts[2], 1, 2);
+    sim.step_until_no_events();
+    let stats = p2p.borrow().peer_stats(hosts[2]).unwrap();
+    assert_eq!(stats.join_time, 10.0);
+    assert_eq!(stats.download_time(), Some(5.0));
+    assert_eq!(p2p.borrow().peer_stats(hosts[1]).unwrap().uploaded_chunks, 5);
+    assert_eq!(p2p.borrow().stats().makespan, 15.0);
+}
+
+#[derive(Default)]
+struct Receiver {
+    completed: Vec<(usize, f64)>,
+}
+
+impl EventHandler for Receiver {
+    fn on(&mut self, event: Event) {
+        cast!(match event.data {
+            DataTransferCompleted { dt } => {
+                self.completed.push((dt.id, event.time));
+            }
+        })
+    }
+}
+
+#[rstest]
+#[case::topology_aware(false)]
+#[case::topology_aware_full_mesh(true)]
+// Three transfers of size 1000 share the link with bandwidth 100 and latency 1.
+// The first transfer is cancelled before it is started, the second one is cancelled at 6 when
+// the remaining transfers have 750 left each, so the third transfer finishes at 6 + 750 / 100.
+fn test_cancel_transfer(#[case] full_mesh_optimization: bool) {
+    let mut sim = Simulation::new(123);
+    let network_model = TopologyAwareNetworkModel::new().with_full_mesh_optimization(full_mesh_optimization);
+    let mut network = Network::new(Box::new(network_model), sim.create_context("net"));
+    network.add_node("host1", Box::new(ConstantBandwidthNetworkModel::new(100.0, 0.0)));
+    network.add_node("host2", Box::new(ConstantBandwidthNetworkModel::new(100.0, 0.0)));
+    network.add_link("host1", "host2", Link::shared(100.0, 1.0));
+    network.init_topology();
+    let network_rc = Rc::new(RefCell::new(network));
+    sim.add_handler("net", network_rc.clone());
+
+    let sender_id = sim.create_context("sender").id();
+    let receiver = Rc::new(RefCell::new(Receiver::default()));
+    let receiver_id = sim.add_handler("receiver", receiver.clone());
+    network_rc.borrow_mut().set_location(sender_id, "host1");
+    network_rc.borrow_mut().set_location(receiver_id, "host2");
+
+    let transfers = (0..3)
+        .map(|_| {
+            network_rc
+                .borrow_mut()
+                .transfer_data(sender_id, receiver_id, 1000.0, receiver_id)
+        })
+        .collect::<Vec<_>>();
+    sim.step_until_time(0.5);
+    assert!(network_rc.borrow_mut().cancel_transfer(transfers[0]));
+    assert!(!network_rc.borrow_mut().cancel_transfer(transfers[0]));
+    sim.step_until_time(6.0);
+    assert!(network_rc.borrow_mut().cancel_transfer(transfers[1]));
+    sim.step_until_no_events();
+
+    let completed = &receiver.borrow().completed;
+    assert_eq!(completed.len(), 1);
+    assert_eq!(completed[0].0, transfers[2]);
+    assert_float_eq(completed[0].1, 13.5, EPSILON);
+    assert!(!network_rc.borrow_mut().cancel_transfer(transfers[2]));
+}
+
+#[test]
+// With constant bandwidth model each transfer gets the full bandwidth, so cancellation does not affect
+// the other transfer.
+fn test_cancel_transfer_constant() {
+    let mut sim = Simulation::new(123);
+    let network_model = ConstantBandwidthNetworkModel::new(100.0, 0.0);
+    let mut network = Network::new(Box::new(network_model), sim.create_context("net"));
+    network.add_node("host1", Box::new(ConstantBandwidthNetworkModel::new(100.0, 0.0)));
+    network.add_node("host2", Box::new(ConstantBandwidthNetworkModel::new(100.0, 0.0)));
+    let network_rc = Rc::new(RefCell::new(network));
+    sim.add_handler("net", network_rc.clone());
+
+    let sender_id = sim.create_context("sender").id();
+    let receiver = Rc::new(RefCell::new(Receiver::default()));
+    let receiver_id = sim.add_handler("receiver", receiver.clone());
+    network_rc.borrow_mut().set_location(sender_id, "host1");
+    network_rc.borrow_mut().set_location(receiver_id, "host2");
+
+    let first = network_rc
+        .borrow_mut()
+        .transfer_data(sender_id, receiver_id, 1000.0, receiver_id);
+    let second = network_rc
+        .borrow_mut()
+        .transfer_data(sender_id, receiver_id, 1000.0, receiver_id);
+    sim.step_until_time(5.0);
+    assert!(network_rc.borrow_mut().cancel_transfer(first));
+    sim.step_until_no_events();
+
+    assert_eq!(receiver.borrow().completed, vec![(second, 10.0)]);
+}
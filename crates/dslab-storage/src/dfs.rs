@@ -0,0 +1,844 @@
+//! Replicated distributed file system model.
+//!
+//! It is built on top of the storage and network models and supports modeling of a distributed file system similar
+//! to HDFS. Files are split into chunks of fixed size, and each chunk is replicated on several data nodes selected
+//! by a pluggable [replica placement policy](ReplicaPlacementPolicy). Data is transferred between the data nodes and
+//! clients through the network, and each data node stores its replicas on a separate storage (e.g. disk).
+//!
+//! When a data node fails, the file system re-replicates the chunks which lost their replicas on this node by copying
+//! them from the remaining replicas. The chunk is lost if all its replicas are lost.
+//!
+//! Data nodes are identified by the ids of simulation components (e.g. hosts) bound to the network nodes, so the chunk
+//! locations returned by [`DistributedFileSystem::chunk_locations()`] can be directly used by the data-aware schedulers.
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::rc::Rc;
+
+use dslab_core::component::Id;
+use dslab_core::{
+    cast, context::SimulationContext, event::Event, handler::EventHandler, log_debug, log_error, log_warn,
+};
+use dslab_network::{DataTransferCompleted, Network};
+
+use crate::events::*;
+use crate::storage::Storage;
+
+/// Information about data node passed to the replica placement policy.
+#[derive(Clone, Debug)]
+pub struct DataNodeInfo {
+    /// Data node id.
+    pub id: Id,
+    /// Free space in node storage.
+    pub free_space: u64,
+    /// Whether the node is located on the same network node as the writer.
+    pub is_local: bool,
+}
+
+/// Selects data nodes for storing the chunk replicas.
+pub trait ReplicaPlacementPolicy {
+    /// Returns up to `count` distinct nodes from `candidates` to store a chunk replica.
+    ///
+    /// The candidates include only the alive nodes with enough free space which do not store the chunk yet.
+    fn select_nodes(&mut self, count: usize, candidates: &[DataNodeInfo], ctx: &SimulationContext) -> Vec<Id>;
+}
+
+fn shuffle(nodes: &mut [Id], ctx: &SimulationContext) {
+    for i in (1..nodes.len()).rev() {
+        let j = ctx.gen_range(0..=i);
+        nodes.swap(i, j);
+    }
+}
+
+/// Places replicas on randomly selected nodes.
+pub struct RandomPlacementPolicy {}
+
+impl ReplicaPlacementPolicy for RandomPlacementPolicy {
+    fn select_nodes(&mut self, count: usize, candidates: &[DataNodeInfo], ctx: &SimulationContext) -> Vec<Id> {
+        let mut nodes = candidates.iter().map(|node| node.id).collect::<Vec<_>>();
+        shuffle(&mut nodes, ctx);
+        nodes.truncate(count);
+        nodes
+    }
+}
+
+/// Places the first replica on the writer's node (if it is a data node) and other replicas on random nodes,
+/// similar to the default HDFS policy.
+pub struct LocalFirstPlacementPolicy {}
+
+impl ReplicaPlacementPolicy for LocalFirstPlacementPolicy {
+    fn select_nodes(&mut self, count: usize, candidates: &[DataNodeInfo], ctx: &SimulationContext) -> Vec<Id> {
+        let mut nodes = candidates.iter().map(|node| node.id).collect::<Vec<_>>();
+        shuffle(&mut nodes, ctx);
+        if let Some(pos) = nodes
+            .iter()
+            .position(|id| candidates.iter().any(|node| node.id == *id && node.is_local))
+        {
+            nodes.swap(0, pos);
+        }
+        nodes.truncate(count);
+        nodes
+    }
+}
+
+/// Places replicas on the nodes with the most free space.
+pub struct MostFreeSpacePlacementPolicy {}
+
+impl ReplicaPlacementPolicy for MostFreeSpacePlacementPolicy {
+    fn select_nodes(&mut self, count: usize, candidates: &[DataNodeInfo], _ctx: &SimulationContext) -> Vec<Id> {
+        let mut nodes = candidates.to_vec();
+        nodes.sort_by(|a, b| b.free_space.cmp(&a.free_space).then(a.id.cmp(&b.id)));
+        nodes.into_iter().take(count).map(|node| node.id).collect()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
+
+struct DataNode {
+    storage: Rc<RefCell<dyn Storage>>,
+    alive: bool,
+}
+
+struct Chunk {
+    size: u64,
+    /// Nodes storing the chunk replicas.
+    locations: Vec<Id>,
+    /// Nodes where the chunk replicas are being written.
+    targets: Vec<Id>,
+    lost: bool,
+}
+
+struct DfsFile {
+    size: u64,
+    chunks: Vec<Chunk>,
+    complete: bool,
+    active_reads: u64,
+}
+
+struct WriteRequest {
+    file_name: String,
+    requester: Id,
+    pending_replicas: Vec<usize>,
+    chunks_left: usize,
+    error: Option<String>,
+}
+
+struct ReadRequest {
+    file_name: String,
+    dst: Id,
+    requester: Id,
+    chunks_left: usize,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum OperationKey {
+    Transfer(usize),
+    StorageRead(Id, u64),
+    StorageWrite(Id, u64),
+}
+
+#[derive(Clone)]
+enum Operation {
+    WriteReplica {
+        request_id: u64,
+        file_name: String,
+        chunk: usize,
+        node: Id,
+    },
+    ReadChunk {
+        request_id: u64,
+        chunk: usize,
+        node: Id,
+    },
+    Replicate {
+        file_name: String,
+        chunk: usize,
+        src: Id,
+        dst: Id,
+    },
+}
+
+/// Representation of replicated distributed file system.
+pub struct DistributedFileSystem {
+    network: Rc<RefCell<Network>>,
+    nodes: BTreeMap<Id, DataNode>,
+    files: BTreeMap<String, DfsFile>,
+    chunk_size: u64,
+    replication_factor: usize,
+    placement_policy: Box<dyn ReplicaPlacementPolicy>,
+    operations: HashMap<OperationKey, Operation>,
+    writes: HashMap<u64, WriteRequest>,
+    reads: HashMap<u64, ReadRequest>,
+    next_request_id: u64,
+    re_replicated_chunks: u64,
+    lost_chunks: u64,
+    ctx: SimulationContext,
+}
+
+impl DistributedFileSystem {
+    /// Creates new empty file system with given chunk size and replication factor.
+    ///
+    /// Uses [`LocalFirstPlacementPolicy`] by default.
+    pub fn new(
+        network: Rc<RefCell<Network>>,
+        chunk_size: u64,
+        replication_factor: usize,
+        ctx: SimulationContext,
+    ) -> Self {
+        assert!(chunk_size > 0, "Chunk size should be positive");
+        assert!(replication_factor > 0, "Replication factor should be positive");
+        Self {
+            network,
+            nodes: BTreeMap::new(),
+            files: BTreeMap::new(),
+            chunk_size,
+            replication_factor,
+            placement_policy: Box::new(LocalFirstPlacementPolicy {}),
+            operations: HashMap::new(),
+            writes: HashMap::new(),
+            reads: HashMap::new(),
+            next_request_id: 0,
+            re_replicated_chunks: 0,
+            lost_chunks: 0,
+            ctx,
+        }
+    }
+
+    /// Sets replica placement policy.
+    pub fn set_placement_policy(&mut self, placement_policy: Box<dyn ReplicaPlacementPolicy>) {
+        self.placement_policy = placement_policy;
+    }
+
+    /// Adds data node which stores the chunks on the given storage.
+    ///
+    /// The node id should be bound to some network node.
+    pub fn add_data_node(&mut self, id: Id, storage: Rc<RefCell<dyn Storage>>) -> Result<(), String> {
+        if self.nodes.contains_key(&id) {
+            return Err(format!("data node {} already exists", id));
+        }
+        self.nodes.insert(id, DataNode { storage, alive: true });
+        Ok(())
+    }
+
+    /// Returns ids of all data nodes including the failed ones.
+    pub fn data_nodes(&self) -> Vec<Id> {
+        self.nodes.keys().copied().collect()
+    }
+
+    /// Returns whether the data node is alive.
+    pub fn is_node_alive(&self, id: Id) -> bool {
+        self.nodes.get(&id).is_some_and(|node| node.alive)
+    }
+
+    /// Returns names of all files including the ones being written.
+    pub fn file_names(&self) -> Vec<String> {
+        self.files.keys().cloned().collect()
+    }
+
+    /// Returns file size.
+    pub fn file_size(&self, file_name: &str) -> Result<u64, String> {
+        match self.files.get(file_name) {
+            Some(file) => Ok(file.size),
+            None => Err(format!("file [{}] does not exist", file_name)),
+        }
+    }
+
+    /// Returns the size and current replica locations of each file chunk.
+    pub fn chunk_locations(&self, file_name: &str) -> Result<Vec<(u64, Vec<Id>)>, String> {
+        match self.files.get(file_name) {
+            Some(file) => Ok(file
+                .chunks
+                .iter()
+                .map(|chunk| (chunk.size, chunk.locations.clone()))
+                .collect()),
+            None => Err(format!("file [{}] does not exist", file_name)),
+        }
+    }
+
+    /// Returns the number of chunk replicas created by re-replication.
+    pub fn re_replicated_chunks(&self) -> u64 {
+        self.re_replicated_chunks
+    }
+
+    /// Returns the number of chunks which lost all their replicas.
+    pub fn lost_chunks(&self) -> u64 {
+        self.lost_chunks
+    }
+
+    fn make_unique_request_id(&mut self) -> u64 {
+        let request_id = self.next_request_id;
+        self.next_request_id += 1;
+        request_id
+    }
+
+    fn is_local(&self, a: Id, b: Id) -> bool {
+        let network = self.network.borrow();
+        network.get_location(a) == network.get_location(b)
+    }
+
+    fn transfer(&mut self, src: Id, dst: Id, size: u64, operation: Operation) {
+        let transfer_id = self
+            .network
+            .borrow_mut()
+            .transfer_data(src, dst, size as f64, self.ctx.id());
+        self.operations.insert(OperationKey::Transfer(transfer_id), operation);
+    }
+
+    fn storage_read(&mut self, node: Id, size: u64, operation: Operation) {
+        let storage = self.nodes[&node].storage.clone();
+        let request_id = storage.borrow_mut().read(size, self.ctx.id());
+        let storage_id = storage.borrow().id();
+        self.operations
+            .insert(OperationKey::StorageRead(storage_id, request_id), operation);
+    }
+
+    fn storage_write(&mut self, node: Id, size: u64, operation: Operation) {
+        let storage = self.nodes[&node].storage.clone();
+        let request_id = storage.borrow_mut().write(size, self.ctx.id());
+        let storage_id = storage.borrow().id();
+        self.operations
+            .insert(OperationKey::StorageWrite(storage_id, request_id), operation);
+    }
+
+    fn select_nodes(&mut self, count: usize, size: u64, writer: Option<Id>, excluded: &[Id]) -> Vec<Id> {
+        let candidates = self
+            .nodes
+            .iter()
+            .filter(|(id, node)| node.alive && !excluded.contains(id))
+            .map(|(&id, node)| DataNodeInfo {
+                id,
+                free_space: node.storage.borrow().free_space(),
+                is_local: writer.is_some_and(|writer| self.is_local(writer, id)),
+            })
+            .filter(|node| node.free_space >= size)
+            .collect::<Vec<_>>();
+        let mut nodes = self.placement_policy.select_nodes(count, &candidates, &self.ctx);
+        nodes.retain(|id| candidates.iter().any(|node| node.id == *id));
+        nodes.dedup();
+        nodes
+    }
+
+    // Write -----------------------------------------------------------------------------------------------------------
+
+    /// Submits file write request and returns unique request id.
+    ///
+    /// Creates a new file of given size written by the `writer` component. The file is split into chunks, and the
+    /// replicas of each chunk are transferred from the writer to the selected data nodes in parallel.
+    /// The component specified in `requester` will receive [`DfsFileWriteCompleted`] event after all chunks
+    /// are written, or [`DfsFileWriteFailed`] event if the file cannot be written.
+    pub fn write_file(&mut self, file_name: &str, size: u64, writer: Id, requester: Id) -> u64 {
+        log_debug!(
+            self.ctx,
+            "Received write request, file: [{}], size: {}, writer: {}",
+            file_name,
+            size,
+            writer
+        );
+        let request_id = self.make_unique_request_id();
+        if self.files.contains_key(file_name) {
+            self.fail_write_immediately(request_id, file_name, requester, "file already exists".to_string());
+            return request_id;
+        }
+
+        let chunk_count = size.div_ceil(self.chunk_size) as usize;
+        let mut placements = Vec::with_capacity(chunk_count);
+        for i in 0..chunk_count {
+            let chunk_size = u64::min(self.chunk_size, size - i as u64 * self.chunk_size);
+            let nodes = self.select_nodes(self.replication_factor, chunk_size, Some(writer), &[]);
+            if nodes.is_empty() {
+                self.fail_write_immediately(
+                    request_id,
+                    file_name,
+                    requester,
+                    "no data nodes available for storing the chunk".to_string(),
+                );
+                return request_id;
+            }
+            if nodes.len() < self.replication_factor {
+                log_warn!(
+                    self.ctx,
+                    "Only {} data nodes available for storing the chunk {} of file [{}]",
+                    nodes.len(),
+                    i,
+                    file_name
+                );
+            }
+            placements.push((chunk_size, nodes));
+        }
+
+        self.files.insert(
+            file_name.to_string(),
+            DfsFile {
+                size,
+                chunks: placements
+                    .iter()
+                    .map(|(chunk_size, nodes)| Chunk {
+                        size: *chunk_size,
+                        locations: Vec::new(),
+                        targets: nodes.clone(),
+                        lost: false,
+                    })
+                    .collect(),
+                complete: false,
+                active_reads: 0,
+            },
+        );
+        self.writes.insert(
+            request_id,
+            WriteRequest {
+                file_name: file_name.to_string(),
+                requester,
+                pending_replicas: placements.iter().map(|(_, nodes)| nodes.len()).collect(),
+                chunks_left: chunk_count,
+                error: None,
+            },
+        );
+        if chunk_count == 0 {
+            self.complete_write(request_id);
+        }
+        for (chunk, (chunk_size, nodes)) in placements.into_iter().enumerate() {
+            for node in nodes {
+                let operation = Operation::WriteReplica {
+                    request_id,
+                    file_name: file_name.to_string(),
+                    chunk,
+                    node,
+                };
+                if self.is_local(writer, node) {
+                    self.storage_write(node, chunk_size, operation);
+                } else {
+                    self.transfer(writer, node, chunk_size, operation);
+                }
+            }
+        }
+        request_id
+    }
+
+    fn fail_write_immediately(&mut self, request_id: u64, file_name: &str, requester: Id, error: String) {
+        log_error!(self.ctx, "Failed writing file [{}]: {}", file_name, error);
+        self.ctx.emit_now(
+            DfsFileWriteFailed {
+                request_id,
+                file_name: file_name.to_string(),
+                error,
+            },
+            requester,
+        );
+    }
+
+    fn on_replica_written(&mut self, request_id: u64, file_name: &str, chunk_idx: usize, node: Id, success: bool) {
+        let alive = self.is_node_alive(node);
+        let file = self.files.get_mut(file_name).unwrap();
+        let chunk = &mut file.chunks[chunk_idx];
+        chunk.targets.retain(|&id| id != node);
+        if success && alive {
+            chunk.locations.push(node);
+        }
+        let has_replicas = !chunk.locations.is_empty();
+        let request = self.writes.get_mut(&request_id).unwrap();
+        request.pending_replicas[chunk_idx] -= 1;
+        if request.pending_replicas[chunk_idx] > 0 {
+            return;
+        }
+        if !has_replicas {
+            request.error = Some(format!("all replicas of chunk {} are lost", chunk_idx));
+        }
+        request.chunks_left -= 1;
+        if request.chunks_left == 0 {
+            self.complete_write(request_id);
+        }
+    }
+
+    fn complete_write(&mut self, request_id: u64) {
+        let request = self.writes.remove(&request_id).unwrap();
+        if let Some(error) = request.error {
+            let file = self.files.remove(&request.file_name).unwrap();
+            for chunk in file.chunks.iter() {
+                for node in chunk.locations.iter() {
+                    self.nodes[node].storage.borrow_mut().mark_free(chunk.size).unwrap();
+                }
+            }
+            self.fail_write_immediately(request_id, &request.file_name, request.requester, error);
+            return;
+        }
+        let file = self.files.get_mut(&request.file_name).unwrap();
+        file.complete = true;
+        let size = file.size;
+        log_debug!(self.ctx, "Completed writing file [{}]", request.file_name);
+        for chunk in 0..self.files[&request.file_name].chunks.len() {
+            self.ensure_replication(&request.file_name, chunk);
+        }
+        self.ctx.emit_now(
+            DfsFileWriteCompleted {
+                request_id,
+                file_name: request.file_name,
+                size,
+            },
+            request.requester,
+        );
+    }
+
+    // Read ------------------------------------------------------------------------------------------------------------
+
+    /// Submits file read request and returns unique request id.
+    ///
+    /// Reads all file chunks and transfers them to the `dst` component. Each chunk is read from the replica located
+    /// on the same network node as `dst` if there is any, otherwise from the first available replica.
+    /// The component specified in `requester` will receive [`DfsFileReadCompleted`] event after all chunks are read,
+    /// or [`DfsFileReadFailed`] event if the file cannot be read.
+    pub fn read_file(&mut self, file_name: &str, dst: Id, requester: Id) -> u64 {
+        log_debug!(
+            self.ctx,
+            "Received read request, file: [{}], destination: {}",
+            file_name,
+            dst
+        );
+        let request_id = self.make_unique_request_id();
+        let chunk_count = match self.files.get_mut(file_name) {
+            Some(file) if file.complete => {
+                file.active_reads += 1;
+                file.chunks.len()
+            }
+            Some(_) => {
+                self.fail_read(request_id, file_name, requester, "file is being written".to_string());
+                return request_id;
+            }
+            None => {
+                self.fail_read(request_id, file_name, requester, "file does not exist".to_string());
+                return request_id;
+            }
+        };
+        self.reads.insert(
+            request_id,
+            ReadRequest {
+                file_name: file_name.to_string(),
+                dst,
+                requester,
+                chunks_left: chunk_count,
+            },
+        );
+        if chunk_count == 0 {
+            self.complete_read(request_id);
+        }
+        for chunk in 0..chunk_count {
+            self.start_chunk_read(request_id, chunk);
+        }
+        request_id
+    }
+
+    fn start_chunk_read(&mut self, request_id: u64, chunk_idx: usize) {
+        let request = match self.reads.get(&request_id) {
+            Some(request) => request,
+            None => return,
+        };
+        let chunk = &self.files[&request.file_name].chunks[chunk_idx];
+        let node = chunk
+            .locations
+            .iter()
+            .find(|&&node| self.is_local(node, request.dst))
+            .or_else(|| chunk.locations.first())
+            .copied();
+        match node {
+            Some(node) => {
+                let size = chunk.size;
+                self.storage_read(
+                    node,
+                    size,
+                    Operation::ReadChunk {
+                        request_id,
+                        chunk: chunk_idx,
+                        node,
+                    },
+                );
+            }
+            None => {
+                let request = self.reads.remove(&request_id).unwrap();
+                self.files.get_mut(&request.file_name).unwrap().active_reads -= 1;
+                self.fail_read(
+                    request_id,
+                    &request.file_name,
+                    request.requester,
+                    format!("all replicas of chunk {} are lost", chunk_idx),
+                );
+            }
+        }
+    }
+
+    fn on_chunk_read(&mut self, request_id: u64) {
+        if let Some(request) = self.reads.get_mut(&request_id) {
+            request.chunks_left -= 1;
+            if request.chunks_left == 0 {
+                self.complete_read(request_id);
+            }
+        }
+    }
+
+    fn complete_read(&mut self, request_id: u64) {
+        let request = self.reads.remove(&request_id).unwrap();
+        let file = self.files.get_mut(&request.file_name).unwrap();
+        file.active_reads -= 1;
+        log_debug!(self.ctx, "Completed reading file [{}]", request.file_name);
+        self.ctx.emit_now(
+            DfsFileReadCompleted {
+                request_id,
+                file_name: request.file_name,
+                size: file.size,
+            },
+            request.requester,
+        );
+    }
+
+    fn fail_read(&mut self, request_id: u64, file_name: &str, requester: Id, error: String) {
+        log_error!(self.ctx, "Failed reading file [{}]: {}", file_name, error);
+        self.ctx.emit_now(
+            DfsFileReadFailed {
+                request_id,
+                file_name: file_name.to_string(),
+                error,
+            },
+            requester,
+        );
+    }
+
+    // Replication -----------------------------------------------------------------------------------------------------
+
+    fn ensure_replication(&mut self, file_name: &str, chunk_idx: usize) {
+        let file = match self.files.get_mut(file_name) {
+            Some(file) if file.complete => file,
+            _ => return,
+        };
+        let chunk = &mut file.chunks[chunk_idx];
+        if chunk.locations.is_empty() {
+            if chunk.targets.is_empty() && !chunk.lost {
+                chunk.lost = true;
+                self.lost_chunks += 1;
+                log_error!(self.ctx, "Chunk {} of file [{}] is lost", chunk_idx, file_name);
+            }
+            return;
+        }
+        let existing = chunk.locations.len() + chunk.targets.len();
+        if existing >= self.replication_factor {
+            return;
+        }
+        let src = chunk.locations[0];
+        let size = chunk.size;
+        let excluded = chunk
+            .locations
+            .iter()
+            .chain(chunk.targets.iter())
+            .copied()
+            .collect::<Vec<_>>();
+        let nodes = self.select_nodes(self.replication_factor - existing, size, None, &excluded);
+        if nodes.is_empty() {
+            log_warn!(
+                self.ctx,
+                "No data nodes available for re-replication of chunk {} of file [{}]",
+                chunk_idx,
+                file_name
+            );
+            return;
+        }
+        for dst in nodes {
+            log_debug!(
+                self.ctx,
+                "Replicating chunk {} of file [{}] from {} to {}",
+                chunk_idx,
+                file_name,
+                src,
+                dst
+            );
+            self.files.get_mut(file_name).unwrap().chunks[chunk_idx]
+                .targets
+                .push(dst);
+            self.storage_read(
+                src,
+                size,
+                Operation::Replicate {
+                    file_name: file_name.to_string(),
+                    chunk: chunk_idx,
+                    src,
+                    dst,
+                },
+            );
+        }
+    }
+
+    fn on_replication_finished(&mut self, file_name: &str, chunk_idx: usize, dst: Id, success: bool) {
+        let alive = self.is_node_alive(dst);
+        let chunk = &mut self.files.get_mut(file_name).unwrap().chunks[chunk_idx];
+        chunk.targets.retain(|&id| id != dst);
+        if success && alive {
+            chunk.locations.push(dst);
+            self.re_replicated_chunks += 1;
+        }
+        self.ensure_replication(file_name, chunk_idx);
+    }
+
+    /// Marks data node as failed and starts re-replication of the chunks stored on this node.
+    ///
+    /// The operations involving the failed node are completed as usual, but their results are discarded.
+    pub fn fail_node(&mut self, id: Id) -> Result<(), String> {
+        match self.nodes.get_mut(&id) {
+            Some(node) if node.alive => node.alive = false,
+            Some(_) => return Err(format!("data node {} has already failed", id)),
+            None => return Err(format!("unknown data node {}", id)),
+        }
+        log_debug!(self.ctx, "Data node {} failed", id);
+        let mut affected = Vec::new();
+        for (file_name, file) in self.files.iter_mut() {
+            for (chunk_idx, chunk) in file.chunks.iter_mut().enumerate() {
+                if chunk.locations.contains(&id) {
+                    chunk.locations.retain(|&node| node != id);
+                    affected.push((file_name.clone(), chunk_idx));
+                }
+            }
+        }
+        for (file_name, chunk_idx) in affected {
+            self.ensure_replication(&file_name, chunk_idx);
+        }
+        Ok(())
+    }
+
+    /// Deletes the file and frees the space occupied by its replicas.
+    ///
+    /// The file can be deleted only if it is not being written, read or re-replicated.
+    pub fn delete_file(&mut self, file_name: &str) -> Result<(), String> {
+        match self.files.get(file_name) {
+            Some(file) if !file.complete => return Err(format!("file [{}] is being written", file_name)),
+            Some(file) if file.active_reads > 0 => return Err(format!("file [{}] is being read", file_name)),
+            Some(file) if file.chunks.iter().any(|chunk| !chunk.targets.is_empty()) => {
+                return Err(format!("file [{}] is being re-replicated", file_name))
+            }
+            Some(_) => {}
+            None => return Err(format!("file [{}] does not exist", file_name)),
+        }
+        let file = self.files.remove(file_name).unwrap();
+        for chunk in file.chunks.iter() {
+            for node in chunk.locations.iter() {
+                if self.nodes[node].alive {
+                    self.nodes[node].storage.borrow_mut().mark_free(chunk.size)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Event handling --------------------------------------------------------------------------------------------------
+
+    fn on_transfer_completed(&mut self, transfer_id: usize) {
+        let operation = match self.operations.remove(&OperationKey::Transfer(transfer_id)) {
+            Some(operation) => operation,
+            None => return,
+        };
+        match operation.clone() {
+            Operation::WriteReplica {
+                request_id,
+                file_name,
+                chunk,
+                node,
+            } => {
+                if self.is_node_alive(node) {
+                    let size = self.files[&file_name].chunks[chunk].size;
+                    self.storage_write(node, size, operation);
+                } else {
+                    self.on_replica_written(request_id, &file_name, chunk, node, false);
+                }
+            }
+            Operation::ReadChunk { request_id, .. } => {
+                self.on_chunk_read(request_id);
+            }
+            Operation::Replicate {
+                file_name, chunk, dst, ..
+            } => {
+                if self.is_node_alive(dst) {
+                    let size = self.files[&file_name].chunks[chunk].size;
+                    self.storage_write(dst, size, operation);
+                } else {
+                    self.on_replication_finished(&file_name, chunk, dst, false);
+                }
+            }
+        }
+    }
+
+    fn on_storage_read(&mut self, key: OperationKey, success: bool) {
+        let operation = match self.operations.remove(&key) {
+            Some(operation) => operation,
+            None => return,
+        };
+        match operation.clone() {
+            Operation::ReadChunk {
+                request_id,
+                chunk,
+                node,
+            } => {
+                let (file_name, dst) = match self.reads.get(&request_id) {
+                    Some(request) => (request.file_name.clone(), request.dst),
+                    None => return,
+                };
+                if !success || !self.is_node_alive(node) {
+                    self.start_chunk_read(request_id, chunk);
+                } else if self.is_local(node, dst) {
+                    self.on_chunk_read(request_id);
+                } else {
+                    let size = self.files[&file_name].chunks[chunk].size;
+                    self.transfer(node, dst, size, operation);
+                }
+            }
+            Operation::Replicate {
+                file_name,
+                chunk,
+                src,
+                dst,
+            } => {
+                if success && self.is_node_alive(src) {
+                    let size = self.files[&file_name].chunks[chunk].size;
+                    self.transfer(src, dst, size, operation);
+                } else {
+                    self.on_replication_finished(&file_name, chunk, dst, false);
+                }
+            }
+            Operation::WriteReplica { .. } => {}
+        }
+    }
+
+    fn on_storage_write(&mut self, key: OperationKey, success: bool) {
+        match self.operations.remove(&key) {
+            Some(Operation::WriteReplica {
+                request_id,
+                file_name,
+                chunk,
+                node,
+            }) => {
+                self.on_replica_written(request_id, &file_name, chunk, node, success);
+            }
+            Some(Operation::Replicate {
+                file_name, chunk, dst, ..
+            }) => {
+                self.on_replication_finished(&file_name, chunk, dst, success);
+            }
+            _ => {}
+        }
+    }
+}
+
+impl EventHandler for DistributedFileSystem {
+    fn on(&mut self, event: Event) {
+        cast!(match event.data {
+            DataTransferCompleted { dt } => {
+                self.on_transfer_completed(dt.id);
+            }
+            DataReadCompleted { request_id, .. } => {
+                self.on_storage_read(OperationKey::StorageRead(event.src, request_id), true);
+            }
+            DataReadFailed { request_id, .. } => {
+                self.on_storage_read(OperationKey::StorageRead(event.src, request_id), false);
+            }
+            DataWriteCompleted { request_id, .. } => {
+                self.on_storage_write(OperationKey::StorageWrite(event.src, request_id), true);
+            }
+            DataWriteFailed { request_id, .. } => {
+                self.on_storage_write(OperationKey::StorageWrite(event.src, request_id), false);
+            }
+        })
+    }
+}
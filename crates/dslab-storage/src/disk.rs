@@ -5,6 +5,9 @@
 //! and dependence on operation properties by means of user-defined throughput and factor functions. For detailed
 //! information about these functions, please refer to documentation in `dslab-models` crate.
 //!
+//! The disk can also model a fixed access latency of each operation and lower throughput of random access operations
+//! compared to sequential ones (see [`DiskBuilder::access_latency()`] and [`DiskBuilder::random_read_factor()`]).
+//!
 //! Note that this model is quite generic and can be used to model other types of storage as well.
 
 use serde::Serialize;
@@ -23,7 +26,7 @@ use crate::scheduler::{FifoScheduler, Scheduler};
 use crate::storage::{Storage, StorageInfo};
 
 /// Describes a disk operation.
-#[derive(Clone)]
+#[derive(Clone, Serialize)]
 pub struct DiskOperation {
     /// Request Id.
     pub request_id: u64,
@@ -33,6 +36,8 @@ pub struct DiskOperation {
     pub op_type: DiskOperationType,
     /// Size.
     pub size: u64,
+    /// Access pattern.
+    pub access_pattern: DiskAccessPattern,
 }
 
 #[derive(Clone, Serialize)]
@@ -44,11 +49,41 @@ pub enum DiskOperationType {
     Write,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+/// Access pattern of disk operation.
+pub enum DiskAccessPattern {
+    /// Operation accesses contiguous data.
+    Sequential,
+    /// Operation accesses data at random locations.
+    Random,
+}
+
+#[derive(Clone, Serialize)]
+pub(crate) struct DiskOperationSubmitted {
+    pub operation: DiskOperation,
+}
+
 #[derive(Clone, Serialize)]
 pub(crate) struct DiskOperationCompleted {
     pub request_id: u64,
 }
 
+/// Factor function which additionally scales the throughput of random access operations.
+struct AccessPatternFactorFn {
+    inner: Box<dyn ActivityFactorFn<DiskOperation>>,
+    random_factor: f64,
+}
+
+impl ActivityFactorFn<DiskOperation> for AccessPatternFactorFn {
+    fn get_factor(&mut self, item: &DiskOperation, ctx: &mut SimulationContext) -> f64 {
+        let factor = self.inner.get_factor(item, ctx);
+        match item.access_pattern {
+            DiskAccessPattern::Sequential => factor,
+            DiskAccessPattern::Random => factor * self.random_factor,
+        }
+    }
+}
+
 ////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
 
 /// Disk builder. This is a type for convenient disk setup.
@@ -63,6 +98,9 @@ pub struct DiskBuilder {
     concurrent_ops_limit: Option<u64>,
     concurrent_read_ops_limit: Option<u64>,
     concurrent_write_ops_limit: Option<u64>,
+    access_latency: f64,
+    random_read_factor: f64,
+    random_write_factor: f64,
 }
 
 impl Default for DiskBuilder {
@@ -79,6 +117,9 @@ impl Default for DiskBuilder {
             concurrent_ops_limit: None,
             concurrent_read_ops_limit: None,
             concurrent_write_ops_limit: None,
+            access_latency: 0.,
+            random_read_factor: 1.,
+            random_write_factor: 1.,
         }
     }
 }
@@ -167,15 +208,51 @@ impl DiskBuilder {
         self
     }
 
+    /// Sets access latency, i.e. the fixed delay before each operation starts transferring data.
+    pub fn access_latency(mut self, access_latency: f64) -> Self {
+        self.access_latency = access_latency;
+        self
+    }
+
+    /// Sets throughput factor for random read operations relative to sequential ones.
+    ///
+    /// For example, factor 0.1 means that random reads are 10 times slower than sequential reads.
+    pub fn random_read_factor(mut self, random_read_factor: f64) -> Self {
+        self.random_read_factor = random_read_factor;
+        self
+    }
+
+    /// Sets throughput factor for random write operations relative to sequential ones.
+    pub fn random_write_factor(mut self, random_write_factor: f64) -> Self {
+        self.random_write_factor = random_write_factor;
+        self
+    }
+
     /// Builds disk from given builder and simulation context.
     ///
     /// Panics on invalid or incomplete disk settings.
     pub fn build(self, ctx: SimulationContext) -> Disk {
-        let read_throughput_model =
-            FairThroughputSharingModel::new(self.read_throughput_fn.unwrap(), self.read_factor_fn);
+        assert!(self.access_latency >= 0., "Access latency should be non-negative");
+        assert!(
+            self.random_read_factor > 0. && self.random_write_factor > 0.,
+            "Random access factors should be positive"
+        );
+
+        let read_throughput_model = FairThroughputSharingModel::new(
+            self.read_throughput_fn.unwrap(),
+            boxed!(AccessPatternFactorFn {
+                inner: self.read_factor_fn,
+                random_factor: self.random_read_factor,
+            }),
+        );
 
-        let write_throughput_model =
-            FairThroughputSharingModel::new(self.write_throughput_fn.unwrap(), self.write_factor_fn);
+        let write_throughput_model = FairThroughputSharingModel::new(
+            self.write_throughput_fn.unwrap(),
+            boxed!(AccessPatternFactorFn {
+                inner: self.write_factor_fn,
+                random_factor: self.random_write_factor,
+            }),
+        );
 
         let scheduler = boxed!(FifoScheduler::new(
             read_throughput_model,
@@ -189,6 +266,7 @@ impl DiskBuilder {
             capacity: self.capacity.unwrap(),
             used: 0,
             scheduler,
+            access_latency: self.access_latency,
             next_request_id: 0,
             ctx,
         }
@@ -199,7 +277,7 @@ impl DiskBuilder {
 
 /// Represents a disk.
 ///
-/// Disk is characterized by its capacity and read/write throughput models.
+/// Disk is characterized by its capacity, access latency and read/write throughput models.
 ///
 /// Disk state includes the amount of used disk space and state of throughput models.
 /// Should be created using [`DiskBuilder`].
@@ -207,6 +285,7 @@ pub struct Disk {
     pub(in crate::disk) capacity: u64,
     pub(in crate::disk) used: u64,
     pub(in crate::disk) scheduler: Box<dyn Scheduler>,
+    pub(in crate::disk) access_latency: f64,
     pub(in crate::disk) next_request_id: u64,
     pub(in crate::disk) ctx: SimulationContext,
 }
@@ -217,15 +296,25 @@ impl Disk {
         self.next_request_id += 1;
         request_id
     }
-}
 
-/// Storage model implementation for disk.
-impl Storage for Disk {
-    fn read(&mut self, size: u64, requester: Id) -> u64 {
+    fn submit(&mut self, operation: DiskOperation) {
+        if self.access_latency > 0. {
+            self.ctx
+                .emit_self(DiskOperationSubmitted { operation }, self.access_latency);
+        } else {
+            self.scheduler.submit(operation, &mut self.ctx);
+        }
+    }
+
+    /// Submits data read request with given access pattern and returns unique request id.
+    ///
+    /// Same as [`Storage::read()`] which assumes sequential access.
+    pub fn read_with_pattern(&mut self, size: u64, access_pattern: DiskAccessPattern, requester: Id) -> u64 {
         log_debug!(
             self.ctx,
-            "Received read request, size: {}, requester: {}",
+            "Received read request, size: {}, access pattern: {:?}, requester: {}",
             size,
+            access_pattern,
             requester
         );
         let request_id = self.make_unique_request_id();
@@ -237,25 +326,27 @@ impl Storage for Disk {
             log_error!(self.ctx, "Failed reading: {}", error,);
             self.ctx.emit_now(DataReadFailed { request_id, error }, requester);
         } else {
-            self.scheduler.submit(
-                DiskOperation {
-                    request_id,
-                    requester,
-                    op_type: DiskOperationType::Read,
-                    size,
-                },
-                &mut self.ctx,
-            );
+            self.submit(DiskOperation {
+                request_id,
+                requester,
+                op_type: DiskOperationType::Read,
+                size,
+                access_pattern,
+            });
         }
         request_id
     }
 
-    fn write(&mut self, size: u64, requester: Id) -> u64 {
+    /// Submits data write request with given access pattern and returns unique request id.
+    ///
+    /// Same as [`Storage::write()`] which assumes sequential access.
+    pub fn write_with_pattern(&mut self, size: u64, access_pattern: DiskAccessPattern, requester: Id) -> u64 {
         let request_id = self.make_unique_request_id();
         log_debug!(
             self.ctx,
-            "Received write request, size: {}, requester: {}",
+            "Received write request, size: {}, access pattern: {:?}, requester: {}",
             size,
+            access_pattern,
             requester
         );
         let available = self.capacity - self.used;
@@ -265,18 +356,27 @@ impl Storage for Disk {
             self.ctx.emit_now(DataWriteFailed { request_id, error }, requester);
         } else {
             self.used += size;
-            self.scheduler.submit(
-                DiskOperation {
-                    request_id,
-                    requester,
-                    op_type: DiskOperationType::Write,
-                    size,
-                },
-                &mut self.ctx,
-            );
+            self.submit(DiskOperation {
+                request_id,
+                requester,
+                op_type: DiskOperationType::Write,
+                size,
+                access_pattern,
+            });
         }
         request_id
     }
+}
+
+/// Storage model implementation for disk.
+impl Storage for Disk {
+    fn read(&mut self, size: u64, requester: Id) -> u64 {
+        self.read_with_pattern(size, DiskAccessPattern::Sequential, requester)
+    }
+
+    fn write(&mut self, size: u64, requester: Id) -> u64 {
+        self.write_with_pattern(size, DiskAccessPattern::Sequential, requester)
+    }
 
     fn mark_free(&mut self, size: u64) -> Result<(), String> {
         if size <= self.used {
@@ -314,6 +414,9 @@ impl Storage for Disk {
 impl EventHandler for Disk {
     fn on(&mut self, event: Event) {
         cast!(match event.data {
+            DiskOperationSubmitted { operation } => {
+                self.scheduler.submit(operation, &mut self.ctx);
+            }
             DiskOperationCompleted { request_id } => {
                 let operation = self.scheduler.complete(request_id, &mut self.ctx);
                 match operation.op_type {
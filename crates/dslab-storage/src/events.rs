@@ -85,3 +85,99 @@ pub struct FileWriteFailed {
     /// Reason of failure.
     pub error: String,
 }
+
+// Distributed file system events
+
+#[derive(Clone, Serialize)]
+/// Corresponds to completion of distributed file system read request. Source: DFS, destination: requester.
+pub struct DfsFileReadCompleted {
+    /// Request id returned by [`crate::dfs::DistributedFileSystem::read_file()`] method.
+    pub request_id: u64,
+    /// Name of read file.
+    pub file_name: String,
+    /// Size of read data.
+    pub size: u64,
+}
+
+#[derive(Clone, Serialize)]
+/// Corresponds to failure of distributed file system read request. Source: DFS, destination: requester.
+pub struct DfsFileReadFailed {
+    /// Request id returned by [`crate::dfs::DistributedFileSystem::read_file()`] method.
+    pub request_id: u64,
+    /// Name of read file.
+    pub file_name: String,
+    /// Reason of failure.
+    pub error: String,
+}
+
+#[derive(Clone, Serialize)]
+/// Corresponds to completion of distributed file system write request. Source: DFS, destination: requester.
+pub struct DfsFileWriteCompleted {
+    /// Request id returned by [`crate::dfs::DistributedFileSystem::write_file()`] method.
+    pub request_id: u64,
+    /// Name of written file.
+    pub file_name: String,
+    /// Size of written file.
+    pub size: u64,
+}
+
+#[derive(Clone, Serialize)]
+/// Corresponds to failure of distributed file system write request. Source: DFS, destination: requester.
+pub struct DfsFileWriteFailed {
+    /// Request id returned by [`crate::dfs::DistributedFileSystem::write_file()`] method.
+    pub request_id: u64,
+    /// Name of written file.
+    pub file_name: String,
+    /// Reason of failure.
+    pub error: String,
+}
+
+// Key-value store events
+
+#[derive(Clone, Serialize)]
+/// Corresponds to completion of key-value store get request. Source: store, destination: requester.
+pub struct KvGetCompleted {
+    /// Request id returned by [`crate::kv::ReplicatedKvStore::get()`] method.
+    pub request_id: u64,
+    /// Requested key.
+    pub key: String,
+    /// Version of the returned value or `None` if the key is not found.
+    pub version: Option<u64>,
+    /// Size of the returned value.
+    pub size: u64,
+    /// Whether the returned value is older than the latest value acknowledged to some client before the request.
+    pub stale: bool,
+}
+
+#[derive(Clone, Serialize)]
+/// Corresponds to failure of key-value store get request. Source: store, destination: requester.
+pub struct KvGetFailed {
+    /// Request id returned by [`crate::kv::ReplicatedKvStore::get()`] method.
+    pub request_id: u64,
+    /// Requested key.
+    pub key: String,
+    /// Reason of failure.
+    pub error: String,
+}
+
+#[derive(Clone, Serialize)]
+/// Corresponds to completion of key-value store put request. Source: store, destination: requester.
+pub struct KvPutCompleted {
+    /// Request id returned by [`crate::kv::ReplicatedKvStore::put()`] method.
+    pub request_id: u64,
+    /// Written key.
+    pub key: String,
+    /// Version of the written value.
+    pub version: u64,
+}
+
+#[derive(Clone, Serialize)]
+/// Corresponds to failure of key-value store put request. Source: store, destination: requester.
+pub struct KvPutFailed {
+    /// Request id returned by [`crate::kv::ReplicatedKvStore::put()`] method.
+    pub request_id: u64,
+    /// Written key.
+    pub key: String,
+    /// Reason of failure.
+    pub error: String,
+}
@@ -0,0 +1,898 @@
+//! Replicated key-value store model.
+//!
+//! It is built on top of the storage and network models and supports modeling of a key-value store where each key is
+//! replicated on all replicas. The replicas are identified by the ids of simulation components (e.g. hosts) bound to
+//! the network nodes, and each replica stores the values on a separate storage (e.g. disk), so that the request
+//! latency includes both the network and the storage latency. The requests and responses are transferred between the
+//! clients and the replicas through the network, the messages without values have the size configured via
+//! [`ReplicatedKvStore::set_message_size()`].
+//!
+//! The following [replication protocols](ReplicationProtocol) are supported:
+//!
+//! - Primary-backup: the writes are sent to the primary replica (the first added one), which assigns the value
+//!   version and propagates the value to the backups synchronously (before acknowledging the write) or asynchronously.
+//!   The reads are served by the primary or, if enabled, by a random replica.
+//! - Quorum: the requests are sent to all replicas and are completed after receiving `write_quorum` or `read_quorum`
+//!   responses, the read returns the value with the latest version among the received ones.
+//!
+//! The store collects the [statistics](KvStats) of request latency and the staleness of read values, i.e. whether
+//! the read returned the value older than the latest value acknowledged to some client before the read started.
+//! The workload can be generated by [`KvClient`] components.
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::rc::Rc;
+
+use dslab_core::component::Id;
+use dslab_core::{cast, context::SimulationContext, event::Event, handler::EventHandler, log_debug};
+use dslab_models::queueing::TimeDistribution;
+use dslab_network::{DataTransferCompleted, Network};
+
+use crate::events::*;
+use crate::storage::Storage;
+
+/// Protocol used to replicate the values and serve the requests.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ReplicationProtocol {
+    /// Writes are processed by the primary replica and propagated to backups.
+    PrimaryBackup {
+        /// Whether the write is acknowledged after it is applied by all backups.
+        sync: bool,
+        /// Whether the reads are served by a random replica instead of the primary.
+        read_from_backups: bool,
+    },
+    /// Requests are sent to all replicas and complete after receiving the quorum of responses.
+    Quorum {
+        /// Number of responses needed to complete the read.
+        read_quorum: usize,
+        /// Number of acknowledgements needed to complete the write.
+        write_quorum: usize,
+    },
+}
+
+/// Statistics of requests processed by the store.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct KvStats {
+    /// Number of completed get requests.
+    pub gets: u64,
+    /// Number of completed put requests.
+    pub puts: u64,
+    /// Number of failed get requests.
+    pub failed_gets: u64,
+    /// Number of failed put requests.
+    pub failed_puts: u64,
+    /// Mean latency of completed get requests.
+    pub mean_get_latency: f64,
+    /// 99th percentile of latency of completed get requests.
+    pub p99_get_latency: f64,
+    /// Mean latency of completed put requests.
+    pub mean_put_latency: f64,
+    /// 99th percentile of latency of completed put requests.
+    pub p99_put_latency: f64,
+    /// Number of get requests which returned stale values.
+    pub stale_reads: u64,
+    /// Mean number of versions by which the returned values lag behind the latest acknowledged ones.
+    pub mean_version_lag: f64,
+}
+
+impl KvStats {
+    /// Returns the fraction of completed get requests which returned stale values.
+    pub fn stale_read_ratio(&self) -> f64 {
+        if self.gets == 0 {
+            0.
+        } else {
+            self.stale_reads as f64 / self.gets as f64
+        }
+    }
+}
+
+/// Value stored on replica.
+#[derive(Clone, Copy)]
+struct StoredValue {
+    version: u64,
+    size: u64,
+}
+
+struct Replica {
+    storage: Rc<RefCell<dyn Storage>>,
+    values: HashMap<String, StoredValue>,
+}
+
+struct PutRequest {
+    key: String,
+    size: u64,
+    version: u64,
+    client: Id,
+    requester: Id,
+    start_time: f64,
+    acks_needed: usize,
+    acks: usize,
+    pending: usize,
+    done: bool,
+}
+
+struct GetRequest {
+    key: String,
+    client: Id,
+    requester: Id,
+    start_time: f64,
+    acked_version: u64,
+    responses_needed: usize,
+    responses: usize,
+    pending: usize,
+    result: Option<StoredValue>,
+    done: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum OperationKey {
+    Transfer(usize),
+    StorageRead(Id, u64),
+    StorageWrite(Id, u64),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Stage {
+    /// Request or value is transferred to the replica.
+    Request,
+    /// Value is written to or read from the replica storage.
+    Storage,
+    /// Response is transferred from the replica.
+    Response,
+}
+
+#[derive(Clone, Copy)]
+enum Operation {
+    /// Writing of value to replica as part of put request.
+    Put { request_id: u64, replica: Id, stage: Stage },
+    /// Propagation of value from primary to backup in primary-backup protocol.
+    Backup {
+        request_id: u64,
+        replica: Id,
+        stage: Stage,
+        success: bool,
+    },
+    /// Reading of value from replica as part of get request.
+    Get {
+        request_id: u64,
+        replica: Id,
+        stage: Stage,
+        value: Option<StoredValue>,
+    },
+}
+
+/// Representation of replicated key-value store.
+pub struct ReplicatedKvStore {
+    network: Rc<RefCell<Network>>,
+    protocol: ReplicationProtocol,
+    replicas: BTreeMap<Id, Replica>,
+    primary: Option<Id>,
+    message_size: u64,
+    next_versions: HashMap<String, u64>,
+    acked_versions: HashMap<String, u64>,
+    operations: HashMap<OperationKey, Operation>,
+    puts: HashMap<u64, PutRequest>,
+    gets: HashMap<u64, GetRequest>,
+    next_request_id: u64,
+    get_latencies: Vec<f64>,
+    put_latencies: Vec<f64>,
+    stats: KvStats,
+    total_version_lag: u64,
+    ctx: SimulationContext,
+}
+
+impl ReplicatedKvStore {
+    /// Creates new empty store using the given replication protocol.
+    pub fn new(network: Rc<RefCell<Network>>, protocol: ReplicationProtocol, ctx: SimulationContext) -> Self {
+        Self {
+            network,
+            protocol,
+            replicas: BTreeMap::new(),
+            primary: None,
+            message_size: 0,
+            next_versions: HashMap::new(),
+            acked_versions: HashMap::new(),
+            operations: HashMap::new(),
+            puts: HashMap::new(),
+            gets: HashMap::new(),
+            next_request_id: 0,
+            get_latencies: Vec::new(),
+            put_latencies: Vec::new(),
+            stats: KvStats::default(),
+            total_version_lag: 0,
+            ctx,
+        }
+    }
+
+    /// Sets the size of messages without values (requests, acknowledgements), zero by default.
+    pub fn set_message_size(&mut self, size: u64) {
+        self.message_size = size;
+    }
+
+    /// Adds replica which stores the values on the given storage.
+    ///
+    /// The replica id should be bound to some network node. The first added replica is the primary one.
+    pub fn add_replica(&mut self, id: Id, storage: Rc<RefCell<dyn Storage>>) -> Result<(), String> {
+        if self.replicas.contains_key(&id) {
+            return Err(format!("replica {} already exists", id));
+        }
+        if let ReplicationProtocol::Quorum {
+            read_quorum,
+            write_quorum,
+        } = self.protocol
+        {
+            if read_quorum == 0 || write_quorum == 0 {
+                return Err("quorum sizes should be positive".to_string());
+            }
+        }
+        self.replicas.insert(
+            id,
+            Replica {
+                storage,
+                values: HashMap::new(),
+            },
+        );
+        self.primary.get_or_insert(id);
+        Ok(())
+    }
+
+    /// Returns ids of all replicas.
+    pub fn replicas(&self) -> Vec<Id> {
+        self.replicas.keys().copied().collect()
+    }
+
+    /// Returns the version of value stored on the replica.
+    pub fn replica_version(&self, replica: Id, key: &str) -> Option<u64> {
+        self.replicas
+            .get(&replica)
+            .and_then(|replica| replica.values.get(key))
+            .map(|value| value.version)
+    }
+
+    /// Returns the statistics of processed requests.
+    pub fn stats(&self) -> KvStats {
+        let mut stats = self.stats.clone();
+        stats.mean_get_latency = mean(&self.get_latencies);
+        stats.p99_get_latency = percentile(&self.get_latencies, 0.99);
+        stats.mean_put_latency = mean(&self.put_latencies);
+        stats.p99_put_latency = percentile(&self.put_latencies, 0.99);
+        if stats.gets > 0 {
+            stats.mean_version_lag = self.total_version_lag as f64 / stats.gets as f64;
+        }
+        stats
+    }
+
+    fn make_unique_request_id(&mut self) -> u64 {
+        let request_id = self.next_request_id;
+        self.next_request_id += 1;
+        request_id
+    }
+
+    fn next_version(&mut self, key: &str) -> u64 {
+        let version = self.next_versions.entry(key.to_string()).or_insert(0);
+        *version += 1;
+        *version
+    }
+
+    fn transfer(&mut self, src: Id, dst: Id, size: u64, operation: Operation) {
+        let transfer_id = self
+            .network
+            .borrow_mut()
+            .transfer_data(src, dst, size as f64, self.ctx.id());
+        self.operations.insert(OperationKey::Transfer(transfer_id), operation);
+    }
+
+    fn storage_read(&mut self, replica: Id, size: u64, operation: Operation) {
+        let storage = self.replicas[&replica].storage.clone();
+        let request_id = storage.borrow_mut().read(size, self.ctx.id());
+        let storage_id = storage.borrow().id();
+        self.operations
+            .insert(OperationKey::StorageRead(storage_id, request_id), operation);
+    }
+
+    fn storage_write(&mut self, replica: Id, size: u64, operation: Operation) {
+        let storage = self.replicas[&replica].storage.clone();
+        let request_id = storage.borrow_mut().write(size, self.ctx.id());
+        let storage_id = storage.borrow().id();
+        self.operations
+            .insert(OperationKey::StorageWrite(storage_id, request_id), operation);
+    }
+
+    /// Stores the written value on replica if it is newer than the stored one, and frees the space of older value.
+    fn apply_value(&mut self, replica: Id, key: &str, value: StoredValue) {
+        let replica = self.replicas.get_mut(&replica).unwrap();
+        let mut storage = replica.storage.borrow_mut();
+        match replica.values.get(key).copied() {
+            Some(old) if old.version >= value.version => {
+                storage.mark_free(value.size).unwrap();
+            }
+            old => {
+                if let Some(old) = old {
+                    storage.mark_free(old.size).unwrap();
+                }
+                replica.values.insert(key.to_string(), value);
+            }
+        }
+    }
+
+    // Put -------------------------------------------------------------------------------------------------------------
+
+    /// Submits put request and returns unique request id.
+    ///
+    /// Writes the value of given size issued by the `client` component, which should be bound to some network node.
+    /// The component specified in `requester` will receive [`KvPutCompleted`] event after the write is acknowledged
+    /// according to the replication protocol, or [`KvPutFailed`] event if the value cannot be written.
+    pub fn put(&mut self, key: &str, size: u64, client: Id, requester: Id) -> u64 {
+        log_debug!(self.ctx, "Received put request, key: [{}], size: {}", key, size);
+        let request_id = self.make_unique_request_id();
+        if self.replicas.is_empty() {
+            self.fail_put(request_id, key, requester, "store has no replicas".to_string());
+            return request_id;
+        }
+        let (targets, acks_needed, version) = match self.protocol {
+            ReplicationProtocol::PrimaryBackup { .. } => (vec![self.primary.unwrap()], 1, 0),
+            ReplicationProtocol::Quorum { write_quorum, .. } => {
+                if write_quorum > self.replicas.len() {
+                    self.fail_put(
+                        request_id,
+                        key,
+                        requester,
+                        "write quorum exceeds replica count".to_string(),
+                    );
+                    return request_id;
+                }
+                (self.replicas(), write_quorum, self.next_version(key))
+            }
+        };
+        self.puts.insert(
+            request_id,
+            PutRequest {
+                key: key.to_string(),
+                size,
+                version,
+                client,
+                requester,
+                start_time: self.ctx.time(),
+                acks_needed,
+                acks: 0,
+                pending: targets.len(),
+                done: false,
+            },
+        );
+        for replica in targets {
+            let operation = Operation::Put {
+                request_id,
+                replica,
+                stage: Stage::Request,
+            };
+            self.transfer(client, replica, size, operation);
+        }
+        request_id
+    }
+
+    fn on_put_stage_completed(&mut self, request_id: u64, replica: Id, stage: Stage, success: bool) {
+        let request = &self.puts[&request_id];
+        let (key, size, client) = (request.key.clone(), request.size, request.client);
+        match stage {
+            Stage::Request => {
+                let operation = Operation::Put {
+                    request_id,
+                    replica,
+                    stage: Stage::Storage,
+                };
+                self.storage_write(replica, size, operation);
+            }
+            Stage::Storage => {
+                if !success {
+                    self.on_put_response(request_id, false);
+                    return;
+                }
+                let version = match self.protocol {
+                    ReplicationProtocol::PrimaryBackup { .. } => {
+                        let version = self.next_version(&key);
+                        self.puts.get_mut(&request_id).unwrap().version = version;
+                        version
+                    }
+                    ReplicationProtocol::Quorum { .. } => request.version,
+                };
+                self.apply_value(replica, &key, StoredValue { version, size });
+                match self.protocol {
+                    ReplicationProtocol::PrimaryBackup { sync, .. } => {
+                        let backups: Vec<Id> = self.replicas.keys().copied().filter(|id| *id != replica).collect();
+                        if sync && !backups.is_empty() {
+                            let request = self.puts.get_mut(&request_id).unwrap();
+                            request.acks_needed = backups.len();
+                            request.pending = backups.len();
+                        } else {
+                            self.respond_put(request_id, replica, client);
+                        }
+                        for backup in backups {
+                            let operation = Operation::Backup {
+                                request_id,
+                                replica: backup,
+                                stage: Stage::Request,
+                                success: true,
+                            };
+                            self.transfer(replica, backup, size, operation);
+                        }
+                    }
+                    ReplicationProtocol::Quorum { .. } => {
+                        self.respond_put(request_id, replica, client);
+                    }
+                }
+            }
+            Stage::Response => {
+                self.on_put_response(request_id, success);
+            }
+        }
+    }
+
+    fn respond_put(&mut self, request_id: u64, replica: Id, client: Id) {
+        let operation = Operation::Put {
+            request_id,
+            replica,
+            stage: Stage::Response,
+        };
+        self.transfer(replica, client, self.message_size, operation);
+    }
+
+    fn on_backup_stage_completed(&mut self, request_id: u64, replica: Id, stage: Stage, success: bool) {
+        let request = &self.puts[&request_id];
+        let (key, size, version) = (request.key.clone(), request.size, request.version);
+        let sync = matches!(self.protocol, ReplicationProtocol::PrimaryBackup { sync: true, .. });
+        let primary = self.primary.unwrap();
+        match stage {
+            Stage::Request => {
+                let operation = Operation::Backup {
+                    request_id,
+                    replica,
+                    stage: Stage::Storage,
+                    success: true,
+                };
+                self.storage_write(replica, size, operation);
+            }
+            Stage::Storage => {
+                if success {
+                    self.apply_value(replica, &key, StoredValue { version, size });
+                }
+                if sync {
+                    let operation = Operation::Backup {
+                        request_id,
+                        replica,
+                        stage: Stage::Response,
+                        success,
+                    };
+                    self.transfer(replica, primary, self.message_size, operation);
+                } else {
+                    self.release_put(request_id);
+                }
+            }
+            Stage::Response => {
+                let request = self.puts.get_mut(&request_id).unwrap();
+                request.pending -= 1;
+                if !success {
+                    request.acks_needed = usize::MAX;
+                } else {
+                    request.acks += 1;
+                }
+                if request.pending == 0 {
+                    if request.acks >= request.acks_needed {
+                        let client = request.client;
+                        // the write is acknowledged to the client by the primary after all backups applied it
+                        request.acks = 0;
+                        request.acks_needed = 1;
+                        request.pending = 1;
+                        self.respond_put(request_id, primary, client);
+                    } else {
+                        request.pending = 1;
+                        self.on_put_response(request_id, false);
+                    }
+                }
+            }
+        }
+    }
+
+    fn on_put_response(&mut self, request_id: u64, success: bool) {
+        let request = self.puts.get_mut(&request_id).unwrap();
+        request.pending -= 1;
+        if success {
+            request.acks += 1;
+        }
+        if !request.done {
+            if request.acks >= request.acks_needed {
+                request.done = true;
+                let (key, version, requester) = (request.key.clone(), request.version, request.requester);
+                self.put_latencies.push(self.ctx.time() - request.start_time);
+                self.stats.puts += 1;
+                let acked = self.acked_versions.entry(key.clone()).or_insert(0);
+                *acked = (*acked).max(version);
+                self.ctx.emit_now(
+                    KvPutCompleted {
+                        request_id,
+                        key,
+                        version,
+                    },
+                    requester,
+                );
+            } else if request.acks + request.pending < request.acks_needed {
+                request.done = true;
+                let (key, requester) = (request.key.clone(), request.requester);
+                self.fail_put(
+                    request_id,
+                    &key,
+                    requester,
+                    "not enough replicas acknowledged the write".to_string(),
+                );
+            }
+        }
+        self.release_put(request_id);
+    }
+
+    /// Removes the request after all its operations are finished.
+    fn release_put(&mut self, request_id: u64) {
+        let request = &self.puts[&request_id];
+        let backups_pending = self.operations.values().any(|operation| {
+            matches!(operation, Operation::Backup { request_id: id, .. } | Operation::Put { request_id: id, .. } if *id == request_id)
+        });
+        if request.done && request.pending == 0 && !backups_pending {
+            self.puts.remove(&request_id);
+        }
+    }
+
+    fn fail_put(&mut self, request_id: u64, key: &str, requester: Id, error: String) {
+        log_debug!(self.ctx, "Failed put of key [{}]: {}", key, error);
+        self.stats.failed_puts += 1;
+        self.ctx.emit_now(
+            KvPutFailed {
+                request_id,
+                key: key.to_string(),
+                error,
+            },
+            requester,
+        );
+    }
+
+    // Get -------------------------------------------------------------------------------------------------------------
+
+    /// Submits get request and returns unique request id.
+    ///
+    /// Reads the value for the `client` component, which should be bound to some network node.
+    /// The component specified in `requester` will receive [`KvGetCompleted`] event after receiving the responses
+    /// from replicas according to the replication protocol, or [`KvGetFailed`] event if the value cannot be read.
+    pub fn get(&mut self, key: &str, client: Id, requester: Id) -> u64 {
+        log_debug!(self.ctx, "Received get request, key: [{}]", key);
+        let request_id = self.make_unique_request_id();
+        if self.replicas.is_empty() {
+            self.fail_get(request_id, key, requester, "store has no replicas".to_string());
+            return request_id;
+        }
+        let (targets, responses_needed) = match self.protocol {
+            ReplicationProtocol::PrimaryBackup { read_from_backups, .. } => {
+                let replica = if read_from_backups {
+                    let replicas = self.replicas();
+                    replicas[self.ctx.gen_range(0..replicas.len())]
+                } else {
+                    self.primary.unwrap()
+                };
+                (vec![replica], 1)
+            }
+            ReplicationProtocol::Quorum { read_quorum, .. } => {
+                if read_quorum > self.replicas.len() {
+                    self.fail_get(
+                        request_id,
+                        key,
+                        requester,
+                        "read quorum exceeds replica count".to_string(),
+                    );
+                    return request_id;
+                }
+                (self.replicas(), read_quorum)
+            }
+        };
+        self.gets.insert(
+            request_id,
+            GetRequest {
+                key: key.to_string(),
+                client,
+                requester,
+                start_time: self.ctx.time(),
+                acked_version: self.acked_versions.get(key).copied().unwrap_or(0),
+                responses_needed,
+                responses: 0,
+                pending: targets.len(),
+                result: None,
+                done: false,
+            },
+        );
+        for replica in targets {
+            let operation = Operation::Get {
+                request_id,
+                replica,
+                stage: Stage::Request,
+                value: None,
+            };
+            self.transfer(client, replica, self.message_size, operation);
+        }
+        request_id
+    }
+
+    fn on_get_stage_completed(
+        &mut self,
+        request_id: u64,
+        replica: Id,
+        stage: Stage,
+        value: Option<StoredValue>,
+        success: bool,
+    ) {
+        match stage {
+            Stage::Request => {
+                let key = &self.gets[&request_id].key;
+                let value = self.replicas[&replica].values.get(key).copied();
+                match value {
+                    Some(stored) if stored.size > 0 => {
+                        let operation = Operation::Get {
+                            request_id,
+                            replica,
+                            stage: Stage::Storage,
+                            value,
+                        };
+                        self.storage_read(replica, stored.size, operation);
+                    }
+                    _ => self.respond_get(request_id, replica, value),
+                }
+            }
+            Stage::Storage => {
+                if success {
+                    self.respond_get(request_id, replica, value);
+                } else {
+                    self.on_get_response(request_id, None, false);
+                }
+            }
+            Stage::Response => {
+                self.on_get_response(request_id, value, true);
+            }
+        }
+    }
+
+    fn respond_get(&mut self, request_id: u64, replica: Id, value: Option<StoredValue>) {
+        let client = self.gets[&request_id].client;
+        let size = value.map_or(0, |value| value.size).max(self.message_size);
+        let operation = Operation::Get {
+            request_id,
+            replica,
+            stage: Stage::Response,
+            value,
+        };
+        self.transfer(replica, client, size, operation);
+    }
+
+    fn on_get_response(&mut self, request_id: u64, value: Option<StoredValue>, success: bool) {
+        let request = self.gets.get_mut(&request_id).unwrap();
+        request.pending -= 1;
+        if success {
+            request.responses += 1;
+            if value.map(|v| v.version) > request.result.map(|v| v.version) {
+                request.result = value;
+            }
+        }
+        if !request.done {
+            if request.responses >= request.responses_needed {
+                request.done = true;
+                let version = request.result.map(|value| value.version);
+                let version_lag = request.acked_version.saturating_sub(version.unwrap_or(0));
+                let completed = KvGetCompleted {
+                    request_id,
+                    key: request.key.clone(),
+                    version,
+                    size: request.result.map_or(0, |value| value.size),
+                    stale: version_lag > 0,
+                };
+                let requester = request.requester;
+                self.get_latencies.push(self.ctx.time() - request.start_time);
+                self.stats.gets += 1;
+                if version_lag > 0 {
+                    self.stats.stale_reads += 1;
+                    self.total_version_lag += version_lag;
+                }
+                self.ctx.emit_now(completed, requester);
+            } else if request.responses + request.pending < request.responses_needed {
+                request.done = true;
+                let (key, requester) = (request.key.clone(), request.requester);
+                self.fail_get(request_id, &key, requester, "not enough replicas responded".to_string());
+            }
+        }
+        if self.gets[&request_id].pending == 0 {
+            self.gets.remove(&request_id);
+        }
+    }
+
+    fn fail_get(&mut self, request_id: u64, key: &str, requester: Id, error: String) {
+        log_debug!(self.ctx, "Failed get of key [{}]: {}", key, error);
+        self.stats.failed_gets += 1;
+        self.ctx.emit_now(
+            KvGetFailed {
+                request_id,
+                key: key.to_string(),
+                error,
+            },
+            requester,
+        );
+    }
+
+    // Events ----------------------------------------------------------------------------------------------------------
+
+    fn on_operation_completed(&mut self, key: OperationKey, success: bool) {
+        let operation = match self.operations.remove(&key) {
+            Some(operation) => operation,
+            None => return,
+        };
+        match operation {
+            Operation::Put {
+                request_id,
+                replica,
+                stage,
+            } => self.on_put_stage_completed(request_id, replica, stage, success),
+            Operation::Backup {
+                request_id,
+                replica,
+                stage,
+                success: backup_success,
+            } => self.on_backup_stage_completed(request_id, replica, stage, success && backup_success),
+            Operation::Get {
+                request_id,
+                replica,
+                stage,
+                value,
+            } => self.on_get_stage_completed(request_id, replica, stage, value, success),
+        }
+    }
+}
+
+fn mean(values: &[f64]) -> f64 {
+    if values.is_empty() {
+        0.
+    } else {
+        values.iter().sum::<f64>() / values.len() as f64
+    }
+}
+
+/// Returns the percentile of values using the nearest-rank method.
+fn percentile(values: &[f64], p: f64) -> f64 {
+    if values.is_empty() {
+        return 0.;
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let rank = (p * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+impl EventHandler for ReplicatedKvStore {
+    fn on(&mut self, event: Event) {
+        cast!(match event.data {
+            DataTransferCompleted { dt } => {
+                self.on_operation_completed(OperationKey::Transfer(dt.id), true);
+            }
+            DataReadCompleted { request_id, .. } => {
+                self.on_operation_completed(OperationKey::StorageRead(event.src, request_id), true);
+            }
+            DataReadFailed { request_id, .. } => {
+                self.on_operation_completed(OperationKey::StorageRead(event.src, request_id), false);
+            }
+            DataWriteCompleted { request_id, .. } => {
+                self.on_operation_completed(OperationKey::StorageWrite(event.src, request_id), true);
+            }
+            DataWriteFailed { request_id, .. } => {
+                self.on_operation_completed(OperationKey::StorageWrite(event.src, request_id), false);
+            }
+        })
+    }
+}
+
+// Client --------------------------------------------------------------------------------------------------------------
+
+/// Description of workload generated by [`KvClient`].
+#[derive(Clone, Debug)]
+pub struct KvWorkload {
+    /// Distribution of time between the requests.
+    pub inter_arrival_time: TimeDistribution,
+    /// Fraction of get requests.
+    pub read_ratio: f64,
+    /// Number of keys, the requested key is selected uniformly at random.
+    pub key_count: u64,
+    /// Size of written values.
+    pub value_size: u64,
+    /// Total number of requests.
+    pub request_count: u64,
+}
+
+#[derive(Clone, serde::Serialize)]
+struct IssueRequest {}
+
+/// Client issuing get and put requests to the store according to the specified workload.
+///
+/// The client component should be bound to some network node. The requests are issued independently of
+/// the completion of previous requests (open-loop workload).
+pub struct KvClient {
+    store: Rc<RefCell<ReplicatedKvStore>>,
+    workload: KvWorkload,
+    issued: u64,
+    completed: u64,
+    failed: u64,
+    ctx: SimulationContext,
+}
+
+impl KvClient {
+    /// Creates client with the specified workload.
+    pub fn new(store: Rc<RefCell<ReplicatedKvStore>>, workload: KvWorkload, ctx: SimulationContext) -> Self {
+        Self {
+            store,
+            workload,
+            issued: 0,
+            completed: 0,
+            failed: 0,
+            ctx,
+        }
+    }
+
+    /// Starts issuing requests, the first request is issued after the inter-arrival time.
+    pub fn start(&mut self) {
+        self.schedule_next_request();
+    }
+
+    /// Returns the number of issued requests.
+    pub fn issued_requests(&self) -> u64 {
+        self.issued
+    }
+
+    /// Returns the number of completed requests.
+    pub fn completed_requests(&self) -> u64 {
+        self.completed
+    }
+
+    /// Returns the number of failed requests.
+    pub fn failed_requests(&self) -> u64 {
+        self.failed
+    }
+
+    fn schedule_next_request(&mut self) {
+        if self.issued < self.workload.request_count {
+            let delay = self.workload.inter_arrival_time.sample(&self.ctx);
+            self.ctx.emit_self(IssueRequest {}, delay);
+        }
+    }
+
+    fn issue_request(&mut self) {
+        self.issued += 1;
+        let key = format!("key{}", self.ctx.gen_range(0..self.workload.key_count));
+        let id = self.ctx.id();
+        if self.ctx.rand() < self.workload.read_ratio {
+            self.store.borrow_mut().get(&key, id, id);
+        } else {
+            self.store.borrow_mut().put(&key, self.workload.value_size, id, id);
+        }
+        self.schedule_next_request();
+    }
+}
+
+impl EventHandler for KvClient {
+    fn on(&mut self, event: Event) {
+        cast!(match event.data {
+            IssueRequest {} => {
+                self.issue_request();
+            }
+            KvGetCompleted { .. } => {
+                self.completed += 1;
+            }
+            KvPutCompleted { .. } => {
+                self.completed += 1;
+            }
+            KvGetFailed { .. } => {
+                self.failed += 1;
+            }
+            KvPutFailed { .. } => {
+                self.failed += 1;
+            }
+        })
+    }
+}
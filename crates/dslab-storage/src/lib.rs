@@ -1,9 +1,11 @@
 #![warn(missing_docs)]
 #![doc = include_str!("../README.md")]
 
+pub mod dfs;
 pub mod disk;
 pub mod events;
 pub mod fs;
+pub mod kv;
 pub mod scheduler;
 pub mod storage;
 
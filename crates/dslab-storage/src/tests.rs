@@ -1,14 +1,20 @@
 use std::cell::RefCell;
 use std::rc::Rc;
 
-use sugars::{rc, refcell};
+use sugars::{boxed, rc, refcell};
 
+use dslab_core::component::Id;
 use dslab_core::simulation::Simulation;
 use dslab_core::{cast, Event, EventHandler};
+use dslab_models::queueing::TimeDistribution;
+use dslab_network::models::ConstantBandwidthNetworkModel;
+use dslab_network::Network;
 
-use crate::disk::{Disk, DiskBuilder};
+use crate::dfs::{DistributedFileSystem, MostFreeSpacePlacementPolicy};
+use crate::disk::{Disk, DiskAccessPattern, DiskBuilder};
 use crate::events::*;
 use crate::fs::FileSystem;
+use crate::kv::{KvClient, KvWorkload, ReplicatedKvStore, ReplicationProtocol};
 use crate::storage::{Storage, StorageInfo};
 
 ///////////////////////////////////////////////////////////////////////////////
@@ -634,3 +640,399 @@ fn disk_with_read_write_ops_limit() {
     assert_eq!(write_checker.borrow().received_events_count(), 2);
     assert_eq!(read_checker.borrow().received_events_count(), 2);
 }
+
+#[test]
+fn disk_with_access_latency_and_random_access() {
+    let mut sim = Simulation::new(SEED);
+
+    let read_checker = rc!(refcell!(Checker::new(ExpectedEventType::DataReadCompleted)));
+    let read_checker_id = sim.add_handler("Reader", read_checker.clone());
+
+    let write_checker = rc!(refcell!(Checker::new(ExpectedEventType::DataWriteCompleted)));
+    let write_checker_id = sim.add_handler("Writer", write_checker.clone());
+
+    let disk = rc!(refcell!(DiskBuilder::simple(
+        DISK_CAPACITY,
+        DISK_READ_BW,
+        DISK_WRITE_BW,
+    )
+    .access_latency(0.5)
+    .random_read_factor(0.25)
+    .random_write_factor(0.5)
+    .build(sim.create_context("Disk-1"))));
+
+    sim.add_handler("Disk-1", disk.clone());
+
+    disk.borrow_mut().read(50, read_checker_id);
+    sim.step_until_no_events();
+    assert_eq!(sim.time(), 0.5 + 50. / DISK_READ_BW);
+
+    disk.borrow_mut()
+        .read_with_pattern(50, DiskAccessPattern::Random, read_checker_id);
+    sim.step_until_no_events();
+    assert_eq!(sim.time(), 1. + 0.5 + 50. / (DISK_READ_BW * 0.25));
+
+    disk.borrow_mut()
+        .write_with_pattern(50, DiskAccessPattern::Random, write_checker_id);
+    sim.step_until_no_events();
+    assert_eq!(sim.time(), 3.5 + 0.5 + 50. / (DISK_WRITE_BW * 0.5));
+    assert_eq!(disk.borrow().used_space(), 50);
+
+    assert_eq!(read_checker.borrow().received_events_count(), 2);
+    assert_eq!(write_checker.borrow().received_events_count(), 1);
+}
+
+// Distributed file system tests
+
+const NETWORK_BW: f64 = 10.;
+
+#[derive(Default)]
+struct DfsChecker {
+    events: Vec<(f64, String)>,
+}
+
+impl EventHandler for DfsChecker {
+    fn on(&mut self, event: Event) {
+        cast!(match event.data {
+            DfsFileWriteCompleted { file_name, .. } => {
+                self.events
+                    .push((event.time, format!("write completed: {}", file_name)));
+            }
+            DfsFileWriteFailed { file_name, .. } => {
+                self.events.push((event.time, format!("write failed: {}", file_name)));
+            }
+            DfsFileReadCompleted { file_name, .. } => {
+                self.events.push((event.time, format!("read completed: {}", file_name)));
+            }
+            DfsFileReadFailed { file_name, .. } => {
+                self.events.push((event.time, format!("read failed: {}", file_name)));
+            }
+        })
+    }
+}
+
+// Creates DFS with chunk size 30, replication factor 2 and data nodes located on separate network nodes.
+fn make_dfs(sim: &mut Simulation, node_count: usize) -> (Rc<RefCell<DistributedFileSystem>>, Vec<Id>) {
+    let mut network = Network::new(
+        boxed!(ConstantBandwidthNetworkModel::new(NETWORK_BW, 0.)),
+        sim.create_context("Net"),
+    );
+    let mut nodes = Vec::new();
+    let mut disks = Vec::new();
+    for i in 0..node_count {
+        let name = format!("Host-{}", i);
+        network.add_node(&name, boxed!(ConstantBandwidthNetworkModel::new(1e9, 0.)));
+        let node_id = sim.create_context(&name).id();
+        network.set_location(node_id, &name);
+        nodes.push(node_id);
+        disks.push(make_simple_disk(sim, &format!("Disk-{}", i)));
+    }
+    let network = rc!(refcell!(network));
+    sim.add_handler("Net", network.clone());
+    let mut dfs = DistributedFileSystem::new(network, 30, 2, sim.create_context("DFS"));
+    dfs.set_placement_policy(boxed!(MostFreeSpacePlacementPolicy {}));
+    for (node, disk) in nodes.iter().zip(disks) {
+        assert!(dfs.add_data_node(*node, disk).is_ok());
+    }
+    let dfs = rc!(refcell!(dfs));
+    sim.add_handler("DFS", dfs.clone());
+    (dfs, nodes)
+}
+
+#[test]
+fn dfs_write_and_read_file() {
+    let mut sim = Simulation::new(SEED);
+    let checker = rc!(refcell!(DfsChecker::default()));
+    let checker_id = sim.add_handler("User", checker.clone());
+    let (dfs, nodes) = make_dfs(&mut sim, 3);
+
+    dfs.borrow_mut().write_file("file", 50, nodes[0], checker_id);
+    sim.step_until_no_events();
+
+    // both chunks are stored on Host-0 (local write) and Host-1 (remote write)
+    assert_eq!(
+        dfs.borrow().chunk_locations("file"),
+        Ok(vec![(30, vec![nodes[0], nodes[1]]), (20, vec![nodes[0], nodes[1]])])
+    );
+    assert_eq!(dfs.borrow().file_size("file"), Ok(50));
+    // 30 units are transferred to Host-1 and then written to its disk
+    assert!((sim.time() - (30. / NETWORK_BW + 30. / DISK_WRITE_BW)).abs() < 1e-9);
+
+    // the file cannot be written twice
+    dfs.borrow_mut().write_file("file", 10, nodes[0], checker_id);
+    // chunks are read from Host-0 and transferred to Host-2
+    dfs.borrow_mut().read_file("file", nodes[2], checker_id);
+    let start = sim.time();
+    sim.step_until_no_events();
+    assert!((sim.time() - start - (50. / DISK_READ_BW + 30. / NETWORK_BW)).abs() < 1e-9);
+
+    let events = checker.borrow().events.iter().map(|e| e.1.clone()).collect::<Vec<_>>();
+    assert_eq!(
+        events,
+        vec!["write completed: file", "write failed: file", "read completed: file"]
+    );
+
+    assert!(dfs.borrow_mut().delete_file("file").is_ok());
+    assert!(dfs.borrow().file_names().is_empty());
+}
+
+#[test]
+fn dfs_re_replication_on_node_failure() {
+    let mut sim = Simulation::new(SEED);
+    let checker = rc!(refcell!(DfsChecker::default()));
+    let checker_id = sim.add_handler("User", checker.clone());
+    let (dfs, nodes) = make_dfs(&mut sim, 3);
+
+    dfs.borrow_mut().write_file("file", 50, nodes[0], checker_id);
+    sim.step_until_no_events();
+
+    // the lost replicas are copied from Host-1 to Host-2
+    assert!(dfs.borrow_mut().fail_node(nodes[0]).is_ok());
+    assert!(dfs.borrow_mut().fail_node(nodes[0]).is_err());
+    sim.step_until_no_events();
+    assert_eq!(dfs.borrow().re_replicated_chunks(), 2);
+    assert_eq!(
+        dfs.borrow().chunk_locations("file"),
+        Ok(vec![(30, vec![nodes[1], nodes[2]]), (20, vec![nodes[1], nodes[2]])])
+    );
+
+    // the file is still readable
+    dfs.borrow_mut().read_file("file", nodes[0], checker_id);
+    sim.step_until_no_events();
+
+    // no nodes are available for re-replication, so the chunks are lost after the second failure
+    assert!(dfs.borrow_mut().fail_node(nodes[1]).is_ok());
+    assert_eq!(dfs.borrow().lost_chunks(), 0);
+    assert!(dfs.borrow_mut().fail_node(nodes[2]).is_ok());
+    assert_eq!(dfs.borrow().lost_chunks(), 2);
+    dfs.borrow_mut().read_file("file", nodes[0], checker_id);
+    sim.step_until_no_events();
+
+    let events = checker.borrow().events.iter().map(|e| e.1.clone()).collect::<Vec<_>>();
+    assert_eq!(
+        events,
+        vec!["write completed: file", "read completed: file", "read failed: file"]
+    );
+}
+
+// Replicated key-value store tests
+
+#[derive(Default)]
+struct KvChecker {
+    events: Vec<(f64, String)>,
+}
+
+impl EventHandler for KvChecker {
+    fn on(&mut self, event: Event) {
+        cast!(match event.data {
+            KvPutCompleted { key, version, .. } => {
+                self.events
+                    .push((event.time, format!("put completed: {} v{}", key, version)));
+            }
+            KvPutFailed { key, .. } => {
+                self.events.push((event.time, format!("put failed: {}", key)));
+            }
+            KvGetCompleted {
+                key, version, stale, ..
+            } => {
+                self.events.push((
+                    event.time,
+                    format!("get completed: {} {:?} stale={}", key, version, stale),
+                ));
+            }
+            KvGetFailed { key, .. } => {
+                self.events.push((event.time, format!("get failed: {}", key)));
+            }
+        })
+    }
+}
+
+// Creates store with replicas and client located on separate network nodes, the last returned id is the client.
+// The disk of i-th replica has i+1 times higher read bandwidth and i+1 times lower write bandwidth.
+fn make_kv_store(
+    sim: &mut Simulation,
+    replica_count: usize,
+    protocol: ReplicationProtocol,
+) -> (Rc<RefCell<ReplicatedKvStore>>, Vec<Id>) {
+    let mut network = Network::new(
+        boxed!(ConstantBandwidthNetworkModel::new(NETWORK_BW, 0.)),
+        sim.create_context("Net"),
+    );
+    let mut nodes = Vec::new();
+    let mut disks = Vec::new();
+    for i in 0..=replica_count {
+        let name = if i < replica_count {
+            format!("Replica-{}", i)
+        } else {
+            "Client".to_string()
+        };
+        network.add_node(&name, boxed!(ConstantBandwidthNetworkModel::new(1e9, 0.)));
+        let node_id = sim.create_context(&name).id();
+        network.set_location(node_id, &name);
+        nodes.push(node_id);
+        if i < replica_count {
+            let disk_name = format!("Disk-{}", i);
+            let k = (i + 1) as f64;
+            let disk = rc!(refcell!(DiskBuilder::simple(
+                DISK_CAPACITY,
+                DISK_READ_BW * k,
+                DISK_WRITE_BW / k
+            )
+            .build(sim.create_context(&disk_name))));
+            sim.add_handler(&disk_name, disk.clone());
+            disks.push(disk);
+        }
+    }
+    let network = rc!(refcell!(network));
+    sim.add_handler("Net", network.clone());
+    let mut store = ReplicatedKvStore::new(network, protocol, sim.create_context("KV"));
+    for (node, disk) in nodes.iter().zip(disks) {
+        assert!(store.add_replica(*node, disk).is_ok());
+    }
+    let store = rc!(refcell!(store));
+    sim.add_handler("KV", store.clone());
+    (store, nodes)
+}
+
+#[test]
+fn kv_primary_backup_sync_and_async_writes() {
+    for sync in [true, false] {
+        let mut sim = Simulation::new(SEED);
+        let checker = rc!(refcell!(KvChecker::default()));
+        let checker_id = sim.add_handler("User", checker.clone());
+        let protocol = ReplicationProtocol::PrimaryBackup {
+            sync,
+            read_from_backups: false,
+        };
+        let (store, nodes) = make_kv_store(&mut sim, 3, protocol);
+        let client = nodes[3];
+
+        store.borrow_mut().put("key", 10, client, checker_id);
+        sim.step_until_no_events();
+
+        // the value is transferred to the primary and written to its disk,
+        // then the synchronous write waits until the value is transferred to the backups and written to their disks
+        let write_time = 10. / NETWORK_BW + 10. / DISK_WRITE_BW;
+        let backup_write_time = 10. / NETWORK_BW + 10. / (DISK_WRITE_BW / 3.);
+        let expected_latency = if sync {
+            write_time + backup_write_time
+        } else {
+            write_time
+        };
+        let stats = store.borrow().stats();
+        assert_eq!(stats.puts, 1);
+        assert!((stats.mean_put_latency - expected_latency).abs() < 1e-9);
+        // the backups are updated in both cases
+        for replica in store.borrow().replicas() {
+            assert_eq!(store.borrow().replica_version(replica, "key"), Some(1));
+        }
+
+        store.borrow_mut().get("key", client, checker_id);
+        store.borrow_mut().get("missing", client, checker_id);
+        sim.step_until_no_events();
+        let events = checker.borrow().events.iter().map(|e| e.1.clone()).collect::<Vec<_>>();
+        assert_eq!(
+            events,
+            vec![
+                "put completed: key v1",
+                "get completed: missing None stale=false",
+                "get completed: key Some(1) stale=false",
+            ]
+        );
+    }
+}
+
+#[test]
+fn kv_primary_backup_stale_reads_from_backups() {
+    let mut stale_reads = Vec::new();
+    for sync in [true, false] {
+        let mut sim = Simulation::new(SEED);
+        let checker = rc!(refcell!(KvChecker::default()));
+        let checker_id = sim.add_handler("User", checker.clone());
+        let protocol = ReplicationProtocol::PrimaryBackup {
+            sync,
+            read_from_backups: true,
+        };
+        let (store, nodes) = make_kv_store(&mut sim, 3, protocol);
+        let client = nodes[3];
+
+        for _ in 0..5 {
+            store.borrow_mut().put("key", 10, client, checker_id);
+            // the reads are issued right after the asynchronous write is acknowledged,
+            // when the backups are still writing the value to their disks
+            sim.step_for_duration(10. / NETWORK_BW + 10. / DISK_WRITE_BW);
+            for _ in 0..4 {
+                store.borrow_mut().get("key", client, checker_id);
+            }
+            sim.step_until_no_events();
+        }
+        let stats = store.borrow().stats();
+        assert_eq!(stats.gets, 20);
+        stale_reads.push(stats.stale_reads);
+        // the stale reads lag behind by one version
+        assert!((stats.mean_version_lag * stats.gets as f64 - stats.stale_reads as f64).abs() < 1e-9);
+    }
+    // asynchronous replication allows reading the outdated values from backups
+    assert_eq!(stale_reads[0], 0);
+    assert!(stale_reads[1] > 0);
+}
+
+#[test]
+fn kv_quorum_reads_and_writes() {
+    let mut stale_reads = Vec::new();
+    for (read_quorum, write_quorum) in [(2, 2), (1, 1)] {
+        let mut sim = Simulation::new(SEED);
+        let protocol = ReplicationProtocol::Quorum {
+            read_quorum,
+            write_quorum,
+        };
+        let (store, nodes) = make_kv_store(&mut sim, 3, protocol);
+        // the client component is located on the client network node
+        let client_ctx = sim.create_context("Client");
+        assert_eq!(client_ctx.id(), nodes[3]);
+        let workload = KvWorkload {
+            inter_arrival_time: TimeDistribution::Exponential { rate: 2. },
+            read_ratio: 0.7,
+            key_count: 3,
+            value_size: 1,
+            request_count: 500,
+        };
+        let client = rc!(refcell!(KvClient::new(store.clone(), workload, client_ctx)));
+        sim.add_handler("Client", client.clone());
+        client.borrow_mut().start();
+        sim.step_until_no_events();
+
+        assert_eq!(client.borrow().issued_requests(), 500);
+        assert_eq!(client.borrow().completed_requests(), 500);
+        let stats = store.borrow().stats();
+        assert_eq!(stats.gets + stats.puts, 500);
+        stale_reads.push(stats.stale_read_ratio());
+    }
+    // overlapping read and write quorums guarantee reading the latest acknowledged value
+    assert_eq!(stale_reads[0], 0.);
+    assert!(stale_reads[1] > 0.);
+}
+
+#[test]
+fn kv_failed_requests() {
+    let mut sim = Simulation::new(SEED);
+    let checker = rc!(refcell!(KvChecker::default()));
+    let checker_id = sim.add_handler("User", checker.clone());
+    let protocol = ReplicationProtocol::Quorum {
+        read_quorum: 3,
+        write_quorum: 4,
+    };
+    let (store, nodes) = make_kv_store(&mut sim, 3, protocol);
+    let client = nodes[3];
+
+    // the write quorum exceeds the number of replicas
+    store.borrow_mut().put("key", 10, client, checker_id);
+    store.borrow_mut().get("key", client, checker_id);
+    sim.step_until_no_events();
+
+    let events = checker.borrow().events.iter().map(|e| e.1.clone()).collect::<Vec<_>>();
+    assert_eq!(events, vec!["put failed: key", "get completed: key None stale=false"]);
+    let stats = store.borrow().stats();
+    assert_eq!(stats.failed_puts, 1);
+    assert_eq!(stats.gets, 1);
+}
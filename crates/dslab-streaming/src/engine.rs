@@ -0,0 +1,464 @@
+//! Streaming engine running the pipeline on a cluster.
+
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
+use std::rc::Rc;
+
+use serde::Serialize;
+
+use dslab_core::component::Id;
+use dslab_core::context::SimulationContext;
+use dslab_core::event::Event;
+use dslab_core::handler::EventHandler;
+use dslab_core::{cast, log_debug, log_info};
+use dslab_network::{DataTransferCompleted, Network};
+
+use crate::events::{Record, RecordDelivered, RecordProcessed, SourceEmit};
+use crate::pipeline::{OperatorSpec, Pipeline, SourceSpec};
+use crate::placement::{HostSlots, PlacementPolicy};
+
+/// Statistics of operator.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct OperatorStats {
+    /// Operator name.
+    pub name: String,
+    /// Number of operator instances.
+    pub instances: u32,
+    /// Number of processed input records.
+    pub processed: u64,
+    /// Average fraction of time the instances were busy processing the records.
+    pub utilization: f64,
+    /// Total time the instances were blocked because the downstream buffers were full.
+    pub backpressure_time: f64,
+    /// Maximum number of records in the input buffer of an instance.
+    pub max_queue: usize,
+}
+
+/// Summary statistics of streaming application.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct StreamStats {
+    /// Number of records produced by the sources.
+    pub produced: u64,
+    /// Number of records delivered to the sink.
+    pub delivered: u64,
+    /// Rate of records delivered to the sink.
+    pub throughput: f64,
+    /// Mean end-to-end latency, i.e. the time from the production of source record until the delivery of
+    /// the derived record to the sink.
+    pub mean_latency: f64,
+    /// Median end-to-end latency.
+    pub p50_latency: f64,
+    /// 95th percentile of end-to-end latency.
+    pub p95_latency: f64,
+    /// 99th percentile of end-to-end latency.
+    pub p99_latency: f64,
+    /// Maximum end-to-end latency.
+    pub max_latency: f64,
+    /// Maximum number of produced records waiting at a source to be sent to the pipeline.
+    pub max_source_backlog: usize,
+    /// Total time the sources were blocked by backpressure.
+    pub source_backpressure_time: f64,
+    /// Statistics of operators in the pipeline order.
+    pub operators: Vec<OperatorStats>,
+}
+
+struct Instance {
+    host: Id,
+    speed: f64,
+    queue: VecDeque<Record>,
+    in_flight: usize,
+    busy_since: Option<f64>,
+    outbox: VecDeque<Record>,
+    next_target: usize,
+    output_credit: f64,
+    blocked_since: Option<f64>,
+    processed: u64,
+    busy_time: f64,
+    backpressure_time: f64,
+    max_queue: usize,
+}
+
+struct SourceState {
+    spec: SourceSpec,
+    backlog: VecDeque<Record>,
+    next_target: usize,
+    produced: u64,
+    max_backlog: usize,
+    blocked_since: Option<f64>,
+    backpressure_time: f64,
+}
+
+/// Component running a stream processing pipeline on a cluster of hosts.
+///
+/// Each operator instance processes the records from its input buffer one by one in FIFO order using one core
+/// of its host. The output records are distributed among the instances of the next operator in round-robin fashion,
+/// skipping the instances with full input buffers. If all downstream buffers are full, the instance stops processing
+/// until some buffer space is released. Similarly, the source keeps the produced records in its backlog while
+/// the buffers of the first operator are full, and the waiting time of these records is included in their latency.
+///
+/// The records are transferred between different hosts through the network, so the hosts (and the source hosts)
+/// must be registered in the network via [`Network::set_location`].
+pub struct StreamingEngine {
+    network: Rc<RefCell<Network>>,
+    hosts: Vec<HostSlots>,
+    operators: Vec<OperatorSpec>,
+    instances: Vec<Vec<Instance>>,
+    sources: Vec<SourceState>,
+    transfers: HashMap<usize, (usize, usize, Record)>,
+    latencies: Vec<f64>,
+    start_time: Option<f64>,
+    ctx: SimulationContext,
+}
+
+impl StreamingEngine {
+    /// Creates engine without hosts which uses the specified network for record transfers.
+    pub fn new(network: Rc<RefCell<Network>>, ctx: SimulationContext) -> Self {
+        Self {
+            network,
+            hosts: Vec::new(),
+            operators: Vec::new(),
+            instances: Vec::new(),
+            sources: Vec::new(),
+            transfers: HashMap::new(),
+            latencies: Vec::new(),
+            start_time: None,
+            ctx,
+        }
+    }
+
+    /// Returns component id.
+    pub fn id(&self) -> Id {
+        self.ctx.id()
+    }
+
+    /// Adds host with given number of cores and core speed.
+    ///
+    /// The host is identified by the id of a simulation component bound to some network node.
+    pub fn add_host(&mut self, id: Id, cores: u32, speed: f64) {
+        assert!(self.hosts.iter().all(|h| h.id != id), "host {} is already added", id);
+        assert!(speed > 0., "host speed should be positive");
+        self.hosts.push(HostSlots { id, cores, speed });
+    }
+
+    /// Places the pipeline operators on the hosts using the specified policy and starts the sources.
+    pub fn deploy(&mut self, pipeline: Pipeline, placement: &dyn PlacementPolicy) -> Result<(), String> {
+        if self.start_time.is_some() {
+            return Err("pipeline is already deployed".to_string());
+        }
+        if pipeline.operators.is_empty() {
+            return Err("pipeline should have at least one operator".to_string());
+        }
+        let hosts = placement.place(&pipeline.operators, &self.hosts)?;
+        log_info!(
+            self.ctx,
+            "deployed pipeline with {} operators using {} placement",
+            pipeline.operators.len(),
+            placement.name()
+        );
+        self.instances = hosts
+            .iter()
+            .map(|instance_hosts| {
+                instance_hosts
+                    .iter()
+                    .map(|host| Instance {
+                        host: *host,
+                        speed: self.hosts.iter().find(|h| h.id == *host).unwrap().speed,
+                        queue: VecDeque::new(),
+                        in_flight: 0,
+                        busy_since: None,
+                        outbox: VecDeque::new(),
+                        next_target: 0,
+                        output_credit: 0.,
+                        blocked_since: None,
+                        processed: 0,
+                        busy_time: 0.,
+                        backpressure_time: 0.,
+                        max_queue: 0,
+                    })
+                    .collect()
+            })
+            .collect();
+        self.operators = pipeline.operators;
+        for (source, spec) in pipeline.sources.into_iter().enumerate() {
+            self.sources.push(SourceState {
+                spec,
+                backlog: VecDeque::new(),
+                next_target: 0,
+                produced: 0,
+                max_backlog: 0,
+                blocked_since: None,
+                backpressure_time: 0.,
+            });
+            self.ctx.emit_self_now(SourceEmit { source });
+        }
+        self.start_time = Some(self.ctx.time());
+        Ok(())
+    }
+
+    /// Returns the hosts of operator instances.
+    pub fn instance_hosts(&self, operator: usize) -> Vec<Id> {
+        self.instances[operator].iter().map(|inst| inst.host).collect()
+    }
+
+    /// Returns the summary statistics.
+    pub fn stats(&self) -> StreamStats {
+        let time = self.ctx.time();
+        let elapsed = self.start_time.map_or(0., |start| time - start);
+        let mut stats = StreamStats {
+            produced: self.sources.iter().map(|s| s.produced).sum(),
+            delivered: self.latencies.len() as u64,
+            max_source_backlog: self.sources.iter().map(|s| s.max_backlog).max().unwrap_or(0),
+            source_backpressure_time: self
+                .sources
+                .iter()
+                .map(|s| s.backpressure_time + s.blocked_since.map_or(0., |since| time - since))
+                .sum(),
+            ..Default::default()
+        };
+        if elapsed > 0. {
+            stats.throughput = stats.delivered as f64 / elapsed;
+        }
+        if !self.latencies.is_empty() {
+            let mut latencies = self.latencies.clone();
+            latencies.sort_by(|a, b| a.total_cmp(b));
+            stats.mean_latency = latencies.iter().sum::<f64>() / latencies.len() as f64;
+            stats.p50_latency = percentile(&latencies, 0.5);
+            stats.p95_latency = percentile(&latencies, 0.95);
+            stats.p99_latency = percentile(&latencies, 0.99);
+            stats.max_latency = *latencies.last().unwrap();
+        }
+        for (operator, instances) in self.operators.iter().zip(self.instances.iter()) {
+            let busy_time: f64 = instances
+                .iter()
+                .map(|inst| inst.busy_time + inst.busy_since.map_or(0., |since| time - since))
+                .sum();
+            stats.operators.push(OperatorStats {
+                name: operator.name.clone(),
+                instances: operator.parallelism,
+                processed: instances.iter().map(|inst| inst.processed).sum(),
+                utilization: if elapsed > 0. {
+                    busy_time / (elapsed * instances.len() as f64)
+                } else {
+                    0.
+                },
+                backpressure_time: instances
+                    .iter()
+                    .map(|inst| inst.backpressure_time + inst.blocked_since.map_or(0., |since| time - since))
+                    .sum(),
+                max_queue: instances.iter().map(|inst| inst.max_queue).max().unwrap_or(0),
+            });
+        }
+        stats
+    }
+
+    /// Sends the record from the host to some instance of the operator with free buffer space, starting from
+    /// the specified instance. Returns the index of selected instance or `None` if all buffers are full.
+    fn try_send(&mut self, operator: usize, start: usize, from: Id, record: &Record) -> Option<usize> {
+        let buffer_size = self.operators[operator].buffer_size;
+        let count = self.instances[operator].len();
+        let instance = (0..count).map(|i| (start + i) % count).find(|i| {
+            let inst = &self.instances[operator][*i];
+            inst.queue.len() + inst.in_flight < buffer_size
+        })?;
+        let inst = &mut self.instances[operator][instance];
+        inst.in_flight += 1;
+        if inst.host == from {
+            self.ctx.emit_self_now(RecordDelivered {
+                operator,
+                instance,
+                record: record.clone(),
+            });
+        } else {
+            let transfer_id = self
+                .network
+                .borrow_mut()
+                .transfer_data(from, inst.host, record.size, self.ctx.id());
+            self.transfers.insert(transfer_id, (operator, instance, record.clone()));
+        }
+        Some(instance)
+    }
+
+    fn flush_source(&mut self, source: usize) {
+        while let Some(record) = self.sources[source].backlog.front().cloned() {
+            let state = &self.sources[source];
+            match self.try_send(0, state.next_target, state.spec.host, &record) {
+                Some(instance) => {
+                    let state = &mut self.sources[source];
+                    state.next_target = instance + 1;
+                    state.backlog.pop_front();
+                }
+                None => break,
+            }
+        }
+        let time = self.ctx.time();
+        let state = &mut self.sources[source];
+        update_blocked(
+            &mut state.blocked_since,
+            &mut state.backpressure_time,
+            !state.backlog.is_empty(),
+            time,
+        );
+    }
+
+    fn flush_instance(&mut self, operator: usize, instance: usize) {
+        while let Some(record) = self.instances[operator][instance].outbox.front().cloned() {
+            let inst = &self.instances[operator][instance];
+            match self.try_send(operator + 1, inst.next_target, inst.host, &record) {
+                Some(target) => {
+                    let inst = &mut self.instances[operator][instance];
+                    inst.next_target = target + 1;
+                    inst.outbox.pop_front();
+                }
+                None => break,
+            }
+        }
+        let time = self.ctx.time();
+        let inst = &mut self.instances[operator][instance];
+        update_blocked(
+            &mut inst.blocked_since,
+            &mut inst.backpressure_time,
+            !inst.outbox.is_empty(),
+            time,
+        );
+        self.try_start(operator, instance);
+    }
+
+    fn try_start(&mut self, operator: usize, instance: usize) {
+        let time = self.ctx.time();
+        let inst = &mut self.instances[operator][instance];
+        if inst.busy_since.is_some() || !inst.outbox.is_empty() {
+            return;
+        }
+        if let Some(record) = inst.queue.pop_front() {
+            inst.busy_since = Some(time);
+            let duration = self.operators[operator].cost / inst.speed;
+            self.ctx.emit_self(
+                RecordProcessed {
+                    operator,
+                    instance,
+                    record,
+                },
+                duration,
+            );
+            self.notify_upstream(operator);
+        }
+    }
+
+    /// Retries sending the blocked records to the operator after its buffer space is released.
+    fn notify_upstream(&mut self, operator: usize) {
+        if operator == 0 {
+            for source in 0..self.sources.len() {
+                self.flush_source(source);
+            }
+        } else {
+            for instance in 0..self.instances[operator - 1].len() {
+                if !self.instances[operator - 1][instance].outbox.is_empty() {
+                    self.flush_instance(operator - 1, instance);
+                }
+            }
+        }
+    }
+
+    fn on_source_emit(&mut self, source: usize) {
+        let time = self.ctx.time();
+        let state = &mut self.sources[source];
+        state.produced += 1;
+        state.backlog.push_back(Record {
+            created: time,
+            size: state.spec.record_size,
+        });
+        state.max_backlog = state.max_backlog.max(state.backlog.len());
+        let delay = state.spec.interval.sample(&self.ctx);
+        if state.spec.until.is_none_or(|until| time + delay <= until) {
+            self.ctx.emit_self(SourceEmit { source }, delay);
+        }
+        self.flush_source(source);
+    }
+
+    fn on_record_delivered(&mut self, operator: usize, instance: usize, record: Record) {
+        let inst = &mut self.instances[operator][instance];
+        inst.in_flight -= 1;
+        inst.queue.push_back(record);
+        inst.max_queue = inst.max_queue.max(inst.queue.len());
+        self.try_start(operator, instance);
+    }
+
+    fn on_record_processed(&mut self, operator: usize, instance: usize, record: Record) {
+        let time = self.ctx.time();
+        let spec = &self.operators[operator];
+        let inst = &mut self.instances[operator][instance];
+        inst.busy_time += time - inst.busy_since.take().unwrap();
+        inst.processed += 1;
+        inst.output_credit += spec.selectivity;
+        let outputs = inst.output_credit.floor();
+        inst.output_credit -= outputs;
+        let output = Record {
+            created: record.created,
+            size: record.size * spec.output_size_ratio,
+        };
+        if operator + 1 == self.operators.len() {
+            for _ in 0..outputs as u64 {
+                self.latencies.push(time - output.created);
+            }
+            self.try_start(operator, instance);
+        } else {
+            for _ in 0..outputs as u64 {
+                inst.outbox.push_back(output.clone());
+            }
+            self.flush_instance(operator, instance);
+        }
+    }
+
+    fn on_transfer_completed(&mut self, transfer_id: usize) {
+        if let Some((operator, instance, record)) = self.transfers.remove(&transfer_id) {
+            log_debug!(
+                self.ctx,
+                "record transferred to instance {} of operator {}",
+                instance,
+                self.operators[operator].name
+            );
+            self.on_record_delivered(operator, instance, record);
+        }
+    }
+}
+
+fn update_blocked(blocked_since: &mut Option<f64>, backpressure_time: &mut f64, blocked: bool, time: f64) {
+    if blocked {
+        blocked_since.get_or_insert(time);
+    } else if let Some(since) = blocked_since.take() {
+        *backpressure_time += time - since;
+    }
+}
+
+/// Returns the percentile of sorted values using the nearest-rank method.
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    let rank = (p * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+impl EventHandler for StreamingEngine {
+    fn on(&mut self, event: Event) {
+        cast!(match event.data {
+            SourceEmit { source } => {
+                self.on_source_emit(source);
+            }
+            RecordDelivered {
+                operator,
+                instance,
+                record,
+            } => {
+                self.on_record_delivered(operator, instance, record);
+            }
+            RecordProcessed {
+                operator,
+                instance,
+                record,
+            } => {
+                self.on_record_processed(operator, instance, record);
+            }
+            DataTransferCompleted { dt } => {
+                self.on_transfer_completed(dt.id);
+            }
+        })
+    }
+}
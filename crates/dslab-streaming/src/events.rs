@@ -0,0 +1,41 @@
+//! Events used by streaming engine.
+
+use serde::Serialize;
+
+/// Record flowing through the pipeline.
+#[derive(Clone, Debug, Serialize)]
+pub struct Record {
+    /// Time when the source record from which this record is derived was produced.
+    pub created: f64,
+    /// Record size.
+    pub size: f64,
+}
+
+/// Production of the next record by source (internal event).
+#[derive(Clone, Serialize)]
+pub struct SourceEmit {
+    /// Source index.
+    pub source: usize,
+}
+
+/// Delivery of record to operator instance located on the sender host (internal event).
+#[derive(Clone, Serialize)]
+pub struct RecordDelivered {
+    /// Operator index.
+    pub operator: usize,
+    /// Instance index.
+    pub instance: usize,
+    /// Delivered record.
+    pub record: Record,
+}
+
+/// Completion of record processing by operator instance (internal event).
+#[derive(Clone, Serialize)]
+pub struct RecordProcessed {
+    /// Operator index.
+    pub operator: usize,
+    /// Instance index.
+    pub instance: usize,
+    /// Processed record.
+    pub record: Record,
+}
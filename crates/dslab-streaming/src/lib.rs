@@ -0,0 +1,14 @@
+#![warn(missing_docs)]
+#![doc = include_str!("../README.md")]
+
+pub mod engine;
+pub mod events;
+pub mod pipeline;
+pub mod placement;
+
+pub use engine::{OperatorStats, StreamStats, StreamingEngine};
+pub use pipeline::{OperatorSpec, Pipeline, SourceSpec};
+pub use placement::{HostSlots, ManualPlacement, PackedPlacement, PlacementPolicy, SpreadPlacement};
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,139 @@
+//! Specification of stream processing pipeline.
+
+use dslab_core::component::Id;
+use dslab_models::queueing::TimeDistribution;
+
+/// Source producing records.
+#[derive(Clone, Debug)]
+pub struct SourceSpec {
+    /// Source name.
+    pub name: String,
+    /// Host where the source is located.
+    pub host: Id,
+    /// Distribution of intervals between the produced records.
+    pub interval: TimeDistribution,
+    /// Size of produced records.
+    pub record_size: f64,
+    /// Time after which the source stops producing records.
+    pub until: Option<f64>,
+}
+
+impl SourceSpec {
+    /// Creates source producing records at the specified constant rate.
+    pub fn new(name: &str, host: Id, rate: f64) -> Self {
+        assert!(rate > 0., "source rate should be positive");
+        Self {
+            name: name.to_string(),
+            host,
+            interval: TimeDistribution::Constant(1. / rate),
+            record_size: 0.,
+            until: None,
+        }
+    }
+
+    /// Creates source producing records according to Poisson process with the specified rate.
+    pub fn poisson(name: &str, host: Id, rate: f64) -> Self {
+        Self::new(name, host, rate).with_interval(TimeDistribution::Exponential { rate })
+    }
+
+    /// Sets the distribution of intervals between the records.
+    pub fn with_interval(mut self, interval: TimeDistribution) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Sets the size of records.
+    pub fn with_record_size(mut self, record_size: f64) -> Self {
+        self.record_size = record_size;
+        self
+    }
+
+    /// Sets the time after which the source stops producing records.
+    pub fn with_until(mut self, until: f64) -> Self {
+        self.until = Some(until);
+        self
+    }
+}
+
+/// Operator processing records.
+#[derive(Clone, Debug)]
+pub struct OperatorSpec {
+    /// Operator name.
+    pub name: String,
+    /// Number of parallel operator instances.
+    pub parallelism: u32,
+    /// Amount of computations (in flops) needed to process a record.
+    pub cost: f64,
+    /// Average number of output records per input record (e.g. less than 1 for filters and aggregations).
+    pub selectivity: f64,
+    /// Ratio of output record size to input record size.
+    pub output_size_ratio: f64,
+    /// Maximum number of records in the input buffer of each instance, including the records being transferred
+    /// to the instance.
+    pub buffer_size: usize,
+}
+
+impl OperatorSpec {
+    /// Creates operator with unit selectivity and output size ratio.
+    pub fn new(name: &str, parallelism: u32, cost: f64) -> Self {
+        assert!(parallelism > 0, "operator parallelism should be positive");
+        Self {
+            name: name.to_string(),
+            parallelism,
+            cost,
+            selectivity: 1.,
+            output_size_ratio: 1.,
+            buffer_size: 16,
+        }
+    }
+
+    /// Sets the operator selectivity.
+    pub fn with_selectivity(mut self, selectivity: f64) -> Self {
+        self.selectivity = selectivity;
+        self
+    }
+
+    /// Sets the ratio of output record size to input record size.
+    pub fn with_output_size_ratio(mut self, ratio: f64) -> Self {
+        self.output_size_ratio = ratio;
+        self
+    }
+
+    /// Sets the input buffer size of instances.
+    pub fn with_buffer_size(mut self, buffer_size: usize) -> Self {
+        assert!(buffer_size > 0, "buffer size should be positive");
+        self.buffer_size = buffer_size;
+        self
+    }
+}
+
+/// Linear pipeline of operators fed by the sources.
+///
+/// All sources send their records to the first operator, each operator sends its output records to the next one,
+/// and the output records of the last operator are delivered to the sink.
+#[derive(Clone, Debug, Default)]
+pub struct Pipeline {
+    /// Sources.
+    pub sources: Vec<SourceSpec>,
+    /// Operators in the order of processing.
+    pub operators: Vec<OperatorSpec>,
+}
+
+impl Pipeline {
+    /// Creates empty pipeline.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds source.
+    pub fn with_source(mut self, source: SourceSpec) -> Self {
+        self.sources.push(source);
+        self
+    }
+
+    /// Adds operator after the previously added ones.
+    pub fn with_operator(mut self, operator: OperatorSpec) -> Self {
+        self.operators.push(operator);
+        self
+    }
+}
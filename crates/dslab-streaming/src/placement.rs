@@ -0,0 +1,133 @@
+//! Placement of operator instances on hosts.
+
+use dslab_core::component::Id;
+
+use crate::pipeline::OperatorSpec;
+
+/// Host resources available to operator instances.
+#[derive(Clone, Debug)]
+pub struct HostSlots {
+    /// Host id.
+    pub id: Id,
+    /// Number of cores, each operator instance uses one core.
+    pub cores: u32,
+    /// Core speed in flop/s.
+    pub speed: f64,
+}
+
+/// Policy placing operator instances on hosts.
+pub trait PlacementPolicy {
+    /// Returns the policy name.
+    fn name(&self) -> &str;
+
+    /// Returns the hosts of instances of each operator, or error if the instances cannot be placed.
+    fn place(&self, operators: &[OperatorSpec], hosts: &[HostSlots]) -> Result<Vec<Vec<Id>>, String>;
+}
+
+fn not_enough_cores(operator: &OperatorSpec) -> String {
+    format!("not enough cores to place instances of operator {}", operator.name)
+}
+
+/// Spreads instances across hosts in round-robin fashion, which balances the load but increases the network traffic.
+pub struct SpreadPlacement;
+
+impl PlacementPolicy for SpreadPlacement {
+    fn name(&self) -> &str {
+        "Spread"
+    }
+
+    fn place(&self, operators: &[OperatorSpec], hosts: &[HostSlots]) -> Result<Vec<Vec<Id>>, String> {
+        let mut free = hosts.iter().map(|h| h.cores).collect::<Vec<_>>();
+        let mut next = 0;
+        let mut placement = Vec::new();
+        for operator in operators {
+            let mut instances = Vec::new();
+            for _ in 0..operator.parallelism {
+                let host_idx = (0..hosts.len())
+                    .map(|i| (next + i) % hosts.len())
+                    .find(|i| free[*i] > 0)
+                    .ok_or_else(|| not_enough_cores(operator))?;
+                free[host_idx] -= 1;
+                next = host_idx + 1;
+                instances.push(hosts[host_idx].id);
+            }
+            placement.push(instances);
+        }
+        Ok(placement)
+    }
+}
+
+/// Packs instances on hosts in the order of adding, which keeps the consecutive operators on the same hosts
+/// and reduces the network traffic.
+pub struct PackedPlacement;
+
+impl PlacementPolicy for PackedPlacement {
+    fn name(&self) -> &str {
+        "Packed"
+    }
+
+    fn place(&self, operators: &[OperatorSpec], hosts: &[HostSlots]) -> Result<Vec<Vec<Id>>, String> {
+        let mut free = hosts.iter().map(|h| h.cores).collect::<Vec<_>>();
+        let mut placement = Vec::new();
+        for operator in operators {
+            let mut instances = Vec::new();
+            for _ in 0..operator.parallelism {
+                let host_idx = free
+                    .iter()
+                    .position(|f| *f > 0)
+                    .ok_or_else(|| not_enough_cores(operator))?;
+                free[host_idx] -= 1;
+                instances.push(hosts[host_idx].id);
+            }
+            placement.push(instances);
+        }
+        Ok(placement)
+    }
+}
+
+/// Places instances on the explicitly specified hosts.
+pub struct ManualPlacement {
+    /// Hosts of instances of each operator.
+    pub hosts: Vec<Vec<Id>>,
+}
+
+impl ManualPlacement {
+    /// Creates placement with given hosts of operator instances.
+    pub fn new(hosts: Vec<Vec<Id>>) -> Self {
+        Self { hosts }
+    }
+}
+
+impl PlacementPolicy for ManualPlacement {
+    fn name(&self) -> &str {
+        "Manual"
+    }
+
+    fn place(&self, operators: &[OperatorSpec], hosts: &[HostSlots]) -> Result<Vec<Vec<Id>>, String> {
+        if self.hosts.len() != operators.len() {
+            return Err("placement should be specified for each operator".to_string());
+        }
+        let mut free = hosts.iter().map(|h| h.cores).collect::<Vec<_>>();
+        for (operator, instances) in operators.iter().zip(self.hosts.iter()) {
+            if instances.len() != operator.parallelism as usize {
+                return Err(format!(
+                    "operator {} has {} instances, but {} hosts are specified",
+                    operator.name,
+                    operator.parallelism,
+                    instances.len()
+                ));
+            }
+            for host in instances {
+                let host_idx = hosts
+                    .iter()
+                    .position(|h| h.id == *host)
+                    .ok_or_else(|| format!("unknown host {}", host))?;
+                if free[host_idx] == 0 {
+                    return Err(not_enough_cores(operator));
+                }
+                free[host_idx] -= 1;
+            }
+        }
+        Ok(self.hosts.clone())
+    }
+}
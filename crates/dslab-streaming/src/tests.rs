@@ -0,0 +1,166 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use sugars::{boxed, rc, refcell};
+
+use dslab_core::component::Id;
+use dslab_core::simulation::Simulation;
+use dslab_network::models::ConstantBandwidthNetworkModel;
+use dslab_network::Network;
+
+use crate::engine::StreamingEngine;
+use crate::pipeline::{OperatorSpec, Pipeline, SourceSpec};
+use crate::placement::{ManualPlacement, PackedPlacement, PlacementPolicy, SpreadPlacement};
+
+///////////////////////////////////////////////////////////////////////////////
+
+const SEED: u64 = 16;
+const BANDWIDTH: f64 = 10.;
+const HOST_SPEED: f64 = 100.;
+
+struct Setup {
+    sim: Simulation,
+    engine: Rc<RefCell<StreamingEngine>>,
+    hosts: Vec<Id>,
+}
+
+// Creates cluster where each host is located on a separate network node with zero latency.
+fn make_cluster(host_count: usize, cores: u32) -> Setup {
+    let mut sim = Simulation::new(SEED);
+    let mut network = Network::new(
+        boxed!(ConstantBandwidthNetworkModel::new(BANDWIDTH, 0.)),
+        sim.create_context("net"),
+    );
+    let mut hosts = Vec::new();
+    for i in 0..host_count {
+        let name = format!("host{}", i);
+        network.add_node(&name, boxed!(ConstantBandwidthNetworkModel::new(1e9, 0.)));
+        let host_id = sim.create_context(&name).id();
+        network.set_location(host_id, &name);
+        hosts.push(host_id);
+    }
+    let network = rc!(refcell!(network));
+    sim.add_handler("net", network.clone());
+    let mut engine = StreamingEngine::new(network, sim.create_context("engine"));
+    for &host in hosts.iter() {
+        engine.add_host(host, cores, HOST_SPEED);
+    }
+    let engine = rc!(refcell!(engine));
+    sim.add_handler("engine", engine.clone());
+    Setup { sim, engine, hosts }
+}
+
+fn run(setup: &mut Setup, pipeline: Pipeline, placement: &dyn PlacementPolicy) {
+    setup.engine.borrow_mut().deploy(pipeline, placement).unwrap();
+    setup.sim.step_until_no_events();
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn single_operator() {
+    let mut setup = make_cluster(1, 1);
+    let pipeline = Pipeline::new()
+        .with_source(SourceSpec::new("source", setup.hosts[0], 5.).with_until(10.))
+        .with_operator(OperatorSpec::new("map", 1, 10.));
+    run(&mut setup, pipeline, &PackedPlacement);
+
+    let stats = setup.engine.borrow().stats();
+    assert_eq!(stats.produced, 51);
+    assert_eq!(stats.delivered, 51);
+    assert!((stats.mean_latency - 0.1).abs() < 1e-9);
+    assert!((stats.p99_latency - 0.1).abs() < 1e-9);
+    assert!((stats.max_latency - 0.1).abs() < 1e-9);
+    assert_eq!(stats.max_source_backlog, 1);
+    assert_eq!(stats.source_backpressure_time, 0.);
+    assert_eq!(stats.operators[0].processed, 51);
+    assert!((stats.operators[0].utilization - 5.1 / 10.1).abs() < 1e-9);
+}
+
+#[test]
+fn backpressure() {
+    // source rate exceeds the capacity of a single operator instance
+    let pipeline = |hosts: &[Id], parallelism: u32| {
+        Pipeline::new()
+            .with_source(SourceSpec::new("source", hosts[0], 16.).with_until(10.))
+            .with_operator(OperatorSpec::new("map", parallelism, 10.).with_buffer_size(4))
+    };
+
+    let mut setup = make_cluster(1, 2);
+    let overloaded = pipeline(&setup.hosts, 1);
+    run(&mut setup, overloaded, &PackedPlacement);
+    let stats = setup.engine.borrow().stats();
+    assert_eq!(stats.produced, 161);
+    assert_eq!(stats.delivered, 161);
+    assert!(stats.max_source_backlog > 50);
+    assert!(stats.source_backpressure_time > 9.);
+    assert!(stats.p50_latency > 2.5);
+    assert!(stats.p99_latency > 5.5);
+    assert_eq!(stats.operators[0].max_queue, 4);
+    assert!(stats.operators[0].utilization > 0.99);
+
+    let mut setup = make_cluster(1, 2);
+    let scaled = pipeline(&setup.hosts, 2);
+    run(&mut setup, scaled, &PackedPlacement);
+    let stats = setup.engine.borrow().stats();
+    assert_eq!(stats.delivered, 161);
+    assert_eq!(stats.max_source_backlog, 1);
+    assert!(stats.max_latency < 0.1 + 1e-9);
+}
+
+#[test]
+fn operator_placement() {
+    let pipeline = |hosts: &[Id]| {
+        Pipeline::new()
+            .with_source(
+                SourceSpec::new("source", hosts[0], 2.)
+                    .with_record_size(1.)
+                    .with_until(10.),
+            )
+            .with_operator(OperatorSpec::new("parse", 2, 10.))
+            .with_operator(OperatorSpec::new("filter", 2, 10.).with_selectivity(0.5))
+    };
+
+    let mut setup = make_cluster(2, 4);
+    let hosts = setup.hosts.clone();
+    run(&mut setup, pipeline(&hosts), &PackedPlacement);
+    assert_eq!(setup.engine.borrow().instance_hosts(1), vec![hosts[0], hosts[0]]);
+    let packed = setup.engine.borrow().stats();
+    assert_eq!(packed.produced, 21);
+    assert!(packed.delivered >= 9 && packed.delivered <= 10);
+    assert_eq!(packed.operators[1].processed, 21);
+    // all operators run on the source host, so the latency includes only the processing time
+    assert!((packed.max_latency - 0.2).abs() < 1e-9);
+
+    let mut setup = make_cluster(2, 4);
+    run(&mut setup, pipeline(&hosts), &SpreadPlacement);
+    assert_eq!(setup.engine.borrow().instance_hosts(0), vec![hosts[0], hosts[1]]);
+    let spread = setup.engine.borrow().stats();
+    assert_eq!(spread.delivered, packed.delivered);
+    // records are transferred between the hosts with delay 0.1
+    assert!(spread.mean_latency > packed.mean_latency);
+    assert!((spread.max_latency - 0.4).abs() < 1e-9);
+}
+
+#[test]
+fn placement_errors() {
+    let setup = make_cluster(2, 1);
+    let hosts = setup.hosts.clone();
+    let pipeline = Pipeline::new()
+        .with_source(SourceSpec::new("source", hosts[0], 1.))
+        .with_operator(OperatorSpec::new("map", 2, 10.))
+        .with_operator(OperatorSpec::new("reduce", 1, 10.));
+
+    let mut engine = setup.engine.borrow_mut();
+    assert!(engine.deploy(pipeline.clone(), &SpreadPlacement).is_err());
+    assert!(engine.deploy(pipeline.clone(), &PackedPlacement).is_err());
+    let manual = ManualPlacement::new(vec![vec![hosts[0], hosts[0]], vec![hosts[1]]]);
+    assert!(engine.deploy(pipeline.clone(), &manual).is_err());
+    assert!(engine.deploy(Pipeline::new(), &PackedPlacement).is_err());
+
+    let setup = make_cluster(3, 1);
+    let hosts = setup.hosts.clone();
+    let manual = ManualPlacement::new(vec![vec![hosts[0], hosts[2]], vec![hosts[1]]]);
+    setup.engine.borrow_mut().deploy(pipeline, &manual).unwrap();
+    assert_eq!(setup.engine.borrow().instance_hosts(0), vec![hosts[0], hosts[2]]);
+}
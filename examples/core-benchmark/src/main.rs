@@ -0,0 +1,125 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Instant;
+
+use clap::Parser;
+use serde::Serialize;
+
+use dslab_core::{cast, Event, EventHandler, Id, Simulation, SimulationContext};
+
+#[cfg(feature = "pool-allocator")]
+#[global_allocator]
+static ALLOCATOR: dslab_core::alloc::PoolAllocator = dslab_core::alloc::PoolAllocator;
+
+/// Event queue benchmark based on the classic hold model:
+/// the queue is filled with the specified number of pending events, then each processed event
+/// emits a new event with random delay, so the number of pending events stays constant.
+#[derive(Parser, Debug)]
+#[clap(about, long_about = None)]
+struct Args {
+    /// Number of pending events
+    #[clap(long, default_value_t = 1_000_000)]
+    pending: u64,
+
+    /// Number of processed events
+    #[clap(long, default_value_t = 10_000_000)]
+    events: u64,
+
+    /// Number of components exchanging events
+    #[clap(long, default_value_t = 100)]
+    components: u32,
+
+    /// Delay distribution: uniform, exponential or bimodal (mostly short delays with rare long ones)
+    #[clap(long, default_value = "uniform")]
+    delay: String,
+
+    /// Fraction of emitted events which are cancelled right away (models timeouts that are not fired)
+    #[clap(long, default_value_t = 0.)]
+    cancel: f64,
+}
+
+#[derive(Clone, Serialize)]
+struct Message {
+    payload: u64,
+}
+
+struct Process {
+    ctx: SimulationContext,
+    components: u32,
+    delay: String,
+    cancel: f64,
+    processed: Rc<RefCell<u64>>,
+}
+
+impl Process {
+    fn next_delay(&self) -> f64 {
+        match self.delay.as_str() {
+            "uniform" => self.ctx.gen_range(0.0..2.0),
+            "exponential" => -(1. - self.ctx.rand()).ln(),
+            "bimodal" => {
+                if self.ctx.rand() < 0.9 {
+                    self.ctx.gen_range(0.0..0.1)
+                } else {
+                    self.ctx.gen_range(0.0..10.0)
+                }
+            }
+            _ => panic!("Unknown delay distribution: {}", self.delay),
+        }
+    }
+
+    fn send(&self, payload: u64) {
+        let dst = self.ctx.gen_range(0..self.components) as Id;
+        if self.cancel > 0. && self.ctx.rand() < self.cancel {
+            let timeout = self.ctx.emit(Message { payload }, dst, self.next_delay());
+            self.ctx.cancel_event(timeout);
+        }
+        self.ctx.emit(Message { payload }, dst, self.next_delay());
+    }
+}
+
+impl EventHandler for Process {
+    fn on(&mut self, event: Event) {
+        cast!(match event.data {
+            Message { payload } => {
+                *self.processed.borrow_mut() += 1;
+                self.send(payload + 1);
+            }
+        })
+    }
+}
+
+fn main() {
+    let args = Args::parse();
+    let mut sim = Simulation::new(123);
+    let processed = Rc::new(RefCell::new(0));
+    let mut processes = Vec::new();
+    for i in 0..args.components {
+        let name = format!("process-{}", i);
+        let process = Rc::new(RefCell::new(Process {
+            ctx: sim.create_context(&name),
+            components: args.components,
+            delay: args.delay.clone(),
+            cancel: args.cancel,
+            processed: processed.clone(),
+        }));
+        sim.add_handler(&name, process.clone());
+        processes.push(process);
+    }
+
+    let t = Instant::now();
+    for i in 0..args.pending {
+        processes[(i % args.components as u64) as usize].borrow().send(0);
+    }
+    let fill_time = t.elapsed().as_secs_f64();
+
+    let t = Instant::now();
+    sim.steps(args.events);
+    let run_time = t.elapsed().as_secs_f64();
+
+    println!("Pending events: {}", args.pending);
+    println!("Processed events: {}", processed.borrow());
+    println!("Simulation time: {:.3}", sim.time());
+    println!("Fill time: {:.3} s", fill_time);
+    println!("Run time: {:.3} s", run_time);
+    println!("Events per second: {:.0}", *processed.borrow() as f64 / run_time);
+}
@@ -19,6 +19,7 @@ const ALGORITHMS: &[&str] = &[
     "Simple",
     "DLS",
     "HEFT",
+    "CPOP",
     "Lookahead[depth=0]",                  // Identical to HEFT
     "Lookahead[depth=1,depth_mode=Local]", // Original Lookahead version considering only task's children
     "Lookahead[depth=1]",                  // Lookahead version considering all ready unscheduled tasks (depth=1)